@@ -1,2 +1,14 @@
+pub mod command_monitor;
+pub mod data_exporter;
+pub mod db_watchdog;
+pub mod email_worker;
+pub mod entity_cache;
+pub mod maintenance_mode;
 pub mod env_reader;
 pub mod open_api;
+pub mod request_context;
+pub mod search_highlighter;
+pub mod self_check;
+pub mod slow_query_logger;
+pub mod tenant_router;
+pub mod user_scheduler;
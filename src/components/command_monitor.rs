@@ -0,0 +1,258 @@
+use crate::components::request_context::RequestContext;
+use log::debug;
+use mongodb::event::command::{
+    CommandEventHandler, CommandFailedEvent, CommandStartedEvent, CommandSucceededEvent,
+};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Clone, Debug, Default)]
+pub struct CommandMetrics {
+    pub command: String,
+    pub collection: String,
+    pub count: u64,
+    pub failures: u64,
+    pub total_duration: Duration,
+    pub max_duration: Duration,
+}
+
+impl CommandMetrics {
+    /// # Summary
+    ///
+    /// Get the average duration of the observed commands.
+    ///
+    /// # Returns
+    ///
+    /// * `Duration` - The average duration, or zero if no command was observed.
+    pub fn average_duration(&self) -> Duration {
+        match u32::try_from(self.count) {
+            Ok(0) => Duration::ZERO,
+            Ok(c) => self.total_duration / c,
+            Err(_) => Duration::ZERO,
+        }
+    }
+}
+
+/// A command that was sent to the database and for which no reply has been received yet.
+struct PendingCommand {
+    collection: String,
+    context: Option<RequestContext>,
+}
+
+pub struct CommandMonitor {
+    pending: Mutex<HashMap<i32, PendingCommand>>,
+    metrics: Mutex<HashMap<(String, String), CommandMetrics>>,
+}
+
+impl CommandMonitor {
+    /// # Summary
+    ///
+    /// Create a new CommandMonitor.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let command_monitor = CommandMonitor::new();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `CommandMonitor` - The new CommandMonitor.
+    pub fn new() -> CommandMonitor {
+        CommandMonitor {
+            pending: Mutex::new(HashMap::new()),
+            metrics: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Get the metrics of every command and collection combination that has been observed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// for m in command_monitor.snapshot() {
+    ///     println!("{} on {}: {} calls", m.command, m.collection, m.count);
+    /// }
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<CommandMetrics>` - The metrics, sorted by their total duration in descending order.
+    pub fn snapshot(&self) -> Vec<CommandMetrics> {
+        let mut metrics: Vec<CommandMetrics> = match self.metrics.lock() {
+            Ok(m) => m.values().cloned().collect(),
+            Err(e) => e.into_inner().values().cloned().collect(),
+        };
+
+        metrics.sort_by(|a, b| b.total_duration.cmp(&a.total_duration));
+        metrics
+    }
+
+    /// # Summary
+    ///
+    /// Record a finished database command and log it, together with the request that caused it.
+    ///
+    /// # Arguments
+    ///
+    /// * `request_id` - The ID of the request that was sent to the database.
+    /// * `command_name` - The name of the command.
+    /// * `duration` - The duration of the command.
+    /// * `failed` - Whether the command failed.
+    fn finish(&self, request_id: i32, command_name: &str, duration: Duration, failed: bool) {
+        let pending = match self.pending.lock() {
+            Ok(mut p) => p.remove(&request_id),
+            Err(e) => e.into_inner().remove(&request_id),
+        };
+
+        let (collection, context) = match pending {
+            Some(p) => (p.collection, p.context),
+            None => (String::from("-"), None),
+        };
+
+        match context {
+            Some(c) => debug!(
+                "MongoDB command {} on collection {} took {} µs (failed: {}, request: {}, route: {} {})",
+                command_name,
+                collection,
+                duration.as_micros(),
+                failed,
+                c.request_id,
+                c.method,
+                c.route
+            ),
+            None => debug!(
+                "MongoDB command {} on collection {} took {} µs (failed: {})",
+                command_name,
+                collection,
+                duration.as_micros(),
+                failed
+            ),
+        }
+
+        let mut metrics = match self.metrics.lock() {
+            Ok(m) => m,
+            Err(e) => e.into_inner(),
+        };
+
+        let entry = metrics
+            .entry((command_name.to_string(), collection.clone()))
+            .or_insert_with(|| CommandMetrics {
+                command: command_name.to_string(),
+                collection,
+                ..Default::default()
+            });
+
+        entry.count += 1;
+        if failed {
+            entry.failures += 1;
+        }
+        entry.total_duration += duration;
+        entry.max_duration = entry.max_duration.max(duration);
+    }
+}
+
+impl Default for CommandMonitor {
+    fn default() -> Self {
+        CommandMonitor::new()
+    }
+}
+
+impl CommandEventHandler for CommandMonitor {
+    /// # Summary
+    ///
+    /// Remember the collection and the request of a database command that has been started.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The CommandStartedEvent.
+    fn handle_command_started_event(&self, event: CommandStartedEvent) {
+        // The value of the first field of a command is the name of the collection it operates on
+        let collection = match event.command.iter().next() {
+            Some((_, v)) => v.as_str().unwrap_or("-").to_string(),
+            None => String::from("-"),
+        };
+
+        let pending = PendingCommand {
+            collection,
+            context: RequestContext::current(),
+        };
+
+        match self.pending.lock() {
+            Ok(mut p) => p.insert(event.request_id, pending),
+            Err(e) => e.into_inner().insert(event.request_id, pending),
+        };
+    }
+
+    /// # Summary
+    ///
+    /// Record a database command that succeeded.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The CommandSucceededEvent.
+    fn handle_command_succeeded_event(&self, event: CommandSucceededEvent) {
+        self.finish(event.request_id, &event.command_name, event.duration, false);
+    }
+
+    /// # Summary
+    ///
+    /// Record a database command that failed.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The CommandFailedEvent.
+    fn handle_command_failed_event(&self, event: CommandFailedEvent) {
+        self.finish(event.request_id, &event.command_name, event.duration, true);
+    }
+}
+
+/// Forwards the command events of the database driver, which accepts a single handler, to multiple handlers.
+pub struct CommandEventHandlers {
+    handlers: Vec<Arc<dyn CommandEventHandler>>,
+}
+
+impl CommandEventHandlers {
+    /// # Summary
+    ///
+    /// Create a new CommandEventHandlers.
+    ///
+    /// # Arguments
+    ///
+    /// * `handlers` - The CommandEventHandlers to forward the events to.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let handlers = CommandEventHandlers::new(vec![slow_query_logger, command_monitor]);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `CommandEventHandlers` - The new CommandEventHandlers.
+    pub fn new(handlers: Vec<Arc<dyn CommandEventHandler>>) -> CommandEventHandlers {
+        CommandEventHandlers { handlers }
+    }
+}
+
+impl CommandEventHandler for CommandEventHandlers {
+    fn handle_command_started_event(&self, event: CommandStartedEvent) {
+        for h in &self.handlers {
+            h.handle_command_started_event(event.clone());
+        }
+    }
+
+    fn handle_command_succeeded_event(&self, event: CommandSucceededEvent) {
+        for h in &self.handlers {
+            h.handle_command_succeeded_event(event.clone());
+        }
+    }
+
+    fn handle_command_failed_event(&self, event: CommandFailedEvent) {
+        for h in &self.handlers {
+            h.handle_command_failed_event(event.clone());
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use crate::services::audit::audit_service::AuditService;
+use crate::services::backup::backup_service::BackupService;
+use crate::services::export::s3_client::S3Client;
+use actix_web::rt;
+use chrono::{DateTime, Duration as ChronoDuration, TimeZone, Utc};
+use log::{error, info};
+use mongodb::Database;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The format of the timestamps in the keys of the exported objects.
+const KEY_TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+#[derive(Clone)]
+pub struct DataExporter {
+    database: Database,
+    audit_service: Arc<dyn AuditService>,
+    backup_service: Arc<dyn BackupService>,
+    s3_client: Option<S3Client>,
+    prefix: String,
+    interval: Duration,
+}
+
+impl DataExporter {
+    /// # Summary
+    ///
+    /// Create a new DataExporter.
+    ///
+    /// # Arguments
+    ///
+    /// * `database` - The Database that contains the Audits and Users.
+    /// * `audit_service` - The AuditService that finds the Audits to export.
+    /// * `backup_service` - The BackupService that creates the snapshots of the Users.
+    /// * `s3_client` - The S3Client that writes the exports, or None to disable the exporter.
+    /// * `prefix` - The prefix of the keys of the exported objects.
+    /// * `interval` - The interval at which the exports are written, or zero to disable the exporter.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let data_exporter = DataExporter::new(db.clone(), audit_service.clone(), backup_service.clone(), Some(s3_client), String::from("auth-rs/"), Duration::from_secs(86400));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `DataExporter` - The new DataExporter.
+    pub fn new(
+        database: Database,
+        audit_service: Arc<dyn AuditService>,
+        backup_service: Arc<dyn BackupService>,
+        s3_client: Option<S3Client>,
+        prefix: String,
+        interval: Duration,
+    ) -> DataExporter {
+        DataExporter {
+            database,
+            audit_service,
+            backup_service,
+            s3_client,
+            prefix,
+            interval,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Start a background task that periodically writes the Audits of the past interval and a snapshot of the
+    /// Permissions, Roles and Users to the S3-compatible storage.
+    /// The task must be started from within an Actix runtime.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// config.data_exporter.start();
+    /// ```
+    pub fn start(&self) {
+        let s3_client = match &self.s3_client {
+            Some(c) => c.clone(),
+            None => return,
+        };
+
+        if self.interval.is_zero() {
+            return;
+        }
+
+        let exporter = self.clone();
+        rt::spawn(async move {
+            loop {
+                // Wait until the current window has ended, with a little slack for the difference between the clocks
+                let (_, until) = exporter.window(Utc::now());
+                let wait = (until + exporter.interval_duration()) - Utc::now();
+                rt::time::sleep(wait.to_std().unwrap_or(Duration::ZERO) + Duration::from_secs(1))
+                    .await;
+
+                let (from, until) = exporter.window(Utc::now());
+
+                if cfg!(feature = "audit") {
+                    if let Err(e) = exporter.export_audits(&s3_client, from, until).await {
+                        error!("Failed to export Audits: {}", e);
+                    }
+                }
+
+                if let Err(e) = exporter.export_snapshot(&s3_client, Utc::now()).await {
+                    error!("Failed to export the User snapshot: {}", e);
+                }
+            }
+        });
+    }
+
+    /// # Summary
+    ///
+    /// Get the interval as a chrono Duration.
+    ///
+    /// # Returns
+    ///
+    /// * `ChronoDuration` - The interval.
+    fn interval_duration(&self) -> ChronoDuration {
+        ChronoDuration::seconds(self.interval.as_secs() as i64)
+    }
+
+    /// # Summary
+    ///
+    /// Get the window of Audits that is exported next. Windows are aligned to the interval, so that every Audit is
+    /// exported exactly once and every instance writes the same window to the same key.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current moment.
+    ///
+    /// # Returns
+    ///
+    /// * `(DateTime<Utc>, DateTime<Utc>)` - The start and the end of the last window that has ended.
+    fn window(&self, now: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+        let interval = self.interval.as_secs() as i64;
+        let end = now.timestamp() - now.timestamp().rem_euclid(interval);
+        let until = Utc.timestamp_opt(end, 0).single().unwrap_or(now);
+
+        (until - self.interval_duration(), until)
+    }
+
+    /// # Summary
+    ///
+    /// Write the Audits that were created within a window to the storage.
+    ///
+    /// # Arguments
+    ///
+    /// * `s3_client` - The S3Client that writes the export.
+    /// * `from` - The start of the window, inclusive.
+    /// * `until` - The end of the window, exclusive.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), String>` - The result of the operation.
+    async fn export_audits(
+        &self,
+        s3_client: &S3Client,
+        from: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<(), String> {
+        let audits = self
+            .audit_service
+            .find_created_between(from, until, &self.database)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let body = serde_json::to_vec(&audits).map_err(|e| e.to_string())?;
+        let key = format!(
+            "{}audits/{}.json",
+            self.prefix,
+            from.format(KEY_TIMESTAMP_FORMAT)
+        );
+
+        s3_client
+            .put_object(&key, body, "application/json")
+            .await
+            .map_err(|e| e.to_string())?;
+
+        info!("Exported {} audit(s) to {}", audits.len(), key);
+        Ok(())
+    }
+
+    /// # Summary
+    ///
+    /// Write a snapshot of the Permissions, Roles and Users to the storage. Password hashes are never exported.
+    ///
+    /// # Arguments
+    ///
+    /// * `s3_client` - The S3Client that writes the export.
+    /// * `now` - The moment of the snapshot.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), String>` - The result of the operation.
+    async fn export_snapshot(
+        &self,
+        s3_client: &S3Client,
+        now: DateTime<Utc>,
+    ) -> Result<(), String> {
+        let snapshot = self
+            .backup_service
+            .snapshot(&self.database)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let body = serde_json::to_vec(&snapshot).map_err(|e| e.to_string())?;
+        let key = format!(
+            "{}users/{}.json",
+            self.prefix,
+            now.format(KEY_TIMESTAMP_FORMAT)
+        );
+
+        s3_client
+            .put_object(&key, body, "application/json")
+            .await
+            .map_err(|e| e.to_string())?;
+
+        info!("Exported {} user(s) to {}", snapshot.users.len(), key);
+        Ok(())
+    }
+}
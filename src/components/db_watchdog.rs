@@ -0,0 +1,96 @@
+use actix_web::rt;
+use log::{error, info};
+use mongodb::bson::doc;
+use mongodb::Database;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Clone)]
+pub struct DbWatchdog {
+    database: Database,
+    interval: Duration,
+    healthy: Arc<AtomicBool>,
+}
+
+impl DbWatchdog {
+    /// # Summary
+    ///
+    /// Create a new DbWatchdog.
+    ///
+    /// # Arguments
+    ///
+    /// * `database` - The Database that should be monitored.
+    /// * `interval` - The interval at which the Database is pinged, or zero to disable the watchdog.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let db_watchdog = DbWatchdog::new(db.clone(), Duration::from_secs(30));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `DbWatchdog` - The new DbWatchdog.
+    pub fn new(database: Database, interval: Duration) -> DbWatchdog {
+        DbWatchdog {
+            database,
+            interval,
+            healthy: Arc::new(AtomicBool::new(true)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check whether the last ping to the Database succeeded.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the Database is reachable, false otherwise.
+    pub fn is_healthy(&self) -> bool {
+        self.healthy.load(Ordering::Relaxed)
+    }
+
+    /// # Summary
+    ///
+    /// Start a background task that periodically pings the Database and updates the health state.
+    /// Outages and reconnections are logged when the health state changes.
+    /// The task must be started from within an Actix runtime.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// config.db_watchdog.start();
+    /// ```
+    pub fn start(&self) {
+        if self.interval.is_zero() {
+            return;
+        }
+
+        let watchdog = self.clone();
+        rt::spawn(async move {
+            loop {
+                rt::time::sleep(watchdog.interval).await;
+                watchdog.ping().await;
+            }
+        });
+    }
+
+    /// # Summary
+    ///
+    /// Ping the Database once and update the health state.
+    async fn ping(&self) {
+        match self.database.run_command(doc! { "ping": 1 }, None).await {
+            Ok(_) => {
+                if !self.healthy.swap(true, Ordering::Relaxed) {
+                    info!("Database connection has been restored");
+                }
+            }
+            Err(e) => {
+                if self.healthy.swap(false, Ordering::Relaxed) {
+                    error!("Database connection has been lost: {}", e);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use crate::services::email::email_sender::EmailSender;
+use crate::services::email_job::email_job_service::EmailJobService;
+use actix_web::rt;
+use log::{error, info};
+use mongodb::Database;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Clone)]
+pub struct EmailWorker {
+    database: Database,
+    email_job_service: Arc<dyn EmailJobService>,
+    interval: Duration,
+}
+
+impl EmailWorker {
+    /// # Summary
+    ///
+    /// Create a new EmailWorker.
+    ///
+    /// # Arguments
+    ///
+    /// * `database` - The Database that contains the queued emails.
+    /// * `email_job_service` - The EmailJobService that sends the queued emails.
+    /// * `interval` - The interval at which queued emails are sent, or zero to disable the worker.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let email_worker = EmailWorker::new(db.clone(), email_job_service.clone(), Duration::from_secs(10));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `EmailWorker` - The new EmailWorker.
+    pub fn new(
+        database: Database,
+        email_job_service: Arc<dyn EmailJobService>,
+        interval: Duration,
+    ) -> EmailWorker {
+        EmailWorker {
+            database,
+            email_job_service,
+            interval,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Start a background task that periodically sends the queued emails using the given EmailSender.
+    /// Nothing is started if no EmailSender is registered, in which case no emails are queued either.
+    /// The task must be started from within an Actix runtime.
+    ///
+    /// # Arguments
+    ///
+    /// * `email_sender` - The registered EmailSender, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// config.email_worker.start(config.services.email_sender.clone());
+    /// ```
+    pub fn start(&self, email_sender: Option<Arc<dyn EmailSender>>) {
+        let email_sender = match email_sender {
+            Some(s) => s,
+            None => return,
+        };
+
+        if self.interval.is_zero() {
+            return;
+        }
+
+        let worker = self.clone();
+        rt::spawn(async move {
+            loop {
+                rt::time::sleep(worker.interval).await;
+                match worker
+                    .email_job_service
+                    .process(email_sender.clone(), &worker.database)
+                    .await
+                {
+                    Ok(0) => (),
+                    Ok(sent) => info!("Sent {} queued email(s)", sent),
+                    Err(e) => error!("Failed to process the email queue: {}", e),
+                }
+            }
+        });
+    }
+}
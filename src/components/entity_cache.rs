@@ -0,0 +1,206 @@
+use mongodb::Database;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Debug)]
+pub struct CacheStats {
+    pub name: String,
+    pub entries: usize,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl CacheStats {
+    /// # Summary
+    ///
+    /// Get the ratio of lookups that were served from the cache.
+    ///
+    /// # Returns
+    ///
+    /// * `f64` - The hit rate between zero and one, or zero if no lookup was made.
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            t => self.hits as f64 / t as f64,
+        }
+    }
+}
+
+/// An in-process cache of entities by their key. Entries expire after the configured time to live, so that changes
+/// that were made by other instances of the application are eventually picked up.
+pub struct EntityCache<T: Clone> {
+    name: String,
+    ttl: Duration,
+    entries: Mutex<HashMap<String, (T, Instant)>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl<T: Clone> EntityCache<T> {
+    /// # Summary
+    ///
+    /// Create a new EntityCache.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the cache, as reported in its statistics.
+    /// * `ttl` - The time after which an entry expires.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let role_cache: EntityCache<Role> = EntityCache::new("roles", Duration::from_secs(60));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `EntityCache<T>` - The new EntityCache.
+    pub fn new(name: &str, ttl: Duration) -> EntityCache<T> {
+        EntityCache {
+            name: name.to_string(),
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Look up multiple entries at once.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - The keys of the entries.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let (roles, missing) = role_cache.get_many(&keys);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `(Vec<T>, Vec<String>)` - The entries that were found, and the keys of the entries that were missing or expired.
+    pub fn get_many(&self, keys: &[String]) -> (Vec<T>, Vec<String>) {
+        let mut found: Vec<T> = vec![];
+        let mut missing: Vec<String> = vec![];
+
+        {
+            let entries = match self.entries.lock() {
+                Ok(e) => e,
+                Err(e) => e.into_inner(),
+            };
+
+            for key in keys {
+                match entries.get(key) {
+                    Some((v, inserted_at)) if inserted_at.elapsed() < self.ttl => {
+                        found.push(v.clone())
+                    }
+                    _ => missing.push(key.clone()),
+                }
+            }
+        }
+
+        self.hits.fetch_add(found.len() as u64, Ordering::Relaxed);
+        self.misses
+            .fetch_add(missing.len() as u64, Ordering::Relaxed);
+
+        (found, missing)
+    }
+
+    /// # Summary
+    ///
+    /// Insert or replace multiple entries at once.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The keys and the entries to insert.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// role_cache.insert_many(roles.into_iter().map(|r| (r.id.to_hex(), r)).collect());
+    /// ```
+    pub fn insert_many(&self, values: Vec<(String, T)>) {
+        let now = Instant::now();
+        let mut entries = match self.entries.lock() {
+            Ok(e) => e,
+            Err(e) => e.into_inner(),
+        };
+
+        entries.retain(|_, (_, inserted_at)| inserted_at.elapsed() < self.ttl);
+        for (key, value) in values {
+            entries.insert(key, (value, now));
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Remove all entries from the cache.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// role_cache.clear();
+    /// ```
+    pub fn clear(&self) {
+        match self.entries.lock() {
+            Ok(mut e) => e.clear(),
+            Err(e) => e.into_inner().clear(),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Get the statistics of the cache.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let stats = role_cache.stats();
+    /// println!("{}: {:.2}", stats.name, stats.hit_rate());
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `CacheStats` - The number of entries, hits and misses of the cache.
+    pub fn stats(&self) -> CacheStats {
+        let entries = match self.entries.lock() {
+            Ok(e) => e.len(),
+            Err(e) => e.into_inner().len(),
+        };
+
+        CacheStats {
+            name: self.name.clone(),
+            entries,
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// # Summary
+///
+/// Build the key of an entity in an EntityCache. The name of the database is part of the key, because entities of
+/// different tenants can be served by the same process.
+///
+/// # Arguments
+///
+/// * `db` - The Database that holds the entity.
+/// * `id` - The ID of the entity.
+///
+/// # Example
+///
+/// ```
+/// let key = cache_key(&db, &role.id.to_hex());
+/// ```
+///
+/// # Returns
+///
+/// * `String` - The key of the entity.
+pub fn cache_key(db: &Database, id: &str) -> String {
+    format!("{}:{}", db.name(), id)
+}
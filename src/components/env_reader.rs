@@ -1,10 +1,43 @@
+use crate::configuration::admin_config::AdminConfig;
+use crate::configuration::backup_config::BackupConfig;
+use crate::configuration::cache_config::CacheConfig;
+use crate::configuration::captcha_config::CaptchaConfig;
 use crate::configuration::config::Config;
+use crate::configuration::config_error::ConfigError;
 use crate::configuration::db_config::DbConfig;
 use crate::configuration::default_user_config::DefaultUserConfig;
-use crate::configuration::jwt_config::JwtConfig;
+use crate::configuration::device_config::DeviceConfig;
+use crate::configuration::email_config::EmailConfig;
+use crate::configuration::email_queue_config::EmailQueueConfig;
+use crate::configuration::export_config::ExportConfig;
+use crate::configuration::guest_config::GuestConfig;
+use crate::configuration::health_config::HealthConfig;
+use crate::configuration::invitation_config::InvitationConfig;
+use crate::configuration::jwt_config::{JwtConfig, PreviousJwtKey, TokenMode};
+use crate::configuration::magic_link_config::MagicLinkConfig;
+use crate::configuration::mfa_config::MfaConfig;
+use crate::configuration::permission_config::PermissionConfig;
+use crate::configuration::registration_config::RegistrationConfig;
+use crate::configuration::request_logging_config::RequestLoggingConfig;
+use crate::configuration::role_change_config::RoleChangeConfig;
+use crate::configuration::role_template::RoleTemplate;
 use crate::configuration::server_config::ServerConfig;
+use crate::configuration::social_login_config::{SocialLoginConfig, SocialProviderConfig};
+use crate::configuration::sso_config::SsoConfig;
+use crate::configuration::user_store_config::UserStoreConfig;
+use crate::configuration::webauthn_config::WebauthnConfig;
+use crate::services::event::syslog_sink::{SyslogProtocol, SyslogSink};
+use crate::services::jwt::static_claims_enricher::StaticClaimsEnricher;
+use crate::services::quota::quota_service::QuotaMode;
+use crate::services::social::social_provider::SocialProvider;
+use jsonwebtoken::Algorithm;
 use log::info;
-use std::env;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::{env, fs};
 
 pub struct EnvReader {}
 
@@ -24,51 +57,39 @@ impl EnvReader {
     ///
     /// # Returns
     ///
-    /// A Config instance.
-    pub async fn read_configuration() -> Config {
+    /// * `Result<Config, Vec<ConfigError>>` - The Config instance or every ConfigError that was encountered.
+    pub async fn read_configuration() -> Result<Config, Vec<ConfigError>> {
         info!("Reading configuration from environment variables");
 
+        let mut errors: Vec<ConfigError> = vec![];
+
         let addr = match env::var("SERVER_ADDR") {
             Ok(d) => d,
             Err(_) => String::from("0.0.0.0"),
         };
 
-        let port = match env::var("SERVER_PORT") {
-            Ok(d) => {
-                let res: u16 = d.trim().parse().expect("PORT must be a number");
-                res
-            }
-            Err(_) => 8080,
-        };
+        let port: u16 = Self::parse_variable("SERVER_PORT", 8080, &mut errors);
 
-        let max_limit = match env::var("MAX_FETCH_LIMIT") {
-            Ok(d) => {
-                let res: i64 = d.trim().parse().expect("MAX_FETCH_LIMIT must be a number");
-                res
-            }
-            Err(_) => 100,
-        };
+        let max_limit: i64 = Self::parse_variable("MAX_FETCH_LIMIT", 100, &mut errors);
 
-        let workers = match env::var("SERVER_WORKERS") {
-            Ok(d) => {
-                let res: usize = d
-                    .trim()
-                    .parse()
-                    .expect("SERVER_WORKERS must be a valid usize");
-                res
-            }
-            Err(_) => 0,
-        };
+        let workers: usize = Self::parse_variable("SERVER_WORKERS", 0, &mut errors);
 
-        let conn_string = match env::var("DB_CONNECTION_STRING") {
-            Ok(d) => d,
-            Err(_) => panic!("No connection string specified"),
-        };
+        let token_quota: u32 = Self::parse_variable("TOKEN_QUOTA_PER_MINUTE", 0, &mut errors);
 
-        let database = match env::var("DB_DATABASE") {
-            Ok(d) => d,
-            Err(_) => panic!("No database specified"),
-        };
+        let token_quota_mode: QuotaMode =
+            Self::parse_variable("TOKEN_QUOTA_MODE", QuotaMode::Enforce, &mut errors);
+
+        let user_schedule_interval: u64 =
+            Self::parse_variable("USER_SCHEDULE_INTERVAL", 60, &mut errors);
+
+        let max_user_roles: usize = Self::parse_variable("MAX_USER_ROLES", 100, &mut errors);
+
+        let max_role_permissions: usize =
+            Self::parse_variable("MAX_ROLE_PERMISSIONS", 1000, &mut errors);
+
+        let conn_string = Self::required_variable("DB_CONNECTION_STRING", &mut errors);
+
+        let database = Self::required_variable("DB_DATABASE", &mut errors);
 
         let permission_collection = match env::var("DB_PERMISSION_COLLECTION") {
             Ok(d) => d,
@@ -90,99 +111,635 @@ impl EnvReader {
             Err(_) => String::from("audits"),
         };
 
-        let jwt_secret = match env::var("JWT_SECRET") {
+        let device_collection = match env::var("DB_DEVICE_COLLECTION") {
             Ok(d) => d,
-            Err(_) => panic!("No JWT secret specified"),
+            Err(_) => String::from("devices"),
         };
 
-        let jwt_expiration = match env::var("JWT_EXPIRATION") {
-            Ok(d) => {
-                let res: usize = d.trim().parse().expect("JWT_EXPIRATION must be a number");
-                res
-            }
-            Err(_) => 3600,
+        let client_collection = match env::var("DB_CLIENT_COLLECTION") {
+            Ok(d) => d,
+            Err(_) => String::from("clients"),
         };
 
-        let generate_default_user = match env::var("GENERATE_DEFAULT_USER") {
-            Ok(d) => {
-                let res: bool = d
-                    .trim()
-                    .parse()
-                    .expect("GENERATE_DEFAULT_USER must be a boolean");
-                res
-            }
-            Err(_) => true,
+        let consent_collection = match env::var("DB_CONSENT_COLLECTION") {
+            Ok(d) => d,
+            Err(_) => String::from("consents"),
+        };
+
+        let organization_collection = match env::var("DB_ORGANIZATION_COLLECTION") {
+            Ok(d) => d,
+            Err(_) => String::from("organizations"),
+        };
+
+        let invitation_collection = match env::var("DB_INVITATION_COLLECTION") {
+            Ok(d) => d,
+            Err(_) => String::from("invitations"),
+        };
+
+        let refresh_token_collection = match env::var("DB_REFRESH_TOKEN_COLLECTION") {
+            Ok(d) => d,
+            Err(_) => String::from("refreshTokens"),
+        };
+
+        let revoked_token_collection = match env::var("DB_REVOKED_TOKEN_COLLECTION") {
+            Ok(d) => d,
+            Err(_) => String::from("revokedTokens"),
         };
 
+        let email_job_collection = match env::var("DB_EMAIL_JOB_COLLECTION") {
+            Ok(d) => d,
+            Err(_) => String::from("emailJobs"),
+        };
+
+        let user_summary_collection = match env::var("DB_USER_SUMMARY_COLLECTION") {
+            Ok(d) => d,
+            Err(_) => String::from("userSummaries"),
+        };
+
+        let session_collection = match env::var("DB_SESSION_COLLECTION") {
+            Ok(d) => d,
+            Err(_) => String::from("sessions"),
+        };
+
+        let api_key_collection = match env::var("DB_API_KEY_COLLECTION") {
+            Ok(d) => d,
+            Err(_) => String::from("apiKeys"),
+        };
+
+        let authorization_code_collection = match env::var("DB_AUTHORIZATION_CODE_COLLECTION") {
+            Ok(d) => d,
+            Err(_) => String::from("authorizationCodes"),
+        };
+
+        let role_change_collection = match env::var("DB_ROLE_CHANGE_COLLECTION") {
+            Ok(d) => d,
+            Err(_) => String::from("roleChanges"),
+        };
+
+        let credential_collection = match env::var("DB_CREDENTIAL_COLLECTION") {
+            Ok(d) => d,
+            Err(_) => String::from("credentials"),
+        };
+
+        let credential_ceremony_collection = match env::var("DB_CREDENTIAL_CEREMONY_COLLECTION") {
+            Ok(d) => d,
+            Err(_) => String::from("credentialCeremonies"),
+        };
+
+        let magic_link_collection = match env::var("DB_MAGIC_LINK_COLLECTION") {
+            Ok(d) => d,
+            Err(_) => String::from("magicLinks"),
+        };
+
+        let jwt_algorithm: Algorithm =
+            Self::parse_variable("JWT_ALGORITHM", Algorithm::HS256, &mut errors);
+
+        let jwt_expiration: usize = Self::parse_variable("JWT_EXPIRATION", 3600, &mut errors);
+
+        let jwt_leeway: u64 = Self::parse_variable("JWT_LEEWAY", 60, &mut errors);
+
+        let jwt_refresh_expiration: u64 =
+            Self::parse_variable("JWT_REFRESH_EXPIRATION", 1209600, &mut errors);
+
+        let mut jwt_config = JwtConfig::new(
+            env::var("JWT_SECRET").unwrap_or_default(),
+            jwt_expiration,
+            jwt_leeway,
+            jwt_refresh_expiration,
+        );
+        jwt_config.jwt_algorithm = jwt_algorithm;
+
+        // Tokens are signed with either the HMAC secret or the key pair, depending on the algorithm
+        if jwt_config.is_asymmetric() {
+            let jwt_private_key = Self::key_variable("JWT_PRIVATE_KEY", &mut errors);
+            if jwt_private_key.is_none() {
+                errors.push(ConfigError::MissingVariable(String::from(
+                    "JWT_PRIVATE_KEY",
+                )));
+            }
+
+            match Self::key_variable("JWT_PUBLIC_KEY", &mut errors) {
+                Some(k) => jwt_config = jwt_config.with_key_pair(jwt_algorithm, jwt_private_key, k),
+                None => errors.push(ConfigError::MissingVariable(String::from("JWT_PUBLIC_KEY"))),
+            }
+        } else if env::var("JWT_SECRET").is_err() {
+            errors.push(ConfigError::MissingVariable(String::from("JWT_SECRET")));
+        }
+
+        // Previous keys keep verifying the tokens that were issued before the signing key was rotated
+        if let Ok(d) = env::var("JWT_PREVIOUS_KEYS") {
+            match serde_json::from_str::<Vec<PreviousJwtKey>>(d.trim()) {
+                Ok(keys) => jwt_config = jwt_config.with_previous_keys(keys),
+                Err(e) => errors.push(ConfigError::invalid(
+                    "JWT_PREVIOUS_KEYS",
+                    &format!(
+                        "must be a JSON array of objects with a secret or publicKey and an expiresAt ({})",
+                        e
+                    ),
+                )),
+            }
+        }
+
+        jwt_config = jwt_config.with_issuer_and_audience(
+            Self::optional_variable("JWT_ISSUER"),
+            Self::optional_variable("JWT_AUDIENCE"),
+        );
+
+        jwt_config = jwt_config.with_token_mode(Self::parse_variable(
+            "TOKEN_MODE",
+            TokenMode::Jwt,
+            &mut errors,
+        ));
+
+        let generate_default_user: bool =
+            Self::parse_variable("GENERATE_DEFAULT_USER", true, &mut errors);
+
         let mut default_username = String::new();
         let mut default_email = Some(String::new());
         let mut default_password = String::new();
         let mut default_user_enabled = false;
+        let mut default_user_require_password_change = false;
+        let mut default_user_disable_after_admin = false;
 
         if generate_default_user {
-            default_username = match env::var("DEFAULT_USER_USERNAME") {
-                Ok(d) => d,
-                Err(_) => panic!("No default username specified"),
-            };
+            default_username = Self::required_variable("DEFAULT_USER_USERNAME", &mut errors);
 
             default_email = match env::var("DEFAULT_USER_EMAIL") {
                 Ok(d) => Some(d),
                 Err(_) => None,
             };
 
-            default_password = match env::var("DEFAULT_USER_PASSWORD") {
-                Ok(d) => d,
-                Err(_) => panic!("No default password specified"),
-            };
+            default_password = Self::required_variable("DEFAULT_USER_PASSWORD", &mut errors);
 
             default_user_enabled = match env::var("DEFAULT_USER_ENABLED") {
-                Ok(d) => {
-                    let res: bool = d
-                        .trim()
-                        .parse()
-                        .expect("DEFAULT_USER_ENABLED must be a boolean");
-                    res
+                Ok(_) => Self::parse_variable("DEFAULT_USER_ENABLED", false, &mut errors),
+                Err(_) => {
+                    errors.push(ConfigError::MissingVariable(String::from(
+                        "DEFAULT_USER_ENABLED",
+                    )));
+                    false
                 }
-                Err(_) => panic!("No default user enabled specified"),
             };
+
+            default_user_require_password_change =
+                Self::parse_variable("DEFAULT_USER_REQUIRE_PASSWORD_CHANGE", false, &mut errors);
+
+            default_user_disable_after_admin =
+                Self::parse_variable("DEFAULT_USER_DISABLE_AFTER_ADMIN", false, &mut errors);
         }
 
-        let audit_enabled = match env::var("DB_AUDIT_ENABLED") {
-            Ok(d) => {
-                let res: bool = d
-                    .trim()
-                    .parse()
-                    .expect("DB_AUDIT_ENABLED must be a boolean");
-                res
+        let audit_enabled: bool = Self::parse_variable("DB_AUDIT_ENABLED", false, &mut errors);
+
+        let audit_ttl: u64 = Self::parse_variable("DB_AUDIT_TTL", 0, &mut errors);
+
+        let slow_query_threshold: u64 =
+            Self::parse_variable("DB_SLOW_QUERY_THRESHOLD", 0, &mut errors);
+
+        let health_check_interval: u64 =
+            Self::parse_variable("DB_HEALTH_CHECK_INTERVAL", 30, &mut errors);
+
+        let read_preference = Self::optional_variable("DB_READ_PREFERENCE");
+
+        let read_concern = Self::optional_variable("DB_READ_CONCERN");
+
+        let write_concern = Self::optional_variable("DB_WRITE_CONCERN");
+
+        let audit_write_concern = Self::optional_variable("DB_AUDIT_WRITE_CONCERN");
+        let audit_chaining: bool = Self::parse_variable("DB_AUDIT_CHAINING", false, &mut errors);
+
+        let command_monitoring: bool =
+            Self::parse_variable("DB_COMMAND_MONITORING", false, &mut errors);
+
+        let tenant_connections: HashMap<String, String> = match env::var("DB_TENANT_CONNECTIONS") {
+            Ok(d) => match serde_json::from_str(d.trim()) {
+                Ok(c) => c,
+                Err(e) => {
+                    errors.push(ConfigError::invalid(
+                        "DB_TENANT_CONNECTIONS",
+                        &format!(
+                            "must be a JSON object of Organization IDs and connection strings ({})",
+                            e
+                        ),
+                    ));
+                    HashMap::new()
+                }
+            },
+            Err(_) => HashMap::new(),
+        };
+
+        let create_indexes: bool = Self::parse_variable("DB_CREATE_INDEXES", true, &mut errors);
+
+        let create_validators: bool =
+            Self::parse_variable("DB_CREATE_VALIDATORS", false, &mut errors);
+
+        let enable_openapi: bool = Self::parse_variable("ENABLE_OPENAPI", true, &mut errors);
+
+        let enable_swagger_ui: bool = Self::parse_variable("ENABLE_SWAGGER_UI", true, &mut errors);
+
+        let request_logging: bool = Self::parse_variable("REQUEST_LOGGING", false, &mut errors);
+
+        let request_logging_headers: bool =
+            Self::parse_variable("REQUEST_LOGGING_HEADERS", false, &mut errors);
+
+        let request_logging_bodies: bool =
+            Self::parse_variable("REQUEST_LOGGING_BODIES", false, &mut errors);
+
+        let health_token = Self::optional_variable("HEALTH_TOKEN");
+
+        let health_addr = match env::var("HEALTH_ADDR") {
+            Ok(d) => d,
+            Err(_) => String::from("127.0.0.1"),
+        };
+
+        let health_port: u16 = Self::parse_variable("HEALTH_PORT", 0, &mut errors);
+
+        let mut admin_allowed_ips: Vec<IpAddr> = vec![];
+
+        if let Some(d) = Self::optional_variable("ADMIN_ALLOWED_IPS") {
+            for ip in d.split(',').map(|i| i.trim()).filter(|i| !i.is_empty()) {
+                match IpAddr::from_str(ip) {
+                    Ok(i) => admin_allowed_ips.push(i),
+                    Err(e) => errors.push(ConfigError::invalid(
+                        "ADMIN_ALLOWED_IPS",
+                        &format!("'{}' is not an IP address ({})", ip, e),
+                    )),
+                }
             }
-            Err(_) => false,
+        }
+
+        let admin_addr = match env::var("ADMIN_ADDR") {
+            Ok(d) => d,
+            Err(_) => String::from("127.0.0.1"),
+        };
+
+        let admin_port: u16 = Self::parse_variable("ADMIN_PORT", 0, &mut errors);
+
+        let cache_ttl: u64 = Self::parse_variable("CACHE_TTL", 0, &mut errors);
+        let cache_warming: bool = Self::parse_variable("CACHE_WARMING", false, &mut errors);
+
+        let email_strip_plus_tags: bool =
+            Self::parse_variable("EMAIL_STRIP_PLUS_TAGS", false, &mut errors);
+
+        let gravatar_enabled: bool = Self::parse_variable("GRAVATAR_ENABLED", false, &mut errors);
+
+        let email_hash_key = Self::optional_variable("EMAIL_HASH_KEY");
+
+        let email_queue_interval: u64 =
+            Self::parse_variable("EMAIL_QUEUE_INTERVAL", 10, &mut errors);
+        let email_queue_rate_limit: u32 =
+            Self::parse_variable("EMAIL_QUEUE_RATE_LIMIT", 20, &mut errors);
+        let email_queue_max_attempts: u32 =
+            Self::parse_variable("EMAIL_QUEUE_MAX_ATTEMPTS", 5, &mut errors);
+        let email_queue_retry_delay: u64 =
+            Self::parse_variable("EMAIL_QUEUE_RETRY_DELAY", 60, &mut errors);
+
+        let email_welcome_subject = match env::var("EMAIL_WELCOME_SUBJECT") {
+            Ok(d) => d,
+            Err(_) => String::from("Welcome, {username}"),
         };
 
-        let audit_ttl = match env::var("DB_AUDIT_TTL") {
-            Ok(d) => {
-                let res: u64 = d.trim().parse().expect("DB_AUDIT_TTL must be a number");
-                res
+        // Line breaks can be written as \n, so that the template fits on a single line of the .env file
+        let email_welcome_body = match env::var("EMAIL_WELCOME_BODY") {
+            Ok(d) => d.replace("\\n", "\n"),
+            Err(_) => {
+                String::from("Hello {username},\n\nYour account was created. Welcome aboard!")
             }
-            Err(_) => 0,
         };
 
-        let create_indexes = match env::var("DB_CREATE_INDEXES") {
-            Ok(d) => {
-                let res: bool = d
-                    .trim()
-                    .parse()
-                    .expect("DB_CREATE_INDEXES must be a boolean");
-                res
+        let backup_secret = Self::optional_variable("BACKUP_SECRET");
+
+        let export_endpoint = Self::optional_variable("EXPORT_S3_ENDPOINT");
+        let export_bucket = Self::optional_variable("EXPORT_S3_BUCKET").unwrap_or_default();
+        let export_region = match Self::optional_variable("EXPORT_S3_REGION") {
+            Some(d) => d,
+            None => String::from("us-east-1"),
+        };
+        let export_access_key_id =
+            Self::optional_variable("EXPORT_S3_ACCESS_KEY_ID").unwrap_or_default();
+        let export_secret_access_key =
+            Self::optional_variable("EXPORT_S3_SECRET_ACCESS_KEY").unwrap_or_default();
+        let export_prefix = match env::var("EXPORT_S3_PREFIX") {
+            Ok(d) => d,
+            Err(_) => String::from("auth-rs/"),
+        };
+        let export_interval: u64 = Self::parse_variable("EXPORT_INTERVAL", 86400, &mut errors);
+
+        // The bucket and the credentials are only required once an endpoint is configured
+        if export_endpoint.is_some() {
+            for (variable, value) in [
+                ("EXPORT_S3_BUCKET", &export_bucket),
+                ("EXPORT_S3_ACCESS_KEY_ID", &export_access_key_id),
+                ("EXPORT_S3_SECRET_ACCESS_KEY", &export_secret_access_key),
+            ] {
+                if value.is_empty() {
+                    errors.push(ConfigError::MissingVariable(variable.to_string()));
+                }
             }
-            Err(_) => true,
+        }
+
+        let device_verification_uri = match env::var("DEVICE_VERIFICATION_URI") {
+            Ok(d) => d,
+            Err(_) => String::from("/device"),
         };
 
-        let enable_openapi = match env::var("ENABLE_OPENAPI") {
-            Ok(d) => {
-                let res: bool = d.trim().parse().expect("ENABLE_OPENAPI must be a boolean");
-                res
+        let device_code_expiration: u64 =
+            Self::parse_variable("DEVICE_CODE_EXPIRATION", 600, &mut errors);
+
+        let device_code_interval: u64 =
+            Self::parse_variable("DEVICE_CODE_INTERVAL", 5, &mut errors);
+
+        let invitation_expiration: u64 =
+            Self::parse_variable("INVITATION_EXPIRATION", 604800, &mut errors);
+
+        let magic_link_url = Self::optional_variable("MAGIC_LINK_URL");
+        if magic_link_url
+            .as_ref()
+            .is_some_and(|u| !u.contains("{token}"))
+        {
+            errors.push(ConfigError::invalid(
+                "MAGIC_LINK_URL",
+                "must contain the {token} placeholder",
+            ));
+        }
+
+        let magic_link_expiration: u64 =
+            Self::parse_variable("MAGIC_LINK_EXPIRATION", 900, &mut errors);
+        if magic_link_expiration == 0 {
+            errors.push(ConfigError::invalid(
+                "MAGIC_LINK_EXPIRATION",
+                "must be greater than zero",
+            ));
+        }
+
+        let magic_link_subject = match env::var("MAGIC_LINK_SUBJECT") {
+            Ok(d) => d,
+            Err(_) => String::from("Your login link"),
+        };
+
+        let magic_link_body = match env::var("MAGIC_LINK_BODY") {
+            Ok(d) => d.replace("\\n", "\n"),
+            Err(_) => String::from(
+                "Hello {username},\n\nUse the following link to log in. It can only be used once.\n\n{link}\n\nIf you did not request this link, you can ignore this email.",
+            ),
+        };
+
+        let registration_enabled: bool =
+            Self::parse_variable("REGISTRATION_ENABLED", true, &mut errors);
+
+        let registration_require_approval: bool =
+            Self::parse_variable("REGISTRATION_REQUIRE_APPROVAL", false, &mut errors);
+
+        let registration_allowed_domains: Vec<String> =
+            match Self::optional_variable("REGISTRATION_ALLOWED_DOMAINS") {
+                Some(d) => d.split(',').map(String::from).collect(),
+                None => vec![],
+            };
+
+        let mut registration_domain_roles: HashMap<String, Vec<String>> = HashMap::new();
+
+        if let Some(d) = Self::optional_variable("REGISTRATION_DOMAIN_ROLES") {
+            for entry in d.split(';').filter(|e| !e.trim().is_empty()) {
+                match entry.split_once('=') {
+                    Some((domain, roles)) => {
+                        registration_domain_roles.insert(
+                            domain.to_string(),
+                            roles
+                                .split(',')
+                                .map(|r| r.trim().to_string())
+                                .filter(|r| !r.is_empty())
+                                .collect(),
+                        );
+                    }
+                    None => errors.push(ConfigError::invalid(
+                        "REGISTRATION_DOMAIN_ROLES",
+                        &format!("entry '{}' must be formatted as domain=ROLE,ROLE", entry),
+                    )),
+                }
+            }
+        }
+
+        let guest_tokens_enabled: bool =
+            Self::parse_variable("GUEST_TOKENS_ENABLED", false, &mut errors);
+
+        let guest_permissions: Vec<String> = match Self::optional_variable("GUEST_PERMISSIONS") {
+            Some(d) => d.split(',').map(String::from).collect(),
+            None => vec![],
+        };
+
+        let guest_token_expiration: usize =
+            Self::parse_variable("GUEST_TOKEN_EXPIRATION", 900, &mut errors);
+
+        let mut social_providers: Vec<SocialProviderConfig> = vec![];
+        for (provider, id_variable, secret_variable) in [
+            (
+                SocialProvider::Google,
+                "SOCIAL_GOOGLE_CLIENT_ID",
+                "SOCIAL_GOOGLE_CLIENT_SECRET",
+            ),
+            (
+                SocialProvider::GitHub,
+                "SOCIAL_GITHUB_CLIENT_ID",
+                "SOCIAL_GITHUB_CLIENT_SECRET",
+            ),
+        ] {
+            match (
+                Self::optional_variable(id_variable),
+                Self::optional_variable(secret_variable),
+            ) {
+                (Some(id), Some(secret)) => {
+                    social_providers.push(SocialProviderConfig::new(provider, id, secret))
+                }
+                (Some(_), None) => {
+                    errors.push(ConfigError::MissingVariable(secret_variable.to_string()))
+                }
+                (None, Some(_)) => {
+                    errors.push(ConfigError::MissingVariable(id_variable.to_string()))
+                }
+                (None, None) => {}
+            }
+        }
+
+        let social_redirect_uri =
+            Self::optional_variable("SOCIAL_REDIRECT_URI").unwrap_or_default();
+        if !social_providers.is_empty() && social_redirect_uri.is_empty() {
+            errors.push(ConfigError::MissingVariable(String::from(
+                "SOCIAL_REDIRECT_URI",
+            )));
+        }
+
+        let social_roles: Vec<String> = match Self::optional_variable("SOCIAL_ROLES") {
+            Some(d) => d
+                .split(',')
+                .map(|r| r.trim().to_string())
+                .filter(|r| !r.is_empty())
+                .collect(),
+            None => vec![],
+        };
+
+        let social_auto_create: bool =
+            Self::parse_variable("SOCIAL_AUTO_CREATE", true, &mut errors);
+
+        let sso_issuer = Self::optional_variable("SSO_ISSUER_URL");
+        let sso_client_id = Self::optional_variable("SSO_CLIENT_ID").unwrap_or_default();
+        let sso_client_secret = Self::optional_variable("SSO_CLIENT_SECRET").unwrap_or_default();
+        let sso_redirect_uri = Self::optional_variable("SSO_REDIRECT_URI").unwrap_or_default();
+        if sso_issuer.is_some() {
+            for (variable, value) in [
+                ("SSO_CLIENT_ID", &sso_client_id),
+                ("SSO_CLIENT_SECRET", &sso_client_secret),
+                ("SSO_REDIRECT_URI", &sso_redirect_uri),
+            ] {
+                if value.is_empty() {
+                    errors.push(ConfigError::MissingVariable(String::from(variable)));
+                }
             }
-            Err(_) => true,
+        }
+
+        let sso_scope =
+            Self::optional_variable("SSO_SCOPE").unwrap_or(String::from("openid email profile"));
+        if !sso_scope.split_whitespace().any(|s| s == "openid") {
+            errors.push(ConfigError::invalid("SSO_SCOPE", "must include openid"));
+        }
+
+        let sso_roles: Vec<String> = match Self::optional_variable("SSO_ROLES") {
+            Some(d) => d
+                .split(',')
+                .map(|r| r.trim().to_string())
+                .filter(|r| !r.is_empty())
+                .collect(),
+            None => vec![],
+        };
+
+        let sso_auto_create: bool = Self::parse_variable("SSO_AUTO_CREATE", true, &mut errors);
+
+        let captcha_secret = Self::optional_variable("CAPTCHA_SECRET");
+        let captcha_verify_url = Self::optional_variable("CAPTCHA_VERIFY_URL").unwrap_or(
+            String::from("https://www.google.com/recaptcha/api/siteverify"),
+        );
+        let captcha_threshold: u32 = Self::parse_variable("CAPTCHA_THRESHOLD", 3, &mut errors);
+        if captcha_threshold == 0 {
+            errors.push(ConfigError::invalid(
+                "CAPTCHA_THRESHOLD",
+                "must be greater than zero",
+            ));
+        }
+
+        let login_attempt_window: u64 =
+            Self::parse_variable("LOGIN_ATTEMPT_WINDOW", 900, &mut errors);
+
+        let mfa_secret = Self::optional_variable("MFA_SECRET");
+        let mfa_issuer = Self::optional_variable("MFA_ISSUER").unwrap_or(String::from("auth-rs"));
+        let mfa_challenge_expiration: u64 =
+            Self::parse_variable("MFA_CHALLENGE_EXPIRATION", 300, &mut errors);
+        if mfa_challenge_expiration == 0 {
+            errors.push(ConfigError::invalid(
+                "MFA_CHALLENGE_EXPIRATION",
+                "must be greater than zero",
+            ));
+        }
+
+        let role_change_approval: bool =
+            Self::parse_variable("ROLE_CHANGE_APPROVAL", false, &mut errors);
+
+        let webauthn_rp_id = Self::optional_variable("WEBAUTHN_RP_ID");
+        let webauthn_rp_origin = Self::optional_variable("WEBAUTHN_RP_ORIGIN");
+        if webauthn_rp_id.is_some() != webauthn_rp_origin.is_some() {
+            errors.push(ConfigError::invalid(
+                "WEBAUTHN_RP_ORIGIN",
+                "WEBAUTHN_RP_ID and WEBAUTHN_RP_ORIGIN must be specified together",
+            ));
+        }
+        let webauthn_rp_name =
+            Self::optional_variable("WEBAUTHN_RP_NAME").unwrap_or(String::from("auth-rs"));
+
+        let mut role_templates: Vec<RoleTemplate> = vec![];
+
+        if let Some(d) = Self::optional_variable("ROLE_TEMPLATES") {
+            for name in d.split(',').filter(|n| !n.trim().is_empty()) {
+                match RoleTemplate::from_str(name) {
+                    Ok(t) if !role_templates.contains(&t) => role_templates.push(t),
+                    Ok(_) => {}
+                    Err(e) => errors.push(ConfigError::invalid("ROLE_TEMPLATES", &e)),
+                }
+            }
+        }
+
+        let mut permission_overrides: HashMap<String, Option<String>> = HashMap::new();
+
+        if let Ok(d) = env::var("PERMISSION_OVERRIDES_FILE") {
+            match fs::read_to_string(d.trim()) {
+                Ok(content) => {
+                    match serde_json::from_str::<HashMap<String, Option<String>>>(&content) {
+                        Ok(overrides) => permission_overrides.extend(overrides),
+                        Err(e) => errors.push(ConfigError::invalid(
+                            "PERMISSION_OVERRIDES_FILE",
+                            &format!("must contain a JSON object ({})", e),
+                        )),
+                    }
+                }
+                Err(e) => errors.push(ConfigError::invalid(
+                    "PERMISSION_OVERRIDES_FILE",
+                    &format!("must point to a readable file ({})", e),
+                )),
+            }
+        }
+
+        if let Ok(d) = env::var("PERMISSION_OVERRIDES") {
+            for entry in d.split(';').filter(|e| !e.trim().is_empty()) {
+                match entry.split_once('=') {
+                    Some((route, permission)) => {
+                        permission_overrides
+                            .insert(route.to_string(), Some(permission.to_string()));
+                    }
+                    None => errors.push(ConfigError::invalid(
+                        "PERMISSION_OVERRIDES",
+                        &format!(
+                            "entry '{}' must be formatted as METHOD /path=PERMISSION",
+                            entry
+                        ),
+                    )),
+                }
+            }
+        }
+
+        let static_claims: Option<Map<String, Value>> = match env::var("JWT_STATIC_CLAIMS") {
+            Ok(d) => match serde_json::from_str(d.trim()) {
+                Ok(claims) => Some(claims),
+                Err(e) => {
+                    errors.push(ConfigError::invalid(
+                        "JWT_STATIC_CLAIMS",
+                        &format!("must be a JSON object ({})", e),
+                    ));
+                    None
+                }
+            },
+            Err(_) => None,
+        };
+
+        let syslog_address = Self::optional_variable("SECURITY_SYSLOG_ADDRESS");
+        let syslog_protocol: SyslogProtocol =
+            Self::parse_variable("SECURITY_SYSLOG_PROTOCOL", SyslogProtocol::Udp, &mut errors);
+
+        let verbose_permission_errors: bool =
+            Self::parse_variable("VERBOSE_PERMISSION_ERRORS", false, &mut errors);
+        let include_permission_dependencies: bool =
+            Self::parse_variable("INCLUDE_PERMISSION_DEPENDENCIES", true, &mut errors);
+
+        let permission_config = match PermissionConfig::new(
+            permission_overrides,
+            verbose_permission_errors,
+            include_permission_dependencies,
+        ) {
+            Ok(d) => Some(d),
+            Err(e) => {
+                errors.push(ConfigError::invalid("PERMISSION_OVERRIDES", &e.to_string()));
+                None
+            }
+        };
+
+        let permission_config = match permission_config {
+            Some(d) if errors.is_empty() => d,
+            _ => return Err(errors),
         };
 
         let default_user_config = DefaultUserConfig::new(
@@ -190,6 +747,8 @@ impl EnvReader {
             default_email,
             default_password,
             default_user_enabled,
+            default_user_require_password_change,
+            default_user_disable_after_admin,
         );
 
         let db_config = DbConfig::new(
@@ -199,21 +758,265 @@ impl EnvReader {
             role_collection,
             user_collection,
             audit_collection,
+            device_collection,
+            client_collection,
+            consent_collection,
+            organization_collection,
+            invitation_collection,
+            refresh_token_collection,
+            revoked_token_collection,
+            email_job_collection,
+            user_summary_collection,
+            session_collection,
+            api_key_collection,
+            authorization_code_collection,
+            role_change_collection,
+            credential_collection,
+            credential_ceremony_collection,
+            magic_link_collection,
             create_indexes,
+            create_validators,
             audit_enabled,
             audit_ttl,
+            slow_query_threshold,
+            health_check_interval,
+            read_preference,
+            read_concern,
+            write_concern,
+            audit_write_concern,
+            audit_chaining,
+            command_monitoring,
+            tenant_connections,
         );
 
-        let server_config = ServerConfig::new(addr, port, max_limit, workers);
+        let server_config = ServerConfig::new(
+            addr,
+            port,
+            max_limit,
+            workers,
+            token_quota,
+            user_schedule_interval,
+        )
+        .with_list_limits(max_user_roles, max_role_permissions)
+        .with_quota_mode(token_quota_mode);
 
-        Config::new(
+        let mut config = Config::new(
             server_config,
             db_config,
             default_user_config,
             generate_default_user,
-            JwtConfig::new(jwt_secret, jwt_expiration),
+            jwt_config,
             enable_openapi,
+            enable_swagger_ui,
+            permission_config,
+            UserStoreConfig::new(None, false),
+            EmailConfig::new(email_strip_plus_tags, gravatar_enabled, email_hash_key),
+            EmailQueueConfig::new(
+                email_queue_interval,
+                email_queue_rate_limit,
+                email_queue_max_attempts,
+                email_queue_retry_delay,
+                email_welcome_subject,
+                email_welcome_body,
+            ),
+            BackupConfig::new(backup_secret),
+            DeviceConfig::new(
+                device_verification_uri,
+                device_code_expiration,
+                device_code_interval,
+            ),
+            InvitationConfig::new(invitation_expiration),
+            MagicLinkConfig::new(
+                magic_link_url,
+                magic_link_expiration,
+                magic_link_subject,
+                magic_link_body,
+            ),
+            RegistrationConfig::new(
+                registration_enabled,
+                registration_require_approval,
+                registration_allowed_domains,
+                registration_domain_roles,
+            ),
+            RequestLoggingConfig::new(
+                request_logging,
+                request_logging_headers,
+                request_logging_bodies,
+            ),
+            HealthConfig::new(health_token, health_addr, health_port),
+            GuestConfig::new(
+                guest_tokens_enabled,
+                guest_permissions,
+                guest_token_expiration,
+            ),
+            AdminConfig::new(admin_allowed_ips, admin_addr, admin_port),
+            CacheConfig::new(cache_ttl, cache_warming),
+            ExportConfig::new(
+                export_endpoint,
+                export_bucket,
+                export_region,
+                export_access_key_id,
+                export_secret_access_key,
+                export_prefix,
+                export_interval,
+            ),
+            SocialLoginConfig::new(
+                social_providers,
+                social_redirect_uri,
+                social_roles,
+                social_auto_create,
+            ),
+            SsoConfig::new(
+                sso_issuer,
+                sso_client_id,
+                sso_client_secret,
+                sso_redirect_uri,
+                sso_scope,
+                sso_roles,
+                sso_auto_create,
+            ),
+            CaptchaConfig::new(
+                captcha_secret,
+                captcha_verify_url,
+                captcha_threshold,
+                login_attempt_window,
+            ),
+            MfaConfig::new(mfa_secret, mfa_issuer, mfa_challenge_expiration),
+            RoleChangeConfig::new(role_change_approval),
+            WebauthnConfig::new(
+                webauthn_rp_id,
+                webauthn_rp_origin,
+                webauthn_rp_name,
+                mfa_challenge_expiration,
+            ),
+            role_templates,
         )
         .await
+        .map_err(|e| vec![e])?;
+
+        if let Some(claims) = static_claims {
+            config
+                .services
+                .register_claims_enricher(Arc::new(StaticClaimsEnricher::new(claims)));
+        }
+
+        if let Some(address) = syslog_address {
+            config
+                .services
+                .register_security_event_sink(Arc::new(SyslogSink::new(address, syslog_protocol)));
+        }
+
+        Ok(config)
+    }
+
+    /// # Summary
+    ///
+    /// Read an optional environment variable.
+    ///
+    /// # Arguments
+    ///
+    /// * `variable` - The name of the environment variable.
+    ///
+    /// # Returns
+    ///
+    /// The trimmed value of the environment variable, or None if it was not specified or empty.
+    fn optional_variable(variable: &str) -> Option<String> {
+        match env::var(variable) {
+            Ok(d) if !d.trim().is_empty() => Some(d.trim().to_string()),
+            _ => None,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Read a PEM-encoded key from an environment variable, or from the file that the `_FILE` variant of the variable
+    /// points to.
+    ///
+    /// # Arguments
+    ///
+    /// * `variable` - The name of the environment variable.
+    /// * `errors` - The list of ConfigErrors to which an unreadable file is added.
+    ///
+    /// # Returns
+    ///
+    /// The key, or None if neither variable was specified.
+    fn key_variable(variable: &str, errors: &mut Vec<ConfigError>) -> Option<String> {
+        // Line breaks are commonly escaped when a key is stored in an environment file
+        if let Some(k) = Self::optional_variable(variable) {
+            return Some(k.replace("\\n", "\n"));
+        }
+
+        let file_variable = format!("{}_FILE", variable);
+        let path = Self::optional_variable(&file_variable)?;
+        match fs::read_to_string(&path) {
+            Ok(k) => Some(k),
+            Err(e) => {
+                errors.push(ConfigError::invalid(
+                    &file_variable,
+                    &format!("must point to a readable file ({})", e),
+                ));
+                None
+            }
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Read a required environment variable.
+    ///
+    /// # Arguments
+    ///
+    /// * `variable` - The name of the environment variable.
+    /// * `errors` - The list of ConfigErrors to which a missing variable is added.
+    ///
+    /// # Returns
+    ///
+    /// The value of the environment variable, or an empty String if it was not specified.
+    fn required_variable(variable: &str, errors: &mut Vec<ConfigError>) -> String {
+        match env::var(variable) {
+            Ok(d) => d,
+            Err(_) => {
+                errors.push(ConfigError::MissingVariable(variable.to_string()));
+                String::new()
+            }
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Read and parse an optional environment variable.
+    ///
+    /// # Arguments
+    ///
+    /// * `variable` - The name of the environment variable.
+    /// * `default` - The value that is used if the variable was not specified or could not be parsed.
+    /// * `errors` - The list of ConfigErrors to which a parsing failure is added.
+    ///
+    /// # Returns
+    ///
+    /// The parsed value of the environment variable, or the default value.
+    fn parse_variable<T>(variable: &str, default: T, errors: &mut Vec<ConfigError>) -> T
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        match env::var(variable) {
+            Ok(d) => match d.trim().parse::<T>() {
+                Ok(res) => res,
+                Err(e) => {
+                    errors.push(ConfigError::invalid(
+                        variable,
+                        &format!(
+                            "'{}' could not be parsed as {} ({})",
+                            d,
+                            std::any::type_name::<T>(),
+                            e
+                        ),
+                    ));
+                    default
+                }
+            },
+            Err(_) => default,
+        }
     }
 }
@@ -0,0 +1,45 @@
+use log::warn;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Whether the instance is in maintenance mode. The state is shared by the clones of the Config of every worker, but
+/// not by other instances.
+#[derive(Clone, Default)]
+pub struct MaintenanceMode {
+    enabled: Arc<AtomicBool>,
+}
+
+impl MaintenanceMode {
+    /// # Summary
+    ///
+    /// Check whether the instance is in maintenance mode.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if requests outside the admin and authentication routes are rejected, false otherwise.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// # Summary
+    ///
+    /// Enter or leave maintenance mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether requests outside the admin and authentication routes should be rejected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// config.maintenance_mode.set_enabled(true);
+    /// ```
+    pub fn set_enabled(&self, enabled: bool) {
+        if self.enabled.swap(enabled, Ordering::Relaxed) != enabled {
+            warn!(
+                "Maintenance mode {}",
+                if enabled { "enabled" } else { "disabled" }
+            );
+        }
+    }
+}
@@ -1,12 +1,16 @@
+use std::sync::OnceLock;
 use utoipa::openapi::security::{Http, HttpAuthScheme, SecurityScheme};
 use utoipa::OpenApi;
 use utoipa::{openapi, Modify};
 
+/// The OpenAPI specification, which is generated once on first use.
+static OPEN_API_SPEC: OnceLock<openapi::OpenApi> = OnceLock::new();
+
 pub struct SecurityAddon;
 impl Modify for SecurityAddon {
     /// # Summary
     ///
-    /// Adds the security scheme to the OpenAPI specification.
+    /// Adds the security scheme to the OpenAPI specification and removes the paths of disabled features.
     ///
     /// # Arguments
     ///
@@ -18,6 +22,20 @@ impl Modify for SecurityAddon {
             "Token",
             SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)),
         );
+
+        if !cfg!(feature = "audit") {
+            openapi
+                .paths
+                .paths
+                .retain(|p, _| !p.starts_with("/api/v1/audits") && !p.ends_with("/audit/"));
+        }
+
+        if !cfg!(feature = "metrics") {
+            openapi
+                .paths
+                .paths
+                .retain(|p, _| p != "/api/v1/admin/commands/" && p != "/api/v1/admin/caches/");
+        }
     }
 }
 
@@ -25,9 +43,27 @@ impl Modify for SecurityAddon {
 #[openapi(
     paths(
         crate::web::controller::authentication::authentication_controller::login,
+        crate::web::controller::authentication::authentication_controller::verify_mfa,
+        crate::web::controller::authentication::authentication_controller::start_passkey_login,
+        crate::web::controller::authentication::authentication_controller::finish_passkey_login,
+        crate::web::controller::authentication::authentication_controller::request_magic_link,
+        crate::web::controller::authentication::authentication_controller::verify_magic_link,
+        crate::web::controller::authentication::authentication_controller::refresh,
+        crate::web::controller::authentication::authentication_controller::logout,
         crate::web::controller::authentication::authentication_controller::register,
+        crate::web::controller::authentication::authentication_controller::social_login,
+        crate::web::controller::authentication::authentication_controller::social_callback,
+        crate::web::controller::authentication::authentication_controller::sso_login,
+        crate::web::controller::authentication::authentication_controller::sso_callback,
+        crate::web::controller::authentication::authentication_controller::guest,
         crate::web::controller::authentication::authentication_controller::current_user,
+        crate::web::controller::authentication::authentication_controller::current_permissions,
+        crate::web::controller::authentication::authentication_controller::current_organizations,
+        crate::web::controller::authentication::authentication_controller::switch_tenant,
+        crate::web::controller::authorization::authorization_controller::batch_authorize,
         crate::web::controller::health::health_controller::health,
+        crate::web::controller::health::health_controller::ready,
+        crate::web::controller::well_known::well_known_controller::jwks,
         crate::web::controller::permission::permission_controller::create_permission,
         crate::web::controller::permission::permission_controller::find_all_permissions,
         crate::web::controller::permission::permission_controller::find_by_id,
@@ -45,23 +81,118 @@ impl Modify for SecurityAddon {
         crate::web::controller::user::user_controller::update_self,
         crate::web::controller::user::user_controller::update_password,
         crate::web::controller::user::user_controller::admin_update_password,
+        crate::web::controller::user::user_controller::revoke_sessions,
+        crate::web::controller::user::user_controller::approve,
+        crate::web::controller::user::user_controller::reject,
+        crate::web::controller::user::user_controller::add_email,
+        crate::web::controller::user::user_controller::update_email,
+        crate::web::controller::user::user_controller::remove_email,
+        crate::web::controller::user::user_controller::merge,
         crate::web::controller::user::user_controller::delete,
         crate::web::controller::user::user_controller::delete_self,
+        crate::web::controller::user::mfa_controller::enroll_totp,
+        crate::web::controller::user::mfa_controller::confirm_totp,
+        crate::web::controller::user::mfa_controller::remove_totp,
+        crate::web::controller::user::mfa_controller::find_passkeys,
+        crate::web::controller::user::mfa_controller::start_passkey_registration,
+        crate::web::controller::user::mfa_controller::finish_passkey_registration,
+        crate::web::controller::user::mfa_controller::remove_passkey,
+        crate::web::controller::user::mfa_controller::find_recovery_code_count,
+        crate::web::controller::user::mfa_controller::regenerate_recovery_codes,
         crate::web::controller::audit::audit_controller::find_all,
+        crate::web::controller::audit::audit_controller::verify,
         crate::web::controller::audit::audit_controller::find_by_id,
+        crate::web::controller::audit::audit_controller::find_by_user,
+        crate::web::controller::admin::admin_controller::backup,
+        crate::web::controller::admin::admin_controller::restore,
+        crate::web::controller::admin::admin_controller::routes,
+        crate::web::controller::admin::admin_controller::selfcheck,
+        crate::web::controller::admin::admin_controller::purge,
+        crate::web::controller::admin::admin_controller::indexes,
+        crate::web::controller::admin::admin_controller::maintenance,
+        crate::web::controller::admin::admin_controller::update_maintenance,
+        crate::web::controller::admin::admin_controller::commands,
+        crate::web::controller::admin::admin_controller::caches,
+        crate::web::controller::oauth::oauth_controller::device_code,
+        crate::web::controller::oauth::oauth_controller::authorize,
+        crate::web::controller::oauth::oauth_controller::token,
+        crate::web::controller::oauth::oauth_controller::find_device,
+        crate::web::controller::oauth::oauth_controller::verify_device,
+        crate::web::controller::client::client_controller::create,
+        crate::web::controller::client::client_controller::find_all,
+        crate::web::controller::client::client_controller::find_by_id,
+        crate::web::controller::client::client_controller::update,
+        crate::web::controller::client::client_controller::rotate_secret,
+        crate::web::controller::client::client_controller::delete,
+        crate::web::controller::api_key::api_key_controller::create,
+        crate::web::controller::api_key::api_key_controller::find_all,
+        crate::web::controller::api_key::api_key_controller::find_by_id,
+        crate::web::controller::api_key::api_key_controller::update,
+        crate::web::controller::api_key::api_key_controller::rotate_key,
+        crate::web::controller::api_key::api_key_controller::delete,
+        crate::web::controller::consent::consent_controller::find_all,
+        crate::web::controller::consent::consent_controller::grant,
+        crate::web::controller::consent::consent_controller::check,
+        crate::web::controller::consent::consent_controller::revoke,
+        crate::web::controller::organization::organization_controller::create,
+        crate::web::controller::organization::organization_controller::find_all,
+        crate::web::controller::organization::organization_controller::find_by_id,
+        crate::web::controller::organization::organization_controller::update,
+        crate::web::controller::organization::organization_controller::set_member,
+        crate::web::controller::organization::organization_controller::remove_member,
+        crate::web::controller::organization::organization_controller::create_invitation,
+        crate::web::controller::organization::organization_controller::find_invitations,
+        crate::web::controller::organization::organization_controller::revoke_invitation,
+        crate::web::controller::organization::organization_controller::create_role,
+        crate::web::controller::organization::organization_controller::find_roles,
+        crate::web::controller::organization::organization_controller::update_role,
+        crate::web::controller::organization::organization_controller::delete_role,
+        crate::web::controller::organization::organization_controller::delete,
+        crate::web::controller::invitation::invitation_controller::accept,
+        crate::web::controller::role_change::role_change_controller::find_pending,
+        crate::web::controller::role_change::role_change_controller::approve,
+        crate::web::controller::role_change::role_change_controller::reject,
     ),
     components(
         schemas(
             crate::errors::internal_server_error::InternalServerError,
             crate::errors::bad_request::BadRequest,
+            crate::errors::forbidden::Forbidden,
+            crate::errors::mfa_enrollment_required::MfaEnrollmentRequired,
+            crate::errors::captcha_required::CaptchaRequired,
+            crate::errors::mfa_required::MfaRequired,
             crate::web::dto::permission::create_permission::CreatePermission,
             crate::web::dto::permission::permission_dto::PermissionDto,
             crate::web::dto::permission::update_permission::UpdatePermission,
             crate::web::controller::health::health_controller::HealthResponse,
+            crate::services::jwt::jwk::Jwk,
+            crate::services::jwt::jwk::JwkSet,
             crate::web::dto::authentication::login_request::LoginRequest,
+            crate::web::dto::authentication::mfa_login_request::MfaLoginRequest,
+            crate::web::dto::mfa::totp_code_request::TotpCodeRequest,
+            crate::web::dto::mfa::totp_enrollment_dto::TotpEnrollmentDto,
+            crate::web::dto::mfa::credential_dto::CredentialDto,
+            crate::web::dto::mfa::passkey_registration_request::PasskeyRegistrationRequest,
+            crate::web::dto::mfa::finish_passkey_registration::FinishPasskeyRegistration,
+            crate::web::dto::mfa::passkey_challenge_dto::PasskeyChallengeDto,
+            crate::web::dto::mfa::recovery_codes_dto::RecoveryCodesDto,
+            crate::web::dto::mfa::recovery_code_count_dto::RecoveryCodeCountDto,
+            crate::web::dto::authentication::passkey_login_request::PasskeyLoginRequest,
+            crate::web::dto::authentication::finish_passkey_login::FinishPasskeyLogin,
+            crate::web::dto::authentication::magic_link_request::MagicLinkRequest,
+            crate::web::dto::authentication::magic_link_login_request::MagicLinkLoginRequest,
             crate::web::dto::authentication::login_response::LoginResponse,
+            crate::web::dto::authentication::logout_request::LogoutRequest,
+            crate::web::dto::authentication::refresh_request::RefreshRequest,
             crate::web::dto::authentication::register_request::RegisterRequest,
+            crate::web::dto::authentication::switch_tenant_request::SwitchTenantRequest,
+            crate::web::dto::authorization::authorization_request::AuthorizationCheck,
+            crate::web::dto::authorization::authorization_request::BatchAuthorizationRequest,
+            crate::web::dto::authorization::authorization_response::AuthorizationResult,
+            crate::web::dto::authorization::authorization_response::BatchAuthorizationResponse,
             crate::web::dto::user::user_dto::SimpleUserDto,
+            crate::web::dto::user::user_summary::UserSummaryDto,
+            crate::web::dto::user::user_summary::UserSummaryRoleDto,
             crate::web::dto::role::role_dto::SimpleRoleDto,
             crate::web::dto::permission::permission_dto::SimplePermissionDto,
             crate::web::dto::role::role_dto::RoleDto,
@@ -73,12 +204,78 @@ impl Modify for SecurityAddon {
             crate::web::dto::user::update_user::UpdateOwnUser,
             crate::web::dto::user::update_password::UpdatePassword,
             crate::web::dto::user::update_password::AdminUpdatePassword,
+            crate::web::dto::user::user_email::UserEmailDto,
+            crate::repository::user::user_status::UserStatus,
+            crate::repository::user::user_source::UserSource,
+            crate::web::dto::user::user_email::AddUserEmail,
+            crate::web::dto::user::user_email::UpdateUserEmail,
             crate::web::dto::audit::audit_dto::AuditDto,
+            crate::web::dto::audit::audit_chain_dto::AuditChainVerificationDto,
             crate::web::dto::audit::audit_dto::ActionDto,
             crate::web::dto::audit::audit_dto::ResourceIdTypeDto,
             crate::web::dto::audit::audit_dto::ResourceTypeDto,
+            crate::web::dto::backup::backup_dto::BackupDto,
+            crate::web::dto::backup::backup_dto::RestoreSummaryDto,
+            crate::web::dto::route::route_dto::RouteDto,
+            crate::web::dto::selfcheck::selfcheck_dto::SelfCheckDto,
+            crate::web::dto::selfcheck::selfcheck_dto::CheckResultDto,
+            crate::web::dto::maintenance::maintenance_dto::MaintenanceDto,
+            crate::web::dto::maintenance::maintenance_dto::PurgeResultDto,
+            crate::web::dto::metrics::command_metrics_dto::CommandMetricsDto,
+            crate::web::dto::metrics::cache_metrics_dto::CacheMetricsDto,
+            crate::components::self_check::CheckStatus,
+            crate::web::dto::oauth::authorization_request::AuthorizationRequest,
+            crate::web::dto::oauth::authorization_request::AuthorizationResponse,
+            crate::web::dto::oauth::device_request::DeviceCodeRequest,
+            crate::web::dto::oauth::device_request::DeviceVerification,
+            crate::web::dto::oauth::device_response::DeviceCodeResponse,
+            crate::web::dto::oauth::device_response::DeviceDto,
+            crate::web::dto::oauth::token::TokenRequest,
+            crate::web::dto::oauth::token::TokenResponse,
+            crate::web::dto::oauth::token::OAuthError,
+            crate::web::dto::client::create_client::CreateClient,
+            crate::web::dto::client::update_client::UpdateClient,
+            crate::web::dto::client::client_dto::ClientDto,
+            crate::web::dto::client::client_dto::ClientSecretDto,
+            crate::web::dto::api_key::create_api_key::CreateApiKey,
+            crate::web::dto::api_key::update_api_key::UpdateApiKey,
+            crate::web::dto::api_key::api_key_dto::ApiKeyDto,
+            crate::web::dto::api_key::api_key_dto::ApiKeySecretDto,
+            crate::web::dto::consent::grant_consent::GrantConsent,
+            crate::web::dto::consent::consent_dto::ConsentDto,
+            crate::web::dto::consent::consent_dto::ConsentCheckDto,
+            crate::web::dto::organization::create_organization::CreateOrganization,
+            crate::web::dto::organization::update_organization::UpdateOrganization,
+            crate::web::dto::organization::update_member::UpdateMember,
+            crate::web::dto::organization::organization_dto::OrganizationDto,
+            crate::web::dto::organization::organization_dto::MemberDto,
+            crate::web::dto::organization::organization_dto::SimpleOrganizationDto,
+            crate::web::dto::invitation::create_invitation::CreateInvitation,
+            crate::web::dto::invitation::create_invitation::AcceptInvitation,
+            crate::web::dto::invitation::invitation_dto::InvitationDto,
+            crate::web::dto::invitation::invitation_dto::InvitationTokenDto,
+            crate::web::dto::role_change::role_change_dto::RoleChangeDto,
         )
     ),
     modifiers(&SecurityAddon)
 )]
 pub struct ApiDoc;
+
+impl ApiDoc {
+    /// # Summary
+    ///
+    /// Get the OpenAPI specification of the application, which is generated once on first use.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let paths = &ApiDoc::spec().paths.paths;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `&'static openapi::OpenApi` - The OpenAPI specification.
+    pub fn spec() -> &'static openapi::OpenApi {
+        OPEN_API_SPEC.get_or_init(ApiDoc::openapi)
+    }
+}
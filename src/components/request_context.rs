@@ -0,0 +1,109 @@
+use std::future::Future;
+
+tokio::task_local! {
+    static REQUEST_CONTEXT: RequestContext;
+}
+
+#[derive(Clone, Debug)]
+pub struct RequestContext {
+    pub request_id: String,
+    pub method: String,
+    pub route: String,
+    pub reason: Option<String>,
+}
+
+impl RequestContext {
+    /// # Summary
+    ///
+    /// Create a new RequestContext.
+    ///
+    /// # Arguments
+    ///
+    /// * `request_id` - The ID of the request.
+    /// * `method` - The HTTP method of the request.
+    /// * `route` - The route pattern that matched the request, or its path if no route matched.
+    /// * `reason` - The reason for the change that was given by the caller, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let context = RequestContext::new(
+    ///     String::from("id"),
+    ///     String::from("PUT"),
+    ///     String::from("/api/v1/users/{id}"),
+    ///     Some(String::from("CHG-1234")),
+    /// );
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `RequestContext` - The new RequestContext.
+    pub fn new(
+        request_id: String,
+        method: String,
+        route: String,
+        reason: Option<String>,
+    ) -> RequestContext {
+        RequestContext {
+            request_id,
+            method,
+            route,
+            reason,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Get the RequestContext of the request that is currently being handled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// if let Some(context) = RequestContext::current() {
+    ///     audit.request_id = Some(context.request_id);
+    /// }
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Option<RequestContext>` - The RequestContext, or None when called outside of a request, for example by a background task.
+    pub fn current() -> Option<RequestContext> {
+        REQUEST_CONTEXT.try_with(|c| c.clone()).ok()
+    }
+
+    /// # Summary
+    ///
+    /// Run a synchronous function within this RequestContext.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The function to run.
+    ///
+    /// # Returns
+    ///
+    /// * `R` - The result of the function.
+    pub fn sync_scope<F, R>(self, f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        REQUEST_CONTEXT.sync_scope(self, f)
+    }
+
+    /// # Summary
+    ///
+    /// Run a future within this RequestContext.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The future to run.
+    ///
+    /// # Returns
+    ///
+    /// * `impl Future<Output = F::Output>` - The future that runs within this RequestContext.
+    pub fn scope<F>(self, f: F) -> impl Future<Output = F::Output>
+    where
+        F: Future,
+    {
+        REQUEST_CONTEXT.scope(self, f)
+    }
+}
@@ -0,0 +1,144 @@
+use regex::{Regex, RegexBuilder};
+use std::collections::HashMap;
+
+/// The markup that is placed before a matched search term.
+pub const HIGHLIGHT_START: &str = "<em>";
+
+/// The markup that is placed after a matched search term.
+pub const HIGHLIGHT_END: &str = "</em>";
+
+/// Marks the terms of a text search in the fields of the entities that were found.
+pub struct SearchHighlighter {
+    pattern: Option<Regex>,
+}
+
+impl SearchHighlighter {
+    /// # Summary
+    ///
+    /// Create a new SearchHighlighter for the text of a search. Negated terms and the quotes of phrases are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text that was searched for.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let highlighter = SearchHighlighter::new("admin -guest");
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `SearchHighlighter` - The new SearchHighlighter.
+    pub fn new(text: &str) -> SearchHighlighter {
+        let terms: Vec<String> = text
+            .split_whitespace()
+            .filter(|t| !t.starts_with('-'))
+            .map(|t| t.trim_matches('"'))
+            .filter(|t| !t.is_empty())
+            .map(regex::escape)
+            .collect();
+
+        let pattern = match terms.is_empty() {
+            true => None,
+            false => RegexBuilder::new(&terms.join("|"))
+                .case_insensitive(true)
+                .build()
+                .ok(),
+        };
+
+        SearchHighlighter { pattern }
+    }
+
+    /// # Summary
+    ///
+    /// Highlight the search terms in a value. The value is HTML-escaped, so that the result can be rendered as is.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value of a field.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let snippet = highlighter.highlight("The administrator role");
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Option<String>` - The value with its matches wrapped in `<em>` tags, or None if no term matches.
+    pub fn highlight(&self, value: &str) -> Option<String> {
+        let pattern = self.pattern.as_ref()?;
+
+        let mut snippet = String::new();
+        let mut last = 0;
+        for m in pattern.find_iter(value) {
+            snippet.push_str(&escape(&value[last..m.start()]));
+            snippet.push_str(HIGHLIGHT_START);
+            snippet.push_str(&escape(m.as_str()));
+            snippet.push_str(HIGHLIGHT_END);
+            last = m.end();
+        }
+
+        if last == 0 {
+            return None;
+        }
+
+        snippet.push_str(&escape(&value[last..]));
+        Some(snippet)
+    }
+
+    /// # Summary
+    ///
+    /// Highlight the search terms in the fields of an entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `fields` - The names and values of the fields.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let highlights = highlighter.highlight_fields(&[
+    ///     ("name", Some(&role.name)),
+    ///     ("description", role.description.as_deref()),
+    /// ]);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Option<HashMap<String, String>>` - The highlighted values by the names of their fields, or None if no field matches.
+    pub fn highlight_fields(
+        &self,
+        fields: &[(&str, Option<&str>)],
+    ) -> Option<HashMap<String, String>> {
+        let highlights: HashMap<String, String> = fields
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), self.highlight((*value)?)?)))
+            .collect();
+
+        match highlights.is_empty() {
+            true => None,
+            false => Some(highlights),
+        }
+    }
+}
+
+/// # Summary
+///
+/// Escape the characters of a value that have a meaning in HTML.
+///
+/// # Arguments
+///
+/// * `value` - The value.
+///
+/// # Returns
+///
+/// * `String` - The escaped value.
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
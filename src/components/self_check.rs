@@ -0,0 +1,465 @@
+use crate::configuration::config::Config;
+use crate::configuration::default_user_config::DefaultUserConfig;
+use futures::TryStreamExt;
+use mongodb::bson::{doc, Document};
+use mongodb::{Database, IndexModel};
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+use utoipa::ToSchema;
+
+/// The minimum length of the JWT secret, in bytes, that matches the output size of HMAC-SHA256.
+const MIN_JWT_SECRET_LENGTH: usize = 32;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Clone, Debug)]
+pub struct CheckResult {
+    pub name: String,
+    pub status: CheckStatus,
+    pub message: String,
+    pub remedy: Option<String>,
+}
+
+impl CheckResult {
+    /// # Summary
+    ///
+    /// Create a new CheckResult for a check that passed.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the check.
+    /// * `message` - What was verified.
+    ///
+    /// # Returns
+    ///
+    /// * `CheckResult` - The new CheckResult.
+    fn ok(name: &str, message: &str) -> CheckResult {
+        CheckResult {
+            name: name.to_string(),
+            status: CheckStatus::Ok,
+            message: message.to_string(),
+            remedy: None,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Create a new CheckResult for a check that did not pass.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the check.
+    /// * `status` - Whether the issue is a warning or an error.
+    /// * `message` - What is wrong.
+    /// * `remedy` - How the issue can be fixed.
+    ///
+    /// # Returns
+    ///
+    /// * `CheckResult` - The new CheckResult.
+    fn failed(name: &str, status: CheckStatus, message: &str, remedy: &str) -> CheckResult {
+        CheckResult {
+            name: name.to_string(),
+            status,
+            message: message.to_string(),
+            remedy: Some(remedy.to_string()),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SelfCheckReport {
+    pub checks: Vec<CheckResult>,
+}
+
+impl SelfCheckReport {
+    /// # Summary
+    ///
+    /// Check whether none of the checks resulted in an error. Warnings do not make a report unhealthy.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if no check resulted in an error.
+    pub fn is_healthy(&self) -> bool {
+        self.checks.iter().all(|c| c.status != CheckStatus::Error)
+    }
+}
+
+impl Display for SelfCheckReport {
+    /// # Summary
+    ///
+    /// Display the SelfCheckReport as a human-readable report with a line per check, followed by its remedy.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The Formatter.
+    ///
+    /// # Returns
+    ///
+    /// * `std::fmt::Result` - The result of the display.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "auth-rs self-check report:")?;
+
+        for check in &self.checks {
+            let status = match check.status {
+                CheckStatus::Ok => "OK",
+                CheckStatus::Warning => "WARN",
+                CheckStatus::Error => "ERROR",
+            };
+
+            writeln!(f, "  [{:<5}] {}: {}", status, check.name, check.message)?;
+            if let Some(remedy) = &check.remedy {
+                writeln!(f, "          -> {}", remedy)?;
+            }
+        }
+
+        let errors = self
+            .checks
+            .iter()
+            .filter(|c| c.status == CheckStatus::Error)
+            .count();
+        let warnings = self
+            .checks
+            .iter()
+            .filter(|c| c.status == CheckStatus::Warning)
+            .count();
+
+        write!(f, "{} error(s), {} warning(s)", errors, warnings)
+    }
+}
+
+#[derive(Clone)]
+pub struct SelfCheck {
+    database: Database,
+    indexes: Vec<(String, Vec<&'static str>)>,
+    default_user: Option<DefaultUserConfig>,
+    jwt_secret_length: usize,
+}
+
+impl SelfCheck {
+    /// # Summary
+    ///
+    /// Create a new SelfCheck.
+    ///
+    /// # Arguments
+    ///
+    /// * `database` - The Database that should be checked.
+    /// * `indexes` - The collections and the fields that should be the leading key of an index in that collection.
+    /// * `default_user` - The configuration of the default User, if a default User is generated.
+    /// * `jwt_secret_length` - The length of the JWT secret, in bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let self_check = SelfCheck::new(db.clone(), vec![(String::from("users"), vec!["username"])], None, 64);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `SelfCheck` - The new SelfCheck.
+    pub fn new(
+        database: Database,
+        indexes: Vec<(String, Vec<&'static str>)>,
+        default_user: Option<DefaultUserConfig>,
+        jwt_secret_length: usize,
+    ) -> SelfCheck {
+        SelfCheck {
+            database,
+            indexes,
+            default_user,
+            jwt_secret_length,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Validate the configuration, the Database connectivity, the presence of indexes, the JWT key, the EmailSender
+    /// and the default User. The checks that require the Database are skipped if it cannot be reached.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The Config of the application.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let report = config.self_check.run(&config).await;
+    /// println!("{}", report);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `SelfCheckReport` - The results of all checks.
+    pub async fn run(&self, config: &Config) -> SelfCheckReport {
+        let mut checks = self.check_configuration(config);
+        checks.push(self.check_jwt(config));
+        checks.push(self.check_email(config).await);
+
+        match self.database.run_command(doc! { "ping": 1 }, None).await {
+            Ok(_) => {
+                checks.push(CheckResult::ok("database", "The database is reachable"));
+                checks.extend(self.check_indexes().await);
+                if let Some(c) = self.check_default_user(config).await {
+                    checks.push(c);
+                }
+            }
+            Err(e) => checks.push(CheckResult::failed(
+                "database",
+                CheckStatus::Error,
+                &format!("The database cannot be reached: {}", e),
+                "Verify DB_CONNECTION_STRING and DB_DATABASE and make sure the database is running",
+            )),
+        }
+
+        SelfCheckReport { checks }
+    }
+
+    /// # Summary
+    ///
+    /// Find configuration combinations that are valid, but likely unintended.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The Config of the application.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<CheckResult>` - The results of the configuration checks.
+    fn check_configuration(&self, config: &Config) -> Vec<CheckResult> {
+        let mut checks = vec![];
+
+        if cfg!(feature = "swagger") && config.swagger_ui && !config.open_api {
+            checks.push(CheckResult::failed(
+                "configuration",
+                CheckStatus::Warning,
+                "The Swagger UI is enabled, but the OpenAPI specification is not served",
+                "Enable ENABLE_OPENAPI or disable ENABLE_SWAGGER_UI",
+            ));
+        }
+
+        let registration = &config.registration_config;
+        if registration.enabled
+            && !registration.require_approval
+            && registration.allowed_domains.is_empty()
+        {
+            checks.push(CheckResult::failed(
+                "configuration",
+                CheckStatus::Warning,
+                "Anyone can register an account without approval",
+                "Set REGISTRATION_ALLOWED_DOMAINS, enable REGISTRATION_REQUIRE_APPROVAL or disable REGISTRATION_ENABLED",
+            ));
+        }
+
+        if config.request_logging_config.enabled && config.request_logging_config.bodies {
+            checks.push(CheckResult::failed(
+                "configuration",
+                CheckStatus::Warning,
+                "Request bodies are logged, which may include personal data",
+                "Disable REQUEST_LOGGING_BODIES unless you are debugging",
+            ));
+        }
+
+        if checks.is_empty() {
+            checks.push(CheckResult::ok(
+                "configuration",
+                "The configuration is valid",
+            ));
+        }
+
+        checks
+    }
+
+    /// # Summary
+    ///
+    /// Check that the JWT secret is long enough and that tokens that are generated can be verified.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The Config of the application.
+    ///
+    /// # Returns
+    ///
+    /// * `CheckResult` - The result of the JWT check.
+    fn check_jwt(&self, config: &Config) -> CheckResult {
+        if let Err(e) = config.services.jwt_service.check_configuration() {
+            return CheckResult::failed(
+                "jwt",
+                CheckStatus::Error,
+                &e.to_string(),
+                "Verify JWT_SECRET, JWT_PRIVATE_KEY, JWT_PUBLIC_KEY and JWT_EXPIRATION",
+            );
+        }
+
+        // The length of the secret is irrelevant when tokens are signed with a key pair
+        if !config.services.jwt_service.jwt_config.is_asymmetric()
+            && self.jwt_secret_length < MIN_JWT_SECRET_LENGTH
+        {
+            return CheckResult::failed(
+                "jwt",
+                CheckStatus::Warning,
+                &format!("JWT_SECRET is only {} bytes long", self.jwt_secret_length),
+                &format!(
+                    "Use a random JWT_SECRET of at least {} bytes",
+                    MIN_JWT_SECRET_LENGTH
+                ),
+            );
+        }
+
+        CheckResult::ok("jwt", "Tokens can be signed and verified")
+    }
+
+    /// # Summary
+    ///
+    /// Check whether an EmailSender is registered and whether it can reach its mail server.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The Config of the application.
+    ///
+    /// # Returns
+    ///
+    /// * `CheckResult` - The result of the email check.
+    async fn check_email(&self, config: &Config) -> CheckResult {
+        if !cfg!(feature = "email") {
+            return CheckResult::ok("email", "Email delivery is not part of this build");
+        }
+
+        match &config.services.email_sender {
+            None => CheckResult::failed(
+                "email",
+                CheckStatus::Warning,
+                "No EmailSender is registered, so notification emails are not sent",
+                "Register an EmailSender using Services::register_email_sender",
+            ),
+            Some(sender) => match sender.check().await {
+                Ok(_) => CheckResult::ok("email", "The EmailSender is reachable"),
+                Err(e) => CheckResult::failed(
+                    "email",
+                    CheckStatus::Error,
+                    &format!("The EmailSender is not reachable: {}", e),
+                    "Verify the configuration of the mail server",
+                ),
+            },
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check whether every expected index exists.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<CheckResult>` - The result of the index check of every collection.
+    async fn check_indexes(&self) -> Vec<CheckResult> {
+        let mut checks = vec![];
+
+        for (collection, fields) in &self.indexes {
+            let indexes: Vec<IndexModel> = match self
+                .database
+                .collection::<Document>(collection)
+                .list_indexes(None)
+                .await
+            {
+                Ok(cursor) => cursor.try_collect().await.unwrap_or_default(),
+                // The collection does not exist yet
+                Err(_) => vec![],
+            };
+
+            let missing: Vec<&str> = fields
+                .iter()
+                .filter(|f| {
+                    !indexes
+                        .iter()
+                        .any(|i| i.keys.keys().next().map(|k| k.as_str()) == Some(**f))
+                })
+                .copied()
+                .collect();
+
+            let name = format!("indexes ({})", collection);
+            if missing.is_empty() {
+                checks.push(CheckResult::ok(&name, "All indexes are present"));
+            } else {
+                checks.push(CheckResult::failed(
+                    &name,
+                    CheckStatus::Warning,
+                    &format!("Missing indexes on: {}", missing.join(", ")),
+                    "Restart with DB_CREATE_INDEXES enabled or create the indexes manually",
+                ));
+            }
+        }
+
+        checks
+    }
+
+    /// # Summary
+    ///
+    /// Check whether the default User exists, is active and no longer uses the configured default password.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The Config of the application.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<CheckResult>` - The result of the default User check, or None if no default User is generated.
+    async fn check_default_user(&self, config: &Config) -> Option<CheckResult> {
+        let default_user = self.default_user.as_ref()?;
+
+        let user = match config
+            .services
+            .user_service
+            .find_by_username(&default_user.username, &self.database)
+            .await
+        {
+            Ok(Some(u)) => u,
+            Ok(None) => {
+                return Some(CheckResult::failed(
+                    "default_user",
+                    CheckStatus::Warning,
+                    &format!("The default User {} does not exist", default_user.username),
+                    "Restart with GENERATE_DEFAULT_USER enabled or disable it if the User was removed on purpose",
+                ))
+            }
+            Err(e) => {
+                return Some(CheckResult::failed(
+                    "default_user",
+                    CheckStatus::Error,
+                    &format!("The default User cannot be read: {}", e),
+                    "Verify DB_USER_COLLECTION",
+                ))
+            }
+        };
+
+        if !user.is_active() {
+            return Some(CheckResult::failed(
+                "default_user",
+                CheckStatus::Warning,
+                &format!("The default User {} is {}", user.username, user.status),
+                "Reactivate the default User if it should be able to log in",
+            ));
+        }
+
+        if let Ok(true) = config
+            .services
+            .password_service
+            .verify_password(&default_user.password, &user.password)
+        {
+            return Some(CheckResult::failed(
+                "default_user",
+                CheckStatus::Warning,
+                "The default User still uses DEFAULT_USER_PASSWORD",
+                "Change the password of the default User",
+            ));
+        }
+
+        Some(CheckResult::ok(
+            "default_user",
+            "The default User is active",
+        ))
+    }
+}
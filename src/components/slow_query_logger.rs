@@ -0,0 +1,127 @@
+use log::warn;
+use mongodb::event::command::{
+    CommandEventHandler, CommandFailedEvent, CommandStartedEvent, CommandSucceededEvent,
+};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+pub struct SlowQueryLogger {
+    threshold: Duration,
+    collections: Mutex<HashMap<i32, String>>,
+    slow_queries: AtomicU64,
+}
+
+impl SlowQueryLogger {
+    /// # Summary
+    ///
+    /// Create a new SlowQueryLogger.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - The duration after which a database operation is considered to be slow.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let slow_query_logger = SlowQueryLogger::new(Duration::from_millis(500));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `SlowQueryLogger` - The new SlowQueryLogger.
+    pub fn new(threshold: Duration) -> SlowQueryLogger {
+        SlowQueryLogger {
+            threshold,
+            collections: Mutex::new(HashMap::new()),
+            slow_queries: AtomicU64::new(0),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Get the amount of slow database operations that have been observed.
+    ///
+    /// # Returns
+    ///
+    /// * `u64` - The amount of slow database operations.
+    pub fn slow_query_count(&self) -> u64 {
+        self.slow_queries.load(Ordering::Relaxed)
+    }
+
+    /// # Summary
+    ///
+    /// Log a finished database operation if it took longer than the threshold.
+    ///
+    /// # Arguments
+    ///
+    /// * `request_id` - The ID of the request that was sent to the database.
+    /// * `command_name` - The name of the command.
+    /// * `duration` - The duration of the operation.
+    fn finish(&self, request_id: i32, command_name: &str, duration: Duration) {
+        let collection = match self.collections.lock() {
+            Ok(mut c) => c.remove(&request_id),
+            Err(e) => e.into_inner().remove(&request_id),
+        };
+
+        if duration < self.threshold {
+            return;
+        }
+
+        self.slow_queries.fetch_add(1, Ordering::Relaxed);
+        warn!(
+            "Slow database operation: {} on collection {} took {} ms",
+            command_name,
+            collection.as_deref().unwrap_or("-"),
+            duration.as_millis()
+        );
+    }
+}
+
+impl CommandEventHandler for SlowQueryLogger {
+    /// # Summary
+    ///
+    /// Remember the collection of a database operation that has been started.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The CommandStartedEvent.
+    fn handle_command_started_event(&self, event: CommandStartedEvent) {
+        // The value of the first field of a command is the name of the collection it operates on
+        let collection = match event.command.iter().next() {
+            Some((_, v)) => match v.as_str() {
+                Some(c) => c.to_string(),
+                None => return,
+            },
+            None => return,
+        };
+
+        match self.collections.lock() {
+            Ok(mut c) => c.insert(event.request_id, collection),
+            Err(e) => e.into_inner().insert(event.request_id, collection),
+        };
+    }
+
+    /// # Summary
+    ///
+    /// Log a database operation that succeeded if it was slow.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The CommandSucceededEvent.
+    fn handle_command_succeeded_event(&self, event: CommandSucceededEvent) {
+        self.finish(event.request_id, &event.command_name, event.duration);
+    }
+
+    /// # Summary
+    ///
+    /// Log a database operation that failed if it was slow.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The CommandFailedEvent.
+    fn handle_command_failed_event(&self, event: CommandFailedEvent) {
+        self.finish(event.request_id, &event.command_name, event.duration);
+    }
+}
@@ -0,0 +1,142 @@
+use crate::configuration::config_error::ConfigError;
+use mongodb::bson::oid::ObjectId;
+use mongodb::options::{ClientOptions, ServerApi, ServerApiVersion};
+use mongodb::{Client, Database};
+use std::collections::HashMap;
+
+#[derive(Clone)]
+pub struct TenantRouter {
+    primary: Database,
+    databases: HashMap<String, Database>,
+    distinct: Vec<Database>,
+}
+
+impl TenantRouter {
+    /// # Summary
+    ///
+    /// Connect to the Databases that the data of Organizations with residency constraints is routed to.
+    /// Organizations that share a connection string share a Client and a Database as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `primary` - The Database that is used for all other Organizations.
+    /// * `connections` - The connection strings by the ID of the Organization. The database name is taken from the connection string, or defaults to `database_name`.
+    /// * `database_name` - The name of the Database if the connection string does not specify one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let tenant_router = TenantRouter::connect(db.clone(), &db_config.tenant_connections, &db_config.database_name).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<TenantRouter, ConfigError>` - The new TenantRouter or the ConfigError that occurred.
+    pub async fn connect(
+        primary: Database,
+        connections: &HashMap<String, String>,
+        database_name: &str,
+    ) -> Result<TenantRouter, ConfigError> {
+        let mut clients: HashMap<&str, (Client, String)> = HashMap::new();
+        let mut databases = HashMap::new();
+
+        for (tenant, connection_string) in connections {
+            if ObjectId::parse_str(tenant).is_err() {
+                return Err(ConfigError::invalid(
+                    "DB_TENANT_CONNECTIONS",
+                    &format!("{} is not a valid Organization ID", tenant),
+                ));
+            }
+
+            if !clients.contains_key(connection_string.as_str()) {
+                let mut client_options = match ClientOptions::parse(connection_string).await {
+                    Ok(d) => d,
+                    Err(e) => {
+                        return Err(ConfigError::invalid(
+                            "DB_TENANT_CONNECTIONS",
+                            &format!("Failed to parse options of tenant {}: {}", tenant, e),
+                        ));
+                    }
+                };
+
+                let server_api = ServerApi::builder().version(ServerApiVersion::V1).build();
+                client_options.server_api = Some(server_api);
+
+                let name = client_options
+                    .default_database
+                    .clone()
+                    .unwrap_or_else(|| String::from(database_name));
+
+                let client = match Client::with_options(client_options) {
+                    Ok(d) => d,
+                    Err(e) => {
+                        return Err(ConfigError::Database(format!(
+                            "Failed to initialize client of tenant {}: {}",
+                            tenant, e
+                        )))
+                    }
+                };
+
+                clients.insert(connection_string, (client, name));
+            }
+
+            let (client, name) = &clients[connection_string.as_str()];
+            databases.insert(tenant.clone(), client.database(name));
+        }
+
+        let distinct = clients
+            .into_values()
+            .map(|(client, name)| client.database(&name))
+            .collect();
+
+        Ok(TenantRouter {
+            primary,
+            databases,
+            distinct,
+        })
+    }
+
+    /// # Summary
+    ///
+    /// Resolve the Database that holds the data of a tenant.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant` - The ID of the Organization, or None for data that does not belong to an Organization.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let db = tenant_router.resolve(Some(&organization.id.to_hex()));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `&Database` - The Database of the tenant, or the primary Database if the tenant is not routed elsewhere.
+    pub fn resolve(&self, tenant: Option<&str>) -> &Database {
+        tenant
+            .and_then(|t| self.databases.get(t))
+            .unwrap_or(&self.primary)
+    }
+
+    /// # Summary
+    ///
+    /// Get the primary Database followed by the Database of every connection that tenants are routed to.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// for db in tenant_router.databases() {
+    ///     db.run_command(doc! { "ping": 1 }, None).await?;
+    /// }
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<&Database>` - The Databases.
+    pub fn databases(&self) -> Vec<&Database> {
+        std::iter::once(&self.primary)
+            .chain(self.distinct.iter())
+            .collect()
+    }
+}
@@ -0,0 +1,90 @@
+use crate::services::role::role_service::RoleService;
+use crate::services::user::user_service::UserService;
+use actix_web::rt;
+use log::error;
+use mongodb::Database;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Clone)]
+pub struct UserScheduler {
+    database: Database,
+    user_service: Arc<dyn UserService>,
+    role_service: Arc<dyn RoleService>,
+    interval: Duration,
+}
+
+impl UserScheduler {
+    /// # Summary
+    ///
+    /// Create a new UserScheduler.
+    ///
+    /// # Arguments
+    ///
+    /// * `database` - The Database that contains the Users.
+    /// * `user_service` - The UserService that applies the schedules of the Users.
+    /// * `role_service` - The RoleService that removes the expired Permission grants of the Roles.
+    /// * `interval` - The interval at which the schedules are applied, or zero to disable the scheduler.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_scheduler = UserScheduler::new(db.clone(), user_service.clone(), role_service.clone(), Duration::from_secs(60));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `UserScheduler` - The new UserScheduler.
+    pub fn new(
+        database: Database,
+        user_service: Arc<dyn UserService>,
+        role_service: Arc<dyn RoleService>,
+        interval: Duration,
+    ) -> UserScheduler {
+        UserScheduler {
+            database,
+            user_service,
+            role_service,
+            interval,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Start a background task that periodically activates and deactivates Users at the boundaries of their schedule
+    /// and removes the Permission grants of Roles that have expired.
+    /// The task must be started from within an Actix runtime.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// config.user_scheduler.start();
+    /// ```
+    pub fn start(&self) {
+        if self.interval.is_zero() {
+            return;
+        }
+
+        let scheduler = self.clone();
+        rt::spawn(async move {
+            loop {
+                rt::time::sleep(scheduler.interval).await;
+                if let Err(e) = scheduler
+                    .user_service
+                    .apply_schedules(&scheduler.database)
+                    .await
+                {
+                    error!("Failed to apply User schedules: {}", e);
+                }
+
+                if let Err(e) = scheduler
+                    .role_service
+                    .remove_expired_permissions(&scheduler.database)
+                    .await
+                {
+                    error!("Failed to remove expired Permission grants: {}", e);
+                }
+            }
+        });
+    }
+}
@@ -1,5 +1,28 @@
+pub mod admin_config;
+pub mod backup_config;
+pub mod cache_config;
+pub mod captcha_config;
 pub mod config;
+pub mod config_error;
 pub mod db_config;
 pub mod default_user_config;
+pub mod device_config;
+pub mod email_config;
+pub mod email_queue_config;
+pub mod export_config;
+pub mod guest_config;
+pub mod health_config;
+pub mod invitation_config;
 pub mod jwt_config;
+pub mod magic_link_config;
+pub mod mfa_config;
+pub mod permission_config;
+pub mod registration_config;
+pub mod request_logging_config;
+pub mod role_change_config;
+pub mod role_template;
 pub mod server_config;
+pub mod social_login_config;
+pub mod sso_config;
+pub mod user_store_config;
+pub mod webauthn_config;
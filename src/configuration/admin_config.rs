@@ -0,0 +1,78 @@
+use std::net::IpAddr;
+
+#[derive(Clone)]
+pub struct AdminConfig {
+    pub allowed_ips: Vec<IpAddr>,
+    pub address: String,
+    pub port: u16,
+}
+
+impl AdminConfig {
+    /// # Summary
+    ///
+    /// Create a new AdminConfig.
+    ///
+    /// # Arguments
+    ///
+    /// * `allowed_ips` - The IP addresses that can call the admin endpoints. Any address is allowed if empty.
+    /// * `address` - The address of the internal listener that serves the admin endpoints.
+    /// * `port` - The port of the internal listener that serves the admin endpoints, or zero to serve them on the main listener.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let admin_config = AdminConfig::new(vec![], String::from("127.0.0.1"), 0);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `AdminConfig` - The new AdminConfig.
+    pub fn new(allowed_ips: Vec<IpAddr>, address: String, port: u16) -> AdminConfig {
+        AdminConfig {
+            allowed_ips,
+            address,
+            port,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check whether the admin endpoints are served on a separate internal listener.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if a port was configured for the internal listener.
+    pub fn has_internal_listener(&self) -> bool {
+        self.port > 0
+    }
+
+    /// # Summary
+    ///
+    /// Check whether a caller may reach the admin endpoints, based on the IP address of its connection.
+    ///
+    /// # Arguments
+    ///
+    /// * `ip` - The IP address of the caller, if known.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// if !config.admin_config.is_allowed(req.peer_addr().map(|a| a.ip())) {
+    ///     return HttpResponse::NotFound().finish();
+    /// }
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if no addresses were configured or if the address of the caller is one of them.
+    pub fn is_allowed(&self, ip: Option<IpAddr>) -> bool {
+        if self.allowed_ips.is_empty() {
+            return true;
+        }
+
+        match ip {
+            Some(ip) => self.allowed_ips.contains(&ip),
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,27 @@
+#[derive(Clone)]
+pub struct BackupConfig {
+    pub secret: Option<String>,
+}
+
+impl BackupConfig {
+    /// # Summary
+    ///
+    /// Create a new BackupConfig.
+    ///
+    /// # Arguments
+    ///
+    /// * `secret` - The secret that is used to encrypt and decrypt backups, or None to disable backups.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let backup_config = BackupConfig::new(Some(String::from("topSecretSecret")));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `BackupConfig` - The new BackupConfig.
+    pub fn new(secret: Option<String>) -> BackupConfig {
+        BackupConfig { secret }
+    }
+}
@@ -0,0 +1,40 @@
+#[derive(Clone)]
+pub struct CacheConfig {
+    pub ttl: u64,
+    pub warming: bool,
+}
+
+impl CacheConfig {
+    /// # Summary
+    ///
+    /// Create a new CacheConfig.
+    ///
+    /// # Arguments
+    ///
+    /// * `ttl` - The number of seconds that Role and Permission entities are kept in the in-process cache, or zero to disable the cache.
+    /// * `warming` - Whether all Role and Permission entities are loaded into the cache at startup.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let cache_config = CacheConfig::new(60, true);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `CacheConfig` - The new CacheConfig.
+    pub fn new(ttl: u64, warming: bool) -> CacheConfig {
+        CacheConfig { ttl, warming }
+    }
+
+    /// # Summary
+    ///
+    /// Check whether the in-process cache is enabled.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if a time to live was configured.
+    pub fn is_enabled(&self) -> bool {
+        self.ttl > 0
+    }
+}
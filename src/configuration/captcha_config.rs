@@ -0,0 +1,43 @@
+#[derive(Clone)]
+pub struct CaptchaConfig {
+    pub secret: Option<String>,
+    pub verify_url: String,
+    pub threshold: u32,
+    pub window: u64,
+}
+
+impl CaptchaConfig {
+    /// # Summary
+    ///
+    /// Create a new CaptchaConfig.
+    ///
+    /// # Arguments
+    ///
+    /// * `secret` - The secret key of the CAPTCHA provider, or None to disable CAPTCHA escalation.
+    /// * `verify_url` - The URL of the siteverify endpoint of the CAPTCHA provider.
+    /// * `threshold` - The number of failed logins of a username or IP address after which a CAPTCHA is required.
+    /// * `window` - The number of seconds after which failed logins are forgotten.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let captcha_config = CaptchaConfig::new(None, String::from("https://www.google.com/recaptcha/api/siteverify"), 3, 900);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `CaptchaConfig` - The new CaptchaConfig.
+    pub fn new(
+        secret: Option<String>,
+        verify_url: String,
+        threshold: u32,
+        window: u64,
+    ) -> CaptchaConfig {
+        CaptchaConfig {
+            secret,
+            verify_url,
+            threshold,
+            window,
+        }
+    }
+}
@@ -1,36 +1,154 @@
+use crate::components::command_monitor::{CommandEventHandlers, CommandMonitor};
+use crate::components::data_exporter::DataExporter;
+use crate::components::db_watchdog::DbWatchdog;
+use crate::components::email_worker::EmailWorker;
+use crate::components::entity_cache::EntityCache;
+use crate::components::maintenance_mode::MaintenanceMode;
+use crate::components::self_check::SelfCheck;
+use crate::components::slow_query_logger::SlowQueryLogger;
+use crate::components::tenant_router::TenantRouter;
+use crate::components::user_scheduler::UserScheduler;
+use crate::configuration::admin_config::AdminConfig;
+use crate::configuration::backup_config::BackupConfig;
+use crate::configuration::cache_config::CacheConfig;
+use crate::configuration::captcha_config::CaptchaConfig;
+use crate::configuration::config_error::ConfigError;
 use crate::configuration::db_config::DbConfig;
 use crate::configuration::default_user_config::DefaultUserConfig;
+use crate::configuration::device_config::DeviceConfig;
+use crate::configuration::email_config::EmailConfig;
+use crate::configuration::email_queue_config::EmailQueueConfig;
+use crate::configuration::export_config::ExportConfig;
+use crate::configuration::guest_config::GuestConfig;
+use crate::configuration::health_config::HealthConfig;
+use crate::configuration::invitation_config::InvitationConfig;
 use crate::configuration::jwt_config::JwtConfig;
+use crate::configuration::magic_link_config::MagicLinkConfig;
+use crate::configuration::mfa_config::MfaConfig;
+use crate::configuration::permission_config::PermissionConfig;
+use crate::configuration::registration_config::RegistrationConfig;
+use crate::configuration::request_logging_config::RequestLoggingConfig;
+use crate::configuration::role_change_config::RoleChangeConfig;
+use crate::configuration::role_template::RoleTemplate;
 use crate::configuration::server_config::ServerConfig;
+use crate::configuration::social_login_config::SocialLoginConfig;
+use crate::configuration::sso_config::SsoConfig;
+use crate::configuration::user_store_config::UserStoreConfig;
+use crate::configuration::webauthn_config::WebauthnConfig;
+use crate::repository::api_key::api_key_model::ApiKey;
+use crate::repository::api_key::api_key_repository::ApiKeyRepository;
 use crate::repository::audit::audit_model::Audit;
 use crate::repository::audit::audit_repository::AuditRepository;
+use crate::repository::authorization_code::authorization_code_model::AuthorizationCode;
+use crate::repository::authorization_code::authorization_code_repository::AuthorizationCodeRepository;
+use crate::repository::backup::backup_repository::BackupRepository;
+use crate::repository::client::client_repository::ClientRepository;
+use crate::repository::consent::consent_model::Consent;
+use crate::repository::consent::consent_repository::ConsentRepository;
+use crate::repository::credential::credential_ceremony::CredentialCeremony;
+use crate::repository::credential::credential_model::Credential;
+use crate::repository::credential::credential_repository::CredentialRepository;
+use crate::repository::device::device_model::DeviceAuthorization;
+use crate::repository::device::device_repository::DeviceRepository;
+use crate::repository::email_job::email_job_model::EmailJob;
+use crate::repository::email_job::email_job_repository::EmailJobRepository;
+use crate::repository::invitation::invitation_model::Invitation;
+use crate::repository::invitation::invitation_repository::InvitationRepository;
+use crate::repository::magic_link::magic_link_model::MagicLink;
+use crate::repository::magic_link::magic_link_repository::MagicLinkRepository;
+use crate::repository::organization::organization_model::Organization;
+use crate::repository::organization::organization_repository::OrganizationRepository;
 use crate::repository::permission::permission_model::Permission;
 use crate::repository::permission::permission_repository::PermissionRepository;
+use crate::repository::refresh_token::refresh_token_model::RefreshToken;
+use crate::repository::refresh_token::refresh_token_repository::RefreshTokenRepository;
+use crate::repository::revoked_token::revoked_token_model::RevokedToken;
+use crate::repository::revoked_token::revoked_token_repository::RevokedTokenRepository;
 use crate::repository::role::role_model::Role;
 use crate::repository::role::role_repository::RoleRepository;
+use crate::repository::role_change::role_change_model::RoleChange;
+use crate::repository::role_change::role_change_repository::RoleChangeRepository;
+use crate::repository::session::session_model::Session;
+use crate::repository::session::session_repository::SessionRepository;
 use crate::repository::user::user_model::User;
 use crate::repository::user::user_repository::UserRepository;
-use crate::services::audit::audit_service::AuditService;
+use crate::repository::user::user_source::UserSource;
+use crate::repository::user::user_status::UserStatus;
+use crate::repository::user_summary::user_summary_model::UserSummary;
+use crate::repository::user_summary::user_summary_repository::UserSummaryRepository;
+use crate::services::api_key::api_key_service::MongoApiKeyService;
+use crate::services::audit::audit_service::MongoAuditService;
+use crate::services::authorization_code::authorization_code_service::MongoAuthorizationCodeService;
+use crate::services::backup::backup_service::MongoBackupService;
+use crate::services::client::client_service::MongoClientService;
+use crate::services::consent::consent_service::MongoConsentService;
+use crate::services::credential::credential_service::MongoCredentialService;
+use crate::services::device::device_service::MongoDeviceService;
+use crate::services::email_job::email_job_service::MongoEmailJobService;
+use crate::services::export::s3_client::S3Client;
+use crate::services::invitation::invitation_service::MongoInvitationService;
 use crate::services::jwt::jwt_service::JwtService;
+use crate::services::login_attempt::login_attempt_service::LoginAttemptService;
+use crate::services::magic_link::magic_link_service::MongoMagicLinkService;
+use crate::services::mfa::totp_service::TotpService;
+use crate::services::mfa::webauthn_service::WebauthnService;
+use crate::services::organization::organization_service::MongoOrganizationService;
+use crate::services::password::argon2_password_hasher::Argon2PasswordHasher;
 use crate::services::password::password_service::PasswordService;
-use crate::services::permission::permission_service::PermissionService;
-use crate::services::role::role_service::RoleService;
-use crate::services::user::user_service::UserService;
+use crate::services::permission::permission_service::MongoPermissionService;
+use crate::services::quota::quota_service::QuotaService;
+use crate::services::refresh_token::refresh_token_service::MongoRefreshTokenService;
+use crate::services::revoked_token::revoked_token_service::MongoRevokedTokenService;
+use crate::services::role::role_service::MongoRoleService;
+use crate::services::role_change::role_change_service::MongoRoleChangeService;
+use crate::services::session::session_service::MongoSessionService;
+use crate::services::social::social_login_service::SocialLoginService;
+use crate::services::sso::sso_service::SsoService;
+use crate::services::user::user_service::MongoUserService;
 use crate::services::Services;
-use log::{error, info};
-use mongodb::bson::doc;
+use futures::TryStreamExt;
+use log::{error, info, warn};
 use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, Bson, Document};
 use mongodb::error::ErrorKind;
-use mongodb::options::{ClientOptions, IndexOptions, ServerApi, ServerApiVersion};
+use mongodb::event::command::CommandEventHandler;
+use mongodb::options::{
+    ClientOptions, CreateCollectionOptions, IndexOptions, SelectionCriteria, ServerApi,
+    ServerApiVersion, ValidationAction, ValidationLevel,
+};
 use mongodb::{Client, Database, IndexModel};
 use regex::Regex;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
 
 #[derive(Clone)]
 pub struct Config {
     pub server_config: ServerConfig,
     pub database: Database,
+    pub tenant_router: TenantRouter,
     pub services: Services,
     pub open_api: bool,
+    pub swagger_ui: bool,
+    pub permission_config: PermissionConfig,
+    pub email_config: EmailConfig,
+    pub email_queue_config: EmailQueueConfig,
+    pub device_config: DeviceConfig,
+    pub magic_link_config: MagicLinkConfig,
+    pub registration_config: RegistrationConfig,
+    pub request_logging_config: RequestLoggingConfig,
+    pub health_config: HealthConfig,
+    pub guest_config: GuestConfig,
+    pub admin_config: AdminConfig,
+    pub slow_query_logger: Option<Arc<SlowQueryLogger>>,
+    pub command_monitor: Option<Arc<CommandMonitor>>,
+    pub db_watchdog: DbWatchdog,
+    pub user_scheduler: UserScheduler,
+    pub email_worker: EmailWorker,
+    pub data_exporter: DataExporter,
+    pub self_check: SelfCheck,
+    pub db_config: Arc<DbConfig>,
+    pub maintenance_mode: MaintenanceMode,
 }
 
 impl Config {
@@ -45,11 +163,35 @@ impl Config {
     /// * `default_user_config` - A DefaultUserConfig instance.
     /// * `generate_default_user` - A bool that indicates whether to generate a default user or not.
     /// * `jwt_config` - A JwtConfig instance.
-    /// * `open_api` - A bool that indicates whether to enable OpenAPI or not.
+    /// * `open_api` - A bool that indicates whether to serve the OpenAPI specification or not.
+    /// * `swagger_ui` - A bool that indicates whether to serve the Swagger UI or not.
+    /// * `permission_config` - A PermissionConfig instance.
+    /// * `user_store_config` - A UserStoreConfig instance.
+    /// * `email_config` - An EmailConfig instance.
+    /// * `email_queue_config` - An EmailQueueConfig instance.
+    /// * `backup_config` - A BackupConfig instance.
+    /// * `device_config` - A DeviceConfig instance.
+    /// * `invitation_config` - An InvitationConfig instance.
+    /// * `magic_link_config` - A MagicLinkConfig instance.
+    /// * `registration_config` - A RegistrationConfig instance.
+    /// * `request_logging_config` - A RequestLoggingConfig instance.
+    /// * `health_config` - A HealthConfig instance.
+    /// * `guest_config` - A GuestConfig instance.
+    /// * `admin_config` - An AdminConfig instance.
+    /// * `cache_config` - A CacheConfig instance.
+    /// * `export_config` - An ExportConfig instance.
+    /// * `social_login_config` - A SocialLoginConfig instance.
+    /// * `sso_config` - An SsoConfig instance.
+    /// * `captcha_config` - A CaptchaConfig instance.
+    /// * `mfa_config` - An MfaConfig instance.
+    /// * `role_change_config` - A RoleChangeConfig instance.
+    /// * `webauthn_config` - A WebauthnConfig instance.
+    /// * `role_templates` - The RoleTemplates of which a Role should be created if it does not exist yet.
     ///
     /// # Returns
     ///
-    /// A Config instance.
+    /// * `Result<Config, ConfigError>` - The Config instance or the ConfigError that occurred.
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         server_config: ServerConfig,
         db_config: DbConfig,
@@ -57,32 +199,206 @@ impl Config {
         generate_default_user: bool,
         jwt_config: JwtConfig,
         open_api: bool,
-    ) -> Config {
+        swagger_ui: bool,
+        permission_config: PermissionConfig,
+        user_store_config: UserStoreConfig,
+        email_config: EmailConfig,
+        email_queue_config: EmailQueueConfig,
+        backup_config: BackupConfig,
+        device_config: DeviceConfig,
+        invitation_config: InvitationConfig,
+        magic_link_config: MagicLinkConfig,
+        registration_config: RegistrationConfig,
+        request_logging_config: RequestLoggingConfig,
+        health_config: HealthConfig,
+        guest_config: GuestConfig,
+        admin_config: AdminConfig,
+        cache_config: CacheConfig,
+        export_config: ExportConfig,
+        social_login_config: SocialLoginConfig,
+        sso_config: SsoConfig,
+        captcha_config: CaptchaConfig,
+        mfa_config: MfaConfig,
+        role_change_config: RoleChangeConfig,
+        webauthn_config: WebauthnConfig,
+        role_templates: Vec<RoleTemplate>,
+    ) -> Result<Config, ConfigError> {
         let mut client_options = match ClientOptions::parse(&db_config.connection_string).await {
             Ok(d) => d,
             Err(e) => {
-                panic!("Failed to parse options: {:?}", e);
+                return Err(ConfigError::invalid(
+                    "DB_CONNECTION_STRING",
+                    &format!("Failed to parse options: {}", e),
+                ));
             }
         };
 
         let server_api = ServerApi::builder().version(ServerApiVersion::V1).build();
         client_options.server_api = Some(server_api);
 
-        let client = Client::with_options(client_options).expect("Failed to initialize client");
+        let slow_query_logger = match db_config.slow_query_threshold {
+            0 => None,
+            t => Some(Arc::new(SlowQueryLogger::new(Duration::from_millis(t)))),
+        };
+
+        let command_monitor = if db_config.command_monitoring && cfg!(feature = "metrics") {
+            Some(Arc::new(CommandMonitor::new()))
+        } else {
+            None
+        };
+
+        let mut command_event_handlers: Vec<Arc<dyn CommandEventHandler>> = vec![];
+        if let Some(l) = &slow_query_logger {
+            command_event_handlers.push(l.clone());
+        }
+        if let Some(m) = &command_monitor {
+            command_event_handlers.push(m.clone());
+        }
+
+        client_options.command_event_handler = match command_event_handlers.len() {
+            0 => None,
+            1 => command_event_handlers.pop(),
+            _ => Some(Arc::new(CommandEventHandlers::new(command_event_handlers))),
+        };
+
+        if let Some(read_preference) = db_config.parse_read_preference()? {
+            client_options.selection_criteria =
+                Some(SelectionCriteria::ReadPreference(read_preference));
+        }
+
+        if let Some(read_concern) = db_config.parse_read_concern()? {
+            client_options.read_concern = Some(read_concern);
+        }
+
+        if let Some(write_concern) = db_config.parse_write_concern()? {
+            client_options.write_concern = Some(write_concern);
+        }
+
+        let client = match Client::with_options(client_options) {
+            Ok(d) => d,
+            Err(e) => {
+                return Err(ConfigError::Database(format!(
+                    "Failed to initialize client: {}",
+                    e
+                )))
+            }
+        };
         let db = client.database(&db_config.database_name);
+        let tenant_router = TenantRouter::connect(
+            db.clone(),
+            &db_config.tenant_connections,
+            &db_config.database_name,
+        )
+        .await?;
+        let db_watchdog = DbWatchdog::new(
+            db.clone(),
+            Duration::from_secs(db_config.health_check_interval),
+        );
 
         let permission_repository =
             match PermissionRepository::new(db_config.permission_collection.clone()) {
                 Ok(d) => d,
-                Err(e) => panic!("Failed to initialize Permission repository: {:?}", e),
+                Err(e) => {
+                    return Err(ConfigError::invalid(
+                        "DB_PERMISSION_COLLECTION",
+                        &e.to_string(),
+                    ))
+                }
             };
         let role_repository = match RoleRepository::new(db_config.role_collection.clone()) {
             Ok(d) => d,
-            Err(e) => panic!("Failed to initialize Role repository: {:?}", e),
+            Err(e) => return Err(ConfigError::invalid("DB_ROLE_COLLECTION", &e.to_string())),
+        };
+        let mut audit_repository = match AuditRepository::new(db_config.audit_collection.clone()) {
+            Ok(d) => d,
+            Err(e) => return Err(ConfigError::invalid("DB_AUDIT_COLLECTION", &e.to_string())),
+        };
+        audit_repository.set_write_concern(db_config.parse_audit_write_concern()?);
+        audit_repository.set_chained(db_config.audit_chaining);
+
+        let device_repository = match DeviceRepository::new(db_config.device_collection.clone()) {
+            Ok(d) => d,
+            Err(e) => return Err(ConfigError::invalid("DB_DEVICE_COLLECTION", &e.to_string())),
+        };
+
+        let client_repository = match ClientRepository::new(db_config.client_collection.clone()) {
+            Ok(d) => d,
+            Err(e) => return Err(ConfigError::invalid("DB_CLIENT_COLLECTION", &e.to_string())),
+        };
+
+        let consent_repository = match ConsentRepository::new(db_config.consent_collection.clone())
+        {
+            Ok(d) => d,
+            Err(e) => {
+                return Err(ConfigError::invalid(
+                    "DB_CONSENT_COLLECTION",
+                    &e.to_string(),
+                ))
+            }
         };
-        let audit_repository = match AuditRepository::new(db_config.audit_collection.clone()) {
+
+        let organization_repository =
+            match OrganizationRepository::new(db_config.organization_collection.clone()) {
+                Ok(d) => d,
+                Err(e) => {
+                    return Err(ConfigError::invalid(
+                        "DB_ORGANIZATION_COLLECTION",
+                        &e.to_string(),
+                    ))
+                }
+            };
+
+        let invitation_repository =
+            match InvitationRepository::new(db_config.invitation_collection.clone()) {
+                Ok(d) => d,
+                Err(e) => {
+                    return Err(ConfigError::invalid(
+                        "DB_INVITATION_COLLECTION",
+                        &e.to_string(),
+                    ))
+                }
+            };
+
+        let refresh_token_repository =
+            match RefreshTokenRepository::new(db_config.refresh_token_collection.clone()) {
+                Ok(d) => d,
+                Err(e) => {
+                    return Err(ConfigError::invalid(
+                        "DB_REFRESH_TOKEN_COLLECTION",
+                        &e.to_string(),
+                    ))
+                }
+            };
+
+        let revoked_token_repository =
+            match RevokedTokenRepository::new(db_config.revoked_token_collection.clone()) {
+                Ok(d) => d,
+                Err(e) => {
+                    return Err(ConfigError::invalid(
+                        "DB_REVOKED_TOKEN_COLLECTION",
+                        &e.to_string(),
+                    ))
+                }
+            };
+
+        let email_job_repository =
+            match EmailJobRepository::new(db_config.email_job_collection.clone()) {
+                Ok(d) => d,
+                Err(e) => {
+                    return Err(ConfigError::invalid(
+                        "DB_EMAIL_JOB_COLLECTION",
+                        &e.to_string(),
+                    ))
+                }
+            };
+
+        let backup_repository = match BackupRepository::new(
+            db_config.permission_collection.clone(),
+            db_config.role_collection.clone(),
+            db_config.user_collection.clone(),
+        ) {
             Ok(d) => d,
-            Err(e) => panic!("Failed to initialize Audit repository: {:?}", e),
+            Err(e) => return Err(ConfigError::Initialization(e.to_string())),
         };
 
         let email_regex = Regex::new(
@@ -90,17 +406,289 @@ impl Config {
         )
         .unwrap();
 
-        let user_repository =
-            match UserRepository::new(db_config.user_collection.clone(), email_regex.clone()) {
+        let user_repository = match UserRepository::new(
+            db_config.user_collection.clone(),
+            email_regex.clone(),
+            email_config.strip_plus_tags,
+            email_config.hash_key.clone(),
+        ) {
+            Ok(d) => d,
+            Err(e) => return Err(ConfigError::invalid("DB_USER_COLLECTION", &e.to_string())),
+        };
+
+        let session_repository = match SessionRepository::new(db_config.session_collection.clone())
+        {
+            Ok(d) => d,
+            Err(e) => {
+                return Err(ConfigError::invalid(
+                    "DB_SESSION_COLLECTION",
+                    &e.to_string(),
+                ))
+            }
+        };
+
+        let api_key_repository = match ApiKeyRepository::new(db_config.api_key_collection.clone()) {
+            Ok(d) => d,
+            Err(e) => {
+                return Err(ConfigError::invalid(
+                    "DB_API_KEY_COLLECTION",
+                    &e.to_string(),
+                ))
+            }
+        };
+
+        let authorization_code_repository =
+            match AuthorizationCodeRepository::new(db_config.authorization_code_collection.clone())
+            {
+                Ok(d) => d,
+                Err(e) => {
+                    return Err(ConfigError::invalid(
+                        "DB_AUTHORIZATION_CODE_COLLECTION",
+                        &e.to_string(),
+                    ))
+                }
+            };
+
+        let role_change_repository =
+            match RoleChangeRepository::new(db_config.role_change_collection.clone()) {
+                Ok(d) => d,
+                Err(e) => {
+                    return Err(ConfigError::invalid(
+                        "DB_ROLE_CHANGE_COLLECTION",
+                        &e.to_string(),
+                    ))
+                }
+            };
+
+        let credential_repository = match CredentialRepository::new(
+            db_config.credential_collection.clone(),
+            db_config.credential_ceremony_collection.clone(),
+        ) {
+            Ok(d) => d,
+            Err(e) => {
+                return Err(ConfigError::invalid(
+                    "DB_CREDENTIAL_COLLECTION",
+                    &e.to_string(),
+                ))
+            }
+        };
+
+        let magic_link_repository =
+            match MagicLinkRepository::new(db_config.magic_link_collection.clone()) {
                 Ok(d) => d,
-                Err(e) => panic!("Failed to initialize User repository: {:?}", e),
+                Err(e) => {
+                    return Err(ConfigError::invalid(
+                        "DB_MAGIC_LINK_COLLECTION",
+                        &e.to_string(),
+                    ))
+                }
             };
 
-        let permission_service = PermissionService::new(permission_repository);
-        let role_service = RoleService::new(role_repository);
-        let user_service = UserService::new(user_repository);
-        let audit_service = AuditService::new(audit_repository, db_config.audit_enabled);
-        let jwt_service = JwtService::new(jwt_config);
+        let user_summary_repository = match UserSummaryRepository::new(
+            db_config.user_summary_collection.clone(),
+            db_config.user_collection.clone(),
+            db_config.role_collection.clone(),
+        ) {
+            Ok(d) => d,
+            Err(e) => {
+                return Err(ConfigError::invalid(
+                    "DB_USER_SUMMARY_COLLECTION",
+                    &e.to_string(),
+                ))
+            }
+        };
+
+        let (permission_cache, role_cache) = if cache_config.is_enabled() {
+            let ttl = Duration::from_secs(cache_config.ttl);
+            (
+                Some(Arc::new(EntityCache::new("permissions", ttl))),
+                Some(Arc::new(EntityCache::new("roles", ttl))),
+            )
+        } else {
+            (None, None)
+        };
+
+        let permission_service = Arc::new(MongoPermissionService::new(
+            permission_repository,
+            permission_cache,
+        ));
+        let role_service = Arc::new(MongoRoleService::new(
+            role_repository,
+            registration_config.domain_roles.clone(),
+            server_config.max_role_permissions,
+            role_cache,
+        ));
+        let user_service = Arc::new(MongoUserService::new(
+            user_repository,
+            user_summary_repository,
+            user_store_config,
+            server_config.max_user_roles,
+        ));
+        let audit_service = Arc::new(MongoAuditService::new(
+            audit_repository,
+            db_config.audit_enabled,
+        ));
+        let jwt_secret_length = jwt_config.jwt_secret.len();
+        let jwt_service = match JwtService::new(jwt_config) {
+            Ok(d) => d,
+            Err(e) => return Err(ConfigError::Initialization(e.to_string())),
+        };
+        let password_service = PasswordService::new(Arc::new(Argon2PasswordHasher::default()));
+        let quota_service =
+            QuotaService::new(server_config.token_quota, server_config.token_quota_mode);
+        let backup_service = Arc::new(MongoBackupService::new(
+            backup_repository,
+            backup_config.secret,
+        ));
+        let device_service = Arc::new(MongoDeviceService::new(
+            device_repository,
+            device_config.clone(),
+        ));
+        let client_service = Arc::new(MongoClientService::new(client_repository));
+        let consent_service = Arc::new(MongoConsentService::new(consent_repository));
+        let organization_service = Arc::new(MongoOrganizationService::new(organization_repository));
+        let invitation_service = Arc::new(MongoInvitationService::new(
+            invitation_repository,
+            invitation_config,
+        ));
+        let refresh_token_service =
+            Arc::new(MongoRefreshTokenService::new(refresh_token_repository));
+        let revoked_token_service =
+            Arc::new(MongoRevokedTokenService::new(revoked_token_repository));
+        let email_job_service = Arc::new(MongoEmailJobService::new(
+            email_job_repository,
+            email_queue_config.clone(),
+        ));
+        let session_service = Arc::new(MongoSessionService::new(session_repository));
+        let api_key_service = Arc::new(MongoApiKeyService::new(api_key_repository));
+        let authorization_code_service = Arc::new(MongoAuthorizationCodeService::new(
+            authorization_code_repository,
+        ));
+        let role_change_service = Arc::new(MongoRoleChangeService::new(
+            role_change_repository,
+            role_change_config,
+        ));
+        let credential_service = Arc::new(MongoCredentialService::new(credential_repository));
+        let magic_link_service = Arc::new(MongoMagicLinkService::new(magic_link_repository));
+        let webauthn_service = match WebauthnService::new(webauthn_config) {
+            Ok(d) => d,
+            Err(e) => return Err(ConfigError::invalid("WEBAUTHN_RP_ORIGIN", &e.to_string())),
+        };
+
+        let user_scheduler = UserScheduler::new(
+            db.clone(),
+            user_service.clone(),
+            role_service.clone(),
+            Duration::from_secs(server_config.user_schedule_interval),
+        );
+
+        let email_worker = EmailWorker::new(
+            db.clone(),
+            email_job_service.clone(),
+            Duration::from_secs(email_queue_config.interval),
+        );
+
+        let s3_client = match &export_config.endpoint {
+            Some(endpoint) if export_config.is_enabled() => match S3Client::new(
+                endpoint,
+                &export_config.bucket,
+                &export_config.region,
+                &export_config.access_key_id,
+                &export_config.secret_access_key,
+            ) {
+                Ok(d) => Some(d),
+                Err(e) => return Err(ConfigError::invalid("EXPORT_S3_ENDPOINT", &e.to_string())),
+            },
+            _ => None,
+        };
+
+        let data_exporter = DataExporter::new(
+            db.clone(),
+            audit_service.clone(),
+            backup_service.clone(),
+            s3_client,
+            export_config.prefix,
+            Duration::from_secs(export_config.interval),
+        );
+
+        let mut indexes = vec![
+            (db_config.permission_collection.clone(), vec!["name"]),
+            (
+                db_config.role_collection.clone(),
+                vec!["name", "permissions"],
+            ),
+            (
+                db_config.user_collection.clone(),
+                vec![
+                    "username",
+                    "email",
+                    "emailVerified",
+                    "secondaryEmails.email",
+                    "emailHashes",
+                    "roles",
+                    "source",
+                    "identities.provider",
+                ],
+            ),
+            (
+                db_config.device_collection.clone(),
+                vec!["deviceCodeHash", "userCode", "expiresAt"],
+            ),
+            (db_config.consent_collection.clone(), vec!["userId"]),
+            (
+                db_config.organization_collection.clone(),
+                vec!["members.userId"],
+            ),
+            (
+                db_config.invitation_collection.clone(),
+                vec!["tokenHash", "organizationId", "expiresAt"],
+            ),
+            (
+                db_config.refresh_token_collection.clone(),
+                vec!["tokenHash", "family", "expiresAt"],
+            ),
+            (
+                db_config.revoked_token_collection.clone(),
+                vec!["tokenHash", "expiresAt"],
+            ),
+            (
+                db_config.email_job_collection.clone(),
+                vec!["nextAttemptAt"],
+            ),
+            (db_config.user_summary_collection.clone(), vec!["username"]),
+            (
+                db_config.session_collection.clone(),
+                vec!["tokenHash", "subject", "expiresAt"],
+            ),
+            (db_config.api_key_collection.clone(), vec!["keyHash"]),
+            (
+                db_config.authorization_code_collection.clone(),
+                vec!["codeHash", "expiresAt"],
+            ),
+            (db_config.role_change_collection.clone(), vec!["status"]),
+            (
+                db_config.credential_collection.clone(),
+                vec!["credentialId", "userId"],
+            ),
+            (
+                db_config.credential_ceremony_collection.clone(),
+                vec!["expiresAt"],
+            ),
+            (db_config.magic_link_collection.clone(), vec!["expiresAt"]),
+        ];
+        if cfg!(feature = "audit") {
+            indexes.push((
+                db_config.audit_collection.clone(),
+                vec!["action", "userId", "resourceIdType", "resourceType"],
+            ));
+        }
+
+        let self_check = SelfCheck::new(
+            db.clone(),
+            indexes,
+            generate_default_user.then(|| default_user_config.clone()),
+            jwt_secret_length,
+        );
 
         let services = Services::new(
             permission_service,
@@ -108,31 +696,125 @@ impl Config {
             user_service,
             jwt_service,
             audit_service,
+            password_service,
+            quota_service,
+            backup_service,
+            device_service,
+            client_service,
+            consent_service,
+            organization_service,
+            invitation_service,
+            refresh_token_service,
+            revoked_token_service,
+            email_job_service,
+            session_service,
+            api_key_service,
+            authorization_code_service,
+            role_change_service,
+            credential_service,
+            magic_link_service,
+            SocialLoginService::new(social_login_config),
+            SsoService::new(sso_config),
+            LoginAttemptService::new(
+                captcha_config.secret,
+                captcha_config.verify_url,
+                captcha_config.threshold,
+                Duration::from_secs(captcha_config.window),
+            ),
+            TotpService::new(
+                mfa_config.secret,
+                mfa_config.issuer,
+                mfa_config.challenge_expiration,
+            ),
+            webauthn_service,
         );
 
+        let db_config = Arc::new(db_config);
+
         let cfg = Config {
             server_config,
             database: db,
+            tenant_router,
             services,
             open_api,
+            swagger_ui,
+            permission_config,
+            email_config,
+            email_queue_config,
+            device_config,
+            magic_link_config,
+            registration_config,
+            request_logging_config,
+            health_config,
+            guest_config,
+            admin_config,
+            slow_query_logger,
+            command_monitor,
+            db_watchdog,
+            user_scheduler,
+            email_worker,
+            data_exporter,
+            self_check,
+            db_config: db_config.clone(),
+            maintenance_mode: MaintenanceMode::default(),
         };
 
+        cfg.migrate_permission_ids(
+            &db_config.permission_collection,
+            &[&db_config.role_collection, &db_config.client_collection],
+        )
+        .await?;
+
         if generate_default_user {
             cfg.initialize_database(default_user_config, email_regex)
-                .await;
+                .await?;
+        }
+
+        for t in role_templates {
+            cfg.create_role_template(t).await?;
+        }
+
+        if cache_config.is_enabled() && cache_config.warming {
+            cfg.warm_caches().await?;
         }
 
         if db_config.create_indexes {
-            cfg.create_permission_indexes(&db_config.permission_collection)
-                .await;
-            cfg.create_role_indexes(&db_config.role_collection).await;
-            cfg.create_user_indexes(&db_config.user_collection).await;
-            cfg.create_audit_indexes(&db_config.audit_collection).await;
-            cfg.create_or_delete_audit_ttl_index(db_config.audit_ttl, &db_config.audit_collection)
-                .await;
+            cfg.create_indexes().await?;
+        }
+
+        cfg.services
+            .user_service
+            .backfill_summaries(&cfg.database)
+            .await
+            .map_err(|e| {
+                ConfigError::Database(format!("Failed to rebuild UserSummaries: {}", e))
+            })?;
+
+        cfg.services
+            .user_service
+            .backfill_email_hashes(&cfg.database)
+            .await
+            .map_err(|e| {
+                ConfigError::Database(format!("Failed to backfill email hashes: {}", e))
+            })?;
+
+        if db_config.create_validators {
+            cfg.apply_collection_validator(
+                &db_config.permission_collection,
+                Permission::json_schema(),
+            )
+            .await?;
+            cfg.apply_collection_validator(&db_config.role_collection, Role::json_schema())
+                .await?;
+            cfg.apply_collection_validator(&db_config.user_collection, User::json_schema())
+                .await?;
+            if cfg!(feature = "audit") {
+                cfg.apply_collection_validator(&db_config.audit_collection, Audit::json_schema())
+                    .await?;
+            }
         }
 
-        cfg
+        Ok(cfg)
     }
 
     /// # Summary
@@ -146,12 +828,12 @@ impl Config {
     ///
     /// # Returns
     ///
-    /// A Permission instance.
+    /// * `Result<Permission, ConfigError>` - The Permission instance or the ConfigError that occurred.
     async fn find_or_create_permission(
         &self,
         name: &str,
         description: Option<String>,
-    ) -> Permission {
+    ) -> Result<Permission, ConfigError> {
         match self
             .services
             .permission_service
@@ -167,13 +849,21 @@ impl Config {
                         .create(p, None, &self.database, &self.services.audit_service)
                         .await
                     {
-                        Ok(p) => return p,
-                        Err(e) => panic!("Failed to create permission: {:?}", e),
+                        Ok(p) => return Ok(p),
+                        Err(e) => {
+                            return Err(ConfigError::Initialization(format!(
+                                "Failed to create permission {}: {}",
+                                name, e
+                            )))
+                        }
                     }
                 }
-                d.unwrap()
+                Ok(d.unwrap())
             }
-            Err(e) => panic!("Failed to find permission: {:?}", e),
+            Err(e) => Err(ConfigError::Database(format!(
+                "Failed to find permission {}: {}",
+                name, e
+            ))),
         }
     }
 
@@ -189,21 +879,22 @@ impl Config {
     ///
     /// # Returns
     ///
-    /// A Role instance.
+    /// * `Result<Role, ConfigError>` - The Role instance or the ConfigError that occurred.
     async fn find_or_create_role(
         &self,
         name: &str,
         description: Option<String>,
         permissions: Option<Vec<String>>,
-    ) -> Role {
+    ) -> Result<Role, ConfigError> {
         match self
             .services
             .role_service
             .find_by_name(name, &self.database)
             .await
         {
-            Ok(d) => {
-                if d.is_none() {
+            Ok(d) => match d {
+                Some(r) => Ok(r),
+                None => {
                     let permission_oid_vec: Option<Vec<ObjectId>> = match permissions {
                         None => None,
                         Some(p_vec) => {
@@ -229,14 +920,18 @@ impl Config {
                         .create(new_role, None, &self.database, &self.services.audit_service)
                         .await
                     {
-                        Ok(d) => d,
-                        Err(e) => panic!("Failed to create role: {:?}", e),
+                        Ok(d) => Ok(d),
+                        Err(e) => Err(ConfigError::Initialization(format!(
+                            "Failed to create role {}: {}",
+                            name, e
+                        ))),
                     }
-                } else {
-                    d.unwrap()
                 }
-            }
-            Err(e) => panic!("Failed to find role: {:?}", e),
+            },
+            Err(e) => Err(ConfigError::Database(format!(
+                "Failed to find role {}: {}",
+                name, e
+            ))),
         }
     }
 
@@ -250,19 +945,22 @@ impl Config {
     /// * `roles` - An optional vector of string slices that holds the roles.
     /// * `email_regex` - A Regex instance that holds the email regex.
     ///
-    /// # Panics
+    /// # Returns
     ///
-    /// This method will panic if the email address is invalid or if the user could not be found or created.
+    /// * `Result<(), ConfigError>` - The ConfigError that occurred if the email address is invalid or if the user could not be found or created.
     async fn find_or_create_user(
         &self,
         default_user_config: DefaultUserConfig,
         roles: Option<Vec<String>>,
         email_regex: Regex,
-    ) {
+    ) -> Result<(), ConfigError> {
         if default_user_config.email.is_some()
-            && !email_regex.is_match(&default_user_config.email.clone().unwrap())
+            && !email_regex.is_match(&default_user_config.email.clone().unwrap().to_lowercase())
         {
-            panic!("Invalid email address");
+            return Err(ConfigError::invalid(
+                "DEFAULT_USER_EMAIL",
+                "Invalid email address",
+            ));
         }
 
         match self
@@ -273,11 +971,11 @@ impl Config {
         {
             Ok(user) => {
                 if user.is_some() {
-                    return;
+                    return Ok(());
                 }
             }
             Err(e) => {
-                panic!("Failed to find user: {:?}", e);
+                return Err(ConfigError::Database(format!("Failed to find user: {}", e)));
             }
         }
 
@@ -289,23 +987,35 @@ impl Config {
         {
             Ok(d) => {
                 if d.is_none() {
-                    let password_hash =
-                        match PasswordService::hash_password(default_user_config.password) {
-                            Ok(e) => e,
-                            Err(e) => {
-                                panic!("Failed to hash password: {}", e);
-                            }
-                        };
+                    let password_hash = match self
+                        .services
+                        .password_service
+                        .hash_password(&default_user_config.password)
+                    {
+                        Ok(e) => e,
+                        Err(e) => {
+                            return Err(ConfigError::Initialization(format!(
+                                "Failed to hash password: {}",
+                                e
+                            )));
+                        }
+                    };
 
-                    let user = User::new(
+                    let mut user = User::new(
                         default_user_config.username,
                         default_user_config.email,
                         None,
                         None,
                         password_hash,
                         roles,
-                        default_user_config.enabled,
+                        if default_user_config.enabled {
+                            UserStatus::Active
+                        } else {
+                            UserStatus::Deactivated
+                        },
                     );
+                    user.source = Some(UserSource::System);
+
                     match self
                         .services
                         .user_service
@@ -313,12 +1023,243 @@ impl Config {
                         .await
                     {
                         Ok(_) => {}
-                        Err(e) => panic!("Failed to create user: {:?}", e),
+                        Err(e) => {
+                            return Err(ConfigError::Initialization(format!(
+                                "Failed to create user: {}",
+                                e
+                            )))
+                        }
                     }
                 }
+
+                Ok(())
+            }
+            Err(e) => Err(ConfigError::Database(format!("Failed to find user: {}", e))),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Migrate Permissions that were stored with a string ID to an ObjectId, and replace the string references to
+    /// Permissions in the given collections with ObjectIds. Permissions keep their ID if it is a valid ObjectId in
+    /// hexadecimal form and receive a new ID otherwise. References to Permissions that do not exist are removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `permission_collection` - A string slice that holds the name of the Permission collection.
+    /// * `referencing_collections` - The names of the collections whose documents have a `permissions` array.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), ConfigError>` - The ConfigError that occurred if the documents could not be migrated.
+    pub async fn migrate_permission_ids(
+        &self,
+        permission_collection: &str,
+        referencing_collections: &[&str],
+    ) -> Result<(), ConfigError> {
+        let permissions = self.database.collection::<Document>(permission_collection);
+
+        let legacy: Vec<Document> = permissions
+            .find(doc! { "_id": { "$type": "string" } }, None)
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to find permissions: {}", e)))?
+            .try_collect()
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to read permissions: {}", e)))?;
+
+        let mut migrated: HashMap<String, ObjectId> = HashMap::new();
+        for mut permission in legacy {
+            let old_id = match permission.get_str("_id") {
+                Ok(d) => d.to_string(),
+                Err(_) => continue,
+            };
+            let new_id = ObjectId::parse_str(&old_id).unwrap_or_else(|_| ObjectId::new());
+            permission.insert("_id", new_id);
+
+            permissions
+                .insert_one(permission, None)
+                .await
+                .map_err(|e| {
+                    ConfigError::Database(format!("Failed to migrate permission: {}", e))
+                })?;
+            permissions
+                .delete_one(doc! { "_id": &old_id }, None)
+                .await
+                .map_err(|e| {
+                    ConfigError::Database(format!("Failed to migrate permission: {}", e))
+                })?;
+
+            migrated.insert(old_id, new_id);
+        }
+
+        if !migrated.is_empty() {
+            info!("Migrated {} permission(s) to ObjectId IDs", migrated.len());
+        }
+
+        for collection in referencing_collections {
+            let collection = self.database.collection::<Document>(collection);
+
+            let documents: Vec<Document> = collection
+                .find(doc! { "permissions": { "$type": "string" } }, None)
+                .await
+                .map_err(|e| ConfigError::Database(format!("Failed to find documents: {}", e)))?
+                .try_collect()
+                .await
+                .map_err(|e| ConfigError::Database(format!("Failed to read documents: {}", e)))?;
+
+            for document in documents {
+                let permission_ids: Vec<Bson> = document
+                    .get_array("permissions")
+                    .map(|a| a.to_vec())
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|p| match p {
+                        Bson::String(s) => match migrated.get(&s) {
+                            Some(oid) => Some(Bson::ObjectId(*oid)),
+                            None => match ObjectId::parse_str(&s) {
+                                Ok(oid) => Some(Bson::ObjectId(oid)),
+                                Err(_) => {
+                                    warn!("Removing reference to unknown permission: {}", s);
+                                    None
+                                }
+                            },
+                        },
+                        p => Some(p),
+                    })
+                    .collect();
+
+                collection
+                    .update_one(
+                        doc! { "_id": document.get("_id").cloned() },
+                        doc! { "$set": { "permissions": permission_ids } },
+                        None,
+                    )
+                    .await
+                    .map_err(|e| {
+                        ConfigError::Database(format!(
+                            "Failed to migrate permission references: {}",
+                            e
+                        ))
+                    })?;
             }
-            Err(e) => panic!("Failed to find user: {:?}", e),
         }
+
+        Ok(())
+    }
+
+    /// # Summary
+    ///
+    /// Create the indexes of every collection. Indexes that already exist are left unchanged, so this can be run again
+    /// to restore indexes that were dropped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// config.create_indexes().await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), ConfigError>` - The ConfigError that occurred if an index could not be created.
+    pub async fn create_indexes(&self) -> Result<(), ConfigError> {
+        self.create_permission_indexes(&self.db_config.permission_collection)
+            .await?;
+        self.create_role_indexes(&self.db_config.role_collection)
+            .await?;
+        self.create_user_indexes(&self.db_config.user_collection)
+            .await?;
+        self.create_device_indexes(&self.db_config.device_collection)
+            .await?;
+        self.create_consent_indexes(&self.db_config.consent_collection)
+            .await?;
+        self.create_organization_indexes(&self.db_config.organization_collection)
+            .await?;
+        self.create_invitation_indexes(&self.db_config.invitation_collection)
+            .await?;
+        self.create_refresh_token_indexes(&self.db_config.refresh_token_collection)
+            .await?;
+        self.create_revoked_token_indexes(&self.db_config.revoked_token_collection)
+            .await?;
+        if cfg!(feature = "email") {
+            self.create_email_job_indexes(&self.db_config.email_job_collection)
+                .await?;
+        }
+        self.create_user_summary_indexes(&self.db_config.user_summary_collection)
+            .await?;
+        self.create_session_indexes(&self.db_config.session_collection)
+            .await?;
+        self.create_api_key_indexes(&self.db_config.api_key_collection)
+            .await?;
+        self.create_authorization_code_indexes(&self.db_config.authorization_code_collection)
+            .await?;
+        self.create_role_change_indexes(&self.db_config.role_change_collection)
+            .await?;
+        if cfg!(feature = "mfa") {
+            self.create_credential_indexes(
+                &self.db_config.credential_collection,
+                &self.db_config.credential_ceremony_collection,
+            )
+            .await?;
+        }
+        self.create_magic_link_indexes(&self.db_config.magic_link_collection)
+            .await?;
+        if cfg!(feature = "audit") {
+            self.create_audit_indexes(&self.db_config.audit_collection)
+                .await?;
+            self.create_or_delete_audit_ttl_index(
+                self.db_config.audit_ttl,
+                &self.db_config.audit_collection,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// # Summary
+    ///
+    /// Delete the expired records of the collections that have a TTL index on `expiresAt`. MongoDB removes these
+    /// records on its own, but only once a minute and only if the TTL indexes were created.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let purged = config.purge_expired().await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<(String, u64)>, ConfigError>` - The amount of records that were deleted per collection.
+    pub async fn purge_expired(&self) -> Result<Vec<(String, u64)>, ConfigError> {
+        let mut collections = vec![
+            &self.db_config.device_collection,
+            &self.db_config.invitation_collection,
+            &self.db_config.refresh_token_collection,
+            &self.db_config.revoked_token_collection,
+            &self.db_config.session_collection,
+            &self.db_config.authorization_code_collection,
+            &self.db_config.magic_link_collection,
+        ];
+        if cfg!(feature = "mfa") {
+            collections.push(&self.db_config.credential_ceremony_collection);
+        }
+
+        let filter = doc! { "expiresAt": { "$lte": mongodb::bson::DateTime::now() } };
+
+        let mut res: Vec<(String, u64)> = vec![];
+        for c in collections {
+            info!("Purging expired records of the {} collection", c);
+            let deleted = self
+                .database
+                .collection::<Document>(c)
+                .delete_many(filter.clone(), None)
+                .await
+                .map_err(|e| ConfigError::Database(format!("Failed to purge {}: {}", c, e)))?;
+
+            res.push((c.clone(), deleted.deleted_count));
+        }
+
+        Ok(res)
     }
 
     /// # Summary
@@ -329,10 +1270,13 @@ impl Config {
     ///
     /// * `permission_collection` - A string slice that holds the name of the Permission collection.
     ///
-    /// # Panics
+    /// # Returns
     ///
-    /// This method will panic if the indexes could not be created.
-    pub async fn create_permission_indexes(&self, permission_collection: &str) {
+    /// * `Result<(), ConfigError>` - The ConfigError that occurred if the indexes could not be created.
+    pub async fn create_permission_indexes(
+        &self,
+        permission_collection: &str,
+    ) -> Result<(), ConfigError> {
         info!("Creating indexes for the Permission collection");
         let options = IndexOptions::builder().build();
         let model = IndexModel::builder()
@@ -344,7 +1288,7 @@ impl Config {
             .collection::<Permission>(permission_collection)
             .create_index(model, None)
             .await
-            .expect("Creating an index should succeed");
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
 
         let options = IndexOptions::builder().build();
         let model = IndexModel::builder()
@@ -356,7 +1300,9 @@ impl Config {
             .collection::<Permission>(permission_collection)
             .create_index(model, None)
             .await
-            .expect("Creating an index should succeed");
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        Ok(())
     }
 
     /// # Summary
@@ -367,10 +1313,10 @@ impl Config {
     ///
     /// * `role_collection` - A string slice that holds the name of the Role collection.
     ///
-    /// # Panics
+    /// # Returns
     ///
-    /// This method will panic if the indexes could not be created.
-    pub async fn create_role_indexes(&self, role_collection: &str) {
+    /// * `Result<(), ConfigError>` - The ConfigError that occurred if the indexes could not be created.
+    pub async fn create_role_indexes(&self, role_collection: &str) -> Result<(), ConfigError> {
         info!("Creating indexes for the Role collection");
         let options = IndexOptions::builder().build();
         let model = IndexModel::builder()
@@ -382,11 +1328,43 @@ impl Config {
             .collection::<Role>(role_collection)
             .create_index(model, None)
             .await
-            .expect("Creating an index should succeed");
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
 
         let options = IndexOptions::builder().build();
         let model = IndexModel::builder()
-            .keys(doc! { "name": "text" })
+            .keys(doc! { "permissions": 1u32 })
+            .options(options)
+            .build();
+
+        self.database
+            .collection::<Role>(role_collection)
+            .create_index(model, None)
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        // A collection can only have a single text index, so the previous index that only covered the name is replaced
+        match self
+            .database
+            .collection::<Role>(role_collection)
+            .drop_index("name_text", None)
+            .await
+        {
+            Ok(_) => info!("Replaced the text index of the Role collection"),
+            Err(e) => match e.kind.as_ref() {
+                // The index or the collection does not exist
+                ErrorKind::Command(e) if e.code == 26 || e.code == 27 => {}
+                _ => {
+                    return Err(ConfigError::Database(format!(
+                        "Failed to delete index: {}",
+                        e
+                    )))
+                }
+            },
+        }
+
+        let options = IndexOptions::builder().build();
+        let model = IndexModel::builder()
+            .keys(doc! { "name": "text", "description": "text" })
             .options(options)
             .build();
 
@@ -394,7 +1372,9 @@ impl Config {
             .collection::<Role>(role_collection)
             .create_index(model, None)
             .await
-            .expect("Creating an index should succeed");
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        Ok(())
     }
 
     /// # Summary
@@ -405,10 +1385,10 @@ impl Config {
     ///
     /// * `user_collection` - A string slice that holds the name of the User collection.
     ///
-    /// # Panics
+    /// # Returns
     ///
-    /// This method will panic if the indexes could not be created.
-    pub async fn create_user_indexes(&self, user_collection: &str) {
+    /// * `Result<(), ConfigError>` - The ConfigError that occurred if the indexes could not be created.
+    pub async fn create_user_indexes(&self, user_collection: &str) -> Result<(), ConfigError> {
         info!("Creating indexes for the User collection");
         let options = IndexOptions::builder().build();
         let model = IndexModel::builder()
@@ -420,7 +1400,7 @@ impl Config {
             .collection::<User>(user_collection)
             .create_index(model, None)
             .await
-            .expect("Creating an index should succeed");
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
 
         let options = IndexOptions::builder().build();
         let model = IndexModel::builder()
@@ -432,11 +1412,11 @@ impl Config {
             .collection::<User>(user_collection)
             .create_index(model, None)
             .await
-            .expect("Creating an index should succeed");
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
 
         let options = IndexOptions::builder().build();
         let model = IndexModel::builder()
-            .keys(doc! { "username": "text", "email": "text", "firstName": "text", "lastName": "text"})
+            .keys(doc! { "emailVerified": 1u32, "email": 1u32})
             .options(options)
             .build();
 
@@ -444,70 +1424,754 @@ impl Config {
             .collection::<User>(user_collection)
             .create_index(model, None)
             .await
-            .expect("Creating an index should succeed");
-    }
-
-    /// # Summary
-    ///
-    /// Create default indexes for the Audit collection.
-    ///
-    /// # Arguments
-    ///
-    /// * `audit_collection` - A string slice that holds the name of the Audit collection.
-    ///
-    /// # Panics
-    ///
-    /// This method will panic if the indexes could not be created.
-    pub async fn create_audit_indexes(&self, audit_collection: &str) {
-        info!("Creating indexes for the Audit collection");
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
 
         let options = IndexOptions::builder().build();
         let model = IndexModel::builder()
-            .keys(doc! { "action": 1u32})
+            .keys(doc! { "secondaryEmails.email": 1u32})
             .options(options)
             .build();
 
         self.database
-            .collection::<Audit>(audit_collection)
+            .collection::<User>(user_collection)
             .create_index(model, None)
             .await
-            .expect("Creating an index should succeed");
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
 
-        let options = IndexOptions::builder().build();
+        let options = IndexOptions::builder().unique(true).sparse(true).build();
         let model = IndexModel::builder()
-            .keys(doc! { "resourceIdType": 1u32})
+            .keys(doc! { "emailHashes": 1u32})
             .options(options)
             .build();
 
         self.database
-            .collection::<Audit>(audit_collection)
+            .collection::<User>(user_collection)
             .create_index(model, None)
             .await
-            .expect("Creating an index should succeed");
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
 
         let options = IndexOptions::builder().build();
         let model = IndexModel::builder()
-            .keys(doc! { "resourceType": 1u32})
+            .keys(doc! { "roles": 1u32})
             .options(options)
             .build();
 
         self.database
-            .collection::<Audit>(audit_collection)
+            .collection::<User>(user_collection)
+            .create_index(model, None)
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        let options = IndexOptions::builder().unique(true).sparse(true).build();
+        let model = IndexModel::builder()
+            .keys(doc! { "identities.provider": 1u32, "identities.subject": 1u32})
+            .options(options)
+            .build();
+
+        self.database
+            .collection::<User>(user_collection)
+            .create_index(model, None)
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        let options = IndexOptions::builder().build();
+        let model = IndexModel::builder()
+            .keys(doc! { "source": 1u32})
+            .options(options)
+            .build();
+
+        self.database
+            .collection::<User>(user_collection)
+            .create_index(model, None)
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        let options = IndexOptions::builder().build();
+        let model = IndexModel::builder()
+            .keys(doc! { "username": "text", "email": "text", "firstName": "text", "lastName": "text"})
+            .options(options)
+            .build();
+
+        self.database
+            .collection::<User>(user_collection)
+            .create_index(model, None)
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// # Summary
+    ///
+    /// Create default indexes for the Audit collection.
+    ///
+    /// # Arguments
+    ///
+    /// * `audit_collection` - A string slice that holds the name of the Audit collection.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), ConfigError>` - The ConfigError that occurred if the indexes could not be created.
+    pub async fn create_audit_indexes(&self, audit_collection: &str) -> Result<(), ConfigError> {
+        info!("Creating indexes for the Audit collection");
+
+        let options = IndexOptions::builder().build();
+        let model = IndexModel::builder()
+            .keys(doc! { "action": 1u32})
+            .options(options)
+            .build();
+
+        self.database
+            .collection::<Audit>(audit_collection)
+            .create_index(model, None)
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        let options = IndexOptions::builder().build();
+        let model = IndexModel::builder()
+            .keys(doc! { "userId": 1u32, "createdAt": -1i32 })
+            .options(options)
+            .build();
+
+        self.database
+            .collection::<Audit>(audit_collection)
+            .create_index(model, None)
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        let options = IndexOptions::builder().build();
+        let model = IndexModel::builder()
+            .keys(doc! { "resourceIdType": 1u32})
+            .options(options)
+            .build();
+
+        self.database
+            .collection::<Audit>(audit_collection)
+            .create_index(model, None)
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        let options = IndexOptions::builder().build();
+        let model = IndexModel::builder()
+            .keys(doc! { "resourceType": 1u32})
+            .options(options)
+            .build();
+
+        self.database
+            .collection::<Audit>(audit_collection)
+            .create_index(model, None)
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        let options = IndexOptions::builder().build();
+        let model = IndexModel::builder()
+            .keys(doc! { "action": "text", "resourceIdType": "text", "resourceType": "text"})
+            .options(options)
+            .build();
+
+        self.database
+            .collection::<Audit>(audit_collection)
+            .create_index(model, None)
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        // Only a single record can be appended at every position of a chain
+        let options = IndexOptions::builder().unique(true).sparse(true).build();
+        let model = IndexModel::builder()
+            .keys(doc! { "chain.partition": 1u32, "chain.sequence": 1u32 })
+            .options(options)
+            .build();
+
+        self.database
+            .collection::<Audit>(audit_collection)
+            .create_index(model, None)
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// # Summary
+    ///
+    /// Create default indexes for the DeviceAuthorization collection.
+    ///
+    /// # Arguments
+    ///
+    /// * `device_collection` - A string slice that holds the name of the DeviceAuthorization collection.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), ConfigError>` - The ConfigError that occurred if the indexes could not be created.
+    pub async fn create_device_indexes(&self, device_collection: &str) -> Result<(), ConfigError> {
+        info!("Creating indexes for the DeviceAuthorization collection");
+        let options = IndexOptions::builder().unique(true).build();
+        let model = IndexModel::builder()
+            .keys(doc! { "deviceCodeHash": 1u32})
+            .options(options)
+            .build();
+
+        self.database
+            .collection::<DeviceAuthorization>(device_collection)
+            .create_index(model, None)
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        let options = IndexOptions::builder().unique(true).build();
+        let model = IndexModel::builder()
+            .keys(doc! { "userCode": 1u32})
+            .options(options)
+            .build();
+
+        self.database
+            .collection::<DeviceAuthorization>(device_collection)
+            .create_index(model, None)
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        // Expired device authorizations are removed automatically
+        let options = IndexOptions::builder()
+            .expire_after(Some(Duration::from_secs(0)))
+            .build();
+        let model = IndexModel::builder()
+            .keys(doc! { "expiresAt": 1u32})
+            .options(options)
+            .build();
+
+        self.database
+            .collection::<DeviceAuthorization>(device_collection)
+            .create_index(model, None)
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// # Summary
+    ///
+    /// Create default indexes for the Consent collection.
+    ///
+    /// # Arguments
+    ///
+    /// * `consent_collection` - A string slice that holds the name of the Consent collection.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), ConfigError>` - The ConfigError that occurred if the index could not be created.
+    pub async fn create_consent_indexes(
+        &self,
+        consent_collection: &str,
+    ) -> Result<(), ConfigError> {
+        info!("Creating indexes for the Consent collection");
+        // A User has at most one Consent per Client, which also covers the lookups by User
+        let options = IndexOptions::builder().unique(true).build();
+        let model = IndexModel::builder()
+            .keys(doc! { "userId": 1u32, "clientId": 1u32})
+            .options(options)
+            .build();
+
+        self.database
+            .collection::<Consent>(consent_collection)
+            .create_index(model, None)
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// # Summary
+    ///
+    /// Create default indexes for the Organization collection.
+    ///
+    /// # Arguments
+    ///
+    /// * `organization_collection` - A string slice that holds the name of the Organization collection.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), ConfigError>` - The ConfigError that occurred if the index could not be created.
+    pub async fn create_organization_indexes(
+        &self,
+        organization_collection: &str,
+    ) -> Result<(), ConfigError> {
+        info!("Creating indexes for the Organization collection");
+        // The Organizations of a User are looked up whenever a tenant token is issued or used
+        let model = IndexModel::builder()
+            .keys(doc! { "members.userId": 1u32})
+            .build();
+
+        self.database
+            .collection::<Organization>(organization_collection)
+            .create_index(model, None)
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// # Summary
+    ///
+    /// Create default indexes for the Invitation collection.
+    ///
+    /// # Arguments
+    ///
+    /// * `invitation_collection` - A string slice that holds the name of the Invitation collection.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), ConfigError>` - The ConfigError that occurred if the indexes could not be created.
+    pub async fn create_invitation_indexes(
+        &self,
+        invitation_collection: &str,
+    ) -> Result<(), ConfigError> {
+        info!("Creating indexes for the Invitation collection");
+
+        // Invitations of Organizations with residency constraints are stored in the Database of their tenant
+        for db in self.tenant_router.databases() {
+            let options = IndexOptions::builder().unique(true).build();
+            let model = IndexModel::builder()
+                .keys(doc! { "tokenHash": 1u32})
+                .options(options)
+                .build();
+
+            db.collection::<Invitation>(invitation_collection)
+                .create_index(model, None)
+                .await
+                .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+            let model = IndexModel::builder()
+                .keys(doc! { "organizationId": 1u32})
+                .build();
+
+            db.collection::<Invitation>(invitation_collection)
+                .create_index(model, None)
+                .await
+                .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+            // Expired invitations are removed automatically
+            let options = IndexOptions::builder()
+                .expire_after(Some(Duration::from_secs(0)))
+                .build();
+            let model = IndexModel::builder()
+                .keys(doc! { "expiresAt": 1u32})
+                .options(options)
+                .build();
+
+            db.collection::<Invitation>(invitation_collection)
+                .create_index(model, None)
+                .await
+                .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+        }
+
+        Ok(())
+    }
+
+    /// # Summary
+    ///
+    /// Create default indexes for the RefreshToken collection.
+    ///
+    /// # Arguments
+    ///
+    /// * `refresh_token_collection` - A string slice that holds the name of the RefreshToken collection.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), ConfigError>` - The ConfigError that occurred if the indexes could not be created.
+    pub async fn create_refresh_token_indexes(
+        &self,
+        refresh_token_collection: &str,
+    ) -> Result<(), ConfigError> {
+        info!("Creating indexes for the RefreshToken collection");
+        let options = IndexOptions::builder().unique(true).build();
+        let model = IndexModel::builder()
+            .keys(doc! { "tokenHash": 1u32})
+            .options(options)
+            .build();
+
+        self.database
+            .collection::<RefreshToken>(refresh_token_collection)
+            .create_index(model, None)
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        let model = IndexModel::builder().keys(doc! { "family": 1u32}).build();
+
+        self.database
+            .collection::<RefreshToken>(refresh_token_collection)
+            .create_index(model, None)
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        // Expired refresh tokens are removed automatically
+        let options = IndexOptions::builder()
+            .expire_after(Some(Duration::from_secs(0)))
+            .build();
+        let model = IndexModel::builder()
+            .keys(doc! { "expiresAt": 1u32})
+            .options(options)
+            .build();
+
+        self.database
+            .collection::<RefreshToken>(refresh_token_collection)
+            .create_index(model, None)
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// # Summary
+    ///
+    /// Create default indexes for the RevokedToken collection.
+    ///
+    /// # Arguments
+    ///
+    /// * `revoked_token_collection` - A string slice that holds the name of the RevokedToken collection.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), ConfigError>` - The ConfigError that occurred if the indexes could not be created.
+    pub async fn create_revoked_token_indexes(
+        &self,
+        revoked_token_collection: &str,
+    ) -> Result<(), ConfigError> {
+        info!("Creating indexes for the RevokedToken collection");
+        let options = IndexOptions::builder().unique(true).build();
+        let model = IndexModel::builder()
+            .keys(doc! { "tokenHash": 1u32})
+            .options(options)
+            .build();
+
+        self.database
+            .collection::<RevokedToken>(revoked_token_collection)
+            .create_index(model, None)
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        // Revoked tokens are removed from the denylist once they would have expired anyway
+        let options = IndexOptions::builder()
+            .expire_after(Some(Duration::from_secs(0)))
+            .build();
+        let model = IndexModel::builder()
+            .keys(doc! { "expiresAt": 1u32})
+            .options(options)
+            .build();
+
+        self.database
+            .collection::<RevokedToken>(revoked_token_collection)
+            .create_index(model, None)
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// # Summary
+    ///
+    /// Create default indexes for the Session collection.
+    ///
+    /// # Arguments
+    ///
+    /// * `session_collection` - A string slice that holds the name of the Session collection.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), ConfigError>` - The ConfigError that occurred if the indexes could not be created.
+    pub async fn create_session_indexes(
+        &self,
+        session_collection: &str,
+    ) -> Result<(), ConfigError> {
+        info!("Creating indexes for the Session collection");
+        let options = IndexOptions::builder().unique(true).build();
+        let model = IndexModel::builder()
+            .keys(doc! { "tokenHash": 1u32})
+            .options(options)
+            .build();
+
+        self.database
+            .collection::<Session>(session_collection)
+            .create_index(model, None)
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        let model = IndexModel::builder().keys(doc! { "subject": 1u32}).build();
+
+        self.database
+            .collection::<Session>(session_collection)
+            .create_index(model, None)
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        // Sessions are removed once they have expired
+        let options = IndexOptions::builder()
+            .expire_after(Some(Duration::from_secs(0)))
+            .build();
+        let model = IndexModel::builder()
+            .keys(doc! { "expiresAt": 1u32})
+            .options(options)
+            .build();
+
+        self.database
+            .collection::<Session>(session_collection)
+            .create_index(model, None)
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// # Summary
+    ///
+    /// Create default indexes for the ApiKey collection.
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key_collection` - A string slice that holds the name of the ApiKey collection.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), ConfigError>` - The ConfigError that occurred if the indexes could not be created.
+    pub async fn create_api_key_indexes(
+        &self,
+        api_key_collection: &str,
+    ) -> Result<(), ConfigError> {
+        info!("Creating indexes for the ApiKey collection");
+        let options = IndexOptions::builder().unique(true).build();
+        let model = IndexModel::builder()
+            .keys(doc! { "keyHash": 1u32})
+            .options(options)
+            .build();
+
+        self.database
+            .collection::<ApiKey>(api_key_collection)
+            .create_index(model, None)
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// # Summary
+    ///
+    /// Create default indexes for the AuthorizationCode collection.
+    ///
+    /// # Arguments
+    ///
+    /// * `authorization_code_collection` - A string slice that holds the name of the AuthorizationCode collection.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), ConfigError>` - The ConfigError that occurred if the indexes could not be created.
+    pub async fn create_authorization_code_indexes(
+        &self,
+        authorization_code_collection: &str,
+    ) -> Result<(), ConfigError> {
+        info!("Creating indexes for the AuthorizationCode collection");
+        let options = IndexOptions::builder().unique(true).build();
+        let model = IndexModel::builder()
+            .keys(doc! { "codeHash": 1u32})
+            .options(options)
+            .build();
+
+        self.database
+            .collection::<AuthorizationCode>(authorization_code_collection)
+            .create_index(model, None)
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        // Authorization codes that were never exchanged are removed automatically
+        let options = IndexOptions::builder()
+            .expire_after(Some(Duration::from_secs(0)))
+            .build();
+        let model = IndexModel::builder()
+            .keys(doc! { "expiresAt": 1u32})
+            .options(options)
+            .build();
+
+        self.database
+            .collection::<AuthorizationCode>(authorization_code_collection)
+            .create_index(model, None)
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// # Summary
+    ///
+    /// Create default indexes for the RoleChange collection.
+    ///
+    /// # Arguments
+    ///
+    /// * `role_change_collection` - A string slice that holds the name of the RoleChange collection.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), ConfigError>` - The ConfigError that occurred if the indexes could not be created.
+    pub async fn create_role_change_indexes(
+        &self,
+        role_change_collection: &str,
+    ) -> Result<(), ConfigError> {
+        info!("Creating indexes for the RoleChange collection");
+        let model = IndexModel::builder()
+            .keys(doc! { "status": 1u32, "createdAt": 1u32})
+            .build();
+
+        self.database
+            .collection::<RoleChange>(role_change_collection)
             .create_index(model, None)
             .await
-            .expect("Creating an index should succeed");
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
 
-        let options = IndexOptions::builder().build();
+        Ok(())
+    }
+
+    /// # Summary
+    ///
+    /// Create default indexes for the Credential and the CredentialCeremony collection.
+    ///
+    /// # Arguments
+    ///
+    /// * `credential_collection` - A string slice that holds the name of the Credential collection.
+    /// * `credential_ceremony_collection` - A string slice that holds the name of the CredentialCeremony collection.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), ConfigError>` - The ConfigError that occurred if the indexes could not be created.
+    pub async fn create_credential_indexes(
+        &self,
+        credential_collection: &str,
+        credential_ceremony_collection: &str,
+    ) -> Result<(), ConfigError> {
+        info!("Creating indexes for the Credential collection");
+        let options = IndexOptions::builder().unique(true).build();
         let model = IndexModel::builder()
-            .keys(doc! { "action": "text", "resourceIdType": "text", "resourceType": "text"})
+            .keys(doc! { "credentialId": 1u32})
             .options(options)
             .build();
 
         self.database
-            .collection::<Audit>(audit_collection)
+            .collection::<Credential>(credential_collection)
+            .create_index(model, None)
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        let model = IndexModel::builder()
+            .keys(doc! { "userId": 1u32, "createdAt": 1u32})
+            .build();
+
+        self.database
+            .collection::<Credential>(credential_collection)
+            .create_index(model, None)
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        info!("Creating indexes for the CredentialCeremony collection");
+        // Ceremonies that were never finished are removed automatically
+        let options = IndexOptions::builder()
+            .expire_after(Some(Duration::from_secs(0)))
+            .build();
+        let model = IndexModel::builder()
+            .keys(doc! { "expiresAt": 1u32})
+            .options(options)
+            .build();
+
+        self.database
+            .collection::<CredentialCeremony>(credential_ceremony_collection)
+            .create_index(model, None)
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// # Summary
+    ///
+    /// Create default indexes for the MagicLink collection.
+    ///
+    /// # Arguments
+    ///
+    /// * `magic_link_collection` - A string slice that holds the name of the MagicLink collection.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), ConfigError>` - The ConfigError that occurred if the indexes could not be created.
+    pub async fn create_magic_link_indexes(
+        &self,
+        magic_link_collection: &str,
+    ) -> Result<(), ConfigError> {
+        info!("Creating indexes for the MagicLink collection");
+        // Magic links that were never used are removed automatically
+        let options = IndexOptions::builder()
+            .expire_after(Some(Duration::from_secs(0)))
+            .build();
+        let model = IndexModel::builder()
+            .keys(doc! { "expiresAt": 1u32})
+            .options(options)
+            .build();
+
+        self.database
+            .collection::<MagicLink>(magic_link_collection)
+            .create_index(model, None)
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// # Summary
+    ///
+    /// Create default indexes for the EmailJob collection.
+    ///
+    /// # Arguments
+    ///
+    /// * `email_job_collection` - A string slice that holds the name of the EmailJob collection.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), ConfigError>` - The ConfigError that occurred if the indexes could not be created.
+    pub async fn create_email_job_indexes(
+        &self,
+        email_job_collection: &str,
+    ) -> Result<(), ConfigError> {
+        info!("Creating indexes for the EmailJob collection");
+        let model = IndexModel::builder()
+            .keys(doc! { "nextAttemptAt": 1u32})
+            .build();
+
+        self.database
+            .collection::<EmailJob>(email_job_collection)
+            .create_index(model, None)
+            .await
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// # Summary
+    ///
+    /// Create default indexes for the UserSummary collection.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_summary_collection` - A string slice that holds the name of the UserSummary collection.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), ConfigError>` - The ConfigError that occurred if the indexes could not be created.
+    pub async fn create_user_summary_indexes(
+        &self,
+        user_summary_collection: &str,
+    ) -> Result<(), ConfigError> {
+        info!("Creating indexes for the UserSummary collection");
+        let model = IndexModel::builder().keys(doc! { "username": 1u32}).build();
+
+        self.database
+            .collection::<UserSummary>(user_summary_collection)
             .create_index(model, None)
             .await
-            .expect("Creating an index should succeed");
+            .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
+
+        Ok(())
     }
 
     /// # Summary
@@ -519,14 +2183,14 @@ impl Config {
     /// * `expire_after` - A u64 that holds the TTL in seconds.
     /// * `audit_collection` - A string slice that holds the name of the Audit collection.
     ///
-    /// # Panics
+    /// # Returns
     ///
-    /// This method will panic if the index could not be created or deleted (unless Error Command code 27 applies).
+    /// * `Result<(), ConfigError>` - The ConfigError that occurred if the index could not be created or deleted (unless Error Command code 27 applies).
     pub async fn create_or_delete_audit_ttl_index(
         &self,
         expire_after: u64,
         audit_collection: &str,
-    ) {
+    ) -> Result<(), ConfigError> {
         if expire_after > 0 {
             info!("Creating TTL index for the Audit collection");
 
@@ -544,7 +2208,7 @@ impl Config {
                 .collection::<Audit>(audit_collection)
                 .create_index(model, None)
                 .await
-                .expect("Creating an index should succeed");
+                .map_err(|e| ConfigError::Database(format!("Failed to create index: {}", e)))?;
         } else {
             info!("Deleting TTL index for the Audit collection");
 
@@ -560,15 +2224,152 @@ impl Config {
                         if e.code == 27 {
                             info!("TTL index for the Audit collection does not exist");
                         } else {
-                            panic!("Failed to delete TTL index: {:?}", e);
+                            return Err(ConfigError::Database(format!(
+                                "Failed to delete TTL index: {}",
+                                e
+                            )));
                         }
                     }
                     _ => {
-                        panic!("Failed to delete TTL index: {:?}", e);
+                        return Err(ConfigError::Database(format!(
+                            "Failed to delete TTL index: {}",
+                            e
+                        )));
                     }
                 },
             }
         }
+
+        Ok(())
+    }
+
+    /// # Summary
+    ///
+    /// Apply a JSON Schema validator to a collection, so that malformed documents that are written by other
+    /// applications are rejected by the database. The collection is created if it does not exist yet. The `moderate`
+    /// validation level is used, so that existing documents that do not match the schema can still be updated.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection` - A string slice that holds the name of the collection.
+    /// * `schema` - The JSON Schema of the documents in the collection.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), ConfigError>` - The ConfigError that occurred if the validator could not be applied.
+    pub async fn apply_collection_validator(
+        &self,
+        collection: &str,
+        schema: Document,
+    ) -> Result<(), ConfigError> {
+        info!("Applying the validator of the {} collection", collection);
+
+        let command = doc! {
+            "collMod": collection,
+            "validator": { "$jsonSchema": schema.clone() },
+            "validationLevel": "moderate",
+            "validationAction": "error",
+        };
+
+        match self.database.run_command(command, None).await {
+            Ok(_) => Ok(()),
+            Err(e) => match e.kind.as_ref() {
+                // NamespaceNotFound
+                ErrorKind::Command(c) if c.code == 26 => {
+                    let options = CreateCollectionOptions::builder()
+                        .validator(doc! { "$jsonSchema": schema })
+                        .validation_level(ValidationLevel::Moderate)
+                        .validation_action(ValidationAction::Error)
+                        .build();
+
+                    self.database
+                        .create_collection(collection, options)
+                        .await
+                        .map_err(|e| {
+                            ConfigError::Database(format!("Failed to create collection: {}", e))
+                        })
+                }
+                _ => Err(ConfigError::Database(format!(
+                    "Failed to apply validator: {}",
+                    e
+                ))),
+            },
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Create the Role of a RoleTemplate if no Role with the same name exists yet. Existing Roles are left untouched, so
+    /// that changes that were made to a seeded Role are not reverted when the service restarts.
+    ///
+    /// # Arguments
+    ///
+    /// * `template` - The RoleTemplate.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Role, ConfigError>` - The Role instance or the ConfigError that occurred.
+    async fn create_role_template(&self, template: RoleTemplate) -> Result<Role, ConfigError> {
+        let mut permissions: Vec<String> = vec![];
+
+        for name in template.permissions() {
+            match self
+                .services
+                .permission_service
+                .find_by_name(name, &self.database)
+                .await
+            {
+                Ok(Some(p)) => permissions.push(p.id.to_hex()),
+                Ok(None) => warn!(
+                    "Permission {} of role template {} does not exist",
+                    name, template
+                ),
+                Err(e) => {
+                    return Err(ConfigError::Database(format!(
+                        "Failed to find permission {}: {}",
+                        name, e
+                    )))
+                }
+            }
+        }
+
+        self.find_or_create_role(
+            &template.to_string(),
+            Some(template.description().to_string()),
+            Some(permissions),
+        )
+        .await
+    }
+
+    /// # Summary
+    ///
+    /// Load all Permissions and global Roles into the in-process caches, so that the first requests after a start do
+    /// not pay for the round trips to the database.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), ConfigError>` - The ConfigError that occurred if the Permissions or Roles could not be loaded.
+    async fn warm_caches(&self) -> Result<(), ConfigError> {
+        if let Err(e) = self
+            .services
+            .permission_service
+            .warm_cache(&self.database)
+            .await
+        {
+            return Err(ConfigError::Database(format!(
+                "Failed to load permissions into the cache: {}",
+                e
+            )));
+        }
+
+        if let Err(e) = self.services.role_service.warm_cache(&self.database).await {
+            return Err(ConfigError::Database(format!(
+                "Failed to load roles into the cache: {}",
+                e
+            )));
+        }
+
+        Ok(())
     }
 
     /// # Summary
@@ -580,109 +2381,256 @@ impl Config {
     /// * `default_user_config` - A DefaultUserConfig instance that holds the default user configuration.
     /// * `email_regex` - A Regex instance that holds the email regex.
     ///
-    /// # Panics
+    /// # Returns
     ///
-    /// This method will panic if the email address is invalid, if the database connection is invalid or if permissions, roles or users could not be created.
+    /// * `Result<(), ConfigError>` - The ConfigError that occurred if the email address is invalid, if the database connection is invalid or if permissions, roles or users could not be created.
     pub async fn initialize_database(
         &self,
         default_user_config: DefaultUserConfig,
         email_regex: Regex,
-    ) {
+    ) -> Result<(), ConfigError> {
         let create_permission = self
             .find_or_create_permission(
                 "CAN_CREATE_PERMISSION",
                 Some("The ability to create permissions".to_string()),
             )
-            .await;
+            .await?;
         let read_permission = self
             .find_or_create_permission(
                 "CAN_READ_PERMISSION",
                 Some("The ability to read permissions".to_string()),
             )
-            .await;
+            .await?;
         let update_permission = self
             .find_or_create_permission(
                 "CAN_UPDATE_PERMISSION",
                 Some("The ability to update permissions".to_string()),
             )
-            .await;
+            .await?;
         let delete_permission = self
             .find_or_create_permission(
                 "CAN_DELETE_PERMISSION",
                 Some("The ability to delete permissions".to_string()),
             )
-            .await;
+            .await?;
 
         let create_role = self
             .find_or_create_permission(
                 "CAN_CREATE_ROLE",
                 Some("The ability to create roles".to_string()),
             )
-            .await;
+            .await?;
         let read_role = self
             .find_or_create_permission(
                 "CAN_READ_ROLE",
                 Some("The ability to read roles".to_string()),
             )
-            .await;
+            .await?;
         let update_role = self
             .find_or_create_permission(
                 "CAN_UPDATE_ROLE",
                 Some("The ability to update roles".to_string()),
             )
-            .await;
+            .await?;
         let delete_delete = self
             .find_or_create_permission(
                 "CAN_DELETE_ROLE",
                 Some("The ability to delete roles".to_string()),
             )
-            .await;
+            .await?;
 
         let create_user = self
             .find_or_create_permission(
                 "CAN_CREATE_USER",
                 Some("The ability to create users".to_string()),
             )
-            .await;
+            .await?;
         let read_user = self
             .find_or_create_permission(
                 "CAN_READ_USER",
                 Some("The ability to read users".to_string()),
             )
-            .await;
+            .await?;
         let update_user = self
             .find_or_create_permission(
                 "CAN_UPDATE_USER",
                 Some("The ability to update users".to_string()),
             )
-            .await;
+            .await?;
         let delete_user = self
             .find_or_create_permission(
                 "CAN_DELETE_USER",
                 Some("The ability to delete users".to_string()),
             )
-            .await;
+            .await?;
 
         let read_audit = self
             .find_or_create_permission(
                 "CAN_READ_AUDIT",
                 Some("The ability to read audits".to_string()),
             )
-            .await;
+            .await?;
+
+        let create_backup = self
+            .find_or_create_permission(
+                "CAN_CREATE_BACKUP",
+                Some("The ability to create backups".to_string()),
+            )
+            .await?;
+
+        let restore_backup = self
+            .find_or_create_permission(
+                "CAN_RESTORE_BACKUP",
+                Some("The ability to restore backups".to_string()),
+            )
+            .await?;
+
+        let read_route = self
+            .find_or_create_permission(
+                "CAN_READ_ROUTE",
+                Some("The ability to read the routes and their required permissions".to_string()),
+            )
+            .await?;
+
+        let run_self_check = self
+            .find_or_create_permission(
+                "CAN_RUN_SELFCHECK",
+                Some("The ability to run the self-check".to_string()),
+            )
+            .await?;
+
+        let read_metrics = self
+            .find_or_create_permission(
+                "CAN_READ_METRICS",
+                Some("The ability to read the database command metrics".to_string()),
+            )
+            .await?;
+
+        let purge_data = self
+            .find_or_create_permission(
+                "CAN_PURGE_DATA",
+                Some("The ability to purge expired records".to_string()),
+            )
+            .await?;
+
+        let rebuild_indexes = self
+            .find_or_create_permission(
+                "CAN_REBUILD_INDEXES",
+                Some("The ability to rebuild the database indexes".to_string()),
+            )
+            .await?;
+
+        let manage_maintenance = self
+            .find_or_create_permission(
+                "CAN_MANAGE_MAINTENANCE",
+                Some("The ability to enter and leave maintenance mode".to_string()),
+            )
+            .await?;
+
+        let create_client = self
+            .find_or_create_permission(
+                "CAN_CREATE_CLIENT",
+                Some("The ability to create OAuth clients".to_string()),
+            )
+            .await?;
+
+        let read_client = self
+            .find_or_create_permission(
+                "CAN_READ_CLIENT",
+                Some("The ability to read OAuth clients".to_string()),
+            )
+            .await?;
+
+        let update_client = self
+            .find_or_create_permission(
+                "CAN_UPDATE_CLIENT",
+                Some("The ability to update OAuth clients and rotate their secrets".to_string()),
+            )
+            .await?;
+
+        let delete_client = self
+            .find_or_create_permission(
+                "CAN_DELETE_CLIENT",
+                Some("The ability to delete OAuth clients".to_string()),
+            )
+            .await?;
+
+        let create_api_key = self
+            .find_or_create_permission(
+                "CAN_CREATE_API_KEY",
+                Some("The ability to create API keys".to_string()),
+            )
+            .await?;
+
+        let read_api_key = self
+            .find_or_create_permission(
+                "CAN_READ_API_KEY",
+                Some("The ability to read API keys".to_string()),
+            )
+            .await?;
+
+        let update_api_key = self
+            .find_or_create_permission(
+                "CAN_UPDATE_API_KEY",
+                Some("The ability to update API keys and rotate their keys".to_string()),
+            )
+            .await?;
+
+        let delete_api_key = self
+            .find_or_create_permission(
+                "CAN_DELETE_API_KEY",
+                Some("The ability to delete API keys".to_string()),
+            )
+            .await?;
+
+        let create_organization = self
+            .find_or_create_permission(
+                "CAN_CREATE_ORGANIZATION",
+                Some("The ability to create organizations".to_string()),
+            )
+            .await?;
+
+        let read_organization = self
+            .find_or_create_permission(
+                "CAN_READ_ORGANIZATION",
+                Some("The ability to read organizations".to_string()),
+            )
+            .await?;
+
+        let update_organization = self
+            .find_or_create_permission(
+                "CAN_UPDATE_ORGANIZATION",
+                Some("The ability to update organizations and their members".to_string()),
+            )
+            .await?;
+
+        let delete_organization = self
+            .find_or_create_permission(
+                "CAN_DELETE_ORGANIZATION",
+                Some("The ability to delete organizations".to_string()),
+            )
+            .await?;
+
+        let review_role_change = self
+            .find_or_create_permission(
+                "CAN_REVIEW_ROLE_CHANGE",
+                Some("The ability to approve or reject role changes of other users".to_string()),
+            )
+            .await?;
 
         let can_update_self = self
             .find_or_create_permission(
                 "CAN_UPDATE_SELF",
                 Some("The ability to update your own user".to_string()),
             )
-            .await;
+            .await?;
 
         let can_delete_self = self
             .find_or_create_permission(
                 "CAN_DELETE_SELF",
                 Some("The ability to delete your own user".to_string()),
             )
-            .await;
+            .await?;
 
         let admin_role = self
             .find_or_create_role(
@@ -702,9 +2650,30 @@ impl Config {
                     update_user.id.to_hex(),
                     delete_user.id.to_hex(),
                     read_audit.id.to_hex(),
+                    create_backup.id.to_hex(),
+                    restore_backup.id.to_hex(),
+                    read_route.id.to_hex(),
+                    run_self_check.id.to_hex(),
+                    read_metrics.id.to_hex(),
+                    purge_data.id.to_hex(),
+                    rebuild_indexes.id.to_hex(),
+                    manage_maintenance.id.to_hex(),
+                    create_client.id.to_hex(),
+                    read_client.id.to_hex(),
+                    update_client.id.to_hex(),
+                    delete_client.id.to_hex(),
+                    create_api_key.id.to_hex(),
+                    read_api_key.id.to_hex(),
+                    update_api_key.id.to_hex(),
+                    delete_api_key.id.to_hex(),
+                    create_organization.id.to_hex(),
+                    read_organization.id.to_hex(),
+                    update_organization.id.to_hex(),
+                    delete_organization.id.to_hex(),
+                    review_role_change.id.to_hex(),
                 ]),
             )
-            .await;
+            .await?;
 
         let default_role = self
             .find_or_create_role(
@@ -715,13 +2684,130 @@ impl Config {
                     can_delete_self.id.to_hex(),
                 ]),
             )
-            .await;
+            .await?;
 
         self.find_or_create_user(
-            default_user_config,
+            default_user_config.clone(),
             Some(vec![admin_role.id.to_hex(), default_role.id.to_hex()]),
             email_regex,
         )
-        .await;
+        .await?;
+
+        self.rotate_default_user(&default_user_config, admin_role.id)
+            .await
+    }
+
+    /// # Summary
+    ///
+    /// Limit the lifetime of the default user. The default user is deactivated once another active user holds the
+    /// administrator role, and is required to change its password for as long as it still has the configured password.
+    ///
+    /// # Arguments
+    ///
+    /// * `default_user_config` - The DefaultUserConfig.
+    /// * `admin_role_id` - The ID of the administrator role.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), ConfigError>` - The ConfigError that occurred if the default user could not be updated.
+    async fn rotate_default_user(
+        &self,
+        default_user_config: &DefaultUserConfig,
+        admin_role_id: ObjectId,
+    ) -> Result<(), ConfigError> {
+        if !default_user_config.require_password_change && !default_user_config.disable_after_admin
+        {
+            return Ok(());
+        }
+
+        let mut user = match self
+            .services
+            .user_service
+            .find_by_username(&default_user_config.username, &self.database)
+            .await
+        {
+            Ok(Some(u)) => u,
+            Ok(None) => return Ok(()),
+            Err(e) => return Err(ConfigError::Database(format!("Failed to find user: {}", e))),
+        };
+
+        let is_admin = user
+            .roles
+            .as_ref()
+            .is_some_and(|r| r.contains(&admin_role_id));
+
+        if default_user_config.disable_after_admin && is_admin && user.is_active() {
+            let admins = match self
+                .services
+                .user_service
+                .count_active_by_role(&admin_role_id.to_hex(), &self.database)
+                .await
+            {
+                Ok(c) => c,
+                Err(e) => {
+                    return Err(ConfigError::Database(format!(
+                        "Failed to count administrators: {}",
+                        e
+                    )))
+                }
+            };
+
+            if admins > 1 {
+                info!(
+                    "Deactivating the default user now that another administrator exists: {}",
+                    user.username
+                );
+
+                user.status = UserStatus::Deactivated;
+                return match self
+                    .services
+                    .user_service
+                    .update(user, None, &self.database, &self.services.audit_service)
+                    .await
+                {
+                    Ok(_) => Ok(()),
+                    Err(e) => Err(ConfigError::Initialization(format!(
+                        "Failed to deactivate the default user: {}",
+                        e
+                    ))),
+                };
+            }
+        }
+
+        if !default_user_config.require_password_change || user.must_change_password {
+            return Ok(());
+        }
+
+        match self
+            .services
+            .password_service
+            .verify_password(&default_user_config.password, &user.password)
+        {
+            Ok(true) => {}
+            Ok(false) => return Ok(()),
+            Err(e) => {
+                return Err(ConfigError::Initialization(format!(
+                    "Failed to verify the password of the default user: {}",
+                    e
+                )))
+            }
+        }
+
+        info!(
+            "Requiring the default user to change its password: {}",
+            user.username
+        );
+        match self
+            .services
+            .user_service
+            .require_password_change(&user.id.to_hex(), &self.database)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(ConfigError::Initialization(format!(
+                "Failed to require the default user to change its password: {}",
+                e
+            ))),
+        }
     }
 }
@@ -0,0 +1,68 @@
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("{0} is required but was not specified")]
+    MissingVariable(String),
+    #[error("{variable} is invalid: {reason}")]
+    InvalidVariable { variable: String, reason: String },
+    #[error("Database error: {0}")]
+    Database(String),
+    #[error("Initialization error: {0}")]
+    Initialization(String),
+}
+
+impl ConfigError {
+    /// # Summary
+    ///
+    /// Create a new ConfigError for an environment variable that holds an invalid value.
+    ///
+    /// # Arguments
+    ///
+    /// * `variable` - The name of the environment variable.
+    /// * `reason` - The reason why the value is invalid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let error = ConfigError::invalid("SERVER_PORT", "must be a number");
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `ConfigError` - The new ConfigError.
+    pub fn invalid(variable: &str, reason: &str) -> ConfigError {
+        ConfigError::InvalidVariable {
+            variable: variable.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Create a human-readable report of every configuration issue.
+    ///
+    /// # Arguments
+    ///
+    /// * `errors` - The ConfigErrors that occurred.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// eprintln!("{}", ConfigError::report(&errors));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The report.
+    pub fn report(errors: &[ConfigError]) -> String {
+        let mut report = format!(
+            "Failed to start auth-rs, {} configuration issue(s) found:",
+            errors.len()
+        );
+
+        for e in errors {
+            report.push_str(&format!("\n  - {}", e));
+        }
+
+        report
+    }
+}
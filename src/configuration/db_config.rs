@@ -1,6 +1,11 @@
+use crate::configuration::config_error::ConfigError;
+use mongodb::options::{
+    Acknowledgment, ReadConcern, ReadPreference, ReadPreferenceOptions, WriteConcern,
+};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Default)]
 pub struct DbConfig {
     pub connection_string: String,
     pub database_name: String,
@@ -8,9 +13,35 @@ pub struct DbConfig {
     pub role_collection: String,
     pub user_collection: String,
     pub audit_collection: String,
+    pub device_collection: String,
+    pub client_collection: String,
+    pub consent_collection: String,
+    pub organization_collection: String,
+    pub invitation_collection: String,
+    pub refresh_token_collection: String,
+    pub revoked_token_collection: String,
+    pub email_job_collection: String,
+    pub user_summary_collection: String,
+    pub session_collection: String,
+    pub api_key_collection: String,
+    pub authorization_code_collection: String,
+    pub role_change_collection: String,
+    pub credential_collection: String,
+    pub credential_ceremony_collection: String,
+    pub magic_link_collection: String,
     pub create_indexes: bool,
+    pub create_validators: bool,
     pub audit_enabled: bool,
     pub audit_ttl: u64,
+    pub slow_query_threshold: u64,
+    pub health_check_interval: u64,
+    pub read_preference: Option<String>,
+    pub read_concern: Option<String>,
+    pub write_concern: Option<String>,
+    pub audit_write_concern: Option<String>,
+    pub audit_chaining: bool,
+    pub command_monitoring: bool,
+    pub tenant_connections: HashMap<String, String>,
 }
 
 impl DbConfig {
@@ -26,9 +57,35 @@ impl DbConfig {
     /// * `role_collection` - A String that holds the role collection name.
     /// * `user_collection` - A String that holds the user collection name.
     /// * `audit_collection` - A String that holds the audit collection name.
+    /// * `device_collection` - A String that holds the device authorization collection name.
+    /// * `client_collection` - A String that holds the OAuth client collection name.
+    /// * `consent_collection` - A String that holds the consent collection name.
+    /// * `organization_collection` - A String that holds the organization collection name.
+    /// * `invitation_collection` - A String that holds the organization invitation collection name.
+    /// * `refresh_token_collection` - A String that holds the refresh token collection name.
+    /// * `revoked_token_collection` - A String that holds the revoked token collection name.
+    /// * `email_job_collection` - A String that holds the queued email collection name.
+    /// * `user_summary_collection` - A String that holds the denormalized user summary collection name.
+    /// * `session_collection` - A String that holds the server-side session collection name.
+    /// * `api_key_collection` - A String that holds the API key collection name.
+    /// * `authorization_code_collection` - A String that holds the OAuth authorization code collection name.
+    /// * `role_change_collection` - A String that holds the pending role change collection name.
+    /// * `credential_collection` - A String that holds the passkey credential collection name.
+    /// * `credential_ceremony_collection` - A String that holds the ongoing WebAuthn ceremony collection name.
+    /// * `magic_link_collection` - A String that holds the magic link collection name.
     /// * `create_indexes` - A bool that indicates whether to create indexes or not.
+    /// * `create_validators` - A bool that indicates whether to apply JSON Schema validators to the collections or not.
     /// * `audit_enabled` - A bool that indicates whether auditing is enabled or not.
     /// * `audit_ttl` - A u64 that holds the audit TTL.
+    /// * `slow_query_threshold` - A u64 that holds the amount of milliseconds after which a database operation is logged as slow, or zero to disable slow query logging.
+    /// * `health_check_interval` - A u64 that holds the amount of seconds between two database health checks, or zero to disable the health checks.
+    /// * `read_preference` - An optional String that holds the read preference.
+    /// * `read_concern` - An optional String that holds the read concern level.
+    /// * `write_concern` - An optional String that holds the write concern.
+    /// * `audit_write_concern` - An optional String that holds the write concern that is used for audit writes.
+    /// * `audit_chaining` - A bool that indicates whether every audit record stores the hash of the previous record of its partition.
+    /// * `command_monitoring` - A bool that indicates whether the duration of every database command is recorded.
+    /// * `tenant_connections` - A HashMap that holds the connection strings of the Databases that the data of Organizations is routed to, by the ID of the Organization.
     ///
     /// # Returns
     ///
@@ -41,9 +98,35 @@ impl DbConfig {
         role_collection: String,
         user_collection: String,
         audit_collection: String,
+        device_collection: String,
+        client_collection: String,
+        consent_collection: String,
+        organization_collection: String,
+        invitation_collection: String,
+        refresh_token_collection: String,
+        revoked_token_collection: String,
+        email_job_collection: String,
+        user_summary_collection: String,
+        session_collection: String,
+        api_key_collection: String,
+        authorization_code_collection: String,
+        role_change_collection: String,
+        credential_collection: String,
+        credential_ceremony_collection: String,
+        magic_link_collection: String,
         create_indexes: bool,
+        create_validators: bool,
         audit_enabled: bool,
         audit_ttl: u64,
+        slow_query_threshold: u64,
+        health_check_interval: u64,
+        read_preference: Option<String>,
+        read_concern: Option<String>,
+        write_concern: Option<String>,
+        audit_write_concern: Option<String>,
+        audit_chaining: bool,
+        command_monitoring: bool,
+        tenant_connections: HashMap<String, String>,
     ) -> DbConfig {
         DbConfig {
             connection_string,
@@ -52,9 +135,147 @@ impl DbConfig {
             role_collection,
             user_collection,
             audit_collection,
+            device_collection,
+            client_collection,
+            consent_collection,
+            organization_collection,
+            invitation_collection,
+            refresh_token_collection,
+            revoked_token_collection,
+            email_job_collection,
+            user_summary_collection,
+            session_collection,
+            api_key_collection,
+            authorization_code_collection,
+            role_change_collection,
+            credential_collection,
+            credential_ceremony_collection,
+            magic_link_collection,
             create_indexes,
+            create_validators,
             audit_enabled,
             audit_ttl,
+            slow_query_threshold,
+            health_check_interval,
+            read_preference,
+            read_concern,
+            write_concern,
+            audit_write_concern,
+            audit_chaining,
+            command_monitoring,
+            tenant_connections,
         }
     }
+
+    /// # Summary
+    ///
+    /// Parse the configured read preference.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<ReadPreference>, ConfigError>` - The ReadPreference, None if it was not configured, or the ConfigError if it is invalid.
+    pub fn parse_read_preference(&self) -> Result<Option<ReadPreference>, ConfigError> {
+        let read_preference = match &self.read_preference {
+            Some(d) => d,
+            None => return Ok(None),
+        };
+
+        let options = ReadPreferenceOptions::default();
+        match read_preference.as_str() {
+            "primary" => Ok(Some(ReadPreference::Primary)),
+            "primaryPreferred" => Ok(Some(ReadPreference::PrimaryPreferred { options })),
+            "secondary" => Ok(Some(ReadPreference::Secondary { options })),
+            "secondaryPreferred" => Ok(Some(ReadPreference::SecondaryPreferred { options })),
+            "nearest" => Ok(Some(ReadPreference::Nearest { options })),
+            _ => Err(ConfigError::invalid(
+                "DB_READ_PREFERENCE",
+                &format!("'{}' is not a valid read preference", read_preference),
+            )),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Parse the configured read concern.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<ReadConcern>, ConfigError>` - The ReadConcern, None if it was not configured, or the ConfigError if it is invalid.
+    pub fn parse_read_concern(&self) -> Result<Option<ReadConcern>, ConfigError> {
+        let read_concern = match &self.read_concern {
+            Some(d) => d,
+            None => return Ok(None),
+        };
+
+        match read_concern.as_str() {
+            "local" => Ok(Some(ReadConcern::local())),
+            "majority" => Ok(Some(ReadConcern::majority())),
+            "linearizable" => Ok(Some(ReadConcern::linearizable())),
+            "available" => Ok(Some(ReadConcern::available())),
+            "snapshot" => Ok(Some(ReadConcern::snapshot())),
+            _ => Err(ConfigError::invalid(
+                "DB_READ_CONCERN",
+                &format!("'{}' is not a valid read concern", read_concern),
+            )),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Parse the configured write concern.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<WriteConcern>, ConfigError>` - The WriteConcern, None if it was not configured, or the ConfigError if it is invalid.
+    pub fn parse_write_concern(&self) -> Result<Option<WriteConcern>, ConfigError> {
+        Self::write_concern_from("DB_WRITE_CONCERN", &self.write_concern)
+    }
+
+    /// # Summary
+    ///
+    /// Parse the configured write concern for audit writes.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<WriteConcern>, ConfigError>` - The WriteConcern, None if it was not configured, or the ConfigError if it is invalid.
+    pub fn parse_audit_write_concern(&self) -> Result<Option<WriteConcern>, ConfigError> {
+        Self::write_concern_from("DB_AUDIT_WRITE_CONCERN", &self.audit_write_concern)
+    }
+
+    /// # Summary
+    ///
+    /// Parse a write concern, which is either `majority` or the amount of nodes that must acknowledge a write.
+    ///
+    /// # Arguments
+    ///
+    /// * `variable` - The name of the environment variable that holds the write concern.
+    /// * `write_concern` - The write concern to parse.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<WriteConcern>, ConfigError>` - The WriteConcern, None if it was not configured, or the ConfigError if it is invalid.
+    fn write_concern_from(
+        variable: &str,
+        write_concern: &Option<String>,
+    ) -> Result<Option<WriteConcern>, ConfigError> {
+        let write_concern = match write_concern {
+            Some(d) => d,
+            None => return Ok(None),
+        };
+
+        let acknowledgment = match write_concern.as_str() {
+            "majority" => Acknowledgment::Majority,
+            w => match w.parse::<u32>() {
+                Ok(n) => Acknowledgment::Nodes(n),
+                Err(_) => {
+                    return Err(ConfigError::invalid(
+                        variable,
+                        &format!("'{}' is not a valid write concern", write_concern),
+                    ))
+                }
+            },
+        };
+
+        Ok(Some(WriteConcern::builder().w(acknowledgment).build()))
+    }
 }
@@ -4,6 +4,8 @@ pub struct DefaultUserConfig {
     pub email: Option<String>,
     pub password: String,
     pub enabled: bool,
+    pub require_password_change: bool,
+    pub disable_after_admin: bool,
 }
 
 impl DefaultUserConfig {
@@ -17,6 +19,8 @@ impl DefaultUserConfig {
     /// * `email` - A String that holds the default email.
     /// * `password` - A String that holds the default password.
     /// * `enabled` - A bool that holds the default enabled value.
+    /// * `require_password_change` - A bool that indicates whether the default user must change its password while it still has the configured password.
+    /// * `disable_after_admin` - A bool that indicates whether the default user is deactivated once another active user holds the administrator role.
     ///
     /// # Returns
     ///
@@ -26,12 +30,16 @@ impl DefaultUserConfig {
         email: Option<String>,
         password: String,
         enabled: bool,
+        require_password_change: bool,
+        disable_after_admin: bool,
     ) -> DefaultUserConfig {
         DefaultUserConfig {
             username,
             email,
             password,
             enabled,
+            require_password_change,
+            disable_after_admin,
         }
     }
 }
@@ -0,0 +1,35 @@
+#[derive(Clone)]
+pub struct DeviceConfig {
+    pub verification_uri: String,
+    pub expiration: u64,
+    pub interval: u64,
+}
+
+impl DeviceConfig {
+    /// # Summary
+    ///
+    /// Create a new DeviceConfig.
+    ///
+    /// # Arguments
+    ///
+    /// * `verification_uri` - The URI at which users can approve a device.
+    /// * `expiration` - The amount of seconds after which a device code expires.
+    /// * `interval` - The minimum amount of seconds that a device must wait between two token requests.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let device_config = DeviceConfig::new(String::from("https://opserva.io/device"), 600, 5);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `DeviceConfig` - The new DeviceConfig.
+    pub fn new(verification_uri: String, expiration: u64, interval: u64) -> DeviceConfig {
+        DeviceConfig {
+            verification_uri,
+            expiration,
+            interval,
+        }
+    }
+}
@@ -0,0 +1,35 @@
+#[derive(Clone)]
+pub struct EmailConfig {
+    pub strip_plus_tags: bool,
+    pub gravatar: bool,
+    pub hash_key: Option<String>,
+}
+
+impl EmailConfig {
+    /// # Summary
+    ///
+    /// Create a new EmailConfig.
+    ///
+    /// # Arguments
+    ///
+    /// * `strip_plus_tags` - Whether plus-tags (`user+tag@example.com`) should be removed when an email address is normalized.
+    /// * `gravatar` - Whether a Gravatar URL that is derived from the email address should be used as the avatar of a User.
+    /// * `hash_key` - The key of the keyed email hashes that allow looking up Users without relying on the plaintext email address.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let email_config = EmailConfig::new(false, true, None);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `EmailConfig` - The new EmailConfig.
+    pub fn new(strip_plus_tags: bool, gravatar: bool, hash_key: Option<String>) -> EmailConfig {
+        EmailConfig {
+            strip_plus_tags,
+            gravatar,
+            hash_key,
+        }
+    }
+}
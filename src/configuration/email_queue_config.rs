@@ -0,0 +1,75 @@
+#[derive(Clone)]
+pub struct EmailQueueConfig {
+    pub interval: u64,
+    pub rate_limit: u32,
+    pub max_attempts: u32,
+    pub retry_delay: u64,
+    pub welcome_subject: String,
+    pub welcome_body: String,
+}
+
+impl EmailQueueConfig {
+    /// # Summary
+    ///
+    /// Create a new EmailQueueConfig.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - The interval in seconds at which queued emails are sent, or zero to disable the queue.
+    /// * `rate_limit` - The maximum amount of emails that are sent per interval.
+    /// * `max_attempts` - The amount of attempts after which an email that cannot be sent is dropped.
+    /// * `retry_delay` - The delay in seconds before the first retry of a failed email. The delay doubles with every attempt.
+    /// * `welcome_subject` - The subject of the welcome email. `{username}` is replaced by the username of the new User.
+    /// * `welcome_body` - The body of the welcome email. `{username}` is replaced by the username of the new User.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let email_queue_config = EmailQueueConfig::new(10, 20, 5, 60, String::from("Welcome"), String::from("Hello {username}"));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `EmailQueueConfig` - The new EmailQueueConfig.
+    pub fn new(
+        interval: u64,
+        rate_limit: u32,
+        max_attempts: u32,
+        retry_delay: u64,
+        welcome_subject: String,
+        welcome_body: String,
+    ) -> EmailQueueConfig {
+        EmailQueueConfig {
+            interval,
+            rate_limit,
+            max_attempts,
+            retry_delay,
+            welcome_subject,
+            welcome_body,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Render the subject and the body of the welcome email for a User.
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - The username of the new User.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let (subject, body) = email_queue_config.welcome_email("admin");
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `(String, String)` - The subject and the body of the welcome email.
+    pub fn welcome_email(&self, username: &str) -> (String, String) {
+        (
+            self.welcome_subject.replace("{username}", username),
+            self.welcome_body.replace("{username}", username),
+        )
+    }
+}
@@ -0,0 +1,66 @@
+#[derive(Clone)]
+pub struct ExportConfig {
+    pub endpoint: Option<String>,
+    pub bucket: String,
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub prefix: String,
+    pub interval: u64,
+}
+
+impl ExportConfig {
+    /// # Summary
+    ///
+    /// Create a new ExportConfig.
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoint` - The URL of the S3-compatible storage, such as `https://s3.eu-west-1.amazonaws.com`, or None to disable the export.
+    /// * `bucket` - The bucket that the exports are written to.
+    /// * `region` - The region of the bucket, which is part of the request signature.
+    /// * `access_key_id` - The ID of the access key that is used to sign the requests.
+    /// * `secret_access_key` - The secret access key that is used to sign the requests.
+    /// * `prefix` - The prefix of the keys of the exported objects.
+    /// * `interval` - The interval in seconds at which the Audits and Users are exported, or zero to disable the export.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let export_config = ExportConfig::new(Some(String::from("https://s3.eu-west-1.amazonaws.com")), String::from("archive"), String::from("eu-west-1"), String::from("AKIA..."), String::from("secret"), String::from("auth-rs/"), 86400);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `ExportConfig` - The new ExportConfig.
+    pub fn new(
+        endpoint: Option<String>,
+        bucket: String,
+        region: String,
+        access_key_id: String,
+        secret_access_key: String,
+        prefix: String,
+        interval: u64,
+    ) -> ExportConfig {
+        ExportConfig {
+            endpoint,
+            bucket,
+            region,
+            access_key_id,
+            secret_access_key,
+            prefix,
+            interval,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check whether the export is enabled.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if an endpoint and an interval were configured.
+    pub fn is_enabled(&self) -> bool {
+        self.endpoint.is_some() && self.interval > 0
+    }
+}
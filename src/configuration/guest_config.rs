@@ -0,0 +1,44 @@
+#[derive(Clone)]
+pub struct GuestConfig {
+    pub enabled: bool,
+    pub permissions: Vec<String>,
+    pub expiration: usize,
+}
+
+impl GuestConfig {
+    /// # Summary
+    ///
+    /// Create a new GuestConfig.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether anonymous visitors can obtain a guest token.
+    /// * `permissions` - The names of the permissions that are granted to guest tokens.
+    /// * `expiration` - The amount of seconds after which a guest token expires.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let guest_config = GuestConfig::new(true, vec![String::from("CAN_READ_PERMISSION")], 900);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `GuestConfig` - The new GuestConfig.
+    pub fn new(enabled: bool, permissions: Vec<String>, expiration: usize) -> GuestConfig {
+        let mut permissions: Vec<String> = permissions
+            .iter()
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty())
+            .collect();
+
+        permissions.sort();
+        permissions.dedup();
+
+        GuestConfig {
+            enabled,
+            permissions,
+            expiration,
+        }
+    }
+}
@@ -0,0 +1,88 @@
+#[derive(Clone)]
+pub struct HealthConfig {
+    pub token: Option<String>,
+    pub address: String,
+    pub port: u16,
+}
+
+impl HealthConfig {
+    /// # Summary
+    ///
+    /// Create a new HealthConfig.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The static bearer token that is required to call the health endpoints, or None to allow anonymous calls.
+    /// * `address` - The address of the internal listener that serves the health endpoints.
+    /// * `port` - The port of the internal listener that serves the health endpoints, or zero to serve them on the main listener.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let health_config = HealthConfig::new(None, String::from("127.0.0.1"), 0);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `HealthConfig` - The new HealthConfig.
+    pub fn new(token: Option<String>, address: String, port: u16) -> HealthConfig {
+        HealthConfig {
+            token,
+            address,
+            port,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check whether the health endpoints are served on a separate internal listener.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if a port was configured for the internal listener.
+    pub fn has_internal_listener(&self) -> bool {
+        self.port > 0
+    }
+
+    /// # Summary
+    ///
+    /// Check whether a caller may call the health endpoints. The token is compared in constant time, so that it cannot be
+    /// guessed by measuring the response times.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The bearer token that was sent by the caller, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// if !config.health_config.is_authorized(Some("token")) {
+    ///     return HttpResponse::Unauthorized().finish();
+    /// }
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if no token is required or if the token matches the configured token.
+    pub fn is_authorized(&self, token: Option<&str>) -> bool {
+        let expected = match &self.token {
+            Some(t) => t.as_bytes(),
+            None => return true,
+        };
+
+        let actual = match token {
+            Some(t) => t.as_bytes(),
+            None => return false,
+        };
+
+        if expected.len() != actual.len() {
+            return false;
+        }
+
+        expected
+            .iter()
+            .zip(actual)
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+    }
+}
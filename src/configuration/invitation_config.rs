@@ -0,0 +1,27 @@
+#[derive(Clone)]
+pub struct InvitationConfig {
+    pub expiration: u64,
+}
+
+impl InvitationConfig {
+    /// # Summary
+    ///
+    /// Create a new InvitationConfig.
+    ///
+    /// # Arguments
+    ///
+    /// * `expiration` - The amount of seconds after which an invitation to an organization expires.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let invitation_config = InvitationConfig::new(604800);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `InvitationConfig` - The new InvitationConfig.
+    pub fn new(expiration: u64) -> InvitationConfig {
+        InvitationConfig { expiration }
+    }
+}
@@ -1,32 +1,236 @@
+use chrono::{DateTime, Utc};
+use jsonwebtoken::Algorithm;
+use serde::Deserialize;
+use std::str::FromStr;
+
+#[derive(Clone, Deserialize)]
+pub struct PreviousJwtKey {
+    #[serde(default)]
+    pub algorithm: Option<Algorithm>,
+    #[serde(default)]
+    pub secret: Option<String>,
+    #[serde(default, rename = "publicKey")]
+    pub public_key: Option<String>,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenMode {
+    Jwt,
+    Session,
+}
+
+impl FromStr for TokenMode {
+    type Err = String;
+
+    /// # Summary
+    ///
+    /// Parse a TokenMode.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The mode, either `jwt` or `session`.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<TokenMode, String>` - The TokenMode, or an error message if the mode is unknown.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "jwt" => Ok(TokenMode::Jwt),
+            "session" => Ok(TokenMode::Session),
+            _ => Err(format!("Unknown token mode: {}", s)),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct JwtConfig {
     pub jwt_secret: String,
     pub jwt_expiration: usize,
+    pub jwt_leeway: u64,
+    pub jwt_refresh_expiration: u64,
+    pub jwt_algorithm: Algorithm,
+    pub jwt_private_key: Option<String>,
+    pub jwt_public_key: Option<String>,
+    pub jwt_previous_keys: Vec<PreviousJwtKey>,
+    pub jwt_issuer: Option<String>,
+    pub jwt_audience: Option<String>,
+    pub token_mode: TokenMode,
 }
 
 impl JwtConfig {
     /// # Summary
     ///
-    /// Create a new JwtConfig.
+    /// Create a new JwtConfig that signs tokens with an HMAC secret using HS256.
     ///
     /// # Arguments
     ///
     /// * `jwt_secret` - The secret to use for signing and verifying JWTs.
     /// * `jwt_expiration` - The expiration time in seconds.
+    /// * `jwt_leeway` - The amount of seconds of clock skew that is tolerated when validating the `exp` and `nbf` claims.
+    /// * `jwt_refresh_expiration` - The amount of seconds after which a refresh token expires, or zero to disable refresh tokens.
     ///
     /// # Example
     ///
     /// ```
-    /// let jwt_config = JwtConfig::new(String::from("secret"), 3600);
+    /// let jwt_config = JwtConfig::new(String::from("secret"), 3600, 60, 1209600);
     /// ```
     ///
     /// # Returns
     ///
     /// * `JwtConfig` - The new JwtConfig.
-    pub fn new(jwt_secret: String, jwt_expiration: usize) -> JwtConfig {
+    pub fn new(
+        jwt_secret: String,
+        jwt_expiration: usize,
+        jwt_leeway: u64,
+        jwt_refresh_expiration: u64,
+    ) -> JwtConfig {
         JwtConfig {
             jwt_secret,
             jwt_expiration,
+            jwt_leeway,
+            jwt_refresh_expiration,
+            jwt_algorithm: Algorithm::HS256,
+            jwt_private_key: None,
+            jwt_public_key: None,
+            jwt_previous_keys: vec![],
+            jwt_issuer: None,
+            jwt_audience: None,
+            token_mode: TokenMode::Jwt,
         }
     }
+
+    /// # Summary
+    ///
+    /// Sign tokens with an asymmetric key pair instead of the HMAC secret. Without a private key, tokens can only be
+    /// verified, which allows other services to validate tokens with nothing but the public key.
+    ///
+    /// # Arguments
+    ///
+    /// * `jwt_algorithm` - The RSA (`RS*`, `PS*`), ECDSA (`ES*`) or EdDSA algorithm.
+    /// * `jwt_private_key` - The PEM-encoded private key, if tokens should be signed.
+    /// * `jwt_public_key` - The PEM-encoded public key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let jwt_config = JwtConfig::new(String::new(), 3600, 60, 0).with_key_pair(Algorithm::ES256, None, public_key);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `JwtConfig` - The JwtConfig with the key pair.
+    pub fn with_key_pair(
+        mut self,
+        jwt_algorithm: Algorithm,
+        jwt_private_key: Option<String>,
+        jwt_public_key: String,
+    ) -> JwtConfig {
+        self.jwt_algorithm = jwt_algorithm;
+        self.jwt_private_key = jwt_private_key;
+        self.jwt_public_key = Some(jwt_public_key);
+        self
+    }
+
+    /// # Summary
+    ///
+    /// Keep accepting tokens that were signed with previous keys after the signing key was rotated. Tokens that are signed
+    /// with a previous key are rejected once its grace period ends, while new tokens are always signed with the current key.
+    ///
+    /// # Arguments
+    ///
+    /// * `jwt_previous_keys` - The previous HMAC secrets or public keys, with the end of their grace periods.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let jwt_config = JwtConfig::new(String::from("secret"), 3600, 60, 0).with_previous_keys(previous_keys);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `JwtConfig` - The JwtConfig with the previous keys.
+    pub fn with_previous_keys(mut self, jwt_previous_keys: Vec<PreviousJwtKey>) -> JwtConfig {
+        self.jwt_previous_keys = jwt_previous_keys;
+        self
+    }
+
+    /// # Summary
+    ///
+    /// Set the `iss` and `aud` claims of every token that is issued and reject tokens of which the claims do not match.
+    /// This prevents tokens that were issued by another environment that shares the signing key from being accepted.
+    ///
+    /// # Arguments
+    ///
+    /// * `jwt_issuer` - The issuer of the tokens, or None to neither set nor validate the `iss` claim.
+    /// * `jwt_audience` - The audience of the tokens, or None to neither set nor validate the `aud` claim.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let jwt_config = JwtConfig::new(String::from("secret"), 3600, 60, 0)
+    ///     .with_issuer_and_audience(Some(String::from("https://auth.opserva.io")), Some(String::from("opserva-api")));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `JwtConfig` - The JwtConfig with the issuer and audience.
+    pub fn with_issuer_and_audience(
+        mut self,
+        jwt_issuer: Option<String>,
+        jwt_audience: Option<String>,
+    ) -> JwtConfig {
+        self.jwt_issuer = jwt_issuer;
+        self.jwt_audience = jwt_audience;
+        self
+    }
+
+    /// # Summary
+    ///
+    /// Choose whether clients receive the signed JWTs themselves or opaque session tokens. Session tokens refer to a
+    /// server-side session that holds the JWT, so that they can be revoked immediately and reveal no claims to clients.
+    ///
+    /// # Arguments
+    ///
+    /// * `token_mode` - The TokenMode.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let jwt_config = JwtConfig::new(String::from("secret"), 3600, 60, 0).with_token_mode(TokenMode::Session);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `JwtConfig` - The JwtConfig with the TokenMode.
+    pub fn with_token_mode(mut self, token_mode: TokenMode) -> JwtConfig {
+        self.token_mode = token_mode;
+        self
+    }
+
+    /// # Summary
+    ///
+    /// Check whether clients receive opaque session tokens instead of JWTs.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the TokenMode is Session.
+    pub fn is_session_mode(&self) -> bool {
+        self.token_mode == TokenMode::Session
+    }
+
+    /// # Summary
+    ///
+    /// Check whether tokens are signed with an asymmetric key pair.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the algorithm is not an HMAC algorithm, false otherwise.
+    pub fn is_asymmetric(&self) -> bool {
+        !matches!(
+            self.jwt_algorithm,
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+        )
+    }
 }
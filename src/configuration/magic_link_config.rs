@@ -0,0 +1,87 @@
+#[derive(Clone)]
+pub struct MagicLinkConfig {
+    pub url: Option<String>,
+    pub expiration: u64,
+    pub subject: String,
+    pub body: String,
+}
+
+impl MagicLinkConfig {
+    /// # Summary
+    ///
+    /// Create a new MagicLinkConfig.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL of the page that completes a magic link login, in which `{token}` is replaced by the magic link token, or None to disable magic link logins.
+    /// * `expiration` - The amount of seconds after which a magic link expires.
+    /// * `subject` - The subject of the magic link email. `{username}` is replaced by the username of the User.
+    /// * `body` - The body of the magic link email. `{username}` is replaced by the username of the User and `{link}` by the magic link.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let magic_link_config = MagicLinkConfig::new(Some(String::from("https://app.opserva.io/login?token={token}")), 900, String::from("Your login link"), String::from("Hello {username}, {link}"));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `MagicLinkConfig` - The new MagicLinkConfig.
+    pub fn new(
+        url: Option<String>,
+        expiration: u64,
+        subject: String,
+        body: String,
+    ) -> MagicLinkConfig {
+        MagicLinkConfig {
+            url,
+            expiration,
+            subject,
+            body,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check whether magic link logins are enabled.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if a magic link URL is configured, false otherwise.
+    pub fn is_enabled(&self) -> bool {
+        self.url.is_some()
+    }
+
+    /// # Summary
+    ///
+    /// Render the subject and the body of the magic link email for a User.
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - The username of the User.
+    /// * `token` - The magic link token.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let (subject, body) = magic_link_config.magic_link_email("admin", "token");
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `(String, String)` - The subject and the body of the magic link email.
+    pub fn magic_link_email(&self, username: &str, token: &str) -> (String, String) {
+        let link = self
+            .url
+            .as_deref()
+            .unwrap_or_default()
+            .replace("{token}", token);
+
+        (
+            self.subject.replace("{username}", username),
+            self.body
+                .replace("{username}", username)
+                .replace("{link}", &link),
+        )
+    }
+}
@@ -0,0 +1,35 @@
+#[derive(Clone)]
+pub struct MfaConfig {
+    pub secret: Option<String>,
+    pub issuer: String,
+    pub challenge_expiration: u64,
+}
+
+impl MfaConfig {
+    /// # Summary
+    ///
+    /// Create a new MfaConfig.
+    ///
+    /// # Arguments
+    ///
+    /// * `secret` - The secret from which the key that encrypts TOTP secrets is derived, or None to disable TOTP.
+    /// * `issuer` - The name of the issuer that is shown in authenticator apps.
+    /// * `challenge_expiration` - The number of seconds after which an MFA challenge token expires.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mfa_config = MfaConfig::new(Some(String::from("secret")), String::from("auth-rs"), 300);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `MfaConfig` - The new MfaConfig.
+    pub fn new(secret: Option<String>, issuer: String, challenge_expiration: u64) -> MfaConfig {
+        MfaConfig {
+            secret,
+            issuer,
+            challenge_expiration,
+        }
+    }
+}
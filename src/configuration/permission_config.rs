@@ -0,0 +1,105 @@
+use crate::web::controller::ROUTE_PERMISSIONS;
+use std::collections::HashMap;
+
+#[derive(Clone)]
+pub struct PermissionConfig {
+    pub overrides: HashMap<String, Option<String>>,
+    pub verbose_errors: bool,
+    pub include_dependencies: bool,
+}
+
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Invalid route: {0}")]
+    InvalidRoute(String),
+    #[error("Unknown route: {0}")]
+    UnknownRoute(String),
+}
+
+impl PermissionConfig {
+    /// # Summary
+    ///
+    /// Create a new PermissionConfig.
+    ///
+    /// # Arguments
+    ///
+    /// * `overrides` - A map of routes (`METHOD /path`) to the permission they should require instead of their default. A `None` value removes the permission requirement.
+    /// * `verbose_errors` - Whether permission-denied responses should include the permission that was required.
+    /// * `include_dependencies` - Whether the dependencies of the permissions of a role are added to the role automatically, instead of rejecting roles that lack them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut overrides = HashMap::new();
+    /// overrides.insert(String::from("GET /api/v1/roles/"), Some(String::from("CAN_LIST_ROLES")));
+    ///
+    /// let permission_config = PermissionConfig::new(overrides, false, true);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<PermissionConfig, Error>` - The new PermissionConfig or the Error that occurred.
+    pub fn new(
+        overrides: HashMap<String, Option<String>>,
+        verbose_errors: bool,
+        include_dependencies: bool,
+    ) -> Result<PermissionConfig, Error> {
+        let mut normalized: HashMap<String, Option<String>> = HashMap::new();
+
+        for (route, permission) in overrides {
+            let (method, path) = match route.trim().split_once(' ') {
+                Some((m, p)) => (m.trim().to_uppercase(), p.trim().to_string()),
+                None => return Err(Error::InvalidRoute(route)),
+            };
+
+            if !ROUTE_PERMISSIONS
+                .iter()
+                .any(|r| r.method == method && r.path == path)
+            {
+                return Err(Error::UnknownRoute(route));
+            }
+
+            let permission = match permission {
+                Some(p) if !p.trim().is_empty() => Some(p.trim().to_string()),
+                _ => None,
+            };
+
+            normalized.insert(format!("{} {}", method, path), permission);
+        }
+
+        Ok(PermissionConfig {
+            overrides: normalized,
+            verbose_errors,
+            include_dependencies,
+        })
+    }
+
+    /// # Summary
+    ///
+    /// Get the permission that is required to access a route.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The HTTP method of the route.
+    /// * `path` - The path pattern of the route.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let permission = permission_config.required_permission("GET", "/api/v1/roles/");
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Option<String>` - The required permission, if any.
+    pub fn required_permission(&self, method: &str, path: &str) -> Option<String> {
+        if let Some(p) = self.overrides.get(&format!("{} {}", method, path)) {
+            return p.clone();
+        }
+
+        ROUTE_PERMISSIONS
+            .iter()
+            .find(|r| r.method == method && r.path == path)
+            .map(|r| r.permission.to_string())
+    }
+}
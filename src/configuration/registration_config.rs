@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+
+#[derive(Clone)]
+pub struct RegistrationConfig {
+    pub enabled: bool,
+    pub require_approval: bool,
+    pub allowed_domains: Vec<String>,
+    pub domain_roles: HashMap<String, Vec<String>>,
+}
+
+impl RegistrationConfig {
+    /// # Summary
+    ///
+    /// Create a new RegistrationConfig.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether Users can register themselves.
+    /// * `require_approval` - Whether Users that register themselves are pending until an administrator approves them.
+    /// * `allowed_domains` - The email domains that Users can register with. Any domain is allowed if empty.
+    /// * `domain_roles` - The names of the roles that are granted to new Users, by the domain of their email address.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let registration_config = RegistrationConfig::new(
+    ///     true,
+    ///     true,
+    ///     vec![String::from("opserva.io")],
+    ///     HashMap::from([(String::from("partner.com"), vec![String::from("PARTNER")])]),
+    /// );
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `RegistrationConfig` - The new RegistrationConfig.
+    pub fn new(
+        enabled: bool,
+        require_approval: bool,
+        allowed_domains: Vec<String>,
+        domain_roles: HashMap<String, Vec<String>>,
+    ) -> RegistrationConfig {
+        let allowed_domains = allowed_domains
+            .iter()
+            .map(|d| d.trim().trim_start_matches('@').to_lowercase())
+            .filter(|d| !d.is_empty())
+            .collect();
+
+        let domain_roles = domain_roles
+            .into_iter()
+            .map(|(d, r)| (d.trim().trim_start_matches('@').to_lowercase(), r))
+            .collect();
+
+        RegistrationConfig {
+            enabled,
+            require_approval,
+            allowed_domains,
+            domain_roles,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check whether a User can register with an email address.
+    ///
+    /// # Arguments
+    ///
+    /// * `email` - The email address of the User, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let allowed = registration_config.is_email_allowed(Some("user@opserva.io"));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if no domains are configured, or if the domain of the email address is one of the allowed domains.
+    pub fn is_email_allowed(&self, email: Option<&str>) -> bool {
+        if self.allowed_domains.is_empty() {
+            return true;
+        }
+
+        match email.and_then(|e| e.rsplit_once('@')) {
+            Some((_, domain)) => self.allowed_domains.contains(&domain.to_lowercase()),
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,35 @@
+#[derive(Clone)]
+pub struct RequestLoggingConfig {
+    pub enabled: bool,
+    pub headers: bool,
+    pub bodies: bool,
+}
+
+impl RequestLoggingConfig {
+    /// # Summary
+    ///
+    /// Create a new RequestLoggingConfig.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether every request to the API should be logged in detail.
+    /// * `headers` - Whether the headers of a request should be logged, with sensitive headers redacted.
+    /// * `bodies` - Whether JSON request bodies should be logged, with sensitive fields redacted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let request_logging_config = RequestLoggingConfig::new(true, false, false);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `RequestLoggingConfig` - The new RequestLoggingConfig.
+    pub fn new(enabled: bool, headers: bool, bodies: bool) -> RequestLoggingConfig {
+        RequestLoggingConfig {
+            enabled,
+            headers,
+            bodies,
+        }
+    }
+}
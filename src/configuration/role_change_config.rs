@@ -0,0 +1,27 @@
+#[derive(Clone)]
+pub struct RoleChangeConfig {
+    pub approval_required: bool,
+}
+
+impl RoleChangeConfig {
+    /// # Summary
+    ///
+    /// Create a new RoleChangeConfig.
+    ///
+    /// # Arguments
+    ///
+    /// * `approval_required` - Whether changes to the permissions of roles and grants of the ADMIN role must be approved by a second User.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let role_change_config = RoleChangeConfig::new(true);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `RoleChangeConfig` - The new RoleChangeConfig.
+    pub fn new(approval_required: bool) -> RoleChangeConfig {
+        RoleChangeConfig { approval_required }
+    }
+}
@@ -0,0 +1,123 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoleTemplate {
+    Viewer,
+    Operator,
+    Auditor,
+}
+
+impl RoleTemplate {
+    /// # Summary
+    ///
+    /// Get the description of the Role that is created from the RoleTemplate.
+    ///
+    /// # Returns
+    ///
+    /// * `&'static str` - The description of the Role.
+    pub fn description(&self) -> &'static str {
+        match self {
+            RoleTemplate::Viewer => {
+                "Read-only access to users, roles, permissions and organizations"
+            }
+            RoleTemplate::Operator => "The day-to-day management of users and organizations",
+            RoleTemplate::Auditor => "Read-only access to the audit trail and the access model",
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Get the names of the built-in permissions that are granted by the Role that is created from the RoleTemplate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let permissions = RoleTemplate::Viewer.permissions();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `&'static [&'static str]` - The names of the permissions.
+    pub fn permissions(&self) -> &'static [&'static str] {
+        match self {
+            RoleTemplate::Viewer => &[
+                "CAN_READ_PERMISSION",
+                "CAN_READ_ROLE",
+                "CAN_READ_USER",
+                "CAN_READ_CLIENT",
+                "CAN_READ_API_KEY",
+                "CAN_READ_ORGANIZATION",
+            ],
+            RoleTemplate::Operator => &[
+                "CAN_READ_PERMISSION",
+                "CAN_READ_ROLE",
+                "CAN_CREATE_USER",
+                "CAN_READ_USER",
+                "CAN_UPDATE_USER",
+                "CAN_READ_CLIENT",
+                "CAN_READ_API_KEY",
+                "CAN_CREATE_ORGANIZATION",
+                "CAN_READ_ORGANIZATION",
+                "CAN_UPDATE_ORGANIZATION",
+            ],
+            RoleTemplate::Auditor => &[
+                "CAN_READ_AUDIT",
+                "CAN_READ_PERMISSION",
+                "CAN_READ_ROLE",
+                "CAN_READ_USER",
+                "CAN_READ_ROUTE",
+            ],
+        }
+    }
+}
+
+impl Display for RoleTemplate {
+    /// # Summary
+    ///
+    /// Display the RoleTemplate as the name of the Role that is created from it.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The Formatter.
+    ///
+    /// # Returns
+    ///
+    /// * `std::fmt::Result` - The result of the display.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            RoleTemplate::Viewer => "VIEWER",
+            RoleTemplate::Operator => "OPERATOR",
+            RoleTemplate::Auditor => "AUDITOR",
+        };
+
+        write!(f, "{}", s)
+    }
+}
+
+impl FromStr for RoleTemplate {
+    type Err = String;
+
+    /// # Summary
+    ///
+    /// Parse a RoleTemplate.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The name of the RoleTemplate, case-insensitive.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<RoleTemplate, String>` - The RoleTemplate, or an error message if the template is unknown.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_str() {
+            "VIEWER" => Ok(RoleTemplate::Viewer),
+            "OPERATOR" => Ok(RoleTemplate::Operator),
+            "AUDITOR" => Ok(RoleTemplate::Auditor),
+            _ => Err(format!(
+                "'{}' is not a role template (VIEWER, OPERATOR or AUDITOR)",
+                s.trim()
+            )),
+        }
+    }
+}
@@ -1,9 +1,16 @@
+use crate::services::quota::quota_service::QuotaMode;
+
 #[derive(Clone)]
 pub struct ServerConfig {
     pub address: String,
     pub port: u16,
     pub max_limit: i64,
     pub workers: usize,
+    pub token_quota: u32,
+    pub token_quota_mode: QuotaMode,
+    pub user_schedule_interval: u64,
+    pub max_user_roles: usize,
+    pub max_role_permissions: usize,
 }
 
 impl ServerConfig {
@@ -17,22 +24,84 @@ impl ServerConfig {
     /// * `port` - The port of the ServerConfig.
     /// * `max_limit` - The maximum amount of entity records that can be retrieved in one call.
     /// * `workers` - The number of workers to start (per bind address).
+    /// * `token_quota` - The amount of requests that can be made using a single token per minute, or zero for no limit.
+    /// * `user_schedule_interval` - The interval in seconds at which scheduled User activations and expirations are applied, or zero to disable them.
     ///
     /// # Example
     ///
     /// ```
-    /// let server_config = ServerConfig::new(String::from("address"), 8080);
+    /// let server_config = ServerConfig::new(String::from("address"), 8080, 100, 0, 0, 60);
     /// ```
     ///
     /// # Returns
     ///
     /// * `ServerConfig` - The new ServerConfig.
-    pub fn new(address: String, port: u16, max_limit: i64, workers: usize) -> ServerConfig {
+    pub fn new(
+        address: String,
+        port: u16,
+        max_limit: i64,
+        workers: usize,
+        token_quota: u32,
+        user_schedule_interval: u64,
+    ) -> ServerConfig {
         ServerConfig {
             address,
             port,
             max_limit,
             workers,
+            token_quota,
+            token_quota_mode: QuotaMode::Enforce,
+            user_schedule_interval,
+            max_user_roles: 0,
+            max_role_permissions: 0,
         }
     }
+
+    /// # Summary
+    ///
+    /// Limit the number of Roles that a User can have and the number of Permissions that a Role can have, so that no
+    /// documents are stored that make resolving the permissions of a request arbitrarily slow.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_user_roles` - The maximum number of Roles per User, or zero for no limit.
+    /// * `max_role_permissions` - The maximum number of Permissions per Role, or zero for no limit.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let server_config = ServerConfig::new(String::from("address"), 8080, 100, 0, 0, 60).with_list_limits(100, 1000);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `ServerConfig` - The ServerConfig with the limits.
+    pub fn with_list_limits(mut self, max_user_roles: usize, max_role_permissions: usize) -> Self {
+        self.max_user_roles = max_user_roles;
+        self.max_role_permissions = max_role_permissions;
+        self
+    }
+
+    /// # Summary
+    ///
+    /// Set whether requests that exceed the token quota are rejected, or only reported, so that a quota can be tuned
+    /// before it is enforced.
+    ///
+    /// # Arguments
+    ///
+    /// * `token_quota_mode` - The QuotaMode.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let server_config = ServerConfig::new(String::from("address"), 8080, 100, 0, 120, 60).with_quota_mode(QuotaMode::Monitor);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `ServerConfig` - The ServerConfig with the QuotaMode.
+    pub fn with_quota_mode(mut self, token_quota_mode: QuotaMode) -> Self {
+        self.token_quota_mode = token_quota_mode;
+        self
+    }
 }
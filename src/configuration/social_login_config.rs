@@ -0,0 +1,116 @@
+use crate::services::social::social_provider::SocialProvider;
+
+#[derive(Clone)]
+pub struct SocialProviderConfig {
+    pub provider: SocialProvider,
+    pub client_id: String,
+    pub client_secret: String,
+}
+
+#[derive(Clone)]
+pub struct SocialLoginConfig {
+    pub providers: Vec<SocialProviderConfig>,
+    pub redirect_uri: String,
+    pub roles: Vec<String>,
+    pub auto_create: bool,
+}
+
+impl SocialProviderConfig {
+    /// # Summary
+    ///
+    /// Create a new SocialProviderConfig.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - The SocialProvider.
+    /// * `client_id` - The ID of the OAuth application that was registered at the provider.
+    /// * `client_secret` - The secret of the OAuth application that was registered at the provider.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let provider_config = SocialProviderConfig::new(SocialProvider::GitHub, String::from("Iv1.8a61f9b3a7aba766"), String::from("secret"));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `SocialProviderConfig` - The new SocialProviderConfig.
+    pub fn new(
+        provider: SocialProvider,
+        client_id: String,
+        client_secret: String,
+    ) -> SocialProviderConfig {
+        SocialProviderConfig {
+            provider,
+            client_id,
+            client_secret,
+        }
+    }
+}
+
+impl SocialLoginConfig {
+    /// # Summary
+    ///
+    /// Create a new SocialLoginConfig.
+    ///
+    /// # Arguments
+    ///
+    /// * `providers` - The SocialProviderConfig of every provider that users can log in with.
+    /// * `redirect_uri` - The URL of the callback route, in which `{provider}` is replaced by the name of the provider.
+    /// * `roles` - The names of the roles that are granted to Users that are created on their first social login.
+    /// * `auto_create` - Whether a User is created on the first social login of an unknown account.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let social_login_config = SocialLoginConfig::new(vec![], String::from("https://auth.opserva.io/api/v1/authentication/social/{provider}/callback/"), vec![], true);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `SocialLoginConfig` - The new SocialLoginConfig.
+    pub fn new(
+        providers: Vec<SocialProviderConfig>,
+        redirect_uri: String,
+        roles: Vec<String>,
+        auto_create: bool,
+    ) -> SocialLoginConfig {
+        SocialLoginConfig {
+            providers,
+            redirect_uri,
+            roles,
+            auto_create,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Find the configuration of a provider.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - The SocialProvider.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<&SocialProviderConfig>` - The configuration, or None if the provider is not enabled.
+    pub fn provider(&self, provider: SocialProvider) -> Option<&SocialProviderConfig> {
+        self.providers.iter().find(|p| p.provider == provider)
+    }
+
+    /// # Summary
+    ///
+    /// Get the redirect URI of a provider.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - The SocialProvider.
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The URL of the callback route of the provider.
+    pub fn redirect_uri(&self, provider: SocialProvider) -> String {
+        self.redirect_uri
+            .replace("{provider}", &provider.to_string())
+    }
+}
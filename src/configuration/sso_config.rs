@@ -0,0 +1,66 @@
+#[derive(Clone)]
+pub struct SsoConfig {
+    pub issuer: Option<String>,
+    pub client_id: String,
+    pub client_secret: String,
+    pub redirect_uri: String,
+    pub scope: String,
+    pub roles: Vec<String>,
+    pub auto_create: bool,
+}
+
+impl SsoConfig {
+    /// # Summary
+    ///
+    /// Create a new SsoConfig.
+    ///
+    /// # Arguments
+    ///
+    /// * `issuer` - The issuer URL of the OpenID Connect provider, or None to disable single sign-on.
+    /// * `client_id` - The ID of the client that was registered at the provider.
+    /// * `client_secret` - The secret of the client that was registered at the provider.
+    /// * `redirect_uri` - The URL of the callback route.
+    /// * `scope` - The space-separated scopes that are requested.
+    /// * `roles` - The names of the roles that are granted to Users that are created on their first single sign-on.
+    /// * `auto_create` - Whether a User is created on the first single sign-on of an unknown account.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let sso_config = SsoConfig::new(Some(String::from("https://login.example.com")), String::from("auth-rs"), String::from("secret"), String::from("https://auth.opserva.io/api/v1/authentication/sso/callback/"), String::from("openid email profile"), vec![], true);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `SsoConfig` - The new SsoConfig.
+    pub fn new(
+        issuer: Option<String>,
+        client_id: String,
+        client_secret: String,
+        redirect_uri: String,
+        scope: String,
+        roles: Vec<String>,
+        auto_create: bool,
+    ) -> SsoConfig {
+        SsoConfig {
+            issuer,
+            client_id,
+            client_secret,
+            redirect_uri,
+            scope,
+            roles,
+            auto_create,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check whether single sign-on is enabled.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if an issuer was configured.
+    pub fn is_enabled(&self) -> bool {
+        self.issuer.is_some()
+    }
+}
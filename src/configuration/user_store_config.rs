@@ -0,0 +1,35 @@
+use crate::services::user::user_store::UserStore;
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct UserStoreConfig {
+    pub user_store: Option<Arc<dyn UserStore>>,
+    pub provision_users: bool,
+}
+
+impl UserStoreConfig {
+    /// # Summary
+    ///
+    /// Create a new UserStoreConfig.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_store` - The external UserStore that is used when a User cannot be found locally, if any.
+    /// * `provision_users` - Whether Users that are found in the external UserStore should be stored locally.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_store_config = UserStoreConfig::new(Some(Arc::new(DirectoryUserStore::new())), true);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `UserStoreConfig` - The new UserStoreConfig.
+    pub fn new(user_store: Option<Arc<dyn UserStore>>, provision_users: bool) -> UserStoreConfig {
+        UserStoreConfig {
+            user_store,
+            provision_users,
+        }
+    }
+}
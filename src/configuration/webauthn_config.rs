@@ -0,0 +1,43 @@
+#[derive(Clone)]
+pub struct WebauthnConfig {
+    pub rp_id: Option<String>,
+    pub rp_origin: Option<String>,
+    pub rp_name: String,
+    pub ceremony_expiration: u64,
+}
+
+impl WebauthnConfig {
+    /// # Summary
+    ///
+    /// Create a new WebauthnConfig.
+    ///
+    /// # Arguments
+    ///
+    /// * `rp_id` - The ID of the relying party, which is the domain that passkeys are bound to, or None to disable passkeys.
+    /// * `rp_origin` - The origin of the web application that performs the WebAuthn ceremonies.
+    /// * `rp_name` - The name of the relying party that is shown by authenticators.
+    /// * `ceremony_expiration` - The number of seconds after which a registration or authentication ceremony expires.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let webauthn_config = WebauthnConfig::new(Some(String::from("example.com")), Some(String::from("https://example.com")), String::from("auth-rs"), 300);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `WebauthnConfig` - The new WebauthnConfig.
+    pub fn new(
+        rp_id: Option<String>,
+        rp_origin: Option<String>,
+        rp_name: String,
+        ceremony_expiration: u64,
+    ) -> WebauthnConfig {
+        WebauthnConfig {
+            rp_id,
+            rp_origin,
+            rp_name,
+            ceremony_expiration,
+        }
+    }
+}
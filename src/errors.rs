@@ -1,2 +1,8 @@
 pub mod bad_request;
+pub mod captcha_required;
+pub mod forbidden;
 pub mod internal_server_error;
+pub mod into_http_response;
+pub mod invalid_id;
+pub mod mfa_enrollment_required;
+pub mod mfa_required;
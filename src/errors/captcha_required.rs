@@ -0,0 +1,45 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::time::SystemTime;
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema)]
+pub struct CaptchaRequired {
+    message: String,
+    #[serde(rename(serialize = "captchaRequired"))]
+    captcha_required: bool,
+    timestamp: String,
+    #[serde(rename(serialize = "errorCode"))]
+    error_code: u16,
+}
+
+impl CaptchaRequired {
+    /// # Summary
+    ///
+    /// Create a new CaptchaRequired.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The reason why the CAPTCHA was rejected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let captcha_required = CaptchaRequired::new("A CAPTCHA is required after too many failed logins");
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `CaptchaRequired` - The new CaptchaRequired.
+    pub fn new(message: &str) -> CaptchaRequired {
+        let now: DateTime<Utc> = SystemTime::now().into();
+        let now: String = now.to_rfc3339();
+
+        CaptchaRequired {
+            message: message.to_string(),
+            captcha_required: true,
+            timestamp: now,
+            error_code: 400,
+        }
+    }
+}
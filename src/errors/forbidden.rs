@@ -0,0 +1,46 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::time::SystemTime;
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema)]
+pub struct Forbidden {
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    permission: Option<String>,
+    timestamp: String,
+    #[serde(rename(serialize = "errorCode", deserialize = "errorCode"))]
+    error_code: u16,
+}
+
+impl Forbidden {
+    /// # Summary
+    ///
+    /// Create a new Forbidden.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The error message.
+    /// * `permission` - The permission that is required, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let forbidden = Forbidden::new("Missing permission", Some(String::from("CAN_READ_USER")));
+    /// ```
+    /// # Returns
+    ///
+    /// * `Forbidden` - The new Forbidden.
+    ///
+    pub fn new(message: &str, permission: Option<String>) -> Forbidden {
+        let now: DateTime<Utc> = SystemTime::now().into();
+        let now: String = now.to_rfc3339();
+
+        Forbidden {
+            message: String::from(message),
+            permission,
+            timestamp: now,
+            error_code: 403,
+        }
+    }
+}
@@ -0,0 +1,411 @@
+use crate::errors::bad_request::BadRequest;
+use crate::errors::internal_server_error::InternalServerError;
+use crate::repository::api_key::api_key_repository::Error as ApiKeyError;
+use crate::repository::audit::audit_repository::Error as AuditError;
+use crate::repository::authorization_code::authorization_code_repository::Error as AuthorizationCodeError;
+use crate::repository::backup::backup_repository::Error as BackupError;
+use crate::repository::client::client_repository::Error as ClientError;
+use crate::repository::consent::consent_repository::Error as ConsentError;
+use crate::repository::credential::credential_repository::Error as CredentialError;
+use crate::repository::device::device_repository::Error as DeviceError;
+use crate::repository::invitation::invitation_repository::Error as InvitationError;
+use crate::repository::magic_link::magic_link_repository::Error as MagicLinkError;
+use crate::repository::organization::organization_repository::Error as OrganizationError;
+use crate::repository::permission::permission_repository::Error as PermissionError;
+use crate::repository::refresh_token::refresh_token_repository::Error as RefreshTokenError;
+use crate::repository::revoked_token::revoked_token_repository::Error as RevokedTokenError;
+use crate::repository::role::role_repository::Error as RoleError;
+use crate::repository::role_change::role_change_repository::Error as RoleChangeError;
+use crate::repository::session::session_repository::Error as SessionError;
+use crate::repository::user::user_repository::Error as UserError;
+use crate::services::mfa::totp_service::Error as TotpError;
+use crate::services::mfa::webauthn_service::Error as WebauthnError;
+use crate::services::social::social_login_service::Error as SocialLoginError;
+use crate::services::sso::sso_service::Error as SsoError;
+use actix_web::http::StatusCode;
+use actix_web::HttpResponse;
+
+pub trait IntoHttpResponse: std::error::Error {
+    /// # Summary
+    ///
+    /// Get the HTTP status code that corresponds to the error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let status_code = Error::NameAlreadyTaken.status_code();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `StatusCode` - The HTTP status code.
+    fn status_code(&self) -> StatusCode;
+
+    /// # Summary
+    ///
+    /// Convert the error into an HttpResponse.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// Err(e) => {
+    ///     error!("Error while creating Permission: {}", e);
+    ///     return e.into_http_response();
+    /// }
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `HttpResponse` - A BadRequest or InternalServerError body, or an empty NotFound response.
+    fn into_http_response(self) -> HttpResponse
+    where
+        Self: Sized,
+    {
+        match self.status_code() {
+            StatusCode::BAD_REQUEST => {
+                HttpResponse::BadRequest().json(BadRequest::new(&self.to_string()))
+            }
+            StatusCode::NOT_FOUND => HttpResponse::NotFound().finish(),
+            _ => HttpResponse::InternalServerError()
+                .json(InternalServerError::new(&self.to_string())),
+        }
+    }
+}
+
+impl IntoHttpResponse for AuditError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AuditError::InvalidId(_) | AuditError::ObjectId(_) | AuditError::EmptyTextSearch => {
+                StatusCode::BAD_REQUEST
+            }
+            AuditError::EmptyCollection | AuditError::MongoDb(_) | AuditError::ChainConflict(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+}
+
+impl IntoHttpResponse for BackupError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            BackupError::BackupDisabled
+            | BackupError::InvalidBackup(_)
+            | BackupError::UnsupportedVersion(_) => StatusCode::BAD_REQUEST,
+            BackupError::EmptyCollection | BackupError::Encryption(_) | BackupError::MongoDb(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+}
+
+impl IntoHttpResponse for ApiKeyError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ApiKeyError::InvalidId(_)
+            | ApiKeyError::EmptyId
+            | ApiKeyError::EmptyName
+            | ApiKeyError::InvalidKey => StatusCode::BAD_REQUEST,
+            ApiKeyError::ApiKeyNotFound(_) => StatusCode::NOT_FOUND,
+            ApiKeyError::EmptyCollection | ApiKeyError::MongoDb(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+            ApiKeyError::Audit(e) => e.status_code(),
+        }
+    }
+}
+
+impl IntoHttpResponse for ClientError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ClientError::InvalidId(_)
+            | ClientError::EmptyId
+            | ClientError::EmptyName
+            | ClientError::InvalidCredentials
+            | ClientError::InvalidGrantType(_)
+            | ClientError::InvalidRedirectUri(_)
+            | ClientError::InvalidScope(_)
+            | ClientError::PublicClientCredentials => StatusCode::BAD_REQUEST,
+            ClientError::ClientNotFound(_) => StatusCode::NOT_FOUND,
+            ClientError::EmptyCollection | ClientError::MongoDb(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+            ClientError::Audit(e) => e.status_code(),
+        }
+    }
+}
+
+impl IntoHttpResponse for ConsentError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ConsentError::InvalidId(_)
+            | ConsentError::EmptyId
+            | ConsentError::EmptyScopes
+            | ConsentError::InvalidScope(_) => StatusCode::BAD_REQUEST,
+            ConsentError::ConsentNotFound(_) | ConsentError::ClientNotFound(_) => {
+                StatusCode::NOT_FOUND
+            }
+            ConsentError::EmptyCollection | ConsentError::MongoDb(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+            ConsentError::Audit(e) => e.status_code(),
+        }
+    }
+}
+
+impl IntoHttpResponse for InvitationError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            InvitationError::InvalidId(_)
+            | InvitationError::EmptyId
+            | InvitationError::EmptyEmail
+            | InvitationError::InvalidEmail(_)
+            | InvitationError::InvalidToken
+            | InvitationError::InvitationExpired
+            | InvitationError::EmailMismatch => StatusCode::BAD_REQUEST,
+            InvitationError::InvitationNotFound(_) => StatusCode::NOT_FOUND,
+            InvitationError::EmptyCollection | InvitationError::MongoDb(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+            InvitationError::Organization(e) => e.status_code(),
+            InvitationError::Audit(e) => e.status_code(),
+        }
+    }
+}
+
+impl IntoHttpResponse for RefreshTokenError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            RefreshTokenError::InvalidToken
+            | RefreshTokenError::TokenExpired
+            | RefreshTokenError::TokenReused => StatusCode::BAD_REQUEST,
+            RefreshTokenError::Disabled => StatusCode::NOT_FOUND,
+            RefreshTokenError::EmptyCollection | RefreshTokenError::MongoDb(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+}
+
+impl IntoHttpResponse for RevokedTokenError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            RevokedTokenError::EmptyCollection | RevokedTokenError::MongoDb(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+}
+
+impl IntoHttpResponse for SessionError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            SessionError::EmptyCollection | SessionError::MongoDb(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+}
+
+impl IntoHttpResponse for OrganizationError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            OrganizationError::InvalidId(_)
+            | OrganizationError::EmptyId
+            | OrganizationError::EmptyName
+            | OrganizationError::NameAlreadyTaken => StatusCode::BAD_REQUEST,
+            OrganizationError::OrganizationNotFound(_) | OrganizationError::MemberNotFound(_) => {
+                StatusCode::NOT_FOUND
+            }
+            OrganizationError::EmptyCollection | OrganizationError::MongoDb(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+            OrganizationError::Audit(e) => e.status_code(),
+        }
+    }
+}
+
+impl IntoHttpResponse for DeviceError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            DeviceError::UserCodeNotFound(_) => StatusCode::NOT_FOUND,
+            DeviceError::EmptyClientId
+            | DeviceError::InvalidClient
+            | DeviceError::UnsupportedGrantType(_)
+            | DeviceError::UnauthorizedClient
+            | DeviceError::InvalidScope(_)
+            | DeviceError::InvalidGrant
+            | DeviceError::AuthorizationPending
+            | DeviceError::SlowDown
+            | DeviceError::AccessDenied
+            | DeviceError::ExpiredToken => StatusCode::BAD_REQUEST,
+            DeviceError::EmptyCollection | DeviceError::MongoDb(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+}
+
+impl IntoHttpResponse for AuthorizationCodeError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AuthorizationCodeError::UnsupportedResponseType(_)
+            | AuthorizationCodeError::InvalidClient
+            | AuthorizationCodeError::UnauthorizedClient
+            | AuthorizationCodeError::InvalidRedirectUri(_)
+            | AuthorizationCodeError::RedirectUriRequired
+            | AuthorizationCodeError::InvalidScope(_)
+            | AuthorizationCodeError::ConsentRequired(_)
+            | AuthorizationCodeError::InvalidGrant => StatusCode::BAD_REQUEST,
+            AuthorizationCodeError::Pkce(e) => e.status_code(),
+            AuthorizationCodeError::EmptyCollection | AuthorizationCodeError::MongoDb(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+}
+
+impl IntoHttpResponse for PermissionError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            PermissionError::InvalidId(_)
+            | PermissionError::EmptyId
+            | PermissionError::EmptyName
+            | PermissionError::EmptyTextSearch
+            | PermissionError::NameAlreadyTaken => StatusCode::BAD_REQUEST,
+            PermissionError::PermissionNotFound(_) => StatusCode::NOT_FOUND,
+            PermissionError::Role(e) => e.status_code(),
+            PermissionError::Audit(e) => e.status_code(),
+            PermissionError::EmptyCollection | PermissionError::MongoDb(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+}
+
+impl IntoHttpResponse for RoleError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            RoleError::InvalidId(_)
+            | RoleError::EmptyId
+            | RoleError::EmptyName
+            | RoleError::EmptyTextSearch
+            | RoleError::NameAlreadyTaken
+            | RoleError::TooManyPermissions(_) => StatusCode::BAD_REQUEST,
+            RoleError::RoleNotFound(_) => StatusCode::NOT_FOUND,
+            RoleError::User(e) => e.status_code(),
+            RoleError::Audit(e) => e.status_code(),
+            RoleError::EmptyCollection | RoleError::MongoDb(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl IntoHttpResponse for UserError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            UserError::InvalidId(_)
+            | UserError::EmptyId
+            | UserError::EmptyUsername
+            | UserError::EmptyEmail
+            | UserError::EmptyPassword
+            | UserError::EmptyTextSearch
+            | UserError::UsernameAlreadyTaken
+            | UserError::EmailAlreadyTaken
+            | UserError::InvalidEmail(_)
+            | UserError::InvalidPhoneNumber(_)
+            | UserError::InvalidLocale(_)
+            | UserError::InvalidTimezone(_)
+            | UserError::PrimaryEmailRemoval
+            | UserError::InvalidStatusTransition(_, _)
+            | UserError::InvalidSchedule
+            | UserError::TooManyRoles(_)
+            | UserError::IdentityAlreadyLinked(_) => StatusCode::BAD_REQUEST,
+            UserError::UserNotFound(_) | UserError::EmailNotFound(_) => StatusCode::NOT_FOUND,
+            UserError::Audit(e) => e.status_code(),
+            UserError::EmptyCollection | UserError::MongoDb(_) | UserError::UserStore(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+}
+
+impl IntoHttpResponse for SocialLoginError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            SocialLoginError::ProviderDisabled(_) => StatusCode::NOT_FOUND,
+            SocialLoginError::InvalidGrant | SocialLoginError::InvalidProfile(_) => {
+                StatusCode::BAD_REQUEST
+            }
+            SocialLoginError::Request(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl IntoHttpResponse for SsoError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            SsoError::Disabled => StatusCode::NOT_FOUND,
+            SsoError::InvalidGrant | SsoError::InvalidProfile(_) => StatusCode::BAD_REQUEST,
+            SsoError::Discovery(_) | SsoError::Request(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl IntoHttpResponse for TotpError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            TotpError::Disabled => StatusCode::NOT_FOUND,
+            TotpError::Encryption | TotpError::InvalidSecret => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl IntoHttpResponse for RoleChangeError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            RoleChangeError::InvalidId(_)
+            | RoleChangeError::EmptyId
+            | RoleChangeError::NotPending(_)
+            | RoleChangeError::SelfReview
+            | RoleChangeError::RoleChanged(_) => StatusCode::BAD_REQUEST,
+            RoleChangeError::RoleChangeNotFound(_) => StatusCode::NOT_FOUND,
+            RoleChangeError::Role(e) => e.status_code(),
+            RoleChangeError::User(e) => e.status_code(),
+            RoleChangeError::EmptyCollection | RoleChangeError::MongoDb(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+}
+
+impl IntoHttpResponse for CredentialError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            CredentialError::InvalidId(_) | CredentialError::EmptyId => StatusCode::BAD_REQUEST,
+            CredentialError::CredentialNotFound(_) => StatusCode::NOT_FOUND,
+            CredentialError::EmptyCollection | CredentialError::MongoDb(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+}
+
+impl IntoHttpResponse for MagicLinkError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            MagicLinkError::InvalidLink => StatusCode::BAD_REQUEST,
+            MagicLinkError::EmptyCollection | MagicLinkError::MongoDb(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+}
+
+impl IntoHttpResponse for WebauthnError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            WebauthnError::Disabled => StatusCode::NOT_FOUND,
+            WebauthnError::Ceremony(_) => StatusCode::BAD_REQUEST,
+            WebauthnError::Configuration(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use crate::errors::into_http_response::IntoHttpResponse;
+use actix_web::http::StatusCode;
+use mongodb::bson::oid::ObjectId;
+
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("Invalid {field}: {value}")]
+pub struct InvalidId {
+    field: String,
+    value: String,
+}
+
+impl InvalidId {
+    /// # Summary
+    ///
+    /// Create a new InvalidId.
+    ///
+    /// # Arguments
+    ///
+    /// * `field` - The name of the ID that could not be parsed, such as `Role ID`.
+    /// * `value` - The value that was sent by the client.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let invalid_id = InvalidId::new("Role ID", "not-an-id");
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `InvalidId` - The new InvalidId.
+    pub fn new(field: &str, value: &str) -> InvalidId {
+        InvalidId {
+            field: String::from(field),
+            value: String::from(value),
+        }
+    }
+}
+
+impl IntoHttpResponse for InvalidId {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::BAD_REQUEST
+    }
+}
+
+/// # Summary
+///
+/// Parse an ID that was sent by a client, so that a malformed ID results in a `400 Bad Request` response.
+///
+/// # Arguments
+///
+/// * `field` - The name of the ID, which is included in the error message.
+/// * `value` - The value that was sent by the client.
+///
+/// # Example
+///
+/// ```
+/// let role_id = match parse_object_id("Role ID", &r) {
+///     Ok(oid) => oid,
+///     Err(e) => return e.into_http_response(),
+/// };
+/// ```
+///
+/// # Returns
+///
+/// * `Result<ObjectId, InvalidId>` - The ObjectId, or the InvalidId error if the value is not a valid ObjectId.
+pub fn parse_object_id(field: &str, value: &str) -> Result<ObjectId, InvalidId> {
+    ObjectId::parse_str(value.trim()).map_err(|_| InvalidId::new(field, value))
+}
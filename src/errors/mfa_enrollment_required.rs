@@ -0,0 +1,47 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::time::SystemTime;
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema)]
+pub struct MfaEnrollmentRequired {
+    message: String,
+    roles: Vec<String>,
+    #[serde(rename(serialize = "mfaEnrollmentRequired"))]
+    mfa_enrollment_required: bool,
+    timestamp: String,
+    #[serde(rename(serialize = "errorCode"))]
+    error_code: u16,
+}
+
+impl MfaEnrollmentRequired {
+    /// # Summary
+    ///
+    /// Create a new MfaEnrollmentRequired.
+    ///
+    /// # Arguments
+    ///
+    /// * `roles` - The names of the roles of the User that require multi-factor authentication.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mfa_enrollment_required = MfaEnrollmentRequired::new(vec![String::from("ADMIN")]);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `MfaEnrollmentRequired` - The new MfaEnrollmentRequired.
+    pub fn new(roles: Vec<String>) -> MfaEnrollmentRequired {
+        let now: DateTime<Utc> = SystemTime::now().into();
+        let now: String = now.to_rfc3339();
+
+        MfaEnrollmentRequired {
+            message: String::from("Multi-factor authentication must be enrolled before logging in"),
+            roles,
+            mfa_enrollment_required: true,
+            timestamp: now,
+            error_code: 403,
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::time::SystemTime;
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema)]
+pub struct MfaRequired {
+    message: String,
+    #[serde(rename(serialize = "challengeToken"))]
+    challenge_token: String,
+    #[serde(rename(serialize = "expiresIn"))]
+    expires_in: u64,
+    #[serde(rename(serialize = "mfaRequired"))]
+    mfa_required: bool,
+    methods: Vec<String>,
+    timestamp: String,
+    #[serde(rename(serialize = "errorCode"))]
+    error_code: u16,
+}
+
+impl MfaRequired {
+    /// # Summary
+    ///
+    /// Create a new MfaRequired.
+    ///
+    /// # Arguments
+    ///
+    /// * `challenge_token` - The challenge token that must be sent along with the second factor.
+    /// * `expires_in` - The number of seconds after which the challenge token expires.
+    /// * `methods` - The second factors that the User can complete the login with.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mfa_required = MfaRequired::new(String::from("challenge"), 300, vec![String::from("totp")]);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `MfaRequired` - The new MfaRequired.
+    pub fn new(challenge_token: String, expires_in: u64, methods: Vec<String>) -> MfaRequired {
+        let now: DateTime<Utc> = SystemTime::now().into();
+        let now: String = now.to_rfc3339();
+
+        MfaRequired {
+            message: String::from("A second factor is required to complete the login"),
+            challenge_token,
+            expires_in,
+            mfa_required: true,
+            methods,
+            timestamp: now,
+            error_code: 401,
+        }
+    }
+}
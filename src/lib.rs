@@ -12,3 +12,125 @@
 //! # Maintainers
 //!
 //! * [CodeDead](https://codedead.com)
+use crate::configuration::config::Config;
+use crate::web::controller::Controller;
+use crate::web::guards::maintenance_guard::MaintenanceGuard;
+use crate::web::guards::permission_guard::PermissionGuard;
+use crate::web::guards::quota_guard::QuotaGuard;
+use crate::web::middleware::request_logger::RequestLogger;
+use crate::web::middleware::request_tracker::RequestTracker;
+use crate::web::middleware::session_resolver::SessionResolver;
+use actix_web::web as a_web;
+use actix_web_grants::GrantsMiddleware;
+
+pub mod components;
+pub mod configuration;
+pub mod errors;
+pub mod repository;
+pub mod services;
+#[cfg(test)]
+pub mod test_support;
+pub mod web;
+
+/// # Summary
+///
+/// Mount all routes of the application, including the authentication and authorization middleware, on an existing Actix application.
+///
+/// # Arguments
+///
+/// * `cfg` - The web server configuration of the application or scope that the routes should be mounted on.
+/// * `config` - The Config that is used by the routes.
+///
+/// # Example
+///
+/// ```
+/// App::new().service(web::scope("/auth").configure(|cfg| auth_rs::mount(cfg, config.clone())));
+/// ```
+pub fn mount(cfg: &mut a_web::ServiceConfig, config: Config) {
+    let open_api = config.open_api;
+    let internal_health = config.health_config.has_internal_listener();
+    let internal_admin = config.admin_config.has_internal_listener();
+
+    cfg.app_data(a_web::Data::new(config)).service(
+        a_web::scope("/api/v1")
+            .wrap(PermissionGuard)
+            // The quota is tracked per authenticated caller, so it runs after the GrantsMiddleware
+            .wrap(QuotaGuard)
+            .wrap(GrantsMiddleware::with_extractor(
+                web::extractors::jwt_extractor::extract,
+            ))
+            .wrap(SessionResolver)
+            .wrap(MaintenanceGuard)
+            .wrap(RequestLogger)
+            .wrap(RequestTracker)
+            .configure(Controller::configure_api_routes)
+            .configure(|cfg| {
+                // The admin routes are served by the internal listener instead, keeping the public surface minimal
+                if !internal_admin {
+                    Controller::configure_admin_routes(cfg);
+                }
+            }),
+    );
+
+    // The health routes are served by the internal listener instead, so that they are not exposed publicly
+    if !internal_health {
+        cfg.configure(Controller::configure_health_routes);
+    }
+
+    cfg.configure(Controller::configure_well_known_routes);
+
+    if open_api {
+        cfg.configure(Controller::configure_open_api_routes);
+    }
+}
+
+/// # Summary
+///
+/// Mount the health routes of the application on an existing Actix application, such as the application of an internal
+/// listener that is not exposed publicly.
+///
+/// # Arguments
+///
+/// * `cfg` - The web server configuration of the application or scope that the routes should be mounted on.
+/// * `config` - The Config that is used by the routes.
+///
+/// # Example
+///
+/// ```
+/// App::new().configure(|cfg| auth_rs::mount_health(cfg, config.clone()));
+/// ```
+pub fn mount_health(cfg: &mut a_web::ServiceConfig, config: Config) {
+    cfg.app_data(a_web::Data::new(config))
+        .configure(Controller::configure_health_routes);
+}
+
+/// # Summary
+///
+/// Mount the admin routes of the application, including the authentication and authorization middleware, on an existing
+/// Actix application, such as the application of an internal listener that is not exposed publicly.
+///
+/// # Arguments
+///
+/// * `cfg` - The web server configuration of the application or scope that the routes should be mounted on.
+/// * `config` - The Config that is used by the routes.
+///
+/// # Example
+///
+/// ```
+/// App::new().configure(|cfg| auth_rs::mount_admin(cfg, config.clone()));
+/// ```
+pub fn mount_admin(cfg: &mut a_web::ServiceConfig, config: Config) {
+    cfg.app_data(a_web::Data::new(config)).service(
+        a_web::scope("/api/v1")
+            .wrap(PermissionGuard)
+            // The quota is tracked per authenticated caller, so it runs after the GrantsMiddleware
+            .wrap(QuotaGuard)
+            .wrap(GrantsMiddleware::with_extractor(
+                web::extractors::jwt_extractor::extract,
+            ))
+            .wrap(SessionResolver)
+            .wrap(RequestLogger)
+            .wrap(RequestTracker)
+            .configure(Controller::configure_admin_routes),
+    );
+}
@@ -1,22 +1,16 @@
-use crate::components::env_reader::EnvReader;
-use crate::components::open_api::ApiDoc;
-use crate::web::controller::Controller;
 use actix_cors::Cors;
 use actix_web::middleware::Logger;
-use actix_web::{web as a_web, App, HttpServer};
-use actix_web_grants::GrantsMiddleware;
+use actix_web::{App, HttpServer};
+use auth_rs::components::env_reader::EnvReader;
+use auth_rs::configuration::config_error::ConfigError;
 use dotenvy::dotenv;
 use env_logger::Env;
+use futures::future::try_join_all;
 use log::info;
-use utoipa::OpenApi;
-use utoipa_swagger_ui::SwaggerUi;
-
-mod components;
-mod configuration;
-mod errors;
-mod repository;
-mod services;
-mod web;
+#[cfg(feature = "swagger")]
+use log::warn;
+#[cfg(feature = "swagger")]
+use utoipa_swagger_ui::{Config as SwaggerConfig, SwaggerUi};
 
 /// # Summary
 ///
@@ -30,47 +24,162 @@ mod web;
 /// # Returns
 ///
 /// Returns a Result of type std::io::Result<()>. If the server starts successfully, it returns Ok(()).
-/// Otherwise, it returns an Err with an error message.
+/// Otherwise, it returns an Err with an error message. If the configuration is invalid, a report of every
+/// configuration issue is printed and the process exits with a non-zero exit code. If the `--doctor` argument is
+/// passed, a self-check report is printed instead of starting the server.
+// The Swagger UI is only added to the App if the `swagger` feature is enabled
+#[allow(clippy::let_and_return)]
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     dotenv().ok();
     env_logger::init_from_env(Env::default().default_filter_or("info"));
 
-    let config = EnvReader::read_configuration().await;
+    let config = match EnvReader::read_configuration().await {
+        Ok(d) => d,
+        Err(errors) => exit_with_report(&errors),
+    };
+
+    if std::env::args().any(|a| a == "--doctor") {
+        let report = config.self_check.run(&config).await;
+        println!("{}", report);
+        std::process::exit(if report.is_healthy() { 0 } else { 1 });
+    }
+
+    config.db_watchdog.start();
+    config.user_scheduler.start();
+    config.data_exporter.start();
+    config
+        .email_worker
+        .start(config.services.email_sender.clone());
 
     let addr = config.server_config.address.clone();
     let port = config.server_config.port;
     let workers = config.server_config.workers;
+    let health_config = config.health_config.clone();
+    let health_app_config = config.clone();
+    let admin_config = config.admin_config.clone();
+    let admin_app_config = config.clone();
 
     info!("Starting server at {}:{}", addr, port);
 
-    let openapi = ApiDoc::openapi();
+    #[cfg(feature = "swagger")]
+    if config.swagger_ui && !config.open_api {
+        warn!("The Swagger UI cannot load the OpenAPI specification if ENABLE_OPENAPI is disabled");
+    }
 
-    let mut server = HttpServer::new(move || {
+    let mut server = match HttpServer::new(move || {
         let logger = Logger::default();
-        let mut app = App::new()
+        let app = App::new()
             .wrap(logger)
-            .wrap(GrantsMiddleware::with_extractor(
-                web::extractors::jwt_extractor::extract,
-            ))
-            .app_data(a_web::Data::new(config.clone()))
             .wrap(Cors::permissive())
-            .configure(Controller::configure_routes);
+            .configure(|cfg| auth_rs::mount(cfg, config.clone()));
 
-        if config.open_api {
-            app = app.service(
-                SwaggerUi::new("/swagger-ui/{_:.*}").url("/api-docs/openapi.json", openapi.clone()),
-            );
-        }
+        #[cfg(feature = "swagger")]
+        let app = if config.swagger_ui {
+            app.service(
+                SwaggerUi::new("/swagger-ui/{_:.*}")
+                    .config(SwaggerConfig::from("/api-docs/openapi.json")),
+            )
+        } else {
+            app
+        };
 
         app
     })
-    .bind((addr, port))
-    .expect("Failed to bind server");
+    .bind((addr.clone(), port))
+    {
+        Ok(s) => s,
+        Err(e) => exit_with_report(&[bind_error("SERVER_PORT", &addr, port, e)]),
+    };
 
     if workers > 0 {
         server = server.workers(workers);
     }
 
-    server.run().await
+    let mut servers = vec![server.run()];
+
+    if health_config.has_internal_listener() {
+        info!(
+            "Starting health listener at {}:{}",
+            health_config.address, health_config.port
+        );
+
+        let health_server = match HttpServer::new(move || {
+            App::new().configure(|cfg| auth_rs::mount_health(cfg, health_app_config.clone()))
+        })
+        .workers(1)
+        .bind((health_config.address.clone(), health_config.port))
+        {
+            Ok(s) => s,
+            Err(e) => exit_with_report(&[bind_error(
+                "HEALTH_PORT",
+                &health_config.address,
+                health_config.port,
+                e,
+            )]),
+        };
+
+        servers.push(health_server.run());
+    }
+
+    if admin_config.has_internal_listener() {
+        info!(
+            "Starting admin listener at {}:{}",
+            admin_config.address, admin_config.port
+        );
+
+        let admin_server = match HttpServer::new(move || {
+            App::new()
+                .wrap(Logger::default())
+                .configure(|cfg| auth_rs::mount_admin(cfg, admin_app_config.clone()))
+        })
+        .workers(1)
+        .bind((admin_config.address.clone(), admin_config.port))
+        {
+            Ok(s) => s,
+            Err(e) => exit_with_report(&[bind_error(
+                "ADMIN_PORT",
+                &admin_config.address,
+                admin_config.port,
+                e,
+            )]),
+        };
+
+        servers.push(admin_server.run());
+    }
+
+    try_join_all(servers).await.map(|_| ())
+}
+
+/// # Summary
+///
+/// Print a report of every configuration issue and exit with a non-zero exit code.
+///
+/// # Arguments
+///
+/// * `errors` - The ConfigErrors that occurred.
+fn exit_with_report(errors: &[ConfigError]) -> ! {
+    eprintln!("{}", ConfigError::report(errors));
+    std::process::exit(1);
+}
+
+/// # Summary
+///
+/// Create the ConfigError for a listener that could not be bound to its address and port.
+///
+/// # Arguments
+///
+/// * `variable` - The name of the environment variable that holds the port of the listener.
+/// * `address` - The address of the listener.
+/// * `port` - The port of the listener.
+/// * `error` - The error that occurred while binding the listener.
+///
+/// # Returns
+///
+/// * `ConfigError` - The ConfigError.
+fn bind_error(variable: &str, address: &str, port: u16, error: std::io::Error) -> ConfigError {
+    ConfigError::invalid(
+        variable,
+        &format!("failed to bind {}:{} ({})", address, port, error),
+    )
 }
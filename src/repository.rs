@@ -1,4 +1,21 @@
+pub mod api_key;
 pub mod audit;
+pub mod authorization_code;
+pub mod backup;
+pub mod client;
+pub mod consent;
+pub mod credential;
+pub mod device;
+pub mod email_job;
+pub mod invitation;
+pub mod magic_link;
+pub mod organization;
 pub mod permission;
+pub mod refresh_token;
+pub mod revoked_token;
 pub mod role;
+pub mod role_change;
+pub mod search_hit;
+pub mod session;
 pub mod user;
+pub mod user_summary;
@@ -0,0 +1,108 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::time::SystemTime;
+
+/// The number of characters of a key that are stored in plain text, so that keys can be told apart.
+pub const KEY_PREFIX_LENGTH: usize = 8;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ApiKey {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(rename = "keyHash")]
+    pub key_hash: String,
+    pub prefix: String,
+    pub permissions: Option<Vec<ObjectId>>,
+    pub enabled: bool,
+    #[serde(
+        rename = "expiresAt",
+        default,
+        with = "crate::repository::user::user_model::optional_chrono_datetime_as_bson_datetime"
+    )]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ApiKey {
+    /// # Summary
+    ///
+    /// Create a new ApiKey without a key. The key is generated when the ApiKey is created by the ApiKeyService.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the ApiKey.
+    /// * `description` - The description of the ApiKey.
+    /// * `permissions` - The Permissions that are granted to the requests that carry the key.
+    /// * `expires_at` - The moment at which the key stops working, or None if it does not expire.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let api_key = ApiKey::new(String::from("Monitoring"), None, Some(vec![ObjectId::new()]), None);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `ApiKey` - The new ApiKey.
+    pub fn new(
+        name: String,
+        description: Option<String>,
+        permissions: Option<Vec<ObjectId>>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        let now: DateTime<Utc> = SystemTime::now().into();
+
+        ApiKey {
+            id: ObjectId::new(),
+            name,
+            description,
+            key_hash: String::new(),
+            prefix: String::new(),
+            permissions,
+            enabled: true,
+            expires_at,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check whether the ApiKey has expired.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the ApiKey has an expiry date that has passed.
+    pub fn is_expired(&self) -> bool {
+        match self.expires_at {
+            Some(e) => e <= Utc::now(),
+            None => false,
+        }
+    }
+}
+
+impl Display for ApiKey {
+    /// # Summary
+    ///
+    /// Display the ApiKey, without its key hash.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The Formatter.
+    ///
+    /// # Returns
+    ///
+    /// * `std::fmt::Result` - The result of the display.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ApiKey: {{ id: {}, name: {}, description: {}, prefix: {}, permissions: {:?}, enabled: {}, expires_at: {}, created_at: {}, updated_at: {} }}", self.id.to_hex(), self.name, self.description.as_ref().unwrap_or(&String::from("None")), self.prefix, self.permissions.as_ref().unwrap_or(&vec![]), self.enabled, self.expires_at.map(|e| e.to_string()).unwrap_or(String::from("None")), self.created_at, self.updated_at)
+    }
+}
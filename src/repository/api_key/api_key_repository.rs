@@ -0,0 +1,356 @@
+use crate::repository::api_key::api_key_model::ApiKey;
+use crate::repository::audit::audit_repository::Error as AuditError;
+use futures::TryStreamExt;
+use mongodb::bson::doc;
+use mongodb::bson::oid::ObjectId;
+use mongodb::error::Error as MongoError;
+use mongodb::options::FindOptions;
+use mongodb::Database;
+
+#[derive(Clone)]
+pub struct ApiKeyRepository {
+    pub collection: String,
+}
+
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Invalid ApiKey ID: {0}")]
+    InvalidId(String),
+    #[error("Empty collection")]
+    EmptyCollection,
+    #[error("Empty ApiKey ID")]
+    EmptyId,
+    #[error("Empty ApiKey name")]
+    EmptyName,
+    #[error("ApiKey not found: {0}")]
+    ApiKeyNotFound(String),
+    #[error("Invalid API key")]
+    InvalidKey,
+    #[error("Audit error: {0}")]
+    Audit(#[source] AuditError),
+    #[error("MongoDB error: {0}")]
+    MongoDb(#[source] MongoError),
+}
+
+impl ApiKeyRepository {
+    /// # Summary
+    ///
+    /// Creates a new ApiKeyRepository instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection` - A String that holds the collection name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let api_key_repository = match ApiKeyRepository::new("apiKeys".to_string()) {
+    ///    Ok(d) => d,
+    ///    Err(e) => panic!("Failed to initialize ApiKey repository: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A ApiKeyRepository instance.
+    pub fn new(collection: String) -> Result<ApiKeyRepository, Error> {
+        if collection.is_empty() {
+            return Err(Error::EmptyCollection);
+        }
+
+        Ok(ApiKeyRepository { collection })
+    }
+
+    /// # Summary
+    ///
+    /// Create a new API key.
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - An ApiKey instance.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let api_key = match api_key_repository.create(api_key, &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to create ApiKey: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the created ApiKey instance or an Error.
+    pub async fn create(&self, api_key: ApiKey, db: &Database) -> Result<ApiKey, Error> {
+        if api_key.name.is_empty() {
+            return Err(Error::EmptyName);
+        }
+
+        let api_key_id = api_key.id.to_hex();
+
+        match db
+            .collection::<ApiKey>(&self.collection)
+            .insert_one(api_key, None)
+            .await
+        {
+            Ok(_) => (),
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        match self.find_by_id(&api_key_id, db).await? {
+            Some(c) => Ok(c),
+            None => Err(Error::ApiKeyNotFound(api_key_id)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Find all API keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - An optional i64 that holds the limit.
+    /// * `page` - An optional i64 that holds the page.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let api_keys = match api_key_repository.find_all(Some(10), Some(1), &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to find ApiKeys: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with a vector of ApiKey instances or an Error.
+    pub async fn find_all(
+        &self,
+        limit: Option<i64>,
+        page: Option<i64>,
+        db: &Database,
+    ) -> Result<Vec<ApiKey>, Error> {
+        let mut skip: Option<u64> = None;
+
+        if let Some(l) = limit {
+            if l > 1 {
+                if let Some(p) = page {
+                    if p > 1 {
+                        let res = u64::try_from((p - 1) * l).unwrap_or(0);
+                        skip = Some(res);
+                    }
+                }
+            }
+        }
+
+        let find_options = FindOptions::builder().limit(limit).skip(skip).build();
+
+        let cursor = match db
+            .collection::<ApiKey>(&self.collection)
+            .find(None, find_options)
+            .await
+        {
+            Ok(d) => d,
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        Ok(cursor.try_collect().await.unwrap_or_else(|_| vec![]))
+    }
+
+    /// # Summary
+    ///
+    /// Find an API key by its ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - A string slice that holds the ID.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let api_key = match api_key_repository.find_by_id("id", &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to find ApiKey by ID: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with an Option of a ApiKey instance or an Error.
+    pub async fn find_by_id(&self, id: &str, db: &Database) -> Result<Option<ApiKey>, Error> {
+        if id.is_empty() {
+            return Err(Error::EmptyId);
+        }
+
+        let target_object_id = match ObjectId::parse_str(id) {
+            Ok(res) => res,
+            Err(e) => {
+                return Err(Error::InvalidId(e.to_string()));
+            }
+        };
+
+        let filter = doc! {
+            "_id": target_object_id,
+        };
+
+        match db
+            .collection::<ApiKey>(&self.collection)
+            .find_one(filter, None)
+            .await
+        {
+            Ok(d) => Ok(d),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Find an API key by the hash of its key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_hash` - A string slice that holds the hash of the key.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let api_key = match api_key_repository.find_by_key_hash("hash", &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to find ApiKey by key hash: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with an Option of an ApiKey instance or an Error.
+    pub async fn find_by_key_hash(
+        &self,
+        key_hash: &str,
+        db: &Database,
+    ) -> Result<Option<ApiKey>, Error> {
+        let filter = doc! {
+            "keyHash": key_hash,
+        };
+
+        match db
+            .collection::<ApiKey>(&self.collection)
+            .find_one(filter, None)
+            .await
+        {
+            Ok(d) => Ok(d),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Update an API key, including its key hash.
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - An ApiKey instance.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let api_key = match api_key_repository.update(api_key, &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to update ApiKey: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the updated ApiKey instance or an Error.
+    pub async fn update(&self, api_key: ApiKey, db: &Database) -> Result<ApiKey, Error> {
+        if api_key.name.is_empty() {
+            return Err(Error::EmptyName);
+        }
+
+        let api_key_id = api_key.id.to_hex();
+        let filter = doc! {
+            "_id": api_key.id,
+        };
+
+        let update = doc! {
+            "$set": {
+                "name": api_key.name,
+                "description": api_key.description,
+                "keyHash": api_key.key_hash,
+                "prefix": api_key.prefix,
+                "permissions": api_key.permissions,
+                "enabled": api_key.enabled,
+                "expiresAt": api_key.expires_at.map(mongodb::bson::DateTime::from_chrono),
+                "updatedAt": mongodb::bson::DateTime::now(),
+            }
+        };
+
+        match db
+            .collection::<ApiKey>(&self.collection)
+            .update_one(filter, update, None)
+            .await
+        {
+            Ok(r) if r.matched_count == 0 => return Err(Error::ApiKeyNotFound(api_key_id)),
+            Ok(_) => (),
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        match self.find_by_id(&api_key_id, db).await? {
+            Some(c) => Ok(c),
+            None => Err(Error::ApiKeyNotFound(api_key_id)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Delete an API key by its ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - A string slice that holds the ID.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// match api_key_repository.delete("id", &db).await {
+    ///   Ok(_) => (),
+    ///   Err(e) => panic!("Failed to delete ApiKey: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the unit type or an Error.
+    pub async fn delete(&self, id: &str, db: &Database) -> Result<(), Error> {
+        if id.is_empty() {
+            return Err(Error::EmptyId);
+        }
+
+        let target_object_id = match ObjectId::parse_str(id) {
+            Ok(res) => res,
+            Err(e) => {
+                return Err(Error::InvalidId(e.to_string()));
+            }
+        };
+
+        let filter = doc! {
+            "_id": target_object_id,
+        };
+
+        match db
+            .collection::<ApiKey>(&self.collection)
+            .delete_one(filter, None)
+            .await
+        {
+            Ok(r) if r.deleted_count == 0 => Err(Error::ApiKeyNotFound(id.to_string())),
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+}
@@ -1,2 +1,3 @@
+pub mod audit_filter;
 pub mod audit_model;
 pub mod audit_repository;
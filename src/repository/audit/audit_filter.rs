@@ -0,0 +1,66 @@
+use crate::repository::audit::audit_model::{Action, ResourceType};
+use mongodb::bson::{doc, to_bson, Document};
+
+#[derive(Default)]
+pub struct AuditFilter {
+    pub text: Option<String>,
+    pub action: Option<Action>,
+    pub resource_type: Option<ResourceType>,
+}
+
+impl AuditFilter {
+    /// # Summary
+    ///
+    /// Check whether the AuditFilter does not restrict the Audits in any way.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let filter = AuditFilter::default();
+    /// assert!(filter.is_empty());
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if none of the criteria are set.
+    pub fn is_empty(&self) -> bool {
+        self.text.is_none() && self.action.is_none() && self.resource_type.is_none()
+    }
+
+    /// # Summary
+    ///
+    /// Convert the AuditFilter into a query document that matches the Audits that meet all criteria.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let filter = AuditFilter {
+    ///     action: Some(Action::Deny),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let document = filter.to_document();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Document` - The query document.
+    pub fn to_document(&self) -> Document {
+        let mut document = Document::new();
+
+        if let Some(text) = &self.text {
+            document.insert("$text", doc! { "$search": text });
+        }
+
+        // The enums are stored by their serialized names, so they are matched the same way
+        if let Some(action) = self.action.as_ref().and_then(|a| to_bson(a).ok()) {
+            document.insert("action", action);
+        }
+
+        if let Some(resource_type) = self.resource_type.as_ref().and_then(|r| to_bson(r).ok()) {
+            document.insert("resourceType", resource_type);
+        }
+
+        document
+    }
+}
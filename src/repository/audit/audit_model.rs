@@ -1,9 +1,14 @@
 use chrono::{DateTime, Utc};
 use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, Document};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::fmt::{Display, Formatter};
 use std::time::SystemTime;
 
+/// The maximum amount of issues that are reported for a single partition.
+const MAX_CHAIN_ISSUES: usize = 100;
+
 #[derive(Serialize, Deserialize)]
 pub enum ResourceType {
     #[serde(rename = "permission")]
@@ -12,6 +17,18 @@ pub enum ResourceType {
     Role,
     #[serde(rename = "user")]
     User,
+    #[serde(rename = "route")]
+    Route,
+    #[serde(rename = "client")]
+    Client,
+    #[serde(rename = "consent")]
+    Consent,
+    #[serde(rename = "organization")]
+    Organization,
+    #[serde(rename = "invitation")]
+    Invitation,
+    #[serde(rename = "apiKey")]
+    ApiKey,
 }
 
 impl Display for ResourceType {
@@ -31,6 +48,12 @@ impl Display for ResourceType {
             ResourceType::Permission => write!(f, "Permission"),
             ResourceType::Role => write!(f, "Role"),
             ResourceType::User => write!(f, "User"),
+            ResourceType::Route => write!(f, "Route"),
+            ResourceType::Client => write!(f, "Client"),
+            ResourceType::Consent => write!(f, "Consent"),
+            ResourceType::Organization => write!(f, "Organization"),
+            ResourceType::Invitation => write!(f, "Invitation"),
+            ResourceType::ApiKey => write!(f, "ApiKey"),
         }
     }
 }
@@ -59,6 +82,16 @@ pub enum ResourceIdType {
     UserName,
     #[serde(rename = "userSearch")]
     UserSearch,
+    #[serde(rename = "clientId")]
+    ClientId,
+    #[serde(rename = "consentId")]
+    ConsentId,
+    #[serde(rename = "organizationId")]
+    OrganizationId,
+    #[serde(rename = "invitationId")]
+    InvitationId,
+    #[serde(rename = "apiKeyId")]
+    ApiKeyId,
     #[serde(rename = "none")]
     None,
 }
@@ -88,6 +121,11 @@ impl Display for ResourceIdType {
             ResourceIdType::UserId => write!(f, "UserId"),
             ResourceIdType::UserName => write!(f, "UserName"),
             ResourceIdType::UserSearch => write!(f, "UserSearch"),
+            ResourceIdType::ClientId => write!(f, "ClientId"),
+            ResourceIdType::ConsentId => write!(f, "ConsentId"),
+            ResourceIdType::OrganizationId => write!(f, "OrganizationId"),
+            ResourceIdType::InvitationId => write!(f, "InvitationId"),
+            ResourceIdType::ApiKeyId => write!(f, "ApiKeyId"),
             ResourceIdType::None => write!(f, "None"),
         }
     }
@@ -101,6 +139,8 @@ pub enum Action {
     Update,
     #[serde(rename = "delete")]
     Delete,
+    #[serde(rename = "deny")]
+    Deny,
 }
 
 impl Display for Action {
@@ -120,6 +160,7 @@ impl Display for Action {
             Action::Create => write!(f, "Create"),
             Action::Update => write!(f, "Update"),
             Action::Delete => write!(f, "Delete"),
+            Action::Deny => write!(f, "Deny"),
         }
     }
 }
@@ -137,11 +178,42 @@ pub struct Audit {
     pub resource_id_type: ResourceIdType,
     #[serde(rename = "resourceType")]
     pub resource_type: ResourceType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub route: Option<String>,
+    #[serde(rename = "requestId", default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permission: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain: Option<AuditChain>,
     #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     #[serde(rename = "createdAt")]
     pub created_at: DateTime<Utc>,
 }
 
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AuditChain {
+    pub partition: String,
+    pub sequence: i64,
+    #[serde(rename = "previousHash")]
+    pub previous_hash: String,
+    pub hash: String,
+}
+
+pub struct AuditChainVerification {
+    pub partition: String,
+    pub records: u64,
+    pub first_sequence: Option<i64>,
+    pub last_sequence: Option<i64>,
+    pub truncated: bool,
+    pub issues: Vec<String>,
+    previous: Option<(i64, String)>,
+}
+
 impl Audit {
     /// # Summary
     ///
@@ -180,9 +252,288 @@ impl Audit {
             resource_id,
             resource_id_type,
             resource_type,
+            method: None,
+            route: None,
+            request_id: None,
+            permission: None,
+            reason: None,
+            chain: None,
             created_at: now,
         }
     }
+
+    /// # Summary
+    ///
+    /// Create a new Audit for a request that was denied because the user does not hold the permission that is required by the route.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The user id of the subject that made the request.
+    /// * `method` - The HTTP method of the request.
+    /// * `route` - The path of the route that was requested.
+    /// * `permission` - The permission that is required by the route, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let audit = Audit::denial(user_id, String::from("DELETE"), String::from("/api/v1/users/{id}"), Some(String::from("CAN_DELETE_USER")));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Audit` - The new Audit.
+    pub fn denial(
+        user_id: ObjectId,
+        method: String,
+        route: String,
+        permission: Option<String>,
+    ) -> Audit {
+        let mut audit = Audit::new(
+            user_id,
+            Action::Deny,
+            user_id,
+            ResourceIdType::None,
+            ResourceType::Route,
+        );
+        audit.method = Some(method);
+        audit.route = Some(route);
+        audit.permission = permission;
+
+        audit
+    }
+
+    /// # Summary
+    ///
+    /// Get the name of the partition of the audit trail that the Audit belongs to. Every type of resource has its own
+    /// hash chain, so that records of unrelated resources do not contend for the same chain.
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The name of the partition.
+    pub fn partition(&self) -> String {
+        match mongodb::bson::to_bson(&self.resource_type) {
+            Ok(mongodb::bson::Bson::String(s)) => s,
+            _ => self.resource_type.to_string().to_lowercase(),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Compute the SHA-256 hash of the contents of the Audit, chained to the hash of the previous record of its partition.
+    ///
+    /// # Arguments
+    ///
+    /// * `partition` - The partition of the Audit.
+    /// * `sequence` - The position of the Audit within its partition.
+    /// * `previous_hash` - The hash of the previous record of the partition, or an empty string for the first record.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let hash = audit.compute_hash("user", 1, "");
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The hexadecimal representation of the hash.
+    pub fn compute_hash(&self, partition: &str, sequence: i64, previous_hash: &str) -> String {
+        // The creation date is stored with millisecond precision, so anything finer would not survive a round trip
+        let mut contents = serde_json::json!([
+            previous_hash,
+            partition,
+            sequence,
+            self.id.to_hex(),
+            self.user_id.to_hex(),
+            self.action.to_string(),
+            self.resource_id.to_hex(),
+            self.resource_id_type.to_string(),
+            self.resource_type.to_string(),
+            self.method,
+            self.route,
+            self.request_id,
+            self.permission,
+            self.created_at.timestamp_millis(),
+        ]);
+
+        // The reason is only hashed if it is present, so that the hashes of records without a reason do not change
+        if let (Some(r), Some(c)) = (&self.reason, contents.as_array_mut()) {
+            c.push(serde_json::json!(r));
+        }
+
+        format!("{:x}", Sha256::digest(contents.to_string().as_bytes()))
+    }
+
+    /// # Summary
+    ///
+    /// Link the Audit to the last record of its partition by storing its sequence and hash chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `previous` - The chain of the last record of the partition, or None if the partition is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// audit.link(last.and_then(|a| a.chain).as_ref());
+    /// ```
+    pub fn link(&mut self, previous: Option<&AuditChain>) {
+        let partition = self.partition();
+        let (sequence, previous_hash) = match previous {
+            Some(p) => (p.sequence + 1, p.hash.clone()),
+            None => (1, String::new()),
+        };
+
+        let hash = self.compute_hash(&partition, sequence, &previous_hash);
+        self.chain = Some(AuditChain {
+            partition,
+            sequence,
+            previous_hash,
+            hash,
+        });
+    }
+
+    /// # Summary
+    ///
+    /// Get the JSON Schema that is used to validate Audit documents that are written to the database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let validator = doc! { "$jsonSchema": Audit::json_schema() };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Document` - The JSON Schema of an Audit.
+    pub fn json_schema() -> Document {
+        doc! {
+            "bsonType": "object",
+            "required": ["_id", "userId", "action", "resourceId", "resourceIdType", "resourceType", "createdAt"],
+            "properties": {
+                "_id": { "bsonType": "objectId" },
+                "userId": { "bsonType": "objectId" },
+                "action": { "enum": ["create", "update", "delete", "deny"] },
+                "resourceId": { "bsonType": "objectId" },
+                "resourceIdType": { "bsonType": "string" },
+                "resourceType": {
+                    "enum": ["permission", "role", "user", "route", "client", "consent", "organization", "invitation", "apiKey"]
+                },
+                "method": { "bsonType": "string" },
+                "route": { "bsonType": "string" },
+                "requestId": { "bsonType": "string" },
+                "permission": { "bsonType": "string" },
+                "reason": { "bsonType": "string" },
+                "chain": {
+                    "bsonType": "object",
+                    "required": ["partition", "sequence", "previousHash", "hash"],
+                    "properties": {
+                        "partition": { "bsonType": "string" },
+                        "sequence": { "bsonType": "long" },
+                        "previousHash": { "bsonType": "string" },
+                        "hash": { "bsonType": "string" },
+                    }
+                },
+                "createdAt": { "bsonType": "date" },
+            }
+        }
+    }
+}
+
+impl AuditChainVerification {
+    /// # Summary
+    ///
+    /// Create a new AuditChainVerification for a partition that has not been checked yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `partition` - The partition that is verified.
+    ///
+    /// # Returns
+    ///
+    /// * `AuditChainVerification` - The new AuditChainVerification.
+    pub fn new(partition: String) -> AuditChainVerification {
+        AuditChainVerification {
+            partition,
+            records: 0,
+            first_sequence: None,
+            last_sequence: None,
+            truncated: false,
+            issues: vec![],
+            previous: None,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check the next record of the partition. The record is recomputed and compared to the hash that was stored, and
+    /// it must link to the record that was checked before it without gaps in the sequence. Records without a chain are
+    /// skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `audit` - The next record of the partition, in the order of the sequence.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut verification = AuditChainVerification::new(String::from("user"));
+    /// verification.check(&audit);
+    /// ```
+    pub fn check(&mut self, audit: &Audit) {
+        let chain = match &audit.chain {
+            Some(c) => c,
+            None => return,
+        };
+
+        let mut issues: Vec<String> = vec![];
+        match &self.previous {
+            Some((sequence, hash)) => {
+                if chain.sequence <= *sequence {
+                    issues.push(format!("Record {} appears more than once", chain.sequence));
+                } else if chain.sequence != sequence + 1 {
+                    issues.push(format!(
+                        "Records {} to {} are missing",
+                        sequence + 1,
+                        chain.sequence - 1
+                    ));
+                } else if &chain.previous_hash != hash {
+                    issues.push(format!(
+                        "Record {} does not link to the previous record",
+                        chain.sequence
+                    ));
+                }
+            }
+            None => {
+                self.first_sequence = Some(chain.sequence);
+                // Older records may have been removed by the TTL index, so the chain starts at the first
+                // remaining record
+                if chain.sequence > 1 {
+                    self.truncated = true;
+                } else if !chain.previous_hash.is_empty() {
+                    issues.push(String::from("The first record links to a previous record"));
+                }
+            }
+        }
+
+        let hash = audit.compute_hash(&chain.partition, chain.sequence, &chain.previous_hash);
+        if hash != chain.hash {
+            issues.push(format!(
+                "Record {} ({}) was modified",
+                chain.sequence,
+                audit.id.to_hex()
+            ));
+        }
+
+        for i in issues {
+            if self.issues.len() < MAX_CHAIN_ISSUES {
+                self.issues.push(i);
+            }
+        }
+
+        self.records += 1;
+        self.last_sequence = Some(chain.sequence);
+        self.previous = Some((chain.sequence, chain.hash.clone()));
+    }
 }
 
 impl Display for Audit {
@@ -205,3 +556,99 @@ impl Display for Audit {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain(length: usize) -> Vec<Audit> {
+        let mut audits: Vec<Audit> = vec![];
+        for _ in 0..length {
+            let mut audit = Audit::new(
+                ObjectId::new(),
+                Action::Update,
+                ObjectId::new(),
+                ResourceIdType::UserId,
+                ResourceType::User,
+            );
+            audit.link(audits.last().and_then(|a| a.chain.as_ref()));
+            audits.push(audit);
+        }
+
+        audits
+    }
+
+    fn verify(audits: &[Audit]) -> AuditChainVerification {
+        let mut verification = AuditChainVerification::new(String::from("user"));
+        for audit in audits {
+            verification.check(audit);
+        }
+
+        verification
+    }
+
+    #[test]
+    fn intact_chain_is_valid() {
+        let verification = verify(&chain(3));
+
+        assert!(verification.issues.is_empty());
+        assert_eq!(verification.records, 3);
+        assert_eq!(verification.first_sequence, Some(1));
+        assert_eq!(verification.last_sequence, Some(3));
+        assert!(!verification.truncated);
+    }
+
+    #[test]
+    fn tampered_record_breaks_chain() {
+        let mut audits = chain(3);
+        audits[1].action = Action::Delete;
+
+        let verification = verify(&audits);
+
+        assert_eq!(
+            verification.issues,
+            vec![format!("Record 2 ({}) was modified", audits[1].id.to_hex())]
+        );
+    }
+
+    #[test]
+    fn rehashed_record_no_longer_links_to_next_record() {
+        let mut audits = chain(3);
+        audits[1].action = Action::Delete;
+        let previous = audits[0].chain.clone();
+        audits[1].link(previous.as_ref());
+
+        let verification = verify(&audits);
+
+        assert_eq!(
+            verification.issues,
+            vec![String::from(
+                "Record 3 does not link to the previous record"
+            )]
+        );
+    }
+
+    #[test]
+    fn removed_record_is_reported() {
+        let mut audits = chain(3);
+        audits.remove(1);
+
+        let verification = verify(&audits);
+
+        assert_eq!(
+            verification.issues,
+            vec![String::from("Records 2 to 2 are missing")]
+        );
+    }
+
+    #[test]
+    fn chain_without_its_oldest_records_is_truncated() {
+        let audits = chain(3);
+
+        let verification = verify(&audits[1..]);
+
+        assert!(verification.issues.is_empty());
+        assert!(verification.truncated);
+        assert_eq!(verification.first_sequence, Some(2));
+    }
+}
@@ -1,67 +1,88 @@
-use crate::repository::audit::audit_model::Audit;
+use crate::repository::audit::audit_filter::AuditFilter;
+use crate::repository::audit::audit_model::{Audit, AuditChainVerification};
+use chrono::{DateTime, Utc};
+use futures::lock::Mutex;
 use futures::TryStreamExt;
-use mongodb::bson::doc;
 use mongodb::bson::oid::ObjectId;
-use mongodb::error::Error as MongodbError;
-use mongodb::options::FindOptions;
+use mongodb::bson::{doc, DateTime as BsonDateTime};
+use mongodb::error::{Error as MongodbError, ErrorKind, WriteFailure};
+use mongodb::options::{CollectionOptions, FindOneOptions, FindOptions, WriteConcern};
 use mongodb::Database;
-use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+
+/// The amount of times that a chained Audit is linked again when another instance appended to its partition first.
+const MAX_CHAIN_ATTEMPTS: usize = 10;
 
 #[derive(Clone)]
 pub struct AuditRepository {
     pub collection: String,
+    pub write_concern: Option<WriteConcern>,
+    pub chained: bool,
+    chain_lock: Arc<Mutex<()>>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, thiserror::Error)]
 pub enum Error {
+    #[error("Invalid Audit ID: {0}")]
     InvalidId(String),
+    #[error("Empty Audit collection")]
     EmptyCollection,
+    #[error("Empty text search")]
     EmptyTextSearch,
-    MongoDb(MongodbError),
+    #[error("MongoDb Error: {0}")]
+    MongoDb(#[source] MongodbError),
+    #[error("ObjectId Error: {0}")]
     ObjectId(String),
+    #[error("Failed to append to audit chain: {0}")]
+    ChainConflict(String),
 }
 
-impl Display for Error {
+impl AuditRepository {
     /// # Summary
     ///
-    /// Display the Error.
+    /// Create a new AuditRepository.
     ///
     /// # Arguments
     ///
-    /// * `f` - A mutable reference to a Formatter.
+    /// * `collection` - The collection name.
     ///
     /// # Returns
     ///
-    /// A std::fmt::Result.
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Error::InvalidId(id) => write!(f, "Invalid Audit ID: {}", id),
-            Error::EmptyCollection => write!(f, "Empty Audit collection"),
-            Error::EmptyTextSearch => write!(f, "Empty text search"),
-            Error::MongoDb(e) => write!(f, "MongoDb Error: {}", e),
-            Error::ObjectId(e) => write!(f, "ObjectId Error: {}", e),
+    /// * `Result<AuditRepository, Error>` - The result of the operation.
+    pub fn new(collection: String) -> Result<AuditRepository, Error> {
+        if collection.is_empty() {
+            return Err(Error::EmptyCollection);
         }
+
+        Ok(AuditRepository {
+            collection,
+            write_concern: None,
+            chained: false,
+            chain_lock: Arc::new(Mutex::new(())),
+        })
     }
-}
 
-impl AuditRepository {
     /// # Summary
     ///
-    /// Create a new AuditRepository.
+    /// Set the write concern that is used when creating Audits.
     ///
     /// # Arguments
     ///
-    /// * `collection` - The collection name.
+    /// * `write_concern` - The WriteConcern, or None to use the write concern of the Database.
+    pub fn set_write_concern(&mut self, write_concern: Option<WriteConcern>) {
+        self.write_concern = write_concern;
+    }
+
+    /// # Summary
     ///
-    /// # Returns
+    /// Set whether new Audits are chained to the previous record of their partition, making the audit trail
+    /// tamper-evident.
     ///
-    /// * `Result<AuditRepository, Error>` - The result of the operation.
-    pub fn new(collection: String) -> Result<AuditRepository, Error> {
-        if collection.is_empty() {
-            return Err(Error::EmptyCollection);
-        }
-
-        Ok(AuditRepository { collection })
+    /// # Arguments
+    ///
+    /// * `chained` - Whether new Audits store the hash of the previous record of their partition.
+    pub fn set_chained(&mut self, chained: bool) {
+        self.chained = chained;
     }
 
     /// # Summary
@@ -76,15 +97,111 @@ impl AuditRepository {
     /// # Returns
     ///
     /// * `Result<(), Error>` - The result of the operation.
-    pub async fn create(&self, audit: Audit, db: &Database) -> Result<(), Error> {
-        match db
-            .collection::<Audit>(&self.collection)
-            .insert_one(audit, None)
-            .await
-        {
-            Ok(_) => Ok(()),
-            Err(e) => Err(Error::MongoDb(e)),
+    pub async fn create(&self, mut audit: Audit, db: &Database) -> Result<(), Error> {
+        let options = CollectionOptions::builder()
+            .write_concern(self.write_concern.clone())
+            .build();
+        let collection = db.collection_with_options::<Audit>(&self.collection, options);
+
+        if !self.chained {
+            return match collection.insert_one(audit, None).await {
+                Ok(_) => Ok(()),
+                Err(e) => Err(Error::MongoDb(e)),
+            };
+        }
+
+        // Chained writes of this instance are serialized, while the unique index on the sequence of a partition
+        // rejects a record that was linked concurrently by another instance
+        let _guard = self.chain_lock.lock().await;
+        let partition = audit.partition();
+
+        for _ in 0..MAX_CHAIN_ATTEMPTS {
+            let find_options = FindOneOptions::builder()
+                .sort(doc! { "chain.sequence": -1 })
+                .build();
+
+            let last = match collection
+                .find_one(doc! { "chain.partition": &partition }, find_options)
+                .await
+            {
+                Ok(d) => d,
+                Err(e) => return Err(Error::MongoDb(e)),
+            };
+
+            audit.link(last.and_then(|a| a.chain).as_ref());
+
+            match collection.insert_one(&audit, None).await {
+                Ok(_) => return Ok(()),
+                Err(e) => match e.kind.as_ref() {
+                    ErrorKind::Write(WriteFailure::WriteError(w)) if w.code == 11000 => continue,
+                    _ => return Err(Error::MongoDb(e)),
+                },
+            }
+        }
+
+        Err(Error::ChainConflict(partition))
+    }
+
+    /// # Summary
+    ///
+    /// Verify the hash chains of the audit trail. Every record is recomputed and compared to the hash that was stored,
+    /// and every record must link to the record that precedes it without gaps in the sequence.
+    ///
+    /// # Arguments
+    ///
+    /// * `partition` - The partition to verify, or None to verify every partition.
+    /// * `db` - The Database that contains the Audits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let verifications = audit_repository.verify_chain(Some("user"), &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<AuditChainVerification>, Error>` - The verification of every partition or the Error that occurred.
+    pub async fn verify_chain(
+        &self,
+        partition: Option<&str>,
+        db: &Database,
+    ) -> Result<Vec<AuditChainVerification>, Error> {
+        let collection = db.collection::<Audit>(&self.collection);
+
+        let partitions: Vec<String> = match partition {
+            Some(p) => vec![p.to_string()],
+            None => match collection.distinct("chain.partition", None, None).await {
+                Ok(d) => d
+                    .into_iter()
+                    .filter_map(|p| p.as_str().map(String::from))
+                    .collect(),
+                Err(e) => return Err(Error::MongoDb(e)),
+            },
+        };
+
+        let mut res: Vec<AuditChainVerification> = vec![];
+        for partition in partitions {
+            let find_options = FindOptions::builder()
+                .sort(doc! { "chain.sequence": 1 })
+                .build();
+
+            let mut cursor = match collection
+                .find(doc! { "chain.partition": &partition }, find_options)
+                .await
+            {
+                Ok(d) => d,
+                Err(e) => return Err(Error::MongoDb(e)),
+            };
+
+            let mut verification = AuditChainVerification::new(partition);
+            while let Some(audit) = cursor.try_next().await.map_err(Error::MongoDb)? {
+                verification.check(&audit);
+            }
+
+            res.push(verification);
         }
+
+        Ok(res)
     }
 
     /// # Summary
@@ -163,13 +280,180 @@ impl AuditRepository {
 
     /// # Summary
     ///
-    /// Search for Audit entities.
+    /// Find the Audits that were created within a period of time, oldest first.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The start of the period, inclusive.
+    /// * `until` - The end of the period, exclusive.
+    /// * `db` - The Database to find the Audits in.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let audits = audit_repository.find_created_between(Utc::now() - Duration::days(1), Utc::now(), &db).await;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Audit>, Error>` - The result of the operation.
+    pub async fn find_created_between(
+        &self,
+        from: DateTime<Utc>,
+        until: DateTime<Utc>,
+        db: &Database,
+    ) -> Result<Vec<Audit>, Error> {
+        let filter = doc! {
+            "createdAt": {
+                "$gte": BsonDateTime::from_chrono(from),
+                "$lt": BsonDateTime::from_chrono(until),
+            },
+        };
+        let find_options = FindOptions::builder()
+            .sort(doc! { "createdAt": 1, "_id": 1 })
+            .build();
+
+        match db
+            .collection::<Audit>(&self.collection)
+            .find(filter, find_options)
+            .await
+        {
+            Ok(r) => r.try_collect().await.map_err(Error::MongoDb),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Attribute the Audits of a User to another User, both the actions that it performed and the changes that were
+    /// made to it. Audits that are part of a hash chain are left unchanged, because modifying them would break the chain.
     ///
     /// # Arguments
     ///
-    /// * `text` - The text to search for.
+    /// * `from` - The ID of the User whose Audits are reattributed.
+    /// * `to` - The ID of the User that the Audits are attributed to.
+    /// * `db` - The Database that contains the Audits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let reattributed = audit_repository.reattribute(other.id, user.id, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64, Error>` - The number of Audits that were reattributed or the Error that occurred.
+    pub async fn reattribute(
+        &self,
+        from: ObjectId,
+        to: ObjectId,
+        db: &Database,
+    ) -> Result<u64, Error> {
+        let collection = db.collection::<Audit>(&self.collection);
+
+        let actions = match collection
+            .update_many(
+                doc! { "userId": from, "chain": { "$exists": false } },
+                doc! { "$set": { "userId": to } },
+                None,
+            )
+            .await
+        {
+            Ok(r) => r.modified_count,
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        let changes = match collection
+            .update_many(
+                doc! {
+                    "resourceId": from,
+                    "resourceType": "user",
+                    "chain": { "$exists": false },
+                },
+                doc! { "$set": { "resourceId": to } },
+                None,
+            )
+            .await
+        {
+            Ok(r) => r.modified_count,
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        Ok(actions + changes)
+    }
+
+    /// # Summary
+    ///
+    /// Find the Audits of the actions that were performed by a User, most recent first.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User that performed the actions.
+    /// * `limit` - The limit of Audits to find.
     /// * `page` - The page of Audits to find.
+    /// * `db` - The Database to find the Audits in.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let audits = audit_repository.find_by_user_id("65620e3ef1c4b2a1d4b9e2a1", Some(100), Some(1), &db).await;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Audit>, Error>` - The result of the operation.
+    pub async fn find_by_user_id(
+        &self,
+        user_id: &str,
+        limit: Option<i64>,
+        page: Option<i64>,
+        db: &Database,
+    ) -> Result<Vec<Audit>, Error> {
+        let user_id = match ObjectId::parse_str(user_id) {
+            Ok(res) => res,
+            Err(e) => {
+                return Err(Error::InvalidId(e.to_string()));
+            }
+        };
+
+        let mut skip: Option<u64> = None;
+
+        if let Some(l) = limit {
+            if l > 1 {
+                if let Some(p) = page {
+                    if p > 1 {
+                        let res = u64::try_from((p - 1) * l).unwrap_or(0);
+                        skip = Some(res);
+                    }
+                }
+            }
+        }
+
+        let find_options = FindOptions::builder()
+            .sort(doc! { "createdAt": -1 })
+            .limit(limit)
+            .skip(skip)
+            .build();
+
+        match db
+            .collection::<Audit>(&self.collection)
+            .find(doc! { "userId": user_id }, find_options)
+            .await
+        {
+            Ok(r) => Ok(r.try_collect().await.unwrap_or_else(|_| vec![])),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Search for the Audit entities that match an AuditFilter.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - The AuditFilter that the Audits must match.
     /// * `limit` - The limit of Audits to find.
+    /// * `page` - The page of Audits to find.
     /// * `db` - The database to use.
     ///
     /// # Example
@@ -181,7 +465,12 @@ impl AuditRepository {
     ///    .unwrap()
     ///    .database("test");
     ///
-    /// let result = audit_repository.search("", Some(100), Some(1), &db).await;
+    /// let filter = AuditFilter {
+    ///     text: Some(String::from("admin")),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let result = audit_repository.search(&filter, Some(100), Some(1), &db).await;
     /// ```
     ///
     /// # Returns
@@ -189,12 +478,12 @@ impl AuditRepository {
     /// * `Result<Vec<Audit>, Error>` - The result of the operation.
     pub async fn search(
         &self,
-        text: &str,
+        filter: &AuditFilter,
         limit: Option<i64>,
         page: Option<i64>,
         db: &Database,
     ) -> Result<Vec<Audit>, Error> {
-        if text.is_empty() {
+        if filter.text.as_deref().is_some_and(str::is_empty) {
             return Err(Error::EmptyTextSearch);
         }
 
@@ -213,11 +502,7 @@ impl AuditRepository {
 
         let find_options = FindOptions::builder().limit(limit).skip(skip).build();
 
-        let filter = doc! {
-            "$text": {
-                "$search": text,
-            },
-        };
+        let filter = filter.to_document();
 
         let cursor = match db
             .collection::<Audit>(&self.collection)
@@ -0,0 +1,2 @@
+pub mod authorization_code_model;
+pub mod authorization_code_repository;
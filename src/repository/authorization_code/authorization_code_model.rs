@@ -0,0 +1,169 @@
+use crate::services::oauth::pkce::CodeChallenge;
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::time::SystemTime;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AuthorizationCode {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "codeHash")]
+    pub code_hash: String,
+    #[serde(rename = "clientId")]
+    pub client_id: ObjectId,
+    #[serde(rename = "userId")]
+    pub user_id: ObjectId,
+    #[serde(rename = "redirectUri")]
+    pub redirect_uri: String,
+    #[serde(rename = "redirectUriRequested", default)]
+    pub redirect_uri_requested: bool,
+    pub scopes: Vec<String>,
+    #[serde(rename = "codeChallenge")]
+    pub code_challenge: Option<CodeChallenge>,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "expiresAt")]
+    pub expires_at: DateTime<Utc>,
+}
+
+impl AuthorizationCode {
+    /// # Summary
+    ///
+    /// Create a new AuthorizationCode.
+    ///
+    /// # Arguments
+    ///
+    /// * `code_hash` - The SHA-256 hash of the code.
+    /// * `client_id` - The ID of the Client that requested the authorization.
+    /// * `user_id` - The ID of the User that approved the authorization.
+    /// * `redirect_uri` - The redirect URI that the code was sent to.
+    /// * `redirect_uri_requested` - Whether the redirect URI was sent with the authorization request.
+    /// * `scopes` - The scopes that were approved.
+    /// * `code_challenge` - The PKCE code challenge of the authorization request, if any.
+    /// * `expires_in` - The amount of seconds after which the AuthorizationCode expires.
+    ///
+    /// # Returns
+    ///
+    /// * `AuthorizationCode` - The new AuthorizationCode.
+    pub fn new(
+        code_hash: String,
+        client_id: ObjectId,
+        user_id: ObjectId,
+        redirect_uri: String,
+        redirect_uri_requested: bool,
+        scopes: Vec<String>,
+        code_challenge: Option<CodeChallenge>,
+        expires_in: u64,
+    ) -> AuthorizationCode {
+        let now: DateTime<Utc> = SystemTime::now().into();
+
+        AuthorizationCode {
+            id: ObjectId::new(),
+            code_hash,
+            client_id,
+            user_id,
+            redirect_uri,
+            redirect_uri_requested,
+            scopes,
+            code_challenge,
+            created_at: now,
+            expires_at: now + chrono::Duration::seconds(expires_in as i64),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check whether the AuthorizationCode has expired.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the AuthorizationCode has expired, false otherwise.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= chrono::Utc::now()
+    }
+
+    /// # Summary
+    ///
+    /// Check whether the redirect URI of a token request matches the AuthorizationCode. If the redirect URI was sent
+    /// with the authorization request, the token request must send the same value (RFC 6749, section 4.1.3).
+    ///
+    /// # Arguments
+    ///
+    /// * `redirect_uri` - The redirect URI of the token request, if any.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the redirect URI matches, false otherwise.
+    pub fn matches_redirect_uri(&self, redirect_uri: Option<&str>) -> bool {
+        match redirect_uri {
+            Some(u) => u == self.redirect_uri,
+            None => !self.redirect_uri_requested,
+        }
+    }
+}
+
+impl Display for AuthorizationCode {
+    /// # Summary
+    ///
+    /// Display the AuthorizationCode, without its code hash.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The Formatter.
+    ///
+    /// # Returns
+    ///
+    /// A std::fmt::Result.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "AuthorizationCode {{ id: {}, client_id: {}, user_id: {}, redirect_uri: {}, scopes: {:?}, expires_at: {} }}",
+            self.id.to_hex(),
+            self.client_id.to_hex(),
+            self.user_id.to_hex(),
+            self.redirect_uri,
+            self.scopes,
+            self.expires_at
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn authorization_code(redirect_uri_requested: bool) -> AuthorizationCode {
+        AuthorizationCode::new(
+            String::from("hash"),
+            ObjectId::new(),
+            ObjectId::new(),
+            String::from("https://billing.example.com/callback"),
+            redirect_uri_requested,
+            vec![],
+            None,
+            60,
+        )
+    }
+
+    #[test]
+    fn requested_redirect_uri_must_be_sent_again() {
+        let code = authorization_code(true);
+
+        assert!(code.matches_redirect_uri(Some("https://billing.example.com/callback")));
+        assert!(!code.matches_redirect_uri(Some("https://evil.example.com/callback")));
+        assert!(!code.matches_redirect_uri(None));
+    }
+
+    #[test]
+    fn omitted_redirect_uri_can_be_omitted_again() {
+        let code = authorization_code(false);
+
+        assert!(code.matches_redirect_uri(Some("https://billing.example.com/callback")));
+        assert!(!code.matches_redirect_uri(Some("https://evil.example.com/callback")));
+        assert!(code.matches_redirect_uri(None));
+    }
+}
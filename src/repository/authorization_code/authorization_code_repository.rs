@@ -0,0 +1,136 @@
+use crate::repository::authorization_code::authorization_code_model::AuthorizationCode;
+use crate::services::oauth::error_code::OAuthErrorCode;
+use crate::services::oauth::pkce::PkceError;
+use mongodb::bson::doc;
+use mongodb::error::Error as MongodbError;
+use mongodb::Database;
+
+#[derive(Clone)]
+pub struct AuthorizationCodeRepository {
+    pub collection: String,
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum Error {
+    #[error("Empty AuthorizationCode collection")]
+    EmptyCollection,
+    #[error("Unsupported response type: {0}")]
+    UnsupportedResponseType(String),
+    #[error("Invalid client ID")]
+    InvalidClient,
+    #[error("The client is not allowed to use this grant type")]
+    UnauthorizedClient,
+    #[error("Invalid redirect URI: {0}")]
+    InvalidRedirectUri(String),
+    #[error("A redirect URI is required")]
+    RedirectUriRequired,
+    #[error("Invalid scope: {0}")]
+    InvalidScope(String),
+    #[error("The user has not consented to the scopes: {0}")]
+    ConsentRequired(String),
+    #[error("Invalid authorization code")]
+    InvalidGrant,
+    #[error("{0}")]
+    Pkce(#[source] PkceError),
+    #[error("MongoDb Error: {0}")]
+    MongoDb(#[source] MongodbError),
+}
+
+impl OAuthErrorCode for Error {
+    /// # Summary
+    ///
+    /// Get the OAuth 2.0 error code that corresponds to the Error, as defined by RFC 6749 and RFC 7636.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<&'static str>` - The OAuth 2.0 error code, or None if the Error is not an OAuth 2.0 error.
+    fn oauth_error_code(&self) -> Option<&'static str> {
+        match self {
+            Error::UnsupportedResponseType(_) => Some("unsupported_response_type"),
+            Error::InvalidClient => Some("invalid_client"),
+            Error::UnauthorizedClient => Some("unauthorized_client"),
+            Error::InvalidRedirectUri(_) | Error::RedirectUriRequired => Some("invalid_request"),
+            Error::InvalidScope(_) => Some("invalid_scope"),
+            Error::ConsentRequired(_) => Some("consent_required"),
+            Error::InvalidGrant => Some("invalid_grant"),
+            // A code verifier that does not match the code challenge invalidates the grant, as required by RFC 7636
+            Error::Pkce(PkceError::VerifierRequired) | Error::Pkce(PkceError::InvalidVerifier) => {
+                Some("invalid_grant")
+            }
+            Error::Pkce(_) => Some("invalid_request"),
+            Error::EmptyCollection | Error::MongoDb(_) => None,
+        }
+    }
+}
+
+impl AuthorizationCodeRepository {
+    /// # Summary
+    ///
+    /// Create a new AuthorizationCodeRepository.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection` - The collection name.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<AuthorizationCodeRepository, Error>` - The result of the operation.
+    pub fn new(collection: String) -> Result<AuthorizationCodeRepository, Error> {
+        if collection.is_empty() {
+            return Err(Error::EmptyCollection);
+        }
+
+        Ok(AuthorizationCodeRepository { collection })
+    }
+
+    /// # Summary
+    ///
+    /// Create a new AuthorizationCode.
+    ///
+    /// # Arguments
+    ///
+    /// * `authorization_code` - The AuthorizationCode to create.
+    /// * `db` - The Database to create the AuthorizationCode in.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    pub async fn create(
+        &self,
+        authorization_code: AuthorizationCode,
+        db: &Database,
+    ) -> Result<(), Error> {
+        match db
+            .collection::<AuthorizationCode>(&self.collection)
+            .insert_one(authorization_code, None)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Find an AuthorizationCode by the hash of its code and delete it in the same operation, so that a code can
+    /// only be exchanged once, even by concurrent requests.
+    ///
+    /// # Arguments
+    ///
+    /// * `code_hash` - The SHA-256 hash of the code.
+    /// * `db` - The Database that holds the AuthorizationCode.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<AuthorizationCode>, Error>` - The AuthorizationCode, or None if it does not exist or was already used.
+    pub async fn take_by_code_hash(
+        &self,
+        code_hash: &str,
+        db: &Database,
+    ) -> Result<Option<AuthorizationCode>, Error> {
+        db.collection::<AuthorizationCode>(&self.collection)
+            .find_one_and_delete(doc! {"codeHash": code_hash}, None)
+            .await
+            .map_err(Error::MongoDb)
+    }
+}
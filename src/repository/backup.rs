@@ -0,0 +1,2 @@
+pub mod backup_model;
+pub mod backup_repository;
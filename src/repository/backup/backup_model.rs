@@ -0,0 +1,63 @@
+use crate::repository::permission::permission_model::Permission;
+use crate::repository::role::role_model::Role;
+use crate::repository::user::user_model::User;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+
+/// The version of the backup format that is produced by this version of auth-rs.
+pub const BACKUP_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+pub struct Backup {
+    pub version: u32,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "includesPasswords")]
+    pub includes_passwords: bool,
+    pub permissions: Vec<Permission>,
+    pub roles: Vec<Role>,
+    pub users: Vec<User>,
+}
+
+impl Backup {
+    /// # Summary
+    ///
+    /// Create a new Backup.
+    ///
+    /// # Arguments
+    ///
+    /// * `includes_passwords` - Whether the password hashes of the Users are part of the Backup.
+    /// * `permissions` - The Permissions of the Backup.
+    /// * `roles` - The Roles of the Backup.
+    /// * `users` - The Users of the Backup.
+    ///
+    /// # Returns
+    ///
+    /// * `Backup` - The new Backup.
+    pub fn new(
+        includes_passwords: bool,
+        permissions: Vec<Permission>,
+        roles: Vec<Role>,
+        users: Vec<User>,
+    ) -> Backup {
+        let now: DateTime<Utc> = SystemTime::now().into();
+
+        Backup {
+            version: BACKUP_VERSION,
+            created_at: now,
+            includes_passwords,
+            permissions,
+            roles,
+            users,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RestoreSummary {
+    pub permissions: u64,
+    pub roles: u64,
+    pub users: u64,
+}
@@ -0,0 +1,197 @@
+use crate::repository::backup::backup_model::{Backup, RestoreSummary};
+use crate::repository::permission::permission_model::Permission;
+use crate::repository::role::role_model::Role;
+use crate::repository::user::user_model::User;
+use futures::TryStreamExt;
+use mongodb::bson::doc;
+use mongodb::error::Error as MongodbError;
+use mongodb::options::ReplaceOptions;
+use mongodb::Database;
+
+#[derive(Clone)]
+pub struct BackupRepository {
+    pub permission_collection: String,
+    pub role_collection: String,
+    pub user_collection: String,
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum Error {
+    #[error("Empty collection")]
+    EmptyCollection,
+    #[error("Backups are disabled")]
+    BackupDisabled,
+    #[error("Invalid backup: {0}")]
+    InvalidBackup(String),
+    #[error("Unsupported backup version: {0}")]
+    UnsupportedVersion(u32),
+    #[error("Encryption error: {0}")]
+    Encryption(String),
+    #[error("MongoDb Error: {0}")]
+    MongoDb(#[source] MongodbError),
+}
+
+impl BackupRepository {
+    /// # Summary
+    ///
+    /// Create a new BackupRepository.
+    ///
+    /// # Arguments
+    ///
+    /// * `permission_collection` - The name of the Permission collection.
+    /// * `role_collection` - The name of the Role collection.
+    /// * `user_collection` - The name of the User collection.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let backup_repository = BackupRepository::new(
+    ///     String::from("permissions"),
+    ///     String::from("roles"),
+    ///     String::from("users"),
+    /// );
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<BackupRepository, Error>` - The result of the operation.
+    pub fn new(
+        permission_collection: String,
+        role_collection: String,
+        user_collection: String,
+    ) -> Result<BackupRepository, Error> {
+        if permission_collection.is_empty()
+            || role_collection.is_empty()
+            || user_collection.is_empty()
+        {
+            return Err(Error::EmptyCollection);
+        }
+
+        Ok(BackupRepository {
+            permission_collection,
+            role_collection,
+            user_collection,
+        })
+    }
+
+    /// # Summary
+    ///
+    /// Export all Permissions, Roles and Users.
+    ///
+    /// # Arguments
+    ///
+    /// * `include_passwords` - Whether the password hashes, the TOTP secrets and the recovery codes of the Users should be exported.
+    /// * `db` - The Database to export the entities from.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Backup, Error>` - The result of the operation.
+    pub async fn export(&self, include_passwords: bool, db: &Database) -> Result<Backup, Error> {
+        let permissions: Vec<Permission> = match db
+            .collection::<Permission>(&self.permission_collection)
+            .find(None, None)
+            .await
+        {
+            Ok(r) => r.try_collect().await.map_err(Error::MongoDb)?,
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        let roles: Vec<Role> = match db
+            .collection::<Role>(&self.role_collection)
+            .find(None, None)
+            .await
+        {
+            Ok(r) => r.try_collect().await.map_err(Error::MongoDb)?,
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        let mut users: Vec<User> = match db
+            .collection::<User>(&self.user_collection)
+            .find(None, None)
+            .await
+        {
+            Ok(r) => r.try_collect().await.map_err(Error::MongoDb)?,
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        if !include_passwords {
+            for user in users.iter_mut() {
+                user.password = String::new();
+                user.totp = None;
+                user.recovery_codes = vec![];
+            }
+        }
+
+        Ok(Backup::new(include_passwords, permissions, roles, users))
+    }
+
+    /// # Summary
+    ///
+    /// Import the Permissions, Roles and Users of a Backup.
+    /// Existing entities with the same ID are replaced. Users without a password hash keep their current password, TOTP
+    /// secret and recovery codes.
+    ///
+    /// # Arguments
+    ///
+    /// * `backup` - The Backup to import.
+    /// * `db` - The Database to import the entities into.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<RestoreSummary, Error>` - The result of the operation.
+    pub async fn import(&self, backup: Backup, db: &Database) -> Result<RestoreSummary, Error> {
+        let options = ReplaceOptions::builder().upsert(true).build();
+
+        let permission_collection = db.collection::<Permission>(&self.permission_collection);
+        for permission in &backup.permissions {
+            if let Err(e) = permission_collection
+                .replace_one(doc! {"_id": permission.id}, permission, options.clone())
+                .await
+            {
+                return Err(Error::MongoDb(e));
+            }
+        }
+
+        let role_collection = db.collection::<Role>(&self.role_collection);
+        for role in &backup.roles {
+            if let Err(e) = role_collection
+                .replace_one(doc! {"_id": role.id}, role, options.clone())
+                .await
+            {
+                return Err(Error::MongoDb(e));
+            }
+        }
+
+        let user_collection = db.collection::<User>(&self.user_collection);
+        for mut user in backup.users.iter().cloned() {
+            if user.password.is_empty() {
+                match user_collection.find_one(doc! {"_id": user.id}, None).await {
+                    Ok(Some(existing)) => {
+                        user.password = existing.password;
+                        if user.totp.is_none() {
+                            user.totp = existing.totp;
+                        }
+                        if user.recovery_codes.is_empty() {
+                            user.recovery_codes = existing.recovery_codes;
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(e) => return Err(Error::MongoDb(e)),
+                }
+            }
+
+            if let Err(e) = user_collection
+                .replace_one(doc! {"_id": user.id}, &user, options.clone())
+                .await
+            {
+                return Err(Error::MongoDb(e));
+            }
+        }
+
+        Ok(RestoreSummary {
+            permissions: backup.permissions.len() as u64,
+            roles: backup.roles.len() as u64,
+            users: backup.users.len() as u64,
+        })
+    }
+}
@@ -0,0 +1,2 @@
+pub mod client_model;
+pub mod client_repository;
@@ -0,0 +1,152 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::time::SystemTime;
+
+/// The grant type that is used by clients to exchange an authorization code for an access token.
+pub const AUTHORIZATION_CODE_GRANT_TYPE: &str = "authorization_code";
+
+/// The grant type that is used by clients to exchange their credentials for an access token.
+pub const CLIENT_CREDENTIALS_GRANT_TYPE: &str = "client_credentials";
+
+/// The grant type that is used by devices to exchange a device code for an access token.
+pub const DEVICE_CODE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+/// The grant types that can be allowed for a Client.
+pub const GRANT_TYPES: &[&str] = &[
+    AUTHORIZATION_CODE_GRANT_TYPE,
+    CLIENT_CREDENTIALS_GRANT_TYPE,
+    DEVICE_CODE_GRANT_TYPE,
+];
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Client {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(rename = "secretHash")]
+    pub secret_hash: String,
+    pub permissions: Option<Vec<ObjectId>>,
+    pub enabled: bool,
+    #[serde(default)]
+    pub public: bool,
+    #[serde(rename = "redirectUris", default)]
+    pub redirect_uris: Vec<String>,
+    #[serde(rename = "grantTypes", default = "default_grant_types")]
+    pub grant_types: Vec<String>,
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    #[serde(rename = "tokenVersion", default)]
+    pub token_version: i64,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Client {
+    /// # Summary
+    ///
+    /// Create a new Client without a secret. The secret is generated when the Client is created by the ClientService.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the Client.
+    /// * `description` - The description of the Client.
+    /// * `permissions` - The Permissions that are granted to the tokens of the Client.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let client = Client::new(String::from("Billing"), None, Some(vec![ObjectId::new()]));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Client` - The new Client.
+    pub fn new(
+        name: String,
+        description: Option<String>,
+        permissions: Option<Vec<ObjectId>>,
+    ) -> Self {
+        let now: DateTime<Utc> = SystemTime::now().into();
+
+        Client {
+            id: ObjectId::new(),
+            name,
+            description,
+            secret_hash: String::new(),
+            permissions,
+            enabled: true,
+            public: false,
+            redirect_uris: vec![],
+            grant_types: default_grant_types(),
+            scopes: vec![],
+            token_version: 0,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check whether a token that was issued with the given token version has been revoked.
+    ///
+    /// # Arguments
+    ///
+    /// * `token_version` - The token version of the token.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the token has been revoked.
+    pub fn is_token_revoked(&self, token_version: i64) -> bool {
+        token_version != self.token_version
+    }
+
+    /// # Summary
+    ///
+    /// Check whether the Client is allowed to use a grant type.
+    ///
+    /// # Arguments
+    ///
+    /// * `grant_type` - The grant type.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the grant type is allowed.
+    pub fn allows_grant(&self, grant_type: &str) -> bool {
+        self.grant_types.iter().any(|g| g == grant_type)
+    }
+}
+
+/// # Summary
+///
+/// Get the grant types of a Client that did not specify any, which is the client credentials grant.
+///
+/// # Returns
+///
+/// * `Vec<String>` - The default grant types.
+fn default_grant_types() -> Vec<String> {
+    vec![String::from(CLIENT_CREDENTIALS_GRANT_TYPE)]
+}
+
+impl Display for Client {
+    /// # Summary
+    ///
+    /// Display the Client, without its secret hash.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The Formatter.
+    ///
+    /// # Returns
+    ///
+    /// * `std::fmt::Result` - The result of the display.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Client: {{ id: {}, name: {}, description: {}, permissions: {:?}, enabled: {}, public: {}, redirect_uris: {:?}, grant_types: {:?}, scopes: {:?}, created_at: {}, updated_at: {} }}", self.id.to_hex(), self.name, self.description.as_ref().unwrap_or(&String::from("None")), self.permissions.as_ref().unwrap_or(&vec![]), self.enabled, self.public, self.redirect_uris, self.grant_types, self.scopes, self.created_at, self.updated_at)
+    }
+}
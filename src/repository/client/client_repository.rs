@@ -0,0 +1,327 @@
+use crate::repository::audit::audit_repository::Error as AuditError;
+use crate::repository::client::client_model::Client;
+use futures::TryStreamExt;
+use mongodb::bson::doc;
+use mongodb::bson::oid::ObjectId;
+use mongodb::error::Error as MongoError;
+use mongodb::options::FindOptions;
+use mongodb::Database;
+
+#[derive(Clone)]
+pub struct ClientRepository {
+    pub collection: String,
+}
+
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Invalid Client ID: {0}")]
+    InvalidId(String),
+    #[error("Empty collection")]
+    EmptyCollection,
+    #[error("Empty Client ID")]
+    EmptyId,
+    #[error("Empty Client name")]
+    EmptyName,
+    #[error("Client not found: {0}")]
+    ClientNotFound(String),
+    #[error("Invalid client credentials")]
+    InvalidCredentials,
+    #[error("Unsupported grant type: {0}")]
+    InvalidGrantType(String),
+    #[error("Invalid redirect URI: {0}")]
+    InvalidRedirectUri(String),
+    #[error("Invalid scope: {0}")]
+    InvalidScope(String),
+    #[error("Public clients cannot use the client credentials grant")]
+    PublicClientCredentials,
+    #[error("Audit error: {0}")]
+    Audit(#[source] AuditError),
+    #[error("MongoDB error: {0}")]
+    MongoDb(#[source] MongoError),
+}
+
+impl ClientRepository {
+    /// # Summary
+    ///
+    /// Creates a new ClientRepository instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection` - A String that holds the collection name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let client_repository = match ClientRepository::new("clients".to_string()) {
+    ///    Ok(d) => d,
+    ///    Err(e) => panic!("Failed to initialize Client repository: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A ClientRepository instance.
+    pub fn new(collection: String) -> Result<ClientRepository, Error> {
+        if collection.is_empty() {
+            return Err(Error::EmptyCollection);
+        }
+
+        Ok(ClientRepository { collection })
+    }
+
+    /// # Summary
+    ///
+    /// Create a new client.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - A Client instance.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let client = match client_repository.create(client, &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to create Client: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the created Client instance or an Error.
+    pub async fn create(&self, client: Client, db: &Database) -> Result<Client, Error> {
+        if client.name.is_empty() {
+            return Err(Error::EmptyName);
+        }
+
+        let client_id = client.id.to_hex();
+
+        match db
+            .collection::<Client>(&self.collection)
+            .insert_one(client, None)
+            .await
+        {
+            Ok(_) => (),
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        match self.find_by_id(&client_id, db).await? {
+            Some(c) => Ok(c),
+            None => Err(Error::ClientNotFound(client_id)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Find all clients.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - An optional i64 that holds the limit.
+    /// * `page` - An optional i64 that holds the page.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let clients = match client_repository.find_all(Some(10), Some(1), &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to find Clients: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with a vector of Client instances or an Error.
+    pub async fn find_all(
+        &self,
+        limit: Option<i64>,
+        page: Option<i64>,
+        db: &Database,
+    ) -> Result<Vec<Client>, Error> {
+        let mut skip: Option<u64> = None;
+
+        if let Some(l) = limit {
+            if l > 1 {
+                if let Some(p) = page {
+                    if p > 1 {
+                        let res = u64::try_from((p - 1) * l).unwrap_or(0);
+                        skip = Some(res);
+                    }
+                }
+            }
+        }
+
+        let find_options = FindOptions::builder().limit(limit).skip(skip).build();
+
+        let cursor = match db
+            .collection::<Client>(&self.collection)
+            .find(None, find_options)
+            .await
+        {
+            Ok(d) => d,
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        Ok(cursor.try_collect().await.unwrap_or_else(|_| vec![]))
+    }
+
+    /// # Summary
+    ///
+    /// Find a client by its ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - A string slice that holds the ID.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let client = match client_repository.find_by_id("id", &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to find Client by ID: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with an Option of a Client instance or an Error.
+    pub async fn find_by_id(&self, id: &str, db: &Database) -> Result<Option<Client>, Error> {
+        if id.is_empty() {
+            return Err(Error::EmptyId);
+        }
+
+        let target_object_id = match ObjectId::parse_str(id) {
+            Ok(res) => res,
+            Err(e) => {
+                return Err(Error::InvalidId(e.to_string()));
+            }
+        };
+
+        let filter = doc! {
+            "_id": target_object_id,
+        };
+
+        match db
+            .collection::<Client>(&self.collection)
+            .find_one(filter, None)
+            .await
+        {
+            Ok(d) => Ok(d),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Update a client, including its secret hash and token version.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - A Client instance.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let client = match client_repository.update(client, &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to update Client: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the updated Client instance or an Error.
+    pub async fn update(&self, client: Client, db: &Database) -> Result<Client, Error> {
+        if client.name.is_empty() {
+            return Err(Error::EmptyName);
+        }
+
+        let client_id = client.id.to_hex();
+        let filter = doc! {
+            "_id": client.id,
+        };
+
+        let update = doc! {
+            "$set": {
+                "name": client.name,
+                "description": client.description,
+                "secretHash": client.secret_hash,
+                "permissions": client.permissions,
+                "enabled": client.enabled,
+                "public": client.public,
+                "redirectUris": client.redirect_uris,
+                "grantTypes": client.grant_types,
+                "scopes": client.scopes,
+                "tokenVersion": client.token_version,
+                "updatedAt": mongodb::bson::DateTime::now(),
+            }
+        };
+
+        match db
+            .collection::<Client>(&self.collection)
+            .update_one(filter, update, None)
+            .await
+        {
+            Ok(r) if r.matched_count == 0 => return Err(Error::ClientNotFound(client_id)),
+            Ok(_) => (),
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        match self.find_by_id(&client_id, db).await? {
+            Some(c) => Ok(c),
+            None => Err(Error::ClientNotFound(client_id)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Delete a client by its ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - A string slice that holds the ID.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// match client_repository.delete("id", &db).await {
+    ///   Ok(_) => (),
+    ///   Err(e) => panic!("Failed to delete Client: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the unit type or an Error.
+    pub async fn delete(&self, id: &str, db: &Database) -> Result<(), Error> {
+        if id.is_empty() {
+            return Err(Error::EmptyId);
+        }
+
+        let target_object_id = match ObjectId::parse_str(id) {
+            Ok(res) => res,
+            Err(e) => {
+                return Err(Error::InvalidId(e.to_string()));
+            }
+        };
+
+        let filter = doc! {
+            "_id": target_object_id,
+        };
+
+        match db
+            .collection::<Client>(&self.collection)
+            .delete_one(filter, None)
+            .await
+        {
+            Ok(r) if r.deleted_count == 0 => Err(Error::ClientNotFound(id.to_string())),
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+}
@@ -0,0 +1,2 @@
+pub mod consent_model;
+pub mod consent_repository;
@@ -0,0 +1,47 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Consent {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "userId")]
+    pub user_id: ObjectId,
+    #[serde(rename = "clientId")]
+    pub client_id: ObjectId,
+    pub scopes: Vec<String>,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Display for Consent {
+    /// # Summary
+    ///
+    /// Display the Consent.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The Formatter.
+    ///
+    /// # Returns
+    ///
+    /// * `std::fmt::Result` - The result of the display.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Consent: {{ id: {}, user_id: {}, client_id: {}, scopes: {:?}, created_at: {}, updated_at: {} }}",
+            self.id.to_hex(),
+            self.user_id.to_hex(),
+            self.client_id.to_hex(),
+            self.scopes,
+            self.created_at,
+            self.updated_at
+        )
+    }
+}
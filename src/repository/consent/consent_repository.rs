@@ -0,0 +1,344 @@
+use crate::repository::audit::audit_repository::Error as AuditError;
+use crate::repository::consent::consent_model::Consent;
+use futures::TryStreamExt;
+use mongodb::bson::doc;
+use mongodb::bson::oid::ObjectId;
+use mongodb::error::Error as MongoError;
+use mongodb::options::{FindOneAndUpdateOptions, FindOptions, ReturnDocument};
+use mongodb::Database;
+
+#[derive(Clone)]
+pub struct ConsentRepository {
+    pub collection: String,
+}
+
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Invalid Consent ID: {0}")]
+    InvalidId(String),
+    #[error("Empty collection")]
+    EmptyCollection,
+    #[error("Empty Consent ID")]
+    EmptyId,
+    #[error("Empty scopes")]
+    EmptyScopes,
+    #[error("Consent not found: {0}")]
+    ConsentNotFound(String),
+    #[error("Client not found: {0}")]
+    ClientNotFound(String),
+    #[error("Invalid scope: {0}")]
+    InvalidScope(String),
+    #[error("Audit error: {0}")]
+    Audit(#[source] AuditError),
+    #[error("MongoDB error: {0}")]
+    MongoDb(#[source] MongoError),
+}
+
+impl ConsentRepository {
+    /// # Summary
+    ///
+    /// Creates a new ConsentRepository instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection` - A String that holds the collection name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let consent_repository = match ConsentRepository::new("consents".to_string()) {
+    ///    Ok(d) => d,
+    ///    Err(e) => panic!("Failed to initialize Consent repository: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A ConsentRepository instance.
+    pub fn new(collection: String) -> Result<ConsentRepository, Error> {
+        if collection.is_empty() {
+            return Err(Error::EmptyCollection);
+        }
+
+        Ok(ConsentRepository { collection })
+    }
+
+    /// # Summary
+    ///
+    /// Record that a user consented to scopes of a client. The scopes are added to the existing Consent of the user
+    /// for the client, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    /// * `client_id` - The ID of the client.
+    /// * `scopes` - The scopes that the user consented to.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let consent = match consent_repository.save(user_id, client_id, scopes, &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to save Consent: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the updated or created Consent instance or an Error.
+    pub async fn save(
+        &self,
+        user_id: ObjectId,
+        client_id: ObjectId,
+        scopes: Vec<String>,
+        db: &Database,
+    ) -> Result<Consent, Error> {
+        let now = mongodb::bson::DateTime::now();
+
+        let filter = doc! {
+            "userId": user_id,
+            "clientId": client_id,
+        };
+
+        let update = doc! {
+            "$setOnInsert": {
+                "_id": ObjectId::new(),
+                "createdAt": now,
+            },
+            "$addToSet": {
+                "scopes": { "$each": scopes },
+            },
+            "$set": {
+                "updatedAt": now,
+            },
+        };
+
+        let options = FindOneAndUpdateOptions::builder()
+            .upsert(true)
+            .return_document(ReturnDocument::After)
+            .build();
+
+        match db
+            .collection::<Consent>(&self.collection)
+            .find_one_and_update(filter, update, options)
+            .await
+        {
+            Ok(Some(c)) => Ok(c),
+            Ok(None) => Err(Error::ConsentNotFound(client_id.to_hex())),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Find all consents of a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let consents = match consent_repository.find_by_user_id(user_id, &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to find Consents: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with a vector of Consent instances or an Error.
+    pub async fn find_by_user_id(
+        &self,
+        user_id: ObjectId,
+        db: &Database,
+    ) -> Result<Vec<Consent>, Error> {
+        let filter = doc! {
+            "userId": user_id,
+        };
+        let find_options = FindOptions::builder().sort(doc! { "createdAt": 1 }).build();
+
+        let cursor = match db
+            .collection::<Consent>(&self.collection)
+            .find(filter, find_options)
+            .await
+        {
+            Ok(d) => d,
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        Ok(cursor.try_collect().await.unwrap_or_else(|_| vec![]))
+    }
+
+    /// # Summary
+    ///
+    /// Find the Consent of a user for a client.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    /// * `client_id` - The ID of the client.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let consent = match consent_repository.find_by_user_and_client(user_id, client_id, &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to find Consent: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with an Option of a Consent instance or an Error.
+    pub async fn find_by_user_and_client(
+        &self,
+        user_id: ObjectId,
+        client_id: ObjectId,
+        db: &Database,
+    ) -> Result<Option<Consent>, Error> {
+        let filter = doc! {
+            "userId": user_id,
+            "clientId": client_id,
+        };
+
+        match db
+            .collection::<Consent>(&self.collection)
+            .find_one(filter, None)
+            .await
+        {
+            Ok(d) => Ok(d),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Delete a Consent of a user. Consents of other users cannot be deleted.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - A string slice that holds the ID of the Consent.
+    /// * `user_id` - The ID of the user that owns the Consent.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// match consent_repository.delete("id", user_id, &db).await {
+    ///   Ok(_) => (),
+    ///   Err(e) => panic!("Failed to delete Consent: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the unit type or an Error.
+    pub async fn delete(&self, id: &str, user_id: ObjectId, db: &Database) -> Result<(), Error> {
+        if id.is_empty() {
+            return Err(Error::EmptyId);
+        }
+
+        let target_object_id = match ObjectId::parse_str(id) {
+            Ok(res) => res,
+            Err(e) => {
+                return Err(Error::InvalidId(e.to_string()));
+            }
+        };
+
+        let filter = doc! {
+            "_id": target_object_id,
+            "userId": user_id,
+        };
+
+        match db
+            .collection::<Consent>(&self.collection)
+            .delete_one(filter, None)
+            .await
+        {
+            Ok(r) if r.deleted_count == 0 => Err(Error::ConsentNotFound(id.to_string())),
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Delete all consents for a client.
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - The ID of the client.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// match consent_repository.delete_by_client_id(client_id, &db).await {
+    ///   Ok(_) => (),
+    ///   Err(e) => panic!("Failed to delete Consents: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the unit type or an Error.
+    pub async fn delete_by_client_id(
+        &self,
+        client_id: ObjectId,
+        db: &Database,
+    ) -> Result<(), Error> {
+        let filter = doc! {
+            "clientId": client_id,
+        };
+
+        match db
+            .collection::<Consent>(&self.collection)
+            .delete_many(filter, None)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Delete all consents of a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// match consent_repository.delete_by_user_id(user_id, &db).await {
+    ///   Ok(_) => (),
+    ///   Err(e) => panic!("Failed to delete Consents: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the unit type or an Error.
+    pub async fn delete_by_user_id(&self, user_id: ObjectId, db: &Database) -> Result<(), Error> {
+        let filter = doc! {
+            "userId": user_id,
+        };
+
+        match db
+            .collection::<Consent>(&self.collection)
+            .delete_many(filter, None)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod credential_ceremony;
+pub mod credential_model;
+pub mod credential_repository;
@@ -0,0 +1,62 @@
+use crate::services::mfa::webauthn_service::{PasskeyAuthentication, PasskeyRegistration};
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum CeremonyState {
+    #[serde(rename = "registration")]
+    Registration {
+        name: String,
+        state: PasskeyRegistration,
+    },
+    #[serde(rename = "authentication")]
+    Authentication { state: PasskeyAuthentication },
+}
+
+/// The server-side state of a WebAuthn ceremony, which holds the challenge that the authenticator has to sign.
+#[derive(Serialize, Deserialize)]
+pub struct CredentialCeremony {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "userId")]
+    pub user_id: ObjectId,
+    pub state: CeremonyState,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "expiresAt")]
+    pub expires_at: DateTime<Utc>,
+}
+
+impl CredentialCeremony {
+    /// # Summary
+    ///
+    /// Create a new CredentialCeremony.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User that performs the ceremony.
+    /// * `state` - The state of the ceremony.
+    /// * `expires_in` - The number of seconds after which the ceremony expires.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let ceremony = CredentialCeremony::new(user.id, CeremonyState::Authentication { state }, 300);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `CredentialCeremony` - The new CredentialCeremony.
+    pub fn new(user_id: ObjectId, state: CeremonyState, expires_in: u64) -> CredentialCeremony {
+        let now: DateTime<Utc> = SystemTime::now().into();
+
+        CredentialCeremony {
+            id: ObjectId::new(),
+            user_id,
+            state,
+            expires_at: now + chrono::Duration::seconds(expires_in as i64),
+        }
+    }
+}
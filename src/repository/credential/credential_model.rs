@@ -0,0 +1,90 @@
+use crate::services::mfa::webauthn_service::Passkey;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::time::SystemTime;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Credential {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "userId")]
+    pub user_id: ObjectId,
+    pub name: String,
+    #[serde(rename = "credentialId")]
+    pub credential_id: String,
+    pub passkey: Passkey,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(
+        rename = "lastUsedAt",
+        default,
+        with = "crate::repository::user::user_model::optional_chrono_datetime_as_bson_datetime"
+    )]
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl Credential {
+    /// # Summary
+    ///
+    /// Create a new Credential for a passkey that was registered by a User.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User that registered the passkey.
+    /// * `name` - The name that the User gave the passkey.
+    /// * `passkey` - The passkey, which holds the public key of the credential.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let credential = Credential::new(user.id, String::from("Laptop"), passkey);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Credential` - The new Credential.
+    pub fn new(user_id: ObjectId, name: String, passkey: Passkey) -> Credential {
+        Credential {
+            id: ObjectId::new(),
+            user_id,
+            name,
+            credential_id: URL_SAFE_NO_PAD.encode(&passkey.cred_id().0),
+            passkey,
+            created_at: SystemTime::now().into(),
+            last_used_at: None,
+        }
+    }
+}
+
+impl Display for Credential {
+    /// # Summary
+    ///
+    /// Display the Credential, without its public key.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The Formatter.
+    ///
+    /// # Returns
+    ///
+    /// * `std::fmt::Result` - The result of the display.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Credential: {{ id: {}, user_id: {}, name: {}, credential_id: {}, created_at: {}, last_used_at: {} }}",
+            self.id.to_hex(),
+            self.user_id.to_hex(),
+            self.name,
+            self.credential_id,
+            self.created_at,
+            self.last_used_at
+                .map(|l| l.to_string())
+                .unwrap_or(String::from("None"))
+        )
+    }
+}
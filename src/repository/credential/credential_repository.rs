@@ -0,0 +1,365 @@
+use crate::repository::credential::credential_ceremony::CredentialCeremony;
+use crate::repository::credential::credential_model::Credential;
+use crate::services::mfa::webauthn_service::Passkey;
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use mongodb::bson::doc;
+use mongodb::bson::oid::ObjectId;
+use mongodb::error::Error as MongoError;
+use mongodb::options::FindOptions;
+use mongodb::Database;
+use std::time::SystemTime;
+
+#[derive(Clone)]
+pub struct CredentialRepository {
+    pub collection: String,
+    pub ceremony_collection: String,
+}
+
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Invalid Credential ID: {0}")]
+    InvalidId(String),
+    #[error("Empty collection")]
+    EmptyCollection,
+    #[error("Empty Credential ID")]
+    EmptyId,
+    #[error("Credential not found: {0}")]
+    CredentialNotFound(String),
+    #[error("MongoDB error: {0}")]
+    MongoDb(#[source] MongoError),
+}
+
+impl CredentialRepository {
+    /// # Summary
+    ///
+    /// Creates a new CredentialRepository instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection` - A String that holds the name of the Credential collection.
+    /// * `ceremony_collection` - A String that holds the name of the collection of the ongoing WebAuthn ceremonies.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let credential_repository = match CredentialRepository::new("credentials".to_string(), "webauthnCeremonies".to_string()) {
+    ///    Ok(d) => d,
+    ///    Err(e) => panic!("Failed to initialize Credential repository: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A CredentialRepository instance.
+    pub fn new(
+        collection: String,
+        ceremony_collection: String,
+    ) -> Result<CredentialRepository, Error> {
+        if collection.is_empty() || ceremony_collection.is_empty() {
+            return Err(Error::EmptyCollection);
+        }
+
+        Ok(CredentialRepository {
+            collection,
+            ceremony_collection,
+        })
+    }
+
+    /// # Summary
+    ///
+    /// Create a new credential.
+    ///
+    /// # Arguments
+    ///
+    /// * `credential` - A Credential instance.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let credential = match credential_repository.create(credential, &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to create Credential: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the created Credential instance or an Error.
+    pub async fn create(&self, credential: Credential, db: &Database) -> Result<Credential, Error> {
+        match db
+            .collection::<Credential>(&self.collection)
+            .insert_one(&credential, None)
+            .await
+        {
+            Ok(_) => Ok(credential),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Find all credentials of a user, oldest first.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let credentials = match credential_repository.find_by_user_id(user.id, &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to find Credentials: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with a vector of Credential instances or an Error.
+    pub async fn find_by_user_id(
+        &self,
+        user_id: ObjectId,
+        db: &Database,
+    ) -> Result<Vec<Credential>, Error> {
+        let find_options = FindOptions::builder().sort(doc! { "createdAt": 1 }).build();
+
+        let cursor = match db
+            .collection::<Credential>(&self.collection)
+            .find(doc! { "userId": user_id }, find_options)
+            .await
+        {
+            Ok(d) => d,
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        cursor.try_collect().await.map_err(Error::MongoDb)
+    }
+
+    /// # Summary
+    ///
+    /// Store the passkey of a credential after it was used to log in, so that its signature counter is kept up to date.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the Credential.
+    /// * `passkey` - The updated passkey.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// match credential_repository.update_passkey(credential.id, &credential.passkey, &db).await {
+    ///   Ok(_) => (),
+    ///   Err(e) => panic!("Failed to update Credential: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the unit type or an Error.
+    pub async fn update_passkey(
+        &self,
+        id: ObjectId,
+        passkey: &Passkey,
+        db: &Database,
+    ) -> Result<(), Error> {
+        let now: DateTime<Utc> = SystemTime::now().into();
+        let passkey = match mongodb::bson::to_bson(passkey) {
+            Ok(p) => p,
+            Err(e) => return Err(Error::MongoDb(e.into())),
+        };
+
+        match db
+            .collection::<Credential>(&self.collection)
+            .update_one(
+                doc! { "_id": id },
+                doc! {
+                    "$set": {
+                        "passkey": passkey,
+                        "lastUsedAt": mongodb::bson::DateTime::from_chrono(now),
+                    },
+                },
+                None,
+            )
+            .await
+        {
+            Ok(r) if r.matched_count == 0 => Err(Error::CredentialNotFound(id.to_hex())),
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Delete a credential of a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - A string slice that holds the ID of the Credential.
+    /// * `user_id` - The ID of the User that owns the Credential.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// match credential_repository.delete("id", user.id, &db).await {
+    ///   Ok(_) => (),
+    ///   Err(e) => panic!("Failed to delete Credential: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the unit type or an Error.
+    pub async fn delete(&self, id: &str, user_id: ObjectId, db: &Database) -> Result<(), Error> {
+        if id.is_empty() {
+            return Err(Error::EmptyId);
+        }
+
+        let target_object_id = match ObjectId::parse_str(id) {
+            Ok(res) => res,
+            Err(e) => {
+                return Err(Error::InvalidId(e.to_string()));
+            }
+        };
+
+        match db
+            .collection::<Credential>(&self.collection)
+            .delete_one(doc! { "_id": target_object_id, "userId": user_id }, None)
+            .await
+        {
+            Ok(r) if r.deleted_count == 0 => Err(Error::CredentialNotFound(id.to_string())),
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Move all credentials of a user to another user.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User that owns the Credentials.
+    /// * `new_user_id` - The ID of the User that will own the Credentials.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// match credential_repository.reassign(other.id, user.id, &db).await {
+    ///   Ok(_) => (),
+    ///   Err(e) => panic!("Failed to reassign Credentials: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the unit type or an Error.
+    pub async fn reassign(
+        &self,
+        user_id: ObjectId,
+        new_user_id: ObjectId,
+        db: &Database,
+    ) -> Result<(), Error> {
+        match db
+            .collection::<Credential>(&self.collection)
+            .update_many(
+                doc! { "userId": user_id },
+                doc! { "$set": { "userId": new_user_id } },
+                None,
+            )
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Store the state of a WebAuthn ceremony until the authenticator responds to its challenge.
+    ///
+    /// # Arguments
+    ///
+    /// * `ceremony` - A CredentialCeremony instance.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// match credential_repository.create_ceremony(&ceremony, &db).await {
+    ///   Ok(_) => (),
+    ///   Err(e) => panic!("Failed to create CredentialCeremony: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the unit type or an Error.
+    pub async fn create_ceremony(
+        &self,
+        ceremony: &CredentialCeremony,
+        db: &Database,
+    ) -> Result<(), Error> {
+        match db
+            .collection::<CredentialCeremony>(&self.ceremony_collection)
+            .insert_one(ceremony, None)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Find a WebAuthn ceremony that has not expired and delete it in the same operation, so that its challenge can
+    /// only be answered once, even by concurrent requests.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - A string slice that holds the ID of the CredentialCeremony.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let ceremony = match credential_repository.take_ceremony("id", &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to find CredentialCeremony: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with an Option of a CredentialCeremony instance or an Error.
+    pub async fn take_ceremony(
+        &self,
+        id: &str,
+        db: &Database,
+    ) -> Result<Option<CredentialCeremony>, Error> {
+        let target_object_id = match ObjectId::parse_str(id) {
+            Ok(res) => res,
+            Err(e) => {
+                return Err(Error::InvalidId(e.to_string()));
+            }
+        };
+
+        let now: DateTime<Utc> = SystemTime::now().into();
+
+        db.collection::<CredentialCeremony>(&self.ceremony_collection)
+            .find_one_and_delete(
+                doc! {
+                    "_id": target_object_id,
+                    "expiresAt": { "$gt": mongodb::bson::DateTime::from_chrono(now) },
+                },
+                None,
+            )
+            .await
+            .map_err(Error::MongoDb)
+    }
+}
@@ -0,0 +1,2 @@
+pub mod device_model;
+pub mod device_repository;
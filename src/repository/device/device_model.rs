@@ -0,0 +1,133 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::time::SystemTime;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceStatus {
+    #[serde(rename = "pending")]
+    Pending,
+    #[serde(rename = "approved")]
+    Approved,
+    #[serde(rename = "denied")]
+    Denied,
+}
+
+impl Display for DeviceStatus {
+    /// # Summary
+    ///
+    /// Display the DeviceStatus.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - A mutable reference to a Formatter.
+    ///
+    /// # Returns
+    ///
+    /// A std::fmt::Result.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeviceStatus::Pending => write!(f, "Pending"),
+            DeviceStatus::Approved => write!(f, "Approved"),
+            DeviceStatus::Denied => write!(f, "Denied"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DeviceAuthorization {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "deviceCodeHash")]
+    pub device_code_hash: String,
+    #[serde(rename = "userCode")]
+    pub user_code: String,
+    #[serde(rename = "clientId")]
+    pub client_id: String,
+    pub status: DeviceStatus,
+    #[serde(rename = "userId")]
+    pub user_id: Option<ObjectId>,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "expiresAt")]
+    pub expires_at: DateTime<Utc>,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "lastPolledAt")]
+    pub last_polled_at: DateTime<Utc>,
+}
+
+impl DeviceAuthorization {
+    /// # Summary
+    ///
+    /// Create a new, pending DeviceAuthorization.
+    ///
+    /// # Arguments
+    ///
+    /// * `device_code_hash` - The SHA-256 hash of the device code.
+    /// * `user_code` - The code that the user enters to approve the device.
+    /// * `client_id` - The ID of the client that requested the authorization.
+    /// * `expires_in` - The amount of seconds after which the DeviceAuthorization expires.
+    ///
+    /// # Returns
+    ///
+    /// * `DeviceAuthorization` - The new DeviceAuthorization.
+    pub fn new(
+        device_code_hash: String,
+        user_code: String,
+        client_id: String,
+        expires_in: u64,
+    ) -> DeviceAuthorization {
+        let now: DateTime<Utc> = SystemTime::now().into();
+
+        DeviceAuthorization {
+            id: ObjectId::new(),
+            device_code_hash,
+            user_code,
+            client_id,
+            status: DeviceStatus::Pending,
+            user_id: None,
+            created_at: now,
+            expires_at: now + chrono::Duration::seconds(expires_in as i64),
+            last_polled_at: now,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check whether the DeviceAuthorization has expired.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the DeviceAuthorization has expired, false otherwise.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= chrono::Utc::now()
+    }
+}
+
+impl Display for DeviceAuthorization {
+    /// # Summary
+    ///
+    /// Display the DeviceAuthorization.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The Formatter.
+    ///
+    /// # Returns
+    ///
+    /// A std::fmt::Result.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "DeviceAuthorization {{ id: {}, user_code: {}, client_id: {}, status: {}, expires_at: {} }}",
+            self.id.to_hex(),
+            self.user_code,
+            self.client_id,
+            self.status,
+            self.expires_at
+        )
+    }
+}
@@ -0,0 +1,250 @@
+use crate::repository::device::device_model::{DeviceAuthorization, DeviceStatus};
+use crate::services::oauth::error_code::OAuthErrorCode;
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, to_bson};
+use mongodb::error::Error as MongodbError;
+use mongodb::Database;
+
+#[derive(Clone)]
+pub struct DeviceRepository {
+    pub collection: String,
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum Error {
+    #[error("Empty DeviceAuthorization collection")]
+    EmptyCollection,
+    #[error("Empty client ID")]
+    EmptyClientId,
+    #[error("Unsupported grant type: {0}")]
+    UnsupportedGrantType(String),
+    #[error("Invalid client ID")]
+    InvalidClient,
+    #[error("The client is not allowed to use this grant type")]
+    UnauthorizedClient,
+    #[error("Invalid scope: {0}")]
+    InvalidScope(String),
+    #[error("Invalid device code")]
+    InvalidGrant,
+    #[error("User code not found: {0}")]
+    UserCodeNotFound(String),
+    #[error("The authorization request is still pending")]
+    AuthorizationPending,
+    #[error("The device is polling too frequently")]
+    SlowDown,
+    #[error("The authorization request was denied")]
+    AccessDenied,
+    #[error("The device code has expired")]
+    ExpiredToken,
+    #[error("MongoDb Error: {0}")]
+    MongoDb(#[source] MongodbError),
+}
+
+impl OAuthErrorCode for Error {
+    /// # Summary
+    ///
+    /// Get the OAuth 2.0 error code that corresponds to the Error, as defined by RFC 6749 and RFC 8628.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<&'static str>` - The OAuth 2.0 error code, or None if the Error is not an OAuth 2.0 error.
+    fn oauth_error_code(&self) -> Option<&'static str> {
+        match self {
+            Error::EmptyClientId | Error::InvalidClient => Some("invalid_client"),
+            Error::UnsupportedGrantType(_) => Some("unsupported_grant_type"),
+            Error::UnauthorizedClient => Some("unauthorized_client"),
+            Error::InvalidScope(_) => Some("invalid_scope"),
+            Error::InvalidGrant => Some("invalid_grant"),
+            Error::AuthorizationPending => Some("authorization_pending"),
+            Error::SlowDown => Some("slow_down"),
+            Error::AccessDenied => Some("access_denied"),
+            Error::ExpiredToken => Some("expired_token"),
+            Error::EmptyCollection | Error::UserCodeNotFound(_) | Error::MongoDb(_) => None,
+        }
+    }
+}
+
+impl DeviceRepository {
+    /// # Summary
+    ///
+    /// Create a new DeviceRepository.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection` - The collection name.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<DeviceRepository, Error>` - The result of the operation.
+    pub fn new(collection: String) -> Result<DeviceRepository, Error> {
+        if collection.is_empty() {
+            return Err(Error::EmptyCollection);
+        }
+
+        Ok(DeviceRepository { collection })
+    }
+
+    /// # Summary
+    ///
+    /// Create a new DeviceAuthorization.
+    ///
+    /// # Arguments
+    ///
+    /// * `device` - The DeviceAuthorization to create.
+    /// * `db` - The Database to create the DeviceAuthorization in.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    pub async fn create(&self, device: DeviceAuthorization, db: &Database) -> Result<(), Error> {
+        match db
+            .collection::<DeviceAuthorization>(&self.collection)
+            .insert_one(device, None)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Find a DeviceAuthorization by the hash of its device code.
+    ///
+    /// # Arguments
+    ///
+    /// * `device_code_hash` - The SHA-256 hash of the device code.
+    /// * `db` - The Database to find the DeviceAuthorization in.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<DeviceAuthorization>, Error>` - The result of the operation.
+    pub async fn find_by_device_code_hash(
+        &self,
+        device_code_hash: &str,
+        db: &Database,
+    ) -> Result<Option<DeviceAuthorization>, Error> {
+        db.collection::<DeviceAuthorization>(&self.collection)
+            .find_one(doc! {"deviceCodeHash": device_code_hash}, None)
+            .await
+            .map_err(Error::MongoDb)
+    }
+
+    /// # Summary
+    ///
+    /// Find a DeviceAuthorization by its user code.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_code` - The user code.
+    /// * `db` - The Database to find the DeviceAuthorization in.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<DeviceAuthorization>, Error>` - The result of the operation.
+    pub async fn find_by_user_code(
+        &self,
+        user_code: &str,
+        db: &Database,
+    ) -> Result<Option<DeviceAuthorization>, Error> {
+        db.collection::<DeviceAuthorization>(&self.collection)
+            .find_one(doc! {"userCode": user_code}, None)
+            .await
+            .map_err(Error::MongoDb)
+    }
+
+    /// # Summary
+    ///
+    /// Approve or deny a pending DeviceAuthorization.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the DeviceAuthorization.
+    /// * `status` - The new DeviceStatus.
+    /// * `user_id` - The ID of the User that approved or denied the DeviceAuthorization.
+    /// * `db` - The Database to update the DeviceAuthorization in.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<bool, Error>` - True if a pending DeviceAuthorization was updated, false otherwise.
+    pub async fn update_status(
+        &self,
+        id: ObjectId,
+        status: DeviceStatus,
+        user_id: ObjectId,
+        db: &Database,
+    ) -> Result<bool, Error> {
+        let status = to_bson(&status).unwrap_or_default();
+        let pending = to_bson(&DeviceStatus::Pending).unwrap_or_default();
+
+        match db
+            .collection::<DeviceAuthorization>(&self.collection)
+            .update_one(
+                doc! {"_id": id, "status": pending},
+                doc! {"$set": {"status": status, "userId": user_id}},
+                None,
+            )
+            .await
+        {
+            Ok(r) => Ok(r.modified_count == 1),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Update the moment at which the device last requested a token.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the DeviceAuthorization.
+    /// * `last_polled_at` - The moment at which the device requested a token.
+    /// * `db` - The Database to update the DeviceAuthorization in.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    pub async fn update_last_polled(
+        &self,
+        id: ObjectId,
+        last_polled_at: DateTime<Utc>,
+        db: &Database,
+    ) -> Result<(), Error> {
+        match db
+            .collection::<DeviceAuthorization>(&self.collection)
+            .update_one(
+                doc! {"_id": id},
+                doc! {"$set": {"lastPolledAt": mongodb::bson::DateTime::from_chrono(last_polled_at)}},
+                None,
+            )
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Delete a DeviceAuthorization.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the DeviceAuthorization.
+    /// * `db` - The Database to delete the DeviceAuthorization from.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<bool, Error>` - True if the DeviceAuthorization was deleted, false if it no longer existed.
+    pub async fn delete(&self, id: ObjectId, db: &Database) -> Result<bool, Error> {
+        match db
+            .collection::<DeviceAuthorization>(&self.collection)
+            .delete_one(doc! {"_id": id}, None)
+            .await
+        {
+            Ok(r) => Ok(r.deleted_count == 1),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+}
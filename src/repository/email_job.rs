@@ -0,0 +1,2 @@
+pub mod email_job_model;
+pub mod email_job_repository;
@@ -0,0 +1,77 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::time::SystemTime;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EmailJob {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+    pub attempts: i64,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "nextAttemptAt")]
+    pub next_attempt_at: DateTime<Utc>,
+    #[serde(rename = "lastError")]
+    pub last_error: Option<String>,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl EmailJob {
+    /// # Summary
+    ///
+    /// Create a new EmailJob that is due immediately.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - The email address of the recipient.
+    /// * `subject` - The subject of the email.
+    /// * `body` - The plain text body of the email.
+    ///
+    /// # Returns
+    ///
+    /// * `EmailJob` - The new EmailJob.
+    pub fn new(to: String, subject: String, body: String) -> EmailJob {
+        let now: DateTime<Utc> = SystemTime::now().into();
+
+        EmailJob {
+            id: ObjectId::new(),
+            to,
+            subject,
+            body,
+            attempts: 0,
+            next_attempt_at: now,
+            last_error: None,
+            created_at: now,
+        }
+    }
+}
+
+impl Display for EmailJob {
+    /// # Summary
+    ///
+    /// Display the EmailJob. The recipient and the body are omitted, so that they do not end up in the logs.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The Formatter.
+    ///
+    /// # Returns
+    ///
+    /// * `std::fmt::Result` - The result of the display.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "EmailJob: {{ id: {}, subject: {}, attempts: {}, next_attempt_at: {} }}",
+            self.id.to_hex(),
+            self.subject,
+            self.attempts,
+            self.next_attempt_at
+        )
+    }
+}
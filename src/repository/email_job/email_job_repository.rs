@@ -0,0 +1,225 @@
+use crate::repository::email_job::email_job_model::EmailJob;
+use chrono::{DateTime, Utc};
+use mongodb::bson::doc;
+use mongodb::bson::oid::ObjectId;
+use mongodb::error::Error as MongoError;
+use mongodb::options::{FindOneAndUpdateOptions, ReturnDocument};
+use mongodb::Database;
+
+#[derive(Clone)]
+pub struct EmailJobRepository {
+    pub collection: String,
+}
+
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Empty collection")]
+    EmptyCollection,
+    #[error("MongoDB error: {0}")]
+    MongoDb(#[source] MongoError),
+}
+
+impl EmailJobRepository {
+    /// # Summary
+    ///
+    /// Creates a new EmailJobRepository instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection` - A String that holds the collection name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let email_job_repository = match EmailJobRepository::new("emailJobs".to_string()) {
+    ///    Ok(d) => d,
+    ///    Err(e) => panic!("Failed to initialize EmailJob repository: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// An EmailJobRepository instance.
+    pub fn new(collection: String) -> Result<EmailJobRepository, Error> {
+        if collection.is_empty() {
+            return Err(Error::EmptyCollection);
+        }
+
+        Ok(EmailJobRepository { collection })
+    }
+
+    /// # Summary
+    ///
+    /// Add an EmailJob to the queue.
+    ///
+    /// # Arguments
+    ///
+    /// * `email_job` - An EmailJob instance.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// match email_job_repository.create(email_job, &db).await {
+    ///   Ok(_) => (),
+    ///   Err(e) => panic!("Failed to create EmailJob: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the unit type or an Error.
+    pub async fn create(&self, email_job: EmailJob, db: &Database) -> Result<(), Error> {
+        match db
+            .collection::<EmailJob>(&self.collection)
+            .insert_one(&email_job, None)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Claim the EmailJob that has been due the longest. The attempt counter of the EmailJob is incremented and the
+    /// next attempt is postponed by the lease, so that concurrent workers do not claim the same EmailJob and an EmailJob
+    /// that was claimed by a worker that stopped is retried once the lease has passed.
+    ///
+    /// # Arguments
+    ///
+    /// * `lease` - The time until which the EmailJob is reserved for the caller.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let email_job = match email_job_repository.claim(lease, &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to claim EmailJob: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the claimed EmailJob, None if no EmailJob is due, or an Error.
+    pub async fn claim(
+        &self,
+        lease: DateTime<Utc>,
+        db: &Database,
+    ) -> Result<Option<EmailJob>, Error> {
+        let filter = doc! {
+            "nextAttemptAt": { "$lte": mongodb::bson::DateTime::now() },
+        };
+
+        let update = doc! {
+            "$set": {
+                "nextAttemptAt": mongodb::bson::DateTime::from_chrono(lease),
+            },
+            "$inc": {
+                "attempts": 1_i64,
+            },
+        };
+
+        let options = FindOneAndUpdateOptions::builder()
+            .sort(doc! { "nextAttemptAt": 1 })
+            .return_document(ReturnDocument::After)
+            .build();
+
+        match db
+            .collection::<EmailJob>(&self.collection)
+            .find_one_and_update(filter, update, options)
+            .await
+        {
+            Ok(d) => Ok(d),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Postpone the next attempt of an EmailJob after a failed attempt.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the EmailJob.
+    /// * `next_attempt_at` - The time at which the EmailJob should be attempted again.
+    /// * `last_error` - The error of the failed attempt.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// match email_job_repository.reschedule(id, next_attempt_at, "Connection refused", &db).await {
+    ///   Ok(_) => (),
+    ///   Err(e) => panic!("Failed to reschedule EmailJob: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the unit type or an Error.
+    pub async fn reschedule(
+        &self,
+        id: ObjectId,
+        next_attempt_at: DateTime<Utc>,
+        last_error: &str,
+        db: &Database,
+    ) -> Result<(), Error> {
+        let filter = doc! {
+            "_id": id,
+        };
+
+        let update = doc! {
+            "$set": {
+                "nextAttemptAt": mongodb::bson::DateTime::from_chrono(next_attempt_at),
+                "lastError": last_error,
+            },
+        };
+
+        match db
+            .collection::<EmailJob>(&self.collection)
+            .update_one(filter, update, None)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Remove an EmailJob from the queue.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the EmailJob.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// match email_job_repository.delete(id, &db).await {
+    ///   Ok(_) => (),
+    ///   Err(e) => panic!("Failed to delete EmailJob: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the unit type or an Error.
+    pub async fn delete(&self, id: ObjectId, db: &Database) -> Result<(), Error> {
+        let filter = doc! {
+            "_id": id,
+        };
+
+        match db
+            .collection::<EmailJob>(&self.collection)
+            .delete_one(filter, None)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+}
@@ -0,0 +1,2 @@
+pub mod invitation_model;
+pub mod invitation_repository;
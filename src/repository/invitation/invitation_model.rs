@@ -0,0 +1,100 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::time::SystemTime;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Invitation {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "organizationId")]
+    pub organization_id: ObjectId,
+    pub email: String,
+    pub roles: Vec<ObjectId>,
+    #[serde(rename = "tokenHash")]
+    pub token_hash: String,
+    #[serde(rename = "invitedBy")]
+    pub invited_by: Option<ObjectId>,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "expiresAt")]
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Invitation {
+    /// # Summary
+    ///
+    /// Create a new Invitation.
+    ///
+    /// # Arguments
+    ///
+    /// * `organization_id` - The ID of the Organization that the invitee is invited to.
+    /// * `email` - The email address of the invitee.
+    /// * `roles` - The IDs of the roles that the invitee receives within the Organization.
+    /// * `invited_by` - The ID of the User that created the Invitation, if any.
+    /// * `expires_in` - The amount of seconds after which the Invitation expires.
+    ///
+    /// # Returns
+    ///
+    /// * `Invitation` - The new Invitation, without a token hash.
+    pub fn new(
+        organization_id: ObjectId,
+        email: String,
+        roles: Vec<ObjectId>,
+        invited_by: Option<ObjectId>,
+        expires_in: u64,
+    ) -> Invitation {
+        let now: DateTime<Utc> = SystemTime::now().into();
+
+        Invitation {
+            id: ObjectId::new(),
+            organization_id,
+            email,
+            roles,
+            token_hash: String::new(),
+            invited_by,
+            created_at: now,
+            expires_at: now + chrono::Duration::seconds(expires_in as i64),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check whether the Invitation has expired.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the Invitation has expired, false otherwise.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= chrono::Utc::now()
+    }
+}
+
+impl Display for Invitation {
+    /// # Summary
+    ///
+    /// Display the Invitation.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The Formatter.
+    ///
+    /// # Returns
+    ///
+    /// * `std::fmt::Result` - The result of the display.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Invitation: {{ id: {}, organization_id: {}, email: {}, roles: {:?}, created_at: {}, expires_at: {} }}",
+            self.id.to_hex(),
+            self.organization_id.to_hex(),
+            self.email,
+            self.roles.iter().map(|r| r.to_hex()).collect::<Vec<String>>(),
+            self.created_at,
+            self.expires_at
+        )
+    }
+}
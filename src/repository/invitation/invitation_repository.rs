@@ -0,0 +1,287 @@
+use crate::repository::audit::audit_repository::Error as AuditError;
+use crate::repository::invitation::invitation_model::Invitation;
+use crate::repository::organization::organization_repository::Error as OrganizationError;
+use futures::TryStreamExt;
+use mongodb::bson::doc;
+use mongodb::bson::oid::ObjectId;
+use mongodb::error::Error as MongoError;
+use mongodb::options::FindOptions;
+use mongodb::Database;
+
+#[derive(Clone)]
+pub struct InvitationRepository {
+    pub collection: String,
+}
+
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Invalid Invitation ID: {0}")]
+    InvalidId(String),
+    #[error("Empty collection")]
+    EmptyCollection,
+    #[error("Empty Invitation ID")]
+    EmptyId,
+    #[error("Empty email address")]
+    EmptyEmail,
+    #[error("Invalid email address: {0}")]
+    InvalidEmail(String),
+    #[error("Invalid invitation token")]
+    InvalidToken,
+    #[error("The invitation has expired")]
+    InvitationExpired,
+    #[error("The invitation was sent to a different email address")]
+    EmailMismatch,
+    #[error("Invitation not found: {0}")]
+    InvitationNotFound(String),
+    #[error("Organization error: {0}")]
+    Organization(#[source] OrganizationError),
+    #[error("Audit error: {0}")]
+    Audit(#[source] AuditError),
+    #[error("MongoDB error: {0}")]
+    MongoDb(#[source] MongoError),
+}
+
+impl InvitationRepository {
+    /// # Summary
+    ///
+    /// Creates a new InvitationRepository instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection` - A String that holds the collection name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let invitation_repository = match InvitationRepository::new("invitations".to_string()) {
+    ///    Ok(d) => d,
+    ///    Err(e) => panic!("Failed to initialize Invitation repository: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// An InvitationRepository instance.
+    pub fn new(collection: String) -> Result<InvitationRepository, Error> {
+        if collection.is_empty() {
+            return Err(Error::EmptyCollection);
+        }
+
+        Ok(InvitationRepository { collection })
+    }
+
+    /// # Summary
+    ///
+    /// Create a new invitation.
+    ///
+    /// # Arguments
+    ///
+    /// * `invitation` - An Invitation instance.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let invitation = match invitation_repository.create(invitation, &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to create Invitation: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the created Invitation instance or an Error.
+    pub async fn create(&self, invitation: Invitation, db: &Database) -> Result<Invitation, Error> {
+        if invitation.email.is_empty() {
+            return Err(Error::EmptyEmail);
+        }
+
+        match db
+            .collection::<Invitation>(&self.collection)
+            .insert_one(&invitation, None)
+            .await
+        {
+            Ok(_) => Ok(invitation),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Find all pending invitations of an organization, oldest first. Expired invitations are excluded.
+    ///
+    /// # Arguments
+    ///
+    /// * `organization_id` - The ID of the organization.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let invitations = match invitation_repository.find_by_organization_id(organization_id, &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to find Invitations: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with a vector of Invitation instances or an Error.
+    pub async fn find_by_organization_id(
+        &self,
+        organization_id: ObjectId,
+        db: &Database,
+    ) -> Result<Vec<Invitation>, Error> {
+        let filter = doc! {
+            "organizationId": organization_id,
+            "expiresAt": { "$gt": mongodb::bson::DateTime::now() },
+        };
+        let find_options = FindOptions::builder().sort(doc! { "createdAt": 1 }).build();
+
+        let cursor = match db
+            .collection::<Invitation>(&self.collection)
+            .find(filter, find_options)
+            .await
+        {
+            Ok(d) => d,
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        Ok(cursor.try_collect().await.unwrap_or_else(|_| vec![]))
+    }
+
+    /// # Summary
+    ///
+    /// Find an invitation by the hash of its token.
+    ///
+    /// # Arguments
+    ///
+    /// * `token_hash` - A string slice that holds the hash of the token.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let invitation = match invitation_repository.find_by_token_hash("hash", &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to find Invitation: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with an Option of an Invitation instance or an Error.
+    pub async fn find_by_token_hash(
+        &self,
+        token_hash: &str,
+        db: &Database,
+    ) -> Result<Option<Invitation>, Error> {
+        let filter = doc! {
+            "tokenHash": token_hash,
+        };
+
+        match db
+            .collection::<Invitation>(&self.collection)
+            .find_one(filter, None)
+            .await
+        {
+            Ok(d) => Ok(d),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Delete an invitation of an organization. Invitations of other organizations cannot be deleted.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - A string slice that holds the ID of the Invitation.
+    /// * `organization_id` - The ID of the organization that the Invitation belongs to.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// match invitation_repository.delete("id", organization_id, &db).await {
+    ///   Ok(_) => (),
+    ///   Err(e) => panic!("Failed to delete Invitation: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the unit type or an Error.
+    pub async fn delete(
+        &self,
+        id: &str,
+        organization_id: ObjectId,
+        db: &Database,
+    ) -> Result<(), Error> {
+        if id.is_empty() {
+            return Err(Error::EmptyId);
+        }
+
+        let target_object_id = match ObjectId::parse_str(id) {
+            Ok(res) => res,
+            Err(e) => {
+                return Err(Error::InvalidId(e.to_string()));
+            }
+        };
+
+        let filter = doc! {
+            "_id": target_object_id,
+            "organizationId": organization_id,
+        };
+
+        match db
+            .collection::<Invitation>(&self.collection)
+            .delete_one(filter, None)
+            .await
+        {
+            Ok(r) if r.deleted_count == 0 => Err(Error::InvitationNotFound(id.to_string())),
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Delete all invitations of an organization.
+    ///
+    /// # Arguments
+    ///
+    /// * `organization_id` - The ID of the organization.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// match invitation_repository.delete_by_organization_id(organization_id, &db).await {
+    ///   Ok(_) => (),
+    ///   Err(e) => panic!("Failed to delete Invitations: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the unit type or an Error.
+    pub async fn delete_by_organization_id(
+        &self,
+        organization_id: ObjectId,
+        db: &Database,
+    ) -> Result<(), Error> {
+        let filter = doc! {
+            "organizationId": organization_id,
+        };
+
+        match db
+            .collection::<Invitation>(&self.collection)
+            .delete_many(filter, None)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+}
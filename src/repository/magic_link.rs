@@ -0,0 +1,2 @@
+pub mod magic_link_model;
+pub mod magic_link_repository;
@@ -0,0 +1,79 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::time::SystemTime;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct MagicLink {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "userId")]
+    pub user_id: ObjectId,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "expiresAt")]
+    pub expires_at: DateTime<Utc>,
+}
+
+impl MagicLink {
+    /// # Summary
+    ///
+    /// Create a new MagicLink.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User that can log in with the MagicLink.
+    /// * `expires_in` - The amount of seconds after which the MagicLink expires.
+    ///
+    /// # Returns
+    ///
+    /// * `MagicLink` - The new MagicLink.
+    pub fn new(user_id: ObjectId, expires_in: u64) -> MagicLink {
+        let now: DateTime<Utc> = SystemTime::now().into();
+
+        MagicLink {
+            id: ObjectId::new(),
+            user_id,
+            created_at: now,
+            expires_at: now + chrono::Duration::seconds(expires_in as i64),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check whether the MagicLink has expired.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the MagicLink has expired, false otherwise.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= chrono::Utc::now()
+    }
+}
+
+impl Display for MagicLink {
+    /// # Summary
+    ///
+    /// Display the MagicLink.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The Formatter.
+    ///
+    /// # Returns
+    ///
+    /// A std::fmt::Result.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "MagicLink {{ id: {}, user_id: {}, created_at: {}, expires_at: {} }}",
+            self.id.to_hex(),
+            self.user_id.to_hex(),
+            self.created_at,
+            self.expires_at
+        )
+    }
+}
@@ -0,0 +1,84 @@
+use crate::repository::magic_link::magic_link_model::MagicLink;
+use mongodb::bson::doc;
+use mongodb::bson::oid::ObjectId;
+use mongodb::error::Error as MongodbError;
+use mongodb::Database;
+
+#[derive(Clone)]
+pub struct MagicLinkRepository {
+    pub collection: String,
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum Error {
+    #[error("Empty MagicLink collection")]
+    EmptyCollection,
+    #[error("Invalid magic link")]
+    InvalidLink,
+    #[error("MongoDb Error: {0}")]
+    MongoDb(#[source] MongodbError),
+}
+
+impl MagicLinkRepository {
+    /// # Summary
+    ///
+    /// Create a new MagicLinkRepository.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection` - The collection name.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<MagicLinkRepository, Error>` - The result of the operation.
+    pub fn new(collection: String) -> Result<MagicLinkRepository, Error> {
+        if collection.is_empty() {
+            return Err(Error::EmptyCollection);
+        }
+
+        Ok(MagicLinkRepository { collection })
+    }
+
+    /// # Summary
+    ///
+    /// Create a new MagicLink.
+    ///
+    /// # Arguments
+    ///
+    /// * `magic_link` - The MagicLink to create.
+    /// * `db` - The Database to create the MagicLink in.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    pub async fn create(&self, magic_link: MagicLink, db: &Database) -> Result<(), Error> {
+        match db
+            .collection::<MagicLink>(&self.collection)
+            .insert_one(magic_link, None)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Find a MagicLink by its ID and delete it in the same operation, so that a link can only be used once, even by
+    /// concurrent requests.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the MagicLink.
+    /// * `db` - The Database that holds the MagicLink.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<MagicLink>, Error>` - The MagicLink, or None if it does not exist or was already used.
+    pub async fn take(&self, id: ObjectId, db: &Database) -> Result<Option<MagicLink>, Error> {
+        db.collection::<MagicLink>(&self.collection)
+            .find_one_and_delete(doc! {"_id": id}, None)
+            .await
+            .map_err(Error::MongoDb)
+    }
+}
@@ -0,0 +1,2 @@
+pub mod organization_model;
+pub mod organization_repository;
@@ -0,0 +1,132 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::time::SystemTime;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Member {
+    #[serde(rename = "userId")]
+    pub user_id: ObjectId,
+    pub roles: Vec<ObjectId>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Organization {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub members: Vec<Member>,
+    #[serde(rename = "defaultRoles", default)]
+    pub default_roles: Vec<ObjectId>,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Organization {
+    /// # Summary
+    ///
+    /// Create a new Organization without members.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the Organization.
+    /// * `description` - The description of the Organization.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let organization = Organization::new(String::from("Opserva"), None);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Organization` - The new Organization.
+    pub fn new(name: String, description: Option<String>) -> Self {
+        let now: DateTime<Utc> = SystemTime::now().into();
+
+        Organization {
+            id: ObjectId::new(),
+            name,
+            description,
+            members: vec![],
+            default_roles: vec![],
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Find the membership of a User.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<&Member>` - The Member, or None if the User is not a member of the Organization.
+    pub fn find_member(&self, user_id: &ObjectId) -> Option<&Member> {
+        self.members.iter().find(|m| &m.user_id == user_id)
+    }
+
+    /// # Summary
+    ///
+    /// Add the default roles of the Organization to the roles of a newly joined member.
+    ///
+    /// # Arguments
+    ///
+    /// * `roles` - The roles that the member was given explicitly.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let roles = organization.with_default_roles(invitation.roles);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<ObjectId>` - The roles, followed by the default roles that were not given explicitly.
+    pub fn with_default_roles(&self, mut roles: Vec<ObjectId>) -> Vec<ObjectId> {
+        for r in &self.default_roles {
+            if !roles.contains(r) {
+                roles.push(*r);
+            }
+        }
+
+        roles
+    }
+}
+
+impl Display for Organization {
+    /// # Summary
+    ///
+    /// Display the Organization.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The Formatter.
+    ///
+    /// # Returns
+    ///
+    /// * `std::fmt::Result` - The result of the display.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Organization: {{ id: {}, name: {}, description: {}, members: {}, created_at: {}, updated_at: {} }}",
+            self.id.to_hex(),
+            self.name,
+            self.description.as_ref().unwrap_or(&String::from("None")),
+            self.members.len(),
+            self.created_at,
+            self.updated_at
+        )
+    }
+}
@@ -0,0 +1,584 @@
+use crate::repository::audit::audit_repository::Error as AuditError;
+use crate::repository::organization::organization_model::{Member, Organization};
+use futures::TryStreamExt;
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, Bson};
+use mongodb::error::Error as MongoError;
+use mongodb::options::FindOptions;
+use mongodb::Database;
+
+#[derive(Clone)]
+pub struct OrganizationRepository {
+    pub collection: String,
+}
+
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Invalid Organization ID: {0}")]
+    InvalidId(String),
+    #[error("Empty collection")]
+    EmptyCollection,
+    #[error("Empty Organization ID")]
+    EmptyId,
+    #[error("Empty Organization name")]
+    EmptyName,
+    #[error("Organization name already taken")]
+    NameAlreadyTaken,
+    #[error("Organization not found: {0}")]
+    OrganizationNotFound(String),
+    #[error("User is not a member of the Organization: {0}")]
+    MemberNotFound(String),
+    #[error("Audit error: {0}")]
+    Audit(#[source] AuditError),
+    #[error("MongoDB error: {0}")]
+    MongoDb(#[source] MongoError),
+}
+
+impl OrganizationRepository {
+    /// # Summary
+    ///
+    /// Creates a new OrganizationRepository instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection` - A String that holds the collection name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let organization_repository = match OrganizationRepository::new("organizations".to_string()) {
+    ///    Ok(d) => d,
+    ///    Err(e) => panic!("Failed to initialize Organization repository: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// An OrganizationRepository instance.
+    pub fn new(collection: String) -> Result<OrganizationRepository, Error> {
+        if collection.is_empty() {
+            return Err(Error::EmptyCollection);
+        }
+
+        Ok(OrganizationRepository { collection })
+    }
+
+    /// # Summary
+    ///
+    /// Create a new organization.
+    ///
+    /// # Arguments
+    ///
+    /// * `organization` - An Organization instance.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let organization = match organization_repository.create(organization, &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to create Organization: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the created Organization instance or an Error.
+    pub async fn create(
+        &self,
+        organization: Organization,
+        db: &Database,
+    ) -> Result<Organization, Error> {
+        if self.find_by_name(&organization.name, db).await?.is_some() {
+            return Err(Error::NameAlreadyTaken);
+        }
+
+        let organization_id = organization.id.to_hex();
+
+        match db
+            .collection::<Organization>(&self.collection)
+            .insert_one(organization, None)
+            .await
+        {
+            Ok(_) => (),
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        match self.find_by_id(&organization_id, db).await? {
+            Some(o) => Ok(o),
+            None => Err(Error::OrganizationNotFound(organization_id)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Find all organizations.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - An optional i64 that holds the limit.
+    /// * `page` - An optional i64 that holds the page.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let organizations = match organization_repository.find_all(Some(10), Some(1), &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to find Organizations: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with a vector of Organization instances or an Error.
+    pub async fn find_all(
+        &self,
+        limit: Option<i64>,
+        page: Option<i64>,
+        db: &Database,
+    ) -> Result<Vec<Organization>, Error> {
+        let mut skip: Option<u64> = None;
+
+        if let Some(l) = limit {
+            if l > 1 {
+                if let Some(p) = page {
+                    if p > 1 {
+                        let res = u64::try_from((p - 1) * l).unwrap_or(0);
+                        skip = Some(res);
+                    }
+                }
+            }
+        }
+
+        let find_options = FindOptions::builder().limit(limit).skip(skip).build();
+
+        let cursor = match db
+            .collection::<Organization>(&self.collection)
+            .find(None, find_options)
+            .await
+        {
+            Ok(d) => d,
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        Ok(cursor.try_collect().await.unwrap_or_else(|_| vec![]))
+    }
+
+    /// # Summary
+    ///
+    /// Find an organization by its ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - A string slice that holds the ID.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let organization = match organization_repository.find_by_id("id", &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to find Organization by ID: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with an Option of an Organization instance or an Error.
+    pub async fn find_by_id(&self, id: &str, db: &Database) -> Result<Option<Organization>, Error> {
+        if id.is_empty() {
+            return Err(Error::EmptyId);
+        }
+
+        let target_object_id = match ObjectId::parse_str(id) {
+            Ok(res) => res,
+            Err(e) => {
+                return Err(Error::InvalidId(e.to_string()));
+            }
+        };
+
+        let filter = doc! {
+            "_id": target_object_id,
+        };
+
+        match db
+            .collection::<Organization>(&self.collection)
+            .find_one(filter, None)
+            .await
+        {
+            Ok(d) => Ok(d),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Find an organization by its name, ignoring case.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - A string slice that holds the name.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let organization = match organization_repository.find_by_name("name", &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to find Organization by name: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with an Option of an Organization instance or an Error.
+    pub async fn find_by_name(
+        &self,
+        name: &str,
+        db: &Database,
+    ) -> Result<Option<Organization>, Error> {
+        if name.is_empty() {
+            return Err(Error::EmptyName);
+        }
+
+        let regex_pattern = format!("^{}$", regex::escape(name));
+        let re = mongodb::bson::Regex {
+            pattern: regex_pattern,
+            options: String::from("i"),
+        };
+
+        let filter = doc! { "name": { "$regex": Bson::RegularExpression(re) } };
+
+        match db
+            .collection::<Organization>(&self.collection)
+            .find_one(filter, None)
+            .await
+        {
+            Ok(d) => Ok(d),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Find all organizations that a user is a member of.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let organizations = match organization_repository.find_by_member(user_id, &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to find Organizations: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with a vector of Organization instances or an Error.
+    pub async fn find_by_member(
+        &self,
+        user_id: ObjectId,
+        db: &Database,
+    ) -> Result<Vec<Organization>, Error> {
+        let filter = doc! {
+            "members.userId": user_id,
+        };
+
+        let cursor = match db
+            .collection::<Organization>(&self.collection)
+            .find(filter, None)
+            .await
+        {
+            Ok(d) => d,
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        Ok(cursor.try_collect().await.unwrap_or_else(|_| vec![]))
+    }
+
+    /// # Summary
+    ///
+    /// Update the name, description and default roles of an organization.
+    ///
+    /// # Arguments
+    ///
+    /// * `organization` - An Organization instance.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let organization = match organization_repository.update(organization, &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to update Organization: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the updated Organization instance or an Error.
+    pub async fn update(
+        &self,
+        organization: Organization,
+        db: &Database,
+    ) -> Result<Organization, Error> {
+        if let Some(o) = self.find_by_name(&organization.name, db).await? {
+            if o.id != organization.id {
+                return Err(Error::NameAlreadyTaken);
+            }
+        }
+
+        let organization_id = organization.id.to_hex();
+        let filter = doc! {
+            "_id": organization.id,
+        };
+
+        let update = doc! {
+            "$set": {
+                "name": organization.name,
+                "description": organization.description,
+                "defaultRoles": organization.default_roles,
+                "updatedAt": mongodb::bson::DateTime::now(),
+            }
+        };
+
+        match db
+            .collection::<Organization>(&self.collection)
+            .update_one(filter, update, None)
+            .await
+        {
+            Ok(r) if r.matched_count == 0 => {
+                return Err(Error::OrganizationNotFound(organization_id))
+            }
+            Ok(_) => (),
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        match self.find_by_id(&organization_id, db).await? {
+            Some(o) => Ok(o),
+            None => Err(Error::OrganizationNotFound(organization_id)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Add a member to an organization, or replace the roles of an existing member.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the organization.
+    /// * `member` - The Member.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let organization = match organization_repository.set_member(organization_id, member, &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to set Member: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the updated Organization instance or an Error.
+    pub async fn set_member(
+        &self,
+        id: ObjectId,
+        member: Member,
+        db: &Database,
+    ) -> Result<Organization, Error> {
+        let collection = db.collection::<Organization>(&self.collection);
+        let now = mongodb::bson::DateTime::now();
+
+        let filter = doc! {
+            "_id": id,
+            "members.userId": member.user_id,
+        };
+        let update = doc! {
+            "$set": {
+                "members.$.roles": member.roles.clone(),
+                "updatedAt": now,
+            }
+        };
+
+        let updated = match collection.update_one(filter, update, None).await {
+            Ok(r) => r.matched_count > 0,
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        if !updated {
+            // The filter on the user ID prevents concurrent requests from adding the same member twice
+            let filter = doc! {
+                "_id": id,
+                "members.userId": { "$ne": member.user_id },
+            };
+            let update = doc! {
+                "$push": {
+                    "members": {
+                        "userId": member.user_id,
+                        "roles": member.roles,
+                    }
+                },
+                "$set": { "updatedAt": now },
+            };
+
+            if let Err(e) = collection.update_one(filter, update, None).await {
+                return Err(Error::MongoDb(e));
+            }
+        }
+
+        match self.find_by_id(&id.to_hex(), db).await? {
+            Some(o) => Ok(o),
+            None => Err(Error::OrganizationNotFound(id.to_hex())),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Remove a member from an organization.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the organization.
+    /// * `user_id` - The ID of the user.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// match organization_repository.remove_member(organization_id, user_id, &db).await {
+    ///   Ok(_) => (),
+    ///   Err(e) => panic!("Failed to remove Member: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the unit type or an Error.
+    pub async fn remove_member(
+        &self,
+        id: ObjectId,
+        user_id: ObjectId,
+        db: &Database,
+    ) -> Result<(), Error> {
+        let filter = doc! {
+            "_id": id,
+            "members.userId": user_id,
+        };
+        let update = doc! {
+            "$pull": { "members": { "userId": user_id } },
+            "$set": { "updatedAt": mongodb::bson::DateTime::now() },
+        };
+
+        match db
+            .collection::<Organization>(&self.collection)
+            .update_one(filter, update, None)
+            .await
+        {
+            Ok(r) if r.matched_count == 0 => Err(Error::MemberNotFound(user_id.to_hex())),
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Remove a role from all members and the default roles of an organization.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the organization.
+    /// * `role_id` - The ID of the role.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// match organization_repository.remove_role(organization_id, role_id, &db).await {
+    ///   Ok(_) => (),
+    ///   Err(e) => panic!("Failed to remove Role: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the unit type or an Error.
+    pub async fn remove_role(
+        &self,
+        id: ObjectId,
+        role_id: ObjectId,
+        db: &Database,
+    ) -> Result<(), Error> {
+        let filter = doc! {
+            "_id": id,
+        };
+        let update = doc! {
+            "$pull": { "members.$[].roles": role_id, "defaultRoles": role_id },
+        };
+
+        match db
+            .collection::<Organization>(&self.collection)
+            .update_one(filter, update, None)
+            .await
+        {
+            Ok(r) if r.matched_count == 0 => Err(Error::OrganizationNotFound(id.to_hex())),
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Delete an organization by its ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - A string slice that holds the ID.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// match organization_repository.delete("id", &db).await {
+    ///   Ok(_) => (),
+    ///   Err(e) => panic!("Failed to delete Organization: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the unit type or an Error.
+    pub async fn delete(&self, id: &str, db: &Database) -> Result<(), Error> {
+        if id.is_empty() {
+            return Err(Error::EmptyId);
+        }
+
+        let target_object_id = match ObjectId::parse_str(id) {
+            Ok(res) => res,
+            Err(e) => {
+                return Err(Error::InvalidId(e.to_string()));
+            }
+        };
+
+        let filter = doc! {
+            "_id": target_object_id,
+        };
+
+        match db
+            .collection::<Organization>(&self.collection)
+            .delete_one(filter, None)
+            .await
+        {
+            Ok(r) if r.deleted_count == 0 => Err(Error::OrganizationNotFound(id.to_string())),
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+}
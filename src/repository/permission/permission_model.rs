@@ -1,6 +1,7 @@
 use crate::web::dto::permission::create_permission::CreatePermission;
 use chrono::{DateTime, Utc};
 use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, Document};
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 use std::time::SystemTime;
@@ -11,6 +12,8 @@ pub struct Permission {
     pub id: ObjectId,
     pub name: String,
     pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<ObjectId>,
     #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     #[serde(rename = "createdAt")]
     pub created_at: DateTime<Utc>,
@@ -45,16 +48,48 @@ impl Permission {
             id: ObjectId::new(),
             name,
             description,
+            dependencies: vec![],
             created_at: now,
             updated_at: now,
         }
     }
+
+    /// # Summary
+    ///
+    /// Get the JSON Schema that is used to validate Permission documents that are written to the database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let validator = doc! { "$jsonSchema": Permission::json_schema() };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Document` - The JSON Schema of a Permission.
+    pub fn json_schema() -> Document {
+        doc! {
+            "bsonType": "object",
+            "required": ["_id", "name", "createdAt", "updatedAt"],
+            "properties": {
+                "_id": { "bsonType": "objectId" },
+                "name": { "bsonType": "string", "minLength": 1 },
+                "description": { "bsonType": ["string", "null"] },
+                "dependencies": {
+                    "bsonType": "array",
+                    "items": { "bsonType": "objectId" },
+                },
+                "createdAt": { "bsonType": "date" },
+                "updatedAt": { "bsonType": "date" },
+            }
+        }
+    }
 }
 
 impl From<CreatePermission> for Permission {
     /// # Summary
     ///
-    /// Convert a CreatePermission into a Permission.
+    /// Convert a CreatePermission into a Permission without its dependencies.
     ///
     /// # Arguments
     ///
@@ -66,6 +101,7 @@ impl From<CreatePermission> for Permission {
     /// let create_permission = CreatePermission {
     ///    name: String::from("Permission Name"),
     ///    description: Some(String::from("Permission Description")),
+    ///    dependencies: None,
     /// };
     ///
     /// let permission = Permission::from(create_permission);
@@ -80,6 +116,7 @@ impl From<CreatePermission> for Permission {
             id: ObjectId::new(),
             name: permission.name,
             description: permission.description,
+            dependencies: vec![],
             created_at: now,
             updated_at: now,
         }
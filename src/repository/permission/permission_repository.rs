@@ -1,16 +1,17 @@
 use crate::repository::audit::audit_repository::Error as AuditError;
 use crate::repository::permission::permission_model::Permission;
 use crate::repository::role::role_repository::Error as RoleError;
+use crate::repository::search_hit::{find_hits, SearchHit};
 use crate::services::role::role_service::RoleService;
 use chrono::{DateTime, Utc};
 use futures::TryStreamExt;
 use mongodb::bson::doc;
 use mongodb::bson::oid::ObjectId;
-use mongodb::bson::Bson;
+use mongodb::bson::{Bson, Document};
 use mongodb::options::FindOptions;
 use mongodb::{error::Error as MongoError, Database};
-use std::fmt;
 use std::fmt::Debug;
+use std::sync::Arc;
 use std::time::SystemTime;
 
 #[derive(Clone)]
@@ -18,53 +19,28 @@ pub struct PermissionRepository {
     pub collection: String,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, thiserror::Error)]
 pub enum Error {
+    #[error("Invalid Permission ID: {0}")]
     InvalidId(String),
+    #[error("Empty collection")]
     EmptyCollection,
+    #[error("Empty Permission ID")]
     EmptyId,
+    #[error("Empty Permission name")]
     EmptyName,
+    #[error("Empty text search")]
     EmptyTextSearch,
+    #[error("Permission name already taken")]
     NameAlreadyTaken,
+    #[error("Permission not found: {0}")]
     PermissionNotFound(String),
-    MongoDb(MongoError),
-    Role(RoleError),
-    Audit(AuditError),
-}
-
-impl fmt::Display for Error {
-    /// # Summary
-    ///
-    /// Display the error message.
-    ///
-    /// # Arguments
-    ///
-    /// * `f` - The formatter.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// let error = Error::EmptyCollection;
-    /// println!("{}", error);
-    /// ```
-    ///
-    /// # Returns
-    ///
-    /// * `fmt::Result` - The result of the display.
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match &self {
-            Error::InvalidId(id) => write!(f, "Invalid Permission ID: {}", id),
-            Error::EmptyCollection => write!(f, "Empty collection"),
-            Error::EmptyId => write!(f, "Empty Permission ID"),
-            Error::EmptyName => write!(f, "Empty Permission name"),
-            Error::EmptyTextSearch => write!(f, "Empty text search"),
-            Error::NameAlreadyTaken => write!(f, "Permission name already taken"),
-            Error::PermissionNotFound(id) => write!(f, "Permission not found: {}", id),
-            Error::MongoDb(e) => write!(f, "MongoDB error: {}", e),
-            Error::Role(e) => write!(f, "Role error: {}", e),
-            Error::Audit(e) => write!(f, "Audit error: {}", e),
-        }
-    }
+    #[error("MongoDB error: {0}")]
+    MongoDb(#[source] MongoError),
+    #[error("Role error: {0}")]
+    Role(#[source] RoleError),
+    #[error("Audit error: {0}")]
+    Audit(#[source] AuditError),
 }
 
 impl PermissionRepository {
@@ -217,7 +193,7 @@ impl PermissionRepository {
     ///
     /// ```
     /// let permission_repository = PermissionRepository::new(String::from("permissions"));
-    /// let permissions = permission_repository.find_by_id_vec(vec![String::from("permission_id")], &db).await;
+    /// let permissions = permission_repository.find_by_id_vec(vec![ObjectId::new()], &db).await;
     /// ```
     ///
     /// # Returns
@@ -225,24 +201,16 @@ impl PermissionRepository {
     /// * `Result<Vec<Permission>, Error>` - The result of the operation.
     pub async fn find_by_id_vec(
         &self,
-        id_vec: Vec<String>,
+        id_vec: Vec<ObjectId>,
         db: &Database,
     ) -> Result<Vec<Permission>, Error> {
-        let mut oid_vec: Vec<ObjectId> = vec![];
-        for id in id_vec {
-            match ObjectId::parse_str(&id) {
-                Ok(oid) => oid_vec.push(oid),
-                Err(e) => return Err(Error::InvalidId(e.to_string())),
-            }
-        }
-
-        if oid_vec.is_empty() {
+        if id_vec.is_empty() {
             return Ok(vec![]);
         }
 
         let filter = doc! {
             "_id": {
-                "$in": oid_vec,
+                "$in": id_vec,
             },
         };
 
@@ -416,6 +384,7 @@ impl PermissionRepository {
             "$set": {
                 "name": permission.name,
                 "description": permission.description,
+                "dependencies": permission.dependencies,
                 "updated_at": now,
             }
         };
@@ -460,7 +429,7 @@ impl PermissionRepository {
         &self,
         id: &str,
         db: &Database,
-        role_service: &RoleService,
+        role_service: &Arc<dyn RoleService>,
     ) -> Result<(), Error> {
         if id.is_empty() {
             return Err(Error::EmptyId);
@@ -483,6 +452,20 @@ impl PermissionRepository {
             .await
         {
             Ok(_) => {
+                let update = doc! {
+                    "$pull": {
+                        "dependencies": target_object_id,
+                    }
+                };
+
+                if let Err(e) = db
+                    .collection::<Permission>(&self.collection)
+                    .update_many(doc! {}, update, None)
+                    .await
+                {
+                    return Err(Error::MongoDb(e));
+                }
+
                 match role_service.delete_permission_from_all_roles(id, db).await {
                     Ok(_) => (),
                     Err(e) => return Err(Error::Role(e)),
@@ -514,14 +497,14 @@ impl PermissionRepository {
     ///
     /// # Returns
     ///
-    /// * `Result<Vec<Permission>, Error>` - The result of the operation.
+    /// * `Result<Vec<SearchHit<Permission>>, Error>` - The Permissions, sorted by their relevance.
     pub async fn search(
         &self,
         text: &str,
         limit: Option<i64>,
         page: Option<i64>,
         db: &Database,
-    ) -> Result<Vec<Permission>, Error> {
+    ) -> Result<Vec<SearchHit<Permission>>, Error> {
         if text.is_empty() {
             return Err(Error::EmptyTextSearch);
         }
@@ -539,23 +522,56 @@ impl PermissionRepository {
             }
         }
 
-        let find_options = FindOptions::builder().limit(limit).skip(skip).build();
-
         let filter = doc! {
             "$text": {
                 "$search": text,
             },
         };
 
-        let cursor = match db
-            .collection::<Permission>(&self.collection)
-            .find(filter, find_options)
-            .await
+        match find_hits(
+            db.collection::<Document>(&self.collection),
+            filter,
+            limit,
+            skip,
+        )
+        .await
         {
-            Ok(d) => d,
-            Err(e) => return Err(Error::MongoDb(e)),
-        };
+            Ok(d) => Ok(d),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
 
-        Ok(cursor.try_collect().await.unwrap_or_else(|_| vec![]))
+    /// # Summary
+    ///
+    /// Count the Permissions, optionally only the ones that match a text search.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to search for, if any.
+    /// * `db` - The database to use.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let permission_repository = PermissionRepository::new(String::from("permissions"));
+    /// let total = permission_repository.count(Some("permission_name"), &db).await;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64, Error>` - The number of Permissions.
+    pub async fn count(&self, text: Option<&str>, db: &Database) -> Result<u64, Error> {
+        let filter = text.map(|t| {
+            doc! {
+                "$text": {
+                    "$search": t,
+                },
+            }
+        });
+
+        db.collection::<Permission>(&self.collection)
+            .count_documents(filter, None)
+            .await
+            .map_err(Error::MongoDb)
     }
 }
@@ -0,0 +1,2 @@
+pub mod refresh_token_model;
+pub mod refresh_token_repository;
@@ -0,0 +1,98 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::time::SystemTime;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RefreshToken {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "tokenHash")]
+    pub token_hash: String,
+    #[serde(rename = "userId")]
+    pub user_id: ObjectId,
+    pub family: ObjectId,
+    #[serde(rename = "tokenVersion")]
+    pub token_version: i64,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "expiresAt")]
+    pub expires_at: DateTime<Utc>,
+    #[serde(rename = "usedAt", default, skip_serializing_if = "Option::is_none")]
+    pub used_at: Option<mongodb::bson::DateTime>,
+}
+
+impl RefreshToken {
+    /// # Summary
+    ///
+    /// Create a new RefreshToken.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User that the RefreshToken was issued to.
+    /// * `family` - The ID of the family of the RefreshToken. All tokens that were obtained by rotating the same login share a family.
+    /// * `token_version` - The token version of the User at the time of the login.
+    /// * `expires_in` - The amount of seconds after which the RefreshToken expires.
+    ///
+    /// # Returns
+    ///
+    /// * `RefreshToken` - The new RefreshToken, without a token hash.
+    pub fn new(
+        user_id: ObjectId,
+        family: ObjectId,
+        token_version: i64,
+        expires_in: u64,
+    ) -> RefreshToken {
+        let now: DateTime<Utc> = SystemTime::now().into();
+
+        RefreshToken {
+            id: ObjectId::new(),
+            token_hash: String::new(),
+            user_id,
+            family,
+            token_version,
+            created_at: now,
+            expires_at: now + chrono::Duration::seconds(expires_in as i64),
+            used_at: None,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check whether the RefreshToken has expired.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the RefreshToken has expired, false otherwise.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= chrono::Utc::now()
+    }
+}
+
+impl Display for RefreshToken {
+    /// # Summary
+    ///
+    /// Display the RefreshToken.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The Formatter.
+    ///
+    /// # Returns
+    ///
+    /// * `std::fmt::Result` - The result of the display.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "RefreshToken: {{ id: {}, user_id: {}, family: {}, created_at: {}, expires_at: {} }}",
+            self.id.to_hex(),
+            self.user_id.to_hex(),
+            self.family.to_hex(),
+            self.created_at,
+            self.expires_at
+        )
+    }
+}
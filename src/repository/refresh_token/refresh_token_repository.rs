@@ -0,0 +1,245 @@
+use crate::repository::refresh_token::refresh_token_model::RefreshToken;
+use mongodb::bson::doc;
+use mongodb::bson::oid::ObjectId;
+use mongodb::error::Error as MongoError;
+use mongodb::Database;
+
+#[derive(Clone)]
+pub struct RefreshTokenRepository {
+    pub collection: String,
+}
+
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Empty collection")]
+    EmptyCollection,
+    #[error("Refresh tokens are disabled")]
+    Disabled,
+    #[error("Invalid refresh token")]
+    InvalidToken,
+    #[error("The refresh token has expired")]
+    TokenExpired,
+    #[error("The refresh token was already used")]
+    TokenReused,
+    #[error("MongoDB error: {0}")]
+    MongoDb(#[source] MongoError),
+}
+
+impl RefreshTokenRepository {
+    /// # Summary
+    ///
+    /// Creates a new RefreshTokenRepository instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection` - A String that holds the collection name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let refresh_token_repository = match RefreshTokenRepository::new("refreshTokens".to_string()) {
+    ///    Ok(d) => d,
+    ///    Err(e) => panic!("Failed to initialize RefreshToken repository: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A RefreshTokenRepository instance.
+    pub fn new(collection: String) -> Result<RefreshTokenRepository, Error> {
+        if collection.is_empty() {
+            return Err(Error::EmptyCollection);
+        }
+
+        Ok(RefreshTokenRepository { collection })
+    }
+
+    /// # Summary
+    ///
+    /// Create a new refresh token.
+    ///
+    /// # Arguments
+    ///
+    /// * `refresh_token` - A RefreshToken instance.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let refresh_token = match refresh_token_repository.create(refresh_token, &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to create RefreshToken: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the created RefreshToken instance or an Error.
+    pub async fn create(
+        &self,
+        refresh_token: RefreshToken,
+        db: &Database,
+    ) -> Result<RefreshToken, Error> {
+        match db
+            .collection::<RefreshToken>(&self.collection)
+            .insert_one(&refresh_token, None)
+            .await
+        {
+            Ok(_) => Ok(refresh_token),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Find a refresh token by the hash of its token.
+    ///
+    /// # Arguments
+    ///
+    /// * `token_hash` - A string slice that holds the hash of the token.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let refresh_token = match refresh_token_repository.find_by_token_hash("hash", &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to find RefreshToken: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with an Option of a RefreshToken instance or an Error.
+    pub async fn find_by_token_hash(
+        &self,
+        token_hash: &str,
+        db: &Database,
+    ) -> Result<Option<RefreshToken>, Error> {
+        let filter = doc! {
+            "tokenHash": token_hash,
+        };
+
+        match db
+            .collection::<RefreshToken>(&self.collection)
+            .find_one(filter, None)
+            .await
+        {
+            Ok(d) => Ok(d),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Mark a refresh token as used. A refresh token can only be marked once, so that concurrent requests cannot both
+    /// rotate the same token.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the RefreshToken.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let marked = match refresh_token_repository.mark_used(refresh_token.id, &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to mark RefreshToken as used: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with true if the refresh token was marked, false if it had already been used, or an Error.
+    pub async fn mark_used(&self, id: ObjectId, db: &Database) -> Result<bool, Error> {
+        let filter = doc! {
+            "_id": id,
+            "usedAt": { "$exists": false },
+        };
+        let update = doc! {
+            "$set": { "usedAt": mongodb::bson::DateTime::now() },
+        };
+
+        match db
+            .collection::<RefreshToken>(&self.collection)
+            .update_one(filter, update, None)
+            .await
+        {
+            Ok(r) => Ok(r.modified_count == 1),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Delete all refresh tokens of a family.
+    ///
+    /// # Arguments
+    ///
+    /// * `family` - The ID of the family.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// match refresh_token_repository.delete_by_family(family, &db).await {
+    ///   Ok(_) => (),
+    ///   Err(e) => panic!("Failed to delete RefreshTokens: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the unit type or an Error.
+    pub async fn delete_by_family(&self, family: ObjectId, db: &Database) -> Result<(), Error> {
+        let filter = doc! {
+            "family": family,
+        };
+
+        match db
+            .collection::<RefreshToken>(&self.collection)
+            .delete_many(filter, None)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Delete all refresh tokens of a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// match refresh_token_repository.delete_by_user_id(user_id, &db).await {
+    ///   Ok(_) => (),
+    ///   Err(e) => panic!("Failed to delete RefreshTokens: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the unit type or an Error.
+    pub async fn delete_by_user_id(&self, user_id: ObjectId, db: &Database) -> Result<(), Error> {
+        let filter = doc! {
+            "userId": user_id,
+        };
+
+        match db
+            .collection::<RefreshToken>(&self.collection)
+            .delete_many(filter, None)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+}
@@ -0,0 +1,2 @@
+pub mod revoked_token_model;
+pub mod revoked_token_repository;
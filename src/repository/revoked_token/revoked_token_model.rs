@@ -0,0 +1,69 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::time::SystemTime;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RevokedToken {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "tokenHash")]
+    pub token_hash: String,
+    pub subject: String,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "revokedAt")]
+    pub revoked_at: DateTime<Utc>,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "expiresAt")]
+    pub expires_at: DateTime<Utc>,
+}
+
+impl RevokedToken {
+    /// # Summary
+    ///
+    /// Create a new RevokedToken.
+    ///
+    /// # Arguments
+    ///
+    /// * `token_hash` - The hash of the JWT token that was revoked.
+    /// * `subject` - The subject of the JWT token.
+    /// * `expires_at` - The time after which the JWT token would have expired anyway.
+    ///
+    /// # Returns
+    ///
+    /// * `RevokedToken` - The new RevokedToken.
+    pub fn new(token_hash: String, subject: String, expires_at: DateTime<Utc>) -> RevokedToken {
+        RevokedToken {
+            id: ObjectId::new(),
+            token_hash,
+            subject,
+            revoked_at: SystemTime::now().into(),
+            expires_at,
+        }
+    }
+}
+
+impl Display for RevokedToken {
+    /// # Summary
+    ///
+    /// Display the RevokedToken.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The Formatter.
+    ///
+    /// # Returns
+    ///
+    /// * `std::fmt::Result` - The result of the display.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "RevokedToken: {{ id: {}, subject: {}, revoked_at: {}, expires_at: {} }}",
+            self.id.to_hex(),
+            self.subject,
+            self.revoked_at,
+            self.expires_at
+        )
+    }
+}
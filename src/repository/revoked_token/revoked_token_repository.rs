@@ -0,0 +1,118 @@
+use crate::repository::revoked_token::revoked_token_model::RevokedToken;
+use mongodb::bson::doc;
+use mongodb::error::{Error as MongoError, ErrorKind, WriteFailure};
+use mongodb::Database;
+
+#[derive(Clone)]
+pub struct RevokedTokenRepository {
+    pub collection: String,
+}
+
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Empty collection")]
+    EmptyCollection,
+    #[error("MongoDB error: {0}")]
+    MongoDb(#[source] MongoError),
+}
+
+impl RevokedTokenRepository {
+    /// # Summary
+    ///
+    /// Creates a new RevokedTokenRepository instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection` - A String that holds the collection name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let revoked_token_repository = match RevokedTokenRepository::new("revokedTokens".to_string()) {
+    ///    Ok(d) => d,
+    ///    Err(e) => panic!("Failed to initialize RevokedToken repository: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A RevokedTokenRepository instance.
+    pub fn new(collection: String) -> Result<RevokedTokenRepository, Error> {
+        if collection.is_empty() {
+            return Err(Error::EmptyCollection);
+        }
+
+        Ok(RevokedTokenRepository { collection })
+    }
+
+    /// # Summary
+    ///
+    /// Add a token to the denylist. Revoking a token that was already revoked is not an error.
+    ///
+    /// # Arguments
+    ///
+    /// * `revoked_token` - A RevokedToken instance.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// match revoked_token_repository.create(revoked_token, &db).await {
+    ///   Ok(_) => (),
+    ///   Err(e) => panic!("Failed to create RevokedToken: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the unit type or an Error.
+    pub async fn create(&self, revoked_token: RevokedToken, db: &Database) -> Result<(), Error> {
+        match db
+            .collection::<RevokedToken>(&self.collection)
+            .insert_one(&revoked_token, None)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => match e.kind.as_ref() {
+                ErrorKind::Write(WriteFailure::WriteError(w)) if w.code == 11000 => Ok(()),
+                _ => Err(Error::MongoDb(e)),
+            },
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check whether a token is on the denylist.
+    ///
+    /// # Arguments
+    ///
+    /// * `token_hash` - A string slice that holds the hash of the token.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let revoked = match revoked_token_repository.exists("hash", &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to find RevokedToken: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with true if the token was revoked, false otherwise, or an Error.
+    pub async fn exists(&self, token_hash: &str, db: &Database) -> Result<bool, Error> {
+        let filter = doc! {
+            "tokenHash": token_hash,
+        };
+
+        match db
+            .collection::<RevokedToken>(&self.collection)
+            .count_documents(filter, None)
+            .await
+        {
+            Ok(c) => Ok(c > 0),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+}
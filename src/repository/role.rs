@@ -1,2 +1,3 @@
+pub mod role_filter;
 pub mod role_model;
 pub mod role_repository;
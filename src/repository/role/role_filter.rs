@@ -0,0 +1,79 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, Document};
+
+#[derive(Clone, Default)]
+pub struct RoleFilter {
+    pub text: Option<String>,
+    pub permission: Option<ObjectId>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+}
+
+impl RoleFilter {
+    /// # Summary
+    ///
+    /// Check whether the RoleFilter does not restrict the Roles in any way.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let filter = RoleFilter::default();
+    /// assert!(filter.is_empty());
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if none of the criteria are set.
+    pub fn is_empty(&self) -> bool {
+        self.text.is_none()
+            && self.permission.is_none()
+            && self.created_after.is_none()
+            && self.created_before.is_none()
+    }
+
+    /// # Summary
+    ///
+    /// Convert the RoleFilter into a query document that matches the global Roles that meet all criteria.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let filter = RoleFilter {
+    ///     text: Some(String::from("admin")),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let document = filter.to_document();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Document` - The query document.
+    pub fn to_document(&self) -> Document {
+        let mut document = doc! {
+            "organizationId": null,
+        };
+
+        if let Some(text) = &self.text {
+            document.insert("$text", doc! { "$search": text });
+        }
+
+        if let Some(permission) = &self.permission {
+            document.insert("permissions", permission);
+        }
+
+        let mut created_at = Document::new();
+        if let Some(after) = self.created_after {
+            created_at.insert("$gte", mongodb::bson::DateTime::from_chrono(after));
+        }
+        if let Some(before) = self.created_before {
+            created_at.insert("$lte", mongodb::bson::DateTime::from_chrono(before));
+        }
+        if !created_at.is_empty() {
+            document.insert("createdAt", created_at);
+        }
+
+        document
+    }
+}
@@ -1,6 +1,7 @@
 use crate::web::dto::role::create_role::CreateRole;
 use chrono::{DateTime, Utc};
 use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, Document};
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 use std::time::SystemTime;
@@ -12,6 +13,20 @@ pub struct Role {
     pub name: String,
     pub description: Option<String>,
     pub permissions: Option<Vec<ObjectId>>,
+    #[serde(
+        rename = "organizationId",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub organization_id: Option<ObjectId>,
+    #[serde(rename = "requireMfa", default)]
+    pub require_mfa: bool,
+    #[serde(
+        rename = "permissionExpirations",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub permission_expirations: Vec<PermissionExpiration>,
     #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     #[serde(rename = "createdAt")]
     pub created_at: DateTime<Utc>,
@@ -20,6 +35,14 @@ pub struct Role {
     pub updated_at: DateTime<Utc>,
 }
 
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PermissionExpiration {
+    pub permission: ObjectId,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "expiresAt")]
+    pub expires_at: DateTime<Utc>,
+}
+
 impl Role {
     /// # Summary
     ///
@@ -52,12 +75,136 @@ impl Role {
             name,
             description,
             permissions,
+            organization_id: None,
+            require_mfa: false,
+            permission_expirations: vec![],
             created_at: now,
             updated_at: now,
         }
     }
 }
 
+impl Role {
+    /// # Summary
+    ///
+    /// Check whether the Role can be used within an Organization. Global roles can be used everywhere, while roles
+    /// of an Organization can only be used within that Organization.
+    ///
+    /// # Arguments
+    ///
+    /// * `organization_id` - The ID of the Organization, or None for the global scope.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let role = Role::new(String::from("Role Name"), None, None);
+    /// assert!(role.in_scope(None));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the Role is global or belongs to the Organization.
+    pub fn in_scope(&self, organization_id: Option<&ObjectId>) -> bool {
+        match &self.organization_id {
+            None => true,
+            Some(o) => Some(o) == organization_id,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Get the Permissions of the Role of which the grant has not expired. Permissions without an expiration are
+    /// granted indefinitely.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// if let Some(oid_vec) = role.active_permissions() {
+    ///   let permissions = permission_service.find_by_id_vec(oid_vec, &db).await?;
+    /// }
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Vec<ObjectId>>` - The IDs of the Permissions that are currently granted by the Role.
+    pub fn active_permissions(&self) -> Option<Vec<ObjectId>> {
+        let now = Utc::now();
+
+        self.permissions.as_ref().map(|p| {
+            p.iter()
+                .filter(|id| {
+                    !self
+                        .permission_expirations
+                        .iter()
+                        .any(|e| &e.permission == *id && e.expires_at <= now)
+                })
+                .cloned()
+                .collect()
+        })
+    }
+
+    /// # Summary
+    ///
+    /// Remove the expirations of the Permissions that are no longer granted by the Role.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// role.permissions = None;
+    /// role.retain_permission_expirations();
+    /// assert!(role.permission_expirations.is_empty());
+    /// ```
+    pub fn retain_permission_expirations(&mut self) {
+        let permissions = self.permissions.as_deref().unwrap_or_default();
+        self.permission_expirations
+            .retain(|e| permissions.contains(&e.permission));
+    }
+
+    /// # Summary
+    ///
+    /// Get the JSON Schema that is used to validate Role documents that are written to the database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let validator = doc! { "$jsonSchema": Role::json_schema() };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Document` - The JSON Schema of a Role.
+    pub fn json_schema() -> Document {
+        doc! {
+            "bsonType": "object",
+            "required": ["_id", "name", "createdAt", "updatedAt"],
+            "properties": {
+                "_id": { "bsonType": "objectId" },
+                "name": { "bsonType": "string", "minLength": 1 },
+                "description": { "bsonType": ["string", "null"] },
+                "permissions": {
+                    "bsonType": ["array", "null"],
+                    "items": { "bsonType": "objectId" },
+                },
+                "organizationId": { "bsonType": "objectId" },
+                "requireMfa": { "bsonType": "bool" },
+                "permissionExpirations": {
+                    "bsonType": "array",
+                    "items": {
+                        "bsonType": "object",
+                        "required": ["permission", "expiresAt"],
+                        "properties": {
+                            "permission": { "bsonType": "objectId" },
+                            "expiresAt": { "bsonType": "date" },
+                        }
+                    },
+                },
+                "createdAt": { "bsonType": "date" },
+                "updatedAt": { "bsonType": "date" },
+            }
+        }
+    }
+}
+
 impl From<CreateRole> for Role {
     /// # Summary
     ///
@@ -74,6 +221,8 @@ impl From<CreateRole> for Role {
     ///   name: String::from("name"),
     ///   description: Some(String::from("description")),
     ///   permissions: Some(vec![String::from("permission")]),
+    ///   require_mfa: false,
+    ///   permission_expirations: None,
     /// };
     ///
     /// let role = Role::from(create_role);
@@ -104,6 +253,9 @@ impl From<CreateRole> for Role {
             name: create_role.name,
             description: create_role.description,
             permissions,
+            organization_id: None,
+            require_mfa: create_role.require_mfa,
+            permission_expirations: vec![],
             created_at: now,
             updated_at: now,
         }
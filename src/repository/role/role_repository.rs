@@ -1,16 +1,18 @@
 use crate::repository::audit::audit_repository::Error as AuditError;
+use crate::repository::role::role_filter::RoleFilter;
 use crate::repository::role::role_model::Role;
+use crate::repository::search_hit::{find_hits, SearchHit};
 use crate::repository::user::user_repository::Error as UserError;
 use crate::services::user::user_service::UserService;
 use chrono::{DateTime, Utc};
 use futures::TryStreamExt;
 use mongodb::bson::doc;
 use mongodb::bson::oid::ObjectId;
-use mongodb::bson::Bson;
+use mongodb::bson::{Bson, Document};
 use mongodb::error::Error as MongoError;
-use mongodb::options::FindOptions;
+use mongodb::options::{FindOneAndUpdateOptions, FindOptions, ReturnDocument};
 use mongodb::Database;
-use std::fmt;
+use std::sync::Arc;
 use std::time::SystemTime;
 
 #[derive(Clone)]
@@ -18,46 +20,30 @@ pub struct RoleRepository {
     pub collection: String,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, thiserror::Error)]
 pub enum Error {
+    #[error("Invalid Role ID: {0}")]
     InvalidId(String),
+    #[error("Empty collection")]
     EmptyCollection,
+    #[error("Empty Role ID")]
     EmptyId,
+    #[error("Empty Role name")]
     EmptyName,
+    #[error("Empty text search")]
     EmptyTextSearch,
+    #[error("Role name already taken")]
     NameAlreadyTaken,
+    #[error("Role not found: {0}")]
     RoleNotFound(String),
-    MongoDb(MongoError),
-    User(UserError),
-    Audit(AuditError),
-}
-
-impl fmt::Display for Error {
-    /// # Summary
-    ///
-    /// Formats the value using the given formatter.
-    ///
-    /// # Arguments
-    ///
-    /// * `f` - A reference to a formatter.
-    ///
-    /// # Returns
-    ///
-    /// A fmt::Result.
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match &self {
-            Error::InvalidId(id) => write!(f, "Invalid Role ID: {}", id),
-            Error::EmptyCollection => write!(f, "Empty collection"),
-            Error::EmptyId => write!(f, "Empty Role ID"),
-            Error::EmptyName => write!(f, "Empty Role name"),
-            Error::EmptyTextSearch => write!(f, "Empty text search"),
-            Error::NameAlreadyTaken => write!(f, "Role name already taken"),
-            Error::RoleNotFound(id) => write!(f, "Role not found: {}", id),
-            Error::MongoDb(e) => write!(f, "MongoDB error: {}", e),
-            Error::User(e) => write!(f, "User error: {}", e),
-            Error::Audit(e) => write!(f, "Audit error: {}", e),
-        }
-    }
+    #[error("A Role cannot have more than {0} permissions")]
+    TooManyPermissions(usize),
+    #[error("MongoDB error: {0}")]
+    MongoDb(#[source] MongoError),
+    #[error("User error: {0}")]
+    User(#[source] UserError),
+    #[error("Audit error: {0}")]
+    Audit(#[source] AuditError),
 }
 
 impl RoleRepository {
@@ -161,7 +147,7 @@ impl RoleRepository {
 
     /// # Summary
     ///
-    /// Find all roles.
+    /// Find all global roles.
     ///
     /// # Arguments
     ///
@@ -204,9 +190,14 @@ impl RoleRepository {
 
         let find_options = FindOptions::builder().limit(limit).skip(skip).build();
 
+        // Roles of an Organization are only listed as part of that Organization
+        let filter = doc! {
+            "organizationId": null,
+        };
+
         let cursor = match db
             .collection::<Role>(&self.collection)
-            .find(None, find_options)
+            .find(filter, find_options)
             .await
         {
             Ok(d) => d,
@@ -335,7 +326,7 @@ impl RoleRepository {
 
     /// # Summary
     ///
-    /// Find a role by its name.
+    /// Find a global role by its name.
     ///
     /// # Arguments
     ///
@@ -370,7 +361,10 @@ impl RoleRepository {
             options: String::from("i"),
         };
 
-        let filter = doc! { "name": { "$regex": Bson::RegularExpression(re) } };
+        let filter = doc! {
+            "name": { "$regex": Bson::RegularExpression(re) },
+            "organizationId": null,
+        };
 
         match db
             .collection::<Role>(&self.collection)
@@ -382,6 +376,102 @@ impl RoleRepository {
         }
     }
 
+    /// # Summary
+    ///
+    /// Find a role of an organization by its name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - A string slice that holds the name.
+    /// * `organization_id` - The ID of the organization.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let role = match role_repository.find_by_name_in_organization("name", organization_id, &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to find Role by name: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with an Option of a Role instance or an Error.
+    pub async fn find_by_name_in_organization(
+        &self,
+        name: &str,
+        organization_id: ObjectId,
+        db: &Database,
+    ) -> Result<Option<Role>, Error> {
+        if name.is_empty() {
+            return Err(Error::EmptyName);
+        }
+
+        let regex_pattern = format!("^{}$", regex::escape(name));
+        let re = mongodb::bson::Regex {
+            pattern: regex_pattern,
+            options: String::from("i"),
+        };
+
+        let filter = doc! {
+            "name": { "$regex": Bson::RegularExpression(re) },
+            "organizationId": organization_id,
+        };
+
+        match db
+            .collection::<Role>(&self.collection)
+            .find_one(filter, None)
+            .await
+        {
+            Ok(d) => Ok(d),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Find all roles of an organization.
+    ///
+    /// # Arguments
+    ///
+    /// * `organization_id` - The ID of the organization.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let roles = match role_repository.find_by_organization_id(organization_id, &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to find Roles: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with a vector of Role instances or an Error.
+    pub async fn find_by_organization_id(
+        &self,
+        organization_id: ObjectId,
+        db: &Database,
+    ) -> Result<Vec<Role>, Error> {
+        let filter = doc! {
+            "organizationId": organization_id,
+        };
+        let find_options = FindOptions::builder().sort(doc! { "name": 1 }).build();
+
+        let cursor = match db
+            .collection::<Role>(&self.collection)
+            .find(filter, find_options)
+            .await
+        {
+            Ok(d) => d,
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        Ok(cursor.try_collect().await.unwrap_or_else(|_| vec![]))
+    }
+
     /// # Summary
     ///
     /// Update a Role.
@@ -409,6 +499,11 @@ impl RoleRepository {
     ///
     /// A Result with a Role instance or an Error.
     pub async fn update(&self, role: Role, db: &Database) -> Result<Role, Error> {
+        let permission_expirations = match mongodb::bson::to_bson(&role.permission_expirations) {
+            Ok(d) => d,
+            Err(e) => return Err(Error::MongoDb(e.into())),
+        };
+
         // Check if the name is already taken
         match self.find_by_name(&role.name.to_lowercase(), db).await {
             Ok(r) => {
@@ -434,6 +529,8 @@ impl RoleRepository {
                 "name": role.name,
                 "description": role.description,
                 "permissions": role.permissions,
+                "requireMfa": role.require_mfa,
+                "permissionExpirations": permission_expirations,
                 "updated_at": now,
             }
         };
@@ -454,6 +551,82 @@ impl RoleRepository {
         Ok(role.unwrap())
     }
 
+    /// # Summary
+    ///
+    /// Replace the permissions of a Role if it still grants the permissions of an earlier version of it. The comparison
+    /// and the update are a single operation, so that a concurrent change to the permissions is never overwritten.
+    ///
+    /// # Arguments
+    ///
+    /// * `role` - A Role instance that holds the new permissions.
+    /// * `expected` - A Role instance that holds the permissions that the stored Role must grant.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// match role_repository.update_permissions(role, &previous, &db).await {
+    ///   Ok(Some(_)) => (),
+    ///   Ok(None) => panic!("The Role was changed in the meantime"),
+    ///   Err(e) => panic!("Failed to update Role: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the updated Role instance, None if the Role does not grant the expected permissions, or an Error.
+    pub async fn update_permissions(
+        &self,
+        role: Role,
+        expected: &Role,
+        db: &Database,
+    ) -> Result<Option<Role>, Error> {
+        let permission_expirations = match mongodb::bson::to_bson(&role.permission_expirations) {
+            Ok(d) => d,
+            Err(e) => return Err(Error::MongoDb(e.into())),
+        };
+
+        let mut filter = doc! {
+            "_id": role.id,
+            "permissions": expected.permissions.clone(),
+        };
+
+        // A Role without expirations may not store the field at all
+        if expected.permission_expirations.is_empty() {
+            filter.insert(
+                "permissionExpirations",
+                doc! { "$in": [Bson::Null, Bson::Array(vec![])] },
+            );
+        } else {
+            match mongodb::bson::to_bson(&expected.permission_expirations) {
+                Ok(d) => filter.insert("permissionExpirations", d),
+                Err(e) => return Err(Error::MongoDb(e.into())),
+            };
+        }
+
+        let now: DateTime<Utc> = SystemTime::now().into();
+        let update = doc! {
+            "$set": {
+                "permissions": role.permissions,
+                "permissionExpirations": permission_expirations,
+                "updatedAt": mongodb::bson::DateTime::from_chrono(now),
+            }
+        };
+
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+
+        match db
+            .collection::<Role>(&self.collection)
+            .find_one_and_update(filter, update, options)
+            .await
+        {
+            Ok(d) => Ok(d),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
     /// # Summary
     ///
     /// Delete a Role.
@@ -485,7 +658,7 @@ impl RoleRepository {
         &self,
         id: &str,
         db: &Database,
-        user_service: &UserService,
+        user_service: &Arc<dyn UserService>,
     ) -> Result<(), Error> {
         if id.is_empty() {
             return Err(Error::EmptyId);
@@ -515,6 +688,46 @@ impl RoleRepository {
         }
     }
 
+    /// # Summary
+    ///
+    /// Delete all roles of an organization.
+    ///
+    /// # Arguments
+    ///
+    /// * `organization_id` - The ID of the organization.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// match role_repository.delete_by_organization_id(organization_id, &db).await {
+    ///   Ok(_) => (),
+    ///   Err(e) => panic!("Failed to delete Roles: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with an empty return value or an Error.
+    pub async fn delete_by_organization_id(
+        &self,
+        organization_id: ObjectId,
+        db: &Database,
+    ) -> Result<(), Error> {
+        let filter = doc! {
+            "organizationId": organization_id,
+        };
+
+        match db
+            .collection::<Role>(&self.collection)
+            .delete_many(filter, None)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
     /// # Summary
     ///
     /// Delete a permission from all roles.
@@ -577,11 +790,93 @@ impl RoleRepository {
 
     /// # Summary
     ///
-    /// Search for Roles.
+    /// Remove the Permissions of which the grant has expired from all Roles.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let role_repository = RoleRepository::new("roles".to_string()).unwrap();
+    ///
+    /// let count = match role_repository.remove_expired_permissions(&db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to remove expired Permissions: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64, Error>` - The number of updated Roles or the Error that occurred.
+    pub async fn remove_expired_permissions(&self, db: &Database) -> Result<u64, Error> {
+        let now = Utc::now();
+
+        let filter = doc! {
+            "permissionExpirations.expiresAt": { "$lte": mongodb::bson::DateTime::from_chrono(now) },
+        };
+
+        let cursor = match db
+            .collection::<Role>(&self.collection)
+            .find(filter, None)
+            .await
+        {
+            Ok(d) => d,
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        let roles: Vec<Role> = match cursor.try_collect().await {
+            Ok(d) => d,
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        let mut count = 0;
+        for role in roles {
+            let (expired, active): (Vec<_>, Vec<_>) = role
+                .permission_expirations
+                .into_iter()
+                .partition(|e| e.expires_at <= now);
+
+            let permissions: Option<Vec<ObjectId>> = role.permissions.map(|p| {
+                p.into_iter()
+                    .filter(|id| !expired.iter().any(|e| &e.permission == id))
+                    .collect()
+            });
+
+            let permission_expirations = match mongodb::bson::to_bson(&active) {
+                Ok(d) => d,
+                Err(e) => return Err(Error::MongoDb(e.into())),
+            };
+
+            let update = doc! {
+                "$set": {
+                    "permissions": permissions,
+                    "permissionExpirations": permission_expirations,
+                    "updatedAt": mongodb::bson::DateTime::from_chrono(now),
+                }
+            };
+
+            match db
+                .collection::<Role>(&self.collection)
+                .update_one(doc! { "_id": role.id }, update, None)
+                .await
+            {
+                Ok(r) => count += r.modified_count,
+                Err(e) => return Err(Error::MongoDb(e)),
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// # Summary
+    ///
+    /// Search for Roles that match a text search and/or structured criteria, such as a Permission that must be granted.
     ///
     /// # Arguments
     ///
-    /// * `text` - The text to search for.
+    /// * `filter` - The criteria that the Roles must meet.
     /// * `limit` - The limit of Roles to find.
     /// * `page` - The page of Roles to find.
     /// * `db` - The database to use.
@@ -590,7 +885,13 @@ impl RoleRepository {
     ///
     /// ```
     /// let role_repository = RoleRepository::new("roles".to_string()).unwrap();
-    /// let roles = match role_repository.search("text", Some(10), Some(1), &db).await {
+    /// let filter = RoleFilter {
+    ///   text: Some(String::from("admin")),
+    ///   permission: Some(permission.id),
+    ///   ..Default::default()
+    /// };
+    ///
+    /// let roles = match role_repository.search(&filter, Some(10), Some(1), &db).await {
     ///   Ok(d) => d,
     ///   Err(e) => panic!("Failed to search for Roles: {:?}", e),
     /// };
@@ -598,15 +899,15 @@ impl RoleRepository {
     ///
     /// # Returns
     ///
-    /// * `Result<Vec<Role>, Error>` - The result of the operation.
+    /// * `Result<Vec<SearchHit<Role>>, Error>` - The Roles, sorted by their relevance if the filter contains text.
     pub async fn search(
         &self,
-        text: &str,
+        filter: &RoleFilter,
         limit: Option<i64>,
         page: Option<i64>,
         db: &Database,
-    ) -> Result<Vec<Role>, Error> {
-        if text.is_empty() {
+    ) -> Result<Vec<SearchHit<Role>>, Error> {
+        if filter.is_empty() || filter.text.as_ref().is_some_and(|t| t.is_empty()) {
             return Err(Error::EmptyTextSearch);
         }
 
@@ -623,23 +924,42 @@ impl RoleRepository {
             }
         }
 
-        let find_options = FindOptions::builder().limit(limit).skip(skip).build();
-
-        let filter = doc! {
-            "$text": {
-                "$search": text,
-            },
-        };
-
-        let cursor = match db
-            .collection::<Role>(&self.collection)
-            .find(filter, find_options)
-            .await
+        match find_hits(
+            db.collection::<Document>(&self.collection),
+            filter.to_document(),
+            limit,
+            skip,
+        )
+        .await
         {
-            Ok(d) => d,
-            Err(e) => return Err(Error::MongoDb(e)),
-        };
+            Ok(d) => Ok(d),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
 
-        Ok(cursor.try_collect().await.unwrap_or_else(|_| vec![]))
+    /// # Summary
+    ///
+    /// Count the global Roles that match a RoleFilter. An empty RoleFilter counts all global Roles.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - The criteria that the Roles must meet.
+    /// * `db` - The database to use.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let role_repository = RoleRepository::new("roles".to_string()).unwrap();
+    /// let total = role_repository.count(&RoleFilter::default(), &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64, Error>` - The number of Roles.
+    pub async fn count(&self, filter: &RoleFilter, db: &Database) -> Result<u64, Error> {
+        db.collection::<Role>(&self.collection)
+            .count_documents(filter.to_document(), None)
+            .await
+            .map_err(Error::MongoDb)
     }
 }
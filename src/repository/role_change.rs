@@ -0,0 +1,2 @@
+pub mod role_change_model;
+pub mod role_change_repository;
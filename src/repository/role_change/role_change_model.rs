@@ -0,0 +1,150 @@
+use crate::repository::role::role_model::PermissionExpiration;
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::time::SystemTime;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RoleChangeStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+impl Display for RoleChangeStatus {
+    /// # Summary
+    ///
+    /// Display the RoleChangeStatus.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The Formatter.
+    ///
+    /// # Returns
+    ///
+    /// * `std::fmt::Result` - The result of the display.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RoleChangeStatus::Pending => write!(f, "pending"),
+            RoleChangeStatus::Approved => write!(f, "approved"),
+            RoleChangeStatus::Rejected => write!(f, "rejected"),
+        }
+    }
+}
+
+/// The change that is applied once a RoleChange is approved.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(tag = "type")]
+pub enum RoleChangeKind {
+    /// Replace the permissions of a Role. The previous permissions are those that the Role held when the change was
+    /// requested, and the change is only applied if the Role still holds them.
+    #[serde(rename = "rolePermissions")]
+    RolePermissions {
+        #[serde(rename = "roleId")]
+        role_id: ObjectId,
+        permissions: Option<Vec<ObjectId>>,
+        #[serde(rename = "permissionExpirations", default)]
+        permission_expirations: Vec<PermissionExpiration>,
+        #[serde(rename = "previousPermissions", default)]
+        previous_permissions: Option<Vec<ObjectId>>,
+        #[serde(rename = "previousPermissionExpirations", default)]
+        previous_permission_expirations: Vec<PermissionExpiration>,
+    },
+    /// Grant the ADMIN role to a User.
+    #[serde(rename = "adminGrant")]
+    AdminGrant {
+        #[serde(rename = "userId")]
+        user_id: ObjectId,
+        #[serde(rename = "roleId")]
+        role_id: ObjectId,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RoleChange {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub change: RoleChangeKind,
+    pub status: RoleChangeStatus,
+    #[serde(rename = "requestedBy")]
+    pub requested_by: ObjectId,
+    #[serde(rename = "reviewedBy", default)]
+    pub reviewed_by: Option<ObjectId>,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(
+        rename = "reviewedAt",
+        default,
+        with = "crate::repository::user::user_model::optional_chrono_datetime_as_bson_datetime"
+    )]
+    pub reviewed_at: Option<DateTime<Utc>>,
+}
+
+impl RoleChange {
+    /// # Summary
+    ///
+    /// Create a new pending RoleChange.
+    ///
+    /// # Arguments
+    ///
+    /// * `change` - The change that is applied once the RoleChange is approved.
+    /// * `requested_by` - The ID of the User that requested the change.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let role_change = RoleChange::new(RoleChangeKind::AdminGrant { user_id, role_id }, requested_by);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `RoleChange` - The new RoleChange.
+    pub fn new(change: RoleChangeKind, requested_by: ObjectId) -> RoleChange {
+        RoleChange {
+            id: ObjectId::new(),
+            change,
+            status: RoleChangeStatus::Pending,
+            requested_by,
+            reviewed_by: None,
+            created_at: SystemTime::now().into(),
+            reviewed_at: None,
+        }
+    }
+}
+
+impl Display for RoleChange {
+    /// # Summary
+    ///
+    /// Display the RoleChange.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The Formatter.
+    ///
+    /// # Returns
+    ///
+    /// * `std::fmt::Result` - The result of the display.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let change = match &self.change {
+            RoleChangeKind::RolePermissions { role_id, .. } => {
+                format!("permissions of role {}", role_id.to_hex())
+            }
+            RoleChangeKind::AdminGrant { user_id, .. } => {
+                format!("admin grant to user {}", user_id.to_hex())
+            }
+        };
+
+        write!(
+            f,
+            "RoleChange: {{ id: {}, change: {}, status: {}, requested_by: {}, created_at: {} }}",
+            self.id.to_hex(),
+            change,
+            self.status,
+            self.requested_by.to_hex(),
+            self.created_at
+        )
+    }
+}
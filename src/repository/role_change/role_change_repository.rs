@@ -0,0 +1,308 @@
+use crate::repository::role::role_repository::Error as RoleError;
+use crate::repository::role_change::role_change_model::{RoleChange, RoleChangeStatus};
+use crate::repository::user::user_repository::Error as UserError;
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use mongodb::bson::doc;
+use mongodb::bson::oid::ObjectId;
+use mongodb::error::Error as MongoError;
+use mongodb::options::{FindOneAndUpdateOptions, FindOptions, ReturnDocument};
+use mongodb::Database;
+use std::time::SystemTime;
+
+#[derive(Clone)]
+pub struct RoleChangeRepository {
+    pub collection: String,
+}
+
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Invalid RoleChange ID: {0}")]
+    InvalidId(String),
+    #[error("Empty collection")]
+    EmptyCollection,
+    #[error("Empty RoleChange ID")]
+    EmptyId,
+    #[error("RoleChange not found: {0}")]
+    RoleChangeNotFound(String),
+    #[error("RoleChange {0} is no longer pending")]
+    NotPending(String),
+    #[error("A RoleChange cannot be reviewed by the User that requested it")]
+    SelfReview,
+    #[error("Role {0} was changed after the RoleChange was requested")]
+    RoleChanged(String),
+    #[error("Role error: {0}")]
+    Role(#[source] RoleError),
+    #[error("User error: {0}")]
+    User(#[source] UserError),
+    #[error("MongoDB error: {0}")]
+    MongoDb(#[source] MongoError),
+}
+
+impl RoleChangeRepository {
+    /// # Summary
+    ///
+    /// Creates a new RoleChangeRepository instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection` - A String that holds the collection name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let role_change_repository = match RoleChangeRepository::new("roleChanges".to_string()) {
+    ///    Ok(d) => d,
+    ///    Err(e) => panic!("Failed to initialize RoleChange repository: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A RoleChangeRepository instance.
+    pub fn new(collection: String) -> Result<RoleChangeRepository, Error> {
+        if collection.is_empty() {
+            return Err(Error::EmptyCollection);
+        }
+
+        Ok(RoleChangeRepository { collection })
+    }
+
+    /// # Summary
+    ///
+    /// Create a new role change.
+    ///
+    /// # Arguments
+    ///
+    /// * `role_change` - A RoleChange instance.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let role_change = match role_change_repository.create(role_change, &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to create RoleChange: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the created RoleChange instance or an Error.
+    pub async fn create(
+        &self,
+        role_change: RoleChange,
+        db: &Database,
+    ) -> Result<RoleChange, Error> {
+        match db
+            .collection::<RoleChange>(&self.collection)
+            .insert_one(&role_change, None)
+            .await
+        {
+            Ok(_) => Ok(role_change),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Find a role change by its ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - A string slice that holds the ID of the RoleChange.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let role_change = match role_change_repository.find_by_id("id", &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to find RoleChange: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with an Option of a RoleChange instance or an Error.
+    pub async fn find_by_id(&self, id: &str, db: &Database) -> Result<Option<RoleChange>, Error> {
+        if id.is_empty() {
+            return Err(Error::EmptyId);
+        }
+
+        let target_object_id = match ObjectId::parse_str(id) {
+            Ok(res) => res,
+            Err(e) => {
+                return Err(Error::InvalidId(e.to_string()));
+            }
+        };
+
+        match db
+            .collection::<RoleChange>(&self.collection)
+            .find_one(doc! { "_id": target_object_id }, None)
+            .await
+        {
+            Ok(d) => Ok(d),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Find all pending role changes, oldest first.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let role_changes = match role_change_repository.find_pending(&db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to find RoleChanges: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with a vector of RoleChange instances or an Error.
+    pub async fn find_pending(&self, db: &Database) -> Result<Vec<RoleChange>, Error> {
+        let filter = doc! {
+            "status": RoleChangeStatus::Pending.to_string(),
+        };
+        let find_options = FindOptions::builder().sort(doc! { "createdAt": 1 }).build();
+
+        let cursor = match db
+            .collection::<RoleChange>(&self.collection)
+            .find(filter, find_options)
+            .await
+        {
+            Ok(d) => d,
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        Ok(cursor.try_collect().await.unwrap_or_else(|_| vec![]))
+    }
+
+    /// # Summary
+    ///
+    /// Record the review of a pending role change. Only the first review of a role change is recorded.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the RoleChange.
+    /// * `status` - The RoleChangeStatus that results from the review.
+    /// * `reviewed_by` - The ID of the User that reviewed the RoleChange.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let role_change = match role_change_repository.review(id, RoleChangeStatus::Approved, user_id, &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to review RoleChange: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the reviewed RoleChange instance or an Error.
+    pub async fn review(
+        &self,
+        id: ObjectId,
+        status: RoleChangeStatus,
+        reviewed_by: ObjectId,
+        db: &Database,
+    ) -> Result<RoleChange, Error> {
+        let now: DateTime<Utc> = SystemTime::now().into();
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+
+        match db
+            .collection::<RoleChange>(&self.collection)
+            .find_one_and_update(
+                doc! {
+                    "_id": id,
+                    "status": RoleChangeStatus::Pending.to_string(),
+                },
+                doc! {
+                    "$set": {
+                        "status": status.to_string(),
+                        "reviewedBy": reviewed_by,
+                        "reviewedAt": mongodb::bson::DateTime::from_chrono(now),
+                    },
+                },
+                options,
+            )
+            .await
+        {
+            Ok(Some(r)) => Ok(r),
+            Ok(None) => Err(Error::NotPending(id.to_hex())),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Undo the approval of a role change that could not be applied. The role change is either rejected, or becomes
+    /// pending again so that it can be reviewed once more.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the RoleChange.
+    /// * `status` - The new status of the RoleChange.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// match role_change_repository.revert_approval(id, RoleChangeStatus::Pending, &db).await {
+    ///   Ok(_) => (),
+    ///   Err(e) => panic!("Failed to revert RoleChange: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the unit type or an Error.
+    pub async fn revert_approval(
+        &self,
+        id: ObjectId,
+        status: RoleChangeStatus,
+        db: &Database,
+    ) -> Result<(), Error> {
+        let mut update = doc! {
+            "$set": {
+                "status": status.to_string(),
+            },
+        };
+
+        if status == RoleChangeStatus::Pending {
+            update.insert(
+                "$unset",
+                doc! {
+                    "reviewedBy": "",
+                    "reviewedAt": "",
+                },
+            );
+        }
+
+        match db
+            .collection::<RoleChange>(&self.collection)
+            .update_one(
+                doc! {
+                    "_id": id,
+                    "status": RoleChangeStatus::Approved.to_string(),
+                },
+                update,
+                None,
+            )
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+}
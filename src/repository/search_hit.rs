@@ -0,0 +1,96 @@
+use futures::TryStreamExt;
+use mongodb::bson::{doc, from_document, Document};
+use mongodb::error::Error as MongoError;
+use mongodb::options::FindOptions;
+use mongodb::Collection;
+use serde::de::DeserializeOwned;
+
+/// The name of the field that temporarily holds the relevance of a search result.
+const SCORE_FIELD: &str = "_textScore";
+
+/// An entity that was found by a search, together with its relevance.
+#[derive(Clone)]
+pub struct SearchHit<T> {
+    pub entity: T,
+    pub score: Option<f64>,
+}
+
+impl<T> From<T> for SearchHit<T> {
+    /// # Summary
+    ///
+    /// Wrap an entity that was not found by a text search, which therefore has no relevance.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The entity.
+    ///
+    /// # Returns
+    ///
+    /// * `SearchHit<T>` - The SearchHit without a score.
+    fn from(value: T) -> Self {
+        SearchHit {
+            entity: value,
+            score: None,
+        }
+    }
+}
+
+/// # Summary
+///
+/// Find the entities that match a filter. If the filter contains a text search, the entities are sorted by their
+/// relevance and the text score that MongoDB assigned to them is returned along with them.
+///
+/// # Arguments
+///
+/// * `collection` - The Collection to search.
+/// * `filter` - The query document.
+/// * `limit` - The limit of entities to find.
+/// * `skip` - The number of entities to skip.
+///
+/// # Example
+///
+/// ```
+/// let hits: Vec<SearchHit<Role>> = find_hits(
+///     db.collection::<Document>("roles"),
+///     filter.to_document(),
+///     Some(10),
+///     None,
+/// )
+/// .await?;
+/// ```
+///
+/// # Returns
+///
+/// * `Result<Vec<SearchHit<T>>, MongoError>` - The entities and their scores, or the MongoError that occurred.
+pub async fn find_hits<T: DeserializeOwned>(
+    collection: Collection<Document>,
+    filter: Document,
+    limit: Option<i64>,
+    skip: Option<u64>,
+) -> Result<Vec<SearchHit<T>>, MongoError> {
+    let scored = filter.contains_key("$text");
+
+    let find_options = if scored {
+        FindOptions::builder()
+            .limit(limit)
+            .skip(skip)
+            .projection(doc! { SCORE_FIELD: { "$meta": "textScore" } })
+            .sort(doc! { SCORE_FIELD: { "$meta": "textScore" } })
+            .build()
+    } else {
+        FindOptions::builder().limit(limit).skip(skip).build()
+    };
+
+    let cursor = collection.find(filter, find_options).await?;
+    let documents: Vec<Document> = cursor.try_collect().await.unwrap_or_else(|_| vec![]);
+
+    Ok(documents
+        .into_iter()
+        .filter_map(|mut d| {
+            let score = d.remove(SCORE_FIELD).and_then(|s| s.as_f64());
+            from_document::<T>(d)
+                .ok()
+                .map(|entity| SearchHit { entity, score })
+        })
+        .collect())
+}
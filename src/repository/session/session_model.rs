@@ -0,0 +1,133 @@
+use crate::services::jwt::jwt_service::Claims;
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::time::SystemTime;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Session {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "tokenHash")]
+    pub token_hash: String,
+    /// The Claims of the JWT tokens that are signed for the Session. Sessions that were created by earlier versions
+    /// stored the JWT token instead and have no Claims.
+    #[serde(default)]
+    pub claims: Option<Claims>,
+    pub subject: String,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "expiresAt")]
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Session {
+    /// # Summary
+    ///
+    /// Create a new Session.
+    ///
+    /// # Arguments
+    ///
+    /// * `token_hash` - The hash of the opaque session token that is handed to the client.
+    /// * `claims` - The Claims of the JWT token that the session token stands for.
+    /// * `expires_at` - The time after which the Session expires.
+    ///
+    /// # Returns
+    ///
+    /// * `Session` - The new Session.
+    pub fn new(token_hash: String, claims: Claims, expires_at: DateTime<Utc>) -> Session {
+        Session {
+            id: ObjectId::new(),
+            token_hash,
+            subject: String::from(claims.subject()),
+            claims: Some(claims),
+            created_at: SystemTime::now().into(),
+            expires_at,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check whether the Session has expired. Expired Sessions are removed by a TTL index, which may lag behind.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the Session has expired, false otherwise.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= Utc::now()
+    }
+}
+
+impl Display for Session {
+    /// # Summary
+    ///
+    /// Display the Session.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The Formatter.
+    ///
+    /// # Returns
+    ///
+    /// * `std::fmt::Result` - The result of the display.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Session: {{ id: {}, subject: {}, created_at: {}, expires_at: {} }}",
+            self.id.to_hex(),
+            self.subject,
+            self.created_at,
+            self.expires_at
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use serde_json::Map;
+
+    #[test]
+    fn session_stores_claims_instead_of_token() {
+        let now = Utc::now();
+        let expires_at = now + Duration::seconds(3600);
+        let claims = Claims::new(
+            String::from("6553a8cf1d9c1e2f3a4b5c6d"),
+            expires_at.timestamp() as usize,
+            now.timestamp() as usize,
+            3,
+            Map::new(),
+        );
+        let session = Session::new(String::from("hash"), claims, expires_at);
+
+        let document = mongodb::bson::to_document(&session).unwrap();
+        assert!(!document.contains_key("accessToken"));
+
+        let session: Session = mongodb::bson::from_document(document).unwrap();
+        let claims = session.claims.unwrap();
+        assert_eq!(session.subject, "6553a8cf1d9c1e2f3a4b5c6d");
+        assert_eq!(claims.subject(), "6553a8cf1d9c1e2f3a4b5c6d");
+        assert_eq!(claims.expires_at(), expires_at.timestamp() as usize);
+        assert_eq!(claims.token_version(), 3);
+    }
+
+    #[test]
+    fn session_of_earlier_version_has_no_claims() {
+        let document = mongodb::bson::doc! {
+            "_id": ObjectId::new(),
+            "tokenHash": "hash",
+            "accessToken": "eyJhbGciOiJIUzI1NiJ9.e30.signature",
+            "subject": "6553a8cf1d9c1e2f3a4b5c6d",
+            "createdAt": mongodb::bson::DateTime::now(),
+            "expiresAt": mongodb::bson::DateTime::now(),
+        };
+
+        let session: Session = mongodb::bson::from_document(document).unwrap();
+
+        assert!(session.claims.is_none());
+    }
+}
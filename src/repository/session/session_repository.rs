@@ -0,0 +1,191 @@
+use crate::repository::session::session_model::Session;
+use mongodb::bson::doc;
+use mongodb::error::Error as MongoError;
+use mongodb::Database;
+
+#[derive(Clone)]
+pub struct SessionRepository {
+    pub collection: String,
+}
+
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Empty collection")]
+    EmptyCollection,
+    #[error("MongoDB error: {0}")]
+    MongoDb(#[source] MongoError),
+}
+
+impl SessionRepository {
+    /// # Summary
+    ///
+    /// Creates a new SessionRepository instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection` - A String that holds the collection name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let session_repository = match SessionRepository::new("sessions".to_string()) {
+    ///    Ok(d) => d,
+    ///    Err(e) => panic!("Failed to initialize Session repository: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A SessionRepository instance.
+    pub fn new(collection: String) -> Result<SessionRepository, Error> {
+        if collection.is_empty() {
+            return Err(Error::EmptyCollection);
+        }
+
+        Ok(SessionRepository { collection })
+    }
+
+    /// # Summary
+    ///
+    /// Store a Session.
+    ///
+    /// # Arguments
+    ///
+    /// * `session` - A Session instance.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// match session_repository.create(session, &db).await {
+    ///   Ok(_) => (),
+    ///   Err(e) => panic!("Failed to create Session: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the unit type or an Error.
+    pub async fn create(&self, session: Session, db: &Database) -> Result<(), Error> {
+        match db
+            .collection::<Session>(&self.collection)
+            .insert_one(&session, None)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Find a Session by the hash of its token.
+    ///
+    /// # Arguments
+    ///
+    /// * `token_hash` - A string slice that holds the hash of the session token.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let session = match session_repository.find_by_token_hash("hash", &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to find Session: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the Session, if any, or an Error.
+    pub async fn find_by_token_hash(
+        &self,
+        token_hash: &str,
+        db: &Database,
+    ) -> Result<Option<Session>, Error> {
+        let filter = doc! {
+            "tokenHash": token_hash,
+        };
+
+        match db
+            .collection::<Session>(&self.collection)
+            .find_one(filter, None)
+            .await
+        {
+            Ok(d) => Ok(d),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Delete a Session by the hash of its token. Deleting a Session that does not exist is not an error.
+    ///
+    /// # Arguments
+    ///
+    /// * `token_hash` - A string slice that holds the hash of the session token.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// match session_repository.delete_by_token_hash("hash", &db).await {
+    ///   Ok(_) => (),
+    ///   Err(e) => panic!("Failed to delete Session: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the unit type or an Error.
+    pub async fn delete_by_token_hash(&self, token_hash: &str, db: &Database) -> Result<(), Error> {
+        let filter = doc! {
+            "tokenHash": token_hash,
+        };
+
+        match db
+            .collection::<Session>(&self.collection)
+            .delete_one(filter, None)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Delete all Sessions of a subject.
+    ///
+    /// # Arguments
+    ///
+    /// * `subject` - A string slice that holds the subject of the Sessions.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// match session_repository.delete_by_subject(&user.id.to_hex(), &db).await {
+    ///   Ok(_) => (),
+    ///   Err(e) => panic!("Failed to delete Sessions: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the unit type or an Error.
+    pub async fn delete_by_subject(&self, subject: &str, db: &Database) -> Result<(), Error> {
+        let filter = doc! {
+            "subject": subject,
+        };
+
+        match db
+            .collection::<Session>(&self.collection)
+            .delete_many(filter, None)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+}
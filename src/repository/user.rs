@@ -1,2 +1,8 @@
+pub mod user_email;
+pub mod user_filter;
+pub mod user_identity;
 pub mod user_model;
 pub mod user_repository;
+pub mod user_source;
+pub mod user_status;
+pub mod user_totp;
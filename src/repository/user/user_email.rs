@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct UserEmail {
+    pub email: String,
+    pub verified: bool,
+}
+
+impl UserEmail {
+    /// # Summary
+    ///
+    /// Create a new, unverified UserEmail.
+    ///
+    /// # Arguments
+    ///
+    /// * `email` - The email address.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_email = UserEmail::new(String::from("alias@opserva.io"));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `UserEmail` - The new UserEmail.
+    pub fn new(email: String) -> UserEmail {
+        UserEmail {
+            email,
+            verified: false,
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use crate::repository::user::user_source::UserSource;
+use mongodb::bson::{doc, Bson, Document};
+
+#[derive(Clone, Default)]
+pub struct UserFilter {
+    pub text: Option<String>,
+    pub source: Option<UserSource>,
+    pub email_missing: Option<bool>,
+    pub email_verified: Option<bool>,
+}
+
+impl UserFilter {
+    /// # Summary
+    ///
+    /// Check whether the UserFilter does not restrict the Users in any way.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let filter = UserFilter::default();
+    /// assert!(filter.is_empty());
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if none of the criteria are set.
+    pub fn is_empty(&self) -> bool {
+        self.text.is_none()
+            && self.source.is_none()
+            && self.email_missing.is_none()
+            && self.email_verified.is_none()
+    }
+
+    /// # Summary
+    ///
+    /// Convert the UserFilter into a query document that matches the Users that meet all criteria.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let filter = UserFilter {
+    ///     source: Some(UserSource::Registration),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let document = filter.to_document();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Document` - The query document.
+    pub fn to_document(&self) -> Document {
+        let mut document = Document::new();
+
+        if let Some(text) = &self.text {
+            document.insert("$text", doc! { "$search": text });
+        }
+
+        if let Some(source) = &self.source {
+            document.insert("source", source.to_string());
+        }
+
+        // A null query also matches the Users of which the email field does not exist
+        if let Some(missing) = self.email_missing {
+            let condition = if missing {
+                Bson::Null
+            } else {
+                Bson::Document(doc! { "$ne": Bson::Null })
+            };
+
+            document.insert("email", condition);
+        }
+
+        // Users that were stored before email verification was introduced do not have the field and are unverified
+        if let Some(verified) = self.email_verified {
+            let condition = if verified {
+                Bson::Boolean(true)
+            } else {
+                Bson::Document(doc! { "$ne": true })
+            };
+
+            document.insert("emailVerified", condition);
+        }
+
+        document
+    }
+}
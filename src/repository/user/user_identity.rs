@@ -0,0 +1,40 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct UserIdentity {
+    pub provider: String,
+    pub subject: String,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "linkedAt")]
+    pub linked_at: DateTime<Utc>,
+}
+
+impl UserIdentity {
+    /// # Summary
+    ///
+    /// Create a new UserIdentity that links a User to an account of an external identity provider.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - The name of the identity provider, such as `google` or `github`.
+    /// * `subject` - The ID of the account at the identity provider.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_identity = UserIdentity::new(String::from("github"), String::from("583231"));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `UserIdentity` - The new UserIdentity.
+    pub fn new(provider: String, subject: String) -> UserIdentity {
+        UserIdentity {
+            provider,
+            subject,
+            linked_at: SystemTime::now().into(),
+        }
+    }
+}
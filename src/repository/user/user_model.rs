@@ -1,21 +1,59 @@
+use crate::repository::user::user_email::UserEmail;
+use crate::repository::user::user_identity::UserIdentity;
+use crate::repository::user::user_source::UserSource;
+use crate::repository::user::user_status::UserStatus;
+use crate::repository::user::user_totp::UserTotp;
 use crate::web::dto::authentication::register_request::RegisterRequest;
 use crate::web::dto::user::create_user::CreateUser;
 use chrono::{DateTime, Utc};
 use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, Document};
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 use std::time::SystemTime;
 
+/// Serialize an optional DateTime as a BSON datetime, as `chrono_datetime_as_bson_datetime` does not support options.
+pub(crate) mod optional_chrono_datetime_as_bson_datetime {
+    use chrono::{DateTime, Utc};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S>(value: &Option<DateTime<Utc>>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        value
+            .map(mongodb::bson::DateTime::from_chrono)
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Option::<mongodb::bson::DateTime>::deserialize(deserializer)?.map(|d| d.to_chrono()))
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct User {
     #[serde(rename = "_id")]
     pub id: ObjectId,
     pub username: String,
     pub email: Option<String>,
+    #[serde(rename = "emailVerified", default)]
+    pub email_verified: bool,
+    #[serde(rename = "secondaryEmails", default)]
+    pub secondary_emails: Vec<UserEmail>,
     #[serde(rename = "firstName")]
     pub first_name: Option<String>,
     #[serde(rename = "lastName")]
     pub last_name: Option<String>,
+    #[serde(rename = "phoneNumber", default)]
+    pub phone_number: Option<String>,
+    #[serde(default)]
+    pub locale: Option<String>,
+    #[serde(default)]
+    pub timezone: Option<String>,
     pub password: String,
     pub roles: Option<Vec<ObjectId>>,
     #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
@@ -24,7 +62,40 @@ pub struct User {
     #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     #[serde(rename = "updatedAt")]
     pub updated_at: DateTime<Utc>,
-    pub enabled: bool,
+    #[serde(alias = "enabled")]
+    pub status: UserStatus,
+    #[serde(rename = "tokenVersion", default)]
+    pub token_version: i64,
+    #[serde(
+        rename = "activeFrom",
+        default,
+        with = "optional_chrono_datetime_as_bson_datetime"
+    )]
+    pub active_from: Option<DateTime<Utc>>,
+    #[serde(
+        rename = "expiresAt",
+        default,
+        with = "optional_chrono_datetime_as_bson_datetime"
+    )]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(rename = "emailHashes", default, skip_serializing_if = "Vec::is_empty")]
+    pub email_hashes: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<UserSource>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub identities: Vec<UserIdentity>,
+    #[serde(rename = "mfaEnrolled", default)]
+    pub mfa_enrolled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub totp: Option<UserTotp>,
+    #[serde(
+        rename = "recoveryCodes",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub recovery_codes: Vec<String>,
+    #[serde(rename = "mustChangePassword", default)]
+    pub must_change_password: bool,
 }
 
 impl User {
@@ -40,7 +111,7 @@ impl User {
     /// * `last_name` - The last name of the User.
     /// * `password` - The password of the User.
     /// * `roles` - The roles of the User.
-    /// * `enabled` - The enabled of the User.
+    /// * `status` - The status of the User.
     ///
     /// # Example
     ///
@@ -52,7 +123,7 @@ impl User {
     ///   String::from("last_name"),
     ///   String::from("password"),
     ///   Some(vec![String::from("role")]),
-    ///   true,
+    ///   UserStatus::Active,
     /// );
     /// ```
     ///
@@ -66,7 +137,7 @@ impl User {
         last_name: Option<String>,
         password: String,
         roles: Option<Vec<String>>,
-        enabled: bool,
+        status: UserStatus,
     ) -> User {
         let now: DateTime<Utc> = SystemTime::now().into();
 
@@ -88,13 +159,266 @@ impl User {
             id: ObjectId::new(),
             username,
             email,
+            email_verified: false,
+            secondary_emails: vec![],
             first_name,
             last_name,
+            phone_number: None,
+            locale: None,
+            timezone: None,
             password,
             roles,
             created_at: now,
             updated_at: now,
-            enabled,
+            status,
+            token_version: 0,
+            active_from: None,
+            expires_at: None,
+            email_hashes: vec![],
+            source: None,
+            identities: vec![],
+            mfa_enrolled: false,
+            totp: None,
+            recovery_codes: vec![],
+            must_change_password: false,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check whether a token that was issued with the given token version has been revoked.
+    ///
+    /// # Arguments
+    ///
+    /// * `token_version` - The token version that is embedded in the token.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let revoked = user.is_token_revoked(claims.token_version());
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Whether the token has been revoked.
+    pub fn is_token_revoked(&self, token_version: i64) -> bool {
+        token_version != self.token_version
+    }
+
+    /// # Summary
+    ///
+    /// Check whether the User can log in and use its tokens. The status of the User must be active and the current time
+    /// must fall within the scheduled activation window of the User, if any. The window is also enforced here because
+    /// the status is only updated periodically when a boundary of the window passes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// if !user.is_active() {
+    ///     return HttpResponse::Forbidden().finish();
+    /// }
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Whether the User is active.
+    pub fn is_active(&self) -> bool {
+        let now = Utc::now();
+
+        self.status.is_active()
+            && self.active_from.map_or(true, |a| a <= now)
+            && self.expires_at.map_or(true, |e| e > now)
+    }
+
+    /// # Summary
+    ///
+    /// Grant additional roles to the User, skipping the roles that the User already has.
+    ///
+    /// # Arguments
+    ///
+    /// * `role_ids` - The IDs of the roles to grant.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// user.grant_roles(&[role.id]);
+    /// ```
+    pub fn grant_roles(&mut self, role_ids: &[ObjectId]) {
+        let roles = self.roles.get_or_insert_with(Vec::new);
+
+        for r in role_ids {
+            if !roles.contains(r) {
+                roles.push(*r);
+            }
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Merge a duplicate User into the User. The roles, email addresses and linked identities of both Users are
+    /// combined, and the profile fields that the User lacks are taken from the duplicate. The password, status and
+    /// second factors of the User are kept.
+    ///
+    /// The primary email address of the User stays the canonical email address if it is verified. Otherwise, a verified
+    /// primary email address of the duplicate takes its place. All other email addresses become secondary email
+    /// addresses.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The duplicate User.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// user.merge(other);
+    /// ```
+    pub fn merge(&mut self, other: User) {
+        if let Some(r) = &other.roles {
+            self.grant_roles(r);
+        }
+
+        let mut primary_emails: Vec<UserEmail> = [
+            (self.email.take(), self.email_verified),
+            (other.email, other.email_verified),
+        ]
+        .into_iter()
+        .filter_map(|(email, verified)| email.map(|email| UserEmail { email, verified }))
+        .collect();
+
+        // A verified email address is known to reach the owner of the account, so it is preferred as the canonical one
+        if let Some(i) = primary_emails.iter().position(|e| e.verified) {
+            let canonical = primary_emails.remove(i);
+            primary_emails.insert(0, canonical);
+        }
+
+        let mut emails: Vec<UserEmail> = vec![];
+        for e in primary_emails
+            .into_iter()
+            .chain(self.secondary_emails.drain(..))
+            .chain(other.secondary_emails)
+        {
+            match emails
+                .iter_mut()
+                .find(|m| m.email.eq_ignore_ascii_case(&e.email))
+            {
+                Some(m) => m.verified = m.verified || e.verified,
+                None => emails.push(e),
+            }
+        }
+
+        let mut emails = emails.into_iter();
+        match emails.next() {
+            Some(e) => {
+                self.email = Some(e.email);
+                self.email_verified = e.verified;
+            }
+            None => self.email_verified = false,
+        }
+        self.secondary_emails = emails.collect();
+
+        for identity in other.identities {
+            if !self
+                .identities
+                .iter()
+                .any(|i| i.provider == identity.provider && i.subject == identity.subject)
+            {
+                self.identities.push(identity);
+            }
+        }
+
+        self.first_name = self.first_name.take().or(other.first_name);
+        self.last_name = self.last_name.take().or(other.last_name);
+        self.phone_number = self.phone_number.take().or(other.phone_number);
+        self.locale = self.locale.take().or(other.locale);
+        self.timezone = self.timezone.take().or(other.timezone);
+        self.created_at = self.created_at.min(other.created_at);
+    }
+
+    /// # Summary
+    ///
+    /// Get the JSON Schema that is used to validate User documents that are written to the database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let validator = doc! { "$jsonSchema": User::json_schema() };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Document` - The JSON Schema of a User.
+    pub fn json_schema() -> Document {
+        doc! {
+            "bsonType": "object",
+            "required": ["_id", "username", "password", "createdAt", "updatedAt"],
+            "properties": {
+                "_id": { "bsonType": "objectId" },
+                "username": { "bsonType": "string", "minLength": 1 },
+                "email": { "bsonType": ["string", "null"] },
+                "emailVerified": { "bsonType": "bool" },
+                "secondaryEmails": {
+                    "bsonType": "array",
+                    "items": {
+                        "bsonType": "object",
+                        "required": ["email", "verified"],
+                        "properties": {
+                            "email": { "bsonType": "string" },
+                            "verified": { "bsonType": "bool" },
+                        }
+                    }
+                },
+                "firstName": { "bsonType": ["string", "null"] },
+                "lastName": { "bsonType": ["string", "null"] },
+                "phoneNumber": { "bsonType": ["string", "null"] },
+                "locale": { "bsonType": ["string", "null"] },
+                "timezone": { "bsonType": ["string", "null"] },
+                "password": { "bsonType": "string" },
+                "roles": {
+                    "bsonType": ["array", "null"],
+                    "items": { "bsonType": "objectId" },
+                },
+                "createdAt": { "bsonType": "date" },
+                "updatedAt": { "bsonType": "date" },
+                "status": { "enum": ["pending", "active", "suspended", "deactivated", "locked"] },
+                "tokenVersion": { "bsonType": ["int", "long"] },
+                "activeFrom": { "bsonType": ["date", "null"] },
+                "expiresAt": { "bsonType": ["date", "null"] },
+                "emailHashes": {
+                    "bsonType": "array",
+                    "items": { "bsonType": "string" },
+                },
+                "source": {
+                    "enum": ["registration", "invitation", "admin", "directory", "system", "scim", "social", "sso", "cli"]
+                },
+                "identities": {
+                    "bsonType": "array",
+                    "items": {
+                        "bsonType": "object",
+                        "required": ["provider", "subject", "linkedAt"],
+                        "properties": {
+                            "provider": { "bsonType": "string" },
+                            "subject": { "bsonType": "string" },
+                            "linkedAt": { "bsonType": "date" },
+                        }
+                    }
+                },
+                "mfaEnrolled": { "bsonType": "bool" },
+                "totp": {
+                    "bsonType": "object",
+                    "required": ["secret", "confirmed", "createdAt"],
+                    "properties": {
+                        "secret": { "bsonType": "string" },
+                        "confirmed": { "bsonType": "bool" },
+                        "lastStep": { "bsonType": "long" },
+                        "createdAt": { "bsonType": "date" },
+                    }
+                },
+                "recoveryCodes": {
+                    "bsonType": "array",
+                    "items": { "bsonType": "string" },
+                },
+                "mustChangePassword": { "bsonType": "bool" },
+            }
         }
     }
 }
@@ -147,13 +471,28 @@ impl From<CreateUser> for User {
             id: ObjectId::new(),
             username: value.username,
             email: value.email,
+            email_verified: false,
+            secondary_emails: vec![],
             first_name: value.first_name,
             last_name: value.last_name,
+            phone_number: value.phone_number,
+            locale: value.locale,
+            timezone: value.timezone,
             password: value.password,
             roles,
             created_at: now,
             updated_at: now,
-            enabled: true,
+            status: UserStatus::Active,
+            token_version: 0,
+            active_from: None,
+            expires_at: None,
+            email_hashes: vec![],
+            source: Some(UserSource::Admin),
+            identities: vec![],
+            mfa_enrolled: false,
+            totp: None,
+            recovery_codes: vec![],
+            must_change_password: false,
         }
     }
 }
@@ -187,17 +526,37 @@ impl From<RegisterRequest> for User {
     fn from(value: RegisterRequest) -> Self {
         let now: DateTime<Utc> = SystemTime::now().into();
 
+        let source = match value.invitation {
+            Some(_) => UserSource::Invitation,
+            None => UserSource::Registration,
+        };
+
         User {
             id: ObjectId::new(),
             username: value.username,
             email: value.email,
+            email_verified: false,
+            secondary_emails: vec![],
             first_name: value.first_name,
             last_name: value.last_name,
+            phone_number: value.phone_number,
+            locale: value.locale,
+            timezone: value.timezone,
             password: value.password,
             roles: None,
             created_at: now,
             updated_at: now,
-            enabled: true,
+            status: UserStatus::Active,
+            token_version: 0,
+            active_from: None,
+            expires_at: None,
+            email_hashes: vec![],
+            source: Some(source),
+            identities: vec![],
+            mfa_enrolled: false,
+            totp: None,
+            recovery_codes: vec![],
+            must_change_password: false,
         }
     }
 }
@@ -224,7 +583,7 @@ impl Display for User {
     ///   roles: Some(vec![String::from("role")]),
     ///   created_at: String::from("created_at"),
     ///   updated_at: String::from("updated_at"),
-    ///   enabled: true,
+    ///   status: UserStatus::Active,
     /// };
     ///
     /// println!("{}", user);
@@ -236,7 +595,7 @@ impl Display for User {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "User: [id: {}, username: {}, email: {}, first_name: {}, last_name: {}, password: {}, roles: {:?}, created_at: {}, updated_at: {}, enabled: {}]",
+            "User: [id: {}, username: {}, email: {}, first_name: {}, last_name: {}, password: {}, roles: {:?}, created_at: {}, updated_at: {}, status: {}]",
             self.id.to_hex(),
             self.username,
             match &self.email {
@@ -252,7 +611,7 @@ impl Display for User {
             },
             self.created_at,
             self.updated_at,
-            self.enabled,
+            self.status,
         )
     }
 }
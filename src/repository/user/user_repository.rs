@@ -1,110 +1,367 @@
 use crate::repository::audit::audit_repository::Error as AuditError;
+use crate::repository::search_hit::{find_hits, SearchHit};
+use crate::repository::user::user_email::UserEmail;
+use crate::repository::user::user_filter::UserFilter;
+use crate::repository::user::user_identity::UserIdentity;
 use crate::repository::user::user_model::User;
+use crate::repository::user::user_status::UserStatus;
+use crate::repository::user::user_totp::UserTotp;
 use chrono::{DateTime, Utc};
 use futures::TryStreamExt;
+use hmac::{Hmac, Mac};
 use mongodb::bson::oid::ObjectId;
-use mongodb::bson::{doc, Bson};
+use mongodb::bson::{doc, Bson, Document};
 use mongodb::error::Error as MongoError;
-use mongodb::options::FindOptions;
+use mongodb::options::{FindOneAndUpdateOptions, FindOptions, ReturnDocument};
 use mongodb::Database;
 use regex::Regex;
-use std::fmt::{Display, Formatter};
+use sha2::Sha256;
 use std::time::SystemTime;
 
 #[derive(Clone)]
 pub struct UserRepository {
     pub collection: String,
     pub email_regex: Regex,
+    pub strip_plus_tags: bool,
+    pub email_hash_key: Option<String>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, thiserror::Error)]
 pub enum Error {
+    #[error("Invalid User ID: {0}")]
     InvalidId(String),
+    #[error("Empty User ID")]
     EmptyId,
+    #[error("Empty username")]
     EmptyUsername,
+    #[error("Empty collection")]
     EmptyCollection,
+    #[error("Empty email")]
     EmptyEmail,
+    #[error("Empty password")]
     EmptyPassword,
+    #[error("Empty text search")]
     EmptyTextSearch,
+    #[error("User not found: {0}")]
     UserNotFound(String),
+    #[error("Username already taken")]
     UsernameAlreadyTaken,
+    #[error("Email already taken")]
     EmailAlreadyTaken,
+    #[error("Invalid email address: {0}")]
     InvalidEmail(String),
-    MongoDb(MongoError),
-    Audit(AuditError),
+    #[error("Invalid phone number: {0}")]
+    InvalidPhoneNumber(String),
+    #[error("Invalid locale: {0}")]
+    InvalidLocale(String),
+    #[error("Invalid timezone: {0}")]
+    InvalidTimezone(String),
+    #[error("MongoDB error: {0}")]
+    MongoDb(#[source] MongoError),
+    #[error("Audit error: {0}")]
+    Audit(#[source] AuditError),
+    #[error("User store error: {0}")]
+    UserStore(String),
+    #[error("Email address not found: {0}")]
+    EmailNotFound(String),
+    #[error("The primary email address cannot be removed")]
+    PrimaryEmailRemoval,
+    #[error("Invalid status transition from {0} to {1}")]
+    InvalidStatusTransition(String, String),
+    #[error("The expiration date must be after the activation date")]
+    InvalidSchedule,
+    #[error("A User cannot have more than {0} roles")]
+    TooManyRoles(usize),
+    #[error("The {0} account is already linked to a User")]
+    IdentityAlreadyLinked(String),
 }
 
-impl Display for Error {
+impl UserRepository {
     /// # Summary
     ///
-    /// Display the Error.
+    /// Create a new UserRepository.
     ///
     /// # Arguments
     ///
-    /// * `f` - The Formatter.
+    /// * `collection` - The name of the collection.
+    /// * `email_regex` - The email regex.
+    /// * `strip_plus_tags` - Whether plus-tags should be removed from email addresses.
+    /// * `email_hash_key` - The key of the keyed email hashes, if email hashes should be stored.
     ///
     /// # Example
     ///
     /// ```
-    /// let error = Error::InvalidEmail(String::from("email"));
-    /// println!("{}", error);
+    /// use regex::Regex;
+    /// use repository::user::user_repository::UserRepository;
+    ///
+    /// let email_regex = Regex::new(r"^[a-zA-Z0-9_.+-]+@[a-zA-Z0-9-]+\.[a-
+    /// zA-Z0-9-.]+$").unwrap();
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex, false, None);
     /// ```
     ///
     /// # Returns
     ///
-    /// * `std::fmt::Result` - The result of the operation.
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match &self {
-            Error::InvalidId(id) => write!(f, "Invalid User ID: {}", id),
-            Error::EmptyId => write!(f, "Empty User ID"),
-            Error::EmptyUsername => write!(f, "Empty username"),
-            Error::EmptyCollection => write!(f, "Empty collection"),
-            Error::EmptyEmail => write!(f, "Empty email"),
-            Error::EmptyPassword => write!(f, "Empty password"),
-            Error::EmptyTextSearch => write!(f, "Empty text search"),
-            Error::UserNotFound(id) => write!(f, "User not found: {}", id),
-            Error::UsernameAlreadyTaken => write!(f, "Username already taken"),
-            Error::EmailAlreadyTaken => write!(f, "Email already taken"),
-            Error::InvalidEmail(email) => write!(f, "Invalid email address: {}", email),
-            Error::MongoDb(e) => write!(f, "MongoDB error: {}", e),
-            Error::Audit(e) => write!(f, "Audit error: {}", e),
+    /// * `Result<UserRepository, Error>` - The result of the operation.
+    pub fn new(
+        collection: String,
+        email_regex: Regex,
+        strip_plus_tags: bool,
+        email_hash_key: Option<String>,
+    ) -> Result<UserRepository, Error> {
+        if collection.is_empty() {
+            return Err(Error::EmptyCollection);
         }
+
+        Ok(UserRepository {
+            collection,
+            email_regex,
+            strip_plus_tags,
+            email_hash_key,
+        })
     }
-}
 
-impl UserRepository {
     /// # Summary
     ///
-    /// Create a new UserRepository.
+    /// Normalize an email address so that addresses that only differ in case (or plus-tag, if enabled) are considered equal.
     ///
     /// # Arguments
     ///
-    /// * `collection` - The name of the collection.
-    /// * `email_regex` - The email regex.
+    /// * `email` - The email address to normalize.
     ///
     /// # Example
     ///
     /// ```
-    /// use regex::Regex;
-    /// use repository::user::user_repository::UserRepository;
+    /// let email = user_repository.normalize_email("Foo+News@Bar.com");
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The normalized email address.
+    pub fn normalize_email(&self, email: &str) -> String {
+        let email = email.trim().to_lowercase();
+
+        if self.strip_plus_tags {
+            if let Some((local, domain)) = email.split_once('@') {
+                if let Some((local, _)) = local.split_once('+') {
+                    return format!("{}@{}", local, domain);
+                }
+            }
+        }
+
+        email
+    }
+
+    /// # Summary
+    ///
+    /// Compute the keyed HMAC-SHA256 hash of a normalized email address. The hash is deterministic, so it can be used to
+    /// look up Users and enforce uniqueness without relying on the plaintext email address.
+    ///
+    /// # Arguments
+    ///
+    /// * `email` - The email address to hash.
+    ///
+    /// # Example
     ///
-    /// let email_regex = Regex::new(r"^[a-zA-Z0-9_.+-]+@[a-zA-Z0-9-]+\.[a-
-    /// zA-Z0-9-.]+$").unwrap();
-    /// let user_repository = UserRepository::new(String::from("users"), email_regex);
+    /// ```
+    /// let hash = user_repository.hash_email("foo@bar.com");
     /// ```
     ///
     /// # Returns
     ///
-    /// * `Result<UserRepository, Error>` - The result of the operation.
-    pub fn new(collection: String, email_regex: Regex) -> Result<UserRepository, Error> {
-        if collection.is_empty() {
-            return Err(Error::EmptyCollection);
+    /// * `Option<String>` - The hex-encoded hash, or None if no email hash key is configured.
+    pub fn hash_email(&self, email: &str) -> Option<String> {
+        let key = self.email_hash_key.as_ref()?;
+
+        // HMAC accepts keys of any length
+        let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes()).ok()?;
+        mac.update(self.normalize_email(email).as_bytes());
+
+        Some(format!("{:x}", mac.finalize().into_bytes()))
+    }
+
+    /// # Summary
+    ///
+    /// Compute the email hashes of the primary and secondary email addresses of a User.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The User.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<String>` - The email hashes, or an empty Vec if no email hash key is configured.
+    fn email_hashes(&self, user: &User) -> Vec<String> {
+        user.email
+            .iter()
+            .chain(user.secondary_emails.iter().map(|e| &e.email))
+            .filter_map(|e| self.hash_email(e))
+            .collect()
+    }
+
+    /// # Summary
+    ///
+    /// Format a phone number in the E.164 format by removing common separators and replacing the `00` international prefix with `+`.
+    /// Empty phone numbers are removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `phone_number` - The phone number to format.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let phone_number = user_repository.normalize_phone_number(Some(String::from("0032 (470) 12-34-56")));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<String>, Error>` - The formatted phone number or the Error that occurred if it is not a valid E.164 phone number.
+    pub fn normalize_phone_number(
+        &self,
+        phone_number: Option<String>,
+    ) -> Result<Option<String>, Error> {
+        let phone_number = match phone_number {
+            Some(p) if !p.trim().is_empty() => p,
+            _ => return Ok(None),
+        };
+
+        let mut formatted: String = phone_number
+            .chars()
+            .filter(|c| !matches!(c, ' ' | '-' | '.' | '(' | ')'))
+            .collect();
+
+        if let Some(p) = formatted.strip_prefix("00") {
+            formatted = format!("+{}", p);
         }
 
-        Ok(UserRepository {
-            collection,
-            email_regex,
-        })
+        let digits = match formatted.strip_prefix('+') {
+            Some(d) => d,
+            None => return Err(Error::InvalidPhoneNumber(phone_number)),
+        };
+
+        if !(2..=15).contains(&digits.len())
+            || digits.starts_with('0')
+            || !digits.chars().all(|c| c.is_ascii_digit())
+        {
+            return Err(Error::InvalidPhoneNumber(phone_number));
+        }
+
+        Ok(Some(formatted))
+    }
+
+    /// # Summary
+    ///
+    /// Format a BCP 47 language tag (`en-US`, `nl_be`, `zh-Hant-TW`) using its canonical casing and `-` separators.
+    /// Empty locales are removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `locale` - The locale to format.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let locale = user_repository.normalize_locale(Some(String::from("nl_be")));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<String>, Error>` - The formatted locale or the Error that occurred if it is not a valid language tag.
+    pub fn normalize_locale(&self, locale: Option<String>) -> Result<Option<String>, Error> {
+        let locale = match locale {
+            Some(l) if !l.trim().is_empty() => l,
+            _ => return Ok(None),
+        };
+
+        let mut subtags: Vec<String> = vec![];
+        for (i, subtag) in locale.trim().split(['-', '_']).enumerate() {
+            if subtag.is_empty()
+                || subtag.len() > 8
+                || !subtag.chars().all(|c| c.is_ascii_alphanumeric())
+                || (i == 0
+                    && (!(2..=3).contains(&subtag.len())
+                        || !subtag.chars().all(|c| c.is_ascii_alphabetic())))
+            {
+                return Err(Error::InvalidLocale(locale.clone()));
+            }
+
+            let subtag = if i == 0 {
+                subtag.to_ascii_lowercase()
+            } else if subtag.len() == 2 && subtag.chars().all(|c| c.is_ascii_alphabetic()) {
+                subtag.to_ascii_uppercase()
+            } else if subtag.len() == 4 && subtag.chars().all(|c| c.is_ascii_alphabetic()) {
+                let lower = subtag.to_ascii_lowercase();
+                format!("{}{}", lower[..1].to_ascii_uppercase(), &lower[1..])
+            } else {
+                subtag.to_ascii_lowercase()
+            };
+
+            subtags.push(subtag);
+        }
+
+        Ok(Some(subtags.join("-")))
+    }
+
+    /// # Summary
+    ///
+    /// Validate that a timezone is formatted as an IANA time zone name (`UTC`, `Europe/Brussels`, `America/Argentina/Buenos_Aires`).
+    /// Empty timezones are removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `timezone` - The timezone to validate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let timezone = user_repository.validate_timezone(Some(String::from("Europe/Brussels")));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<String>, Error>` - The timezone or the Error that occurred if it is not a valid time zone name.
+    pub fn validate_timezone(&self, timezone: Option<String>) -> Result<Option<String>, Error> {
+        let timezone = match timezone {
+            Some(t) if !t.trim().is_empty() => t.trim().to_string(),
+            _ => return Ok(None),
+        };
+
+        let valid = timezone.split('/').all(|part| {
+            part.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '+'))
+        });
+
+        if !valid {
+            return Err(Error::InvalidTimezone(timezone));
+        }
+
+        Ok(Some(timezone))
+    }
+
+    /// # Summary
+    ///
+    /// Validate that the scheduled expiration of a User is after its scheduled activation.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The User to validate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// user_repository.validate_schedule(&user)?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - Nothing, or the Error that occurred if the User expires before it is activated.
+    pub fn validate_schedule(&self, user: &User) -> Result<(), Error> {
+        match (user.active_from, user.expires_at) {
+            (Some(a), Some(e)) if e <= a => Err(Error::InvalidSchedule),
+            _ => Ok(()),
+        }
     }
 
     /// # Summary
@@ -129,21 +386,24 @@ impl UserRepository {
     /// };
     ///
     /// let db = Database::new();
-    /// let user_repository = UserRepository::new(String::from("users"), email_regex);
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex, false, None);
     /// let user = user_repository.create(user, &db);
     /// ```
     ///
     /// # Returns
     ///
     /// * `Result<User, Error>` - The result of the operation.
-    pub async fn create(&self, user: User, db: &Database) -> Result<User, Error> {
+    pub async fn create(&self, mut user: User, db: &Database) -> Result<User, Error> {
+        user.email = user.email.map(|e| self.normalize_email(&e));
+        user.phone_number = self.normalize_phone_number(user.phone_number)?;
+        user.locale = self.normalize_locale(user.locale)?;
+        user.timezone = self.validate_timezone(user.timezone)?;
+        self.validate_schedule(&user)?;
+
         if user.email.is_some() && !self.email_regex.is_match(&user.email.clone().unwrap()) {
             return Err(Error::InvalidEmail(user.email.unwrap()));
         } else if user.email.is_some() {
-            match self
-                .find_by_email(&user.email.clone().unwrap().to_lowercase(), db)
-                .await
-            {
+            match self.find_by_email(&user.email.clone().unwrap(), db).await {
                 Ok(user) => {
                     if user.is_some() {
                         return Err(Error::EmailAlreadyTaken);
@@ -167,6 +427,7 @@ impl UserRepository {
         };
 
         let user_id = user.id.to_hex();
+        user.email_hashes = self.email_hashes(&user);
 
         let collection = db.collection::<User>(&self.collection);
         let result = collection.insert_one(user, None).await;
@@ -199,7 +460,7 @@ impl UserRepository {
     ///
     /// ```
     /// let db = Database::new();
-    /// let user_repository = UserRepository::new(String::from("users"), email_regex);
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex, false, None);
     /// let users = user_repository.find_all(Some(10), Some(1), &db);
     /// ```
     ///
@@ -252,7 +513,7 @@ impl UserRepository {
     ///
     /// ```
     /// let db = Database::new();
-    /// let user_repository = UserRepository::new(String::from("users"), email_regex);
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex, false, None);
     /// let user = user_repository.find_by_id(&String::from("id"), &db);
     /// ```
     ///
@@ -285,6 +546,63 @@ impl UserRepository {
         }
     }
 
+    /// # Summary
+    ///
+    /// Find User entities by their IDs.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - The IDs of the User entities.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let db = Database::new();
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex, false, None);
+    /// let users = user_repository.find_by_id_vec(vec![String::from("id")], &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<User>, Error>` - The result of the operation.
+    pub async fn find_by_id_vec(
+        &self,
+        ids: Vec<String>,
+        db: &Database,
+    ) -> Result<Vec<User>, Error> {
+        let mut oid_vec: Vec<ObjectId> = vec![];
+        for id in ids {
+            match ObjectId::parse_str(id) {
+                Ok(d) => oid_vec.push(d),
+                Err(e) => {
+                    return Err(Error::InvalidId(e.to_string()));
+                }
+            };
+        }
+
+        if oid_vec.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let filter = doc! {
+            "_id": {
+                "$in": oid_vec,
+            },
+        };
+
+        let cursor = match db
+            .collection::<User>(&self.collection)
+            .find(filter, None)
+            .await
+        {
+            Ok(d) => d,
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        Ok(cursor.try_collect().await.unwrap_or_else(|_| vec![]))
+    }
+
     /// # Summary
     ///
     /// Find a User entity by its username.
@@ -298,7 +616,7 @@ impl UserRepository {
     ///
     /// ```
     /// let db = Database::new();
-    /// let user_repository = UserRepository::new(String::from("users"), email_regex);
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex, false, None);
     /// let user = user_repository.find_by_username(&String::from("username"), &db);
     /// ```
     ///
@@ -347,7 +665,7 @@ impl UserRepository {
     ///
     /// ```
     /// let db = Database::new();
-    /// let user_repository = UserRepository::new(String::from("users"), email_regex);
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex, false, None);
     /// let user = user_repository.find_by_email(&String::from("email"), &db);
     /// ```
     ///
@@ -359,8 +677,18 @@ impl UserRepository {
             return Err(Error::EmptyEmail);
         }
 
+        let email = self.normalize_email(email);
+        let mut conditions = vec![
+            doc! { "email": &email },
+            doc! { "secondaryEmails.email": &email },
+        ];
+
+        if let Some(hash) = self.hash_email(&email) {
+            conditions.push(doc! { "emailHashes": hash });
+        }
+
         let filter = doc! {
-            "email": email,
+            "$or": conditions,
         };
 
         let user = match db
@@ -377,155 +705,1105 @@ impl UserRepository {
 
     /// # Summary
     ///
-    /// Insert a User entity.
+    /// Find the User entity that is linked to an account of an external identity provider.
     ///
     /// # Arguments
     ///
-    /// * `user` - The User entity to insert.
+    /// * `provider` - The name of the identity provider.
+    /// * `subject` - The ID of the account at the identity provider.
     /// * `db` - The Database.
     ///
     /// # Example
     ///
     /// ```
     /// let db = Database::new();
-    /// let user_repository = UserRepository::new(String::from("users"), email_regex);
-    /// let user = User::new(String::from("username"), String::from("email"), String::from("password"));
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex, false, None);
+    /// let user = user_repository.find_by_identity("github", "583231", &db);
+    /// ```
     ///
-    /// user.first_name = String::from("first_name");
+    /// # Returns
     ///
-    /// let user = user_repository.update(user, &db);
-    /// ```
-    pub async fn update(&self, user: User, db: &Database) -> Result<User, Error> {
-        if user.email.is_some() && !self.email_regex.is_match(&user.email.clone().unwrap()) {
-            return Err(Error::InvalidEmail(user.email.unwrap()));
-        } else if user.email.is_some() {
-            match self
-                .find_by_email(&user.email.clone().unwrap().to_lowercase(), db)
-                .await
-            {
-                Ok(user) => {
-                    if user.is_some() {
-                        return Err(Error::EmailAlreadyTaken);
-                    }
-                }
-                Err(e) => {
-                    return Err(e);
-                }
-            };
-        }
-
-        match self
-            .find_by_username(&user.username.to_lowercase(), db)
-            .await
-        {
-            Ok(u) => {
-                if let Some(p) = u {
-                    if p.id != user.id {
-                        return Err(Error::UsernameAlreadyTaken);
-                    }
-                }
-            }
-            Err(e) => {
-                return Err(e);
-            }
-        };
-
-        let user_id = user.id;
+    /// * `Result<Option<User>, Error>` - The result of the operation.
+    pub async fn find_by_identity(
+        &self,
+        provider: &str,
+        subject: &str,
+        db: &Database,
+    ) -> Result<Option<User>, Error> {
         let filter = doc! {
-            "_id": &user_id,
-        };
-
-        let now: DateTime<Utc> = SystemTime::now().into();
-        let now: String = now.to_rfc3339();
-
-        let update = doc! {
-            "$set": {
-                "username": user.username,
-                "email": user.email,
-                "firstName": user.first_name,
-                "lastName": user.last_name,
-                "roles": user.roles,
-                "updated_at": now,
-                "enabled": user.enabled,
+            "identities": {
+                "$elemMatch": {
+                    "provider": provider,
+                    "subject": subject,
+                },
             },
         };
 
-        let collection = db.collection::<User>(&self.collection);
-        let result = collection.find_one_and_update(filter, update, None).await;
+        db.collection::<User>(&self.collection)
+            .find_one(filter, None)
+            .await
+            .map_err(Error::MongoDb)
+    }
+
+    /// # Summary
+    ///
+    /// Link a User entity to an account of an external identity provider.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the User entity.
+    /// * `identity` - The UserIdentity of the account.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let db = Database::new();
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex, false, None);
+    ///
+    /// let user = user_repository.add_identity("id", UserIdentity::new(String::from("github"), String::from("583231")), &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<User, Error>` - The updated User entity or the Error that occurred.
+    pub async fn add_identity(
+        &self,
+        id: &str,
+        identity: UserIdentity,
+        db: &Database,
+    ) -> Result<User, Error> {
+        if self
+            .find_by_identity(&identity.provider, &identity.subject, db)
+            .await?
+            .is_some()
+        {
+            return Err(Error::IdentityAlreadyLinked(identity.provider));
+        }
+
+        let user = self.find_existing(id, db).await?;
+        let identity = match mongodb::bson::to_bson(&identity) {
+            Ok(d) => d,
+            Err(e) => return Err(Error::MongoDb(e.into())),
+        };
+
+        let now: DateTime<Utc> = SystemTime::now().into();
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+
+        match db
+            .collection::<User>(&self.collection)
+            .find_one_and_update(
+                doc! { "_id": user.id },
+                doc! {
+                    "$push": { "identities": identity },
+                    "$set": { "updatedAt": mongodb::bson::DateTime::from_chrono(now) },
+                },
+                options,
+            )
+            .await
+        {
+            Ok(Some(u)) => Ok(u),
+            Ok(None) => Err(Error::UserNotFound(id.to_string())),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Store or remove the TOTP secret of a User entity. The User is enrolled in multi-factor authentication once its
+    /// TOTP secret has been confirmed. Removing the TOTP secret leaves the enrollment as it is, because the User can
+    /// have other second factors.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the User entity.
+    /// * `totp` - The UserTotp, or None to remove the TOTP secret.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user = user_repository.set_totp("id", Some(UserTotp::new(secret)), &db).await;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<User, Error>` - The updated User entity or the Error that occurred.
+    pub async fn set_totp(
+        &self,
+        id: &str,
+        totp: Option<UserTotp>,
+        db: &Database,
+    ) -> Result<User, Error> {
+        let user = self.find_existing(id, db).await?;
+        let now: DateTime<Utc> = SystemTime::now().into();
+        let enrolled = totp.as_ref().is_some_and(|t| t.confirmed);
+
+        let update = match totp {
+            Some(t) => {
+                let totp = match mongodb::bson::to_bson(&t) {
+                    Ok(d) => d,
+                    Err(e) => return Err(Error::MongoDb(e.into())),
+                };
+
+                let mut set = doc! {
+                    "totp": totp,
+                    "updatedAt": mongodb::bson::DateTime::from_chrono(now),
+                };
+                if enrolled {
+                    set.insert("mfaEnrolled", true);
+                }
+
+                doc! { "$set": set }
+            }
+            None => doc! {
+                "$unset": { "totp": "" },
+                "$set": {
+                    "updatedAt": mongodb::bson::DateTime::from_chrono(now),
+                },
+            },
+        };
+
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+
+        match db
+            .collection::<User>(&self.collection)
+            .find_one_and_update(doc! { "_id": user.id }, update, options)
+            .await
+        {
+            Ok(Some(u)) => Ok(u),
+            Ok(None) => Err(Error::UserNotFound(id.to_string())),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Record that the TOTP code of a time step was used, unless a code of the same or a later time step was used
+    /// before. The check and the update are a single operation, so that a code cannot be replayed concurrently.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the User entity.
+    /// * `step` - The time step of the TOTP code.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let accepted = user_repository.use_totp_step(user.id, 56_789_012, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<bool, Error>` - True if the time step had not been used yet, or the Error that occurred.
+    pub async fn use_totp_step(
+        &self,
+        id: ObjectId,
+        step: i64,
+        db: &Database,
+    ) -> Result<bool, Error> {
+        let filter = doc! {
+            "_id": id,
+            "totp.lastStep": { "$not": { "$gte": step } },
+        };
+
+        match db
+            .collection::<User>(&self.collection)
+            .update_one(filter, doc! { "$set": { "totp.lastStep": step } }, None)
+            .await
+        {
+            Ok(r) => Ok(r.matched_count > 0),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Replace the hashes of the recovery codes of a User entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the User entity.
+    /// * `recovery_codes` - The hashes of the recovery codes, or an empty Vec to remove them.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user = user_repository.set_recovery_codes("id", hashes, &db).await;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<User, Error>` - The updated User entity or the Error that occurred.
+    pub async fn set_recovery_codes(
+        &self,
+        id: &str,
+        recovery_codes: Vec<String>,
+        db: &Database,
+    ) -> Result<User, Error> {
+        let user = self.find_existing(id, db).await?;
+        let now: DateTime<Utc> = SystemTime::now().into();
+
+        let update = if recovery_codes.is_empty() {
+            doc! {
+                "$unset": { "recoveryCodes": "" },
+                "$set": {
+                    "updatedAt": mongodb::bson::DateTime::from_chrono(now),
+                },
+            }
+        } else {
+            doc! {
+                "$set": {
+                    "recoveryCodes": recovery_codes,
+                    "updatedAt": mongodb::bson::DateTime::from_chrono(now),
+                },
+            }
+        };
+
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+
+        match db
+            .collection::<User>(&self.collection)
+            .find_one_and_update(doc! { "_id": user.id }, update, options)
+            .await
+        {
+            Ok(Some(u)) => Ok(u),
+            Ok(None) => Err(Error::UserNotFound(id.to_string())),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Remove the hash of a recovery code from a User entity. The check and the removal are a single operation, so
+    /// that a recovery code cannot be used twice concurrently.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the User entity.
+    /// * `recovery_code` - The hash of the recovery code.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let accepted = user_repository.use_recovery_code(user.id, &hash, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<bool, Error>` - True if the recovery code belonged to the User and had not been used yet, or the Error
+    ///   that occurred.
+    pub async fn use_recovery_code(
+        &self,
+        id: ObjectId,
+        recovery_code: &str,
+        db: &Database,
+    ) -> Result<bool, Error> {
+        match db
+            .collection::<User>(&self.collection)
+            .update_one(
+                doc! { "_id": id, "recoveryCodes": recovery_code },
+                doc! { "$pull": { "recoveryCodes": recovery_code } },
+                None,
+            )
+            .await
+        {
+            Ok(r) => Ok(r.modified_count > 0),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Mark whether a User entity is enrolled in multi-factor authentication, for second factors that are not stored
+    /// on the User itself, such as passkeys.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the User entity.
+    /// * `enrolled` - Whether the User has a second factor.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user = user_repository.set_mfa_enrolled("id", true, &db).await;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<User, Error>` - The updated User entity or the Error that occurred.
+    pub async fn set_mfa_enrolled(
+        &self,
+        id: &str,
+        enrolled: bool,
+        db: &Database,
+    ) -> Result<User, Error> {
+        let user = self.find_existing(id, db).await?;
+        let now: DateTime<Utc> = SystemTime::now().into();
+
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+
+        match db
+            .collection::<User>(&self.collection)
+            .find_one_and_update(
+                doc! { "_id": user.id },
+                doc! {
+                    "$set": {
+                        "mfaEnrolled": enrolled,
+                        "updatedAt": mongodb::bson::DateTime::from_chrono(now),
+                    },
+                },
+                options,
+            )
+            .await
+        {
+            Ok(Some(u)) => Ok(u),
+            Ok(None) => Err(Error::UserNotFound(id.to_string())),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Insert a User entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The User entity to insert.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let db = Database::new();
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex, false, None);
+    /// let user = User::new(String::from("username"), String::from("email"), String::from("password"));
+    ///
+    /// user.first_name = String::from("first_name");
+    ///
+    /// let user = user_repository.update(user, &db);
+    /// ```
+    pub async fn update(&self, mut user: User, db: &Database) -> Result<User, Error> {
+        user.email = user.email.map(|e| self.normalize_email(&e));
+        user.phone_number = self.normalize_phone_number(user.phone_number)?;
+        user.locale = self.normalize_locale(user.locale)?;
+        user.timezone = self.validate_timezone(user.timezone)?;
+        self.validate_schedule(&user)?;
+        let mut email_verified = false;
+
+        if user.email.is_some() && !self.email_regex.is_match(&user.email.clone().unwrap()) {
+            return Err(Error::InvalidEmail(user.email.unwrap()));
+        } else if user.email.is_some() {
+            match self.find_by_email(&user.email.clone().unwrap(), db).await {
+                Ok(u) => {
+                    if let Some(p) = u {
+                        if p.id != user.id {
+                            return Err(Error::EmailAlreadyTaken);
+                        }
+
+                        // The email address may be promoted from one of the secondary email addresses
+                        email_verified = if p.email == user.email {
+                            p.email_verified
+                        } else {
+                            p.secondary_emails
+                                .iter()
+                                .any(|e| user.email.as_ref() == Some(&e.email) && e.verified)
+                        };
+                    }
+                }
+                Err(e) => {
+                    return Err(e);
+                }
+            };
+        }
+
+        match self
+            .find_by_username(&user.username.to_lowercase(), db)
+            .await
+        {
+            Ok(u) => {
+                if let Some(p) = u {
+                    if p.id != user.id {
+                        return Err(Error::UsernameAlreadyTaken);
+                    }
+                }
+            }
+            Err(e) => {
+                return Err(e);
+            }
+        };
+
+        let user_id = user.id;
+        let filter = doc! {
+            "_id": &user_id,
+        };
+
+        let now: DateTime<Utc> = SystemTime::now().into();
+        let now: String = now.to_rfc3339();
+        let email = user.email.clone();
+
+        let mut update = doc! {
+            "$set": {
+                "username": user.username,
+                "email": user.email,
+                "emailVerified": email_verified,
+                "firstName": user.first_name,
+                "lastName": user.last_name,
+                "phoneNumber": user.phone_number,
+                "locale": user.locale,
+                "timezone": user.timezone,
+                "roles": user.roles,
+                "updated_at": now,
+                "status": user.status.to_string(),
+                "activeFrom": user.active_from.map(mongodb::bson::DateTime::from_chrono),
+                "expiresAt": user.expires_at.map(mongodb::bson::DateTime::from_chrono),
+            },
+            "$pull": {
+                "secondaryEmails": { "email": email },
+            },
+            // Users that were stored before the status was introduced still carry the legacy flag
+            "$unset": {
+                "enabled": "",
+            },
+        };
+
+        // Tokens that were issued before the User was deactivated must not become valid again once it is reactivated
+        if !user.status.is_active() {
+            update.insert("$inc", doc! { "tokenVersion": 1 });
+        }
+
+        let collection = db.collection::<User>(&self.collection);
+        let result = collection.find_one_and_update(filter, update, None).await;
+
+        match result {
+            Ok(user) => {
+                if let Some(u) = user {
+                    // The secondary email addresses may have changed as well, so the hashes are derived from the stored User
+                    if self.email_hash_key.is_some() {
+                        let stored = self.find_existing(&user_id.to_hex(), db).await?;
+                        self.save_emails(stored, db).await?;
+                    }
+
+                    Ok(u)
+                } else {
+                    Err(Error::UserNotFound(user_id.to_hex()))
+                }
+            }
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Update the password of a User entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the User entity.
+    /// * `password` - The new password of the User entity.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let db = Database::new();
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex, false, None);
+    ///
+    /// user_repository.update_password(&String::from("id"), &String::from("password"), &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    pub async fn update_password(
+        &self,
+        id: &str,
+        password: &str,
+        db: &Database,
+    ) -> Result<(), Error> {
+        if id.is_empty() {
+            return Err(Error::EmptyId);
+        }
+
+        if password.is_empty() {
+            return Err(Error::EmptyPassword);
+        }
+
+        let target_object_id = match ObjectId::parse_str(id) {
+            Ok(res) => res,
+            Err(e) => {
+                return Err(Error::InvalidId(e.to_string()));
+            }
+        };
+
+        let filter = doc! {
+            "_id": target_object_id,
+        };
+
+        let now: DateTime<Utc> = SystemTime::now().into();
+        let now: String = now.to_rfc3339();
+
+        let update = doc! {
+            "$set": {
+                "password": password,
+                "mustChangePassword": false,
+                "updated_at": now,
+            },
+            "$inc": {
+                "tokenVersion": 1,
+            },
+        };
+
+        let collection = db.collection::<User>(&self.collection);
+        let result = collection.update_one(filter, update, None).await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Require a User entity to change its password before it can use any other permission.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the User entity.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let db = Database::new();
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex, false, None);
+    ///
+    /// user_repository.require_password_change("id", &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    pub async fn require_password_change(&self, id: &str, db: &Database) -> Result<(), Error> {
+        if id.is_empty() {
+            return Err(Error::EmptyId);
+        }
+
+        let target_object_id = match ObjectId::parse_str(id) {
+            Ok(res) => res,
+            Err(e) => {
+                return Err(Error::InvalidId(e.to_string()));
+            }
+        };
+
+        let filter = doc! {
+            "_id": target_object_id,
+        };
+
+        let update = doc! {
+            "$set": {
+                "mustChangePassword": true,
+            },
+        };
+
+        match db
+            .collection::<User>(&self.collection)
+            .update_one(filter, update, None)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Revoke all tokens that have been issued to a User entity until now by incrementing its token version.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the User entity.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let db = Database::new();
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex, false, None);
+    ///
+    /// user_repository.revoke_tokens("id", &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    pub async fn revoke_tokens(&self, id: &str, db: &Database) -> Result<(), Error> {
+        if id.is_empty() {
+            return Err(Error::EmptyId);
+        }
+
+        let target_object_id = match ObjectId::parse_str(id) {
+            Ok(res) => res,
+            Err(e) => {
+                return Err(Error::InvalidId(e.to_string()));
+            }
+        };
+
+        let filter = doc! {
+            "_id": target_object_id,
+        };
+
+        let now: DateTime<Utc> = SystemTime::now().into();
+
+        let update = doc! {
+            "$set": {
+                "updatedAt": mongodb::bson::DateTime::from_chrono(now),
+            },
+            "$inc": {
+                "tokenVersion": 1,
+            },
+        };
+
+        let collection = db.collection::<User>(&self.collection);
+        match collection.update_one(filter, update, None).await {
+            Ok(r) if r.matched_count == 0 => Err(Error::UserNotFound(id.to_string())),
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Activate the pending User entities of which the scheduled activation date has passed.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let db = Database::new();
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex, false, None);
+    ///
+    /// let count = user_repository.activate_scheduled(&db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64, Error>` - The number of activated User entities or the Error that occurred.
+    pub async fn activate_scheduled(&self, db: &Database) -> Result<u64, Error> {
+        let now = mongodb::bson::DateTime::now();
+
+        let filter = doc! {
+            "status": UserStatus::Pending.to_string(),
+            "activeFrom": { "$lte": now },
+            "$or": [
+                { "expiresAt": null },
+                { "expiresAt": { "$gt": now } },
+            ],
+        };
+
+        let update = doc! {
+            "$set": {
+                "status": UserStatus::Active.to_string(),
+                "updatedAt": now,
+            },
+        };
+
+        match db
+            .collection::<User>(&self.collection)
+            .update_many(filter, update, None)
+            .await
+        {
+            Ok(r) => Ok(r.modified_count),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Deactivate the User entities of which the scheduled expiration date has passed and revoke their tokens.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let db = Database::new();
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex, false, None);
+    ///
+    /// let count = user_repository.expire_scheduled(&db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64, Error>` - The number of deactivated User entities or the Error that occurred.
+    pub async fn expire_scheduled(&self, db: &Database) -> Result<u64, Error> {
+        let now = mongodb::bson::DateTime::now();
+
+        let filter = doc! {
+            "status": { "$ne": UserStatus::Deactivated.to_string() },
+            "expiresAt": { "$lte": now },
+        };
+
+        let update = doc! {
+            "$set": {
+                "status": UserStatus::Deactivated.to_string(),
+                "updatedAt": now,
+            },
+            "$inc": {
+                "tokenVersion": 1,
+            },
+            "$unset": {
+                "enabled": "",
+            },
+        };
+
+        match db
+            .collection::<User>(&self.collection)
+            .update_many(filter, update, None)
+            .await
+        {
+            Ok(r) => Ok(r.modified_count),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Add a secondary email address to a User entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the User entity.
+    /// * `email` - The email address to add.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let db = Database::new();
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex, false, None);
+    ///
+    /// let user = user_repository.add_email("id", "alias@opserva.io", &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<User, Error>` - The updated User entity or the Error that occurred.
+    pub async fn add_email(&self, id: &str, email: &str, db: &Database) -> Result<User, Error> {
+        let email = self.normalize_email(email);
+        if email.is_empty() {
+            return Err(Error::EmptyEmail);
+        }
+
+        if !self.email_regex.is_match(&email) {
+            return Err(Error::InvalidEmail(email));
+        }
+
+        let mut user = self.find_existing(id, db).await?;
+
+        match self.find_by_email(&email, db).await {
+            Ok(Some(_)) => return Err(Error::EmailAlreadyTaken),
+            Ok(None) => {}
+            Err(e) => return Err(e),
+        }
+
+        user.secondary_emails.push(UserEmail::new(email));
+
+        self.save_emails(user, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Remove a secondary email address from a User entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the User entity.
+    /// * `email` - The email address to remove.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let db = Database::new();
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex, false, None);
+    ///
+    /// let user = user_repository.remove_email("id", "alias@opserva.io", &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<User, Error>` - The updated User entity or the Error that occurred.
+    pub async fn remove_email(&self, id: &str, email: &str, db: &Database) -> Result<User, Error> {
+        let email = self.normalize_email(email);
+        let mut user = self.find_existing(id, db).await?;
+
+        if user.email.as_deref() == Some(email.as_str()) {
+            return Err(Error::PrimaryEmailRemoval);
+        }
+
+        let count = user.secondary_emails.len();
+        user.secondary_emails.retain(|e| e.email != email);
+        if user.secondary_emails.len() == count {
+            return Err(Error::EmailNotFound(email));
+        }
+
+        self.save_emails(user, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Mark one of the secondary email addresses of a User entity as the primary email address.
+    /// The previous primary email address, if any, becomes a secondary email address. Both keep their verification state.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the User entity.
+    /// * `email` - The secondary email address that should become the primary email address.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let db = Database::new();
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex, false, None);
+    ///
+    /// let user = user_repository.set_primary_email("id", "alias@opserva.io", &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<User, Error>` - The updated User entity or the Error that occurred.
+    pub async fn set_primary_email(
+        &self,
+        id: &str,
+        email: &str,
+        db: &Database,
+    ) -> Result<User, Error> {
+        let email = self.normalize_email(email);
+        let mut user = self.find_existing(id, db).await?;
 
-        match result {
-            Ok(user) => {
-                if let Some(u) = user {
-                    Ok(u)
-                } else {
-                    Err(Error::UserNotFound(user_id.to_hex()))
-                }
-            }
-            Err(e) => Err(Error::MongoDb(e)),
+        if user.email.as_deref() == Some(email.as_str()) {
+            return Ok(user);
+        }
+
+        let index = match user.secondary_emails.iter().position(|e| e.email == email) {
+            Some(i) => i,
+            None => return Err(Error::EmailNotFound(email)),
+        };
+
+        let new_primary = user.secondary_emails.remove(index);
+        if let Some(old_primary) = user.email.take() {
+            user.secondary_emails.push(UserEmail {
+                email: old_primary,
+                verified: user.email_verified,
+            });
         }
+
+        user.email = Some(new_primary.email);
+        user.email_verified = new_primary.verified;
+
+        self.save_emails(user, db).await
     }
 
     /// # Summary
     ///
-    /// Update the password of a User entity.
+    /// Set the verification state of the primary or one of the secondary email addresses of a User entity.
     ///
     /// # Arguments
     ///
     /// * `id` - The id of the User entity.
-    /// * `password` - The new password of the User entity.
+    /// * `email` - The email address.
+    /// * `verified` - Whether the email address is verified.
+    /// * `db` - The Database.
     ///
     /// # Example
     ///
     /// ```
     /// let db = Database::new();
-    /// let user_repository = UserRepository::new(String::from("users"), email_regex);
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex, false, None);
     ///
-    /// user_repository.update_password(&String::from("id"), &String::from("password"), &db);
+    /// let user = user_repository.set_email_verified("id", "alias@opserva.io", true, &db);
     /// ```
     ///
     /// # Returns
     ///
-    /// * `Result<(), Error>` - The result of the operation.
-    pub async fn update_password(
+    /// * `Result<User, Error>` - The updated User entity or the Error that occurred.
+    pub async fn set_email_verified(
         &self,
         id: &str,
-        password: &str,
+        email: &str,
+        verified: bool,
         db: &Database,
-    ) -> Result<(), Error> {
-        if id.is_empty() {
-            return Err(Error::EmptyId);
+    ) -> Result<User, Error> {
+        let email = self.normalize_email(email);
+        let mut user = self.find_existing(id, db).await?;
+
+        if user.email.as_deref() == Some(email.as_str()) {
+            user.email_verified = verified;
+        } else {
+            match user.secondary_emails.iter_mut().find(|e| e.email == email) {
+                Some(e) => e.verified = verified,
+                None => return Err(Error::EmailNotFound(email)),
+            }
         }
 
-        if password.is_empty() {
-            return Err(Error::EmptyPassword);
+        self.save_emails(user, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Store the email hashes of the User entities that have email addresses but no email hashes, for instance because
+    /// they were created before an email hash key was configured.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let db = Database::new();
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex, false, Some(String::from("key")));
+    ///
+    /// let (backfilled, skipped) = user_repository.backfill_email_hashes(&db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(u64, Vec<String>), Error>` - The amount of User entities that received their email hashes and the ids of
+    ///   the User entities whose hashes could not be stored, or the Error that occurred.
+    pub async fn backfill_email_hashes(&self, db: &Database) -> Result<(u64, Vec<String>), Error> {
+        if self.email_hash_key.is_none() {
+            return Ok((0, vec![]));
         }
 
-        let target_object_id = match ObjectId::parse_str(id) {
-            Ok(res) => res,
-            Err(e) => {
-                return Err(Error::InvalidId(e.to_string()));
+        let filter = doc! {
+            "emailHashes": { "$exists": false },
+            "$or": [
+                { "email": { "$type": "string" } },
+                { "secondaryEmails.0": { "$exists": true } },
+            ],
+        };
+
+        let collection = db.collection::<User>(&self.collection);
+        let mut cursor = match collection.find(filter, None).await {
+            Ok(d) => d,
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        let mut backfilled: u64 = 0;
+        let mut skipped = vec![];
+        while let Some(user) = cursor.try_next().await.map_err(Error::MongoDb)? {
+            let email_hashes = self.email_hashes(&user);
+            if email_hashes.is_empty() {
+                continue;
             }
+
+            // A hash that is already stored for another User violates the unique index, which should not stop the others
+            match collection
+                .update_one(
+                    doc! { "_id": user.id, "emailHashes": { "$exists": false } },
+                    doc! { "$set": { "emailHashes": email_hashes } },
+                    None,
+                )
+                .await
+            {
+                Ok(r) => backfilled += r.modified_count,
+                Err(_) => skipped.push(user.id.to_hex()),
+            }
+        }
+
+        Ok((backfilled, skipped))
+    }
+
+    /// # Summary
+    ///
+    /// Find a User entity by its id, or return an Error if it does not exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the User entity.
+    /// * `db` - The Database.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<User, Error>` - The User entity or the Error that occurred.
+    async fn find_existing(&self, id: &str, db: &Database) -> Result<User, Error> {
+        match self.find_by_id(id, db).await? {
+            Some(u) => Ok(u),
+            None => Err(Error::UserNotFound(id.to_string())),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Store the primary and secondary email addresses of a User entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The User entity.
+    /// * `db` - The Database.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<User, Error>` - The updated User entity or the Error that occurred.
+    async fn save_emails(&self, user: User, db: &Database) -> Result<User, Error> {
+        let secondary_emails = match mongodb::bson::to_bson(&user.secondary_emails) {
+            Ok(d) => d,
+            Err(e) => return Err(Error::MongoDb(e.into())),
         };
 
         let filter = doc! {
-            "_id": target_object_id,
+            "_id": user.id,
         };
 
         let now: DateTime<Utc> = SystemTime::now().into();
-        let now: String = now.to_rfc3339();
+        let email_hashes = self.email_hashes(&user);
 
-        let update = doc! {
+        let mut update = doc! {
             "$set": {
-                "password": password,
-                "updated_at": now,
+                "email": user.email,
+                "emailVerified": user.email_verified,
+                "secondaryEmails": secondary_emails,
+                "updatedAt": mongodb::bson::DateTime::from_chrono(now),
             },
         };
 
-        let collection = db.collection::<User>(&self.collection);
-        let result = collection.update_one(filter, update, None).await;
+        // An empty array would collide with other Users in the unique email hash index, so the field is removed instead
+        if email_hashes.is_empty() {
+            update.insert("$unset", doc! { "emailHashes": "" });
+        } else if let Ok(set) = update.get_document_mut("$set") {
+            set.insert("emailHashes", email_hashes);
+        }
 
-        match result {
-            Ok(_) => Ok(()),
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+
+        match db
+            .collection::<User>(&self.collection)
+            .find_one_and_update(filter, update, options)
+            .await
+        {
+            Ok(Some(u)) => Ok(u),
+            Ok(None) => Err(Error::UserNotFound(user.id.to_hex())),
             Err(e) => Err(Error::MongoDb(e)),
         }
     }
@@ -543,7 +1821,7 @@ impl UserRepository {
     ///
     /// ```
     /// let db = Database::new();
-    /// let user_repository = UserRepository::new(String::from("users"), email_regex);
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex, false, None);
     ///
     /// user_repository.delete(&String::from("id"), &db);
     /// ```
@@ -576,6 +1854,69 @@ impl UserRepository {
         }
     }
 
+    /// # Summary
+    ///
+    /// Store a User entity that a duplicate User entity was merged into. The email hashes and identities of the
+    /// duplicate are removed first, because they must be unique across Users. The duplicate is restored if the merged
+    /// User cannot be stored, and it is not deleted, so that it can be deleted once everything it owns was moved.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The merged User entity.
+    /// * `other` - The duplicate User entity.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user = user_repository.merge(user, other, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<User, Error>` - The merged User entity or the Error that occurred.
+    pub async fn merge(&self, mut user: User, other: User, db: &Database) -> Result<User, Error> {
+        user.email_hashes = self.email_hashes(&user);
+        user.updated_at = SystemTime::now().into();
+
+        let collection = db.collection::<User>(&self.collection);
+        let detach = doc! {
+            "$unset": {
+                "emailHashes": "",
+                "identities": "",
+            },
+        };
+        match collection
+            .update_one(doc! { "_id": other.id }, detach, None)
+            .await
+        {
+            Ok(r) if r.matched_count == 0 => return Err(Error::UserNotFound(other.id.to_hex())),
+            Ok(_) => {}
+            Err(e) => return Err(Error::MongoDb(e)),
+        }
+
+        let res = collection
+            .replace_one(doc! { "_id": user.id }, &user, None)
+            .await;
+        match res {
+            Ok(r) if r.matched_count > 0 => Ok(user),
+            res => {
+                // The merge failed, so the duplicate is put back as it was
+                if let Err(e) = collection
+                    .replace_one(doc! { "_id": other.id }, &other, None)
+                    .await
+                {
+                    return Err(Error::MongoDb(e));
+                }
+
+                match res {
+                    Ok(_) => Err(Error::UserNotFound(user.id.to_hex())),
+                    Err(e) => Err(Error::MongoDb(e)),
+                }
+            }
+        }
+    }
+
     /// # Summary
     ///
     /// Delete a role from all users.
@@ -589,7 +1930,7 @@ impl UserRepository {
     ///
     /// ```
     /// let db = Database::new();
-    /// let user_repository = UserRepository::new(String::from("users"), email_regex);
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex, false, None);
     ///
     /// user_repository.delete_role_from_all_users(&String::from("role_id"), &db);
     /// ```
@@ -633,11 +1974,106 @@ impl UserRepository {
 
     /// # Summary
     ///
-    /// Search for users.
+    /// Count the users that hold a role.
+    ///
+    /// # Arguments
+    ///
+    /// * `role_id` - The id of the role.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let db = Database::new();
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex, false, None);
+    ///
+    /// let count = user_repository.count_by_role("role_id", &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64, Error>` - The number of users that hold the role or the Error that occurred.
+    pub async fn count_by_role(&self, role_id: &str, db: &Database) -> Result<u64, Error> {
+        if role_id.is_empty() {
+            return Err(Error::EmptyId);
+        }
+
+        let target_object_id = match ObjectId::parse_str(role_id) {
+            Ok(res) => res,
+            Err(e) => {
+                return Err(Error::InvalidId(e.to_string()));
+            }
+        };
+
+        let filter = doc! {
+            "roles": target_object_id,
+        };
+
+        match db
+            .collection::<User>(&self.collection)
+            .count_documents(filter, None)
+            .await
+        {
+            Ok(c) => Ok(c),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Count the active users that hold a role.
+    ///
+    /// # Arguments
+    ///
+    /// * `role_id` - The id of the role.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let db = Database::new();
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex, false, None);
+    ///
+    /// let count = user_repository.count_active_by_role("role_id", &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64, Error>` - The number of active users that hold the role or the Error that occurred.
+    pub async fn count_active_by_role(&self, role_id: &str, db: &Database) -> Result<u64, Error> {
+        if role_id.is_empty() {
+            return Err(Error::EmptyId);
+        }
+
+        let target_object_id = match ObjectId::parse_str(role_id) {
+            Ok(res) => res,
+            Err(e) => {
+                return Err(Error::InvalidId(e.to_string()));
+            }
+        };
+
+        let filter = doc! {
+            "roles": target_object_id,
+            "status": UserStatus::Active.to_string(),
+        };
+
+        match db
+            .collection::<User>(&self.collection)
+            .count_documents(filter, None)
+            .await
+        {
+            Ok(c) => Ok(c),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Search for users by text and/or structured criteria.
     ///
     /// # Arguments
     ///
-    /// * `text` - The text to search for.
+    /// * `filter` - The criteria that the users must meet.
     /// * `limit` - The limit of the result.
     /// * `page` - The page of the result.
     /// * `db` - The Database.
@@ -646,21 +2082,26 @@ impl UserRepository {
     ///
     /// ```
     /// let db = Database::new();
-    /// let user_repository = UserRepository::new(String::from("users"), email_regex);
-    /// let users = user_repository.search(&String::from("text"), Some(10), Some(1), &db);
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex, false, None);
+    /// let filter = UserFilter {
+    ///   source: Some(UserSource::Registration),
+    ///   ..Default::default()
+    /// };
+    ///
+    /// let users = user_repository.search(&filter, Some(10), Some(1), &db);
     /// ```
     ///
     /// # Returns
     ///
-    /// * `Result<Vec<User>, Error>` - The result of the operation.
+    /// * `Result<Vec<SearchHit<User>>, Error>` - The Users, sorted by their relevance if the filter contains text.
     pub async fn search(
         &self,
-        text: &str,
+        filter: &UserFilter,
         limit: Option<i64>,
         page: Option<i64>,
         db: &Database,
-    ) -> Result<Vec<User>, Error> {
-        if text.is_empty() {
+    ) -> Result<Vec<SearchHit<User>>, Error> {
+        if filter.is_empty() || filter.text.as_ref().is_some_and(|t| t.is_empty()) {
             return Err(Error::EmptyTextSearch);
         }
 
@@ -677,23 +2118,42 @@ impl UserRepository {
             }
         }
 
-        let find_options = FindOptions::builder().limit(limit).skip(skip).build();
-
-        let filter = doc! {
-            "$text": {
-                "$search": text,
-            },
-        };
-
-        let cursor = match db
-            .collection::<User>(&self.collection)
-            .find(filter, find_options)
-            .await
+        match find_hits(
+            db.collection::<Document>(&self.collection),
+            filter.to_document(),
+            limit,
+            skip,
+        )
+        .await
         {
-            Ok(d) => d,
-            Err(e) => return Err(Error::MongoDb(e)),
-        };
+            Ok(d) => Ok(d),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
 
-        Ok(cursor.try_collect().await.unwrap_or_else(|_| vec![]))
+    /// # Summary
+    ///
+    /// Count the Users that match a UserFilter. An empty UserFilter counts all Users.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - The criteria that the Users must meet.
+    /// * `db` - The database to use.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_repository = UserRepository::new(String::from("users"));
+    /// let total = user_repository.count(&UserFilter::default(), &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64, Error>` - The number of Users.
+    pub async fn count(&self, filter: &UserFilter, db: &Database) -> Result<u64, Error> {
+        db.collection::<User>(&self.collection)
+            .count_documents(filter.to_document(), None)
+            .await
+            .map_err(Error::MongoDb)
     }
 }
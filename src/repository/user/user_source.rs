@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum UserSource {
+    Registration,
+    Invitation,
+    Admin,
+    Directory,
+    System,
+    Scim,
+    Social,
+    Sso,
+    Cli,
+}
+
+impl Display for UserSource {
+    /// # Summary
+    ///
+    /// Display the UserSource in the same format in which it is stored.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The Formatter.
+    ///
+    /// # Returns
+    ///
+    /// * `std::fmt::Result` - The result of the display.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            UserSource::Registration => "registration",
+            UserSource::Invitation => "invitation",
+            UserSource::Admin => "admin",
+            UserSource::Directory => "directory",
+            UserSource::System => "system",
+            UserSource::Scim => "scim",
+            UserSource::Social => "social",
+            UserSource::Sso => "sso",
+            UserSource::Cli => "cli",
+        };
+
+        write!(f, "{}", s)
+    }
+}
+
+impl FromStr for UserSource {
+    type Err = String;
+
+    /// # Summary
+    ///
+    /// Parse a UserSource.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The source, in lowercase.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<UserSource, String>` - The UserSource, or an error message if the source is unknown.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "registration" => Ok(UserSource::Registration),
+            "invitation" => Ok(UserSource::Invitation),
+            "admin" => Ok(UserSource::Admin),
+            "directory" => Ok(UserSource::Directory),
+            "system" => Ok(UserSource::System),
+            "scim" => Ok(UserSource::Scim),
+            "social" => Ok(UserSource::Social),
+            "sso" => Ok(UserSource::Sso),
+            "cli" => Ok(UserSource::Cli),
+            _ => Err(format!("Unknown User source: {}", s)),
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum UserStatus {
+    Pending,
+    Active,
+    Suspended,
+    Deactivated,
+    Locked,
+}
+
+impl UserStatus {
+    /// # Summary
+    ///
+    /// Check whether a User with this status can log in and use its tokens.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// if !user.status.is_active() {
+    ///     return HttpResponse::Forbidden().finish();
+    /// }
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the status is `Active`.
+    pub fn is_active(&self) -> bool {
+        *self == UserStatus::Active
+    }
+
+    /// # Summary
+    ///
+    /// Check whether a User can move from this status to another status.
+    ///
+    /// * A pending User can be activated or deactivated.
+    /// * An active User can be suspended, deactivated or locked.
+    /// * A suspended or locked User can be reactivated or deactivated.
+    /// * A deactivated User can only be reactivated.
+    ///
+    /// # Arguments
+    ///
+    /// * `next` - The new status.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let allowed = UserStatus::Pending.can_transition_to(&UserStatus::Active);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the transition is allowed. Keeping the same status is always allowed.
+    pub fn can_transition_to(&self, next: &UserStatus) -> bool {
+        use UserStatus::*;
+
+        matches!(
+            (self, next),
+            (Pending, Active | Deactivated)
+                | (Active, Suspended | Deactivated | Locked)
+                | (Suspended | Locked, Active | Deactivated)
+                | (Deactivated, Active)
+        ) || self == next
+    }
+}
+
+impl Display for UserStatus {
+    /// # Summary
+    ///
+    /// Display the UserStatus in the same format in which it is stored.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The Formatter.
+    ///
+    /// # Returns
+    ///
+    /// * `std::fmt::Result` - The result of the display.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            UserStatus::Pending => "pending",
+            UserStatus::Active => "active",
+            UserStatus::Suspended => "suspended",
+            UserStatus::Deactivated => "deactivated",
+            UserStatus::Locked => "locked",
+        };
+
+        write!(f, "{}", s)
+    }
+}
+
+impl FromStr for UserStatus {
+    type Err = String;
+
+    /// # Summary
+    ///
+    /// Parse a UserStatus.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The status, in lowercase.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<UserStatus, String>` - The UserStatus, or an error message if the status is unknown.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(UserStatus::Pending),
+            "active" => Ok(UserStatus::Active),
+            "suspended" => Ok(UserStatus::Suspended),
+            "deactivated" => Ok(UserStatus::Deactivated),
+            "locked" => Ok(UserStatus::Locked),
+            _ => Err(format!("Unknown User status: {}", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for UserStatus {
+    /// # Summary
+    ///
+    /// Deserialize a UserStatus. Users that were stored before the status was introduced only have an `enabled` flag,
+    /// which is read as `Active` or `Deactivated`.
+    ///
+    /// # Arguments
+    ///
+    /// * `deserializer` - The Deserializer.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<UserStatus, D::Error>` - The UserStatus.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Status(String),
+            Enabled(bool),
+        }
+
+        match Repr::deserialize(deserializer)? {
+            Repr::Status(s) => UserStatus::from_str(&s).map_err(serde::de::Error::custom),
+            Repr::Enabled(true) => Ok(UserStatus::Active),
+            Repr::Enabled(false) => Ok(UserStatus::Deactivated),
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct UserTotp {
+    pub secret: String,
+    pub confirmed: bool,
+    #[serde(rename = "lastStep", default, skip_serializing_if = "Option::is_none")]
+    pub last_step: Option<i64>,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl UserTotp {
+    /// # Summary
+    ///
+    /// Create a new UserTotp that has not been confirmed yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `secret` - The encrypted TOTP secret.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_totp = UserTotp::new(totp_service.encrypt(&secret)?);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `UserTotp` - The new UserTotp.
+    pub fn new(secret: String) -> UserTotp {
+        UserTotp {
+            secret,
+            confirmed: false,
+            last_step: None,
+            created_at: SystemTime::now().into(),
+        }
+    }
+}
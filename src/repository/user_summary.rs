@@ -0,0 +1,2 @@
+pub mod user_summary_model;
+pub mod user_summary_repository;
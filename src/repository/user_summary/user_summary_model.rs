@@ -0,0 +1,55 @@
+use crate::repository::user::user_status::UserStatus;
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct UserSummaryRole {
+    pub id: ObjectId,
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct UserSummary {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub username: String,
+    pub email: Option<String>,
+    pub status: UserStatus,
+    #[serde(default)]
+    pub roles: Vec<UserSummaryRole>,
+    #[serde(
+        rename = "lastLoginAt",
+        default,
+        with = "crate::repository::user::user_model::optional_chrono_datetime_as_bson_datetime"
+    )]
+    pub last_login_at: Option<DateTime<Utc>>,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Display for UserSummary {
+    /// # Summary
+    ///
+    /// Display the UserSummary. The email address is omitted, so that it does not end up in the logs.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The Formatter.
+    ///
+    /// # Returns
+    ///
+    /// * `std::fmt::Result` - The result of the display.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "UserSummary: {{ id: {}, username: {}, status: {}, roles: {} }}",
+            self.id.to_hex(),
+            self.username,
+            self.status,
+            self.roles.len()
+        )
+    }
+}
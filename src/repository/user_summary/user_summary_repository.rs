@@ -0,0 +1,412 @@
+use crate::repository::user_summary::user_summary_model::UserSummary;
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, Document};
+use mongodb::error::Error as MongoError;
+use mongodb::options::{FindOptions, UpdateOptions};
+use mongodb::Database;
+
+#[derive(Clone)]
+pub struct UserSummaryRepository {
+    pub collection: String,
+    pub user_collection: String,
+    pub role_collection: String,
+}
+
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Empty collection")]
+    EmptyCollection,
+    #[error("MongoDB error: {0}")]
+    MongoDb(#[source] MongoError),
+}
+
+impl UserSummaryRepository {
+    /// # Summary
+    ///
+    /// Creates a new UserSummaryRepository instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection` - A String that holds the UserSummary collection name.
+    /// * `user_collection` - A String that holds the User collection name.
+    /// * `role_collection` - A String that holds the Role collection name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_summary_repository = match UserSummaryRepository::new(
+    ///     String::from("userSummaries"),
+    ///     String::from("users"),
+    ///     String::from("roles"),
+    /// ) {
+    ///    Ok(d) => d,
+    ///    Err(e) => panic!("Failed to initialize UserSummary repository: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A UserSummaryRepository instance.
+    pub fn new(
+        collection: String,
+        user_collection: String,
+        role_collection: String,
+    ) -> Result<UserSummaryRepository, Error> {
+        if collection.is_empty() || user_collection.is_empty() || role_collection.is_empty() {
+            return Err(Error::EmptyCollection);
+        }
+
+        Ok(UserSummaryRepository {
+            collection,
+            user_collection,
+            role_collection,
+        })
+    }
+
+    /// # Summary
+    ///
+    /// Rebuild the UserSummary of one or all Users from the User and Role collections. The Role names are resolved and
+    /// the result is merged into the UserSummary collection by the database itself, so that the last login of a User is
+    /// preserved and no User has to be loaded by the application.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User of which the UserSummary should be rebuilt, or None to rebuild all UserSummaries.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// match user_summary_repository.refresh(Some(user.id), &db).await {
+    ///   Ok(_) => (),
+    ///   Err(e) => panic!("Failed to refresh UserSummary: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the unit type or an Error.
+    pub async fn refresh(&self, user_id: Option<ObjectId>, db: &Database) -> Result<(), Error> {
+        let mut pipeline: Vec<Document> = vec![];
+
+        if let Some(id) = user_id {
+            pipeline.push(doc! { "$match": { "_id": id } });
+        }
+
+        pipeline.push(doc! {
+            "$lookup": {
+                "from": self.role_collection.as_str(),
+                "localField": "roles",
+                "foreignField": "_id",
+                "as": "roleDocuments",
+            }
+        });
+        pipeline.push(doc! {
+            "$project": {
+                "_id": 1,
+                "username": 1,
+                "email": 1,
+                "status": { "$ifNull": ["$status", "$enabled"] },
+                "roles": {
+                    "$map": {
+                        "input": "$roleDocuments",
+                        "as": "r",
+                        "in": { "id": "$$r._id", "name": "$$r.name" },
+                    }
+                },
+                "updatedAt": "$$NOW",
+            }
+        });
+        pipeline.push(doc! {
+            "$merge": {
+                "into": self.collection.as_str(),
+                "on": "_id",
+                "whenMatched": "merge",
+                "whenNotMatched": "insert",
+            }
+        });
+
+        match db
+            .collection::<Document>(&self.user_collection)
+            .aggregate(pipeline, None)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check whether every User has a UserSummary.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let complete = match user_summary_repository.is_complete(&db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to count UserSummaries: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with a bool that indicates whether the number of UserSummaries matches the number of Users, or an Error.
+    pub async fn is_complete(&self, db: &Database) -> Result<bool, Error> {
+        let users = db
+            .collection::<Document>(&self.user_collection)
+            .count_documents(None, None)
+            .await
+            .map_err(Error::MongoDb)?;
+        let summaries = db
+            .collection::<Document>(&self.collection)
+            .count_documents(None, None)
+            .await
+            .map_err(Error::MongoDb)?;
+
+        Ok(users == summaries)
+    }
+
+    /// # Summary
+    ///
+    /// Record the time at which a User last logged in.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User.
+    /// * `last_login_at` - The time of the login.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// match user_summary_repository.set_last_login(user.id, now, &db).await {
+    ///   Ok(_) => (),
+    ///   Err(e) => panic!("Failed to record last login: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the unit type or an Error.
+    pub async fn set_last_login(
+        &self,
+        user_id: ObjectId,
+        last_login_at: DateTime<Utc>,
+        db: &Database,
+    ) -> Result<(), Error> {
+        let filter = doc! {
+            "_id": user_id,
+        };
+
+        let update = doc! {
+            "$set": {
+                "lastLoginAt": mongodb::bson::DateTime::from_chrono(last_login_at),
+            },
+        };
+
+        match db
+            .collection::<UserSummary>(&self.collection)
+            .update_one(filter, update, None)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Update the name of a Role in every UserSummary that lists the Role.
+    ///
+    /// # Arguments
+    ///
+    /// * `role_id` - The ID of the Role.
+    /// * `name` - The new name of the Role.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// match user_summary_repository.rename_role(role.id, &role.name, &db).await {
+    ///   Ok(_) => (),
+    ///   Err(e) => panic!("Failed to rename Role in UserSummaries: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the unit type or an Error.
+    pub async fn rename_role(
+        &self,
+        role_id: ObjectId,
+        name: &str,
+        db: &Database,
+    ) -> Result<(), Error> {
+        let filter = doc! {
+            "roles.id": role_id,
+        };
+
+        let update = doc! {
+            "$set": {
+                "roles.$[r].name": name,
+            },
+        };
+
+        let options = UpdateOptions::builder()
+            .array_filters(vec![doc! { "r.id": role_id }])
+            .build();
+
+        match db
+            .collection::<UserSummary>(&self.collection)
+            .update_many(filter, update, options)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Remove a Role from every UserSummary that lists the Role.
+    ///
+    /// # Arguments
+    ///
+    /// * `role_id` - The ID of the Role.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// match user_summary_repository.remove_role(role.id, &db).await {
+    ///   Ok(_) => (),
+    ///   Err(e) => panic!("Failed to remove Role from UserSummaries: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the unit type or an Error.
+    pub async fn remove_role(&self, role_id: ObjectId, db: &Database) -> Result<(), Error> {
+        let filter = doc! {
+            "roles.id": role_id,
+        };
+
+        let update = doc! {
+            "$pull": {
+                "roles": { "id": role_id },
+            },
+        };
+
+        match db
+            .collection::<UserSummary>(&self.collection)
+            .update_many(filter, update, None)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Delete the UserSummary of a User.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// match user_summary_repository.delete(user.id, &db).await {
+    ///   Ok(_) => (),
+    ///   Err(e) => panic!("Failed to delete UserSummary: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the unit type or an Error.
+    pub async fn delete(&self, user_id: ObjectId, db: &Database) -> Result<(), Error> {
+        let filter = doc! {
+            "_id": user_id,
+        };
+
+        match db
+            .collection::<UserSummary>(&self.collection)
+            .delete_one(filter, None)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Find all UserSummaries, ordered by username.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The maximum number of UserSummaries to return.
+    /// * `page` - The page of UserSummaries to return.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let summaries = match user_summary_repository.find_all(Some(100), Some(1), &db).await {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to find UserSummaries: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the UserSummaries or an Error.
+    pub async fn find_all(
+        &self,
+        limit: Option<i64>,
+        page: Option<i64>,
+        db: &Database,
+    ) -> Result<Vec<UserSummary>, Error> {
+        let mut skip: Option<u64> = None;
+
+        if let Some(l) = limit {
+            if l > 1 {
+                if let Some(p) = page {
+                    if p > 1 {
+                        let res = u64::try_from((p - 1) * l).unwrap_or(0);
+                        skip = Some(res);
+                    }
+                }
+            }
+        }
+
+        let find_options = FindOptions::builder()
+            .sort(doc! { "username": 1 })
+            .limit(limit)
+            .skip(skip)
+            .build();
+
+        let cursor = match db
+            .collection::<UserSummary>(&self.collection)
+            .find(None, find_options)
+            .await
+        {
+            Ok(d) => d,
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        Ok(cursor.try_collect().await.unwrap_or_else(|_| vec![]))
+    }
+}
@@ -1,23 +1,104 @@
+use crate::services::api_key::api_key_service::ApiKeyService;
 use crate::services::audit::audit_service::AuditService;
+use crate::services::authorization_code::authorization_code_service::AuthorizationCodeService;
+use crate::services::backup::backup_service::BackupService;
+use crate::services::client::client_service::ClientService;
+use crate::services::consent::consent_service::ConsentService;
+use crate::services::credential::credential_service::CredentialService;
+use crate::services::device::device_service::DeviceService;
+use crate::services::email::email_sender::EmailSender;
+use crate::services::email_job::email_job_service::EmailJobService;
+use crate::services::event::invalidation_event::InvalidationEvent;
+use crate::services::event::invalidation_listener::InvalidationListener;
+use crate::services::event::security_event::SecurityEvent;
+use crate::services::event::security_event_sink::SecurityEventSink;
+use crate::services::invitation::invitation_service::InvitationService;
+use crate::services::jwt::claims_enricher::ClaimsEnricher;
 use crate::services::jwt::jwt_service::JwtService;
+use crate::services::login_attempt::login_attempt_service::LoginAttemptService;
+use crate::services::magic_link::magic_link_service::MagicLinkService;
+use crate::services::mfa::totp_service::TotpService;
+use crate::services::mfa::webauthn_service::WebauthnService;
+use crate::services::organization::organization_service::OrganizationService;
+use crate::services::password::password_service::PasswordService;
 use crate::services::permission::permission_service::PermissionService;
+use crate::services::quota::quota_service::QuotaService;
+use crate::services::refresh_token::refresh_token_service::RefreshTokenService;
+use crate::services::revoked_token::revoked_token_service::RevokedTokenService;
 use crate::services::role::role_service::RoleService;
+use crate::services::role_change::role_change_service::RoleChangeService;
+use crate::services::session::session_service::SessionService;
+use crate::services::social::social_login_service::SocialLoginService;
+use crate::services::sso::sso_service::SsoService;
 use crate::services::user::user_service::UserService;
+use chrono::{TimeZone, Utc};
+use log::{error, info, warn};
+use mongodb::Database;
+use std::sync::Arc;
 
+pub mod api_key;
 pub mod audit;
+pub mod authorization_code;
+pub mod backup;
+pub mod client;
+pub mod consent;
+pub mod credential;
+pub mod device;
+pub mod email;
+pub mod email_job;
+pub mod event;
+pub mod export;
+pub mod invitation;
 pub mod jwt;
+pub mod login_attempt;
+pub mod magic_link;
+pub mod mfa;
+pub mod oauth;
+pub mod organization;
 pub mod password;
 pub mod permission;
+pub mod quota;
+pub mod refresh_token;
+pub mod revoked_token;
 pub mod role;
+pub mod role_change;
+pub mod session;
+pub mod social;
+pub mod sso;
 pub mod user;
 
 #[derive(Clone)]
 pub struct Services {
-    pub permission_service: PermissionService,
-    pub role_service: RoleService,
-    pub user_service: UserService,
+    pub permission_service: Arc<dyn PermissionService>,
+    pub role_service: Arc<dyn RoleService>,
+    pub user_service: Arc<dyn UserService>,
     pub jwt_service: JwtService,
-    pub audit_service: AuditService,
+    pub audit_service: Arc<dyn AuditService>,
+    pub password_service: PasswordService,
+    pub quota_service: QuotaService,
+    pub backup_service: Arc<dyn BackupService>,
+    pub device_service: Arc<dyn DeviceService>,
+    pub client_service: Arc<dyn ClientService>,
+    pub consent_service: Arc<dyn ConsentService>,
+    pub organization_service: Arc<dyn OrganizationService>,
+    pub invitation_service: Arc<dyn InvitationService>,
+    pub refresh_token_service: Arc<dyn RefreshTokenService>,
+    pub revoked_token_service: Arc<dyn RevokedTokenService>,
+    pub email_job_service: Arc<dyn EmailJobService>,
+    pub session_service: Arc<dyn SessionService>,
+    pub api_key_service: Arc<dyn ApiKeyService>,
+    pub authorization_code_service: Arc<dyn AuthorizationCodeService>,
+    pub role_change_service: Arc<dyn RoleChangeService>,
+    pub credential_service: Arc<dyn CredentialService>,
+    pub magic_link_service: Arc<dyn MagicLinkService>,
+    pub social_login_service: SocialLoginService,
+    pub sso_service: SsoService,
+    pub login_attempt_service: LoginAttemptService,
+    pub totp_service: TotpService,
+    pub webauthn_service: WebauthnService,
+    pub email_sender: Option<Arc<dyn EmailSender>>,
+    pub invalidation_listeners: Vec<Arc<dyn InvalidationListener>>,
+    pub security_event_sinks: Vec<Arc<dyn SecurityEventSink>>,
 }
 
 impl Services {
@@ -32,16 +113,61 @@ impl Services {
     /// * `user_service` - The UserService.
     /// * `jwt_service` - The JwtService.
     /// * `audit_service` - The AuditService.
+    /// * `password_service` - The PasswordService.
+    /// * `quota_service` - The QuotaService.
+    /// * `backup_service` - The BackupService.
+    /// * `device_service` - The DeviceService.
+    /// * `client_service` - The ClientService.
+    /// * `consent_service` - The ConsentService.
+    /// * `organization_service` - The OrganizationService.
+    /// * `invitation_service` - The InvitationService.
+    /// * `refresh_token_service` - The RefreshTokenService.
+    /// * `revoked_token_service` - The RevokedTokenService.
+    /// * `email_job_service` - The EmailJobService.
+    /// * `session_service` - The SessionService.
+    /// * `api_key_service` - The ApiKeyService.
+    /// * `authorization_code_service` - The AuthorizationCodeService.
+    /// * `role_change_service` - The RoleChangeService.
+    /// * `credential_service` - The CredentialService.
+    /// * `magic_link_service` - The MagicLinkService.
+    /// * `social_login_service` - The SocialLoginService.
+    /// * `sso_service` - The SsoService.
+    /// * `login_attempt_service` - The LoginAttemptService.
+    /// * `totp_service` - The TotpService.
+    /// * `webauthn_service` - The WebauthnService.
     ///
     /// # Returns
     ///
     /// A new instance of Services.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
-        permission_service: PermissionService,
-        role_service: RoleService,
-        user_service: UserService,
+        permission_service: Arc<dyn PermissionService>,
+        role_service: Arc<dyn RoleService>,
+        user_service: Arc<dyn UserService>,
         jwt_service: JwtService,
-        audit_service: AuditService,
+        audit_service: Arc<dyn AuditService>,
+        password_service: PasswordService,
+        quota_service: QuotaService,
+        backup_service: Arc<dyn BackupService>,
+        device_service: Arc<dyn DeviceService>,
+        client_service: Arc<dyn ClientService>,
+        consent_service: Arc<dyn ConsentService>,
+        organization_service: Arc<dyn OrganizationService>,
+        invitation_service: Arc<dyn InvitationService>,
+        refresh_token_service: Arc<dyn RefreshTokenService>,
+        revoked_token_service: Arc<dyn RevokedTokenService>,
+        email_job_service: Arc<dyn EmailJobService>,
+        session_service: Arc<dyn SessionService>,
+        api_key_service: Arc<dyn ApiKeyService>,
+        authorization_code_service: Arc<dyn AuthorizationCodeService>,
+        role_change_service: Arc<dyn RoleChangeService>,
+        credential_service: Arc<dyn CredentialService>,
+        magic_link_service: Arc<dyn MagicLinkService>,
+        social_login_service: SocialLoginService,
+        sso_service: SsoService,
+        login_attempt_service: LoginAttemptService,
+        totp_service: TotpService,
+        webauthn_service: WebauthnService,
     ) -> Services {
         Services {
             permission_service,
@@ -49,6 +175,209 @@ impl Services {
             user_service,
             jwt_service,
             audit_service,
+            password_service,
+            quota_service,
+            backup_service,
+            device_service,
+            client_service,
+            consent_service,
+            organization_service,
+            invitation_service,
+            refresh_token_service,
+            revoked_token_service,
+            email_job_service,
+            session_service,
+            api_key_service,
+            authorization_code_service,
+            role_change_service,
+            credential_service,
+            magic_link_service,
+            social_login_service,
+            sso_service,
+            login_attempt_service,
+            totp_service,
+            webauthn_service,
+            email_sender: None,
+            invalidation_listeners: vec![],
+            security_event_sinks: vec![],
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Register a ClaimsEnricher that adds custom claims to every JWT token that is generated.
+    ///
+    /// # Arguments
+    ///
+    /// * `claims_enricher` - The ClaimsEnricher to register.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// config.services.register_claims_enricher(Arc::new(TenantEnricher));
+    /// ```
+    pub fn register_claims_enricher(&mut self, claims_enricher: Arc<dyn ClaimsEnricher>) {
+        self.jwt_service.register_claims_enricher(claims_enricher);
+    }
+
+    /// # Summary
+    ///
+    /// Turn a freshly generated JWT token into the token that is handed to the client. In session mode, the Claims of
+    /// the JWT token are stored in a Session and an opaque token that refers to the Session is returned instead, which
+    /// expires together with the JWT token. Otherwise the JWT token is returned as it is.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The JWT token.
+    /// * `db` - The Database that the Session is stored in.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let token = pool.services.issue_access_token(jwt, &pool.database).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Option<String>` - The token for the client, or None if the Session could not be created.
+    pub async fn issue_access_token(&self, token: String, db: &Database) -> Option<String> {
+        if !self.jwt_service.jwt_config.is_session_mode() {
+            return Some(token);
+        }
+
+        let claims = match self.jwt_service.decode_jwt_token(&token) {
+            Ok(c) => c,
+            Err(e) => {
+                error!("Failed to decode generated JWT token: {}", e);
+                return None;
+            }
+        };
+
+        let expires_at = Utc.timestamp_opt(claims.expires_at() as i64, 0).single()?;
+
+        match self.session_service.create(claims, expires_at, db).await {
+            Ok(t) => Some(t),
+            Err(e) => {
+                error!("Failed to create Session: {}", e);
+                None
+            }
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Register an EmailSender that is used to send notifications to Users.
+    /// Notifications are skipped when no EmailSender is registered. The EmailSender is ignored if the `email` feature is
+    /// disabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `email_sender` - The EmailSender to register.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// config.services.register_email_sender(Arc::new(SmtpEmailSender::new()));
+    /// ```
+    pub fn register_email_sender(&mut self, email_sender: Arc<dyn EmailSender>) {
+        if !cfg!(feature = "email") {
+            warn!("Ignoring the EmailSender, since the email feature is disabled");
+            return;
+        }
+
+        self.email_sender = Some(email_sender);
+    }
+
+    /// # Summary
+    ///
+    /// Register an InvalidationListener that is notified when cached data may have become stale.
+    ///
+    /// # Arguments
+    ///
+    /// * `invalidation_listener` - The InvalidationListener to register.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// config.services.register_invalidation_listener(Arc::new(CacheInvalidator::new()));
+    /// ```
+    pub fn register_invalidation_listener(
+        &mut self,
+        invalidation_listener: Arc<dyn InvalidationListener>,
+    ) {
+        self.invalidation_listeners.push(invalidation_listener);
+    }
+
+    /// # Summary
+    ///
+    /// Publish an InvalidationEvent to all registered InvalidationListeners. A failing listener is logged and does not
+    /// prevent the other listeners from being notified.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The InvalidationEvent to publish.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// pool.services.publish_invalidation(InvalidationEvent::RoleRenamed {
+    ///     id: role.id.to_hex(),
+    ///     old_name: String::from("EDITOR"),
+    ///     new_name: role.name.clone(),
+    /// }).await;
+    /// ```
+    pub async fn publish_invalidation(&self, event: InvalidationEvent) {
+        info!("Publishing invalidation event: {}", event);
+
+        for l in &self.invalidation_listeners {
+            if let Err(e) = l.on_event(&event).await {
+                error!("Failed to publish invalidation event: {}", e);
+            }
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Register a SecurityEventSink that receives logins, lockouts, role grants and permission denials.
+    ///
+    /// # Arguments
+    ///
+    /// * `security_event_sink` - The SecurityEventSink to register.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// config.services.register_security_event_sink(Arc::new(SyslogSink::new(address, SyslogProtocol::Udp)));
+    /// ```
+    pub fn register_security_event_sink(
+        &mut self,
+        security_event_sink: Arc<dyn SecurityEventSink>,
+    ) {
+        self.security_event_sinks.push(security_event_sink);
+    }
+
+    /// # Summary
+    ///
+    /// Publish a SecurityEvent to all registered SecurityEventSinks. A failing sink is logged and does not prevent the
+    /// other sinks from receiving the event, nor does it fail the request that caused the event.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The SecurityEvent to publish.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// pool.services.publish_security_event(SecurityEvent::AccountLocked {
+    ///     user_id: user.id.to_hex(),
+    ///     actor_id: Some(user_id.to_hex()),
+    /// }).await;
+    /// ```
+    pub async fn publish_security_event(&self, event: SecurityEvent) {
+        for s in &self.security_event_sinks {
+            if let Err(e) = s.on_event(&event).await {
+                error!("Failed to publish security event: {}", e);
+            }
         }
     }
 }
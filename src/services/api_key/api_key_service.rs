@@ -0,0 +1,420 @@
+use crate::repository::api_key::api_key_model::{ApiKey, KEY_PREFIX_LENGTH};
+use crate::repository::api_key::api_key_repository::{ApiKeyRepository, Error};
+use crate::repository::audit::audit_model::Action::{Create, Delete, Update};
+use crate::repository::audit::audit_model::{Action, Audit, ResourceIdType, ResourceType};
+use crate::repository::audit::audit_repository::Error as AuditError;
+use crate::services::audit::audit_service::AuditService;
+use crate::services::client::client_service::{generate_secret, hash_secret};
+use async_trait::async_trait;
+use log::{error, info};
+use mongodb::bson::oid::ObjectId;
+use mongodb::Database;
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct MongoApiKeyService {
+    pub api_key_repository: ApiKeyRepository,
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait ApiKeyService: Send + Sync {
+    /// # Summary
+    ///
+    /// Create a new ApiKey with a randomly generated key.
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - The ApiKey to be created.
+    /// * `user_id` - The ID of the User creating the ApiKey.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let (api_key, key) = api_key_service.create(api_key, Some(user_id), &db, &audit_service).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(ApiKey, String), Error>` - The created ApiKey and its key. The key is not stored and cannot be retrieved afterwards.
+    async fn create(
+        &self,
+        api_key: ApiKey,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(ApiKey, String), Error>;
+
+    /// # Summary
+    ///
+    /// Find all ApiKey entities.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The limit of ApiKey entities to find.
+    /// * `page` - The page of ApiKey entities to find.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let api_keys = api_key_service.find_all(Some(100), Some(1), &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<ApiKey>, Error>` - The ApiKey entities.
+    async fn find_all(
+        &self,
+        limit: Option<i64>,
+        page: Option<i64>,
+        db: &Database,
+    ) -> Result<Vec<ApiKey>, Error>;
+
+    /// # Summary
+    ///
+    /// Find an ApiKey entity by its ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the ApiKey entity.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let api_key = api_key_service.find_by_id("api_key_id", &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<ApiKey>, Error>` - The optional ApiKey entity.
+    async fn find_by_id(&self, id: &str, db: &Database) -> Result<Option<ApiKey>, Error>;
+
+    /// # Summary
+    ///
+    /// Update an ApiKey entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - The ApiKey to be updated.
+    /// * `user_id` - The ID of the User updating the ApiKey.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let api_key = api_key_service.update(api_key, Some(user_id), &db, &audit_service).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ApiKey, Error>` - The updated ApiKey entity.
+    async fn update(
+        &self,
+        api_key: ApiKey,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<ApiKey, Error>;
+
+    /// # Summary
+    ///
+    /// Replace the key of an ApiKey. The previous key stops working immediately.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the ApiKey entity.
+    /// * `user_id` - The ID of the User rotating the key.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let (api_key, key) = api_key_service.rotate_key("api_key_id", Some(user_id), &db, &audit_service).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(ApiKey, String), Error>` - The updated ApiKey and its new key.
+    async fn rotate_key(
+        &self,
+        id: &str,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(ApiKey, String), Error>;
+
+    /// # Summary
+    ///
+    /// Delete an ApiKey entity by its ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the ApiKey entity.
+    /// * `user_id` - The ID of the User deleting the ApiKey.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// api_key_service.delete("api_key_id", Some(user_id), &db, &audit_service).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    async fn delete(
+        &self,
+        id: &str,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(), Error>;
+
+    /// # Summary
+    ///
+    /// Authenticate a request using the key that it carries.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let api_key = api_key_service.authenticate("key", &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ApiKey, Error>` - The ApiKey, or InvalidKey if the key is unknown, disabled or expired.
+    async fn authenticate(&self, key: &str, db: &Database) -> Result<ApiKey, Error>;
+}
+
+impl MongoApiKeyService {
+    /// # Summary
+    ///
+    /// Create a new MongoApiKeyService.
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key_repository` - The ApiKeyRepository.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let api_key_repository = ApiKeyRepository::new(String::from("apiKeys"));
+    /// let api_key_service = MongoApiKeyService::new(api_key_repository);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `MongoApiKeyService` - The new MongoApiKeyService.
+    pub fn new(api_key_repository: ApiKeyRepository) -> MongoApiKeyService {
+        MongoApiKeyService { api_key_repository }
+    }
+
+    /// # Summary
+    ///
+    /// Generate a new key for an ApiKey and store its hash and prefix.
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - The ApiKey.
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The new key.
+    fn assign_key(api_key: &mut ApiKey) -> String {
+        let key = generate_secret();
+        api_key.key_hash = hash_secret(&key);
+        api_key.prefix = key.chars().take(KEY_PREFIX_LENGTH).collect();
+
+        key
+    }
+
+    /// # Summary
+    ///
+    /// Record a change to an ApiKey in the audit trail.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User that made the change, if any.
+    /// * `action` - The Action.
+    /// * `api_key_id` - The ID of the ApiKey.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    async fn audit(
+        user_id: Option<ObjectId>,
+        action: Action,
+        api_key_id: ObjectId,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(), Error> {
+        let user_id = match user_id {
+            Some(u) => u,
+            None => return Ok(()),
+        };
+
+        let new_audit = Audit::new(
+            user_id,
+            action,
+            api_key_id,
+            ResourceIdType::ApiKeyId,
+            ResourceType::ApiKey,
+        );
+        match audit_service.create(new_audit, db).await {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                error!("Failed to create Audit: {}", e);
+                Err(Error::Audit(e))
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ApiKeyService for MongoApiKeyService {
+    /// # Summary
+    ///
+    /// Create a new ApiKey with a randomly generated key.
+    async fn create(
+        &self,
+        mut api_key: ApiKey,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(ApiKey, String), Error> {
+        info!("Creating ApiKey: {}", api_key);
+        Self::audit(user_id, Create, api_key.id, db, audit_service).await?;
+
+        let key = Self::assign_key(&mut api_key);
+
+        let api_key = self.api_key_repository.create(api_key, db).await?;
+        Ok((api_key, key))
+    }
+
+    /// # Summary
+    ///
+    /// Find all ApiKey entities.
+    async fn find_all(
+        &self,
+        limit: Option<i64>,
+        page: Option<i64>,
+        db: &Database,
+    ) -> Result<Vec<ApiKey>, Error> {
+        info!("Finding all API keys");
+        self.api_key_repository.find_all(limit, page, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Find an ApiKey entity by its ID.
+    async fn find_by_id(&self, id: &str, db: &Database) -> Result<Option<ApiKey>, Error> {
+        info!("Finding ApiKey by ID: {}", id);
+        self.api_key_repository.find_by_id(id, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Update an ApiKey entity.
+    async fn update(
+        &self,
+        api_key: ApiKey,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<ApiKey, Error> {
+        info!("Updating ApiKey: {}", api_key);
+        Self::audit(user_id, Update, api_key.id, db, audit_service).await?;
+
+        self.api_key_repository.update(api_key, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Replace the key of an ApiKey.
+    async fn rotate_key(
+        &self,
+        id: &str,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(ApiKey, String), Error> {
+        info!("Rotating key of ApiKey: {}", id);
+
+        let mut api_key = match self.api_key_repository.find_by_id(id, db).await? {
+            Some(a) => a,
+            None => return Err(Error::ApiKeyNotFound(id.to_string())),
+        };
+
+        Self::audit(user_id, Update, api_key.id, db, audit_service).await?;
+
+        let key = Self::assign_key(&mut api_key);
+
+        let api_key = self.api_key_repository.update(api_key, db).await?;
+        Ok((api_key, key))
+    }
+
+    /// # Summary
+    ///
+    /// Delete an ApiKey entity by its ID.
+    async fn delete(
+        &self,
+        id: &str,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(), Error> {
+        info!("Deleting ApiKey by ID: {}", id);
+
+        if user_id.is_some() {
+            let oid = match ObjectId::parse_str(id) {
+                Ok(oid) => oid,
+                Err(e) => {
+                    return Err(Error::Audit(AuditError::ObjectId(e.to_string())));
+                }
+            };
+
+            Self::audit(user_id, Delete, oid, db, audit_service).await?;
+        }
+
+        self.api_key_repository.delete(id, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Authenticate a request using the key that it carries.
+    async fn authenticate(&self, key: &str, db: &Database) -> Result<ApiKey, Error> {
+        if key.is_empty() {
+            return Err(Error::InvalidKey);
+        }
+
+        // Keys are looked up by their hash, so the lookup does not reveal anything about the stored keys
+        let api_key = match self
+            .api_key_repository
+            .find_by_key_hash(&hash_secret(key), db)
+            .await?
+        {
+            Some(a) => a,
+            None => return Err(Error::InvalidKey),
+        };
+
+        if !api_key.enabled || api_key.is_expired() {
+            return Err(Error::InvalidKey);
+        }
+
+        Ok(api_key)
+    }
+}
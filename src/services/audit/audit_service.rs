@@ -1,34 +1,22 @@
-use crate::repository::audit::audit_model::Audit;
+use crate::components::request_context::RequestContext;
+use crate::repository::audit::audit_filter::AuditFilter;
+use crate::repository::audit::audit_model::{Audit, AuditChainVerification};
 use crate::repository::audit::audit_repository::{AuditRepository, Error};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use log::info;
+use mongodb::bson::oid::ObjectId;
 use mongodb::Database;
 
 #[derive(Clone)]
-pub struct AuditService {
+pub struct MongoAuditService {
     pub audit_repository: AuditRepository,
     pub enabled: bool,
 }
 
-impl AuditService {
-    /// # Summary
-    ///
-    /// Create a new AuditService.
-    ///
-    /// # Arguments
-    ///
-    /// * `audit_repository` - The AuditRepository.
-    /// * `enabled` - Whether or not the AuditService is enabled.
-    ///
-    /// # Returns
-    ///
-    /// * `AuditService` - The AuditService.
-    pub fn new(audit_repository: AuditRepository, enabled: bool) -> AuditService {
-        AuditService {
-            audit_repository,
-            enabled,
-        }
-    }
-
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait AuditService: Send + Sync {
     /// # Summary
     ///
     /// Create a new Audit.
@@ -41,14 +29,7 @@ impl AuditService {
     /// # Returns
     ///
     /// * `Result<(), Error>` - The result of the operation.
-    pub async fn create(&self, audit: Audit, db: &Database) -> Result<(), Error> {
-        if !self.enabled {
-            return Ok(());
-        }
-
-        info!("Creating audit: {}", audit);
-        self.audit_repository.create(audit, db).await
-    }
+    async fn create(&self, audit: Audit, db: &Database) -> Result<(), Error>;
 
     /// # Summary
     ///
@@ -62,10 +43,7 @@ impl AuditService {
     /// # Returns
     ///
     /// * `Result<Option<Audit>, Error>` - The result of the operation.
-    pub async fn find_by_id(&self, id: &str, db: &Database) -> Result<Option<Audit>, Error> {
-        info!("Finding audit by ID: {}", id);
-        self.audit_repository.find_by_id(id, db).await
-    }
+    async fn find_by_id(&self, id: &str, db: &Database) -> Result<Option<Audit>, Error>;
 
     /// # Summary
     ///
@@ -80,23 +58,42 @@ impl AuditService {
     /// # Returns
     ///
     /// * `Result<Vec<Audit>, Error>` - The result of the operation.
-    pub async fn find_all(
+    async fn find_all(
         &self,
         limit: Option<i64>,
         page: Option<i64>,
         db: &Database,
-    ) -> Result<Vec<Audit>, Error> {
-        info!("Finding all audits");
-        self.audit_repository.find_all(limit, page, db).await
-    }
+    ) -> Result<Vec<Audit>, Error>;
 
     /// # Summary
     ///
-    /// Search for Audit entities.
+    /// Find the Audits of the actions that were performed by a User, most recent first.
     ///
     /// # Arguments
     ///
-    /// * `text` - The text to search for.
+    /// * `user_id` - The ID of the User that performed the actions.
+    /// * `limit` - The limit of Audits to find.
+    /// * `page` - The page of Audits to find.
+    /// * `db` - The Database to find the Audits in.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Audit>, Error>` - The result of the operation.
+    async fn find_by_user_id(
+        &self,
+        user_id: &str,
+        limit: Option<i64>,
+        page: Option<i64>,
+        db: &Database,
+    ) -> Result<Vec<Audit>, Error>;
+
+    /// # Summary
+    ///
+    /// Search for the Audit entities that match an AuditFilter.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - The AuditFilter that the Audits must match.
     /// * `limit` - The limit of Audits to find.
     /// * `page` - The page of Audits to find.
     /// * `db` - The database to use.
@@ -105,24 +102,234 @@ impl AuditService {
     ///
     /// ```
     /// let audit_repository = AuditRepository::new("audit".to_string()).unwrap();
-    /// let audit_service = AuditService::new(audit_repository, true);
+    /// let audit_service = MongoAuditService::new(audit_repository, true);
     /// let db = mongodb::Client::with_uri_str("mongodb://localhost:27017")
     ///    .unwrap()
     ///    .database("test");
-    /// let res = audit_service.search("test", 1, 10, &db).await;
+    /// let filter = AuditFilter {
+    ///     action: Some(Action::Deny),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let res = audit_service.search(&filter, Some(10), Some(1), &db).await;
     /// ```
     ///
     /// # Returns
     ///
     /// * `Result<Vec<Audit>, Error>` - The result of the operation.
-    pub async fn search(
+    async fn search(
+        &self,
+        filter: &AuditFilter,
+        limit: Option<i64>,
+        page: Option<i64>,
+        db: &Database,
+    ) -> Result<Vec<Audit>, Error>;
+
+    /// # Summary
+    ///
+    /// Verify the hash chains of the audit trail to detect records that were removed or modified.
+    ///
+    /// # Arguments
+    ///
+    /// * `partition` - The partition to verify, or None to verify every partition.
+    /// * `db` - The Database that contains the Audits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let verifications = audit_service.verify_chain(Some(String::from("user")), &db).await;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<AuditChainVerification>, Error>` - The verification of every partition or the Error that occurred.
+    async fn verify_chain(
+        &self,
+        partition: Option<String>,
+        db: &Database,
+    ) -> Result<Vec<AuditChainVerification>, Error>;
+
+    /// # Summary
+    ///
+    /// Find the Audits that were created within a period of time, oldest first.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The start of the period, inclusive.
+    /// * `until` - The end of the period, exclusive.
+    /// * `db` - The Database that contains the Audits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let audits = audit_service.find_created_between(Utc::now() - Duration::days(1), Utc::now(), &db).await;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Audit>, Error>` - The result of the operation.
+    async fn find_created_between(
+        &self,
+        from: DateTime<Utc>,
+        until: DateTime<Utc>,
+        db: &Database,
+    ) -> Result<Vec<Audit>, Error>;
+
+    /// # Summary
+    ///
+    /// Attribute the Audits of a User to another User. Audits that are part of a hash chain are left unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The ID of the User whose Audits are reattributed.
+    /// * `to` - The ID of the User that the Audits are attributed to.
+    /// * `db` - The Database that contains the Audits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let reattributed = audit_service.reattribute(other.id, user.id, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64, Error>` - The number of Audits that were reattributed or the Error that occurred.
+    async fn reattribute(&self, from: ObjectId, to: ObjectId, db: &Database) -> Result<u64, Error>;
+}
+
+impl MongoAuditService {
+    /// # Summary
+    ///
+    /// Create a new MongoAuditService.
+    ///
+    /// # Arguments
+    ///
+    /// * `audit_repository` - The AuditRepository.
+    /// * `enabled` - Whether or not the AuditService is enabled. Audits are never created if the `audit` feature is disabled.
+    ///
+    /// # Returns
+    ///
+    /// * `AuditService` - The AuditService.
+    pub fn new(audit_repository: AuditRepository, enabled: bool) -> MongoAuditService {
+        MongoAuditService {
+            audit_repository,
+            enabled: enabled && cfg!(feature = "audit"),
+        }
+    }
+}
+
+#[async_trait]
+impl AuditService for MongoAuditService {
+    /// # Summary
+    ///
+    /// Create a new Audit.
+    async fn create(&self, mut audit: Audit, db: &Database) -> Result<(), Error> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        // Audits that are created while handling a request record where they originated from
+        if let Some(c) = RequestContext::current() {
+            audit.method.get_or_insert(c.method);
+            audit.route.get_or_insert(c.route);
+            audit.request_id = Some(c.request_id);
+            if audit.reason.is_none() {
+                audit.reason = c.reason;
+            }
+        }
+
+        info!("Creating audit: {}", audit);
+        self.audit_repository.create(audit, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Find an Audit by id.
+    async fn find_by_id(&self, id: &str, db: &Database) -> Result<Option<Audit>, Error> {
+        info!("Finding audit by ID: {}", id);
+        self.audit_repository.find_by_id(id, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Find all Audits.
+    async fn find_all(
+        &self,
+        limit: Option<i64>,
+        page: Option<i64>,
+        db: &Database,
+    ) -> Result<Vec<Audit>, Error> {
+        info!("Finding all audits");
+        self.audit_repository.find_all(limit, page, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Find the Audits of the actions that were performed by a User.
+    async fn find_by_user_id(
         &self,
-        text: &str,
+        user_id: &str,
         limit: Option<i64>,
         page: Option<i64>,
         db: &Database,
     ) -> Result<Vec<Audit>, Error> {
-        info!("Searching for audits: {}", text);
-        self.audit_repository.search(text, limit, page, db).await
+        info!("Finding audits of User: {}", user_id);
+        self.audit_repository
+            .find_by_user_id(user_id, limit, page, db)
+            .await
+    }
+
+    /// # Summary
+    ///
+    /// Search for the Audit entities that match an AuditFilter.
+    async fn search(
+        &self,
+        filter: &AuditFilter,
+        limit: Option<i64>,
+        page: Option<i64>,
+        db: &Database,
+    ) -> Result<Vec<Audit>, Error> {
+        info!("Searching for audits");
+        self.audit_repository.search(filter, limit, page, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Verify the hash chains of the audit trail.
+    async fn verify_chain(
+        &self,
+        partition: Option<String>,
+        db: &Database,
+    ) -> Result<Vec<AuditChainVerification>, Error> {
+        info!(
+            "Verifying audit chain: {}",
+            partition.as_deref().unwrap_or("all partitions")
+        );
+        self.audit_repository
+            .verify_chain(partition.as_deref(), db)
+            .await
+    }
+
+    /// # Summary
+    ///
+    /// Find the Audits that were created within a period of time.
+    async fn find_created_between(
+        &self,
+        from: DateTime<Utc>,
+        until: DateTime<Utc>,
+        db: &Database,
+    ) -> Result<Vec<Audit>, Error> {
+        info!("Finding audits created between {} and {}", from, until);
+        self.audit_repository
+            .find_created_between(from, until, db)
+            .await
+    }
+
+    /// # Summary
+    ///
+    /// Attribute the Audits of a User to another User.
+    async fn reattribute(&self, from: ObjectId, to: ObjectId, db: &Database) -> Result<u64, Error> {
+        info!("Reattributing audits of User {} to User {}", from, to);
+        self.audit_repository.reattribute(from, to, db).await
     }
 }
@@ -0,0 +1,225 @@
+use crate::repository::authorization_code::authorization_code_model::AuthorizationCode;
+use crate::repository::authorization_code::authorization_code_repository::{
+    AuthorizationCodeRepository, Error,
+};
+use crate::repository::client::client_model::{Client, AUTHORIZATION_CODE_GRANT_TYPE};
+use crate::services::client::client_service::{generate_secret, hash_secret};
+use crate::services::oauth::pkce;
+use crate::services::oauth::pkce::CodeChallenge;
+use async_trait::async_trait;
+use log::info;
+use mongodb::bson::oid::ObjectId;
+use mongodb::Database;
+
+/// The amount of seconds after which an authorization code expires. RFC 6749 recommends a maximum of ten minutes,
+/// but codes are exchanged by the client immediately after the redirect.
+const AUTHORIZATION_CODE_EXPIRATION: u64 = 60;
+
+pub struct AuthorizationGrant {
+    pub code: String,
+    pub redirect_uri: String,
+}
+
+#[derive(Clone)]
+pub struct MongoAuthorizationCodeService {
+    pub authorization_code_repository: AuthorizationCodeRepository,
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait AuthorizationCodeService: Send + Sync {
+    /// # Summary
+    ///
+    /// Issue an authorization code to a Client after a User approved its authorization request.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The Client that requested the authorization.
+    /// * `user_id` - The ID of the User that approved the authorization.
+    /// * `redirect_uri` - The requested redirect URI. It can only be omitted if the Client registered exactly one redirect URI.
+    /// * `scopes` - The requested scopes, which must all be registered for the Client.
+    /// * `code_challenge` - The PKCE code challenge of the authorization request, if any.
+    /// * `db` - The Database to store the authorization code in.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let grant = authorization_code_service.create(&client, user_id, Some("https://app.opserva.io/callback"), scopes, None, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<AuthorizationGrant, Error>` - The authorization code and the redirect URI that it must be sent to.
+    async fn create(
+        &self,
+        client: &Client,
+        user_id: ObjectId,
+        redirect_uri: Option<&str>,
+        scopes: Vec<String>,
+        code_challenge: Option<CodeChallenge>,
+        db: &Database,
+    ) -> Result<AuthorizationGrant, Error>;
+
+    /// # Summary
+    ///
+    /// Exchange an authorization code for the AuthorizationCode that describes the approved authorization.
+    /// An authorization code can only be exchanged once.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - The authorization code.
+    /// * `client_id` - The ID of the Client that exchanges the authorization code.
+    /// * `redirect_uri` - The `redirect_uri` parameter of the token request, if any.
+    /// * `code_verifier` - The PKCE code verifier of the token request, if any.
+    /// * `db` - The Database that holds the authorization code.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let authorization_code = authorization_code_service.redeem("code", client.id, request.redirect_uri.as_deref(), request.code_verifier.as_deref(), &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<AuthorizationCode, Error>` - The AuthorizationCode, or InvalidGrant if the code is unknown, expired, used or was issued to another Client.
+    async fn redeem(
+        &self,
+        code: &str,
+        client_id: ObjectId,
+        redirect_uri: Option<&str>,
+        code_verifier: Option<&str>,
+        db: &Database,
+    ) -> Result<AuthorizationCode, Error>;
+}
+
+impl MongoAuthorizationCodeService {
+    /// # Summary
+    ///
+    /// Create a new MongoAuthorizationCodeService.
+    ///
+    /// # Arguments
+    ///
+    /// * `authorization_code_repository` - The AuthorizationCodeRepository.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let authorization_code_service = MongoAuthorizationCodeService::new(authorization_code_repository);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `MongoAuthorizationCodeService` - The new MongoAuthorizationCodeService.
+    pub fn new(
+        authorization_code_repository: AuthorizationCodeRepository,
+    ) -> MongoAuthorizationCodeService {
+        MongoAuthorizationCodeService {
+            authorization_code_repository,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Determine the redirect URI of an authorization request. Redirect URIs are compared exactly, as recommended by
+    /// the OAuth 2.0 Security Best Current Practice.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The Client that requested the authorization.
+    /// * `redirect_uri` - The requested redirect URI, if any.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String, Error>` - The redirect URI, or the Error that describes why it is missing or not registered for the Client.
+    fn resolve_redirect_uri(client: &Client, redirect_uri: Option<&str>) -> Result<String, Error> {
+        match redirect_uri {
+            Some(u) if client.redirect_uris.iter().any(|r| r == u) => Ok(u.to_string()),
+            Some(u) => Err(Error::InvalidRedirectUri(u.to_string())),
+            None => match client.redirect_uris.as_slice() {
+                [u] => Ok(u.clone()),
+                _ => Err(Error::RedirectUriRequired),
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl AuthorizationCodeService for MongoAuthorizationCodeService {
+    /// # Summary
+    ///
+    /// Issue an authorization code to a Client after a User approved its authorization request.
+    async fn create(
+        &self,
+        client: &Client,
+        user_id: ObjectId,
+        redirect_uri: Option<&str>,
+        scopes: Vec<String>,
+        code_challenge: Option<CodeChallenge>,
+        db: &Database,
+    ) -> Result<AuthorizationGrant, Error> {
+        let redirect_uri_requested = redirect_uri.is_some();
+        let redirect_uri = Self::resolve_redirect_uri(client, redirect_uri)?;
+
+        if !client.enabled || !client.allows_grant(AUTHORIZATION_CODE_GRANT_TYPE) {
+            return Err(Error::UnauthorizedClient);
+        }
+
+        if let Some(s) = scopes.iter().find(|s| !client.scopes.contains(s)) {
+            return Err(Error::InvalidScope(s.clone()));
+        }
+
+        let code = generate_secret();
+        let authorization_code = AuthorizationCode::new(
+            hash_secret(&code),
+            client.id,
+            user_id,
+            redirect_uri.clone(),
+            redirect_uri_requested,
+            scopes,
+            code_challenge,
+            AUTHORIZATION_CODE_EXPIRATION,
+        );
+
+        info!("Creating authorization code: {}", authorization_code);
+        self.authorization_code_repository
+            .create(authorization_code, db)
+            .await?;
+
+        Ok(AuthorizationGrant { code, redirect_uri })
+    }
+
+    /// # Summary
+    ///
+    /// Exchange an authorization code for the AuthorizationCode that describes the approved authorization.
+    async fn redeem(
+        &self,
+        code: &str,
+        client_id: ObjectId,
+        redirect_uri: Option<&str>,
+        code_verifier: Option<&str>,
+        db: &Database,
+    ) -> Result<AuthorizationCode, Error> {
+        let authorization_code = match self
+            .authorization_code_repository
+            .take_by_code_hash(&hash_secret(code), db)
+            .await?
+        {
+            Some(a) => a,
+            None => return Err(Error::InvalidGrant),
+        };
+
+        if authorization_code.client_id != client_id || authorization_code.is_expired() {
+            return Err(Error::InvalidGrant);
+        }
+
+        if !authorization_code.matches_redirect_uri(redirect_uri) {
+            return Err(Error::InvalidGrant);
+        }
+
+        pkce::verify(authorization_code.code_challenge.as_ref(), code_verifier)
+            .map_err(Error::Pkce)?;
+
+        info!("Redeemed authorization code: {}", authorization_code);
+        Ok(authorization_code)
+    }
+}
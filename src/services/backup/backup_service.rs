@@ -0,0 +1,299 @@
+use crate::repository::backup::backup_model::{Backup, RestoreSummary, BACKUP_VERSION};
+use crate::repository::backup::backup_repository::{BackupRepository, Error};
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use log::info;
+use mongodb::Database;
+use sha2::{Digest, Sha256};
+
+/// The length of the nonce that is prepended to every encrypted backup.
+const NONCE_LENGTH: usize = 12;
+
+#[derive(Clone)]
+pub struct MongoBackupService {
+    pub backup_repository: BackupRepository,
+    key: Option<[u8; 32]>,
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait BackupService: Send + Sync {
+    /// # Summary
+    ///
+    /// Create an encrypted backup of all Permissions, Roles and Users.
+    ///
+    /// # Arguments
+    ///
+    /// * `include_passwords` - Whether the password hashes of the Users should be part of the backup.
+    /// * `db` - The Database to create the backup of.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String, Error>` - The Base64 encoded, encrypted backup.
+    async fn backup(&self, include_passwords: bool, db: &Database) -> Result<String, Error>;
+
+    /// # Summary
+    ///
+    /// Restore an encrypted backup.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The Base64 encoded, encrypted backup.
+    /// * `db` - The Database to restore the backup into.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<RestoreSummary, Error>` - The amount of entities that were restored.
+    async fn restore(&self, data: &str, db: &Database) -> Result<RestoreSummary, Error>;
+
+    /// # Summary
+    ///
+    /// Create an unencrypted snapshot of all Permissions, Roles and Users, without the password hashes of the Users.
+    /// Unlike backups, snapshots do not require a secret and cannot be restored.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - The Database to create the snapshot of.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let snapshot = backup_service.snapshot(&db).await;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Backup, Error>` - The snapshot or the Error that occurred.
+    async fn snapshot(&self, db: &Database) -> Result<Backup, Error>;
+}
+
+impl MongoBackupService {
+    /// # Summary
+    ///
+    /// Create a new MongoBackupService.
+    ///
+    /// # Arguments
+    ///
+    /// * `backup_repository` - The BackupRepository.
+    /// * `secret` - The secret that is used to derive the encryption key, or None to disable backups.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let backup_service = MongoBackupService::new(backup_repository, Some(String::from("secret")));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `MongoBackupService` - The new MongoBackupService.
+    pub fn new(backup_repository: BackupRepository, secret: Option<String>) -> MongoBackupService {
+        let key = secret.map(|s| Sha256::digest(s.as_bytes()).into());
+
+        MongoBackupService {
+            backup_repository,
+            key,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Get the cipher that is used to encrypt and decrypt backups.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Aes256Gcm, Error>` - The cipher, or BackupDisabled if no secret was configured.
+    fn cipher(&self) -> Result<Aes256Gcm, Error> {
+        match &self.key {
+            Some(k) => Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(k))),
+            None => Err(Error::BackupDisabled),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Encrypt a Backup.
+    ///
+    /// # Arguments
+    ///
+    /// * `backup` - The Backup to be encrypted.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String, Error>` - The Base64 encoded nonce and ciphertext, or the Error that occurred.
+    fn encrypt(&self, backup: &Backup) -> Result<String, Error> {
+        let cipher = self.cipher()?;
+
+        let plain = match serde_json::to_vec(backup) {
+            Ok(d) => d,
+            Err(e) => return Err(Error::InvalidBackup(e.to_string())),
+        };
+
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let encrypted = match cipher.encrypt(&nonce, plain.as_ref()) {
+            Ok(d) => d,
+            Err(_) => return Err(Error::Encryption(String::from("Failed to encrypt backup"))),
+        };
+
+        let mut data = nonce.to_vec();
+        data.extend(encrypted);
+
+        Ok(STANDARD.encode(data))
+    }
+
+    /// # Summary
+    ///
+    /// Decrypt a Backup and check that its format is supported.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The Base64 encoded nonce and ciphertext.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Backup, Error>` - The Backup, or InvalidBackup if it was not encrypted with the same secret.
+    fn decrypt(&self, data: &str) -> Result<Backup, Error> {
+        let cipher = self.cipher()?;
+
+        let data = match STANDARD.decode(data.trim()) {
+            Ok(d) => d,
+            Err(e) => return Err(Error::InvalidBackup(e.to_string())),
+        };
+
+        if data.len() <= NONCE_LENGTH {
+            return Err(Error::InvalidBackup(String::from("Backup is too short")));
+        }
+
+        let (nonce, encrypted) = data.split_at(NONCE_LENGTH);
+        let plain = match cipher.decrypt(Nonce::from_slice(nonce), encrypted) {
+            Ok(d) => d,
+            Err(_) => {
+                return Err(Error::InvalidBackup(String::from(
+                    "Backup could not be decrypted",
+                )))
+            }
+        };
+
+        let backup: Backup = match serde_json::from_slice(&plain) {
+            Ok(d) => d,
+            Err(e) => return Err(Error::InvalidBackup(e.to_string())),
+        };
+
+        if backup.version != BACKUP_VERSION {
+            return Err(Error::UnsupportedVersion(backup.version));
+        }
+
+        Ok(backup)
+    }
+}
+
+#[async_trait]
+impl BackupService for MongoBackupService {
+    /// # Summary
+    ///
+    /// Create an encrypted backup of all Permissions, Roles and Users.
+    async fn backup(&self, include_passwords: bool, db: &Database) -> Result<String, Error> {
+        // The cipher is checked first, so that a disabled backup does not read the whole database
+        self.cipher()?;
+
+        info!(
+            "Creating backup (passwords included: {})",
+            include_passwords
+        );
+        let backup = self.backup_repository.export(include_passwords, db).await?;
+
+        self.encrypt(&backup)
+    }
+
+    /// # Summary
+    ///
+    /// Restore an encrypted backup.
+    async fn restore(&self, data: &str, db: &Database) -> Result<RestoreSummary, Error> {
+        let backup = self.decrypt(data)?;
+
+        info!(
+            "Restoring backup: {} permission(s), {} role(s), {} user(s)",
+            backup.permissions.len(),
+            backup.roles.len(),
+            backup.users.len()
+        );
+        self.backup_repository.import(backup, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Create an unencrypted snapshot of all Permissions, Roles and Users, without password hashes.
+    async fn snapshot(&self, db: &Database) -> Result<Backup, Error> {
+        info!("Creating snapshot");
+        self.backup_repository.export(false, db).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backup_service(secret: Option<&str>) -> MongoBackupService {
+        let backup_repository = BackupRepository::new(
+            String::from("permissions"),
+            String::from("roles"),
+            String::from("users"),
+        )
+        .unwrap();
+
+        MongoBackupService::new(backup_repository, secret.map(String::from))
+    }
+
+    fn encrypted(service: &MongoBackupService) -> String {
+        service
+            .encrypt(&Backup::new(false, vec![], vec![], vec![]))
+            .unwrap()
+    }
+
+    #[test]
+    fn backup_decrypts_with_same_secret() {
+        let service = backup_service(Some("secret"));
+
+        let backup = service.decrypt(&encrypted(&service)).unwrap();
+
+        assert_eq!(backup.version, BACKUP_VERSION);
+        assert!(!backup.includes_passwords);
+    }
+
+    #[test]
+    fn backup_does_not_decrypt_with_other_secret() {
+        let data = encrypted(&backup_service(Some("secret")));
+
+        let res = backup_service(Some("other")).decrypt(&data);
+
+        assert!(matches!(res, Err(Error::InvalidBackup(_))));
+    }
+
+    #[test]
+    fn tampered_backup_does_not_decrypt() {
+        let service = backup_service(Some("secret"));
+        let mut data = STANDARD.decode(encrypted(&service)).unwrap();
+        let last = data.len() - 1;
+        data[last] ^= 1;
+
+        let res = service.decrypt(&STANDARD.encode(data));
+
+        assert!(matches!(res, Err(Error::InvalidBackup(_))));
+    }
+
+    #[test]
+    fn backups_are_disabled_without_secret() {
+        let service = backup_service(None);
+
+        assert!(matches!(
+            service.encrypt(&Backup::new(false, vec![], vec![], vec![])),
+            Err(Error::BackupDisabled)
+        ));
+        assert!(matches!(
+            service.decrypt("data"),
+            Err(Error::BackupDisabled)
+        ));
+    }
+}
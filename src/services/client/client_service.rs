@@ -0,0 +1,491 @@
+use crate::repository::audit::audit_model::Action::{Create, Delete, Update};
+use crate::repository::audit::audit_model::{Action, Audit, ResourceIdType, ResourceType};
+use crate::repository::audit::audit_repository::Error as AuditError;
+use crate::repository::client::client_model::{Client, CLIENT_CREDENTIALS_GRANT_TYPE, GRANT_TYPES};
+use crate::repository::client::client_repository::{ClientRepository, Error};
+use crate::services::audit::audit_service::AuditService;
+use async_trait::async_trait;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use log::{error, info};
+use mongodb::bson::oid::ObjectId;
+use mongodb::Database;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct MongoClientService {
+    pub client_repository: ClientRepository,
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait ClientService: Send + Sync {
+    /// # Summary
+    ///
+    /// Create a new Client with a randomly generated secret.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The Client to be created.
+    /// * `user_id` - The ID of the User creating the Client.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let (client, secret) = client_service.create(client, Some(user_id), &db, &audit_service).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(Client, String), Error>` - The created Client and its secret. The secret is not stored and cannot be retrieved afterwards.
+    async fn create(
+        &self,
+        client: Client,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(Client, String), Error>;
+
+    /// # Summary
+    ///
+    /// Find all Client entities.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The limit of Client entities to find.
+    /// * `page` - The page of Client entities to find.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let clients = client_service.find_all(Some(100), Some(1), &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Client>, Error>` - The Client entities.
+    async fn find_all(
+        &self,
+        limit: Option<i64>,
+        page: Option<i64>,
+        db: &Database,
+    ) -> Result<Vec<Client>, Error>;
+
+    /// # Summary
+    ///
+    /// Find a Client entity by its ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the Client entity.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let client = client_service.find_by_id("client_id", &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<Client>, Error>` - The optional Client entity.
+    async fn find_by_id(&self, id: &str, db: &Database) -> Result<Option<Client>, Error>;
+
+    /// # Summary
+    ///
+    /// Update a Client entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The Client to be updated.
+    /// * `user_id` - The ID of the User updating the Client.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let client = client_service.update(client, Some(user_id), &db, &audit_service).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Client, Error>` - The updated Client entity.
+    async fn update(
+        &self,
+        client: Client,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<Client, Error>;
+
+    /// # Summary
+    ///
+    /// Replace the secret of a Client and revoke all tokens that were issued to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the Client entity.
+    /// * `user_id` - The ID of the User rotating the secret.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let (client, secret) = client_service.rotate_secret("client_id", Some(user_id), &db, &audit_service).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(Client, String), Error>` - The updated Client and its new secret.
+    async fn rotate_secret(
+        &self,
+        id: &str,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(Client, String), Error>;
+
+    /// # Summary
+    ///
+    /// Delete a Client entity by its ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the Client entity.
+    /// * `user_id` - The ID of the User deleting the Client.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// client_service.delete("client_id", Some(user_id), &db, &audit_service).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    async fn delete(
+        &self,
+        id: &str,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(), Error>;
+
+    /// # Summary
+    ///
+    /// Authenticate a Client using its ID and secret.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the Client.
+    /// * `secret` - The secret of the Client.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let client = client_service.authenticate("client_id", "client_secret", &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Client, Error>` - The Client, or InvalidCredentials if the Client does not exist, is disabled or the secret does not match.
+    async fn authenticate(&self, id: &str, secret: &str, db: &Database) -> Result<Client, Error>;
+}
+
+impl MongoClientService {
+    /// # Summary
+    ///
+    /// Create a new MongoClientService.
+    ///
+    /// # Arguments
+    ///
+    /// * `client_repository` - The ClientRepository.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let client_repository = ClientRepository::new(String::from("clients"));
+    /// let client_service = MongoClientService::new(client_repository);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `MongoClientService` - The new MongoClientService.
+    pub fn new(client_repository: ClientRepository) -> MongoClientService {
+        MongoClientService { client_repository }
+    }
+
+    /// # Summary
+    ///
+    /// Validate the grant types, redirect URIs and scopes of a Client.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The Client to validate.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The Error that describes the first invalid value.
+    fn validate(client: &Client) -> Result<(), Error> {
+        if let Some(g) = client
+            .grant_types
+            .iter()
+            .find(|g| !GRANT_TYPES.contains(&g.as_str()))
+        {
+            return Err(Error::InvalidGrantType(g.clone()));
+        }
+
+        if client.public && client.allows_grant(CLIENT_CREDENTIALS_GRANT_TYPE) {
+            return Err(Error::PublicClientCredentials);
+        }
+
+        // Redirect URIs must be absolute and cannot contain a fragment, as required by RFC 6749
+        if let Some(u) = client.redirect_uris.iter().find(|u| {
+            !u.contains(':')
+                || u.starts_with(':')
+                || u.contains('#')
+                || u.chars().any(|c| c.is_whitespace())
+        }) {
+            return Err(Error::InvalidRedirectUri(u.clone()));
+        }
+
+        if let Some(s) = client.scopes.iter().find(|s| {
+            s.is_empty()
+                || s.chars()
+                    .any(|c| c.is_whitespace() || c == '"' || c == '\\')
+        }) {
+            return Err(Error::InvalidScope(s.clone()));
+        }
+
+        Ok(())
+    }
+
+    /// # Summary
+    ///
+    /// Record a change to a Client in the audit trail.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User that made the change, if any.
+    /// * `action` - The Action.
+    /// * `client_id` - The ID of the Client.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    async fn audit(
+        user_id: Option<ObjectId>,
+        action: Action,
+        client_id: ObjectId,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(), Error> {
+        let user_id = match user_id {
+            Some(u) => u,
+            None => return Ok(()),
+        };
+
+        let new_audit = Audit::new(
+            user_id,
+            action,
+            client_id,
+            ResourceIdType::ClientId,
+            ResourceType::Client,
+        );
+        match audit_service.create(new_audit, db).await {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                error!("Failed to create Audit: {}", e);
+                Err(Error::Audit(e))
+            }
+        }
+    }
+}
+
+/// # Summary
+///
+/// Generate a random client secret.
+///
+/// # Returns
+///
+/// * `String` - The Base64URL encoded client secret.
+pub fn generate_secret() -> String {
+    let mut secret = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut secret);
+
+    URL_SAFE_NO_PAD.encode(secret)
+}
+
+/// # Summary
+///
+/// Hash a client secret. Client secrets are random and long, so a fast hash is sufficient.
+///
+/// # Arguments
+///
+/// * `secret` - The client secret.
+///
+/// # Returns
+///
+/// * `String` - The hexadecimal SHA-256 hash of the client secret.
+pub fn hash_secret(secret: &str) -> String {
+    Sha256::digest(secret.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[async_trait]
+impl ClientService for MongoClientService {
+    /// # Summary
+    ///
+    /// Create a new Client with a randomly generated secret.
+    async fn create(
+        &self,
+        mut client: Client,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(Client, String), Error> {
+        info!("Creating Client: {}", client);
+        Self::validate(&client)?;
+        Self::audit(user_id, Create, client.id, db, audit_service).await?;
+
+        let secret = generate_secret();
+        client.secret_hash = hash_secret(&secret);
+
+        let client = self.client_repository.create(client, db).await?;
+        Ok((client, secret))
+    }
+
+    /// # Summary
+    ///
+    /// Find all Client entities.
+    async fn find_all(
+        &self,
+        limit: Option<i64>,
+        page: Option<i64>,
+        db: &Database,
+    ) -> Result<Vec<Client>, Error> {
+        info!("Finding all clients");
+        self.client_repository.find_all(limit, page, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Find a Client entity by its ID.
+    async fn find_by_id(&self, id: &str, db: &Database) -> Result<Option<Client>, Error> {
+        info!("Finding Client by ID: {}", id);
+        self.client_repository.find_by_id(id, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Update a Client entity.
+    async fn update(
+        &self,
+        client: Client,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<Client, Error> {
+        info!("Updating Client: {}", client);
+        Self::validate(&client)?;
+        Self::audit(user_id, Update, client.id, db, audit_service).await?;
+
+        self.client_repository.update(client, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Replace the secret of a Client and revoke all tokens that were issued to it.
+    async fn rotate_secret(
+        &self,
+        id: &str,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(Client, String), Error> {
+        info!("Rotating secret of Client: {}", id);
+
+        let mut client = match self.client_repository.find_by_id(id, db).await? {
+            Some(c) => c,
+            None => return Err(Error::ClientNotFound(id.to_string())),
+        };
+
+        Self::audit(user_id, Update, client.id, db, audit_service).await?;
+
+        let secret = generate_secret();
+        client.secret_hash = hash_secret(&secret);
+        client.token_version += 1;
+
+        let client = self.client_repository.update(client, db).await?;
+        Ok((client, secret))
+    }
+
+    /// # Summary
+    ///
+    /// Delete a Client entity by its ID.
+    async fn delete(
+        &self,
+        id: &str,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(), Error> {
+        info!("Deleting Client by ID: {}", id);
+
+        if user_id.is_some() {
+            let oid = match ObjectId::parse_str(id) {
+                Ok(oid) => oid,
+                Err(e) => {
+                    return Err(Error::Audit(AuditError::ObjectId(e.to_string())));
+                }
+            };
+
+            Self::audit(user_id, Delete, oid, db, audit_service).await?;
+        }
+
+        self.client_repository.delete(id, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Authenticate a Client using its ID and secret.
+    async fn authenticate(&self, id: &str, secret: &str, db: &Database) -> Result<Client, Error> {
+        let client = match self.client_repository.find_by_id(id, db).await {
+            Ok(Some(c)) => c,
+            Ok(None) | Err(Error::EmptyId) | Err(Error::InvalidId(_)) => {
+                return Err(Error::InvalidCredentials)
+            }
+            Err(e) => return Err(e),
+        };
+
+        let hash = hash_secret(secret);
+
+        // Every byte is compared, so the comparison does not reveal how much of the hash matched
+        let matches = hash.len() == client.secret_hash.len()
+            && hash
+                .bytes()
+                .zip(client.secret_hash.bytes())
+                .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+                == 0;
+
+        if !client.enabled || !matches {
+            return Err(Error::InvalidCredentials);
+        }
+
+        Ok(client)
+    }
+}
@@ -0,0 +1,358 @@
+use crate::repository::audit::audit_model::Action::{Delete, Update};
+use crate::repository::audit::audit_model::{Action, Audit, ResourceIdType, ResourceType};
+use crate::repository::audit::audit_repository::Error as AuditError;
+use crate::repository::client::client_model::Client;
+use crate::repository::consent::consent_model::Consent;
+use crate::repository::consent::consent_repository::{ConsentRepository, Error};
+use crate::services::audit::audit_service::AuditService;
+use async_trait::async_trait;
+use log::{error, info};
+use mongodb::bson::oid::ObjectId;
+use mongodb::Database;
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct MongoConsentService {
+    pub consent_repository: ConsentRepository,
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait ConsentService: Send + Sync {
+    /// # Summary
+    ///
+    /// Record that a User consented to scopes of a Client.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User.
+    /// * `client` - The Client that requested the scopes.
+    /// * `scopes` - The scopes that the User consented to.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let consent = consent_service.grant(user_id, &client, scopes, &db, &audit_service).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Consent, Error>` - The Consent, including the scopes that were approved earlier.
+    async fn grant(
+        &self,
+        user_id: ObjectId,
+        client: &Client,
+        scopes: Vec<String>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<Consent, Error>;
+
+    /// # Summary
+    ///
+    /// Find all Consent entities of a User.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let consents = consent_service.find_by_user_id(user_id, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Consent>, Error>` - The Consent entities.
+    async fn find_by_user_id(
+        &self,
+        user_id: ObjectId,
+        db: &Database,
+    ) -> Result<Vec<Consent>, Error>;
+
+    /// # Summary
+    ///
+    /// Find the requested scopes that a User has not consented to yet. The consent screen can be skipped if none
+    /// are returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User.
+    /// * `client_id` - The ID of the Client.
+    /// * `scopes` - The requested scopes.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let missing = consent_service.missing_scopes(user_id, client_id, &scopes, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<String>, Error>` - The scopes that still require consent.
+    async fn missing_scopes(
+        &self,
+        user_id: ObjectId,
+        client_id: ObjectId,
+        scopes: &[String],
+        db: &Database,
+    ) -> Result<Vec<String>, Error>;
+
+    /// # Summary
+    ///
+    /// Revoke a Consent of a User.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the Consent.
+    /// * `user_id` - The ID of the User that owns the Consent.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// consent_service.revoke("consent_id", user_id, &db, &audit_service).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    async fn revoke(
+        &self,
+        id: &str,
+        user_id: ObjectId,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(), Error>;
+
+    /// # Summary
+    ///
+    /// Delete all Consent entities of a Client.
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - The ID of the Client.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// consent_service.delete_by_client_id(client_id, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    async fn delete_by_client_id(&self, client_id: ObjectId, db: &Database) -> Result<(), Error>;
+
+    /// # Summary
+    ///
+    /// Delete all Consent entities of a User.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// consent_service.delete_by_user_id(user_id, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    async fn delete_by_user_id(&self, user_id: ObjectId, db: &Database) -> Result<(), Error>;
+}
+
+impl MongoConsentService {
+    /// # Summary
+    ///
+    /// Create a new MongoConsentService.
+    ///
+    /// # Arguments
+    ///
+    /// * `consent_repository` - The ConsentRepository.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let consent_repository = ConsentRepository::new(String::from("consents"));
+    /// let consent_service = MongoConsentService::new(consent_repository);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `MongoConsentService` - The new MongoConsentService.
+    pub fn new(consent_repository: ConsentRepository) -> MongoConsentService {
+        MongoConsentService { consent_repository }
+    }
+
+    /// # Summary
+    ///
+    /// Record a change to a Consent in the audit trail.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User that made the change.
+    /// * `action` - The Action.
+    /// * `consent_id` - The ID of the Consent.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    async fn audit(
+        user_id: ObjectId,
+        action: Action,
+        consent_id: ObjectId,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(), Error> {
+        let new_audit = Audit::new(
+            user_id,
+            action,
+            consent_id,
+            ResourceIdType::ConsentId,
+            ResourceType::Consent,
+        );
+        match audit_service.create(new_audit, db).await {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                error!("Failed to create Audit: {}", e);
+                Err(Error::Audit(e))
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ConsentService for MongoConsentService {
+    /// # Summary
+    ///
+    /// Record that a User consented to scopes of a Client.
+    async fn grant(
+        &self,
+        user_id: ObjectId,
+        client: &Client,
+        scopes: Vec<String>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<Consent, Error> {
+        info!(
+            "Granting consent of User {} for Client {}: {:?}",
+            user_id.to_hex(),
+            client.id.to_hex(),
+            scopes
+        );
+
+        if scopes.is_empty() {
+            return Err(Error::EmptyScopes);
+        }
+
+        if !client.enabled {
+            return Err(Error::ClientNotFound(client.id.to_hex()));
+        }
+
+        // Users can only consent to scopes that were registered for the Client
+        if let Some(s) = scopes.iter().find(|s| !client.scopes.contains(s)) {
+            return Err(Error::InvalidScope(s.clone()));
+        }
+
+        let consent = self
+            .consent_repository
+            .save(user_id, client.id, scopes, db)
+            .await?;
+
+        Self::audit(user_id, Update, consent.id, db, audit_service).await?;
+
+        Ok(consent)
+    }
+
+    /// # Summary
+    ///
+    /// Find all Consent entities of a User.
+    async fn find_by_user_id(
+        &self,
+        user_id: ObjectId,
+        db: &Database,
+    ) -> Result<Vec<Consent>, Error> {
+        info!("Finding Consents of User: {}", user_id.to_hex());
+        self.consent_repository.find_by_user_id(user_id, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Find the requested scopes that a User has not consented to yet.
+    async fn missing_scopes(
+        &self,
+        user_id: ObjectId,
+        client_id: ObjectId,
+        scopes: &[String],
+        db: &Database,
+    ) -> Result<Vec<String>, Error> {
+        let consent = self
+            .consent_repository
+            .find_by_user_and_client(user_id, client_id, db)
+            .await?;
+
+        Ok(match consent {
+            Some(c) => scopes
+                .iter()
+                .filter(|s| !c.scopes.contains(s))
+                .cloned()
+                .collect(),
+            None => scopes.to_vec(),
+        })
+    }
+
+    /// # Summary
+    ///
+    /// Revoke a Consent of a User.
+    async fn revoke(
+        &self,
+        id: &str,
+        user_id: ObjectId,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(), Error> {
+        info!("Revoking Consent {} of User {}", id, user_id.to_hex());
+
+        // The ID is validated by the repository before the Consent is deleted
+        self.consent_repository.delete(id, user_id, db).await?;
+
+        let oid = match ObjectId::parse_str(id) {
+            Ok(oid) => oid,
+            Err(e) => {
+                return Err(Error::Audit(AuditError::ObjectId(e.to_string())));
+            }
+        };
+
+        Self::audit(user_id, Delete, oid, db, audit_service).await
+    }
+
+    /// # Summary
+    ///
+    /// Delete all Consent entities of a Client.
+    async fn delete_by_client_id(&self, client_id: ObjectId, db: &Database) -> Result<(), Error> {
+        info!("Deleting Consents of Client: {}", client_id.to_hex());
+        self.consent_repository
+            .delete_by_client_id(client_id, db)
+            .await
+    }
+
+    /// # Summary
+    ///
+    /// Delete all Consent entities of a User.
+    async fn delete_by_user_id(&self, user_id: ObjectId, db: &Database) -> Result<(), Error> {
+        info!("Deleting Consents of User: {}", user_id.to_hex());
+        self.consent_repository.delete_by_user_id(user_id, db).await
+    }
+}
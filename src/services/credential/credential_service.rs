@@ -0,0 +1,305 @@
+use crate::repository::credential::credential_ceremony::{CeremonyState, CredentialCeremony};
+use crate::repository::credential::credential_model::Credential;
+use crate::repository::credential::credential_repository::{CredentialRepository, Error};
+use crate::services::mfa::webauthn_service::Passkey;
+use async_trait::async_trait;
+use log::info;
+use mongodb::bson::oid::ObjectId;
+use mongodb::Database;
+
+#[derive(Clone)]
+pub struct MongoCredentialService {
+    pub credential_repository: CredentialRepository,
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait CredentialService: Send + Sync {
+    /// # Summary
+    ///
+    /// Store the passkey that a User registered.
+    ///
+    /// # Arguments
+    ///
+    /// * `credential` - The Credential to be created.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let credential = credential_service.create(Credential::new(user.id, name, passkey), &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Credential, Error>` - The created Credential.
+    async fn create(&self, credential: Credential, db: &Database) -> Result<Credential, Error>;
+
+    /// # Summary
+    ///
+    /// Find all Credentials of a User.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let credentials = credential_service.find_by_user_id(user.id, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Credential>, Error>` - The Credentials of the User, oldest first.
+    async fn find_by_user_id(
+        &self,
+        user_id: ObjectId,
+        db: &Database,
+    ) -> Result<Vec<Credential>, Error>;
+
+    /// # Summary
+    ///
+    /// Store the passkey of a Credential after it was used to log in.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the Credential.
+    /// * `passkey` - The updated passkey.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// credential_service.update_passkey(credential.id, &credential.passkey, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    async fn update_passkey(
+        &self,
+        id: ObjectId,
+        passkey: &Passkey,
+        db: &Database,
+    ) -> Result<(), Error>;
+
+    /// # Summary
+    ///
+    /// Delete a Credential of a User.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the Credential.
+    /// * `user_id` - The ID of the User that owns the Credential.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// credential_service.delete("id", user.id, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    async fn delete(&self, id: &str, user_id: ObjectId, db: &Database) -> Result<(), Error>;
+
+    /// # Summary
+    ///
+    /// Move all Credentials of a User to another User, for example when the Users are merged.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User that owns the Credentials.
+    /// * `new_user_id` - The ID of the User that will own the Credentials.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// credential_service.reassign(other.id, user.id, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    async fn reassign(
+        &self,
+        user_id: ObjectId,
+        new_user_id: ObjectId,
+        db: &Database,
+    ) -> Result<(), Error>;
+
+    /// # Summary
+    ///
+    /// Start a WebAuthn ceremony by storing its state until the authenticator responds.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User that performs the ceremony.
+    /// * `state` - The state of the ceremony.
+    /// * `expires_in` - The number of seconds after which the ceremony expires.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let ceremony = credential_service.start_ceremony(user.id, CeremonyState::Authentication { state }, 300, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ObjectId, Error>` - The ID of the ceremony, which the client sends back along with the response of the authenticator.
+    async fn start_ceremony(
+        &self,
+        user_id: ObjectId,
+        state: CeremonyState,
+        expires_in: u64,
+        db: &Database,
+    ) -> Result<ObjectId, Error>;
+
+    /// # Summary
+    ///
+    /// Finish a WebAuthn ceremony. A ceremony can only be finished once.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the ceremony.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let ceremony = credential_service.take_ceremony("id", &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<CredentialCeremony>, Error>` - The ceremony, or None if it does not exist, has expired or was already finished.
+    async fn take_ceremony(
+        &self,
+        id: &str,
+        db: &Database,
+    ) -> Result<Option<CredentialCeremony>, Error>;
+}
+
+impl MongoCredentialService {
+    /// # Summary
+    ///
+    /// Create a new MongoCredentialService.
+    ///
+    /// # Arguments
+    ///
+    /// * `credential_repository` - The CredentialRepository to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let credential_service = MongoCredentialService::new(credential_repository);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `MongoCredentialService` - The new MongoCredentialService.
+    pub fn new(credential_repository: CredentialRepository) -> MongoCredentialService {
+        MongoCredentialService {
+            credential_repository,
+        }
+    }
+}
+
+#[async_trait]
+impl CredentialService for MongoCredentialService {
+    /// # Summary
+    ///
+    /// Store the passkey that a User registered.
+    async fn create(&self, credential: Credential, db: &Database) -> Result<Credential, Error> {
+        info!("Creating Credential: {}", credential);
+        self.credential_repository.create(credential, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Find all Credentials of a User.
+    async fn find_by_user_id(
+        &self,
+        user_id: ObjectId,
+        db: &Database,
+    ) -> Result<Vec<Credential>, Error> {
+        self.credential_repository
+            .find_by_user_id(user_id, db)
+            .await
+    }
+
+    /// # Summary
+    ///
+    /// Store the passkey of a Credential after it was used to log in.
+    async fn update_passkey(
+        &self,
+        id: ObjectId,
+        passkey: &Passkey,
+        db: &Database,
+    ) -> Result<(), Error> {
+        self.credential_repository
+            .update_passkey(id, passkey, db)
+            .await
+    }
+
+    /// # Summary
+    ///
+    /// Delete a Credential of a User.
+    async fn delete(&self, id: &str, user_id: ObjectId, db: &Database) -> Result<(), Error> {
+        info!("Deleting Credential: {}", id);
+        self.credential_repository.delete(id, user_id, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Move all Credentials of a User to another User.
+    async fn reassign(
+        &self,
+        user_id: ObjectId,
+        new_user_id: ObjectId,
+        db: &Database,
+    ) -> Result<(), Error> {
+        info!(
+            "Reassigning Credentials of User {} to User: {}",
+            user_id.to_hex(),
+            new_user_id.to_hex()
+        );
+        self.credential_repository
+            .reassign(user_id, new_user_id, db)
+            .await
+    }
+
+    /// # Summary
+    ///
+    /// Start a WebAuthn ceremony.
+    async fn start_ceremony(
+        &self,
+        user_id: ObjectId,
+        state: CeremonyState,
+        expires_in: u64,
+        db: &Database,
+    ) -> Result<ObjectId, Error> {
+        let ceremony = CredentialCeremony::new(user_id, state, expires_in);
+
+        self.credential_repository
+            .create_ceremony(&ceremony, db)
+            .await?;
+        Ok(ceremony.id)
+    }
+
+    /// # Summary
+    ///
+    /// Finish a WebAuthn ceremony.
+    async fn take_ceremony(
+        &self,
+        id: &str,
+        db: &Database,
+    ) -> Result<Option<CredentialCeremony>, Error> {
+        self.credential_repository.take_ceremony(id, db).await
+    }
+}
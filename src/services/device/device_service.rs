@@ -0,0 +1,347 @@
+use crate::configuration::device_config::DeviceConfig;
+use crate::repository::device::device_model::{DeviceAuthorization, DeviceStatus};
+use crate::repository::device::device_repository::{DeviceRepository, Error};
+use async_trait::async_trait;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use log::info;
+use mongodb::bson::oid::ObjectId;
+use mongodb::Database;
+use rand::{Rng, RngCore};
+use sha2::{Digest, Sha256};
+
+/// The characters that are used in user codes. Vowels and ambiguous characters are omitted.
+const USER_CODE_CHARSET: &[u8] = b"BCDFGHJKLMNPQRSTVWXZ";
+
+/// The amount of characters in a user code, excluding the separator.
+const USER_CODE_LENGTH: usize = 8;
+
+pub struct DeviceCode {
+    pub device_code: String,
+    pub user_code: String,
+    pub expires_in: u64,
+    pub interval: u64,
+}
+
+#[derive(Clone)]
+pub struct MongoDeviceService {
+    pub device_repository: DeviceRepository,
+    pub device_config: DeviceConfig,
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait DeviceService: Send + Sync {
+    /// # Summary
+    ///
+    /// Start a new device authorization request.
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - The ID of the client that requests the authorization.
+    /// * `db` - The Database to store the authorization request in.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<DeviceCode, Error>` - The device code and user code of the authorization request.
+    async fn create(&self, client_id: &str, db: &Database) -> Result<DeviceCode, Error>;
+
+    /// # Summary
+    ///
+    /// Find a pending device authorization request by its user code.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_code` - The user code.
+    /// * `db` - The Database to find the authorization request in.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<DeviceAuthorization, Error>` - The pending DeviceAuthorization.
+    async fn find_by_user_code(
+        &self,
+        user_code: &str,
+        db: &Database,
+    ) -> Result<DeviceAuthorization, Error>;
+
+    /// # Summary
+    ///
+    /// Approve or deny a pending device authorization request.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_code` - The user code of the authorization request.
+    /// * `approve` - Whether the authorization request is approved.
+    /// * `user_id` - The ID of the User that approves or denies the authorization request.
+    /// * `db` - The Database that holds the authorization request.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    async fn verify(
+        &self,
+        user_code: &str,
+        approve: bool,
+        user_id: ObjectId,
+        db: &Database,
+    ) -> Result<(), Error>;
+
+    /// # Summary
+    ///
+    /// Exchange a device code for the ID of the User that approved it.
+    /// An approved device code can only be exchanged once.
+    ///
+    /// # Arguments
+    ///
+    /// * `device_code` - The device code.
+    /// * `client_id` - The ID of the client that requested the authorization.
+    /// * `db` - The Database that holds the authorization request.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ObjectId, Error>` - The ID of the User that approved the authorization request.
+    async fn poll(
+        &self,
+        device_code: &str,
+        client_id: &str,
+        db: &Database,
+    ) -> Result<ObjectId, Error>;
+}
+
+impl MongoDeviceService {
+    /// # Summary
+    ///
+    /// Create a new MongoDeviceService.
+    ///
+    /// # Arguments
+    ///
+    /// * `device_repository` - The DeviceRepository.
+    /// * `device_config` - The DeviceConfig.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let device_service = MongoDeviceService::new(device_repository, device_config);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `MongoDeviceService` - The new MongoDeviceService.
+    pub fn new(
+        device_repository: DeviceRepository,
+        device_config: DeviceConfig,
+    ) -> MongoDeviceService {
+        MongoDeviceService {
+            device_repository,
+            device_config,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Generate a random device code and user code.
+    ///
+    /// # Returns
+    ///
+    /// * `(String, String)` - The device code and the user code.
+    fn generate_codes() -> (String, String) {
+        let mut rng = rand::thread_rng();
+
+        let mut device_code = [0u8; 32];
+        rng.fill_bytes(&mut device_code);
+
+        let user_code: String = (0..USER_CODE_LENGTH)
+            .map(|_| USER_CODE_CHARSET[rng.gen_range(0..USER_CODE_CHARSET.len())] as char)
+            .collect();
+
+        (
+            URL_SAFE_NO_PAD.encode(device_code),
+            format_user_code(&user_code),
+        )
+    }
+}
+
+/// # Summary
+///
+/// Normalize a user code that was entered by a user to the `XXXX-XXXX` format.
+///
+/// # Arguments
+///
+/// * `user_code` - The user code.
+///
+/// # Returns
+///
+/// * `String` - The normalized user code.
+pub fn format_user_code(user_code: &str) -> String {
+    let code: String = user_code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+
+    if code.len() != USER_CODE_LENGTH {
+        return code;
+    }
+
+    let (first, second) = code.split_at(USER_CODE_LENGTH / 2);
+    format!("{}-{}", first, second)
+}
+
+/// # Summary
+///
+/// Hash a device code, so that device codes are never stored in plain text.
+///
+/// # Arguments
+///
+/// * `device_code` - The device code.
+///
+/// # Returns
+///
+/// * `String` - The hexadecimal SHA-256 hash of the device code.
+fn hash_device_code(device_code: &str) -> String {
+    Sha256::digest(device_code.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[async_trait]
+impl DeviceService for MongoDeviceService {
+    /// # Summary
+    ///
+    /// Start a new device authorization request.
+    async fn create(&self, client_id: &str, db: &Database) -> Result<DeviceCode, Error> {
+        if client_id.is_empty() {
+            return Err(Error::EmptyClientId);
+        }
+
+        let (device_code, user_code) = Self::generate_codes();
+        let device = DeviceAuthorization::new(
+            hash_device_code(&device_code),
+            user_code.clone(),
+            client_id.to_string(),
+            self.device_config.expiration,
+        );
+
+        info!("Creating device authorization: {}", device);
+        self.device_repository.create(device, db).await?;
+
+        Ok(DeviceCode {
+            device_code,
+            user_code,
+            expires_in: self.device_config.expiration,
+            interval: self.device_config.interval,
+        })
+    }
+
+    /// # Summary
+    ///
+    /// Find a pending device authorization request by its user code.
+    async fn find_by_user_code(
+        &self,
+        user_code: &str,
+        db: &Database,
+    ) -> Result<DeviceAuthorization, Error> {
+        let user_code = format_user_code(user_code);
+
+        match self
+            .device_repository
+            .find_by_user_code(&user_code, db)
+            .await?
+        {
+            Some(d) if d.status == DeviceStatus::Pending && !d.is_expired() => Ok(d),
+            _ => Err(Error::UserCodeNotFound(user_code)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Approve or deny a pending device authorization request.
+    async fn verify(
+        &self,
+        user_code: &str,
+        approve: bool,
+        user_id: ObjectId,
+        db: &Database,
+    ) -> Result<(), Error> {
+        let device = self.find_by_user_code(user_code, db).await?;
+
+        let status = if approve {
+            DeviceStatus::Approved
+        } else {
+            DeviceStatus::Denied
+        };
+
+        info!(
+            "User {} changed the status of {} to {}",
+            user_id.to_hex(),
+            device,
+            status
+        );
+        if !self
+            .device_repository
+            .update_status(device.id, status, user_id, db)
+            .await?
+        {
+            return Err(Error::UserCodeNotFound(device.user_code));
+        }
+
+        Ok(())
+    }
+
+    /// # Summary
+    ///
+    /// Exchange a device code for the ID of the User that approved it.
+    async fn poll(
+        &self,
+        device_code: &str,
+        client_id: &str,
+        db: &Database,
+    ) -> Result<ObjectId, Error> {
+        let device = match self
+            .device_repository
+            .find_by_device_code_hash(&hash_device_code(device_code), db)
+            .await?
+        {
+            Some(d) => d,
+            None => return Err(Error::InvalidGrant),
+        };
+
+        if device.client_id != client_id {
+            return Err(Error::InvalidClient);
+        }
+
+        if device.is_expired() {
+            self.device_repository.delete(device.id, db).await?;
+            return Err(Error::ExpiredToken);
+        }
+
+        let now = chrono::Utc::now();
+        let elapsed = now - device.last_polled_at;
+        self.device_repository
+            .update_last_polled(device.id, now, db)
+            .await?;
+
+        match device.status {
+            DeviceStatus::Pending => {
+                if elapsed < chrono::Duration::seconds(self.device_config.interval as i64) {
+                    return Err(Error::SlowDown);
+                }
+
+                Err(Error::AuthorizationPending)
+            }
+            DeviceStatus::Denied => {
+                self.device_repository.delete(device.id, db).await?;
+                Err(Error::AccessDenied)
+            }
+            DeviceStatus::Approved => {
+                // The device code is deleted before it is exchanged, so it cannot be used twice
+                if !self.device_repository.delete(device.id, db).await? {
+                    return Err(Error::InvalidGrant);
+                }
+
+                device.user_id.ok_or(Error::InvalidGrant)
+            }
+        }
+    }
+}
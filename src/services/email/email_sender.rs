@@ -0,0 +1,49 @@
+use futures::future::BoxFuture;
+
+pub trait EmailSender: Send + Sync {
+    /// # Summary
+    ///
+    /// Send a plain text email.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - The email address of the recipient.
+    /// * `subject` - The subject of the email.
+    /// * `body` - The plain text body of the email.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// email_sender.send("user@opserva.io", "Welcome", "Your account was approved").await;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `BoxFuture<Result<(), String>>` - Nothing, or the error that occurred.
+    fn send<'a>(
+        &'a self,
+        to: &'a str,
+        subject: &'a str,
+        body: &'a str,
+    ) -> BoxFuture<'a, Result<(), String>>;
+
+    /// # Summary
+    ///
+    /// Check whether the mail server can be reached, without sending an email. The self-check uses this to report email
+    /// connectivity issues. EmailSenders that cannot verify their connection report success.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// if let Err(e) = email_sender.check().await {
+    ///     error!("The mail server cannot be reached: {}", e);
+    /// }
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `BoxFuture<Result<(), String>>` - Nothing, or the error that occurred.
+    fn check(&self) -> BoxFuture<'_, Result<(), String>> {
+        Box::pin(async { Ok(()) })
+    }
+}
@@ -0,0 +1,189 @@
+use crate::configuration::email_queue_config::EmailQueueConfig;
+use crate::repository::email_job::email_job_model::EmailJob;
+use crate::repository::email_job::email_job_repository::{EmailJobRepository, Error};
+use crate::services::email::email_sender::EmailSender;
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use log::{error, info, warn};
+use mongodb::Database;
+use std::sync::Arc;
+
+/// The amount of seconds for which a claimed EmailJob is reserved while it is being sent
+const EMAIL_JOB_LEASE: i64 = 300;
+
+#[derive(Clone)]
+pub struct MongoEmailJobService {
+    pub email_job_repository: EmailJobRepository,
+    pub email_queue_config: EmailQueueConfig,
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait EmailJobService: Send + Sync {
+    /// # Summary
+    ///
+    /// Queue an email, so that it is sent in the background instead of in the request path.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - The email address of the recipient.
+    /// * `subject` - The subject of the email.
+    /// * `body` - The plain text body of the email.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// email_job_service.enqueue("user@opserva.io", "Welcome", "Hello", &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    async fn enqueue(
+        &self,
+        to: &str,
+        subject: &str,
+        body: &str,
+        db: &Database,
+    ) -> Result<(), Error>;
+
+    /// # Summary
+    ///
+    /// Send the queued emails that are due, up to the configured rate limit. Emails that cannot be sent are retried
+    /// with an exponential backoff until the maximum amount of attempts is reached.
+    ///
+    /// # Arguments
+    ///
+    /// * `email_sender` - The EmailSender that sends the emails.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let sent = email_job_service.process(email_sender, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u32, Error>` - The amount of emails that were sent.
+    async fn process(
+        &self,
+        email_sender: Arc<dyn EmailSender>,
+        db: &Database,
+    ) -> Result<u32, Error>;
+}
+
+impl MongoEmailJobService {
+    /// # Summary
+    ///
+    /// Create a new MongoEmailJobService.
+    ///
+    /// # Arguments
+    ///
+    /// * `email_job_repository` - The EmailJobRepository.
+    /// * `email_queue_config` - The EmailQueueConfig.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let email_job_repository = EmailJobRepository::new(String::from("emailJobs"));
+    /// let email_job_service = MongoEmailJobService::new(email_job_repository, email_queue_config);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `MongoEmailJobService` - The new MongoEmailJobService.
+    pub fn new(
+        email_job_repository: EmailJobRepository,
+        email_queue_config: EmailQueueConfig,
+    ) -> MongoEmailJobService {
+        MongoEmailJobService {
+            email_job_repository,
+            email_queue_config,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Get the delay before the next attempt of an EmailJob, which doubles with every failed attempt.
+    ///
+    /// # Arguments
+    ///
+    /// * `attempts` - The amount of attempts that were made.
+    ///
+    /// # Returns
+    ///
+    /// * `Duration` - The delay before the next attempt.
+    fn retry_delay(&self, attempts: i64) -> Duration {
+        let factor = 1_u64 << (attempts - 1).clamp(0, 16);
+        let seconds = self.email_queue_config.retry_delay.saturating_mul(factor);
+
+        Duration::seconds(i64::from(u32::try_from(seconds).unwrap_or(u32::MAX)))
+    }
+}
+
+#[async_trait]
+impl EmailJobService for MongoEmailJobService {
+    /// # Summary
+    ///
+    /// Queue an email, so that it is sent in the background instead of in the request path.
+    async fn enqueue(
+        &self,
+        to: &str,
+        subject: &str,
+        body: &str,
+        db: &Database,
+    ) -> Result<(), Error> {
+        let email_job = EmailJob::new(String::from(to), String::from(subject), String::from(body));
+
+        info!("Creating EmailJob: {}", email_job);
+        self.email_job_repository.create(email_job, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Send the queued emails that are due, up to the configured rate limit.
+    async fn process(
+        &self,
+        email_sender: Arc<dyn EmailSender>,
+        db: &Database,
+    ) -> Result<u32, Error> {
+        let mut sent = 0;
+
+        for _ in 0..self.email_queue_config.rate_limit {
+            let lease = Utc::now() + Duration::seconds(EMAIL_JOB_LEASE);
+            let email_job = match self.email_job_repository.claim(lease, db).await? {
+                Some(d) => d,
+                None => break,
+            };
+
+            match email_sender
+                .send(&email_job.to, &email_job.subject, &email_job.body)
+                .await
+            {
+                Ok(_) => {
+                    self.email_job_repository.delete(email_job.id, db).await?;
+                    sent += 1;
+                }
+                Err(e) => {
+                    if email_job.attempts >= i64::from(self.email_queue_config.max_attempts) {
+                        error!(
+                            "Dropping {} after the last failed attempt: {}",
+                            email_job, e
+                        );
+                        self.email_job_repository.delete(email_job.id, db).await?;
+                    } else {
+                        warn!("Failed to send {}, retrying later: {}", email_job, e);
+                        let next_attempt_at = Utc::now() + self.retry_delay(email_job.attempts);
+                        self.email_job_repository
+                            .reschedule(email_job.id, next_attempt_at, &e, db)
+                            .await?;
+                    }
+                }
+            }
+        }
+
+        Ok(sent)
+    }
+}
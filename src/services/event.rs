@@ -0,0 +1,5 @@
+pub mod invalidation_event;
+pub mod invalidation_listener;
+pub mod security_event;
+pub mod security_event_sink;
+pub mod syslog_sink;
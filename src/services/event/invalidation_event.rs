@@ -0,0 +1,51 @@
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum InvalidationEvent {
+    #[serde(rename_all = "camelCase")]
+    RoleRenamed {
+        id: String,
+        old_name: String,
+        new_name: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    PermissionRenamed {
+        id: String,
+        old_name: String,
+        new_name: String,
+    },
+}
+
+impl Display for InvalidationEvent {
+    /// # Summary
+    ///
+    /// Display the InvalidationEvent.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The Formatter.
+    ///
+    /// # Returns
+    ///
+    /// * `std::fmt::Result` - The result of the display.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvalidationEvent::RoleRenamed {
+                id,
+                old_name,
+                new_name,
+            } => write!(f, "Role {} renamed from {} to {}", id, old_name, new_name),
+            InvalidationEvent::PermissionRenamed {
+                id,
+                old_name,
+                new_name,
+            } => write!(
+                f,
+                "Permission {} renamed from {} to {}",
+                id, old_name, new_name
+            ),
+        }
+    }
+}
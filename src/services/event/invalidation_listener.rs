@@ -0,0 +1,30 @@
+use crate::services::event::invalidation_event::InvalidationEvent;
+use futures::future::BoxFuture;
+
+pub trait InvalidationListener: Send + Sync {
+    /// # Summary
+    ///
+    /// Handle an event after which cached data, such as the permission sets of Users, may be stale. Caching layers evict
+    /// the affected entries and webhook dispatchers forward the event to their subscribers.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The InvalidationEvent.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// struct CacheInvalidator;
+    ///
+    /// impl InvalidationListener for CacheInvalidator {
+    ///     fn on_event<'a>(&'a self, event: &'a InvalidationEvent) -> BoxFuture<'a, Result<(), String>> {
+    ///         Box::pin(async move { cache.clear().await.map_err(|e| e.to_string()) })
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `BoxFuture<Result<(), String>>` - Nothing, or the error that occurred.
+    fn on_event<'a>(&'a self, event: &'a InvalidationEvent) -> BoxFuture<'a, Result<(), String>>;
+}
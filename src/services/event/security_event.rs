@@ -0,0 +1,210 @@
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SecurityEvent {
+    #[serde(rename_all = "camelCase")]
+    LoginSucceeded {
+        user_id: String,
+        username: String,
+        source_ip: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    LoginFailed {
+        username: String,
+        reason: String,
+        source_ip: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    AccountLocked {
+        user_id: String,
+        actor_id: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    RoleGranted {
+        user_id: String,
+        role_id: String,
+        actor_id: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    PermissionDenied {
+        subject: Option<String>,
+        method: String,
+        route: String,
+        permission: Option<String>,
+        source_ip: Option<String>,
+    },
+}
+
+impl SecurityEvent {
+    /// # Summary
+    ///
+    /// Get the stable identifier of the kind of SecurityEvent, which is used as the CEF signature ID.
+    ///
+    /// # Returns
+    ///
+    /// * `&'static str` - The identifier of the kind of SecurityEvent.
+    pub fn signature_id(&self) -> &'static str {
+        match self {
+            SecurityEvent::LoginSucceeded { .. } => "login-succeeded",
+            SecurityEvent::LoginFailed { .. } => "login-failed",
+            SecurityEvent::AccountLocked { .. } => "account-locked",
+            SecurityEvent::RoleGranted { .. } => "role-granted",
+            SecurityEvent::PermissionDenied { .. } => "permission-denied",
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Get the human-readable name of the kind of SecurityEvent.
+    ///
+    /// # Returns
+    ///
+    /// * `&'static str` - The name of the kind of SecurityEvent.
+    pub fn name(&self) -> &'static str {
+        match self {
+            SecurityEvent::LoginSucceeded { .. } => "Login succeeded",
+            SecurityEvent::LoginFailed { .. } => "Login failed",
+            SecurityEvent::AccountLocked { .. } => "Account locked",
+            SecurityEvent::RoleGranted { .. } => "Role granted",
+            SecurityEvent::PermissionDenied { .. } => "Permission denied",
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Get the severity of the SecurityEvent on the CEF scale from 0 (lowest) to 10 (highest).
+    ///
+    /// # Returns
+    ///
+    /// * `u8` - The severity of the SecurityEvent.
+    pub fn severity(&self) -> u8 {
+        match self {
+            SecurityEvent::LoginSucceeded { .. } => 3,
+            SecurityEvent::LoginFailed { .. } | SecurityEvent::PermissionDenied { .. } => 5,
+            SecurityEvent::RoleGranted { .. } => 6,
+            SecurityEvent::AccountLocked { .. } => 7,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Get the details of the SecurityEvent as CEF extension fields.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<(&'static str, String)>` - The CEF extension keys and their values. Unknown values are omitted.
+    pub fn extensions(&self) -> Vec<(&'static str, String)> {
+        let mut extensions = vec![];
+        match self {
+            SecurityEvent::LoginSucceeded {
+                user_id,
+                username,
+                source_ip,
+            } => {
+                extensions.push(("duid", user_id.clone()));
+                extensions.push(("duser", username.clone()));
+                extensions.push(("outcome", String::from("success")));
+                if let Some(s) = source_ip {
+                    extensions.push(("src", s.clone()));
+                }
+            }
+            SecurityEvent::LoginFailed {
+                username,
+                reason,
+                source_ip,
+            } => {
+                extensions.push(("duser", username.clone()));
+                extensions.push(("outcome", String::from("failure")));
+                extensions.push(("reason", reason.clone()));
+                if let Some(s) = source_ip {
+                    extensions.push(("src", s.clone()));
+                }
+            }
+            SecurityEvent::AccountLocked { user_id, actor_id } => {
+                extensions.push(("duid", user_id.clone()));
+                if let Some(a) = actor_id {
+                    extensions.push(("suid", a.clone()));
+                }
+            }
+            SecurityEvent::RoleGranted {
+                user_id,
+                role_id,
+                actor_id,
+            } => {
+                extensions.push(("duid", user_id.clone()));
+                extensions.push(("cs1Label", String::from("roleId")));
+                extensions.push(("cs1", role_id.clone()));
+                if let Some(a) = actor_id {
+                    extensions.push(("suid", a.clone()));
+                }
+            }
+            SecurityEvent::PermissionDenied {
+                subject,
+                method,
+                route,
+                permission,
+                source_ip,
+            } => {
+                if let Some(s) = subject {
+                    extensions.push(("suid", s.clone()));
+                }
+                extensions.push(("requestMethod", method.clone()));
+                extensions.push(("request", route.clone()));
+                extensions.push(("outcome", String::from("failure")));
+                if let Some(p) = permission {
+                    extensions.push(("cs1Label", String::from("permission")));
+                    extensions.push(("cs1", p.clone()));
+                }
+                if let Some(s) = source_ip {
+                    extensions.push(("src", s.clone()));
+                }
+            }
+        }
+
+        extensions
+    }
+}
+
+impl Display for SecurityEvent {
+    /// # Summary
+    ///
+    /// Display the SecurityEvent.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The Formatter.
+    ///
+    /// # Returns
+    ///
+    /// * `std::fmt::Result` - The result of the display.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SecurityEvent::LoginSucceeded { user_id, .. } => {
+                write!(f, "User {} logged in", user_id)
+            }
+            SecurityEvent::LoginFailed {
+                username, reason, ..
+            } => write!(f, "Login of {} failed: {}", username, reason),
+            SecurityEvent::AccountLocked { user_id, .. } => {
+                write!(f, "User {} was locked", user_id)
+            }
+            SecurityEvent::RoleGranted {
+                user_id, role_id, ..
+            } => write!(f, "Role {} granted to User {}", role_id, user_id),
+            SecurityEvent::PermissionDenied {
+                subject,
+                method,
+                route,
+                ..
+            } => write!(
+                f,
+                "Permission denied for {} {} to {}",
+                method,
+                route,
+                subject.as_deref().unwrap_or("anonymous")
+            ),
+        }
+    }
+}
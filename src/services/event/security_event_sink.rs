@@ -0,0 +1,32 @@
+use crate::services::event::security_event::SecurityEvent;
+use futures::future::BoxFuture;
+
+pub trait SecurityEventSink: Send + Sync {
+    /// # Summary
+    ///
+    /// Forward a SecurityEvent, such as a failed login or a denied request, to an external system like a SIEM.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The SecurityEvent.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// struct LogSink;
+    ///
+    /// impl SecurityEventSink for LogSink {
+    ///     fn on_event<'a>(&'a self, event: &'a SecurityEvent) -> BoxFuture<'a, Result<(), String>> {
+    ///         Box::pin(async move {
+    ///             info!("{}", event);
+    ///             Ok(())
+    ///         })
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `BoxFuture<Result<(), String>>` - Nothing, or the error that occurred.
+    fn on_event<'a>(&'a self, event: &'a SecurityEvent) -> BoxFuture<'a, Result<(), String>>;
+}
@@ -0,0 +1,278 @@
+use crate::services::event::security_event::SecurityEvent;
+use crate::services::event::security_event_sink::SecurityEventSink;
+use chrono::{SecondsFormat, Utc};
+use futures::future::BoxFuture;
+use futures::lock::Mutex;
+use std::fmt::{Display, Formatter};
+use std::net::SocketAddr;
+use std::str::FromStr;
+use tokio::io::AsyncWriteExt;
+use tokio::net::{lookup_host, TcpStream, UdpSocket};
+
+/// The syslog facility of the messages, which is `authpriv` (10).
+const FACILITY: u8 = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SyslogProtocol {
+    Udp,
+    Tcp,
+}
+
+impl Display for SyslogProtocol {
+    /// # Summary
+    ///
+    /// Display the SyslogProtocol.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The Formatter.
+    ///
+    /// # Returns
+    ///
+    /// * `std::fmt::Result` - The result of the display.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SyslogProtocol::Udp => write!(f, "udp"),
+            SyslogProtocol::Tcp => write!(f, "tcp"),
+        }
+    }
+}
+
+impl FromStr for SyslogProtocol {
+    type Err = String;
+
+    /// # Summary
+    ///
+    /// Parse a SyslogProtocol.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The protocol, either `udp` or `tcp`.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<SyslogProtocol, String>` - The SyslogProtocol, or an error message if the protocol is unknown.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "udp" => Ok(SyslogProtocol::Udp),
+            "tcp" => Ok(SyslogProtocol::Tcp),
+            _ => Err(format!("Unknown syslog protocol: {}", s)),
+        }
+    }
+}
+
+pub struct SyslogSink {
+    pub address: String,
+    pub protocol: SyslogProtocol,
+    pub hostname: String,
+    connection: Mutex<Option<TcpStream>>,
+}
+
+impl SyslogSink {
+    /// # Summary
+    ///
+    /// Create a new SyslogSink that forwards SecurityEvents as CEF messages to a syslog collector.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The address of the collector, such as `siem.example.com:514`.
+    /// * `protocol` - The SyslogProtocol that is used to reach the collector.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let syslog_sink = SyslogSink::new(String::from("siem.example.com:514"), SyslogProtocol::Udp);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `SyslogSink` - The new SyslogSink.
+    pub fn new(address: String, protocol: SyslogProtocol) -> SyslogSink {
+        let hostname = match std::env::var("HOSTNAME") {
+            Ok(h) if !h.trim().is_empty() => h.trim().to_string(),
+            _ => String::from("-"),
+        };
+
+        SyslogSink {
+            address,
+            protocol,
+            hostname,
+            connection: Mutex::new(None),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Format a SecurityEvent as an RFC 5424 syslog message with a CEF payload.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The SecurityEvent.
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The syslog message.
+    pub fn format(&self, event: &SecurityEvent) -> String {
+        let extensions = event
+            .extensions()
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, escape_extension(v)))
+            .collect::<Vec<String>>()
+            .join(" ");
+
+        let cef = format!(
+            "CEF:0|Opserva|{}|{}|{}|{}|{}|{}",
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION"),
+            escape_header(event.signature_id()),
+            escape_header(event.name()),
+            event.severity(),
+            extensions
+        );
+
+        // CEF severities of 7 and up are warnings, 4 to 6 are notices and anything below is informational
+        let severity: u8 = if event.severity() >= 7 {
+            4
+        } else if event.severity() >= 4 {
+            5
+        } else {
+            6
+        };
+
+        format!(
+            "<{}>1 {} {} {} - {} - {}",
+            FACILITY * 8 + severity,
+            Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            self.hostname,
+            env!("CARGO_PKG_NAME"),
+            event.signature_id(),
+            cef
+        )
+    }
+
+    /// # Summary
+    ///
+    /// Send a message to the collector over UDP.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The syslog message.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), String>` - Nothing, or the error that occurred.
+    async fn send_udp(&self, message: &str) -> Result<(), String> {
+        let target: SocketAddr = lookup_host(&self.address)
+            .await
+            .map_err(|e| e.to_string())?
+            .next()
+            .ok_or_else(|| format!("Failed to resolve {}", self.address))?;
+
+        let bind = if target.is_ipv4() {
+            "0.0.0.0:0"
+        } else {
+            "[::]:0"
+        };
+        let socket = UdpSocket::bind(bind).await.map_err(|e| e.to_string())?;
+        socket
+            .send_to(message.as_bytes(), target)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    /// # Summary
+    ///
+    /// Send a message to the collector over TCP, using octet counting to frame the message. The connection is kept open
+    /// and re-established once if it was closed by the collector.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The syslog message.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), String>` - Nothing, or the error that occurred.
+    async fn send_tcp(&self, message: &str) -> Result<(), String> {
+        let frame = format!("{} {}", message.len(), message);
+        let mut connection = self.connection.lock().await;
+
+        for _ in 0..2 {
+            if connection.is_none() {
+                *connection = Some(
+                    TcpStream::connect(&self.address)
+                        .await
+                        .map_err(|e| e.to_string())?,
+                );
+            }
+
+            if let Some(stream) = connection.as_mut() {
+                if stream.write_all(frame.as_bytes()).await.is_ok() {
+                    return Ok(());
+                }
+            }
+
+            *connection = None;
+        }
+
+        Err(format!("Failed to send syslog message to {}", self.address))
+    }
+}
+
+impl SecurityEventSink for SyslogSink {
+    /// # Summary
+    ///
+    /// Forward a SecurityEvent to the syslog collector.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The SecurityEvent.
+    ///
+    /// # Returns
+    ///
+    /// * `BoxFuture<Result<(), String>>` - Nothing, or the error that occurred.
+    fn on_event<'a>(&'a self, event: &'a SecurityEvent) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            let message = self.format(event);
+            match self.protocol {
+                SyslogProtocol::Udp => self.send_udp(&message).await,
+                SyslogProtocol::Tcp => self.send_tcp(&message).await,
+            }
+        })
+    }
+}
+
+/// # Summary
+///
+/// Escape a value of the CEF header, in which pipes and backslashes have to be escaped.
+///
+/// # Arguments
+///
+/// * `value` - The value.
+///
+/// # Returns
+///
+/// * `String` - The escaped value.
+fn escape_header(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('|', "\\|")
+}
+
+/// # Summary
+///
+/// Escape a value of a CEF extension, in which equal signs, backslashes and line breaks have to be escaped.
+///
+/// # Arguments
+///
+/// * `value` - The value.
+///
+/// # Returns
+///
+/// * `String` - The escaped value.
+fn escape_extension(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('=', "\\=")
+        .replace('\r', "\\r")
+        .replace('\n', "\\n")
+}
@@ -0,0 +1,308 @@
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+#[cfg(feature = "integrations")]
+use std::time::Duration;
+use thiserror::Error;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// The maximum amount of time that a single upload may take.
+#[cfg(feature = "integrations")]
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Invalid endpoint: {0}")]
+    InvalidEndpoint(String),
+    #[error("S3 exports require the integrations feature")]
+    Disabled,
+    #[cfg(feature = "integrations")]
+    #[error("Request error: {0}")]
+    Request(reqwest::Error),
+    #[error("Unexpected response: {0} {1}")]
+    UnexpectedResponse(u16, String),
+}
+
+#[derive(Clone)]
+pub struct S3Client {
+    #[cfg(feature = "integrations")]
+    client: reqwest::Client,
+    endpoint: String,
+    host: String,
+    bucket: String,
+    region: String,
+    access_key_id: String,
+    secret_access_key: String,
+}
+
+impl S3Client {
+    /// # Summary
+    ///
+    /// Create a new S3Client that writes objects to a bucket of an S3-compatible storage.
+    /// Path-style addressing is used, so that storages that do not support virtual hosts, such as MinIO, can be used.
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoint` - The URL of the storage, such as `https://s3.eu-west-1.amazonaws.com`.
+    /// * `bucket` - The bucket that the objects are written to.
+    /// * `region` - The region of the bucket.
+    /// * `access_key_id` - The ID of the access key that is used to sign the requests.
+    /// * `secret_access_key` - The secret access key that is used to sign the requests.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let s3_client = S3Client::new("http://localhost:9000", "archive", "us-east-1", "minio", "minio123").unwrap();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<S3Client, Error>` - The new S3Client, InvalidEndpoint if the endpoint is not an HTTP(S) URL, or Disabled if the `integrations` feature is disabled.
+    #[cfg(feature = "integrations")]
+    pub fn new(
+        endpoint: &str,
+        bucket: &str,
+        region: &str,
+        access_key_id: &str,
+        secret_access_key: &str,
+    ) -> Result<S3Client, Error> {
+        let endpoint = endpoint.trim_end_matches('/');
+        let host = match endpoint
+            .strip_prefix("https://")
+            .or_else(|| endpoint.strip_prefix("http://"))
+        {
+            Some(h) if !h.is_empty() && !h.contains('/') => h.to_string(),
+            _ => return Err(Error::InvalidEndpoint(endpoint.to_string())),
+        };
+
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(Error::Request)?;
+
+        Ok(S3Client {
+            client,
+            endpoint: endpoint.to_string(),
+            host,
+            bucket: bucket.to_string(),
+            region: region.to_string(),
+            access_key_id: access_key_id.to_string(),
+            secret_access_key: secret_access_key.to_string(),
+        })
+    }
+
+    /// # Summary
+    ///
+    /// Fail to create an S3Client, since the storage cannot be called if the `integrations` feature is disabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `_endpoint` - The URL of the storage.
+    /// * `_bucket` - The bucket that the objects would be written to.
+    /// * `_region` - The region of the bucket.
+    /// * `_access_key_id` - The ID of the access key.
+    /// * `_secret_access_key` - The secret access key.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<S3Client, Error>` - Always Disabled.
+    #[cfg(not(feature = "integrations"))]
+    pub fn new(
+        _endpoint: &str,
+        _bucket: &str,
+        _region: &str,
+        _access_key_id: &str,
+        _secret_access_key: &str,
+    ) -> Result<S3Client, Error> {
+        Err(Error::Disabled)
+    }
+
+    /// # Summary
+    ///
+    /// Write an object to the bucket, replacing the object with the same key if it exists.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the object.
+    /// * `body` - The content of the object.
+    /// * `content_type` - The media type of the content.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// s3_client.put_object("auth-rs/users/20240101T000000Z.json", body, "application/json").await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    pub async fn put_object(
+        &self,
+        key: &str,
+        body: Vec<u8>,
+        content_type: &str,
+    ) -> Result<(), Error> {
+        let path = format!("/{}/{}", uri_encode(&self.bucket), uri_encode(key));
+        let payload_hash = hex(&Sha256::digest(&body));
+        let now = Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+
+        let authorization =
+            self.authorization("PUT", &path, content_type, &payload_hash, &amz_date, now);
+
+        #[cfg(feature = "integrations")]
+        {
+            let response = self
+                .client
+                .put(format!("{}{}", self.endpoint, path))
+                .header("Content-Type", content_type)
+                .header("x-amz-content-sha256", &payload_hash)
+                .header("x-amz-date", &amz_date)
+                .header("Authorization", authorization)
+                .body(body)
+                .send()
+                .await
+                .map_err(Error::Request)?;
+
+            let status = response.status();
+            if status.is_success() {
+                return Ok(());
+            }
+
+            let text = response.text().await.unwrap_or_default();
+            Err(Error::UnexpectedResponse(status.as_u16(), text))
+        }
+
+        // An S3Client cannot be created without the feature
+        #[cfg(not(feature = "integrations"))]
+        {
+            let _ = (authorization, body);
+            Err(Error::Disabled)
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Create the value of the Authorization header of a request, according to AWS Signature Version 4.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The HTTP method of the request.
+    /// * `path` - The encoded path of the request.
+    /// * `content_type` - The media type of the content.
+    /// * `payload_hash` - The hex encoded SHA-256 hash of the content.
+    /// * `amz_date` - The moment of the request, as it is sent in the `x-amz-date` header.
+    /// * `now` - The moment of the request.
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The value of the Authorization header.
+    fn authorization(
+        &self,
+        method: &str,
+        path: &str,
+        content_type: &str,
+        payload_hash: &str,
+        amz_date: &str,
+        now: DateTime<Utc>,
+    ) -> String {
+        let date = now.format("%Y%m%d").to_string();
+        let signed_headers = "content-type;host;x-amz-content-sha256;x-amz-date";
+        let canonical_request = format!(
+            "{}\n{}\n\ncontent-type:{}\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+            method,
+            path,
+            content_type,
+            self.host,
+            payload_hash,
+            amz_date,
+            signed_headers,
+            payload_hash
+        );
+
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            hex(&Sha256::digest(canonical_request.as_bytes()))
+        );
+
+        let mut key = hmac(
+            format!("AWS4{}", self.secret_access_key).as_bytes(),
+            date.as_bytes(),
+        );
+        for part in [self.region.as_str(), "s3", "aws4_request"] {
+            key = hmac(&key, part.as_bytes());
+        }
+        let signature = hex(&hmac(&key, string_to_sign.as_bytes()));
+
+        format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+            self.access_key_id, scope, signed_headers, signature
+        )
+    }
+}
+
+/// # Summary
+///
+/// Calculate the HMAC-SHA256 of a message.
+///
+/// # Arguments
+///
+/// * `key` - The key.
+/// * `message` - The message.
+///
+/// # Returns
+///
+/// * `Vec<u8>` - The HMAC of the message.
+fn hmac(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC can take a key of any size");
+    mac.update(message);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// # Summary
+///
+/// Encode bytes as lowercase hexadecimal characters.
+///
+/// # Arguments
+///
+/// * `bytes` - The bytes to encode.
+///
+/// # Returns
+///
+/// * `String` - The hex encoded bytes.
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut s, b| {
+        let _ = write!(s, "{:02x}", b);
+        s
+    })
+}
+
+/// # Summary
+///
+/// Percent-encode a path as required by AWS Signature Version 4. Slashes are kept, so that keys can contain folders.
+///
+/// # Arguments
+///
+/// * `path` - The path to encode.
+///
+/// # Returns
+///
+/// * `String` - The encoded path.
+fn uri_encode(path: &str) -> String {
+    path.bytes().fold(String::new(), |mut s, b| {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                s.push(b as char)
+            }
+            _ => {
+                let _ = write!(s, "%{:02X}", b);
+            }
+        }
+        s
+    })
+}
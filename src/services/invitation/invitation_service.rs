@@ -0,0 +1,464 @@
+use crate::configuration::invitation_config::InvitationConfig;
+use crate::repository::audit::audit_model::Action::{Create, Delete};
+use crate::repository::audit::audit_model::{Action, Audit, ResourceIdType, ResourceType};
+use crate::repository::audit::audit_repository::Error as AuditError;
+use crate::repository::invitation::invitation_model::Invitation;
+use crate::repository::invitation::invitation_repository::{Error, InvitationRepository};
+use crate::repository::organization::organization_model::{Member, Organization};
+use crate::repository::user::user_model::User;
+use crate::services::audit::audit_service::AuditService;
+use crate::services::client::client_service::{generate_secret, hash_secret};
+use crate::services::organization::organization_service::OrganizationService;
+use async_trait::async_trait;
+use log::{error, info};
+use mongodb::bson::oid::ObjectId;
+use mongodb::Database;
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct MongoInvitationService {
+    pub invitation_repository: InvitationRepository,
+    pub invitation_config: InvitationConfig,
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait InvitationService: Send + Sync {
+    /// # Summary
+    ///
+    /// Invite an email address into an Organization with a randomly generated token.
+    ///
+    /// # Arguments
+    ///
+    /// * `organization_id` - The ID of the Organization.
+    /// * `email` - The email address of the invitee.
+    /// * `roles` - The IDs of the roles that the invitee receives within the Organization.
+    /// * `user_id` - The ID of the User creating the Invitation.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let (invitation, token) = invitation_service.create(organization_id, email, roles, Some(user_id), &db, &audit_service).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(Invitation, String), Error>` - The created Invitation and its token. The token is not stored and cannot be retrieved afterwards.
+    async fn create(
+        &self,
+        organization_id: ObjectId,
+        email: String,
+        roles: Vec<ObjectId>,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(Invitation, String), Error>;
+
+    /// # Summary
+    ///
+    /// Find all pending Invitation entities of an Organization.
+    ///
+    /// # Arguments
+    ///
+    /// * `organization_id` - The ID of the Organization.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let invitations = invitation_service.find_by_organization_id(organization_id, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Invitation>, Error>` - The Invitation entities that have not expired yet.
+    async fn find_by_organization_id(
+        &self,
+        organization_id: ObjectId,
+        db: &Database,
+    ) -> Result<Vec<Invitation>, Error>;
+
+    /// # Summary
+    ///
+    /// Find the Invitation that belongs to a token and check that it can be accepted by a User.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The token of the Invitation.
+    /// * `user` - The User that wants to accept the Invitation.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let invitation = invitation_service.find_by_token("token", &user, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Invitation, Error>` - The Invitation, if it has not expired and was sent to one of the email addresses of the User.
+    async fn find_by_token(
+        &self,
+        token: &str,
+        user: &User,
+        db: &Database,
+    ) -> Result<Invitation, Error>;
+
+    /// # Summary
+    ///
+    /// Accept an Invitation on behalf of a User. The User joins the Organization with the roles of the Invitation and the
+    /// default roles of the Organization, after which the Invitation is deleted.
+    ///
+    /// # Arguments
+    ///
+    /// * `invitation` - The Invitation, as returned by `find_by_token`.
+    /// * `user_id` - The ID of the User accepting the Invitation.
+    /// * `db` - The Database that holds the Organization.
+    /// * `invitation_db` - The Database that holds the Invitation, which differs from `db` if the Organization is routed to the Database of its tenant.
+    /// * `organization_service` - The OrganizationService to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let organization = invitation_service.accept(invitation, user_id, &db, &invitation_db, &organization_service, &audit_service).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Organization, Error>` - The Organization that the User joined.
+    async fn accept(
+        &self,
+        invitation: Invitation,
+        user_id: ObjectId,
+        db: &Database,
+        invitation_db: &Database,
+        organization_service: &Arc<dyn OrganizationService>,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<Organization, Error>;
+
+    /// # Summary
+    ///
+    /// Delete an Invitation of an Organization, after it was accepted or revoked.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the Invitation.
+    /// * `organization_id` - The ID of the Organization.
+    /// * `user_id` - The ID of the User deleting the Invitation.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// invitation_service.delete("invitation_id", organization_id, Some(user_id), &db, &audit_service).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    async fn delete(
+        &self,
+        id: &str,
+        organization_id: ObjectId,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(), Error>;
+
+    /// # Summary
+    ///
+    /// Delete all Invitation entities of an Organization.
+    ///
+    /// # Arguments
+    ///
+    /// * `organization_id` - The ID of the Organization.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// invitation_service.delete_by_organization_id(organization_id, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    async fn delete_by_organization_id(
+        &self,
+        organization_id: ObjectId,
+        db: &Database,
+    ) -> Result<(), Error>;
+}
+
+impl MongoInvitationService {
+    /// # Summary
+    ///
+    /// Create a new MongoInvitationService.
+    ///
+    /// # Arguments
+    ///
+    /// * `invitation_repository` - The InvitationRepository.
+    /// * `invitation_config` - The InvitationConfig.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let invitation_repository = InvitationRepository::new(String::from("invitations"));
+    /// let invitation_service = MongoInvitationService::new(invitation_repository, InvitationConfig::new(604800));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `MongoInvitationService` - The new MongoInvitationService.
+    pub fn new(
+        invitation_repository: InvitationRepository,
+        invitation_config: InvitationConfig,
+    ) -> MongoInvitationService {
+        MongoInvitationService {
+            invitation_repository,
+            invitation_config,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Record a change to an Invitation in the audit trail.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User that made the change, if any.
+    /// * `action` - The Action.
+    /// * `invitation_id` - The ID of the Invitation.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    async fn audit(
+        user_id: Option<ObjectId>,
+        action: Action,
+        invitation_id: ObjectId,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(), Error> {
+        let user_id = match user_id {
+            Some(u) => u,
+            None => return Ok(()),
+        };
+
+        let new_audit = Audit::new(
+            user_id,
+            action,
+            invitation_id,
+            ResourceIdType::InvitationId,
+            ResourceType::Invitation,
+        );
+        match audit_service.create(new_audit, db).await {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                error!("Failed to create Audit: {}", e);
+                Err(Error::Audit(e))
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl InvitationService for MongoInvitationService {
+    /// # Summary
+    ///
+    /// Invite an email address into an Organization with a randomly generated token.
+    async fn create(
+        &self,
+        organization_id: ObjectId,
+        email: String,
+        roles: Vec<ObjectId>,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(Invitation, String), Error> {
+        let email = email.trim().to_lowercase();
+        if email.is_empty() {
+            return Err(Error::EmptyEmail);
+        }
+
+        if !email.contains('@') || email.starts_with('@') || email.ends_with('@') {
+            return Err(Error::InvalidEmail(email));
+        }
+
+        let mut invitation = Invitation::new(
+            organization_id,
+            email,
+            roles,
+            user_id,
+            self.invitation_config.expiration,
+        );
+
+        let token = generate_secret();
+        invitation.token_hash = hash_secret(&token);
+
+        info!("Creating Invitation: {}", invitation);
+        Self::audit(user_id, Create, invitation.id, db, audit_service).await?;
+
+        let invitation = self.invitation_repository.create(invitation, db).await?;
+        Ok((invitation, token))
+    }
+
+    /// # Summary
+    ///
+    /// Find all pending Invitation entities of an Organization.
+    async fn find_by_organization_id(
+        &self,
+        organization_id: ObjectId,
+        db: &Database,
+    ) -> Result<Vec<Invitation>, Error> {
+        info!(
+            "Finding Invitations of Organization: {}",
+            organization_id.to_hex()
+        );
+        self.invitation_repository
+            .find_by_organization_id(organization_id, db)
+            .await
+    }
+
+    /// # Summary
+    ///
+    /// Find the Invitation that belongs to a token and check that it can be accepted by a User.
+    async fn find_by_token(
+        &self,
+        token: &str,
+        user: &User,
+        db: &Database,
+    ) -> Result<Invitation, Error> {
+        if token.is_empty() {
+            return Err(Error::InvalidToken);
+        }
+
+        let invitation = match self
+            .invitation_repository
+            .find_by_token_hash(&hash_secret(token), db)
+            .await?
+        {
+            Some(i) => i,
+            None => return Err(Error::InvalidToken),
+        };
+
+        // Expired invitations are removed by a TTL index, which does not run instantly
+        if invitation.is_expired() {
+            return Err(Error::InvitationExpired);
+        }
+
+        let matches = user
+            .email
+            .iter()
+            .chain(user.secondary_emails.iter().map(|e| &e.email))
+            .any(|e| e.to_lowercase() == invitation.email);
+
+        if !matches {
+            return Err(Error::EmailMismatch);
+        }
+
+        Ok(invitation)
+    }
+
+    /// # Summary
+    ///
+    /// Accept an Invitation on behalf of a User.
+    async fn accept(
+        &self,
+        invitation: Invitation,
+        user_id: ObjectId,
+        db: &Database,
+        invitation_db: &Database,
+        organization_service: &Arc<dyn OrganizationService>,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<Organization, Error> {
+        info!(
+            "Accepting Invitation {} on behalf of User {}",
+            invitation.id.to_hex(),
+            user_id.to_hex()
+        );
+
+        let member = Member {
+            user_id,
+            roles: invitation.roles,
+        };
+
+        let organization = organization_service
+            .set_member(
+                &invitation.organization_id.to_hex(),
+                member,
+                Some(user_id),
+                db,
+                audit_service,
+            )
+            .await
+            .map_err(Error::Organization)?;
+
+        // An Invitation can only be accepted once
+        self.delete(
+            &invitation.id.to_hex(),
+            invitation.organization_id,
+            Some(user_id),
+            invitation_db,
+            audit_service,
+        )
+        .await?;
+
+        Ok(organization)
+    }
+
+    /// # Summary
+    ///
+    /// Delete an Invitation of an Organization.
+    async fn delete(
+        &self,
+        id: &str,
+        organization_id: ObjectId,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(), Error> {
+        info!(
+            "Deleting Invitation {} of Organization {}",
+            id,
+            organization_id.to_hex()
+        );
+
+        // The ID is validated by the repository before the Invitation is deleted
+        self.invitation_repository
+            .delete(id, organization_id, db)
+            .await?;
+
+        let oid = match ObjectId::parse_str(id) {
+            Ok(oid) => oid,
+            Err(e) => {
+                return Err(Error::Audit(AuditError::ObjectId(e.to_string())));
+            }
+        };
+
+        Self::audit(user_id, Delete, oid, db, audit_service).await
+    }
+
+    /// # Summary
+    ///
+    /// Delete all Invitation entities of an Organization.
+    async fn delete_by_organization_id(
+        &self,
+        organization_id: ObjectId,
+        db: &Database,
+    ) -> Result<(), Error> {
+        info!(
+            "Deleting Invitations of Organization: {}",
+            organization_id.to_hex()
+        );
+        self.invitation_repository
+            .delete_by_organization_id(organization_id, db)
+            .await
+    }
+}
@@ -1 +1,4 @@
+pub mod claims_enricher;
+pub mod jwk;
 pub mod jwt_service;
+pub mod static_claims_enricher;
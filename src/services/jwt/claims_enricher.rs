@@ -0,0 +1,30 @@
+use serde_json::{Map, Value};
+
+/// The claims that are managed by the JwtService and cannot be changed by a ClaimsEnricher.
+pub const RESERVED_CLAIMS: &[&str] = &[
+    "sub", "exp", "iat", "ver", "cli", "tnt", "gst", "scope", "mfa", "lnk",
+];
+
+pub trait ClaimsEnricher: Send + Sync {
+    /// # Summary
+    ///
+    /// Add custom claims to a JWT token before it is signed.
+    ///
+    /// # Arguments
+    ///
+    /// * `subject` - The subject of the JWT token.
+    /// * `claims` - The custom claims of the JWT token.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// struct TenantEnricher;
+    ///
+    /// impl ClaimsEnricher for TenantEnricher {
+    ///     fn enrich(&self, subject: &str, claims: &mut Map<String, Value>) {
+    ///         claims.insert(String::from("tenant"), Value::from("opserva"));
+    ///     }
+    /// }
+    /// ```
+    fn enrich(&self, subject: &str, claims: &mut Map<String, Value>);
+}
@@ -0,0 +1,233 @@
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use jsonwebtoken::Algorithm;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+
+const TAG_INTEGER: u8 = 0x02;
+const TAG_BIT_STRING: u8 = 0x03;
+const TAG_SEQUENCE: u8 = 0x30;
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct Jwk {
+    pub kty: String,
+    #[serde(rename = "use")]
+    pub key_use: String,
+    pub alg: String,
+    pub kid: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub e: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crv: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub y: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct JwkSet {
+    pub keys: Vec<Jwk>,
+}
+
+impl Jwk {
+    /// # Summary
+    ///
+    /// Convert a PEM-encoded public key into a JSON Web Key (RFC 7517). RSA keys can be encoded as SubjectPublicKeyInfo
+    /// or PKCS#1, ECDSA and EdDSA keys as SubjectPublicKeyInfo. The key ID is the JWK thumbprint of the key (RFC 7638).
+    ///
+    /// # Arguments
+    ///
+    /// * `algorithm` - The algorithm that the key is used with.
+    /// * `pem` - The PEM-encoded public key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let jwk = Jwk::from_public_key_pem(Algorithm::RS256, &public_key)?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Jwk, String>` - The JWK, or the reason why the key cannot be converted.
+    pub fn from_public_key_pem(algorithm: Algorithm, pem: &str) -> Result<Jwk, String> {
+        let pkcs1 = pem.contains("BEGIN RSA PUBLIC KEY");
+        let body: String = pem
+            .lines()
+            .filter(|l| !l.starts_with("-----"))
+            .map(str::trim)
+            .collect();
+        let der = STANDARD
+            .decode(body)
+            .map_err(|e| format!("invalid PEM encoding: {}", e))?;
+
+        let mut jwk = Jwk {
+            kty: String::new(),
+            key_use: String::from("sig"),
+            alg: format!("{:?}", algorithm),
+            kid: String::new(),
+            n: None,
+            e: None,
+            crv: None,
+            x: None,
+            y: None,
+        };
+
+        match algorithm {
+            Algorithm::ES256 | Algorithm::ES384 => {
+                let point = subject_public_key(&der)?;
+                // Only uncompressed points (0x04 || X || Y) are supported
+                if point.first() != Some(&0x04) || point.len() % 2 == 0 {
+                    return Err(String::from("unsupported EC point encoding"));
+                }
+
+                let (x, y) = point[1..].split_at((point.len() - 1) / 2);
+                jwk.kty = String::from("EC");
+                jwk.crv = Some(String::from(if algorithm == Algorithm::ES256 {
+                    "P-256"
+                } else {
+                    "P-384"
+                }));
+                jwk.x = Some(URL_SAFE_NO_PAD.encode(x));
+                jwk.y = Some(URL_SAFE_NO_PAD.encode(y));
+            }
+            Algorithm::EdDSA => {
+                jwk.kty = String::from("OKP");
+                jwk.crv = Some(String::from("Ed25519"));
+                jwk.x = Some(URL_SAFE_NO_PAD.encode(subject_public_key(&der)?));
+            }
+            _ => {
+                let rsa_key = if pkcs1 {
+                    der.as_slice()
+                } else {
+                    subject_public_key(&der)?
+                };
+
+                let (content, _) = read_element(rsa_key, TAG_SEQUENCE)?;
+                let (n, rest) = read_element(content, TAG_INTEGER)?;
+                let (e, _) = read_element(rest, TAG_INTEGER)?;
+                jwk.kty = String::from("RSA");
+                jwk.n = Some(URL_SAFE_NO_PAD.encode(unsigned(n)));
+                jwk.e = Some(URL_SAFE_NO_PAD.encode(unsigned(e)));
+            }
+        }
+
+        jwk.kid = jwk.thumbprint();
+        Ok(jwk)
+    }
+
+    /// # Summary
+    ///
+    /// Compute the JWK thumbprint (RFC 7638), which is the SHA-256 hash of the required members of the key in
+    /// lexicographic order.
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The base64url-encoded thumbprint.
+    fn thumbprint(&self) -> String {
+        let members = match self.kty.as_str() {
+            "RSA" => format!(
+                r#"{{"e":"{}","kty":"RSA","n":"{}"}}"#,
+                self.e.as_deref().unwrap_or_default(),
+                self.n.as_deref().unwrap_or_default()
+            ),
+            "EC" => format!(
+                r#"{{"crv":"{}","kty":"EC","x":"{}","y":"{}"}}"#,
+                self.crv.as_deref().unwrap_or_default(),
+                self.x.as_deref().unwrap_or_default(),
+                self.y.as_deref().unwrap_or_default()
+            ),
+            _ => format!(
+                r#"{{"crv":"{}","kty":"{}","x":"{}"}}"#,
+                self.crv.as_deref().unwrap_or_default(),
+                self.kty,
+                self.x.as_deref().unwrap_or_default()
+            ),
+        };
+
+        URL_SAFE_NO_PAD.encode(Sha256::digest(members.as_bytes()))
+    }
+}
+
+/// # Summary
+///
+/// Extract the key bits of a DER-encoded SubjectPublicKeyInfo.
+///
+/// # Arguments
+///
+/// * `der` - The DER-encoded SubjectPublicKeyInfo.
+///
+/// # Returns
+///
+/// * `Result<&[u8], String>` - The contents of the subjectPublicKey bit string, or the reason why it cannot be read.
+fn subject_public_key(der: &[u8]) -> Result<&[u8], String> {
+    let (spki, _) = read_element(der, TAG_SEQUENCE)?;
+    let (_, rest) = read_element(spki, TAG_SEQUENCE)?;
+    let (bits, _) = read_element(rest, TAG_BIT_STRING)?;
+
+    // The first byte holds the amount of unused bits, which is always zero for keys
+    match bits.split_first() {
+        Some((0, key)) => Ok(key),
+        _ => Err(String::from("invalid public key bit string")),
+    }
+}
+
+/// # Summary
+///
+/// Read a DER element with the expected tag.
+///
+/// # Arguments
+///
+/// * `der` - The DER-encoded data that starts with the element.
+/// * `tag` - The expected tag of the element.
+///
+/// # Returns
+///
+/// * `Result<(&[u8], &[u8]), String>` - The contents of the element and the data that follows it, or the reason why the element cannot be read.
+fn read_element(der: &[u8], tag: u8) -> Result<(&[u8], &[u8]), String> {
+    let invalid = || String::from("invalid DER encoding");
+
+    if der.first() != Some(&tag) {
+        return Err(invalid());
+    }
+
+    let first = *der.get(1).ok_or_else(invalid)?;
+    let (length, offset) = if first < 0x80 {
+        (first as usize, 2)
+    } else {
+        let count = (first & 0x7f) as usize;
+        if count == 0 || count > 4 {
+            return Err(invalid());
+        }
+
+        let bytes = der.get(2..2 + count).ok_or_else(invalid)?;
+        let length = bytes.iter().fold(0usize, |l, b| (l << 8) | *b as usize);
+        (length, 2 + count)
+    };
+
+    let end = offset.checked_add(length).ok_or_else(invalid)?;
+    let content = der.get(offset..end).ok_or_else(invalid)?;
+    Ok((content, &der[end..]))
+}
+
+/// # Summary
+///
+/// Strip the leading zero bytes that DER adds to keep positive integers positive.
+///
+/// # Arguments
+///
+/// * `integer` - The contents of a DER integer.
+///
+/// # Returns
+///
+/// * `&[u8]` - The unsigned big-endian integer.
+fn unsigned(integer: &[u8]) -> &[u8] {
+    let start = integer
+        .iter()
+        .position(|b| *b != 0)
+        .unwrap_or(integer.len().saturating_sub(1));
+    &integer[start..]
+}
@@ -1,14 +1,44 @@
 use crate::configuration::jwt_config::JwtConfig;
-use jsonwebtoken::{encode, DecodingKey, EncodingKey, Header};
-use log::error;
+use crate::repository::refresh_token::refresh_token_model::RefreshToken;
+use crate::services::client::client_service::{generate_secret, hash_secret};
+use crate::services::jwt::claims_enricher::{ClaimsEnricher, RESERVED_CLAIMS};
+use crate::services::jwt::jwk::{Jwk, JwkSet};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use jsonwebtoken::{encode, Algorithm, DecodingKey, EncodingKey, Header};
+use log::{error, warn};
+use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
-use std::fmt::{Display, Formatter};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Claims {
     exp: usize,
     iat: usize,
     sub: String,
+    #[serde(default)]
+    ver: i64,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    cli: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tnt: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    gst: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scope: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    mfa: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    lnk: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    iss: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    aud: Option<String>,
+    #[serde(flatten)]
+    extra: Map<String, Value>,
 }
 
 impl Claims {
@@ -21,66 +51,382 @@ impl Claims {
     /// * `sub` - The subject of the Claims.
     /// * `exp` - The expiration time of the Claims.
     /// * `iat` - The issued at time of the Claims.
-    pub fn new(sub: String, exp: usize, iat: usize) -> Claims {
-        Claims { sub, exp, iat }
+    /// * `ver` - The token version of the subject at the time the Claims were issued.
+    /// * `extra` - The custom claims of the Claims.
+    pub fn new(sub: String, exp: usize, iat: usize, ver: i64, extra: Map<String, Value>) -> Claims {
+        Claims {
+            sub,
+            exp,
+            iat,
+            ver,
+            cli: false,
+            tnt: None,
+            gst: false,
+            scope: None,
+            mfa: false,
+            lnk: None,
+            iss: None,
+            aud: None,
+            extra,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Get the subject of the Claims.
+    ///
+    /// # Returns
+    ///
+    /// * `&str` - The subject of the Claims.
+    pub fn subject(&self) -> &str {
+        &self.sub
+    }
+
+    /// # Summary
+    ///
+    /// Get the time at which the Claims were issued.
+    ///
+    /// # Returns
+    ///
+    /// * `usize` - The issued at time of the Claims, in seconds since the Unix epoch.
+    pub fn issued_at(&self) -> usize {
+        self.iat
+    }
+
+    /// # Summary
+    ///
+    /// Get the time at which the Claims expire.
+    ///
+    /// # Returns
+    ///
+    /// * `usize` - The expiration time of the Claims, in seconds since the Unix epoch.
+    pub fn expires_at(&self) -> usize {
+        self.exp
+    }
+
+    /// # Summary
+    ///
+    /// Get the token version of the subject at the time the Claims were issued.
+    ///
+    /// # Returns
+    ///
+    /// * `i64` - The token version of the Claims.
+    pub fn token_version(&self) -> i64 {
+        self.ver
+    }
+
+    /// # Summary
+    ///
+    /// Check whether the Claims were issued to an OAuth client instead of a User.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the subject of the Claims is a Client.
+    pub fn is_client(&self) -> bool {
+        self.cli
+    }
+
+    /// # Summary
+    ///
+    /// Check whether the Claims were issued to an anonymous guest that has no User document.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the subject of the Claims is a guest.
+    pub fn is_guest(&self) -> bool {
+        self.gst
+    }
+
+    /// # Summary
+    ///
+    /// Get the ID of the Organization that was active when the Claims were issued.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<&str>` - The ID of the active Organization, or None if the Claims are not scoped to a tenant.
+    pub fn tenant(&self) -> Option<&str> {
+        self.tnt.as_deref()
+    }
+
+    /// # Summary
+    ///
+    /// Get the space-separated OAuth 2.0 scopes that were granted when the Claims were issued.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<&str>` - The scopes of the Claims, or None if the Claims were not issued through an OAuth 2.0 grant with a scope.
+    pub fn scope(&self) -> Option<&str> {
+        self.scope.as_deref()
+    }
+
+    /// # Summary
+    ///
+    /// Get the ID of the MagicLink that the Claims were issued for.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<&str>` - The ID of the MagicLink, or None if the Claims are not a magic link token.
+    pub fn magic_link(&self) -> Option<&str> {
+        self.lnk.as_deref()
     }
 }
 
+#[derive(Debug, thiserror::Error)]
 pub enum Error {
+    #[error("Invalid token: {0}")]
     InvalidToken(String),
+    #[error("Invalid configuration: {0}")]
+    InvalidConfiguration(String),
+}
+
+#[derive(Clone)]
+struct VerificationKey {
+    kid: String,
+    algorithm: Algorithm,
+    decoding_key: DecodingKey,
+    jwk: Option<Jwk>,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl VerificationKey {
+    /// # Summary
+    ///
+    /// Check whether tokens that were signed with the key are still accepted.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the key is the current key or its grace period has not ended yet, false otherwise.
+    fn is_valid(&self) -> bool {
+        self.expires_at.map_or(true, |e| e > Utc::now())
+    }
+}
+
+#[derive(Clone)]
+pub struct JwtService {
+    pub jwt_config: JwtConfig,
+    pub claims_enrichers: Vec<Arc<dyn ClaimsEnricher>>,
+    encoding_key: Option<EncodingKey>,
+    // The current key comes first, followed by the previous keys
+    verification_keys: Vec<VerificationKey>,
 }
 
-impl Display for Error {
+impl JwtService {
     /// # Summary
     ///
-    /// Display the Error.
+    /// Create a new JwtService. The keys of an asymmetric algorithm are parsed once, so that invalid keys are reported
+    /// on startup instead of when the first token is issued.
     ///
     /// # Arguments
     ///
-    /// * `f` - The Formatter.
+    /// * `jwt_config` - The configuration for the JwtService.
     ///
     /// # Example
     ///
     /// ```
-    /// let error = Error::InvalidToken(String::from("message"));
-    /// println!("{}", error);
+    /// let jwt_service = JwtService::new(jwt_config)?;
     /// ```
     ///
     /// # Returns
     ///
-    /// * `std::fmt::Result` - The result of the operation.
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Error::InvalidToken(message) => write!(f, "Invalid token: {}", message),
+    /// * `Result<JwtService, Error>` - The new JwtService, or the InvalidConfiguration error if a key cannot be parsed.
+    pub fn new(jwt_config: JwtConfig) -> Result<JwtService, Error> {
+        let encoding_key = if jwt_config.is_asymmetric() {
+            match &jwt_config.jwt_private_key {
+                Some(k) => Some(
+                    Self::parse_private_key(jwt_config.jwt_algorithm, k.as_bytes()).map_err(
+                        |e| Error::InvalidConfiguration(format!("invalid private key: {}", e)),
+                    )?,
+                ),
+                None => None,
+            }
+        } else {
+            Some(EncodingKey::from_secret(jwt_config.jwt_secret.as_bytes()))
+        };
+
+        let mut verification_keys = vec![Self::verification_key(
+            jwt_config.jwt_algorithm,
+            Some(&jwt_config.jwt_secret),
+            jwt_config.jwt_public_key.as_ref(),
+            None,
+        )?];
+
+        for k in &jwt_config.jwt_previous_keys {
+            let key = Self::verification_key(
+                k.algorithm.unwrap_or(jwt_config.jwt_algorithm),
+                k.secret.as_ref(),
+                k.public_key.as_ref(),
+                Some(k.expires_at),
+            )
+            .map_err(|e| Error::InvalidConfiguration(format!("previous key: {}", e)))?;
+
+            verification_keys.push(key);
         }
+
+        Ok(JwtService {
+            jwt_config,
+            claims_enrichers: vec![],
+            encoding_key,
+            verification_keys,
+        })
     }
-}
 
-#[derive(Clone)]
-pub struct JwtService {
-    pub jwt_config: JwtConfig,
-}
+    /// # Summary
+    ///
+    /// Create a key that tokens can be verified with. The key ID of a public key is its JWK thumbprint, while the key ID
+    /// of an HMAC secret is derived from a hash of the secret, so that the secret itself is never revealed.
+    ///
+    /// # Arguments
+    ///
+    /// * `algorithm` - The algorithm.
+    /// * `secret` - The HMAC secret, which is required for HMAC algorithms.
+    /// * `public_key` - The PEM-encoded public key, which is required for asymmetric algorithms.
+    /// * `expires_at` - The end of the grace period of a previous key, or None for the current key.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<VerificationKey, Error>` - The VerificationKey, or the InvalidConfiguration error if the key is missing or cannot be parsed.
+    fn verification_key(
+        algorithm: Algorithm,
+        secret: Option<&String>,
+        public_key: Option<&String>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<VerificationKey, Error> {
+        if matches!(
+            algorithm,
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+        ) {
+            let secret = match secret {
+                Some(s) => s,
+                None => {
+                    return Err(Error::InvalidConfiguration(format!(
+                        "{:?} requires a secret",
+                        algorithm
+                    )))
+                }
+            };
+
+            // Shared secrets must never be published, so there is no JWK for HMAC algorithms
+            let hash = URL_SAFE_NO_PAD.encode(Sha256::digest(secret.as_bytes()));
+            return Ok(VerificationKey {
+                kid: format!("hs-{}", &hash[..16]),
+                algorithm,
+                decoding_key: DecodingKey::from_secret(secret.as_bytes()),
+                jwk: None,
+                expires_at,
+            });
+        }
+
+        let public_key = match public_key {
+            Some(k) => k,
+            None => {
+                return Err(Error::InvalidConfiguration(format!(
+                    "{:?} requires a public key",
+                    algorithm
+                )))
+            }
+        };
+
+        let decoding_key = Self::parse_public_key(algorithm, public_key.as_bytes())
+            .map_err(|e| Error::InvalidConfiguration(format!("invalid public key: {}", e)))?;
+        let jwk = Jwk::from_public_key_pem(algorithm, public_key)
+            .map_err(|e| Error::InvalidConfiguration(format!("invalid public key: {}", e)))?;
+
+        Ok(VerificationKey {
+            kid: jwk.kid.clone(),
+            algorithm,
+            decoding_key,
+            jwk: Some(jwk),
+            expires_at,
+        })
+    }
 
-impl JwtService {
     /// # Summary
     ///
-    /// Create a new JwtService.
+    /// Parse a PEM-encoded private key for an asymmetric algorithm.
     ///
     /// # Arguments
     ///
-    /// * `jwt_config` - The configuration for the JwtService.
+    /// * `algorithm` - The algorithm.
+    /// * `pem` - The PEM-encoded private key.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<EncodingKey, jsonwebtoken::errors::Error>` - The EncodingKey, or the error that occurred.
+    fn parse_private_key(
+        algorithm: Algorithm,
+        pem: &[u8],
+    ) -> Result<EncodingKey, jsonwebtoken::errors::Error> {
+        match algorithm {
+            Algorithm::ES256 | Algorithm::ES384 => EncodingKey::from_ec_pem(pem),
+            Algorithm::EdDSA => EncodingKey::from_ed_pem(pem),
+            _ => EncodingKey::from_rsa_pem(pem),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Parse a PEM-encoded public key for an asymmetric algorithm.
+    ///
+    /// # Arguments
+    ///
+    /// * `algorithm` - The algorithm.
+    /// * `pem` - The PEM-encoded public key.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<DecodingKey, jsonwebtoken::errors::Error>` - The DecodingKey, or the error that occurred.
+    fn parse_public_key(
+        algorithm: Algorithm,
+        pem: &[u8],
+    ) -> Result<DecodingKey, jsonwebtoken::errors::Error> {
+        match algorithm {
+            Algorithm::ES256 | Algorithm::ES384 => DecodingKey::from_ec_pem(pem),
+            Algorithm::EdDSA => DecodingKey::from_ed_pem(pem),
+            _ => DecodingKey::from_rsa_pem(pem),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Get the JSON Web Key Set that resource servers can use to verify the tokens that are issued by the JwtService.
+    /// Previous public keys are included until their grace period ends. The set is empty if tokens are signed with a
+    /// shared secret.
     ///
     /// # Example
     ///
     /// ```
-    /// let jwt_service = JwtService::new(jwt_config);
+    /// let jwks = jwt_service.jwks();
     /// ```
     ///
     /// # Returns
     ///
-    /// * `JwtService` - The new JwtService.
-    pub fn new(jwt_config: JwtConfig) -> JwtService {
-        JwtService { jwt_config }
+    /// * `JwkSet` - The public keys of the JwtService.
+    pub fn jwks(&self) -> JwkSet {
+        JwkSet {
+            keys: self
+                .verification_keys
+                .iter()
+                .filter(|k| k.is_valid())
+                .filter_map(|k| k.jwk.clone())
+                .collect(),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Register a ClaimsEnricher that is invoked whenever a JWT token is generated.
+    ///
+    /// # Arguments
+    ///
+    /// * `claims_enricher` - The ClaimsEnricher to register.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// jwt_service.register_claims_enricher(Arc::new(TenantEnricher));
+    /// ```
+    pub fn register_claims_enricher(&mut self, claims_enricher: Arc<dyn ClaimsEnricher>) {
+        self.claims_enrichers.push(claims_enricher);
     }
 
     /// # Summary
@@ -90,32 +436,423 @@ impl JwtService {
     /// # Arguments
     ///
     /// * `subject` - The subject of the JWT token.
+    /// * `token_version` - The token version of the subject. Tokens are only valid while it is unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let token = jwt_service.generate_jwt_token("subject", user.token_version);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Option<String>` - The JWT token.
+    pub fn generate_jwt_token(&self, subject: &str, token_version: i64) -> Option<String> {
+        self.generate(subject, token_version, false, None, None)
+    }
+
+    /// # Summary
+    ///
+    /// Generate a JWT token that is scoped to an Organization. Only the roles that the subject holds within the
+    /// Organization apply to the token.
+    ///
+    /// # Arguments
+    ///
+    /// * `subject` - The subject of the JWT token.
+    /// * `token_version` - The token version of the subject. Tokens are only valid while it is unchanged.
+    /// * `tenant` - The ID of the Organization.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let token = jwt_service.generate_tenant_token("subject", user.token_version, &organization.id.to_hex());
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Option<String>` - The JWT token.
+    pub fn generate_tenant_token(
+        &self,
+        subject: &str,
+        token_version: i64,
+        tenant: &str,
+    ) -> Option<String> {
+        self.generate(subject, token_version, false, Some(tenant), None)
+    }
+
+    /// # Summary
+    ///
+    /// Generate a JWT token for a User that carries the OAuth 2.0 scopes that the User granted to a Client.
+    ///
+    /// # Arguments
+    ///
+    /// * `subject` - The subject of the JWT token.
+    /// * `token_version` - The token version of the subject. Tokens are only valid while it is unchanged.
+    /// * `scope` - The space-separated scopes, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let token = jwt_service.generate_scoped_token("subject", user.token_version, Some("profile email"));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Option<String>` - The JWT token.
+    pub fn generate_scoped_token(
+        &self,
+        subject: &str,
+        token_version: i64,
+        scope: Option<&str>,
+    ) -> Option<String> {
+        self.generate(subject, token_version, false, None, scope)
+    }
+
+    /// # Summary
+    ///
+    /// Generate a JWT token for an OAuth client. ClaimsEnrichers are not invoked for client tokens.
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - The ID of the Client.
+    /// * `token_version` - The token version of the Client. Tokens are only valid while it is unchanged.
+    /// * `scope` - The space-separated scopes that were granted to the Client, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let token = jwt_service.generate_client_token(&client.id.to_hex(), client.token_version, Some("reports:read"));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Option<String>` - The JWT token.
+    pub fn generate_client_token(
+        &self,
+        client_id: &str,
+        token_version: i64,
+        scope: Option<&str>,
+    ) -> Option<String> {
+        self.generate(client_id, token_version, true, None, scope)
+    }
+
+    /// # Summary
+    ///
+    /// Generate a JWT token for an anonymous guest. Guest tokens have their own lifetime and ClaimsEnrichers are not
+    /// invoked for them, because there is no User that could be enriched.
+    ///
+    /// # Arguments
+    ///
+    /// * `subject` - The subject of the JWT token.
+    /// * `expiration` - The amount of seconds after which the JWT token expires.
     ///
     /// # Example
     ///
     /// ```
-    /// let token = jwt_service.generate_jwt_token("subject");
+    /// let token = jwt_service.generate_guest_token("guest:subject", 900);
     /// ```
     ///
     /// # Returns
     ///
     /// * `Option<String>` - The JWT token.
-    pub fn generate_jwt_token(&self, subject: &str) -> Option<String> {
+    pub fn generate_guest_token(&self, subject: &str, expiration: usize) -> Option<String> {
+        let now = chrono::Utc::now();
+        let exp = now + chrono::Duration::seconds(expiration as i64);
+
+        let mut claims = Claims::new(
+            String::from(subject),
+            exp.timestamp() as usize,
+            now.timestamp() as usize,
+            0,
+            Map::new(),
+        );
+        claims.gst = true;
+
+        self.sign(claims)
+    }
+
+    /// # Summary
+    ///
+    /// Generate a short-lived MFA challenge token for a User that provided a valid password but still has to provide a
+    /// second factor. The token can only be exchanged for a full token and is rejected by decode_jwt_token. ClaimsEnrichers
+    /// are not invoked for it.
+    ///
+    /// # Arguments
+    ///
+    /// * `subject` - The ID of the User.
+    /// * `token_version` - The token version of the User.
+    /// * `expiration` - The amount of seconds after which the challenge token expires.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let challenge = jwt_service.generate_mfa_challenge("user_id", 0, 300);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Option<String>` - The MFA challenge token.
+    pub fn generate_mfa_challenge(
+        &self,
+        subject: &str,
+        token_version: i64,
+        expiration: u64,
+    ) -> Option<String> {
+        let now = chrono::Utc::now();
+        let exp = now + chrono::Duration::seconds(expiration as i64);
+
+        let mut claims = Claims::new(
+            String::from(subject),
+            exp.timestamp() as usize,
+            now.timestamp() as usize,
+            token_version,
+            Map::new(),
+        );
+        claims.mfa = true;
+
+        self.sign(claims)
+    }
+
+    /// # Summary
+    ///
+    /// Generate a magic link token that lets a User log in without a password. The token refers to a MagicLink that
+    /// can only be used once, and is rejected by decode_jwt_token. ClaimsEnrichers are not invoked for it.
+    ///
+    /// # Arguments
+    ///
+    /// * `subject` - The ID of the User.
+    /// * `token_version` - The token version of the User.
+    /// * `link_id` - The ID of the MagicLink.
+    /// * `expiration` - The amount of seconds after which the magic link token expires.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let token = jwt_service.generate_magic_link("user_id", 0, "link_id", 900);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Option<String>` - The magic link token.
+    pub fn generate_magic_link(
+        &self,
+        subject: &str,
+        token_version: i64,
+        link_id: &str,
+        expiration: u64,
+    ) -> Option<String> {
+        let now = chrono::Utc::now();
+        let exp = now + chrono::Duration::seconds(expiration as i64);
+
+        let mut claims = Claims::new(
+            String::from(subject),
+            exp.timestamp() as usize,
+            now.timestamp() as usize,
+            token_version,
+            Map::new(),
+        );
+        claims.lnk = Some(String::from(link_id));
+
+        self.sign(claims)
+    }
+
+    /// # Summary
+    ///
+    /// Check whether refresh tokens are issued alongside access tokens.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if refresh tokens are enabled, false otherwise.
+    pub fn refresh_tokens_enabled(&self) -> bool {
+        self.jwt_config.jwt_refresh_expiration > 0
+    }
+
+    /// # Summary
+    ///
+    /// Generate a refresh token for a User that just logged in. The refresh token starts a new family.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User.
+    /// * `token_version` - The token version of the User. The refresh token is only valid while it is unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let (refresh_token, token) = jwt_service.generate_refresh_token(user.id, user.token_version)?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Option<(RefreshToken, String)>` - The RefreshToken that should be stored and the token that is handed to the client, or None if refresh tokens are disabled.
+    pub fn generate_refresh_token(
+        &self,
+        user_id: ObjectId,
+        token_version: i64,
+    ) -> Option<(RefreshToken, String)> {
+        self.issue_refresh_token(user_id, ObjectId::new(), token_version)
+    }
+
+    /// # Summary
+    ///
+    /// Generate the successor of a refresh token that was used. The successor belongs to the same family and token
+    /// version, and expires `jwt_refresh_expiration` seconds after the rotation.
+    ///
+    /// # Arguments
+    ///
+    /// * `used` - The RefreshToken that was used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let (refresh_token, token) = jwt_service.rotate_refresh_token(&used)?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Option<(RefreshToken, String)>` - The RefreshToken that should be stored and the token that is handed to the client, or None if refresh tokens are disabled.
+    pub fn rotate_refresh_token(&self, used: &RefreshToken) -> Option<(RefreshToken, String)> {
+        self.issue_refresh_token(used.user_id, used.family, used.token_version)
+    }
+
+    /// # Summary
+    ///
+    /// Generate a random refresh token. Only the hash of the token is stored.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User.
+    /// * `family` - The ID of the family of the refresh token.
+    /// * `token_version` - The token version of the User.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<(RefreshToken, String)>` - The RefreshToken and the token, or None if refresh tokens are disabled.
+    fn issue_refresh_token(
+        &self,
+        user_id: ObjectId,
+        family: ObjectId,
+        token_version: i64,
+    ) -> Option<(RefreshToken, String)> {
+        if !self.refresh_tokens_enabled() {
+            return None;
+        }
+
+        let token = generate_secret();
+        let mut refresh_token = RefreshToken::new(
+            user_id,
+            family,
+            token_version,
+            self.jwt_config.jwt_refresh_expiration,
+        );
+        refresh_token.token_hash = hash_secret(&token);
+
+        Some((refresh_token, token))
+    }
+
+    /// # Summary
+    ///
+    /// Generate a JWT token for a User or a Client.
+    ///
+    /// # Arguments
+    ///
+    /// * `subject` - The subject of the JWT token.
+    /// * `token_version` - The token version of the subject.
+    /// * `client` - Whether the subject is a Client.
+    /// * `tenant` - The ID of the active Organization, if any.
+    /// * `scope` - The space-separated OAuth 2.0 scopes, if any.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<String>` - The JWT token.
+    fn generate(
+        &self,
+        subject: &str,
+        token_version: i64,
+        client: bool,
+        tenant: Option<&str>,
+        scope: Option<&str>,
+    ) -> Option<String> {
         let now = chrono::Utc::now();
         let exp = now + chrono::Duration::seconds(self.jwt_config.jwt_expiration as i64);
         let iat = now;
 
-        let claims = Claims::new(
+        let mut extra: Map<String, Value> = Map::new();
+        if !client {
+            for e in &self.claims_enrichers {
+                e.enrich(subject, &mut extra);
+            }
+        }
+
+        for c in RESERVED_CLAIMS {
+            if extra.remove(*c).is_some() {
+                warn!(
+                    "Ignoring reserved claim {} that was added by a ClaimsEnricher",
+                    c
+                );
+            }
+        }
+
+        let mut claims = Claims::new(
             String::from(subject),
             exp.timestamp() as usize,
             iat.timestamp() as usize,
+            token_version,
+            extra,
         );
+        claims.cli = client;
+        claims.tnt = tenant.map(String::from);
+        claims.scope = scope.filter(|s| !s.is_empty()).map(String::from);
 
-        match encode(
-            &Header::default(),
-            &claims,
-            &EncodingKey::from_secret(self.jwt_config.jwt_secret.as_bytes()),
-        ) {
+        self.sign(claims)
+    }
+
+    /// # Summary
+    ///
+    /// Sign Claims with the configured secret or private key. The configured issuer and audience are added to the Claims.
+    /// Claims that were issued earlier, such as those of a Session, can be signed again this way.
+    ///
+    /// # Arguments
+    ///
+    /// * `claims` - The Claims to sign.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let token = jwt_service.sign(claims);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Option<String>` - The JWT token, or None if the JwtService can only verify tokens.
+    pub fn sign(&self, mut claims: Claims) -> Option<String> {
+        let encoding_key = match &self.encoding_key {
+            Some(k) => k,
+            None => {
+                error!("Error generating JWT token: no private key is configured");
+                return None;
+            }
+        };
+
+        // The key ID allows resource servers to select the matching key from the JWKS
+        let mut header = Header::new(self.jwt_config.jwt_algorithm);
+        header.kid = Some(self.verification_keys[0].kid.clone());
+
+        // Custom claims must not contradict the configured issuer and audience
+        if let Some(iss) = &self.jwt_config.jwt_issuer {
+            if claims.extra.remove("iss").is_some() {
+                warn!("Ignoring custom iss claim in favor of the configured issuer");
+            }
+            claims.iss = Some(iss.clone());
+        }
+        if let Some(aud) = &self.jwt_config.jwt_audience {
+            if claims.extra.remove("aud").is_some() {
+                warn!("Ignoring custom aud claim in favor of the configured audience");
+            }
+            claims.aud = Some(aud.clone());
+        }
+
+        match encode(&header, &claims, encoding_key) {
             Ok(t) => Some(t),
             Err(e) => {
                 error!("Error generating JWT token: {}", e.to_string());
@@ -124,6 +861,69 @@ impl JwtService {
         }
     }
 
+    /// # Summary
+    ///
+    /// Check whether the JwtService is configured in a way that allows it to issue tokens that it can verify again. The
+    /// signing key must be present, tokens must not expire immediately and a token that is signed with the key must be
+    /// verifiable with the secret or public key. ClaimsEnrichers are not invoked for the token that is used to check the key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// if let Err(e) = jwt_service.check_configuration() {
+    ///     warn!("The JWT configuration is invalid: {}", e);
+    /// }
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The InvalidConfiguration error that describes the problem, if any.
+    pub fn check_configuration(&self) -> Result<(), Error> {
+        if !self.jwt_config.is_asymmetric() && self.jwt_config.jwt_secret.is_empty() {
+            return Err(Error::InvalidConfiguration(String::from(
+                "the signing key is empty",
+            )));
+        }
+
+        if self.encoding_key.is_none() {
+            return Err(Error::InvalidConfiguration(String::from(
+                "no private key is configured",
+            )));
+        }
+
+        if self.jwt_config.jwt_expiration == 0 {
+            return Err(Error::InvalidConfiguration(String::from(
+                "tokens expire immediately",
+            )));
+        }
+
+        let now = chrono::Utc::now();
+        let exp = now + chrono::Duration::seconds(self.jwt_config.jwt_expiration as i64);
+        let claims = Claims::new(
+            String::from("healthcheck"),
+            exp.timestamp() as usize,
+            now.timestamp() as usize,
+            0,
+            Map::new(),
+        );
+
+        let token = match self.sign(claims) {
+            Some(t) => t,
+            None => {
+                return Err(Error::InvalidConfiguration(String::from(
+                    "tokens cannot be signed with the signing key",
+                )))
+            }
+        };
+
+        match self.decode_jwt_token(&token) {
+            Ok(c) if c.subject() == "healthcheck" => Ok(()),
+            _ => Err(Error::InvalidConfiguration(String::from(
+                "tokens that are signed with the signing key cannot be verified",
+            ))),
+        }
+    }
+
     /// # Summary
     ///
     /// Verify a JWT token.
@@ -142,18 +942,148 @@ impl JwtService {
     ///
     /// * `Result<String, Error>` - The result of the operation.
     pub fn verify_jwt_token(&self, token: &str) -> Result<String, Error> {
-        let token_data = jsonwebtoken::decode::<Claims>(
-            token,
-            &DecodingKey::from_secret(self.jwt_config.jwt_secret.as_bytes()),
-            &jsonwebtoken::Validation::default(),
-        );
+        self.decode_jwt_token(token).map(|c| c.sub)
+    }
+
+    /// # Summary
+    ///
+    /// Verify a JWT token and return all of its Claims. The key is selected by the `kid` header of the token, so that
+    /// tokens that were signed with a previous key remain valid until its grace period ends. Tokens without a `kid`
+    /// were issued before keys were identified and are verified with every key that is still valid.
+    /// The `exp` and `nbf` claims are validated with the configured leeway to tolerate clock skew between nodes. If an
+    /// issuer or audience is configured, tokens without a matching `iss` or `aud` claim are rejected.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The JWT token to verify.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let claims = jwt_service.decode_jwt_token("token");
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Claims, Error>` - The result of the operation.
+    pub fn decode_jwt_token(&self, token: &str) -> Result<Claims, Error> {
+        let claims = self.decode(token)?;
+        if claims.mfa {
+            error!(
+                "Error verifying JWT token: MFA challenge tokens cannot be used for authentication"
+            );
+            return Err(Error::InvalidToken(String::from("MFA challenge token")));
+        }
+        if claims.lnk.is_some() {
+            error!(
+                "Error verifying JWT token: magic link tokens cannot be used for authentication"
+            );
+            return Err(Error::InvalidToken(String::from("magic link token")));
+        }
+
+        Ok(claims)
+    }
+
+    /// # Summary
+    ///
+    /// Verify an MFA challenge token that was issued by generate_mfa_challenge.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The MFA challenge token to verify.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let claims = jwt_service.decode_mfa_challenge("token");
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Claims, Error>` - The result of the operation.
+    pub fn decode_mfa_challenge(&self, token: &str) -> Result<Claims, Error> {
+        let claims = self.decode(token)?;
+        if !claims.mfa {
+            return Err(Error::InvalidToken(String::from(
+                "not an MFA challenge token",
+            )));
+        }
+
+        Ok(claims)
+    }
+
+    /// # Summary
+    ///
+    /// Verify a magic link token that was issued by generate_magic_link.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The magic link token to verify.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let claims = jwt_service.decode_magic_link("token");
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Claims, Error>` - The result of the operation.
+    pub fn decode_magic_link(&self, token: &str) -> Result<Claims, Error> {
+        let claims = self.decode(token)?;
+        if claims.lnk.is_none() {
+            return Err(Error::InvalidToken(String::from("not a magic link token")));
+        }
 
-        match token_data {
-            Ok(t) => Ok(t.claims.sub),
+        Ok(claims)
+    }
+
+    /// # Summary
+    ///
+    /// Verify the signature, expiry, issuer and audience of a JWT token.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The JWT token to verify.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Claims, Error>` - The Claims of the token.
+    fn decode(&self, token: &str) -> Result<Claims, Error> {
+        let kid = match jsonwebtoken::decode_header(token) {
+            Ok(h) => h.kid,
             Err(e) => {
                 error!("Error verifying JWT token: {}", e.to_string());
-                Err(Error::InvalidToken(e.to_string()))
+                return Err(Error::InvalidToken(e.to_string()));
+            }
+        };
+
+        let mut reason = String::from("unknown or expired key");
+        let keys = self
+            .verification_keys
+            .iter()
+            .filter(|k| kid.is_none() || kid.as_ref() == Some(&k.kid))
+            .filter(|k| k.is_valid());
+
+        for key in keys {
+            // Every key is bound to its algorithm, so that a token cannot choose how it is verified
+            let mut validation = jsonwebtoken::Validation::new(key.algorithm);
+            validation.leeway = self.jwt_config.jwt_leeway;
+            validation.validate_nbf = true;
+            if let Some(iss) = &self.jwt_config.jwt_issuer {
+                validation.set_issuer(&[iss]);
+            }
+            if let Some(aud) = &self.jwt_config.jwt_audience {
+                validation.set_audience(&[aud]);
+            }
+
+            match jsonwebtoken::decode::<Claims>(token, &key.decoding_key, &validation) {
+                Ok(t) => return Ok(t.claims),
+                Err(e) => reason = e.to_string(),
             }
         }
+
+        error!("Error verifying JWT token: {}", reason);
+        Err(Error::InvalidToken(reason))
     }
 }
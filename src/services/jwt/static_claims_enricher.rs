@@ -0,0 +1,48 @@
+use crate::services::jwt::claims_enricher::ClaimsEnricher;
+use serde_json::{Map, Value};
+
+pub struct StaticClaimsEnricher {
+    pub claims: Map<String, Value>,
+}
+
+impl StaticClaimsEnricher {
+    /// # Summary
+    ///
+    /// Create a new StaticClaimsEnricher.
+    ///
+    /// # Arguments
+    ///
+    /// * `claims` - The claims that will be added to every JWT token.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut claims = Map::new();
+    /// claims.insert(String::from("tenant"), Value::from("opserva"));
+    ///
+    /// let static_claims_enricher = StaticClaimsEnricher::new(claims);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `StaticClaimsEnricher` - The new StaticClaimsEnricher.
+    pub fn new(claims: Map<String, Value>) -> StaticClaimsEnricher {
+        StaticClaimsEnricher { claims }
+    }
+}
+
+impl ClaimsEnricher for StaticClaimsEnricher {
+    /// # Summary
+    ///
+    /// Add the static claims to a JWT token.
+    ///
+    /// # Arguments
+    ///
+    /// * `_subject` - The subject of the JWT token.
+    /// * `claims` - The custom claims of the JWT token.
+    fn enrich(&self, _subject: &str, claims: &mut Map<String, Value>) {
+        for (k, v) in &self.claims {
+            claims.insert(k.clone(), v.clone());
+        }
+    }
+}
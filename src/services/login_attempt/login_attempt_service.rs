@@ -0,0 +1,315 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// The maximum amount of time that the verification of a CAPTCHA may take.
+#[cfg(feature = "integrations")]
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The amount of tracked usernames and IP addresses after which expired entries are removed.
+const PRUNE_THRESHOLD: usize = 10_000;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("A CAPTCHA is required after too many failed logins")]
+    CaptchaRequired,
+    #[error("The CAPTCHA is invalid")]
+    InvalidCaptcha,
+    #[error("Failed to verify CAPTCHA: {0}")]
+    Request(String),
+}
+
+#[derive(Deserialize)]
+struct VerifyResponse {
+    success: bool,
+}
+
+struct FailedAttempts {
+    first_failure_at: Instant,
+    failures: u32,
+}
+
+#[derive(Clone)]
+pub struct LoginAttemptService {
+    pub captcha_secret: Option<String>,
+    pub captcha_verify_url: String,
+    pub threshold: u32,
+    pub window: Duration,
+    #[cfg(feature = "integrations")]
+    client: reqwest::Client,
+    attempts: Arc<Mutex<HashMap<String, FailedAttempts>>>,
+}
+
+impl LoginAttemptService {
+    /// # Summary
+    ///
+    /// Create a new LoginAttemptService.
+    ///
+    /// # Arguments
+    ///
+    /// * `captcha_secret` - The secret key of the CAPTCHA provider, or None to disable CAPTCHA escalation.
+    /// * `captcha_verify_url` - The URL at which CAPTCHA responses are verified.
+    /// * `threshold` - The amount of failed logins of a username or IP address after which a CAPTCHA is required.
+    /// * `window` - The time after which the failed logins of a username or IP address are forgotten.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let login_attempt_service = LoginAttemptService::new(Some(String::from("secret")), String::from("https://hcaptcha.com/siteverify"), 3, Duration::from_secs(900));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `LoginAttemptService` - The new LoginAttemptService.
+    pub fn new(
+        captcha_secret: Option<String>,
+        captcha_verify_url: String,
+        threshold: u32,
+        window: Duration,
+    ) -> LoginAttemptService {
+        LoginAttemptService {
+            captcha_secret,
+            captcha_verify_url,
+            threshold,
+            window,
+            #[cfg(feature = "integrations")]
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            attempts: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check whether a login may be attempted. Once a username or IP address has failed to log in `threshold` times
+    /// within the window, every login of that username or from that IP address requires a valid CAPTCHA, so that
+    /// guessing passwords is slowed down without locking the legitimate owner out of the account.
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - The username of the login.
+    /// * `source_ip` - The IP address that the login originates from.
+    /// * `captcha` - The CAPTCHA response that was sent with the login.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// login_attempt_service.check("admin", Some("203.0.113.7"), login_request.captcha.as_deref()).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - Ok if the login may be attempted, or the reason why it may not.
+    pub async fn check(
+        &self,
+        username: &str,
+        source_ip: Option<&str>,
+        captcha: Option<&str>,
+    ) -> Result<(), Error> {
+        let secret = match &self.captcha_secret {
+            Some(s) => s,
+            None => return Ok(()),
+        };
+
+        if !self.requires_captcha(username, source_ip) {
+            return Ok(());
+        }
+
+        let captcha = match captcha {
+            Some(c) if !c.trim().is_empty() => c,
+            _ => return Err(Error::CaptchaRequired),
+        };
+
+        let mut form = vec![("secret", secret.as_str()), ("response", captcha)];
+        if let Some(ip) = source_ip {
+            form.push(("remoteip", ip));
+        }
+
+        match self.verify(&form).await?.success {
+            true => Ok(()),
+            false => Err(Error::InvalidCaptcha),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Verify a CAPTCHA response at the CAPTCHA provider.
+    ///
+    /// # Arguments
+    ///
+    /// * `form` - The form that is posted to the provider.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<VerifyResponse, Error>` - The verdict of the provider, or the Error that occurred.
+    #[cfg(feature = "integrations")]
+    async fn verify(&self, form: &[(&str, &str)]) -> Result<VerifyResponse, Error> {
+        self.client
+            .post(&self.captcha_verify_url)
+            .form(form)
+            .send()
+            .await
+            .map_err(|e| Error::Request(e.to_string()))?
+            .json::<VerifyResponse>()
+            .await
+            .map_err(|e| Error::Request(e.to_string()))
+    }
+
+    /// # Summary
+    ///
+    /// Reject every CAPTCHA response, since the CAPTCHA provider cannot be called if the `integrations` feature is
+    /// disabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `_form` - The form that would be posted to the provider.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<VerifyResponse, Error>` - Always a Request error.
+    #[cfg(not(feature = "integrations"))]
+    async fn verify(&self, _form: &[(&str, &str)]) -> Result<VerifyResponse, Error> {
+        Err(Error::Request(String::from(
+            "the integrations feature is disabled",
+        )))
+    }
+
+    /// # Summary
+    ///
+    /// Record a failed login of a username from an IP address.
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - The username of the login.
+    /// * `source_ip` - The IP address that the login originated from.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// login_attempt_service.record_failure("admin", Some("203.0.113.7"));
+    /// ```
+    pub fn record_failure(&self, username: &str, source_ip: Option<&str>) {
+        if self.captcha_secret.is_none() {
+            return;
+        }
+
+        let now = Instant::now();
+        let mut attempts = match self.attempts.lock() {
+            Ok(a) => a,
+            Err(e) => e.into_inner(),
+        };
+
+        if attempts.len() > PRUNE_THRESHOLD {
+            attempts.retain(|_, a| now.duration_since(a.first_failure_at) < self.window);
+        }
+
+        for key in Self::keys(username, source_ip) {
+            let entry = attempts.entry(key).or_insert(FailedAttempts {
+                first_failure_at: now,
+                failures: 0,
+            });
+
+            if now.duration_since(entry.first_failure_at) >= self.window {
+                entry.first_failure_at = now;
+                entry.failures = 0;
+            }
+
+            entry.failures = entry.failures.saturating_add(1);
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Forget the failed logins of a username after it logged in successfully. The failed logins of the IP address
+    /// are kept, so that a single valid account cannot be used to reset the counter of an attacker.
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - The username that logged in.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// login_attempt_service.record_success("admin");
+    /// ```
+    pub fn record_success(&self, username: &str) {
+        if self.captcha_secret.is_none() {
+            return;
+        }
+
+        match self.attempts.lock() {
+            Ok(mut a) => a.remove(&Self::username_key(username)),
+            Err(e) => e.into_inner().remove(&Self::username_key(username)),
+        };
+    }
+
+    /// # Summary
+    ///
+    /// Check whether the username or the IP address of a login has failed to log in too often.
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - The username of the login.
+    /// * `source_ip` - The IP address that the login originates from.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if a CAPTCHA is required.
+    fn requires_captcha(&self, username: &str, source_ip: Option<&str>) -> bool {
+        let now = Instant::now();
+        let attempts = match self.attempts.lock() {
+            Ok(a) => a,
+            Err(e) => e.into_inner(),
+        };
+
+        Self::keys(username, source_ip)
+            .iter()
+            .any(|k| match attempts.get(k) {
+                Some(a) => {
+                    now.duration_since(a.first_failure_at) < self.window
+                        && a.failures >= self.threshold
+                }
+                None => false,
+            })
+    }
+
+    /// # Summary
+    ///
+    /// Get the keys under which the failed logins of a username and an IP address are tracked.
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - The username of the login.
+    /// * `source_ip` - The IP address of the login.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<String>` - The keys.
+    fn keys(username: &str, source_ip: Option<&str>) -> Vec<String> {
+        let mut keys = vec![Self::username_key(username)];
+        if let Some(ip) = source_ip {
+            keys.push(format!("ip:{}", ip));
+        }
+
+        keys
+    }
+
+    /// # Summary
+    ///
+    /// Get the key under which the failed logins of a username are tracked. Usernames are case-insensitive.
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - The username.
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The key.
+    fn username_key(username: &str) -> String {
+        format!("user:{}", username.to_lowercase())
+    }
+}
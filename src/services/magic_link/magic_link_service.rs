@@ -0,0 +1,128 @@
+use crate::repository::magic_link::magic_link_model::MagicLink;
+use crate::repository::magic_link::magic_link_repository::{Error, MagicLinkRepository};
+use async_trait::async_trait;
+use log::info;
+use mongodb::bson::oid::ObjectId;
+use mongodb::Database;
+
+#[derive(Clone)]
+pub struct MongoMagicLinkService {
+    pub magic_link_repository: MagicLinkRepository,
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait MagicLinkService: Send + Sync {
+    /// # Summary
+    ///
+    /// Create a MagicLink that lets a User log in once without a password.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User.
+    /// * `expires_in` - The amount of seconds after which the MagicLink expires.
+    /// * `db` - The Database to store the MagicLink in.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let magic_link = magic_link_service.create(user.id, 900, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<MagicLink, Error>` - The new MagicLink.
+    async fn create(
+        &self,
+        user_id: ObjectId,
+        expires_in: u64,
+        db: &Database,
+    ) -> Result<MagicLink, Error>;
+
+    /// # Summary
+    ///
+    /// Use a MagicLink. A MagicLink can only be used once.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the MagicLink.
+    /// * `user_id` - The ID of the User that the magic link token was issued to.
+    /// * `db` - The Database that holds the MagicLink.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let magic_link = magic_link_service.redeem("link_id", "user_id", &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<MagicLink, Error>` - The MagicLink, or InvalidLink if it is unknown, expired, used or was issued to another User.
+    async fn redeem(&self, id: &str, user_id: &str, db: &Database) -> Result<MagicLink, Error>;
+}
+
+impl MongoMagicLinkService {
+    /// # Summary
+    ///
+    /// Create a new MongoMagicLinkService.
+    ///
+    /// # Arguments
+    ///
+    /// * `magic_link_repository` - The MagicLinkRepository.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let magic_link_service = MongoMagicLinkService::new(magic_link_repository);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `MongoMagicLinkService` - The new MongoMagicLinkService.
+    pub fn new(magic_link_repository: MagicLinkRepository) -> MongoMagicLinkService {
+        MongoMagicLinkService {
+            magic_link_repository,
+        }
+    }
+}
+
+#[async_trait]
+impl MagicLinkService for MongoMagicLinkService {
+    /// # Summary
+    ///
+    /// Create a MagicLink that lets a User log in once without a password.
+    async fn create(
+        &self,
+        user_id: ObjectId,
+        expires_in: u64,
+        db: &Database,
+    ) -> Result<MagicLink, Error> {
+        let magic_link = MagicLink::new(user_id, expires_in);
+
+        info!("Creating magic link: {}", magic_link);
+        self.magic_link_repository
+            .create(magic_link.clone(), db)
+            .await?;
+
+        Ok(magic_link)
+    }
+
+    /// # Summary
+    ///
+    /// Use a MagicLink.
+    async fn redeem(&self, id: &str, user_id: &str, db: &Database) -> Result<MagicLink, Error> {
+        let id = ObjectId::parse_str(id).map_err(|_| Error::InvalidLink)?;
+
+        let magic_link = match self.magic_link_repository.take(id, db).await? {
+            Some(m) => m,
+            None => return Err(Error::InvalidLink),
+        };
+
+        if magic_link.user_id.to_hex() != user_id || magic_link.is_expired() {
+            return Err(Error::InvalidLink);
+        }
+
+        info!("Redeemed magic link: {}", magic_link);
+        Ok(magic_link)
+    }
+}
@@ -0,0 +1,3 @@
+pub mod recovery_code;
+pub mod totp_service;
+pub mod webauthn_service;
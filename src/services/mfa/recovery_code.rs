@@ -0,0 +1,92 @@
+use crate::services::client::client_service::hash_secret;
+use crate::services::mfa::totp_service::TotpService;
+use rand::RngCore;
+
+/// The number of recovery codes that are issued at once.
+pub const RECOVERY_CODE_COUNT: usize = 10;
+
+/// The length of a recovery code in bytes. Ten bytes encode to sixteen base32 characters.
+const RECOVERY_CODE_LENGTH: usize = 10;
+
+/// The number of characters of a recovery code between two dashes, to make the code easier to copy.
+const GROUP_LENGTH: usize = 4;
+
+/// # Summary
+///
+/// Generate a set of random recovery codes.
+///
+/// # Example
+///
+/// ```
+/// let recovery_codes = generate_recovery_codes();
+/// ```
+///
+/// # Returns
+///
+/// * `Vec<String>` - The recovery codes, in groups of base32 characters that are separated by dashes.
+pub fn generate_recovery_codes() -> Vec<String> {
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let mut bytes = [0u8; RECOVERY_CODE_LENGTH];
+            rand::thread_rng().fill_bytes(&mut bytes);
+
+            TotpService::encode_secret(&bytes)
+                .chars()
+                .collect::<Vec<char>>()
+                .chunks(GROUP_LENGTH)
+                .map(|c| c.iter().collect::<String>())
+                .collect::<Vec<String>>()
+                .join("-")
+        })
+        .collect()
+}
+
+/// # Summary
+///
+/// Hash a recovery code, so that it can be stored and compared. Dashes, whitespace and the case of the code are
+/// ignored. Recovery codes are random and long, so a fast hash is sufficient.
+///
+/// # Arguments
+///
+/// * `recovery_code` - The recovery code.
+///
+/// # Example
+///
+/// ```
+/// let hash = hash_recovery_code("abcd-efgh-ijkl-mnop");
+/// ```
+///
+/// # Returns
+///
+/// * `String` - The hexadecimal SHA-256 hash of the recovery code.
+pub fn hash_recovery_code(recovery_code: &str) -> String {
+    let normalized: String = recovery_code
+        .chars()
+        .filter(|c| *c != '-' && !c.is_whitespace())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+
+    hash_secret(&normalized)
+}
+
+/// # Summary
+///
+/// Check whether a code that was submitted at the MFA verification step is a recovery code rather than a TOTP code.
+///
+/// # Arguments
+///
+/// * `code` - The submitted code.
+///
+/// # Example
+///
+/// ```
+/// let is_recovery_code = is_recovery_code("abcd-efgh-ijkl-mnop");
+/// ```
+///
+/// # Returns
+///
+/// * `bool` - True if the code is not a six digit TOTP code.
+pub fn is_recovery_code(code: &str) -> bool {
+    let code = code.trim();
+    !(code.len() == 6 && code.chars().all(|c| c.is_ascii_digit()))
+}
@@ -0,0 +1,383 @@
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use url::Url;
+
+/// The length of the nonce that precedes every encrypted TOTP secret.
+const NONCE_LENGTH: usize = 12;
+
+/// The length of a TOTP secret in bytes, as recommended by RFC 4226.
+const SECRET_LENGTH: usize = 20;
+
+/// The number of seconds that a TOTP code is valid for.
+const PERIOD: i64 = 30;
+
+/// The number of digits of a TOTP code.
+const DIGITS: u32 = 6;
+
+/// The number of time steps before and after the current one of which the codes are accepted, to tolerate clock skew.
+const SKEW: i64 = 1;
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("TOTP is not enabled")]
+    Disabled,
+    #[error("Failed to encrypt TOTP secret")]
+    Encryption,
+    #[error("TOTP secret could not be decrypted")]
+    InvalidSecret,
+}
+
+#[derive(Clone)]
+pub struct TotpService {
+    pub issuer: String,
+    pub challenge_expiration: u64,
+    key: Option<[u8; 32]>,
+}
+
+impl TotpService {
+    /// # Summary
+    ///
+    /// Create a new TotpService.
+    ///
+    /// # Arguments
+    ///
+    /// * `secret` - The secret that is used to derive the key that encrypts the TOTP secrets, or None to disable TOTP. TOTP is always disabled if the `mfa` feature is disabled.
+    /// * `issuer` - The name of the issuer that is shown in authenticator apps.
+    /// * `challenge_expiration` - The number of seconds after which an MFA challenge token expires.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let totp_service = TotpService::new(Some(String::from("secret")), String::from("auth-rs"), 300);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `TotpService` - The new TotpService.
+    pub fn new(secret: Option<String>, issuer: String, challenge_expiration: u64) -> TotpService {
+        let key = secret
+            .filter(|_| cfg!(feature = "mfa"))
+            .map(|s| Sha256::digest(s.as_bytes()).into());
+
+        TotpService {
+            issuer,
+            challenge_expiration,
+            key,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check whether users can enroll TOTP.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if an encryption secret was configured.
+    pub fn is_enabled(&self) -> bool {
+        self.key.is_some()
+    }
+
+    /// # Summary
+    ///
+    /// Generate a new random TOTP secret.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let secret = totp_service.generate_secret();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<u8>` - The TOTP secret.
+    pub fn generate_secret(&self) -> Vec<u8> {
+        let mut secret = vec![0u8; SECRET_LENGTH];
+        OsRng.fill_bytes(&mut secret);
+        secret
+    }
+
+    /// # Summary
+    ///
+    /// Encrypt a TOTP secret, so that it can be stored.
+    ///
+    /// # Arguments
+    ///
+    /// * `secret` - The TOTP secret.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let encrypted = totp_service.encrypt(&secret)?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String, Error>` - The base64-encoded nonce and ciphertext, or the Error that occurred.
+    pub fn encrypt(&self, secret: &[u8]) -> Result<String, Error> {
+        let cipher = self.cipher()?;
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let encrypted = cipher
+            .encrypt(&nonce, secret)
+            .map_err(|_| Error::Encryption)?;
+
+        let mut data = nonce.to_vec();
+        data.extend(encrypted);
+
+        Ok(STANDARD.encode(data))
+    }
+
+    /// # Summary
+    ///
+    /// Decrypt a stored TOTP secret.
+    ///
+    /// # Arguments
+    ///
+    /// * `encrypted` - The base64-encoded nonce and ciphertext.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let secret = totp_service.decrypt(&totp.secret)?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<u8>, Error>` - The TOTP secret, or the Error that occurred.
+    pub fn decrypt(&self, encrypted: &str) -> Result<Vec<u8>, Error> {
+        let cipher = self.cipher()?;
+        let data = STANDARD
+            .decode(encrypted)
+            .map_err(|_| Error::InvalidSecret)?;
+
+        if data.len() <= NONCE_LENGTH {
+            return Err(Error::InvalidSecret);
+        }
+
+        let (nonce, encrypted) = data.split_at(NONCE_LENGTH);
+        cipher
+            .decrypt(Nonce::from_slice(nonce), encrypted)
+            .map_err(|_| Error::InvalidSecret)
+    }
+
+    /// # Summary
+    ///
+    /// Check a TOTP code against a TOTP secret. The codes of the previous and the next time step are accepted as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `secret` - The TOTP secret.
+    /// * `code` - The code that was entered by the user.
+    /// * `now` - The current time, in seconds since the Unix epoch.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let step = totp_service.verify(&secret, "123456", Utc::now().timestamp());
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Option<i64>` - The time step of the code if it is valid, None otherwise.
+    pub fn verify(&self, secret: &[u8], code: &str, now: i64) -> Option<i64> {
+        let code = code.trim();
+        if code.len() != DIGITS as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+
+        let code: u32 = code.parse().ok()?;
+        let current = now.div_euclid(PERIOD);
+
+        (current - SKEW..=current + SKEW).find(|s| Self::hotp(secret, *s as u64) == Some(code))
+    }
+
+    /// # Summary
+    ///
+    /// Build the `otpauth://` URI that authenticator apps import, usually by scanning it as a QR code.
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - The username of the User, which labels the account in the authenticator app.
+    /// * `secret` - The TOTP secret.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let uri = totp_service.otpauth_uri("admin", &secret);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The URI.
+    pub fn otpauth_uri(&self, username: &str, secret: &[u8]) -> String {
+        let label = format!("{}:{}", self.issuer, username);
+        let mut url = match Url::parse("otpauth://totp/") {
+            Ok(u) => u,
+            Err(_) => return String::new(),
+        };
+
+        if let Ok(mut s) = url.path_segments_mut() {
+            s.pop_if_empty().push(&label);
+        }
+
+        url.query_pairs_mut()
+            .append_pair("secret", &Self::encode_secret(secret))
+            .append_pair("issuer", &self.issuer)
+            .append_pair("algorithm", "SHA1")
+            .append_pair("digits", &DIGITS.to_string())
+            .append_pair("period", &PERIOD.to_string());
+
+        url.to_string()
+    }
+
+    /// # Summary
+    ///
+    /// Encode a TOTP secret in unpadded base32, the format in which authenticator apps expect it.
+    ///
+    /// # Arguments
+    ///
+    /// * `secret` - The TOTP secret.
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The encoded TOTP secret.
+    pub fn encode_secret(secret: &[u8]) -> String {
+        let mut encoded = String::new();
+        let mut buffer: u32 = 0;
+        let mut bits = 0;
+
+        for byte in secret {
+            buffer = (buffer << 8) | *byte as u32;
+            bits += 8;
+            while bits >= 5 {
+                bits -= 5;
+                encoded.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+            }
+        }
+
+        if bits > 0 {
+            encoded.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+        }
+
+        encoded
+    }
+
+    /// # Summary
+    ///
+    /// Calculate the HOTP code of a counter, as described in RFC 4226.
+    ///
+    /// # Arguments
+    ///
+    /// * `secret` - The TOTP secret.
+    /// * `counter` - The counter, which is the time step for TOTP.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<u32>` - The code, or None if the secret cannot be used as a key.
+    fn hotp(secret: &[u8], counter: u64) -> Option<u32> {
+        let mut mac = Hmac::<Sha1>::new_from_slice(secret).ok()?;
+        mac.update(&counter.to_be_bytes());
+        let hash = mac.finalize().into_bytes();
+
+        let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+        let binary = u32::from_be_bytes([
+            hash[offset] & 0x7f,
+            hash[offset + 1],
+            hash[offset + 2],
+            hash[offset + 3],
+        ]);
+
+        Some(binary % 10u32.pow(DIGITS))
+    }
+
+    /// # Summary
+    ///
+    /// Get the cipher that is used to encrypt and decrypt TOTP secrets.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Aes256Gcm, Error>` - The cipher, or Disabled if no secret was configured.
+    fn cipher(&self) -> Result<Aes256Gcm, Error> {
+        match &self.key {
+            Some(k) => Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(k))),
+            None => Err(Error::Disabled),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The SHA-1 secret of the test vectors of RFC 6238 appendix B
+    const SECRET: &[u8] = b"12345678901234567890";
+
+    fn totp_service(secret: Option<&str>) -> TotpService {
+        TotpService::new(secret.map(String::from), String::from("auth-rs"), 300)
+    }
+
+    #[test]
+    fn codes_of_rfc_6238_are_accepted() {
+        let service = totp_service(None);
+
+        assert_eq!(service.verify(SECRET, "287082", 59), Some(1));
+        assert_eq!(service.verify(SECRET, "081804", 1111111109), Some(37037036));
+        assert_eq!(service.verify(SECRET, "005924", 1234567890), Some(41152263));
+    }
+
+    #[test]
+    fn codes_of_adjacent_time_steps_are_accepted() {
+        let service = totp_service(None);
+
+        assert_eq!(service.verify(SECRET, "287082", 59 + PERIOD), Some(1));
+        assert_eq!(service.verify(SECRET, "287082", 59 - PERIOD), Some(1));
+        assert_eq!(service.verify(SECRET, "287082", 59 + 2 * PERIOD), None);
+    }
+
+    #[test]
+    fn wrong_or_malformed_codes_are_rejected() {
+        let service = totp_service(None);
+
+        assert_eq!(service.verify(SECRET, "287083", 59), None);
+        assert_eq!(service.verify(SECRET, "28708a", 59), None);
+        assert_eq!(service.verify(SECRET, "2870820", 59), None);
+        assert_eq!(service.verify(SECRET, "", 59), None);
+    }
+
+    #[test]
+    fn secret_is_encoded_in_base32() {
+        assert_eq!(
+            TotpService::encode_secret(SECRET),
+            "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ"
+        );
+    }
+
+    #[test]
+    fn secret_cannot_be_encrypted_without_key() {
+        let service = totp_service(None);
+
+        assert!(!service.is_enabled());
+        assert!(matches!(service.encrypt(SECRET), Err(Error::Disabled)));
+    }
+
+    #[cfg(feature = "mfa")]
+    #[test]
+    fn encrypted_secret_only_decrypts_with_same_key() {
+        let service = totp_service(Some("secret"));
+        let encrypted = service.encrypt(SECRET).unwrap();
+
+        assert_eq!(service.decrypt(&encrypted).unwrap(), SECRET);
+        assert!(matches!(
+            totp_service(Some("other")).decrypt(&encrypted),
+            Err(Error::InvalidSecret)
+        ));
+    }
+}
@@ -0,0 +1,329 @@
+#[cfg(not(feature = "mfa"))]
+pub use self::disabled::{
+    AuthenticationResult, CreationChallengeResponse, Passkey, PasskeyAuthentication,
+    PasskeyRegistration, PublicKeyCredential, RegisterPublicKeyCredential,
+    RequestChallengeResponse, WebauthnError,
+};
+#[cfg(not(feature = "mfa"))]
+use self::disabled::{Uuid, Webauthn};
+use crate::configuration::webauthn_config::WebauthnConfig;
+use crate::repository::credential::credential_model::Credential;
+use mongodb::bson::oid::ObjectId;
+use std::sync::Arc;
+use thiserror::Error;
+#[cfg(feature = "mfa")]
+pub use webauthn_rs::prelude::{
+    AuthenticationResult, CreationChallengeResponse, Passkey, PasskeyAuthentication,
+    PasskeyRegistration, PublicKeyCredential, RegisterPublicKeyCredential,
+    RequestChallengeResponse, WebauthnError,
+};
+#[cfg(feature = "mfa")]
+use webauthn_rs::prelude::{Url, Uuid};
+#[cfg(feature = "mfa")]
+use webauthn_rs::{Webauthn, WebauthnBuilder};
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Passkeys are not enabled")]
+    Disabled,
+    #[error("Invalid relying party: {0}")]
+    Configuration(String),
+    #[error("The WebAuthn ceremony failed: {0}")]
+    Ceremony(#[source] WebauthnError),
+}
+
+#[derive(Clone)]
+pub struct WebauthnService {
+    pub ceremony_expiration: u64,
+    webauthn: Option<Arc<Webauthn>>,
+}
+
+impl WebauthnService {
+    /// # Summary
+    ///
+    /// Create a new WebauthnService.
+    ///
+    /// # Arguments
+    ///
+    /// * `webauthn_config` - The WebauthnConfig. Passkeys are disabled if no relying party was configured, or if the `mfa` feature is disabled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let webauthn_service = WebauthnService::new(webauthn_config)?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<WebauthnService, Error>` - The new WebauthnService, or Configuration if the relying party is invalid.
+    pub fn new(webauthn_config: WebauthnConfig) -> Result<WebauthnService, Error> {
+        let webauthn = match (&webauthn_config.rp_id, &webauthn_config.rp_origin) {
+            #[cfg(feature = "mfa")]
+            (Some(id), Some(origin)) => {
+                let origin = Url::parse(origin).map_err(|e| Error::Configuration(e.to_string()))?;
+                let webauthn = WebauthnBuilder::new(id, &origin)
+                    .and_then(|b| b.rp_name(&webauthn_config.rp_name).build())
+                    .map_err(|e| Error::Configuration(e.to_string()))?;
+
+                Some(Arc::new(webauthn))
+            }
+            _ => None,
+        };
+
+        Ok(WebauthnService {
+            ceremony_expiration: webauthn_config.ceremony_expiration,
+            webauthn,
+        })
+    }
+
+    /// # Summary
+    ///
+    /// Check whether users can register passkeys.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if a relying party was configured.
+    pub fn is_enabled(&self) -> bool {
+        self.webauthn.is_some()
+    }
+
+    /// # Summary
+    ///
+    /// Start the registration of a new passkey. Authenticators refuse to register a second passkey for a User on
+    /// which one of its existing Credentials is stored.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User.
+    /// * `username` - The username of the User, which labels the passkey in the authenticator.
+    /// * `credentials` - The Credentials that the User registered before.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let (challenge, state) = webauthn_service.start_registration(user.id, &user.username, &credentials)?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(CreationChallengeResponse, PasskeyRegistration), Error>` - The options that are passed to the browser, and the state that is needed to finish the registration.
+    pub fn start_registration(
+        &self,
+        user_id: ObjectId,
+        username: &str,
+        credentials: &[Credential],
+    ) -> Result<(CreationChallengeResponse, PasskeyRegistration), Error> {
+        let exclude = credentials
+            .iter()
+            .map(|c| c.passkey.cred_id().clone())
+            .collect();
+
+        self.webauthn()?
+            .start_passkey_registration(
+                Self::user_handle(user_id),
+                username,
+                username,
+                Some(exclude),
+            )
+            .map_err(Error::Ceremony)
+    }
+
+    /// # Summary
+    ///
+    /// Verify the response of an authenticator to a registration challenge.
+    ///
+    /// # Arguments
+    ///
+    /// * `response` - The response of the authenticator.
+    /// * `state` - The state that was stored when the registration was started.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let passkey = webauthn_service.finish_registration(&response, &state)?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Passkey, Error>` - The registered passkey, or the Error that occurred.
+    pub fn finish_registration(
+        &self,
+        response: &RegisterPublicKeyCredential,
+        state: &PasskeyRegistration,
+    ) -> Result<Passkey, Error> {
+        self.webauthn()?
+            .finish_passkey_registration(response, state)
+            .map_err(Error::Ceremony)
+    }
+
+    /// # Summary
+    ///
+    /// Start a login with one of the passkeys of a User.
+    ///
+    /// # Arguments
+    ///
+    /// * `credentials` - The Credentials of the User.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let (challenge, state) = webauthn_service.start_authentication(&credentials)?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(RequestChallengeResponse, PasskeyAuthentication), Error>` - The options that are passed to the browser, and the state that is needed to finish the login.
+    pub fn start_authentication(
+        &self,
+        credentials: &[Credential],
+    ) -> Result<(RequestChallengeResponse, PasskeyAuthentication), Error> {
+        let passkeys: Vec<Passkey> = credentials.iter().map(|c| c.passkey.clone()).collect();
+
+        self.webauthn()?
+            .start_passkey_authentication(&passkeys)
+            .map_err(Error::Ceremony)
+    }
+
+    /// # Summary
+    ///
+    /// Verify the signature of an authenticator over a login challenge.
+    ///
+    /// # Arguments
+    ///
+    /// * `response` - The response of the authenticator.
+    /// * `state` - The state that was stored when the login was started.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let result = webauthn_service.finish_authentication(&response, &state)?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<AuthenticationResult, Error>` - The ID and the signature counter of the passkey that was used, or the Error that occurred.
+    pub fn finish_authentication(
+        &self,
+        response: &PublicKeyCredential,
+        state: &PasskeyAuthentication,
+    ) -> Result<AuthenticationResult, Error> {
+        self.webauthn()?
+            .finish_passkey_authentication(response, state)
+            .map_err(Error::Ceremony)
+    }
+
+    /// # Summary
+    ///
+    /// Derive the WebAuthn user handle of a User from its ID, so that it does not need to be stored.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User.
+    ///
+    /// # Returns
+    ///
+    /// * `Uuid` - The user handle.
+    fn user_handle(user_id: ObjectId) -> Uuid {
+        let mut bytes = [0u8; 16];
+        bytes[..12].copy_from_slice(&user_id.bytes());
+
+        Uuid::from_bytes(bytes)
+    }
+
+    /// # Summary
+    ///
+    /// Get the relying party that performs the ceremonies.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<&Webauthn, Error>` - The relying party, or Disabled if none was configured.
+    fn webauthn(&self) -> Result<&Webauthn, Error> {
+        match &self.webauthn {
+            Some(w) => Ok(w),
+            None => Err(Error::Disabled),
+        }
+    }
+}
+
+/// Stand-ins for the WebAuthn types if the `mfa` feature is disabled, so that the passkeys that were registered by a build
+/// with the feature are kept as they are. No ceremony can be performed, since a relying party is never configured.
+#[cfg(not(feature = "mfa"))]
+mod disabled {
+    use mongodb::bson::Document;
+    use serde::{Deserialize, Serialize};
+    use std::convert::Infallible;
+
+    pub use mongodb::bson::Uuid;
+
+    pub type WebauthnError = Infallible;
+    pub type PasskeyRegistration = Document;
+    pub type PasskeyAuthentication = Document;
+    pub type RegisterPublicKeyCredential = Document;
+    pub type PublicKeyCredential = Document;
+    pub type CreationChallengeResponse = Document;
+    pub type RequestChallengeResponse = Document;
+
+    static NO_CREDENTIAL_ID: CredentialId = CredentialId(Vec::new());
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct CredentialId(pub Vec<u8>);
+
+    #[derive(Serialize, Deserialize, Clone, Debug)]
+    #[serde(transparent)]
+    pub struct Passkey(Document);
+
+    impl Passkey {
+        pub fn cred_id(&self) -> &CredentialId {
+            &NO_CREDENTIAL_ID
+        }
+
+        pub fn update_credential(&mut self, result: &AuthenticationResult) -> Option<bool> {
+            match *result {}
+        }
+    }
+
+    pub enum AuthenticationResult {}
+
+    impl AuthenticationResult {
+        pub fn cred_id(&self) -> &CredentialId {
+            match *self {}
+        }
+    }
+
+    pub enum Webauthn {}
+
+    impl Webauthn {
+        pub fn start_passkey_registration(
+            &self,
+            _user_unique_id: Uuid,
+            _user_name: &str,
+            _user_display_name: &str,
+            _exclude_credentials: Option<Vec<CredentialId>>,
+        ) -> Result<(CreationChallengeResponse, PasskeyRegistration), WebauthnError> {
+            match *self {}
+        }
+
+        pub fn finish_passkey_registration(
+            &self,
+            _reg: &RegisterPublicKeyCredential,
+            _state: &PasskeyRegistration,
+        ) -> Result<Passkey, WebauthnError> {
+            match *self {}
+        }
+
+        pub fn start_passkey_authentication(
+            &self,
+            _creds: &[Passkey],
+        ) -> Result<(RequestChallengeResponse, PasskeyAuthentication), WebauthnError> {
+            match *self {}
+        }
+
+        pub fn finish_passkey_authentication(
+            &self,
+            _reg: &PublicKeyCredential,
+            _state: &PasskeyAuthentication,
+        ) -> Result<AuthenticationResult, WebauthnError> {
+            match *self {}
+        }
+    }
+}
@@ -0,0 +1,2 @@
+pub mod error_code;
+pub mod pkce;
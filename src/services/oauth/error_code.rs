@@ -0,0 +1,16 @@
+pub trait OAuthErrorCode: std::error::Error {
+    /// # Summary
+    ///
+    /// Get the OAuth 2.0 error code that corresponds to the error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let error_code = Error::InvalidGrant.oauth_error_code();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Option<&'static str>` - The OAuth 2.0 error code, or None if the error is not an OAuth 2.0 error.
+    fn oauth_error_code(&self) -> Option<&'static str>;
+}
@@ -0,0 +1,273 @@
+use crate::errors::into_http_response::IntoHttpResponse;
+use actix_web::http::StatusCode;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt::{Display, Formatter};
+
+/// The minimum length of a code verifier, as defined by RFC 7636.
+const MIN_VERIFIER_LENGTH: usize = 43;
+
+/// The maximum length of a code verifier, as defined by RFC 7636.
+const MAX_VERIFIER_LENGTH: usize = 128;
+
+/// The length of an S256 code challenge, which is the unpadded Base64URL encoding of a SHA-256 hash.
+const S256_CHALLENGE_LENGTH: usize = 43;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum CodeChallengeMethod {
+    S256,
+}
+
+impl Display for CodeChallengeMethod {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            CodeChallengeMethod::S256 => write!(f, "S256"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CodeChallenge {
+    pub challenge: String,
+    pub method: CodeChallengeMethod,
+}
+
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum PkceError {
+    #[error("A code challenge is required for public clients")]
+    ChallengeRequired,
+    #[error("Unsupported code challenge method: {0}")]
+    UnsupportedMethod(String),
+    #[error("Invalid code challenge")]
+    InvalidChallenge,
+    #[error("A code verifier is required")]
+    VerifierRequired,
+    #[error("Invalid code verifier")]
+    InvalidVerifier,
+}
+
+impl IntoHttpResponse for PkceError {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::BAD_REQUEST
+    }
+}
+
+impl CodeChallenge {
+    /// # Summary
+    ///
+    /// Create a new CodeChallenge from the `code_challenge` and `code_challenge_method` parameters of an authorization request.
+    /// Only the S256 method is supported, because the plain method offers no protection if the challenge is intercepted.
+    ///
+    /// # Arguments
+    ///
+    /// * `challenge` - The code challenge.
+    /// * `method` - The code challenge method, which defaults to S256 if omitted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let code_challenge = CodeChallenge::new("E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM", Some("S256"))?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<CodeChallenge, PkceError>` - The new CodeChallenge.
+    pub fn new(challenge: &str, method: Option<&str>) -> Result<CodeChallenge, PkceError> {
+        let method = match method {
+            None | Some("S256") => CodeChallengeMethod::S256,
+            Some(m) => return Err(PkceError::UnsupportedMethod(m.to_string())),
+        };
+
+        if challenge.len() != S256_CHALLENGE_LENGTH || URL_SAFE_NO_PAD.decode(challenge).is_err() {
+            return Err(PkceError::InvalidChallenge);
+        }
+
+        Ok(CodeChallenge {
+            challenge: challenge.to_string(),
+            method,
+        })
+    }
+
+    /// # Summary
+    ///
+    /// Verify that a code verifier matches the CodeChallenge.
+    ///
+    /// # Arguments
+    ///
+    /// * `verifier` - The code verifier that was sent to the token endpoint.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// code_challenge.verify("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk")?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), PkceError>` - InvalidVerifier if the code verifier is malformed or does not match.
+    pub fn verify(&self, verifier: &str) -> Result<(), PkceError> {
+        if !(MIN_VERIFIER_LENGTH..=MAX_VERIFIER_LENGTH).contains(&verifier.len())
+            || !verifier
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "-._~".contains(c))
+        {
+            return Err(PkceError::InvalidVerifier);
+        }
+
+        let expected = match self.method {
+            CodeChallengeMethod::S256 => {
+                URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+            }
+        };
+
+        // Every byte is compared, so the comparison does not reveal how much of the challenge matched
+        let matches = expected.len() == self.challenge.len()
+            && expected
+                .bytes()
+                .zip(self.challenge.bytes())
+                .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+                == 0;
+
+        if !matches {
+            return Err(PkceError::InvalidVerifier);
+        }
+
+        Ok(())
+    }
+}
+
+/// # Summary
+///
+/// Parse the PKCE parameters of an authorization request. A code challenge is mandatory for public clients,
+/// because they cannot keep a client secret to protect the authorization code.
+///
+/// # Arguments
+///
+/// * `public_client` - Whether the client is a public client, such as a single-page or mobile application.
+/// * `challenge` - The `code_challenge` parameter, if any.
+/// * `method` - The `code_challenge_method` parameter, if any.
+///
+/// # Example
+///
+/// ```
+/// let code_challenge = pkce::parse_challenge(client.public, request.code_challenge.as_deref(), request.code_challenge_method.as_deref())?;
+/// ```
+///
+/// # Returns
+///
+/// * `Result<Option<CodeChallenge>, PkceError>` - The CodeChallenge, or None if a confidential client did not use PKCE.
+pub fn parse_challenge(
+    public_client: bool,
+    challenge: Option<&str>,
+    method: Option<&str>,
+) -> Result<Option<CodeChallenge>, PkceError> {
+    match challenge {
+        Some(c) => Ok(Some(CodeChallenge::new(c, method)?)),
+        None if public_client => Err(PkceError::ChallengeRequired),
+        None => Ok(None),
+    }
+}
+
+/// # Summary
+///
+/// Verify the code verifier of a token request against the CodeChallenge that was stored with the authorization code.
+///
+/// # Arguments
+///
+/// * `challenge` - The CodeChallenge of the authorization code, if any.
+/// * `verifier` - The `code_verifier` parameter of the token request, if any.
+///
+/// # Example
+///
+/// ```
+/// pkce::verify(authorization_code.code_challenge.as_ref(), request.code_verifier.as_deref())?;
+/// ```
+///
+/// # Returns
+///
+/// * `Result<(), PkceError>` - The PkceError if the code verifier is missing, malformed or does not match.
+pub fn verify(challenge: Option<&CodeChallenge>, verifier: Option<&str>) -> Result<(), PkceError> {
+    match (challenge, verifier) {
+        (Some(c), Some(v)) => c.verify(v),
+        (Some(_), None) => Err(PkceError::VerifierRequired),
+        // A verifier without a challenge indicates that the authorization request was tampered with
+        (None, Some(_)) => Err(PkceError::InvalidVerifier),
+        (None, None) => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The example of RFC 7636 appendix B
+    const VERIFIER: &str = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+    const CHALLENGE: &str = "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM";
+
+    #[test]
+    fn matching_verifier_is_accepted() {
+        let challenge = CodeChallenge::new(CHALLENGE, Some("S256")).unwrap();
+
+        assert!(challenge.verify(VERIFIER).is_ok());
+    }
+
+    #[test]
+    fn mismatching_verifier_is_rejected() {
+        let challenge = CodeChallenge::new(CHALLENGE, None).unwrap();
+        let verifier = "aBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+
+        assert!(matches!(
+            challenge.verify(verifier),
+            Err(PkceError::InvalidVerifier)
+        ));
+    }
+
+    #[test]
+    fn malformed_verifier_is_rejected() {
+        let challenge = CodeChallenge::new(CHALLENGE, None).unwrap();
+
+        assert!(matches!(
+            challenge.verify("too-short"),
+            Err(PkceError::InvalidVerifier)
+        ));
+        assert!(matches!(
+            challenge.verify(&format!("{}+", &VERIFIER[1..])),
+            Err(PkceError::InvalidVerifier)
+        ));
+    }
+
+    #[test]
+    fn plain_method_is_not_supported() {
+        assert!(matches!(
+            CodeChallenge::new(CHALLENGE, Some("plain")),
+            Err(PkceError::UnsupportedMethod(_))
+        ));
+    }
+
+    #[test]
+    fn public_client_requires_challenge() {
+        assert!(matches!(
+            parse_challenge(true, None, None),
+            Err(PkceError::ChallengeRequired)
+        ));
+        assert!(matches!(parse_challenge(false, None, None), Ok(None)));
+    }
+
+    #[test]
+    fn verifier_must_match_stored_challenge() {
+        let challenge = CodeChallenge::new(CHALLENGE, None).unwrap();
+
+        assert!(verify(Some(&challenge), Some(VERIFIER)).is_ok());
+        assert!(matches!(
+            verify(Some(&challenge), None),
+            Err(PkceError::VerifierRequired)
+        ));
+        assert!(matches!(
+            verify(None, Some(VERIFIER)),
+            Err(PkceError::InvalidVerifier)
+        ));
+        assert!(verify(None, None).is_ok());
+    }
+}
@@ -0,0 +1,524 @@
+use crate::repository::audit::audit_model::Action::{Create, Delete, Update};
+use crate::repository::audit::audit_model::{Action, Audit, ResourceIdType, ResourceType};
+use crate::repository::audit::audit_repository::Error as AuditError;
+use crate::repository::organization::organization_model::{Member, Organization};
+use crate::repository::organization::organization_repository::{Error, OrganizationRepository};
+use crate::services::audit::audit_service::AuditService;
+use async_trait::async_trait;
+use log::{error, info};
+use mongodb::bson::oid::ObjectId;
+use mongodb::Database;
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct MongoOrganizationService {
+    pub organization_repository: OrganizationRepository,
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait OrganizationService: Send + Sync {
+    /// # Summary
+    ///
+    /// Create a new Organization.
+    ///
+    /// # Arguments
+    ///
+    /// * `organization` - The Organization to be created.
+    /// * `user_id` - The ID of the User creating the Organization.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let organization = organization_service.create(organization, Some(user_id), &db, &audit_service).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Organization, Error>` - The created Organization.
+    async fn create(
+        &self,
+        organization: Organization,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<Organization, Error>;
+
+    /// # Summary
+    ///
+    /// Find all Organization entities.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The limit of Organization entities to find.
+    /// * `page` - The page of Organization entities to find.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let organizations = organization_service.find_all(Some(100), Some(1), &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Organization>, Error>` - The Organization entities.
+    async fn find_all(
+        &self,
+        limit: Option<i64>,
+        page: Option<i64>,
+        db: &Database,
+    ) -> Result<Vec<Organization>, Error>;
+
+    /// # Summary
+    ///
+    /// Find an Organization entity by its ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the Organization entity.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let organization = organization_service.find_by_id("organization_id", &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<Organization>, Error>` - The optional Organization entity.
+    async fn find_by_id(&self, id: &str, db: &Database) -> Result<Option<Organization>, Error>;
+
+    /// # Summary
+    ///
+    /// Find all Organization entities that a User is a member of.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let organizations = organization_service.find_by_member(user_id, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Organization>, Error>` - The Organization entities.
+    async fn find_by_member(
+        &self,
+        user_id: ObjectId,
+        db: &Database,
+    ) -> Result<Vec<Organization>, Error>;
+
+    /// # Summary
+    ///
+    /// Update the name, description and default roles of an Organization entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `organization` - The Organization to be updated.
+    /// * `user_id` - The ID of the User updating the Organization.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let organization = organization_service.update(organization, Some(user_id), &db, &audit_service).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Organization, Error>` - The updated Organization entity.
+    async fn update(
+        &self,
+        organization: Organization,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<Organization, Error>;
+
+    /// # Summary
+    ///
+    /// Add a Member to an Organization, or replace the roles of an existing Member. Newly joined Members also receive
+    /// the default roles of the Organization.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the Organization entity.
+    /// * `member` - The Member.
+    /// * `user_id` - The ID of the User updating the Organization.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let organization = organization_service.set_member("organization_id", member, Some(user_id), &db, &audit_service).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Organization, Error>` - The updated Organization entity.
+    async fn set_member(
+        &self,
+        id: &str,
+        member: Member,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<Organization, Error>;
+
+    /// # Summary
+    ///
+    /// Remove a Member from an Organization.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the Organization entity.
+    /// * `member_id` - The ID of the User that is removed.
+    /// * `user_id` - The ID of the User updating the Organization.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// organization_service.remove_member("organization_id", member_id, Some(user_id), &db, &audit_service).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    async fn remove_member(
+        &self,
+        id: &str,
+        member_id: ObjectId,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(), Error>;
+
+    /// # Summary
+    ///
+    /// Remove a Role from all Members and the default roles of an Organization.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the Organization entity.
+    /// * `role_id` - The ID of the Role that is removed.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// organization_service.remove_role(organization_id, role_id, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    async fn remove_role(
+        &self,
+        id: ObjectId,
+        role_id: ObjectId,
+        db: &Database,
+    ) -> Result<(), Error>;
+
+    /// # Summary
+    ///
+    /// Delete an Organization entity by its ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the Organization entity.
+    /// * `user_id` - The ID of the User deleting the Organization.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// organization_service.delete("organization_id", Some(user_id), &db, &audit_service).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    async fn delete(
+        &self,
+        id: &str,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(), Error>;
+}
+
+impl MongoOrganizationService {
+    /// # Summary
+    ///
+    /// Create a new MongoOrganizationService.
+    ///
+    /// # Arguments
+    ///
+    /// * `organization_repository` - The OrganizationRepository.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let organization_repository = OrganizationRepository::new(String::from("organizations"));
+    /// let organization_service = MongoOrganizationService::new(organization_repository);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `MongoOrganizationService` - The new MongoOrganizationService.
+    pub fn new(organization_repository: OrganizationRepository) -> MongoOrganizationService {
+        MongoOrganizationService {
+            organization_repository,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Parse the ID of an Organization.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the Organization.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ObjectId, Error>` - The ObjectId of the Organization.
+    fn parse_id(id: &str) -> Result<ObjectId, Error> {
+        if id.is_empty() {
+            return Err(Error::EmptyId);
+        }
+
+        ObjectId::parse_str(id).map_err(|e| Error::InvalidId(e.to_string()))
+    }
+
+    /// # Summary
+    ///
+    /// Record a change to an Organization in the audit trail.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User that made the change, if any.
+    /// * `action` - The Action.
+    /// * `organization_id` - The ID of the Organization.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    async fn audit(
+        user_id: Option<ObjectId>,
+        action: Action,
+        organization_id: ObjectId,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(), Error> {
+        let user_id = match user_id {
+            Some(u) => u,
+            None => return Ok(()),
+        };
+
+        let new_audit = Audit::new(
+            user_id,
+            action,
+            organization_id,
+            ResourceIdType::OrganizationId,
+            ResourceType::Organization,
+        );
+        match audit_service.create(new_audit, db).await {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                error!("Failed to create Audit: {}", e);
+                Err(Error::Audit(e))
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl OrganizationService for MongoOrganizationService {
+    /// # Summary
+    ///
+    /// Create a new Organization.
+    async fn create(
+        &self,
+        organization: Organization,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<Organization, Error> {
+        info!("Creating Organization: {}", organization);
+        Self::audit(user_id, Create, organization.id, db, audit_service).await?;
+
+        self.organization_repository.create(organization, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Find all Organization entities.
+    async fn find_all(
+        &self,
+        limit: Option<i64>,
+        page: Option<i64>,
+        db: &Database,
+    ) -> Result<Vec<Organization>, Error> {
+        info!("Finding all organizations");
+        self.organization_repository.find_all(limit, page, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Find an Organization entity by its ID.
+    async fn find_by_id(&self, id: &str, db: &Database) -> Result<Option<Organization>, Error> {
+        info!("Finding Organization by ID: {}", id);
+        self.organization_repository.find_by_id(id, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Find all Organization entities that a User is a member of.
+    async fn find_by_member(
+        &self,
+        user_id: ObjectId,
+        db: &Database,
+    ) -> Result<Vec<Organization>, Error> {
+        info!("Finding Organizations of User: {}", user_id.to_hex());
+        self.organization_repository
+            .find_by_member(user_id, db)
+            .await
+    }
+
+    /// # Summary
+    ///
+    /// Update the name, description and default roles of an Organization entity.
+    async fn update(
+        &self,
+        organization: Organization,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<Organization, Error> {
+        info!("Updating Organization: {}", organization);
+        Self::audit(user_id, Update, organization.id, db, audit_service).await?;
+
+        self.organization_repository.update(organization, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Add a Member to an Organization, or replace the roles of an existing Member.
+    /// Newly joined Members also receive the default roles of the Organization.
+    async fn set_member(
+        &self,
+        id: &str,
+        mut member: Member,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<Organization, Error> {
+        info!(
+            "Setting Member {} of Organization {}",
+            member.user_id.to_hex(),
+            id
+        );
+
+        let oid = Self::parse_id(id)?;
+        let organization = match self.organization_repository.find_by_id(id, db).await? {
+            Some(o) => o,
+            None => return Err(Error::OrganizationNotFound(id.to_string())),
+        };
+
+        if organization.find_member(&member.user_id).is_none() {
+            member.roles = organization.with_default_roles(member.roles);
+        }
+
+        Self::audit(user_id, Update, oid, db, audit_service).await?;
+
+        self.organization_repository
+            .set_member(oid, member, db)
+            .await
+    }
+
+    /// # Summary
+    ///
+    /// Remove a Member from an Organization.
+    async fn remove_member(
+        &self,
+        id: &str,
+        member_id: ObjectId,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(), Error> {
+        info!(
+            "Removing Member {} from Organization {}",
+            member_id.to_hex(),
+            id
+        );
+
+        let oid = Self::parse_id(id)?;
+        Self::audit(user_id, Update, oid, db, audit_service).await?;
+
+        self.organization_repository
+            .remove_member(oid, member_id, db)
+            .await
+    }
+
+    /// # Summary
+    ///
+    /// Remove a Role from all Members and the default roles of an Organization.
+    async fn remove_role(
+        &self,
+        id: ObjectId,
+        role_id: ObjectId,
+        db: &Database,
+    ) -> Result<(), Error> {
+        info!(
+            "Removing Role {} from Members of Organization {}",
+            role_id.to_hex(),
+            id.to_hex()
+        );
+        self.organization_repository
+            .remove_role(id, role_id, db)
+            .await
+    }
+
+    /// # Summary
+    ///
+    /// Delete an Organization entity by its ID.
+    async fn delete(
+        &self,
+        id: &str,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(), Error> {
+        info!("Deleting Organization by ID: {}", id);
+
+        if user_id.is_some() {
+            let oid = match ObjectId::parse_str(id) {
+                Ok(oid) => oid,
+                Err(e) => {
+                    return Err(Error::Audit(AuditError::ObjectId(e.to_string())));
+                }
+            };
+
+            Self::audit(user_id, Delete, oid, db, audit_service).await?;
+        }
+
+        self.organization_repository.delete(id, db).await
+    }
+}
@@ -1 +1,3 @@
+pub mod argon2_password_hasher;
+pub mod password_hasher;
 pub mod password_service;
@@ -0,0 +1,55 @@
+use crate::services::password::password_hasher::PasswordHasher;
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordVerifier, SaltString},
+    Argon2, PasswordHasher as _,
+};
+
+#[derive(Clone, Default)]
+pub struct Argon2PasswordHasher {}
+
+impl PasswordHasher for Argon2PasswordHasher {
+    /// # Summary
+    ///
+    /// Hash a password using Argon2 and a random salt.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - The password to hash.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String, String>` - The hashed password or the error that occurred.
+    fn hash_password(&self, password: &str) -> Result<String, String> {
+        let argon2 = Argon2::default();
+
+        let salt = SaltString::generate(&mut OsRng);
+
+        match argon2.hash_password(password.as_bytes(), &salt) {
+            Ok(e) => Ok(e.to_string()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Verify a password against an Argon2 hash.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - The password to verify.
+    /// * `hash` - The hash to verify against.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<bool, String>` - Whether the password matches the hash or the error that occurred if the hash could not be parsed.
+    fn verify_password(&self, password: &str, hash: &str) -> Result<bool, String> {
+        let parsed_hash = match PasswordHash::new(hash) {
+            Ok(h) => h,
+            Err(e) => return Err(e.to_string()),
+        };
+
+        Ok(Argon2::default()
+            .verify_password(password.as_bytes(), &parsed_hash)
+            .is_ok())
+    }
+}
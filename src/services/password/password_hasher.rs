@@ -0,0 +1,28 @@
+pub trait PasswordHasher: Send + Sync {
+    /// # Summary
+    ///
+    /// Hash a password.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - The password to hash.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String, String>` - The hashed password or the error that occurred.
+    fn hash_password(&self, password: &str) -> Result<String, String>;
+
+    /// # Summary
+    ///
+    /// Verify a password against a hash that was created by this PasswordHasher.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - The password to verify.
+    /// * `hash` - The hash to verify against.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<bool, String>` - Whether the password matches the hash or the error that occurred if the hash could not be parsed.
+    fn verify_password(&self, password: &str, hash: &str) -> Result<bool, String>;
+}
@@ -1,12 +1,33 @@
-use argon2::{
-    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
-    Argon2,
-};
+use crate::services::password::password_hasher::PasswordHasher;
+use std::sync::Arc;
 
 #[derive(Clone)]
-pub struct PasswordService {}
+pub struct PasswordService {
+    pub password_hasher: Arc<dyn PasswordHasher>,
+}
 
 impl PasswordService {
+    /// # Summary
+    ///
+    /// Create a new PasswordService.
+    ///
+    /// # Arguments
+    ///
+    /// * `password_hasher` - The PasswordHasher to be used by the PasswordService.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let password_service = PasswordService::new(Arc::new(Argon2PasswordHasher::default()));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `PasswordService` - The new PasswordService.
+    pub fn new(password_hasher: Arc<dyn PasswordHasher>) -> PasswordService {
+        PasswordService { password_hasher }
+    }
+
     /// # Summary
     ///
     /// Hash a password.
@@ -18,16 +39,8 @@ impl PasswordService {
     /// # Returns
     ///
     /// A Result containing the hashed password or an error.
-    pub fn hash_password(password: String) -> Result<String, String> {
-        let password = &password.as_bytes();
-        let argon2 = Argon2::default();
-
-        let salt = SaltString::generate(&mut OsRng);
-
-        match argon2.hash_password(password, &salt) {
-            Ok(e) => Ok(e.to_string()),
-            Err(e) => Err(e.to_string()),
-        }
+    pub fn hash_password(&self, password: &str) -> Result<String, String> {
+        self.password_hasher.hash_password(password)
     }
 
     /// # Summary
@@ -41,10 +54,8 @@ impl PasswordService {
     ///
     /// # Returns
     ///
-    /// A boolean indicating whether the password is valid.
-    pub fn verify_password(password: &str, hash: &PasswordHash) -> bool {
-        Argon2::default()
-            .verify_password(&password.as_bytes(), &hash)
-            .is_ok()
+    /// A Result containing a boolean indicating whether the password is valid or an error if the hash is invalid.
+    pub fn verify_password(&self, password: &str, hash: &str) -> Result<bool, String> {
+        self.password_hasher.verify_password(password, hash)
     }
 }
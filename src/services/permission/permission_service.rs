@@ -1,45 +1,28 @@
+use crate::components::entity_cache::{cache_key, CacheStats, EntityCache};
 use crate::repository::audit::audit_model::Action::{Create, Delete, Update};
 use crate::repository::audit::audit_model::ResourceType::Permission as PermissionResourceType;
 use crate::repository::audit::audit_model::{Audit, ResourceIdType};
 use crate::repository::audit::audit_repository::Error as AuditError;
 use crate::repository::permission::permission_model::Permission;
 use crate::repository::permission::permission_repository::{Error, PermissionRepository};
+use crate::repository::search_hit::SearchHit;
 use crate::services::audit::audit_service::AuditService;
 use crate::services::role::role_service::RoleService;
+use async_trait::async_trait;
 use log::{error, info};
 use mongodb::bson::oid::ObjectId;
 use mongodb::Database;
+use std::sync::Arc;
 
 #[derive(Clone)]
-pub struct PermissionService {
+pub struct MongoPermissionService {
     pub permission_repository: PermissionRepository,
+    pub cache: Option<Arc<EntityCache<Permission>>>,
 }
 
-impl PermissionService {
-    /// # Summary
-    ///
-    /// Create a new PermissionService.
-    ///
-    /// # Arguments
-    ///
-    /// * `permission_repository` - The PermissionRepository to be used by the PermissionService.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// let permission_repository = PermissionRepository::new(String::from("permissions"));
-    /// let permission_service = PermissionService::new(permission_repository);
-    /// ```
-    ///
-    /// # Returns
-    ///
-    /// * `PermissionService` - The new PermissionService.
-    pub fn new(permission_repository: PermissionRepository) -> PermissionService {
-        PermissionService {
-            permission_repository,
-        }
-    }
-
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait PermissionService: Send + Sync {
     /// # Summary
     ///
     /// Create a new Permission entity.
@@ -55,7 +38,7 @@ impl PermissionService {
     ///
     /// ```
     /// let permission_repository = PermissionRepository::new(String::from("permissions"));
-    /// let permission_service = PermissionService::new(permission_repository);
+    /// let permission_service = MongoPermissionService::new(permission_repository);
     /// let db = mongodb::Database::new();
     /// let audit_service = AuditService::new(audit_repository);
     /// let user_id = ObjectId::parse_str("user_id").unwrap();
@@ -68,34 +51,13 @@ impl PermissionService {
     ///
     /// * `Option<Permission>` - The Permission entity.
     /// * `Error` - The Error that occurred.
-    pub async fn create(
+    async fn create(
         &self,
         new_permission: Permission,
         user_id: Option<ObjectId>,
         db: &Database,
-        audit: &AuditService,
-    ) -> Result<Permission, Error> {
-        info!("Creating Permission: {}", new_permission);
-
-        if user_id.is_some() {
-            let new_audit = Audit::new(
-                user_id.unwrap(),
-                Create,
-                new_permission.id,
-                ResourceIdType::PermissionId,
-                PermissionResourceType,
-            );
-            match audit.create(new_audit, db).await {
-                Ok(_) => {}
-                Err(e) => {
-                    error!("Failed to create Audit: {}", e);
-                    return Err(Error::Audit(e));
-                }
-            }
-        }
-
-        self.permission_repository.create(new_permission, db).await
-    }
+        audit: &Arc<dyn AuditService>,
+    ) -> Result<Permission, Error>;
 
     /// # Summary
     ///
@@ -112,7 +74,7 @@ impl PermissionService {
     ///
     /// ```
     /// let permission_repository = PermissionRepository::new(String::from("permissions"));
-    /// let permission_service = PermissionService::new(permission_repository);
+    /// let permission_service = MongoPermissionService::new(permission_repository);
     /// let db = mongodb::Database::new();
     /// let permissions = permission_service.find_all(limit, page, &db);
     /// ```
@@ -121,15 +83,12 @@ impl PermissionService {
     ///
     /// * `Vec<Permission>` - The Permission entities.
     /// * `Error` - The Error that occurred.
-    pub async fn find_all(
+    async fn find_all(
         &self,
         limit: Option<i64>,
         page: Option<i64>,
         db: &Database,
-    ) -> Result<Vec<Permission>, Error> {
-        info!("Finding all permissions");
-        self.permission_repository.find_all(limit, page, db).await
-    }
+    ) -> Result<Vec<Permission>, Error>;
 
     /// # Summary
     ///
@@ -146,9 +105,9 @@ impl PermissionService {
     ///
     /// ```
     /// let permission_repository = PermissionRepository::new(String::from("permissions"));
-    /// let permission_service = PermissionService::new(permission_repository);
+    /// let permission_service = MongoPermissionService::new(permission_repository);
     /// let db = mongodb::Database::new();
-    /// let id_vec = vec![String::from("id")];
+    /// let id_vec = vec![ObjectId::new()];
     /// let permissions = permission_service.find_by_id_vec(id_vec, &db);
     /// ```
     ///
@@ -156,14 +115,11 @@ impl PermissionService {
     ///
     /// * `Vec<Permission>` - The Permission entities.
     /// * `Error` - The Error that occurred.
-    pub async fn find_by_id_vec(
+    async fn find_by_id_vec(
         &self,
-        id_vec: Vec<String>,
+        id_vec: Vec<ObjectId>,
         db: &Database,
-    ) -> Result<Vec<Permission>, Error> {
-        info!("Finding permissions by id_vec: {:?}", id_vec);
-        self.permission_repository.find_by_id_vec(id_vec, db).await
-    }
+    ) -> Result<Vec<Permission>, Error>;
 
     /// # Summary
     ///
@@ -178,7 +134,7 @@ impl PermissionService {
     ///
     /// ```
     /// let permission_repository = PermissionRepository::new(String::from("permissions"));
-    /// let permission_service = PermissionService::new(permission_repository);
+    /// let permission_service = MongoPermissionService::new(permission_repository);
     /// let db = mongodb::Database::new();
     ///
     /// let permission = permission_service.find_by_id(String::from("id"), &db);
@@ -188,10 +144,7 @@ impl PermissionService {
     ///
     /// * `Option<Permission>` - The Permission entity.
     /// * `Error` - The Error that occurred.
-    pub async fn find_by_id(&self, id: &str, db: &Database) -> Result<Option<Permission>, Error> {
-        info!("Finding Permission by ID: {}", id);
-        self.permission_repository.find_by_id(id, db).await
-    }
+    async fn find_by_id(&self, id: &str, db: &Database) -> Result<Option<Permission>, Error>;
 
     /// # Summary
     ///
@@ -208,7 +161,7 @@ impl PermissionService {
     ///
     /// ```
     /// let permission_repository = PermissionRepository::new(String::from("permissions"));
-    /// let permission_service = PermissionService::new(permission_repository);
+    /// let permission_service = MongoPermissionService::new(permission_repository);
     /// let db = mongodb::Database::new();
     /// let name = String::from("name");
     /// let permission = permission_service.find_by_name(name, &db);
@@ -217,14 +170,7 @@ impl PermissionService {
     /// # Returns
     ///
     /// * `Result<Option<Permission>, Error>` - The result of the operation.
-    pub async fn find_by_name(
-        &self,
-        name: &str,
-        db: &Database,
-    ) -> Result<Option<Permission>, Error> {
-        info!("Finding Permission by name: {}", name);
-        self.permission_repository.find_by_name(name, db).await
-    }
+    async fn find_by_name(&self, name: &str, db: &Database) -> Result<Option<Permission>, Error>;
 
     /// # Summary
     ///
@@ -241,7 +187,7 @@ impl PermissionService {
     ///
     /// ```
     /// let permission_repository = PermissionRepository::new(String::from("permissions"));
-    /// let permission_service = PermissionService::new(permission_repository);
+    /// let permission_service = MongoPermissionService::new(permission_repository);
     /// let db = mongodb::Database::new();
     /// let audit_service = AuditService::new(audit_repository);
     /// let user_id = ObjectId::parse_str("user_id").unwrap();
@@ -253,34 +199,13 @@ impl PermissionService {
     ///
     /// * `Permission` - The Permission entity.
     /// * `Error` - The Error that occurred.
-    pub async fn update(
+    async fn update(
         &self,
         permission: Permission,
         user_id: Option<ObjectId>,
         db: &Database,
-        audit: &AuditService,
-    ) -> Result<Permission, Error> {
-        info!("Updating Permission: {}", permission);
-
-        if user_id.is_some() {
-            let new_audit = Audit::new(
-                user_id.unwrap(),
-                Update,
-                permission.id,
-                ResourceIdType::PermissionId,
-                PermissionResourceType,
-            );
-            match audit.create(new_audit, db).await {
-                Ok(_) => {}
-                Err(e) => {
-                    error!("Failed to create Audit: {}", e);
-                    return Err(Error::Audit(e));
-                }
-            }
-        }
-
-        self.permission_repository.update(permission, db).await
-    }
+        audit: &Arc<dyn AuditService>,
+    ) -> Result<Permission, Error>;
 
     /// # Summary
     ///
@@ -298,7 +223,7 @@ impl PermissionService {
     ///
     /// ```
     /// let permission_repository = PermissionRepository::new(String::from("permissions"));
-    /// let permission_service = PermissionService::new(permission_repository);
+    /// let permission_service = MongoPermissionService::new(permission_repository);
     /// let db = mongodb::Database::new();
     /// let audit_service = AuditService::new(audit_repository);
     /// let role_service = RoleService::new(role_repository);
@@ -312,13 +237,312 @@ impl PermissionService {
     ///
     /// * `()` - The operation was successful.
     /// * `Error` - The Error that occurred.
-    pub async fn delete(
+    async fn delete(
+        &self,
+        id: &str,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        role_service: &Arc<dyn RoleService>,
+        audit: &Arc<dyn AuditService>,
+    ) -> Result<(), Error>;
+
+    /// # Summary
+    ///
+    /// Search for Permission entities by text.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to search for.
+    /// * `limit` - The limit of Permission entities to find.
+    /// * `page` - The page of Permission entities to find.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let permission_repository = PermissionRepository::new(String::from("permissions"));
+    /// let permission_service = MongoPermissionService::new(permission_repository);
+    /// let db = mongodb::Database::new();
+    /// let text = String::from("text");
+    /// let permissions = permission_service.search(text, limit, page, &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<SearchHit<Permission>>` - The Permission entities and their relevance, the most relevant first.
+    /// * `Error` - The Error that occurred.
+    async fn search(
+        &self,
+        text: &str,
+        limit: Option<i64>,
+        page: Option<i64>,
+        db: &Database,
+    ) -> Result<Vec<SearchHit<Permission>>, Error>;
+
+    /// # Summary
+    ///
+    /// Count the Permission entities, optionally only the ones that match a text search.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to search for, if any.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let total = permission_service.count(Some("text"), &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64, Error>` - The number of Permission entities.
+    async fn count(&self, text: Option<&str>, db: &Database) -> Result<u64, Error>;
+
+    /// # Summary
+    ///
+    /// Load all Permission entities into the in-process cache, so that the first requests after a start do not have
+    /// to wait for the Database.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - The Database to load the Permission entities from.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let count = permission_service.warm_cache(&db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<usize, Error>` - The number of Permission entities that were loaded, or zero if caching is disabled.
+    async fn warm_cache(&self, db: &Database) -> Result<usize, Error>;
+
+    /// # Summary
+    ///
+    /// Remove all Permission entities from the in-process cache.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// permission_service.clear_cache();
+    /// ```
+    fn clear_cache(&self);
+
+    /// # Summary
+    ///
+    /// Get the statistics of the in-process cache.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// if let Some(stats) = permission_service.cache_stats() {
+    ///     println!("{:.2}", stats.hit_rate());
+    /// }
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Option<CacheStats>` - The statistics, or None if caching is disabled.
+    fn cache_stats(&self) -> Option<CacheStats>;
+}
+
+impl MongoPermissionService {
+    /// # Summary
+    ///
+    /// Create a new MongoPermissionService.
+    ///
+    /// # Arguments
+    ///
+    /// * `permission_repository` - The PermissionRepository to be used by the PermissionService.
+    /// * `cache` - The in-process cache of Permission entities, or None to always read them from the Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let permission_repository = PermissionRepository::new(String::from("permissions"));
+    /// let permission_service = MongoPermissionService::new(permission_repository, None);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `MongoPermissionService` - The new MongoPermissionService.
+    pub fn new(
+        permission_repository: PermissionRepository,
+        cache: Option<Arc<EntityCache<Permission>>>,
+    ) -> MongoPermissionService {
+        MongoPermissionService {
+            permission_repository,
+            cache,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Remove all Permission entities from the in-process cache after a change.
+    fn invalidate_cache(&self) {
+        if let Some(c) = &self.cache {
+            c.clear();
+        }
+    }
+}
+
+#[async_trait]
+impl PermissionService for MongoPermissionService {
+    /// # Summary
+    ///
+    /// Create a new Permission entity.
+    async fn create(
+        &self,
+        new_permission: Permission,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit: &Arc<dyn AuditService>,
+    ) -> Result<Permission, Error> {
+        info!("Creating Permission: {}", new_permission);
+
+        if user_id.is_some() {
+            let new_audit = Audit::new(
+                user_id.unwrap(),
+                Create,
+                new_permission.id,
+                ResourceIdType::PermissionId,
+                PermissionResourceType,
+            );
+            match audit.create(new_audit, db).await {
+                Ok(_) => {}
+                Err(e) => {
+                    error!("Failed to create Audit: {}", e);
+                    return Err(Error::Audit(e));
+                }
+            }
+        }
+
+        let res = self.permission_repository.create(new_permission, db).await;
+        self.invalidate_cache();
+        res
+    }
+
+    /// # Summary
+    ///
+    /// Find all Permission entities.
+    async fn find_all(
+        &self,
+        limit: Option<i64>,
+        page: Option<i64>,
+        db: &Database,
+    ) -> Result<Vec<Permission>, Error> {
+        info!("Finding all permissions");
+        self.permission_repository.find_all(limit, page, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Find all Permission entities by id.
+    async fn find_by_id_vec(
+        &self,
+        id_vec: Vec<ObjectId>,
+        db: &Database,
+    ) -> Result<Vec<Permission>, Error> {
+        info!("Finding permissions by id_vec: {:?}", id_vec);
+
+        let cache = match &self.cache {
+            Some(c) => c,
+            None => return self.permission_repository.find_by_id_vec(id_vec, db).await,
+        };
+
+        let keys: Vec<String> = id_vec
+            .iter()
+            .map(|id| cache_key(db, &id.to_hex()))
+            .collect();
+        let (mut permissions, missing) = cache.get_many(&keys);
+        if missing.is_empty() {
+            return Ok(permissions);
+        }
+
+        let missing_ids: Vec<ObjectId> = id_vec
+            .into_iter()
+            .zip(keys)
+            .filter(|(_, k)| missing.contains(k))
+            .map(|(id, _)| id)
+            .collect();
+
+        let fetched = self
+            .permission_repository
+            .find_by_id_vec(missing_ids, db)
+            .await?;
+        cache.insert_many(
+            fetched
+                .iter()
+                .map(|p| (cache_key(db, &p.id.to_hex()), p.clone()))
+                .collect(),
+        );
+        permissions.extend(fetched);
+
+        Ok(permissions)
+    }
+
+    /// # Summary
+    ///
+    /// Find a Permission entity by id.
+    async fn find_by_id(&self, id: &str, db: &Database) -> Result<Option<Permission>, Error> {
+        info!("Finding Permission by ID: {}", id);
+        self.permission_repository.find_by_id(id, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Find a Permission by its name.
+    async fn find_by_name(&self, name: &str, db: &Database) -> Result<Option<Permission>, Error> {
+        info!("Finding Permission by name: {}", name);
+        self.permission_repository.find_by_name(name, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Update a Permission entity.
+    async fn update(
+        &self,
+        permission: Permission,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit: &Arc<dyn AuditService>,
+    ) -> Result<Permission, Error> {
+        info!("Updating Permission: {}", permission);
+
+        if user_id.is_some() {
+            let new_audit = Audit::new(
+                user_id.unwrap(),
+                Update,
+                permission.id,
+                ResourceIdType::PermissionId,
+                PermissionResourceType,
+            );
+            match audit.create(new_audit, db).await {
+                Ok(_) => {}
+                Err(e) => {
+                    error!("Failed to create Audit: {}", e);
+                    return Err(Error::Audit(e));
+                }
+            }
+        }
+
+        let res = self.permission_repository.update(permission, db).await;
+        self.invalidate_cache();
+        res
+    }
+
+    /// # Summary
+    ///
+    /// Delete a Permission entity.
+    async fn delete(
         &self,
         id: &str,
         user_id: Option<ObjectId>,
         db: &Database,
-        role_service: &RoleService,
-        audit: &AuditService,
+        role_service: &Arc<dyn RoleService>,
+        audit: &Arc<dyn AuditService>,
     ) -> Result<(), Error> {
         info!("Deleting Permission by ID: {}", id);
 
@@ -346,46 +570,70 @@ impl PermissionService {
             }
         }
 
-        self.permission_repository
+        let res = self
+            .permission_repository
             .delete(id, db, role_service)
-            .await
+            .await;
+        self.invalidate_cache();
+        res
     }
 
     /// # Summary
     ///
     /// Search for Permission entities by text.
-    ///
-    /// # Arguments
-    ///
-    /// * `text` - The text to search for.
-    /// * `limit` - The limit of Permission entities to find.
-    /// * `page` - The page of Permission entities to find.
-    /// * `db` - The Database to be used.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// let permission_repository = PermissionRepository::new(String::from("permissions"));
-    /// let permission_service = PermissionService::new(permission_repository);
-    /// let db = mongodb::Database::new();
-    /// let text = String::from("text");
-    /// let permissions = permission_service.search(text, limit, page, &db);
-    /// ```
-    ///
-    /// # Returns
-    ///
-    /// * `Vec<Permission>` - The Permission entities.
-    /// * `Error` - The Error that occurred.
-    pub async fn search(
+    async fn search(
         &self,
         text: &str,
         limit: Option<i64>,
         page: Option<i64>,
         db: &Database,
-    ) -> Result<Vec<Permission>, Error> {
+    ) -> Result<Vec<SearchHit<Permission>>, Error> {
         info!("Searching for Permission by text: {}", text);
         self.permission_repository
             .search(text, limit, page, db)
             .await
     }
+
+    /// # Summary
+    ///
+    /// Count the Permission entities, optionally only the ones that match a text search.
+    async fn count(&self, text: Option<&str>, db: &Database) -> Result<u64, Error> {
+        self.permission_repository.count(text, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Load all Permission entities into the in-process cache.
+    async fn warm_cache(&self, db: &Database) -> Result<usize, Error> {
+        let cache = match &self.cache {
+            Some(c) => c,
+            None => return Ok(0),
+        };
+
+        let permissions = self.permission_repository.find_all(None, None, db).await?;
+        let count = permissions.len();
+        cache.insert_many(
+            permissions
+                .into_iter()
+                .map(|p| (cache_key(db, &p.id.to_hex()), p))
+                .collect(),
+        );
+
+        info!("Loaded {} Permission(s) into the cache", count);
+        Ok(count)
+    }
+
+    /// # Summary
+    ///
+    /// Remove all Permission entities from the in-process cache.
+    fn clear_cache(&self) {
+        self.invalidate_cache();
+    }
+
+    /// # Summary
+    ///
+    /// Get the statistics of the in-process cache.
+    fn cache_stats(&self) -> Option<CacheStats> {
+        self.cache.as_ref().map(|c| c.stats())
+    }
 }
@@ -0,0 +1 @@
+pub mod quota_service;
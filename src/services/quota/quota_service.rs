@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// The length of a quota window.
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// The amount of tracked callers after which expired windows are removed.
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// The maximum amount of tracked callers. The caller with the oldest window is evicted to make room for a new one.
+const MAX_SUBJECTS: usize = 100_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuotaMode {
+    Enforce,
+    Monitor,
+}
+
+impl Display for QuotaMode {
+    /// # Summary
+    ///
+    /// Display the QuotaMode.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The Formatter.
+    ///
+    /// # Returns
+    ///
+    /// * `std::fmt::Result` - The result of the display.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QuotaMode::Enforce => write!(f, "enforce"),
+            QuotaMode::Monitor => write!(f, "monitor"),
+        }
+    }
+}
+
+impl FromStr for QuotaMode {
+    type Err = String;
+
+    /// # Summary
+    ///
+    /// Parse a QuotaMode.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The mode, either `enforce` or `monitor`.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<QuotaMode, String>` - The QuotaMode, or an error message if the mode is unknown.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "enforce" => Ok(QuotaMode::Enforce),
+            "monitor" => Ok(QuotaMode::Monitor),
+            _ => Err(format!("Unknown quota mode: {}", s)),
+        }
+    }
+}
+
+struct QuotaWindow {
+    started_at: Instant,
+    requests: u32,
+}
+
+pub struct QuotaStatus {
+    pub allowed: bool,
+    pub enforced: bool,
+    pub limit: u32,
+    pub remaining: u32,
+    pub reset: u64,
+    pub requests: u32,
+    pub subject: String,
+}
+
+#[derive(Clone)]
+pub struct QuotaService {
+    pub requests_per_minute: u32,
+    pub mode: QuotaMode,
+    windows: Arc<Mutex<HashMap<String, QuotaWindow>>>,
+}
+
+impl QuotaService {
+    /// # Summary
+    ///
+    /// Create a new QuotaService.
+    ///
+    /// # Arguments
+    ///
+    /// * `requests_per_minute` - The amount of requests that a single caller can make per minute, or zero to disable the quota.
+    /// * `mode` - Whether requests that exceed the quota are rejected, or only reported.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let quota_service = QuotaService::new(120, QuotaMode::Enforce);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `QuotaService` - The new QuotaService.
+    pub fn new(requests_per_minute: u32, mode: QuotaMode) -> QuotaService {
+        QuotaService {
+            requests_per_minute,
+            mode,
+            windows: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Register a request that is made by an authenticated caller and check whether it fits within the quota of that caller.
+    ///
+    /// # Arguments
+    ///
+    /// * `subject` - The authenticated caller that makes the request, such as `user:<id>`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let status = quota_service.consume("user:6523f1b5e4b0a1a2b3c4d5e6");
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Option<QuotaStatus>` - The QuotaStatus of the caller, or None if the quota is disabled.
+    pub fn consume(&self, subject: &str) -> Option<QuotaStatus> {
+        if self.requests_per_minute == 0 {
+            return None;
+        }
+
+        let now = Instant::now();
+
+        let mut windows = match self.windows.lock() {
+            Ok(w) => w,
+            Err(e) => e.into_inner(),
+        };
+
+        if windows.len() > PRUNE_THRESHOLD {
+            windows.retain(|_, w| now.duration_since(w.started_at) < WINDOW);
+        }
+
+        if windows.len() >= MAX_SUBJECTS && !windows.contains_key(subject) {
+            let oldest = windows
+                .iter()
+                .min_by_key(|(_, w)| w.started_at)
+                .map(|(k, _)| k.clone());
+            if let Some(k) = oldest {
+                windows.remove(&k);
+            }
+        }
+
+        let window = windows.entry(subject.to_string()).or_insert(QuotaWindow {
+            started_at: now,
+            requests: 0,
+        });
+
+        if now.duration_since(window.started_at) >= WINDOW {
+            window.started_at = now;
+            window.requests = 0;
+        }
+
+        // Requests that exceed the quota are counted as well in monitor mode, so that offenders can be told apart
+        let allowed = window.requests < self.requests_per_minute;
+        let enforced = self.mode == QuotaMode::Enforce;
+        if allowed || !enforced {
+            window.requests = window.requests.saturating_add(1);
+        }
+
+        let reset = WINDOW.saturating_sub(now.duration_since(window.started_at));
+
+        Some(QuotaStatus {
+            allowed,
+            enforced,
+            limit: self.requests_per_minute,
+            remaining: self.requests_per_minute.saturating_sub(window.requests),
+            reset: reset.as_secs(),
+            requests: window.requests,
+            subject: subject.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quota_is_tracked_per_subject() {
+        let quota_service = QuotaService::new(1, QuotaMode::Enforce);
+
+        assert!(quota_service.consume("user:a").unwrap().allowed);
+        assert!(!quota_service.consume("user:a").unwrap().allowed);
+        assert!(quota_service.consume("user:b").unwrap().allowed);
+    }
+
+    #[test]
+    fn oldest_subject_is_evicted_at_capacity() {
+        let quota_service = QuotaService::new(1, QuotaMode::Enforce);
+
+        for i in 0..MAX_SUBJECTS {
+            quota_service.consume(&format!("user:{}", i));
+        }
+        quota_service.consume("user:new");
+
+        let windows = quota_service.windows.lock().unwrap();
+        assert_eq!(windows.len(), MAX_SUBJECTS);
+        assert!(windows.contains_key("user:new"));
+    }
+}
@@ -0,0 +1,416 @@
+use crate::repository::refresh_token::refresh_token_model::RefreshToken;
+use crate::repository::refresh_token::refresh_token_repository::{Error, RefreshTokenRepository};
+use crate::services::client::client_service::hash_secret;
+use crate::services::jwt::jwt_service::JwtService;
+use async_trait::async_trait;
+use log::{info, warn};
+use mongodb::bson::oid::ObjectId;
+use mongodb::Database;
+
+#[derive(Clone)]
+pub struct MongoRefreshTokenService {
+    pub refresh_token_repository: RefreshTokenRepository,
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait RefreshTokenService: Send + Sync {
+    /// # Summary
+    ///
+    /// Issue a refresh token to a User that just logged in.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User.
+    /// * `token_version` - The token version of the User.
+    /// * `jwt_service` - The JwtService to be used.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let (refresh_token, token) = refresh_token_service.create(user.id, user.token_version, &jwt_service, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(RefreshToken, String), Error>` - The created RefreshToken and its token. The token is not stored and cannot be retrieved afterwards.
+    async fn create(
+        &self,
+        user_id: ObjectId,
+        token_version: i64,
+        jwt_service: &JwtService,
+        db: &Database,
+    ) -> Result<(RefreshToken, String), Error>;
+
+    /// # Summary
+    ///
+    /// Exchange a refresh token for its successor. Every refresh token can only be used once: presenting a token that
+    /// was already used revokes its whole family, because either the client or an attacker holds a stolen copy.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The refresh token.
+    /// * `jwt_service` - The JwtService to be used.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let (refresh_token, token) = refresh_token_service.rotate("token", &jwt_service, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(RefreshToken, String), Error>` - The successor RefreshToken and its token.
+    async fn rotate(
+        &self,
+        token: &str,
+        jwt_service: &JwtService,
+        db: &Database,
+    ) -> Result<(RefreshToken, String), Error>;
+
+    /// # Summary
+    ///
+    /// Revoke all refresh tokens of a family.
+    ///
+    /// # Arguments
+    ///
+    /// * `family` - The ID of the family.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// refresh_token_service.revoke_family(refresh_token.family, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    async fn revoke_family(&self, family: ObjectId, db: &Database) -> Result<(), Error>;
+
+    /// # Summary
+    ///
+    /// Revoke the family of a refresh token that was issued to a User, for example when the User logs out.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The refresh token.
+    /// * `user_id` - The ID of the User that presents the refresh token.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// refresh_token_service.revoke("token", user_id, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    async fn revoke(&self, token: &str, user_id: ObjectId, db: &Database) -> Result<(), Error>;
+
+    /// # Summary
+    ///
+    /// Revoke all refresh tokens that were issued to a User.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// refresh_token_service.revoke_all(user_id, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    async fn revoke_all(&self, user_id: ObjectId, db: &Database) -> Result<(), Error>;
+}
+
+/// The operations of the RefreshTokenRepository that the rotation of a refresh token relies on, so that the
+/// detection of reused refresh tokens can be tested without a database.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+trait RefreshTokenStore: Send + Sync {
+    async fn create(
+        &self,
+        refresh_token: RefreshToken,
+        db: &Database,
+    ) -> Result<RefreshToken, Error>;
+
+    async fn find_by_token_hash(
+        &self,
+        token_hash: &str,
+        db: &Database,
+    ) -> Result<Option<RefreshToken>, Error>;
+
+    async fn mark_used(&self, id: ObjectId, db: &Database) -> Result<bool, Error>;
+
+    async fn delete_by_family(&self, family: ObjectId, db: &Database) -> Result<(), Error>;
+}
+
+#[async_trait]
+impl RefreshTokenStore for RefreshTokenRepository {
+    async fn create(
+        &self,
+        refresh_token: RefreshToken,
+        db: &Database,
+    ) -> Result<RefreshToken, Error> {
+        RefreshTokenRepository::create(self, refresh_token, db).await
+    }
+
+    async fn find_by_token_hash(
+        &self,
+        token_hash: &str,
+        db: &Database,
+    ) -> Result<Option<RefreshToken>, Error> {
+        RefreshTokenRepository::find_by_token_hash(self, token_hash, db).await
+    }
+
+    async fn mark_used(&self, id: ObjectId, db: &Database) -> Result<bool, Error> {
+        RefreshTokenRepository::mark_used(self, id, db).await
+    }
+
+    async fn delete_by_family(&self, family: ObjectId, db: &Database) -> Result<(), Error> {
+        RefreshTokenRepository::delete_by_family(self, family, db).await
+    }
+}
+
+/// # Summary
+///
+/// Exchange a refresh token for its successor. Presenting a token that was already used revokes its whole family.
+///
+/// # Arguments
+///
+/// * `store` - The RefreshTokenStore to be used.
+/// * `token` - The refresh token.
+/// * `jwt_service` - The JwtService to be used.
+/// * `db` - The Database to be used.
+///
+/// # Returns
+///
+/// * `Result<(RefreshToken, String), Error>` - The successor RefreshToken and its token.
+async fn rotate_in_store(
+    store: &impl RefreshTokenStore,
+    token: &str,
+    jwt_service: &JwtService,
+    db: &Database,
+) -> Result<(RefreshToken, String), Error> {
+    if token.is_empty() {
+        return Err(Error::InvalidToken);
+    }
+
+    let used = match store.find_by_token_hash(&hash_secret(token), db).await? {
+        Some(r) => r,
+        None => return Err(Error::InvalidToken),
+    };
+
+    if used.is_expired() {
+        return Err(Error::TokenExpired);
+    }
+
+    if !store.mark_used(used.id, db).await? {
+        warn!("RefreshToken was used twice, revoking its family: {}", used);
+        info!("Revoking RefreshToken family: {}", used.family.to_hex());
+        store.delete_by_family(used.family, db).await?;
+        return Err(Error::TokenReused);
+    }
+
+    let (refresh_token, token) = match jwt_service.rotate_refresh_token(&used) {
+        Some(r) => r,
+        None => return Err(Error::Disabled),
+    };
+
+    info!("Rotating RefreshToken: {}", used);
+    let refresh_token = store.create(refresh_token, db).await?;
+    Ok((refresh_token, token))
+}
+
+impl MongoRefreshTokenService {
+    /// # Summary
+    ///
+    /// Create a new MongoRefreshTokenService.
+    ///
+    /// # Arguments
+    ///
+    /// * `refresh_token_repository` - The RefreshTokenRepository.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let refresh_token_repository = RefreshTokenRepository::new(String::from("refreshTokens"));
+    /// let refresh_token_service = MongoRefreshTokenService::new(refresh_token_repository);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `MongoRefreshTokenService` - The new MongoRefreshTokenService.
+    pub fn new(refresh_token_repository: RefreshTokenRepository) -> MongoRefreshTokenService {
+        MongoRefreshTokenService {
+            refresh_token_repository,
+        }
+    }
+}
+
+#[async_trait]
+impl RefreshTokenService for MongoRefreshTokenService {
+    /// # Summary
+    ///
+    /// Issue a refresh token to a User that just logged in.
+    async fn create(
+        &self,
+        user_id: ObjectId,
+        token_version: i64,
+        jwt_service: &JwtService,
+        db: &Database,
+    ) -> Result<(RefreshToken, String), Error> {
+        let (refresh_token, token) =
+            match jwt_service.generate_refresh_token(user_id, token_version) {
+                Some(r) => r,
+                None => return Err(Error::Disabled),
+            };
+
+        info!("Creating RefreshToken: {}", refresh_token);
+        let refresh_token = self
+            .refresh_token_repository
+            .create(refresh_token, db)
+            .await?;
+        Ok((refresh_token, token))
+    }
+
+    /// # Summary
+    ///
+    /// Exchange a refresh token for its successor.
+    async fn rotate(
+        &self,
+        token: &str,
+        jwt_service: &JwtService,
+        db: &Database,
+    ) -> Result<(RefreshToken, String), Error> {
+        rotate_in_store(&self.refresh_token_repository, token, jwt_service, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Revoke all refresh tokens of a family.
+    async fn revoke_family(&self, family: ObjectId, db: &Database) -> Result<(), Error> {
+        info!("Revoking RefreshToken family: {}", family.to_hex());
+        self.refresh_token_repository
+            .delete_by_family(family, db)
+            .await
+    }
+
+    /// # Summary
+    ///
+    /// Revoke the family of a refresh token that was issued to a User.
+    async fn revoke(&self, token: &str, user_id: ObjectId, db: &Database) -> Result<(), Error> {
+        let refresh_token = match self
+            .refresh_token_repository
+            .find_by_token_hash(&hash_secret(token), db)
+            .await?
+        {
+            Some(r) if r.user_id == user_id => r,
+            _ => return Err(Error::InvalidToken),
+        };
+
+        self.revoke_family(refresh_token.family, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Revoke all refresh tokens that were issued to a User.
+    async fn revoke_all(&self, user_id: ObjectId, db: &Database) -> Result<(), Error> {
+        info!("Revoking RefreshTokens of User: {}", user_id.to_hex());
+        self.refresh_token_repository
+            .delete_by_user_id(user_id, db)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configuration::jwt_config::JwtConfig;
+    use crate::test_support::{self, MockServices};
+
+    fn jwt_service() -> JwtService {
+        JwtService::new(JwtConfig::new(String::from("secret"), 3600, 0, 1209600)).unwrap()
+    }
+
+    fn stored(used: bool) -> RefreshToken {
+        let mut refresh_token = RefreshToken::new(ObjectId::new(), ObjectId::new(), 0, 1209600);
+        if used {
+            refresh_token.used_at = Some(mongodb::bson::DateTime::now());
+        }
+
+        refresh_token
+    }
+
+    #[actix_web::test]
+    async fn reused_token_revokes_its_family() {
+        let refresh_token = stored(true);
+        let family = refresh_token.family;
+
+        let mut store = MockRefreshTokenStore::new();
+        store
+            .expect_find_by_token_hash()
+            .returning(move |_, _| Ok(Some(refresh_token.clone())));
+        store.expect_mark_used().returning(|_, _| Ok(false));
+        store
+            .expect_delete_by_family()
+            .withf(move |f, _| *f == family)
+            .times(1)
+            .returning(|_, _| Ok(()));
+        store.expect_create().never();
+
+        let db = test_support::config(MockServices::new()).await.database;
+        let res = rotate_in_store(&store, "token", &jwt_service(), &db).await;
+
+        assert!(matches!(res, Err(Error::TokenReused)));
+    }
+
+    #[actix_web::test]
+    async fn unused_token_is_rotated_within_its_family() {
+        let refresh_token = stored(false);
+        let family = refresh_token.family;
+
+        let mut store = MockRefreshTokenStore::new();
+        store
+            .expect_find_by_token_hash()
+            .returning(move |_, _| Ok(Some(refresh_token.clone())));
+        store.expect_mark_used().returning(|_, _| Ok(true));
+        store.expect_delete_by_family().never();
+        store.expect_create().times(1).returning(|r, _| Ok(r));
+
+        let db = test_support::config(MockServices::new()).await.database;
+        let (successor, token) = rotate_in_store(&store, "token", &jwt_service(), &db)
+            .await
+            .unwrap();
+
+        assert_eq!(successor.family, family);
+        assert_eq!(successor.token_hash, hash_secret(&token));
+    }
+
+    #[actix_web::test]
+    async fn expired_token_is_not_rotated() {
+        let mut refresh_token = stored(false);
+        refresh_token.expires_at = chrono::Utc::now() - chrono::Duration::seconds(1);
+
+        let mut store = MockRefreshTokenStore::new();
+        store
+            .expect_find_by_token_hash()
+            .returning(move |_, _| Ok(Some(refresh_token.clone())));
+        store.expect_mark_used().never();
+        store.expect_create().never();
+
+        let db = test_support::config(MockServices::new()).await.database;
+        let res = rotate_in_store(&store, "token", &jwt_service(), &db).await;
+
+        assert!(matches!(res, Err(Error::TokenExpired)));
+    }
+}
@@ -0,0 +1 @@
+pub mod revoked_token_service;
@@ -0,0 +1,124 @@
+use crate::repository::revoked_token::revoked_token_model::RevokedToken;
+use crate::repository::revoked_token::revoked_token_repository::{Error, RevokedTokenRepository};
+use crate::services::client::client_service::hash_secret;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use log::info;
+use mongodb::Database;
+
+#[derive(Clone)]
+pub struct MongoRevokedTokenService {
+    pub revoked_token_repository: RevokedTokenRepository,
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait RevokedTokenService: Send + Sync {
+    /// # Summary
+    ///
+    /// Revoke a JWT token, so that it is rejected for the rest of its lifetime.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The JWT token.
+    /// * `subject` - The subject of the JWT token.
+    /// * `expires_at` - The time after which the JWT token is rejected anyway. The token is kept on the denylist until then.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// revoked_token_service.revoke(token, claims.subject(), expires_at, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    async fn revoke(
+        &self,
+        token: &str,
+        subject: &str,
+        expires_at: DateTime<Utc>,
+        db: &Database,
+    ) -> Result<(), Error>;
+
+    /// # Summary
+    ///
+    /// Check whether a JWT token was revoked.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The JWT token.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// if revoked_token_service.is_revoked(token, &db).await? {
+    ///     return HttpResponse::Forbidden().finish();
+    /// }
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<bool, Error>` - True if the JWT token was revoked, false otherwise.
+    async fn is_revoked(&self, token: &str, db: &Database) -> Result<bool, Error>;
+}
+
+impl MongoRevokedTokenService {
+    /// # Summary
+    ///
+    /// Create a new MongoRevokedTokenService.
+    ///
+    /// # Arguments
+    ///
+    /// * `revoked_token_repository` - The RevokedTokenRepository.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let revoked_token_repository = RevokedTokenRepository::new(String::from("revokedTokens"));
+    /// let revoked_token_service = MongoRevokedTokenService::new(revoked_token_repository);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `MongoRevokedTokenService` - The new MongoRevokedTokenService.
+    pub fn new(revoked_token_repository: RevokedTokenRepository) -> MongoRevokedTokenService {
+        MongoRevokedTokenService {
+            revoked_token_repository,
+        }
+    }
+}
+
+#[async_trait]
+impl RevokedTokenService for MongoRevokedTokenService {
+    /// # Summary
+    ///
+    /// Revoke a JWT token, so that it is rejected for the rest of its lifetime.
+    async fn revoke(
+        &self,
+        token: &str,
+        subject: &str,
+        expires_at: DateTime<Utc>,
+        db: &Database,
+    ) -> Result<(), Error> {
+        // Only the hash is stored, so that the denylist cannot be used to replay tokens that were not yet expired
+        let revoked_token =
+            RevokedToken::new(hash_secret(token), String::from(subject), expires_at);
+
+        info!("Creating RevokedToken: {}", revoked_token);
+        self.revoked_token_repository
+            .create(revoked_token, db)
+            .await
+    }
+
+    /// # Summary
+    ///
+    /// Check whether a JWT token was revoked.
+    async fn is_revoked(&self, token: &str, db: &Database) -> Result<bool, Error> {
+        self.revoked_token_repository
+            .exists(&hash_secret(token), db)
+            .await
+    }
+}
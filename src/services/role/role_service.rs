@@ -1,42 +1,31 @@
+use crate::components::entity_cache::{cache_key, CacheStats, EntityCache};
 use crate::repository::audit::audit_model::Action::{Create, Delete, Update};
 use crate::repository::audit::audit_model::{Audit, ResourceIdType, ResourceType};
 use crate::repository::audit::audit_repository::Error as AuditError;
+use crate::repository::role::role_filter::RoleFilter;
 use crate::repository::role::role_model::Role;
 use crate::repository::role::role_repository::{Error, RoleRepository};
+use crate::repository::search_hit::SearchHit;
 use crate::services::audit::audit_service::AuditService;
 use crate::services::user::user_service::UserService;
-use log::{error, info};
+use async_trait::async_trait;
+use log::{error, info, warn};
 use mongodb::bson::oid::ObjectId;
 use mongodb::Database;
+use std::collections::HashMap;
+use std::sync::Arc;
 
 #[derive(Clone)]
-pub struct RoleService {
+pub struct MongoRoleService {
     pub role_repository: RoleRepository,
+    pub domain_roles: HashMap<String, Vec<String>>,
+    pub max_permissions: usize,
+    pub cache: Option<Arc<EntityCache<Role>>>,
 }
 
-impl RoleService {
-    /// # Summary
-    ///
-    /// Create a new RoleService.
-    ///
-    /// # Arguments
-    ///
-    /// * `role_repository` - The RoleRepository to be used by the RoleService.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// let role_repository = RoleRepository::new(String::from("roles"));
-    /// let role_service = RoleService::new(role_repository);
-    /// ```
-    ///
-    /// # Returns
-    ///
-    /// * `RoleService` - The new RoleService.
-    pub fn new(role_repository: RoleRepository) -> RoleService {
-        RoleService { role_repository }
-    }
-
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait RoleService: Send + Sync {
     /// # Summary
     ///
     /// Create a new Role.
@@ -52,7 +41,7 @@ impl RoleService {
     ///
     /// ```
     /// let role_repository = RoleRepository::new(String::from("roles"));
-    /// let role_service = RoleService::new(role_repository);
+    /// let role_service = MongoRoleService::new(role_repository);
     /// let db = mongodb::Database::new();
     /// let audit_service = AuditService::new(AuditRepository::new(String::from("audits")));
     /// let role = Role::new(String::from("role_name"));
@@ -65,34 +54,13 @@ impl RoleService {
     ///
     /// * `Role` - The created Role entity.
     /// * `Error` - The Error that occurred.
-    pub async fn create(
+    async fn create(
         &self,
         role: Role,
         user_id: Option<ObjectId>,
         db: &Database,
-        audit_service: &AuditService,
-    ) -> Result<Role, Error> {
-        info!("Creating Role: {}", role);
-
-        if user_id.is_some() {
-            let new_audit = Audit::new(
-                user_id.unwrap(),
-                Create,
-                role.id,
-                ResourceIdType::RoleId,
-                ResourceType::Role,
-            );
-            match audit_service.create(new_audit, db).await {
-                Ok(_) => {}
-                Err(e) => {
-                    error!("Failed to create Audit: {}", e);
-                    return Err(Error::Audit(e));
-                }
-            }
-        }
-
-        self.role_repository.create(role, db).await
-    }
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<Role, Error>;
 
     /// # Summary
     ///
@@ -108,7 +76,7 @@ impl RoleService {
     ///
     /// ```
     /// let role_repository = RoleRepository::new(String::from("roles"));
-    /// let role_service = RoleService::new(role_repository);
+    /// let role_service = MongoRoleService::new(role_repository);
     /// let db = mongodb::Database::new();
     ///
     /// let roles = role_service.find_all(Some(100), Some(1), &db);
@@ -118,15 +86,12 @@ impl RoleService {
     ///
     /// * `Vec<Role>` - The Role entities.
     /// * `Error` - The Error that occurred.
-    pub async fn find_all(
+    async fn find_all(
         &self,
         limit: Option<i64>,
         page: Option<i64>,
         db: &Database,
-    ) -> Result<Vec<Role>, Error> {
-        info!("Finding all roles");
-        self.role_repository.find_all(limit, page, db).await
-    }
+    ) -> Result<Vec<Role>, Error>;
 
     /// # Summary
     ///
@@ -143,7 +108,7 @@ impl RoleService {
     ///
     /// ```
     /// let role_repository = RoleRepository::new(String::from("roles"));
-    /// let role_service = RoleService::new(role_repository);
+    /// let role_service = MongoRoleService::new(role_repository);
     /// let db = mongodb::Database::new();
     /// let id = "role_id";
     ///
@@ -154,10 +119,7 @@ impl RoleService {
     ///
     /// * `Option<Role>` - The optional Role entity.
     /// * `Error` - The Error that occurred.
-    pub async fn find_by_id(&self, id: &str, db: &Database) -> Result<Option<Role>, Error> {
-        info!("Finding Role by ID: {}", id);
-        self.role_repository.find_by_id(id, db).await
-    }
+    async fn find_by_id(&self, id: &str, db: &Database) -> Result<Option<Role>, Error>;
 
     /// # Summary
     ///
@@ -174,7 +136,7 @@ impl RoleService {
     ///
     /// ```
     /// let role_repository = RoleRepository::new(String::from("roles"));
-    /// let role_service = RoleService::new(role_repository);
+    /// let role_service = MongoRoleService::new(role_repository);
     /// let db = mongodb::Database::new();
     /// let id_vec = vec!["role_id"];
     ///
@@ -185,14 +147,7 @@ impl RoleService {
     ///
     /// * `Vec<Role>` - The vector of Role entities.
     /// * `Error` - The Error that occurred.
-    pub async fn find_by_id_vec(
-        &self,
-        id_vec: Vec<String>,
-        db: &Database,
-    ) -> Result<Vec<Role>, Error> {
-        info!("Finding roles by id vec: {:?}", id_vec);
-        self.role_repository.find_by_id_vec(id_vec, db).await
-    }
+    async fn find_by_id_vec(&self, id_vec: Vec<String>, db: &Database) -> Result<Vec<Role>, Error>;
 
     /// # Summary
     ///
@@ -209,7 +164,7 @@ impl RoleService {
     ///
     /// ```
     /// let role_repository = RoleRepository::new(String::from("roles"));
-    /// let role_service = RoleService::new(role_repository);
+    /// let role_service = MongoRoleService::new(role_repository);
     /// let db = mongodb::Database::new();
     /// let name = "role_name";
     ///
@@ -219,10 +174,108 @@ impl RoleService {
     /// # Returns
     ///
     /// A Result with an Option of a Role instance or an Error.
-    pub async fn find_by_name(&self, name: &str, db: &Database) -> Result<Option<Role>, Error> {
-        info!("Finding Role by name: {}", name);
-        self.role_repository.find_by_name(name, db).await
-    }
+    async fn find_by_name(&self, name: &str, db: &Database) -> Result<Option<Role>, Error>;
+
+    /// # Summary
+    ///
+    /// Find the roles that are granted to new Users based on the domain of their email address.
+    ///
+    /// # Arguments
+    ///
+    /// * `email` - The email address of the new User.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let role_repository = RoleRepository::new(String::from("roles"));
+    /// let role_service = MongoRoleService::new(role_repository, domain_roles);
+    /// let db = mongodb::Database::new();
+    ///
+    /// let roles = role_service.find_by_email_domain("user@partner.com", &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the roles that are mapped to the domain, or an Error.
+    async fn find_by_email_domain(&self, email: &str, db: &Database) -> Result<Vec<Role>, Error>;
+
+    /// # Summary
+    ///
+    /// Find a Role by its name within the scope of an Organization. The roles of the Organization take precedence
+    /// over global roles with the same name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - A string slice that holds the name.
+    /// * `organization_id` - The ID of the Organization, or None to only find global roles.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let role = role_service.find_by_name_in_scope("role_name", Some(organization_id), &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with an Option of a Role instance or an Error.
+    async fn find_by_name_in_scope(
+        &self,
+        name: &str,
+        organization_id: Option<ObjectId>,
+        db: &Database,
+    ) -> Result<Option<Role>, Error>;
+
+    /// # Summary
+    ///
+    /// Find all Role entities that belong to an Organization.
+    ///
+    /// # Arguments
+    ///
+    /// * `organization_id` - The ID of the Organization.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let roles = role_service.find_by_organization_id(organization_id, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Role>, Error>` - The Role entities of the Organization, excluding global roles.
+    async fn find_by_organization_id(
+        &self,
+        organization_id: ObjectId,
+        db: &Database,
+    ) -> Result<Vec<Role>, Error>;
+
+    /// # Summary
+    ///
+    /// Find a vector of Role entities by their ids, ignoring the roles that cannot be used within the scope.
+    ///
+    /// # Arguments
+    ///
+    /// * `id_vec` - The vector of ids of the Role entities.
+    /// * `organization_id` - The ID of the Organization, or None for the global scope.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let roles = role_service.find_in_scope(id_vec, Some(organization_id), &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Role>, Error>` - The global roles and the roles of the Organization.
+    async fn find_in_scope(
+        &self,
+        id_vec: Vec<String>,
+        organization_id: Option<ObjectId>,
+        db: &Database,
+    ) -> Result<Vec<Role>, Error>;
 
     /// # Summary
     ///
@@ -239,7 +292,7 @@ impl RoleService {
     ///
     /// ```
     /// let role_repository = RoleRepository::new(String::from("roles"));
-    /// let role_service = RoleService::new(role_repository);
+    /// let role_service = MongoRoleService::new(role_repository);
     /// let db = mongodb::Database::new();
     /// let audit_service = AuditService::new(AuditRepository::new(String::from("audits")));
     /// let role = Role::new(String::from("role_name"), vec!["permission_id"]);
@@ -252,34 +305,45 @@ impl RoleService {
     ///
     /// * `Role` - The updated Role entity.
     /// * `Error` - The Error that occurred.
-    pub async fn update(
+    async fn update(
         &self,
         role: Role,
         user_id: Option<ObjectId>,
         db: &Database,
-        audit_service: &AuditService,
-    ) -> Result<Role, Error> {
-        info!("Updating Role: {}", role);
-
-        if user_id.is_some() {
-            let new_audit = Audit::new(
-                user_id.unwrap(),
-                Update,
-                role.id,
-                ResourceIdType::RoleId,
-                ResourceType::Role,
-            );
-            match audit_service.create(new_audit, db).await {
-                Ok(_) => {}
-                Err(e) => {
-                    error!("Failed to create Audit: {}", e);
-                    return Err(Error::Audit(e));
-                }
-            }
-        }
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<Role, Error>;
 
-        self.role_repository.update(role, db).await
-    }
+    /// # Summary
+    ///
+    /// Replace the permissions of a Role entity, unless they were changed since an earlier version of the Role entity
+    /// was read.
+    ///
+    /// # Arguments
+    ///
+    /// * `role` - The Role entity with the new permissions.
+    /// * `expected` - The earlier version of the Role entity, whose permissions the Role entity must still grant.
+    /// * `user_id` - The id of the User updating the Role entity.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let updated_role = role_service.update_permissions(role, &previous, user_id, &db, &audit_service).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Role>` - The updated Role entity, or None if its permissions were changed in the meantime.
+    /// * `Error` - The Error that occurred.
+    async fn update_permissions(
+        &self,
+        role: Role,
+        expected: &Role,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<Option<Role>, Error>;
 
     /// # Summary
     ///
@@ -297,7 +361,7 @@ impl RoleService {
     ///
     /// ```
     /// let role_repository = RoleRepository::new(String::from("roles"));
-    /// let role_service = RoleService::new(role_repository);
+    /// let role_service = MongoRoleService::new(role_repository);
     /// let db = mongodb::Database::new();
     /// let audit_service = AuditService::new(AuditRepository::new(String::from("audits")));
     /// let id = "role_id";
@@ -311,42 +375,14 @@ impl RoleService {
     ///
     /// * `()` - The operation was successful.
     /// * `Error` - The Error that occurred.
-    pub async fn delete(
+    async fn delete(
         &self,
         id: &str,
         user_id: Option<ObjectId>,
         db: &Database,
-        user_service: &UserService,
-        audit_service: &AuditService,
-    ) -> Result<(), Error> {
-        info!("Deleting Role by ID: {}", id);
-
-        if user_id.is_some() {
-            let oid = match ObjectId::parse_str(id) {
-                Ok(oid) => oid,
-                Err(e) => {
-                    return Err(Error::Audit(AuditError::ObjectId(e.to_string())));
-                }
-            };
-
-            let new_audit = Audit::new(
-                user_id.unwrap(),
-                Delete,
-                oid,
-                ResourceIdType::RoleId,
-                ResourceType::Role,
-            );
-            match audit_service.create(new_audit, db).await {
-                Ok(_) => {}
-                Err(e) => {
-                    error!("Failed to create Audit: {}", e);
-                    return Err(Error::Audit(e));
-                }
-            }
-        }
-
-        self.role_repository.delete(id, db, user_service).await
-    }
+        user_service: &Arc<dyn UserService>,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(), Error>;
 
     /// # Summary
     ///
@@ -361,7 +397,7 @@ impl RoleService {
     ///
     /// ```
     /// let role_repository = RoleRepository::new(String::from("roles"));
-    /// let role_service = RoleService::new(role_repository);
+    /// let role_service = MongoRoleService::new(role_repository);
     /// let db = mongodb::Database::new();
     ///
     /// let res = role_service.delete_permission_from_all_roles("id", &db).await;
@@ -371,27 +407,67 @@ impl RoleService {
     ///
     /// * `()` - The operation was successful.
     /// * `Error` - The Error that occurred.
-    pub async fn delete_permission_from_all_roles(
+    async fn delete_permission_from_all_roles(
         &self,
         permission_id: &str,
         db: &Database,
-    ) -> Result<(), Error> {
-        info!(
-            "Deleting permission {} from all Role entities",
-            permission_id
-        );
-        self.role_repository
-            .delete_permission_from_all_roles(permission_id, db)
-            .await
-    }
+    ) -> Result<(), Error>;
+
+    /// # Summary
+    ///
+    /// Delete all Role entities of an Organization.
+    ///
+    /// # Arguments
+    ///
+    /// * `organization_id` - The ID of the Organization.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// role_service.delete_by_organization_id(organization_id, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    async fn delete_by_organization_id(
+        &self,
+        organization_id: ObjectId,
+        db: &Database,
+    ) -> Result<(), Error>;
+
+    /// # Summary
+    ///
+    /// Remove the Permissions of which the grant has expired from all Role entities.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let role_repository = RoleRepository::new(String::from("roles"));
+    /// let role_service = RoleService::new(role_repository);
+    /// let db = mongodb::Database::new();
+    ///
+    /// role_service.remove_expired_permissions(&db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `()` - The expired Permissions were removed.
+    /// * `Error` - The Error that occurred.
+    async fn remove_expired_permissions(&self, db: &Database) -> Result<(), Error>;
 
     /// # Summary
     ///
-    /// Search for Role entities by text.
+    /// Search for Role entities by text and/or structured criteria.
     ///
     /// # Arguments
     ///
-    /// * `text` - The text to search for.
+    /// * `filter` - The criteria that the Role entities must meet.
     /// * `limit` - The limit of Role entities to find.
     /// * `page` - The page of Role entities to find.
     /// * `db` - The Database to be used.
@@ -400,24 +476,621 @@ impl RoleService {
     ///
     /// ```
     /// let role_repository = RoleRepository::new(String::from("roles"));
-    /// let role_service = RoleService::new(role_repository);
+    /// let role_service = MongoRoleService::new(role_repository);
     /// let db = mongodb::Database::new();
     ///
-    /// let result = role_service.search("text", Some(100), Some(1), &db).await;
+    /// let filter = RoleFilter {
+    ///     text: Some(String::from("text")),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let result = role_service.search(&filter, Some(100), Some(1), &db).await;
     /// ```
     ///
     /// # Returns
     ///
-    /// * `Vec<Role>` - The vector of Role entities.
+    /// * `Vec<SearchHit<Role>>` - The Role entities and their relevance, the most relevant first if the filter contains text.
     /// * `Error` - The Error that occurred.
-    pub async fn search(
+    async fn search(
         &self,
-        text: &str,
+        filter: &RoleFilter,
         limit: Option<i64>,
         page: Option<i64>,
         db: &Database,
-    ) -> Result<Vec<Role>, Error> {
-        info!("Searching for Role by text: {}", text);
-        self.role_repository.search(text, limit, page, db).await
+    ) -> Result<Vec<SearchHit<Role>>, Error>;
+
+    /// # Summary
+    ///
+    /// Count the global Role entities that match a RoleFilter.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - The criteria that the Role entities must meet.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let total = role_service.count(&RoleFilter::default(), &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64, Error>` - The number of Role entities.
+    async fn count(&self, filter: &RoleFilter, db: &Database) -> Result<u64, Error>;
+
+    /// # Summary
+    ///
+    /// Load all global Role entities into the in-process cache, so that the first requests after a start do not have
+    /// to wait for the Database.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - The Database to load the Role entities from.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let count = role_service.warm_cache(&db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<usize, Error>` - The number of Role entities that were loaded, or zero if caching is disabled.
+    async fn warm_cache(&self, db: &Database) -> Result<usize, Error>;
+
+    /// # Summary
+    ///
+    /// Remove all Role entities from the in-process cache.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// role_service.clear_cache();
+    /// ```
+    fn clear_cache(&self);
+
+    /// # Summary
+    ///
+    /// Get the statistics of the in-process cache.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// if let Some(stats) = role_service.cache_stats() {
+    ///     println!("{:.2}", stats.hit_rate());
+    /// }
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Option<CacheStats>` - The statistics, or None if caching is disabled.
+    fn cache_stats(&self) -> Option<CacheStats>;
+}
+
+impl MongoRoleService {
+    /// # Summary
+    ///
+    /// Create a new MongoRoleService.
+    ///
+    /// # Arguments
+    ///
+    /// * `role_repository` - The RoleRepository to be used by the RoleService.
+    /// * `domain_roles` - The names of the roles that are granted to new Users, by the domain of their email address.
+    /// * `max_permissions` - The maximum number of Permissions that a Role can have, or zero for no limit.
+    /// * `cache` - The in-process cache of Role entities, or None to always read them from the Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let role_repository = RoleRepository::new(String::from("roles"));
+    /// let role_service = MongoRoleService::new(role_repository, HashMap::new(), 1000, None);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `MongoRoleService` - The new MongoRoleService.
+    pub fn new(
+        role_repository: RoleRepository,
+        domain_roles: HashMap<String, Vec<String>>,
+        max_permissions: usize,
+        cache: Option<Arc<EntityCache<Role>>>,
+    ) -> MongoRoleService {
+        MongoRoleService {
+            role_repository,
+            domain_roles,
+            max_permissions,
+            cache,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check that a Role does not have more Permissions than allowed.
+    ///
+    /// # Arguments
+    ///
+    /// * `role` - The Role to check.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - TooManyPermissions if the Role has more Permissions than allowed.
+    fn check_permissions(&self, role: &Role) -> Result<(), Error> {
+        let count = role.permissions.as_ref().map_or(0, |p| p.len());
+
+        if self.max_permissions > 0 && count > self.max_permissions {
+            return Err(Error::TooManyPermissions(self.max_permissions));
+        }
+
+        Ok(())
+    }
+
+    /// # Summary
+    ///
+    /// Remove all Role entities from the in-process cache after a change, so that no stale roles or permissions are
+    /// granted. Roles are rarely changed, so clearing the whole cache is cheaper than tracking which entries are
+    /// affected.
+    fn invalidate_cache(&self) {
+        if let Some(c) = &self.cache {
+            c.clear();
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check whether the name of a Role collides with another Role. Global roles only collide with other global
+    /// roles, which is checked by the RoleRepository. Roles of an Organization additionally collide with the other
+    /// roles of the same Organization.
+    ///
+    /// # Arguments
+    ///
+    /// * `role` - The Role to check.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - NameAlreadyTaken if the name is used by another Role of the same Organization.
+    async fn check_collision(&self, role: &Role, db: &Database) -> Result<(), Error> {
+        let organization_id = match role.organization_id {
+            Some(o) => o,
+            None => return Ok(()),
+        };
+
+        match self
+            .role_repository
+            .find_by_name_in_organization(&role.name, organization_id, db)
+            .await?
+        {
+            Some(r) if r.id != role.id => Err(Error::NameAlreadyTaken),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[async_trait]
+impl RoleService for MongoRoleService {
+    /// # Summary
+    ///
+    /// Create a new Role.
+    async fn create(
+        &self,
+        role: Role,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<Role, Error> {
+        info!("Creating Role: {}", role);
+        self.check_permissions(&role)?;
+        self.check_collision(&role, db).await?;
+
+        if user_id.is_some() {
+            let new_audit = Audit::new(
+                user_id.unwrap(),
+                Create,
+                role.id,
+                ResourceIdType::RoleId,
+                ResourceType::Role,
+            );
+            match audit_service.create(new_audit, db).await {
+                Ok(_) => {}
+                Err(e) => {
+                    error!("Failed to create Audit: {}", e);
+                    return Err(Error::Audit(e));
+                }
+            }
+        }
+
+        let res = self.role_repository.create(role, db).await;
+        self.invalidate_cache();
+        res
+    }
+
+    /// # Summary
+    ///
+    /// Find all Role entities.
+    async fn find_all(
+        &self,
+        limit: Option<i64>,
+        page: Option<i64>,
+        db: &Database,
+    ) -> Result<Vec<Role>, Error> {
+        info!("Finding all roles");
+        self.role_repository.find_all(limit, page, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Find a Role entity by its ID.
+    async fn find_by_id(&self, id: &str, db: &Database) -> Result<Option<Role>, Error> {
+        info!("Finding Role by ID: {}", id);
+        self.role_repository.find_by_id(id, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Find a vector of Role entities by their ids.
+    async fn find_by_id_vec(&self, id_vec: Vec<String>, db: &Database) -> Result<Vec<Role>, Error> {
+        info!("Finding roles by id vec: {:?}", id_vec);
+
+        let cache = match &self.cache {
+            Some(c) => c,
+            None => return self.role_repository.find_by_id_vec(id_vec, db).await,
+        };
+
+        let keys: Vec<String> = id_vec.iter().map(|id| cache_key(db, id)).collect();
+        let (mut roles, missing) = cache.get_many(&keys);
+        if missing.is_empty() {
+            return Ok(roles);
+        }
+
+        let missing_ids: Vec<String> = id_vec
+            .into_iter()
+            .zip(keys)
+            .filter(|(_, k)| missing.contains(k))
+            .map(|(id, _)| id)
+            .collect();
+
+        let fetched = self.role_repository.find_by_id_vec(missing_ids, db).await?;
+        cache.insert_many(
+            fetched
+                .iter()
+                .map(|r| (cache_key(db, &r.id.to_hex()), r.clone()))
+                .collect(),
+        );
+        roles.extend(fetched);
+
+        Ok(roles)
+    }
+
+    /// # Summary
+    ///
+    /// Find a role by its name.
+    async fn find_by_name(&self, name: &str, db: &Database) -> Result<Option<Role>, Error> {
+        info!("Finding Role by name: {}", name);
+        self.role_repository.find_by_name(name, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Find the roles that are granted to new Users based on the domain of their email address.
+    /// Roles that are mapped to the domain but do not exist are skipped.
+    async fn find_by_email_domain(&self, email: &str, db: &Database) -> Result<Vec<Role>, Error> {
+        let domain = match email.rsplit_once('@') {
+            Some((_, d)) => d.to_lowercase(),
+            None => return Ok(vec![]),
+        };
+
+        let names = match self.domain_roles.get(&domain) {
+            Some(n) => n,
+            None => return Ok(vec![]),
+        };
+
+        info!("Finding roles by email domain: {}", domain);
+
+        let mut roles: Vec<Role> = vec![];
+        for name in names {
+            match self.role_repository.find_by_name(name, db).await? {
+                Some(r) => roles.push(r),
+                None => warn!(
+                    "Role {} that is mapped to email domain {} does not exist",
+                    name, domain
+                ),
+            }
+        }
+
+        Ok(roles)
+    }
+
+    /// # Summary
+    ///
+    /// Find a Role by its name within the scope of an Organization.
+    async fn find_by_name_in_scope(
+        &self,
+        name: &str,
+        organization_id: Option<ObjectId>,
+        db: &Database,
+    ) -> Result<Option<Role>, Error> {
+        info!(
+            "Finding Role by name {} in scope {:?}",
+            name, organization_id
+        );
+
+        if let Some(o) = organization_id {
+            if let Some(r) = self
+                .role_repository
+                .find_by_name_in_organization(name, o, db)
+                .await?
+            {
+                return Ok(Some(r));
+            }
+        }
+
+        self.role_repository.find_by_name(name, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Find all Role entities that belong to an Organization.
+    async fn find_by_organization_id(
+        &self,
+        organization_id: ObjectId,
+        db: &Database,
+    ) -> Result<Vec<Role>, Error> {
+        info!(
+            "Finding Roles of Organization: {}",
+            organization_id.to_hex()
+        );
+        self.role_repository
+            .find_by_organization_id(organization_id, db)
+            .await
+    }
+
+    /// # Summary
+    ///
+    /// Find a vector of Role entities by their ids, ignoring the roles that cannot be used within the scope.
+    async fn find_in_scope(
+        &self,
+        id_vec: Vec<String>,
+        organization_id: Option<ObjectId>,
+        db: &Database,
+    ) -> Result<Vec<Role>, Error> {
+        Ok(self
+            .find_by_id_vec(id_vec, db)
+            .await?
+            .into_iter()
+            .filter(|r| r.in_scope(organization_id.as_ref()))
+            .collect())
+    }
+
+    /// # Summary
+    ///
+    /// Update a Role entity.
+    async fn update(
+        &self,
+        role: Role,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<Role, Error> {
+        info!("Updating Role: {}", role);
+        self.check_permissions(&role)?;
+        self.check_collision(&role, db).await?;
+
+        if user_id.is_some() {
+            let new_audit = Audit::new(
+                user_id.unwrap(),
+                Update,
+                role.id,
+                ResourceIdType::RoleId,
+                ResourceType::Role,
+            );
+            match audit_service.create(new_audit, db).await {
+                Ok(_) => {}
+                Err(e) => {
+                    error!("Failed to create Audit: {}", e);
+                    return Err(Error::Audit(e));
+                }
+            }
+        }
+
+        let res = self.role_repository.update(role, db).await;
+        self.invalidate_cache();
+        res
+    }
+
+    /// # Summary
+    ///
+    /// Replace the permissions of a Role entity unless they were changed in the meantime.
+    async fn update_permissions(
+        &self,
+        role: Role,
+        expected: &Role,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<Option<Role>, Error> {
+        info!("Updating permissions of Role: {}", role);
+        self.check_permissions(&role)?;
+
+        let role_id = role.id;
+        let res = match self
+            .role_repository
+            .update_permissions(role, expected, db)
+            .await?
+        {
+            Some(r) => r,
+            None => return Ok(None),
+        };
+        self.invalidate_cache();
+
+        // The change is only audited once it was applied, because it is not applied if the Role was changed
+        if let Some(user_id) = user_id {
+            let new_audit = Audit::new(
+                user_id,
+                Update,
+                role_id,
+                ResourceIdType::RoleId,
+                ResourceType::Role,
+            );
+            if let Err(e) = audit_service.create(new_audit, db).await {
+                error!("Failed to create Audit: {}", e);
+                return Err(Error::Audit(e));
+            }
+        }
+
+        Ok(Some(res))
+    }
+
+    /// # Summary
+    ///
+    /// Delete a Role entity by its id.
+    async fn delete(
+        &self,
+        id: &str,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        user_service: &Arc<dyn UserService>,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(), Error> {
+        info!("Deleting Role by ID: {}", id);
+
+        if user_id.is_some() {
+            let oid = match ObjectId::parse_str(id) {
+                Ok(oid) => oid,
+                Err(e) => {
+                    return Err(Error::Audit(AuditError::ObjectId(e.to_string())));
+                }
+            };
+
+            let new_audit = Audit::new(
+                user_id.unwrap(),
+                Delete,
+                oid,
+                ResourceIdType::RoleId,
+                ResourceType::Role,
+            );
+            match audit_service.create(new_audit, db).await {
+                Ok(_) => {}
+                Err(e) => {
+                    error!("Failed to create Audit: {}", e);
+                    return Err(Error::Audit(e));
+                }
+            }
+        }
+
+        let res = self.role_repository.delete(id, db, user_service).await;
+        self.invalidate_cache();
+        res
+    }
+
+    /// # Summary
+    ///
+    /// Delete a Permission entity from all Role entities.
+    async fn delete_permission_from_all_roles(
+        &self,
+        permission_id: &str,
+        db: &Database,
+    ) -> Result<(), Error> {
+        info!(
+            "Deleting permission {} from all Role entities",
+            permission_id
+        );
+        let res = self
+            .role_repository
+            .delete_permission_from_all_roles(permission_id, db)
+            .await;
+        self.invalidate_cache();
+        res
+    }
+
+    /// # Summary
+    ///
+    /// Delete all Role entities of an Organization.
+    async fn delete_by_organization_id(
+        &self,
+        organization_id: ObjectId,
+        db: &Database,
+    ) -> Result<(), Error> {
+        info!(
+            "Deleting Roles of Organization: {}",
+            organization_id.to_hex()
+        );
+        let res = self
+            .role_repository
+            .delete_by_organization_id(organization_id, db)
+            .await;
+        self.invalidate_cache();
+        res
+    }
+
+    /// # Summary
+    ///
+    /// Remove the Permissions of which the grant has expired from all Role entities.
+    async fn remove_expired_permissions(&self, db: &Database) -> Result<(), Error> {
+        let count = self.role_repository.remove_expired_permissions(db).await?;
+
+        if count > 0 {
+            info!("Removed expired Permission grants from {} Role(s)", count);
+            self.invalidate_cache();
+        }
+
+        Ok(())
+    }
+
+    /// # Summary
+    ///
+    /// Search for Role entities by text and/or structured criteria.
+    async fn search(
+        &self,
+        filter: &RoleFilter,
+        limit: Option<i64>,
+        page: Option<i64>,
+        db: &Database,
+    ) -> Result<Vec<SearchHit<Role>>, Error> {
+        info!("Searching for Role by filter");
+        self.role_repository.search(filter, limit, page, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Count the global Role entities that match a RoleFilter.
+    async fn count(&self, filter: &RoleFilter, db: &Database) -> Result<u64, Error> {
+        self.role_repository.count(filter, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Load all global Role entities into the in-process cache.
+    async fn warm_cache(&self, db: &Database) -> Result<usize, Error> {
+        let cache = match &self.cache {
+            Some(c) => c,
+            None => return Ok(0),
+        };
+
+        let roles = self.role_repository.find_all(None, None, db).await?;
+        let count = roles.len();
+        cache.insert_many(
+            roles
+                .into_iter()
+                .map(|r| (cache_key(db, &r.id.to_hex()), r))
+                .collect(),
+        );
+
+        info!("Loaded {} Role(s) into the cache", count);
+        Ok(count)
+    }
+
+    /// # Summary
+    ///
+    /// Remove all Role entities from the in-process cache.
+    fn clear_cache(&self) {
+        self.invalidate_cache();
+    }
+
+    /// # Summary
+    ///
+    /// Get the statistics of the in-process cache.
+    fn cache_stats(&self) -> Option<CacheStats> {
+        self.cache.as_ref().map(|c| c.stats())
     }
 }
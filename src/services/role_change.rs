@@ -0,0 +1 @@
+pub mod role_change_service;
@@ -0,0 +1,399 @@
+use crate::configuration::role_change_config::RoleChangeConfig;
+use crate::repository::role::role_repository::Error as RoleError;
+use crate::repository::role_change::role_change_model::{
+    RoleChange, RoleChangeKind, RoleChangeStatus,
+};
+use crate::repository::role_change::role_change_repository::{Error, RoleChangeRepository};
+use crate::repository::user::user_repository::Error as UserError;
+use crate::services::audit::audit_service::AuditService;
+use crate::services::role::role_service::RoleService;
+use crate::services::user::user_service::UserService;
+use async_trait::async_trait;
+use log::{error, info};
+use mongodb::bson::oid::ObjectId;
+use mongodb::Database;
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct MongoRoleChangeService {
+    pub role_change_repository: RoleChangeRepository,
+    pub role_change_config: RoleChangeConfig,
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait RoleChangeService: Send + Sync {
+    /// # Summary
+    ///
+    /// Check whether changes to the permissions of roles and grants of the ADMIN role must be approved by a second User.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// if role_change_service.approval_required() {
+    ///     role_change_service.request(change, user_id, &db).await?;
+    /// }
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the two-person rule is enabled.
+    fn approval_required(&self) -> bool;
+
+    /// # Summary
+    ///
+    /// Store a change that is applied once it is approved by a User other than the one that requested it.
+    ///
+    /// # Arguments
+    ///
+    /// * `change` - The change that is applied once it is approved.
+    /// * `requested_by` - The ID of the User that requested the change.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let role_change = role_change_service.request(RoleChangeKind::AdminGrant { user_id, role_id }, requested_by, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<RoleChange, Error>` - The pending RoleChange.
+    async fn request(
+        &self,
+        change: RoleChangeKind,
+        requested_by: ObjectId,
+        db: &Database,
+    ) -> Result<RoleChange, Error>;
+
+    /// # Summary
+    ///
+    /// Find all RoleChange entities that are waiting for a review.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let role_changes = role_change_service.find_pending(&db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<RoleChange>, Error>` - The pending RoleChange entities, oldest first.
+    async fn find_pending(&self, db: &Database) -> Result<Vec<RoleChange>, Error>;
+
+    /// # Summary
+    ///
+    /// Approve a pending RoleChange and apply it. The change is applied and audited on behalf of the reviewer. A change to
+    /// the permissions of a Role is rejected instead if the permissions of the Role were changed after it was requested.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the RoleChange.
+    /// * `reviewed_by` - The ID of the User that approves the RoleChange.
+    /// * `db` - The Database to be used.
+    /// * `role_service` - The RoleService to be used.
+    /// * `user_service` - The UserService to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let role_change = role_change_service.approve("id", user_id, &db, &role_service, &user_service, &audit_service).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<RoleChange, Error>` - The approved RoleChange.
+    async fn approve(
+        &self,
+        id: &str,
+        reviewed_by: ObjectId,
+        db: &Database,
+        role_service: &Arc<dyn RoleService>,
+        user_service: &Arc<dyn UserService>,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<RoleChange, Error>;
+
+    /// # Summary
+    ///
+    /// Reject a pending RoleChange without applying it.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the RoleChange.
+    /// * `reviewed_by` - The ID of the User that rejects the RoleChange.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let role_change = role_change_service.reject("id", user_id, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<RoleChange, Error>` - The rejected RoleChange.
+    async fn reject(
+        &self,
+        id: &str,
+        reviewed_by: ObjectId,
+        db: &Database,
+    ) -> Result<RoleChange, Error>;
+}
+
+impl MongoRoleChangeService {
+    /// # Summary
+    ///
+    /// Create a new MongoRoleChangeService.
+    ///
+    /// # Arguments
+    ///
+    /// * `role_change_repository` - The RoleChangeRepository to be used.
+    /// * `role_change_config` - The RoleChangeConfig to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let role_change_service = MongoRoleChangeService::new(role_change_repository, role_change_config);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `MongoRoleChangeService` - The new MongoRoleChangeService.
+    pub fn new(
+        role_change_repository: RoleChangeRepository,
+        role_change_config: RoleChangeConfig,
+    ) -> MongoRoleChangeService {
+        MongoRoleChangeService {
+            role_change_repository,
+            role_change_config,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Find a pending RoleChange that may be reviewed by a User.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the RoleChange.
+    /// * `reviewed_by` - The ID of the User that reviews the RoleChange.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<RoleChange, Error>` - The RoleChange, if it is pending and was requested by another User.
+    async fn find_reviewable(
+        &self,
+        id: &str,
+        reviewed_by: ObjectId,
+        db: &Database,
+    ) -> Result<RoleChange, Error> {
+        let role_change = match self.role_change_repository.find_by_id(id, db).await? {
+            Some(r) => r,
+            None => return Err(Error::RoleChangeNotFound(id.to_string())),
+        };
+
+        if role_change.status != RoleChangeStatus::Pending {
+            return Err(Error::NotPending(id.to_string()));
+        }
+
+        if role_change.requested_by == reviewed_by {
+            return Err(Error::SelfReview);
+        }
+
+        Ok(role_change)
+    }
+
+    /// # Summary
+    ///
+    /// Apply an approved RoleChange on behalf of its reviewer.
+    ///
+    /// # Arguments
+    ///
+    /// * `role_change` - The RoleChange.
+    /// * `reviewed_by` - The ID of the User that approved the RoleChange.
+    /// * `db` - The Database to be used.
+    /// * `role_service` - The RoleService to be used.
+    /// * `user_service` - The UserService to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    async fn apply(
+        role_change: &RoleChange,
+        reviewed_by: ObjectId,
+        db: &Database,
+        role_service: &Arc<dyn RoleService>,
+        user_service: &Arc<dyn UserService>,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(), Error> {
+        match &role_change.change {
+            RoleChangeKind::RolePermissions {
+                role_id,
+                permissions,
+                permission_expirations,
+                previous_permissions,
+                previous_permission_expirations,
+            } => {
+                let role = match role_service
+                    .find_by_id(&role_id.to_hex(), db)
+                    .await
+                    .map_err(Error::Role)?
+                {
+                    Some(r) => r,
+                    None => return Err(Error::Role(RoleError::RoleNotFound(role_id.to_hex()))),
+                };
+
+                let mut expected = role.clone();
+                expected.permissions = previous_permissions.clone();
+                expected.permission_expirations = previous_permission_expirations.clone();
+
+                let mut role = role;
+                role.permissions = permissions.clone();
+                role.permission_expirations = permission_expirations.clone();
+
+                match role_service
+                    .update_permissions(role, &expected, Some(reviewed_by), db, audit_service)
+                    .await
+                    .map_err(Error::Role)?
+                {
+                    Some(_) => Ok(()),
+                    None => Err(Error::RoleChanged(role_id.to_hex())),
+                }
+            }
+            RoleChangeKind::AdminGrant { user_id, role_id } => {
+                let mut user = match user_service
+                    .find_by_id(&user_id.to_hex(), db)
+                    .await
+                    .map_err(Error::User)?
+                {
+                    Some(u) => u,
+                    None => return Err(Error::User(UserError::UserNotFound(user_id.to_hex()))),
+                };
+
+                let roles = user.roles.get_or_insert_with(Vec::new);
+                if !roles.contains(role_id) {
+                    roles.push(*role_id);
+                }
+
+                user_service
+                    .update(user, Some(reviewed_by), db, audit_service)
+                    .await
+                    .map_err(Error::User)?;
+
+                Ok(())
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl RoleChangeService for MongoRoleChangeService {
+    /// # Summary
+    ///
+    /// Check whether the two-person rule is enabled.
+    fn approval_required(&self) -> bool {
+        self.role_change_config.approval_required
+    }
+
+    /// # Summary
+    ///
+    /// Store a pending RoleChange.
+    async fn request(
+        &self,
+        change: RoleChangeKind,
+        requested_by: ObjectId,
+        db: &Database,
+    ) -> Result<RoleChange, Error> {
+        let role_change = RoleChange::new(change, requested_by);
+
+        info!("Requesting RoleChange: {}", role_change);
+        self.role_change_repository.create(role_change, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Find all pending RoleChange entities.
+    async fn find_pending(&self, db: &Database) -> Result<Vec<RoleChange>, Error> {
+        info!("Finding pending RoleChanges");
+        self.role_change_repository.find_pending(db).await
+    }
+
+    /// # Summary
+    ///
+    /// Approve and apply a pending RoleChange.
+    async fn approve(
+        &self,
+        id: &str,
+        reviewed_by: ObjectId,
+        db: &Database,
+        role_service: &Arc<dyn RoleService>,
+        user_service: &Arc<dyn UserService>,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<RoleChange, Error> {
+        let role_change = self.find_reviewable(id, reviewed_by, db).await?;
+
+        info!("Approving RoleChange: {}", role_change);
+
+        // The RoleChange is marked as approved before it is applied, so that concurrent approvals cannot apply it twice
+        let role_change = self
+            .role_change_repository
+            .review(role_change.id, RoleChangeStatus::Approved, reviewed_by, db)
+            .await?;
+
+        if let Err(e) = Self::apply(
+            &role_change,
+            reviewed_by,
+            db,
+            role_service,
+            user_service,
+            audit_service,
+        )
+        .await
+        {
+            // A change to a Role that was changed in the meantime no longer applies, while any other failure leaves the
+            // RoleChange pending, so that it can be approved again
+            let status = match e {
+                Error::RoleChanged(_) => RoleChangeStatus::Rejected,
+                _ => RoleChangeStatus::Pending,
+            };
+            if let Err(r) = self
+                .role_change_repository
+                .revert_approval(role_change.id, status, db)
+                .await
+            {
+                error!(
+                    "Failed to revert the approval of RoleChange {}: {}",
+                    role_change.id, r
+                );
+            }
+
+            return Err(e);
+        }
+
+        Ok(role_change)
+    }
+
+    /// # Summary
+    ///
+    /// Reject a pending RoleChange.
+    async fn reject(
+        &self,
+        id: &str,
+        reviewed_by: ObjectId,
+        db: &Database,
+    ) -> Result<RoleChange, Error> {
+        let role_change = self.find_reviewable(id, reviewed_by, db).await?;
+
+        info!("Rejecting RoleChange: {}", role_change);
+        self.role_change_repository
+            .review(role_change.id, RoleChangeStatus::Rejected, reviewed_by, db)
+            .await
+    }
+}
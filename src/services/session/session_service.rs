@@ -0,0 +1,182 @@
+use crate::repository::session::session_model::Session;
+use crate::repository::session::session_repository::{Error, SessionRepository};
+use crate::services::client::client_service::{generate_secret, hash_secret};
+use crate::services::jwt::jwt_service::Claims;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use log::info;
+use mongodb::Database;
+
+#[derive(Clone)]
+pub struct MongoSessionService {
+    pub session_repository: SessionRepository,
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait SessionService: Send + Sync {
+    /// # Summary
+    ///
+    /// Create a Session for the Claims of a JWT token and return the opaque token that refers to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `claims` - The Claims of the JWT token.
+    /// * `expires_at` - The time after which the Session expires.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let token = session_service.create(claims, expires_at, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String, Error>` - The opaque session token.
+    async fn create(
+        &self,
+        claims: Claims,
+        expires_at: DateTime<Utc>,
+        db: &Database,
+    ) -> Result<String, Error>;
+
+    /// # Summary
+    ///
+    /// Find the Claims of the Session that an opaque session token refers to.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The opaque session token.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let claims = session_service.resolve(token, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<Claims>, Error>` - The Claims, or None if the Session does not exist or has expired.
+    async fn resolve(&self, token: &str, db: &Database) -> Result<Option<Claims>, Error>;
+
+    /// # Summary
+    ///
+    /// Revoke a Session, so that its token is rejected immediately.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The opaque session token.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// session_service.revoke(token, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    async fn revoke(&self, token: &str, db: &Database) -> Result<(), Error>;
+
+    /// # Summary
+    ///
+    /// Revoke all Sessions of a subject.
+    ///
+    /// # Arguments
+    ///
+    /// * `subject` - The subject of the Sessions.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// session_service.revoke_all(&user.id.to_hex(), &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    async fn revoke_all(&self, subject: &str, db: &Database) -> Result<(), Error>;
+}
+
+impl MongoSessionService {
+    /// # Summary
+    ///
+    /// Create a new MongoSessionService.
+    ///
+    /// # Arguments
+    ///
+    /// * `session_repository` - The SessionRepository.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let session_repository = SessionRepository::new(String::from("sessions"));
+    /// let session_service = MongoSessionService::new(session_repository);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `MongoSessionService` - The new MongoSessionService.
+    pub fn new(session_repository: SessionRepository) -> MongoSessionService {
+        MongoSessionService { session_repository }
+    }
+}
+
+#[async_trait]
+impl SessionService for MongoSessionService {
+    /// # Summary
+    ///
+    /// Create a Session for the Claims of a JWT token and return the opaque token that refers to it.
+    async fn create(
+        &self,
+        claims: Claims,
+        expires_at: DateTime<Utc>,
+        db: &Database,
+    ) -> Result<String, Error> {
+        // Neither the session token nor a JWT token is stored, only the hash of the session token and the Claims, so that
+        // the collection cannot be used to hijack Sessions
+        let token = generate_secret();
+        let session = Session::new(hash_secret(&token), claims, expires_at);
+
+        info!("Creating Session: {}", session);
+        self.session_repository.create(session, db).await?;
+
+        Ok(token)
+    }
+
+    /// # Summary
+    ///
+    /// Find the Claims of the Session that an opaque session token refers to.
+    async fn resolve(&self, token: &str, db: &Database) -> Result<Option<Claims>, Error> {
+        match self
+            .session_repository
+            .find_by_token_hash(&hash_secret(token), db)
+            .await?
+        {
+            Some(s) if !s.is_expired() => Ok(s.claims),
+            _ => Ok(None),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Revoke a Session, so that its token is rejected immediately.
+    async fn revoke(&self, token: &str, db: &Database) -> Result<(), Error> {
+        info!("Revoking Session");
+        self.session_repository
+            .delete_by_token_hash(&hash_secret(token), db)
+            .await
+    }
+
+    /// # Summary
+    ///
+    /// Revoke all Sessions of a subject.
+    async fn revoke_all(&self, subject: &str, db: &Database) -> Result<(), Error> {
+        info!("Revoking all Sessions of subject: {}", subject);
+        self.session_repository.delete_by_subject(subject, db).await
+    }
+}
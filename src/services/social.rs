@@ -0,0 +1,3 @@
+pub mod external_identity;
+pub mod social_login_service;
+pub mod social_provider;
@@ -0,0 +1,10 @@
+#[derive(Clone, Debug)]
+pub struct ExternalIdentity {
+    pub provider: String,
+    pub subject: String,
+    pub username: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+}
@@ -0,0 +1,403 @@
+use crate::configuration::social_login_config::{SocialLoginConfig, SocialProviderConfig};
+use crate::services::social::external_identity::ExternalIdentity;
+use crate::services::social::social_provider::SocialProvider;
+use log::info;
+use serde::Deserialize;
+#[cfg(feature = "integrations")]
+use std::time::Duration;
+use thiserror::Error;
+use url::Url;
+
+/// The maximum amount of time that a request to a provider may take.
+#[cfg(feature = "integrations")]
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// GitHub rejects API requests without a User-Agent header.
+#[cfg(feature = "integrations")]
+const USER_AGENT: &str = "auth-rs";
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Social login provider is not enabled: {0}")]
+    ProviderDisabled(SocialProvider),
+    #[error("The identity provider rejected the authorization code")]
+    InvalidGrant,
+    #[error("The identity provider did not return a usable account: {0}")]
+    InvalidProfile(String),
+    #[error("Request to the identity provider failed: {0}")]
+    Request(String),
+}
+
+#[cfg(feature = "integrations")]
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GoogleUserInfo {
+    sub: String,
+    email: Option<String>,
+    #[serde(default)]
+    email_verified: bool,
+    given_name: Option<String>,
+    family_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GitHubUser {
+    id: u64,
+    login: String,
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GitHubEmail {
+    email: String,
+    primary: bool,
+    verified: bool,
+}
+
+#[derive(Clone)]
+pub struct SocialLoginService {
+    pub social_login_config: SocialLoginConfig,
+    #[cfg(feature = "integrations")]
+    client: reqwest::Client,
+}
+
+impl SocialLoginService {
+    /// # Summary
+    ///
+    /// Create a new SocialLoginService.
+    ///
+    /// # Arguments
+    ///
+    /// * `social_login_config` - The SocialLoginConfig.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let social_login_service = SocialLoginService::new(social_login_config);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `SocialLoginService` - The new SocialLoginService.
+    pub fn new(social_login_config: SocialLoginConfig) -> SocialLoginService {
+        SocialLoginService {
+            social_login_config,
+            #[cfg(feature = "integrations")]
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .user_agent(USER_AGENT)
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Build the URL to which a user is redirected to log in at a provider.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - The SocialProvider.
+    /// * `state` - The random value that ties the callback to the browser that started the login.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let url = social_login_service.authorization_url(SocialProvider::GitHub, &state)?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String, Error>` - The URL, or ProviderDisabled if the provider is not configured.
+    pub fn authorization_url(
+        &self,
+        provider: SocialProvider,
+        state: &str,
+    ) -> Result<String, Error> {
+        let config = self.provider(provider)?;
+        let redirect_uri = self.social_login_config.redirect_uri(provider);
+
+        Url::parse_with_params(
+            provider.authorization_endpoint(),
+            &[
+                ("response_type", "code"),
+                ("client_id", config.client_id.as_str()),
+                ("redirect_uri", redirect_uri.as_str()),
+                ("scope", provider.scope()),
+                ("state", state),
+            ],
+        )
+        .map(String::from)
+        .map_err(|e| Error::Request(e.to_string()))
+    }
+
+    /// # Summary
+    ///
+    /// Exchange the authorization code that a provider returned for the account of the user.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - The SocialProvider.
+    /// * `code` - The authorization code.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let identity = social_login_service.exchange(SocialProvider::GitHub, &code).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ExternalIdentity, Error>` - The account of the user or the Error that occurred.
+    pub async fn exchange(
+        &self,
+        provider: SocialProvider,
+        code: &str,
+    ) -> Result<ExternalIdentity, Error> {
+        let config = self.provider(provider)?;
+        let redirect_uri = self.social_login_config.redirect_uri(provider);
+
+        let token = self
+            .request_token(
+                provider.token_endpoint(),
+                &[
+                    ("grant_type", "authorization_code"),
+                    ("code", code),
+                    ("redirect_uri", redirect_uri.as_str()),
+                    ("client_id", config.client_id.as_str()),
+                    ("client_secret", config.client_secret.as_str()),
+                ],
+            )
+            .await?;
+
+        let token = match token {
+            Some(t) => t,
+            None => return Err(Error::InvalidGrant),
+        };
+
+        let identity = match provider {
+            SocialProvider::Google => self.google_identity(&token).await?,
+            SocialProvider::GitHub => self.github_identity(&token).await?,
+        };
+
+        info!("Resolved {} account: {}", provider, identity.subject);
+        Ok(identity)
+    }
+
+    /// # Summary
+    ///
+    /// Find the configuration of a provider.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - The SocialProvider.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<&SocialProviderConfig, Error>` - The configuration, or ProviderDisabled if the provider is not configured or the `integrations` feature is disabled.
+    fn provider(&self, provider: SocialProvider) -> Result<&SocialProviderConfig, Error> {
+        self.social_login_config
+            .provider(provider)
+            .filter(|_| cfg!(feature = "integrations"))
+            .ok_or(Error::ProviderDisabled(provider))
+    }
+
+    /// # Summary
+    ///
+    /// Exchange an authorization code for the access token of the user at the token endpoint of a provider.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The token endpoint.
+    /// * `form` - The form that is posted to the token endpoint.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<String>, Error>` - The access token, None if the provider rejected the code, or the Error that occurred.
+    #[cfg(feature = "integrations")]
+    async fn request_token(
+        &self,
+        url: &str,
+        form: &[(&str, &str)],
+    ) -> Result<Option<String>, Error> {
+        let response = self
+            .client
+            .post(url)
+            .header("Accept", "application/json")
+            .form(form)
+            .send()
+            .await
+            .map_err(|e| Error::Request(e.to_string()))?;
+
+        // GitHub reports a rejected code with a 200 response that has no access token
+        match response.status().is_success() {
+            true => Ok(response
+                .json::<TokenResponse>()
+                .await
+                .map_err(|e| Error::Request(e.to_string()))?
+                .access_token),
+            false => Ok(None),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Fail to exchange an authorization code, since providers cannot be called if the `integrations` feature is disabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `_url` - The token endpoint.
+    /// * `_form` - The form that would be posted to the token endpoint.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<String>, Error>` - Always a Request error.
+    #[cfg(not(feature = "integrations"))]
+    async fn request_token(
+        &self,
+        _url: &str,
+        _form: &[(&str, &str)],
+    ) -> Result<Option<String>, Error> {
+        Err(Error::Request(String::from(
+            "the integrations feature is disabled",
+        )))
+    }
+
+    /// # Summary
+    ///
+    /// Read a JSON resource of a provider on behalf of the user.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL of the resource.
+    /// * `token` - The access token of the user.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<T, Error>` - The resource or the Error that occurred.
+    #[cfg(feature = "integrations")]
+    async fn get_json<T: for<'de> Deserialize<'de>>(
+        &self,
+        url: &str,
+        token: &str,
+    ) -> Result<T, Error> {
+        let response = self
+            .client
+            .get(url)
+            .bearer_auth(token)
+            .header("Accept", "application/json")
+            .send()
+            .await
+            .map_err(|e| Error::Request(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(Error::Request(format!(
+                "{} returned {}",
+                url,
+                response.status()
+            )));
+        }
+
+        response
+            .json::<T>()
+            .await
+            .map_err(|e| Error::InvalidProfile(e.to_string()))
+    }
+
+    /// # Summary
+    ///
+    /// Fail to read a resource, since providers cannot be called if the `integrations` feature is disabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `_url` - The URL of the resource.
+    /// * `_token` - The access token of the user.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<T, Error>` - Always a Request error.
+    #[cfg(not(feature = "integrations"))]
+    async fn get_json<T: for<'de> Deserialize<'de>>(
+        &self,
+        _url: &str,
+        _token: &str,
+    ) -> Result<T, Error> {
+        Err(Error::Request(String::from(
+            "the integrations feature is disabled",
+        )))
+    }
+
+    /// # Summary
+    ///
+    /// Read the account of a Google user.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The access token of the user.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ExternalIdentity, Error>` - The account of the user or the Error that occurred.
+    async fn google_identity(&self, token: &str) -> Result<ExternalIdentity, Error> {
+        let user: GoogleUserInfo = self
+            .get_json("https://openidconnect.googleapis.com/v1/userinfo", token)
+            .await?;
+
+        // Google accounts do not have a username, so the local part of the email address is used instead
+        let username = match &user.email {
+            Some(e) => e.split('@').next().unwrap_or_default().to_string(),
+            None => return Err(Error::InvalidProfile(String::from("missing email address"))),
+        };
+
+        Ok(ExternalIdentity {
+            provider: SocialProvider::Google.to_string(),
+            subject: user.sub,
+            username,
+            email: user.email,
+            email_verified: user.email_verified,
+            first_name: user.given_name,
+            last_name: user.family_name,
+        })
+    }
+
+    /// # Summary
+    ///
+    /// Read the account of a GitHub user, including its primary email address.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The access token of the user.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ExternalIdentity, Error>` - The account of the user or the Error that occurred.
+    async fn github_identity(&self, token: &str) -> Result<ExternalIdentity, Error> {
+        let user: GitHubUser = self.get_json("https://api.github.com/user", token).await?;
+        let emails: Vec<GitHubEmail> = self
+            .get_json("https://api.github.com/user/emails", token)
+            .await?;
+
+        let email = emails.into_iter().find(|e| e.primary);
+        let (first_name, last_name) = match user.name {
+            Some(n) => match n.split_once(' ') {
+                Some((f, l)) => (Some(f.to_string()), Some(l.to_string())),
+                None => (Some(n), None),
+            },
+            None => (None, None),
+        };
+
+        Ok(ExternalIdentity {
+            provider: SocialProvider::GitHub.to_string(),
+            subject: user.id.to_string(),
+            username: user.login,
+            email_verified: email.as_ref().map(|e| e.verified).unwrap_or(false),
+            email: email.map(|e| e.email),
+            first_name,
+            last_name,
+        })
+    }
+}
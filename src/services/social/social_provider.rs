@@ -0,0 +1,95 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SocialProvider {
+    Google,
+    GitHub,
+}
+
+impl SocialProvider {
+    /// # Summary
+    ///
+    /// Get the URL to which users are redirected to authorize the login.
+    ///
+    /// # Returns
+    ///
+    /// * `&'static str` - The authorization endpoint of the provider.
+    pub fn authorization_endpoint(&self) -> &'static str {
+        match self {
+            SocialProvider::Google => "https://accounts.google.com/o/oauth2/v2/auth",
+            SocialProvider::GitHub => "https://github.com/login/oauth/authorize",
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Get the URL at which an authorization code is exchanged for an access token.
+    ///
+    /// # Returns
+    ///
+    /// * `&'static str` - The token endpoint of the provider.
+    pub fn token_endpoint(&self) -> &'static str {
+        match self {
+            SocialProvider::Google => "https://oauth2.googleapis.com/token",
+            SocialProvider::GitHub => "https://github.com/login/oauth/access_token",
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Get the scopes that are requested, which grant access to the profile and the email address of the user.
+    ///
+    /// # Returns
+    ///
+    /// * `&'static str` - The space-separated scopes.
+    pub fn scope(&self) -> &'static str {
+        match self {
+            SocialProvider::Google => "openid email profile",
+            SocialProvider::GitHub => "read:user user:email",
+        }
+    }
+}
+
+impl Display for SocialProvider {
+    /// # Summary
+    ///
+    /// Display the SocialProvider in the same format in which it is stored and used in routes.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The Formatter.
+    ///
+    /// # Returns
+    ///
+    /// * `std::fmt::Result` - The result of the display.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SocialProvider::Google => write!(f, "google"),
+            SocialProvider::GitHub => write!(f, "github"),
+        }
+    }
+}
+
+impl FromStr for SocialProvider {
+    type Err = String;
+
+    /// # Summary
+    ///
+    /// Parse a SocialProvider.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The provider, either `google` or `github`.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<SocialProvider, String>` - The SocialProvider, or an error message if the provider is unknown.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "google" => Ok(SocialProvider::Google),
+            "github" => Ok(SocialProvider::GitHub),
+            _ => Err(format!("Unknown social login provider: {}", s)),
+        }
+    }
+}
@@ -0,0 +1 @@
+pub mod sso_service;
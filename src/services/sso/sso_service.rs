@@ -0,0 +1,328 @@
+use crate::configuration::sso_config::SsoConfig;
+use crate::services::social::external_identity::ExternalIdentity;
+#[cfg(feature = "integrations")]
+use log::info;
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "integrations")]
+use std::time::Duration;
+use thiserror::Error;
+use url::Url;
+
+/// The maximum amount of time that a request to the identity provider may take.
+#[cfg(feature = "integrations")]
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The name under which the accounts of the identity provider are linked to Users.
+pub const SSO_PROVIDER: &str = "sso";
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Single sign-on is not enabled")]
+    Disabled,
+    #[error("Failed to discover the identity provider: {0}")]
+    Discovery(String),
+    #[error("The identity provider rejected the authorization code")]
+    InvalidGrant,
+    #[error("The identity provider did not return a usable account: {0}")]
+    InvalidProfile(String),
+    #[error("Request to the identity provider failed: {0}")]
+    Request(String),
+}
+
+#[derive(Deserialize, Clone)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    userinfo_endpoint: String,
+}
+
+#[cfg(feature = "integrations")]
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+}
+
+#[cfg(feature = "integrations")]
+#[derive(Deserialize)]
+struct UserInfo {
+    sub: String,
+    preferred_username: Option<String>,
+    email: Option<String>,
+    #[serde(default)]
+    email_verified: bool,
+    given_name: Option<String>,
+    family_name: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct SsoService {
+    pub sso_config: SsoConfig,
+    #[cfg(feature = "integrations")]
+    client: reqwest::Client,
+    metadata: Arc<Mutex<Option<ProviderMetadata>>>,
+}
+
+impl SsoService {
+    /// # Summary
+    ///
+    /// Create a new SsoService.
+    ///
+    /// # Arguments
+    ///
+    /// * `sso_config` - The SsoConfig.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let sso_service = SsoService::new(sso_config);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `SsoService` - The new SsoService.
+    pub fn new(sso_config: SsoConfig) -> SsoService {
+        SsoService {
+            sso_config,
+            #[cfg(feature = "integrations")]
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            metadata: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Build the URL to which a user is redirected to log in at the identity provider.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The random value that ties the callback to the browser that started the login.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let url = sso_service.authorization_url(&state).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String, Error>` - The URL, or Disabled if single sign-on is not configured.
+    pub async fn authorization_url(&self, state: &str) -> Result<String, Error> {
+        let metadata = self.metadata().await?;
+
+        Url::parse_with_params(
+            &metadata.authorization_endpoint,
+            &[
+                ("response_type", "code"),
+                ("client_id", self.sso_config.client_id.as_str()),
+                ("redirect_uri", self.sso_config.redirect_uri.as_str()),
+                ("scope", self.sso_config.scope.as_str()),
+                ("state", state),
+            ],
+        )
+        .map(String::from)
+        .map_err(|e| Error::Discovery(e.to_string()))
+    }
+
+    /// # Summary
+    ///
+    /// Exchange the authorization code that the identity provider returned for the account of the user.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - The authorization code.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let identity = sso_service.exchange(&code).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ExternalIdentity, Error>` - The account of the user or the Error that occurred.
+    #[cfg(feature = "integrations")]
+    pub async fn exchange(&self, code: &str) -> Result<ExternalIdentity, Error> {
+        let metadata = self.metadata().await?;
+
+        let response = self
+            .client
+            .post(&metadata.token_endpoint)
+            .header("Accept", "application/json")
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", self.sso_config.redirect_uri.as_str()),
+                ("client_id", self.sso_config.client_id.as_str()),
+                ("client_secret", self.sso_config.client_secret.as_str()),
+            ])
+            .send()
+            .await
+            .map_err(|e| Error::Request(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(Error::InvalidGrant);
+        }
+
+        let token = response
+            .json::<TokenResponse>()
+            .await
+            .map_err(|_| Error::InvalidGrant)?
+            .access_token;
+
+        // The claims are read from the UserInfo endpoint, so that the ID token does not have to be verified
+        let response = self
+            .client
+            .get(&metadata.userinfo_endpoint)
+            .bearer_auth(&token)
+            .header("Accept", "application/json")
+            .send()
+            .await
+            .map_err(|e| Error::Request(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(Error::Request(format!(
+                "{} returned {}",
+                metadata.userinfo_endpoint,
+                response.status()
+            )));
+        }
+
+        let user = response
+            .json::<UserInfo>()
+            .await
+            .map_err(|e| Error::InvalidProfile(e.to_string()))?;
+
+        let username = match (&user.preferred_username, &user.email) {
+            (Some(u), _) if !u.trim().is_empty() => u.trim().to_string(),
+            (_, Some(e)) => e.split('@').next().unwrap_or_default().to_string(),
+            _ => user.sub.clone(),
+        };
+
+        info!("Resolved single sign-on account: {}", user.sub);
+
+        Ok(ExternalIdentity {
+            provider: String::from(SSO_PROVIDER),
+            subject: user.sub,
+            username,
+            email: user.email,
+            email_verified: user.email_verified,
+            first_name: user.given_name,
+            last_name: user.family_name,
+        })
+    }
+
+    /// # Summary
+    ///
+    /// Fail to exchange an authorization code, since the identity provider cannot be called if the `integrations`
+    /// feature is disabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `_code` - The authorization code.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ExternalIdentity, Error>` - Always Disabled.
+    #[cfg(not(feature = "integrations"))]
+    pub async fn exchange(&self, _code: &str) -> Result<ExternalIdentity, Error> {
+        Err(Error::Disabled)
+    }
+
+    /// # Summary
+    ///
+    /// Get the metadata of the identity provider, which is discovered once and then kept for the lifetime of the
+    /// process.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ProviderMetadata, Error>` - The metadata, or the Error that occurred.
+    async fn metadata(&self) -> Result<ProviderMetadata, Error> {
+        let issuer = match &self.sso_config.issuer {
+            Some(i) => i.trim_end_matches('/'),
+            None => return Err(Error::Disabled),
+        };
+
+        let cached = match self.metadata.lock() {
+            Ok(m) => m.clone(),
+            Err(e) => e.into_inner().clone(),
+        };
+        if let Some(m) = cached {
+            return Ok(m);
+        }
+
+        let metadata = self
+            .discover(&format!("{}/.well-known/openid-configuration", issuer))
+            .await?;
+
+        // The issuer of the metadata must be the configured issuer, otherwise it may belong to another provider
+        if metadata.issuer.trim_end_matches('/') != issuer {
+            return Err(Error::Discovery(format!(
+                "issuer mismatch: expected {}, got {}",
+                issuer, metadata.issuer
+            )));
+        }
+
+        match self.metadata.lock() {
+            Ok(mut m) => *m = Some(metadata.clone()),
+            Err(e) => *e.into_inner() = Some(metadata.clone()),
+        }
+
+        Ok(metadata)
+    }
+
+    /// # Summary
+    ///
+    /// Read the metadata of the identity provider from its discovery document.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL of the discovery document.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ProviderMetadata, Error>` - The metadata, or the Error that occurred.
+    #[cfg(feature = "integrations")]
+    async fn discover(&self, url: &str) -> Result<ProviderMetadata, Error> {
+        let response = self
+            .client
+            .get(url)
+            .header("Accept", "application/json")
+            .send()
+            .await
+            .map_err(|e| Error::Discovery(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(Error::Discovery(format!(
+                "{} returned {}",
+                url,
+                response.status()
+            )));
+        }
+
+        response
+            .json::<ProviderMetadata>()
+            .await
+            .map_err(|e| Error::Discovery(e.to_string()))
+    }
+
+    /// # Summary
+    ///
+    /// Fail to discover the identity provider, since it cannot be called if the `integrations` feature is disabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `_url` - The URL of the discovery document.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ProviderMetadata, Error>` - Always Disabled.
+    #[cfg(not(feature = "integrations"))]
+    async fn discover(&self, _url: &str) -> Result<ProviderMetadata, Error> {
+        Err(Error::Disabled)
+    }
+}
@@ -1 +1,2 @@
 pub mod user_service;
+pub mod user_store;
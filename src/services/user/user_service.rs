@@ -1,41 +1,41 @@
+use crate::configuration::user_store_config::UserStoreConfig;
 use crate::repository::audit::audit_model::Action::{Create, Delete, Update};
 use crate::repository::audit::audit_model::{Audit, ResourceIdType, ResourceType};
 use crate::repository::audit::audit_repository::Error as AuditError;
+use crate::repository::search_hit::SearchHit;
+use crate::repository::user::user_filter::UserFilter;
+use crate::repository::user::user_identity::UserIdentity;
 use crate::repository::user::user_model::User;
 use crate::repository::user::user_repository::{Error, UserRepository};
+use crate::repository::user::user_source::UserSource;
+use crate::repository::user::user_totp::UserTotp;
+use crate::repository::user_summary::user_summary_model::UserSummary;
+use crate::repository::user_summary::user_summary_repository::{
+    Error as UserSummaryError, UserSummaryRepository,
+};
 use crate::services::audit::audit_service::AuditService;
-use log::{error, info};
+use crate::services::role::role_service::RoleService;
+use crate::services::user::user_store::UserStore;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use log::{error, info, warn};
 use mongodb::bson::oid::ObjectId;
 use mongodb::Database;
+use std::sync::Arc;
+use std::time::SystemTime;
 
 #[derive(Clone)]
-pub struct UserService {
+pub struct MongoUserService {
     pub user_repository: UserRepository,
+    pub user_summary_repository: UserSummaryRepository,
+    pub user_store: Option<Arc<dyn UserStore>>,
+    pub provision_users: bool,
+    pub max_roles: usize,
 }
 
-impl UserService {
-    /// # Summary
-    ///
-    /// Create a new UserService.
-    ///
-    /// # Arguments
-    ///
-    /// * `user_repository` - The UserRepository to be used by the UserService.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// let user_repository = UserRepository::new(String::from("users"));
-    /// let user_service = UserService::new(user_repository);
-    /// ```
-    ///
-    /// # Returns
-    ///
-    /// * `UserService` - The new UserService.
-    pub fn new(user_repository: UserRepository) -> UserService {
-        UserService { user_repository }
-    }
-
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait UserService: Send + Sync {
     /// # Summary
     ///
     /// Create a User entity.
@@ -51,7 +51,7 @@ impl UserService {
     ///
     /// ```
     /// let user_repository = UserRepository::new(String::from("users"));
-    /// let user_service = UserService::new(user_repository);
+    /// let user_service = MongoUserService::new(user_repository);
     /// let db = mongodb::Database::new();
     /// let audit_service = AuditService::new(AuditRepository::new(String::from("audits")));
     /// let user = User::new("username", "password");
@@ -63,34 +63,13 @@ impl UserService {
     ///
     /// * `User` - The created User entity.
     /// * `Error` - The Error that occurred.
-    pub async fn create(
+    async fn create(
         &self,
         user: User,
         user_id: Option<ObjectId>,
         db: &Database,
-        audit_service: &AuditService,
-    ) -> Result<User, Error> {
-        info!("Creating User: {}", user);
-
-        if user_id.is_some() {
-            let new_audit = Audit::new(
-                user_id.unwrap(),
-                Create,
-                user.id,
-                ResourceIdType::UserId,
-                ResourceType::User,
-            );
-            match audit_service.create(new_audit, db).await {
-                Ok(_) => {}
-                Err(e) => {
-                    error!("Failed to create Audit: {}", e);
-                    return Err(Error::Audit(e));
-                }
-            }
-        }
-
-        self.user_repository.create(user, db).await
-    }
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<User, Error>;
 
     /// # Summary
     ///
@@ -106,7 +85,7 @@ impl UserService {
     ///
     /// ```
     /// let user_repository = UserRepository::new(String::from("users"));
-    /// let user_service = UserService::new(user_repository);
+    /// let user_service = MongoUserService::new(user_repository);
     /// let db = mongodb::Database::new();
     /// let users = user_service.find_all(Some(10), Some(1), &db);
     /// ```
@@ -115,15 +94,12 @@ impl UserService {
     ///
     /// * `Vec<User>` - The found User entities.
     /// * `Error` - The Error that occurred.
-    pub async fn find_all(
+    async fn find_all(
         &self,
         limit: Option<i64>,
         page: Option<i64>,
         db: &Database,
-    ) -> Result<Vec<User>, Error> {
-        info!("Finding all users");
-        self.user_repository.find_all(limit, page, db).await
-    }
+    ) -> Result<Vec<User>, Error>;
 
     /// # Summary
     ///
@@ -138,7 +114,7 @@ impl UserService {
     ///
     /// ```
     /// let user_repository = UserRepository::new(String::from("users"));
-    /// let user_service = UserService::new(user_repository);
+    /// let user_service = MongoUserService::new(user_repository);
     /// let db = mongodb::Database::new();
     ///
     /// let user = user_service.find_by_id("id", &db);
@@ -148,10 +124,31 @@ impl UserService {
     ///
     /// * `Option<User>` - The created User entity.
     /// * `Error` - The Error that occurred.
-    pub async fn find_by_id(&self, id: &str, db: &Database) -> Result<Option<User>, Error> {
-        info!("Finding User by ID: {}", id);
-        self.user_repository.find_by_id(id, db).await
-    }
+    async fn find_by_id(&self, id: &str, db: &Database) -> Result<Option<User>, Error>;
+
+    /// # Summary
+    ///
+    /// Find User entities by their IDs.
+    ///
+    /// # Arguments
+    ///
+    /// * `id_vec` - The IDs of the User entities.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_repository = UserRepository::new(String::from("users"));
+    /// let user_service = MongoUserService::new(user_repository);
+    /// let db = mongodb::Database::new();
+    ///
+    /// let users = user_service.find_by_id_vec(vec![String::from("id")], &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<User>, Error>` - The User entities or the Error that occurred.
+    async fn find_by_id_vec(&self, id_vec: Vec<String>, db: &Database) -> Result<Vec<User>, Error>;
 
     /// # Summary
     ///
@@ -168,7 +165,7 @@ impl UserService {
     ///
     /// ```
     /// let user_repository = UserRepository::new(String::from("users"));
-    /// let user_service = UserService::new(user_repository);
+    /// let user_service = MongoUserService::new(user_repository);
     /// let db = mongodb::Database::new();
     /// let user = user_service.find_by_username("username", &db);
     /// ```
@@ -176,22 +173,62 @@ impl UserService {
     /// # Returns
     ///
     /// * `Result<Option<User>, Error>` - The result of the operation.
-    pub async fn find_by_username(
+    async fn find_by_username(&self, username: &str, db: &Database) -> Result<Option<User>, Error>;
+
+    /// # Summary
+    ///
+    /// Find a User entity by its primary or one of its secondary email addresses.
+    ///
+    /// # Arguments
+    ///
+    /// * `email` - The email address of the User entity.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user = user_service.find_by_email("user@opserva.io", &db).await;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<User>, Error>` - The result of the operation.
+    async fn find_by_email(&self, email: &str, db: &Database) -> Result<Option<User>, Error>;
+
+    /// # Summary
+    ///
+    /// Find the User entity that is linked to an account of an external identity provider.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - The name of the identity provider.
+    /// * `subject` - The ID of the account at the identity provider.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user = user_service.find_by_identity("github", "583231", &db).await;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<User>, Error>` - The result of the operation.
+    async fn find_by_identity(
         &self,
-        username: &str,
+        provider: &str,
+        subject: &str,
         db: &Database,
-    ) -> Result<Option<User>, Error> {
-        info!("Finding User by username: {}", username);
-        self.user_repository.find_by_username(username, db).await
-    }
+    ) -> Result<Option<User>, Error>;
 
     /// # Summary
     ///
-    /// Update a user entity.
+    /// Link a User entity to an account of an external identity provider.
     ///
     /// # Arguments
     ///
-    /// * `user` - The User entity to be updated including its updated values.
+    /// * `id` - The ID of the User entity to be updated.
+    /// * `identity` - The UserIdentity of the account.
     /// * `user_id` - The ID of the User entity that is updating the User.
     /// * `db` - The Database to be used.
     /// * `audit_service` - The AuditService to be used.
@@ -199,55 +236,30 @@ impl UserService {
     /// # Example
     ///
     /// ```
-    /// let user_repository = UserRepository::new(String::from("users"));
-    /// let user_service = UserService::new(user_repository);
-    /// let db = mongodb::Database::new();
-    /// let audit_service = AuditService::new(AuditRepository::new(String::from("audits")));
-    ///
-    /// let user = user_service.update(User::new(), ObjectId::parse_str("id").unwrap(), &db);
+    /// let identity = UserIdentity::new(String::from("github"), String::from("583231"));
+    /// let user = user_service.link_identity("id", identity, ObjectId::parse_str("id").ok(), &db, &audit_service).await;
     /// ```
     ///
     /// # Returns
     ///
-    /// * `User` - The updated User entity.
-    /// * `Error` - The Error that occurred.
-    pub async fn update(
+    /// * `Result<User, Error>` - The updated User entity or the Error that occurred.
+    async fn link_identity(
         &self,
-        user: User,
+        id: &str,
+        identity: UserIdentity,
         user_id: Option<ObjectId>,
         db: &Database,
-        audit_service: &AuditService,
-    ) -> Result<User, Error> {
-        info!("Updating User: {}", user);
-
-        if user_id.is_some() {
-            let new_audit = Audit::new(
-                user_id.unwrap(),
-                Update,
-                user.id,
-                ResourceIdType::UserId,
-                ResourceType::User,
-            );
-            match audit_service.create(new_audit, db).await {
-                Ok(_) => {}
-                Err(e) => {
-                    error!("Failed to create Audit: {}", e);
-                    return Err(Error::Audit(e));
-                }
-            }
-        }
-
-        self.user_repository.update(user, db).await
-    }
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<User, Error>;
 
     /// # Summary
     ///
-    /// Update a User entity's password.
+    /// Store or remove the TOTP secret of a User entity.
     ///
     /// # Arguments
     ///
     /// * `id` - The ID of the User entity to be updated.
-    /// * `password` - The new password of the User entity.
+    /// * `totp` - The UserTotp, or None to remove the TOTP secret.
     /// * `user_id` - The ID of the User entity that is updating the User.
     /// * `db` - The Database to be used.
     /// * `audit_service` - The AuditService to be used.
@@ -255,183 +267,1723 @@ impl UserService {
     /// # Example
     ///
     /// ```
-    /// let user_repository = UserRepository::new(String::from("users"));
-    /// let user_service = UserService::new(user_repository);
-    /// let db = mongodb::Database::new();
-    /// let audit_service = AuditService::new(AuditRepository::new(String::from("audits")));
-    ///
-    /// let user = user_service.update_password("id", "password", ObjectId::parse_str("user_id").unwrap(), &db);
+    /// let user = user_service.set_totp("id", None, ObjectId::parse_str("id").ok(), &db, &audit_service).await;
     /// ```
     ///
     /// # Returns
     ///
-    /// * `()` - The update operation was successful.
-    /// * `Error` - The Error that occurred.
-    pub async fn update_password(
+    /// * `Result<User, Error>` - The updated User entity or the Error that occurred.
+    async fn set_totp(
         &self,
         id: &str,
-        password: &str,
+        totp: Option<UserTotp>,
         user_id: Option<ObjectId>,
         db: &Database,
-        audit_service: &AuditService,
-    ) -> Result<(), Error> {
-        info!("Updating User password: {}", id);
-
-        if user_id.is_some() {
-            let oid = match ObjectId::parse_str(id) {
-                Ok(oid) => oid,
-                Err(e) => {
-                    return Err(Error::Audit(AuditError::ObjectId(e.to_string())));
-                }
-            };
-
-            let new_audit = Audit::new(
-                user_id.unwrap(),
-                Update,
-                oid,
-                ResourceIdType::UserId,
-                ResourceType::User,
-            );
-            match audit_service.create(new_audit, db).await {
-                Ok(_) => {}
-                Err(e) => {
-                    error!("Failed to create Audit: {}", e);
-                    return Err(Error::Audit(e));
-                }
-            }
-        }
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<User, Error>;
 
-        self.user_repository.update_password(id, password, db).await
-    }
+    /// # Summary
+    ///
+    /// Record that the TOTP code of a time step was used by a User entity, so that it cannot be used again.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the User entity.
+    /// * `step` - The time step of the TOTP code.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let accepted = user_service.use_totp_step(user.id, step, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<bool, Error>` - True if the time step had not been used yet, or the Error that occurred.
+    async fn use_totp_step(&self, id: ObjectId, step: i64, db: &Database) -> Result<bool, Error>;
 
     /// # Summary
     ///
-    /// Delete a User entity by ID.
+    /// Replace the hashes of the recovery codes of a User entity.
     ///
     /// # Arguments
     ///
-    /// * `id` - The ID of the User entity to be deleted.
-    /// * `user_id` - The ID of the User entity that is deleting the User.
+    /// * `id` - The ID of the User entity to be updated.
+    /// * `recovery_codes` - The hashes of the recovery codes, or an empty Vec to remove them.
+    /// * `user_id` - The ID of the User entity that is updating the User.
     /// * `db` - The Database to be used.
     /// * `audit_service` - The AuditService to be used.
     ///
     /// # Example
     ///
     /// ```
-    /// let user_repository = UserRepository::new(String::from("users"));
-    /// let user_service = UserService::new(user_repository);
-    /// let db = mongodb::Database::new();
-    /// let audit_service = AuditService::new(AuditRepository::new(String::from("audits")));
-    ///
-    /// user_service.delete("id", ObjectId::parse_str("user_id").unwrap(), &db);
+    /// let user = user_service.set_recovery_codes("id", hashes, ObjectId::parse_str("id").ok(), &db, &audit_service).await;
     /// ```
     ///
     /// # Returns
     ///
-    /// * `()` - The delete operation was successful.
-    /// * `Error` - The Error that occurred.
-    pub async fn delete(
+    /// * `Result<User, Error>` - The updated User entity or the Error that occurred.
+    async fn set_recovery_codes(
         &self,
         id: &str,
+        recovery_codes: Vec<String>,
         user_id: Option<ObjectId>,
         db: &Database,
-        audit_service: &AuditService,
-    ) -> Result<(), Error> {
-        info!("Deleting User: {}", id);
-
-        if user_id.is_some() {
-            let oid = match ObjectId::parse_str(id) {
-                Ok(oid) => oid,
-                Err(e) => {
-                    return Err(Error::Audit(AuditError::ObjectId(e.to_string())));
-                }
-            };
-
-            let new_audit = Audit::new(
-                user_id.unwrap(),
-                Delete,
-                oid,
-                ResourceIdType::UserId,
-                ResourceType::User,
-            );
-            match audit_service.create(new_audit, db).await {
-                Ok(_) => {}
-                Err(e) => {
-                    error!("Failed to create Audit: {}", e);
-                    return Err(Error::Audit(e));
-                }
-            }
-        }
-
-        self.user_repository.delete(id, db).await
-    }
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<User, Error>;
 
     /// # Summary
     ///
-    /// Delete a Role from all Users.
+    /// Use a recovery code of a User entity, so that it cannot be used again.
     ///
     /// # Arguments
     ///
-    /// * `role_id` - The ID of the Role entity to be deleted from all Users.
+    /// * `id` - The ID of the User entity.
+    /// * `recovery_code` - The hash of the recovery code.
     /// * `db` - The Database to be used.
     ///
     /// # Example
     ///
     /// ```
-    /// let user_repository = UserRepository::new(String::from("users"));
-    /// let user_service = UserService::new(user_repository);
-    /// let db = mongodb::Database::new();
-    ///
-    /// user_service.delete_role_from_all_users("role_id", &db);
+    /// let accepted = user_service.use_recovery_code(user.id, &hash, &db).await?;
     /// ```
     ///
     /// # Returns
     ///
-    /// * `()` - The delete operation was successful.
-    /// * `Error` - The Error that occurred.
-    pub async fn delete_role_from_all_users(
+    /// * `Result<bool, Error>` - True if the recovery code was valid and unused, or the Error that occurred.
+    async fn use_recovery_code(
         &self,
-        role_id: &str,
+        id: ObjectId,
+        recovery_code: &str,
         db: &Database,
-    ) -> Result<(), Error> {
-        info!("Deleting Role from all Users: {}", role_id);
-        self.user_repository
-            .delete_role_from_all_users(role_id, db)
-            .await
-    }
+    ) -> Result<bool, Error>;
 
     /// # Summary
     ///
-    /// Search for Users.
+    /// Mark whether a User entity is enrolled in multi-factor authentication.
     ///
     /// # Arguments
     ///
-    /// * `text` - The text to search for.
-    /// * `limit` - The maximum number of Users to return.
-    /// * `page` - The page of Users to return.
+    /// * `id` - The ID of the User entity to be updated.
+    /// * `enrolled` - Whether the User has a second factor.
+    /// * `user_id` - The ID of the User entity that is updating the User.
     /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
     ///
     /// # Example
     ///
     /// ```
-    /// let user_repository = UserRepository::new(String::from("users"));
-    /// let user_service = UserService::new(user_repository);
-    /// let db = mongodb::Database::new();
-    /// let users = user_service.search("text", Some(10), Some(1), &db);
+    /// let user = user_service.set_mfa_enrolled("id", true, ObjectId::parse_str("id").ok(), &db, &audit_service).await;
     /// ```
     ///
     /// # Returns
     ///
-    /// * `Vec<User>` - The Users that match the search criteria.
-    /// * `Error` - The Error that occurred.
-    pub async fn search(
+    /// * `Result<User, Error>` - The updated User entity or the Error that occurred.
+    async fn set_mfa_enrolled(
         &self,
-        text: &str,
-        limit: Option<i64>,
-        page: Option<i64>,
+        id: &str,
+        enrolled: bool,
+        user_id: Option<ObjectId>,
         db: &Database,
-    ) -> Result<Vec<User>, Error> {
-        info!("Searching Users: {}", text);
-        self.user_repository.search(text, limit, page, db).await
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<User, Error>;
+
+    /// # Summary
+    ///
+    /// Find a User entity by its username, falling back to the external UserStore if the User cannot be found locally.
+    /// Users that are found in the UserStore are stored locally if provisioning is enabled, together with the roles
+    /// that are mapped to the domain of their email address.
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - The username of the User entity.
+    /// * `db` - The Database.
+    /// * `role_service` - The RoleService that is used to find the roles of provisioned Users.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_repository = UserRepository::new(String::from("users"));
+    /// let user_service = MongoUserService::new(user_repository, user_store_config);
+    /// let db = mongodb::Database::new();
+    /// let user = user_service.find_by_username_federated("username", &db, &role_service);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<User>, Error>` - The result of the operation.
+    async fn find_by_username_federated(
+        &self,
+        username: &str,
+        db: &Database,
+        role_service: &Arc<dyn RoleService>,
+    ) -> Result<Option<User>, Error>;
+
+    /// # Summary
+    ///
+    /// Verify the password of a User entity against the external UserStore.
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - The username of the User entity.
+    /// * `password` - The password to verify.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let valid = user_service.verify_external_password("username", "password");
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<bool, Error>` - Whether the password is valid, or false if no UserStore is configured.
+    async fn verify_external_password(&self, username: &str, password: &str)
+        -> Result<bool, Error>;
+
+    /// # Summary
+    ///
+    /// Update a user entity. A change of status must be an allowed transition (see `UserStatus::can_transition_to`).
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The User entity to be updated including its updated values.
+    /// * `user_id` - The ID of the User entity that is updating the User.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_repository = UserRepository::new(String::from("users"));
+    /// let user_service = MongoUserService::new(user_repository);
+    /// let db = mongodb::Database::new();
+    /// let audit_service = AuditService::new(AuditRepository::new(String::from("audits")));
+    ///
+    /// let user = user_service.update(User::new(), ObjectId::parse_str("id").unwrap(), &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `User` - The updated User entity.
+    /// * `Error` - The Error that occurred.
+    async fn update(
+        &self,
+        user: User,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<User, Error>;
+
+    /// # Summary
+    ///
+    /// Update a User entity's password.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the User entity to be updated.
+    /// * `password` - The new password of the User entity.
+    /// * `user_id` - The ID of the User entity that is updating the User.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_repository = UserRepository::new(String::from("users"));
+    /// let user_service = MongoUserService::new(user_repository);
+    /// let db = mongodb::Database::new();
+    /// let audit_service = AuditService::new(AuditRepository::new(String::from("audits")));
+    ///
+    /// let user = user_service.update_password("id", "password", ObjectId::parse_str("user_id").unwrap(), &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `()` - The update operation was successful.
+    /// * `Error` - The Error that occurred.
+    async fn update_password(
+        &self,
+        id: &str,
+        password: &str,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(), Error>;
+
+    /// # Summary
+    ///
+    /// Revoke all tokens that have been issued to a User entity, which ends all of its sessions.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the User entity whose tokens should be revoked.
+    /// * `user_id` - The ID of the User entity that is revoking the tokens.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_repository = UserRepository::new(String::from("users"));
+    /// let user_service = MongoUserService::new(user_repository);
+    /// let db = mongodb::Database::new();
+    /// let audit_service = AuditService::new(AuditRepository::new(String::from("audits")));
+    ///
+    /// user_service.revoke_sessions("id", ObjectId::parse_str("user_id").ok(), &db, &audit_service);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `()` - The operation was successful.
+    /// * `Error` - The Error that occurred.
+    async fn revoke_sessions(
+        &self,
+        id: &str,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(), Error>;
+
+    /// # Summary
+    ///
+    /// Add a secondary email address to a User entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the User entity to be updated.
+    /// * `email` - The email address to add.
+    /// * `user_id` - The ID of the User entity that is updating the User.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_repository = UserRepository::new(String::from("users"));
+    /// let user_service = MongoUserService::new(user_repository);
+    /// let db = mongodb::Database::new();
+    /// let audit_service = AuditService::new(AuditRepository::new(String::from("audits")));
+    ///
+    /// let user = user_service.add_email("id", "alias@opserva.io", ObjectId::parse_str("user_id").ok(), &db, &audit_service);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `User` - The updated User entity.
+    /// * `Error` - The Error that occurred.
+    async fn add_email(
+        &self,
+        id: &str,
+        email: &str,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<User, Error>;
+
+    /// # Summary
+    ///
+    /// Remove a secondary email address from a User entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the User entity to be updated.
+    /// * `email` - The email address to remove.
+    /// * `user_id` - The ID of the User entity that is updating the User.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_repository = UserRepository::new(String::from("users"));
+    /// let user_service = MongoUserService::new(user_repository);
+    /// let db = mongodb::Database::new();
+    /// let audit_service = AuditService::new(AuditRepository::new(String::from("audits")));
+    ///
+    /// let user = user_service.remove_email("id", "alias@opserva.io", ObjectId::parse_str("user_id").ok(), &db, &audit_service);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `User` - The updated User entity.
+    /// * `Error` - The Error that occurred.
+    async fn remove_email(
+        &self,
+        id: &str,
+        email: &str,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<User, Error>;
+
+    /// # Summary
+    ///
+    /// Mark a secondary email address of a User entity as its primary email address.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the User entity to be updated.
+    /// * `email` - The secondary email address that should become the primary email address.
+    /// * `user_id` - The ID of the User entity that is updating the User.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_repository = UserRepository::new(String::from("users"));
+    /// let user_service = MongoUserService::new(user_repository);
+    /// let db = mongodb::Database::new();
+    /// let audit_service = AuditService::new(AuditRepository::new(String::from("audits")));
+    ///
+    /// let user = user_service.set_primary_email("id", "alias@opserva.io", ObjectId::parse_str("user_id").ok(), &db, &audit_service);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `User` - The updated User entity.
+    /// * `Error` - The Error that occurred.
+    async fn set_primary_email(
+        &self,
+        id: &str,
+        email: &str,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<User, Error>;
+
+    /// # Summary
+    ///
+    /// Set the verification state of one of the email addresses of a User entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the User entity to be updated.
+    /// * `email` - The email address.
+    /// * `verified` - Whether the email address is verified.
+    /// * `user_id` - The ID of the User entity that is updating the User.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_repository = UserRepository::new(String::from("users"));
+    /// let user_service = MongoUserService::new(user_repository);
+    /// let db = mongodb::Database::new();
+    /// let audit_service = AuditService::new(AuditRepository::new(String::from("audits")));
+    ///
+    /// let user = user_service.set_email_verified("id", "alias@opserva.io", true, ObjectId::parse_str("user_id").ok(), &db, &audit_service);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `User` - The updated User entity.
+    /// * `Error` - The Error that occurred.
+    async fn set_email_verified(
+        &self,
+        id: &str,
+        email: &str,
+        verified: bool,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<User, Error>;
+
+    /// # Summary
+    ///
+    /// Delete a User entity by ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the User entity to be deleted.
+    /// * `user_id` - The ID of the User entity that is deleting the User.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_repository = UserRepository::new(String::from("users"));
+    /// let user_service = MongoUserService::new(user_repository);
+    /// let db = mongodb::Database::new();
+    /// let audit_service = AuditService::new(AuditRepository::new(String::from("audits")));
+    ///
+    /// user_service.delete("id", ObjectId::parse_str("user_id").unwrap(), &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `()` - The delete operation was successful.
+    /// * `Error` - The Error that occurred.
+    async fn delete(
+        &self,
+        id: &str,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(), Error>;
+
+    /// # Summary
+    ///
+    /// Merge a duplicate User entity into a User entity. The duplicate is kept until the merge is completed with
+    /// complete_merge, after the entities that belong to it were moved to the User.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The User entity, after the duplicate was merged into it with User::merge.
+    /// * `other` - The duplicate User entity.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut merged = user.clone();
+    /// merged.merge(other.clone());
+    ///
+    /// let user = user_service.merge(merged, other, &db).await;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<User, Error>` - The merged User entity or the Error that occurred.
+    async fn merge(&self, user: User, other: User, db: &Database) -> Result<User, Error>;
+
+    /// # Summary
+    ///
+    /// Complete the merge of a duplicate User entity. The Audits of the duplicate are attributed to the User, the merge
+    /// is audited and the duplicate is deleted.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the User entity that the duplicate was merged into.
+    /// * `other_id` - The ID of the duplicate User entity.
+    /// * `user_id` - The ID of the User entity that is merging the Users.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// user_service.complete_merge(user.id, other.id, ObjectId::parse_str("id").ok(), &db, &audit_service).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `()` - The merge was completed.
+    /// * `Error` - The Error that occurred.
+    async fn complete_merge(
+        &self,
+        id: ObjectId,
+        other_id: ObjectId,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(), Error>;
+
+    /// # Summary
+    ///
+    /// Delete a Role from all Users.
+    ///
+    /// # Arguments
+    ///
+    /// * `role_id` - The ID of the Role entity to be deleted from all Users.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_repository = UserRepository::new(String::from("users"));
+    /// let user_service = MongoUserService::new(user_repository);
+    /// let db = mongodb::Database::new();
+    ///
+    /// user_service.delete_role_from_all_users("role_id", &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `()` - The delete operation was successful.
+    /// * `Error` - The Error that occurred.
+    async fn delete_role_from_all_users(&self, role_id: &str, db: &Database) -> Result<(), Error>;
+
+    /// # Summary
+    ///
+    /// Count the Users that hold a Role.
+    ///
+    /// # Arguments
+    ///
+    /// * `role_id` - The ID of the Role entity.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_repository = UserRepository::new(String::from("users"));
+    /// let user_service = MongoUserService::new(user_repository);
+    /// let db = mongodb::Database::new();
+    ///
+    /// let count = user_service.count_by_role("role_id", &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64, Error>` - The number of Users that hold the Role or the Error that occurred.
+    async fn count_by_role(&self, role_id: &str, db: &Database) -> Result<u64, Error>;
+
+    /// # Summary
+    ///
+    /// Count the active Users that hold a Role.
+    ///
+    /// # Arguments
+    ///
+    /// * `role_id` - The ID of the Role entity.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_repository = UserRepository::new(String::from("users"));
+    /// let user_service = MongoUserService::new(user_repository);
+    /// let db = mongodb::Database::new();
+    ///
+    /// let count = user_service.count_active_by_role("role_id", &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64, Error>` - The number of active Users that hold the Role or the Error that occurred.
+    async fn count_active_by_role(&self, role_id: &str, db: &Database) -> Result<u64, Error>;
+
+    /// # Summary
+    ///
+    /// Require a User to change its password. Until the password is changed, the tokens of the User only grant the
+    /// permission to change its own password.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the User entity.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_repository = UserRepository::new(String::from("users"));
+    /// let user_service = MongoUserService::new(user_repository);
+    /// let db = mongodb::Database::new();
+    ///
+    /// user_service.require_password_change("id", &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `()` - The operation was successful.
+    /// * `Error` - The Error that occurred.
+    async fn require_password_change(&self, id: &str, db: &Database) -> Result<(), Error>;
+
+    /// # Summary
+    ///
+    /// Update the status of the User entities of which a scheduled activation or expiration date has passed.
+    /// Pending Users are activated once their activation date passes and Users are deactivated once they expire.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_repository = UserRepository::new(String::from("users"));
+    /// let user_service = MongoUserService::new(user_repository, user_store_config);
+    /// let db = mongodb::Database::new();
+    ///
+    /// user_service.apply_schedules(&db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `()` - The operation was successful.
+    /// * `Error` - The Error that occurred.
+    async fn apply_schedules(&self, db: &Database) -> Result<(), Error>;
+
+    /// # Summary
+    ///
+    /// Find the denormalized UserSummaries that power the user list of the admin UI, ordered by username. The Role
+    /// names and the last login are stored in the UserSummaries, so that they do not have to be resolved per User.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The maximum number of UserSummaries to return.
+    /// * `page` - The page of UserSummaries to return.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_service = MongoUserService::new(user_repository, user_summary_repository, user_store_config);
+    /// let db = mongodb::Database::new();
+    ///
+    /// let summaries = user_service.find_summaries(Some(10), Some(1), &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<UserSummary>` - The UserSummaries.
+    /// * `Error` - The Error that occurred.
+    async fn find_summaries(
+        &self,
+        limit: Option<i64>,
+        page: Option<i64>,
+        db: &Database,
+    ) -> Result<Vec<UserSummary>, Error>;
+
+    /// # Summary
+    ///
+    /// Rebuild the UserSummaries of all Users if not every User has a UserSummary, for instance after upgrading or after
+    /// the UserSummary collection was dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_service = MongoUserService::new(user_repository, user_summary_repository, user_store_config);
+    /// let db = mongodb::Database::new();
+    ///
+    /// user_service.backfill_summaries(&db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `()` - The operation was successful.
+    /// * `Error` - The Error that occurred.
+    async fn backfill_summaries(&self, db: &Database) -> Result<(), Error>;
+
+    /// # Summary
+    ///
+    /// Rebuild the UserSummaries of all Users, for instance after Users were written directly to the database by
+    /// restoring a backup.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_service = MongoUserService::new(user_repository, user_summary_repository, user_store_config);
+    /// let db = mongodb::Database::new();
+    ///
+    /// user_service.rebuild_summaries(&db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `()` - The operation was successful.
+    /// * `Error` - The Error that occurred.
+    async fn rebuild_summaries(&self, db: &Database) -> Result<(), Error>;
+
+    /// # Summary
+    ///
+    /// Store the email hashes of the Users that do not have them yet, for instance after an email hash key was
+    /// configured for an existing database.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_service = MongoUserService::new(user_repository, user_summary_repository, user_store_config);
+    /// let db = mongodb::Database::new();
+    ///
+    /// user_service.backfill_email_hashes(&db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `()` - The operation was successful.
+    /// * `Error` - The Error that occurred.
+    async fn backfill_email_hashes(&self, db: &Database) -> Result<(), Error>;
+
+    /// # Summary
+    ///
+    /// Record that a User has logged in. A failure is logged and does not fail the login.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the User that logged in.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_service = MongoUserService::new(user_repository, user_summary_repository, user_store_config);
+    /// let db = mongodb::Database::new();
+    ///
+    /// user_service.record_login(user.id, &db);
+    /// ```
+    async fn record_login(&self, id: ObjectId, db: &Database);
+
+    /// # Summary
+    ///
+    /// Update the name of a Role in the UserSummaries of the Users that hold the Role.
+    ///
+    /// # Arguments
+    ///
+    /// * `role_id` - The ID of the renamed Role.
+    /// * `name` - The new name of the Role.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_service = MongoUserService::new(user_repository, user_summary_repository, user_store_config);
+    /// let db = mongodb::Database::new();
+    ///
+    /// user_service.rename_role(role.id, &role.name, &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `()` - The operation was successful.
+    /// * `Error` - The Error that occurred.
+    async fn rename_role(&self, role_id: ObjectId, name: &str, db: &Database) -> Result<(), Error>;
+
+    /// # Summary
+    ///
+    /// Search for Users by text and/or structured criteria.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - The criteria that the Users must meet.
+    /// * `limit` - The maximum number of Users to return.
+    /// * `page` - The page of Users to return.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_repository = UserRepository::new(String::from("users"));
+    /// let user_service = MongoUserService::new(user_repository);
+    /// let db = mongodb::Database::new();
+    /// let filter = UserFilter {
+    ///     text: Some(String::from("text")),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let users = user_service.search(&filter, Some(10), Some(1), &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<SearchHit<User>>` - The Users that match the search criteria and their relevance, the most relevant first if the filter contains text.
+    /// * `Error` - The Error that occurred.
+    async fn search(
+        &self,
+        filter: &UserFilter,
+        limit: Option<i64>,
+        page: Option<i64>,
+        db: &Database,
+    ) -> Result<Vec<SearchHit<User>>, Error>;
+
+    /// # Summary
+    ///
+    /// Count the Users that match a UserFilter.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - The criteria that the Users must meet.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let total = user_service.count(&UserFilter::default(), &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64, Error>` - The number of Users.
+    async fn count(&self, filter: &UserFilter, db: &Database) -> Result<u64, Error>;
+}
+
+impl MongoUserService {
+    /// # Summary
+    ///
+    /// Create a new MongoUserService.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_repository` - The UserRepository to be used by the UserService.
+    /// * `user_summary_repository` - The UserSummaryRepository that maintains the denormalized UserSummaries.
+    /// * `user_store_config` - The configuration of the external UserStore.
+    /// * `max_roles` - The maximum number of Roles that a User can have, or zero for no limit.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_repository = UserRepository::new(String::from("users"));
+    /// let user_summary_repository = UserSummaryRepository::new(
+    ///     String::from("userSummaries"),
+    ///     String::from("users"),
+    ///     String::from("roles"),
+    /// );
+    /// let user_service = MongoUserService::new(
+    ///     user_repository,
+    ///     user_summary_repository,
+    ///     UserStoreConfig::new(None, false),
+    ///     100,
+    /// );
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `MongoUserService` - The new MongoUserService.
+    pub fn new(
+        user_repository: UserRepository,
+        user_summary_repository: UserSummaryRepository,
+        user_store_config: UserStoreConfig,
+        max_roles: usize,
+    ) -> MongoUserService {
+        MongoUserService {
+            user_repository,
+            user_summary_repository,
+            user_store: user_store_config.user_store,
+            provision_users: user_store_config.provision_users,
+            max_roles,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check that a User does not have more Roles than allowed.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The User to check.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - TooManyRoles if the User has more Roles than allowed.
+    fn check_roles(&self, user: &User) -> Result<(), Error> {
+        let count = user.roles.as_ref().map_or(0, |r| r.len());
+
+        if self.max_roles > 0 && count > self.max_roles {
+            return Err(Error::TooManyRoles(self.max_roles));
+        }
+
+        Ok(())
+    }
+
+    /// # Summary
+    ///
+    /// Rebuild the UserSummary of a User after the User was written. The User itself has been stored at this point, so a
+    /// failure is logged instead of failing the write; the UserSummary is rebuilt on the next write or at startup.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the User that was written.
+    /// * `db` - The Database to be used.
+    async fn refresh_summary(&self, id: ObjectId, db: &Database) {
+        if let Err(e) = self.user_summary_repository.refresh(Some(id), db).await {
+            error!("Failed to refresh UserSummary of User {}: {}", id, e);
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Create an Update Audit for a User entity if the User ID of the actor is known.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the User entity that is being updated.
+    /// * `user_id` - The ID of the User entity that is updating the User.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    async fn audit_update(
+        &self,
+        id: &str,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(), Error> {
+        let user_id = match user_id {
+            Some(u) => u,
+            None => return Ok(()),
+        };
+
+        let oid = match ObjectId::parse_str(id) {
+            Ok(oid) => oid,
+            Err(e) => {
+                return Err(Error::Audit(AuditError::ObjectId(e.to_string())));
+            }
+        };
+
+        let new_audit = Audit::new(
+            user_id,
+            Update,
+            oid,
+            ResourceIdType::UserId,
+            ResourceType::User,
+        );
+        match audit_service.create(new_audit, db).await {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                error!("Failed to create Audit: {}", e);
+                Err(Error::Audit(e))
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl UserService for MongoUserService {
+    /// # Summary
+    ///
+    /// Create a User entity.
+    async fn create(
+        &self,
+        user: User,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<User, Error> {
+        info!("Creating User: {}", user);
+        self.check_roles(&user)?;
+
+        if user_id.is_some() {
+            let new_audit = Audit::new(
+                user_id.unwrap(),
+                Create,
+                user.id,
+                ResourceIdType::UserId,
+                ResourceType::User,
+            );
+            match audit_service.create(new_audit, db).await {
+                Ok(_) => {}
+                Err(e) => {
+                    error!("Failed to create Audit: {}", e);
+                    return Err(Error::Audit(e));
+                }
+            }
+        }
+
+        let user = self.user_repository.create(user, db).await?;
+        self.refresh_summary(user.id, db).await;
+        Ok(user)
+    }
+
+    /// # Summary
+    ///
+    /// Find all User entities.
+    async fn find_all(
+        &self,
+        limit: Option<i64>,
+        page: Option<i64>,
+        db: &Database,
+    ) -> Result<Vec<User>, Error> {
+        info!("Finding all users");
+        self.user_repository.find_all(limit, page, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Find a User entity by ID.
+    async fn find_by_id(&self, id: &str, db: &Database) -> Result<Option<User>, Error> {
+        info!("Finding User by ID: {}", id);
+        self.user_repository.find_by_id(id, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Find User entities by their IDs.
+    async fn find_by_id_vec(&self, id_vec: Vec<String>, db: &Database) -> Result<Vec<User>, Error> {
+        info!("Finding Users by ID vec: {:?}", id_vec);
+        self.user_repository.find_by_id_vec(id_vec, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Find a User entity by its username.
+    async fn find_by_username(&self, username: &str, db: &Database) -> Result<Option<User>, Error> {
+        info!("Finding User by username: {}", username);
+        self.user_repository.find_by_username(username, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Find a User entity by its primary or one of its secondary email addresses.
+    async fn find_by_email(&self, email: &str, db: &Database) -> Result<Option<User>, Error> {
+        info!("Finding User by email");
+        self.user_repository.find_by_email(email, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Find the User entity that is linked to an account of an external identity provider.
+    async fn find_by_identity(
+        &self,
+        provider: &str,
+        subject: &str,
+        db: &Database,
+    ) -> Result<Option<User>, Error> {
+        info!("Finding User by {} identity: {}", provider, subject);
+        self.user_repository
+            .find_by_identity(provider, subject, db)
+            .await
+    }
+
+    /// # Summary
+    ///
+    /// Link a User entity to an account of an external identity provider.
+    async fn link_identity(
+        &self,
+        id: &str,
+        identity: UserIdentity,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<User, Error> {
+        info!("Linking {} identity to User: {}", identity.provider, id);
+        self.audit_update(id, user_id, db, audit_service).await?;
+        self.user_repository.add_identity(id, identity, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Store or remove the TOTP secret of a User entity.
+    async fn set_totp(
+        &self,
+        id: &str,
+        totp: Option<UserTotp>,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<User, Error> {
+        info!("Changing TOTP secret of User: {}", id);
+        self.audit_update(id, user_id, db, audit_service).await?;
+        self.user_repository.set_totp(id, totp, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Record that the TOTP code of a time step was used by a User entity.
+    async fn use_totp_step(&self, id: ObjectId, step: i64, db: &Database) -> Result<bool, Error> {
+        self.user_repository.use_totp_step(id, step, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Replace the hashes of the recovery codes of a User entity.
+    async fn set_recovery_codes(
+        &self,
+        id: &str,
+        recovery_codes: Vec<String>,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<User, Error> {
+        info!("Changing recovery codes of User: {}", id);
+        self.audit_update(id, user_id, db, audit_service).await?;
+        self.user_repository
+            .set_recovery_codes(id, recovery_codes, db)
+            .await
+    }
+
+    /// # Summary
+    ///
+    /// Use a recovery code of a User entity.
+    async fn use_recovery_code(
+        &self,
+        id: ObjectId,
+        recovery_code: &str,
+        db: &Database,
+    ) -> Result<bool, Error> {
+        self.user_repository
+            .use_recovery_code(id, recovery_code, db)
+            .await
+    }
+
+    /// # Summary
+    ///
+    /// Mark whether a User entity is enrolled in multi-factor authentication.
+    async fn set_mfa_enrolled(
+        &self,
+        id: &str,
+        enrolled: bool,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<User, Error> {
+        info!("Changing MFA enrollment of User: {}", id);
+        self.audit_update(id, user_id, db, audit_service).await?;
+        self.user_repository
+            .set_mfa_enrolled(id, enrolled, db)
+            .await
+    }
+
+    /// # Summary
+    ///
+    /// Find a User entity by its username, falling back to the external UserStore if the User cannot be found locally.
+    /// Users that are found in the UserStore are stored locally if provisioning is enabled.
+    async fn find_by_username_federated(
+        &self,
+        username: &str,
+        db: &Database,
+        role_service: &Arc<dyn RoleService>,
+    ) -> Result<Option<User>, Error> {
+        if let Some(user) = self.find_by_username(username, db).await? {
+            return Ok(Some(user));
+        }
+
+        let user_store = match &self.user_store {
+            Some(s) => s,
+            None => return Ok(None),
+        };
+
+        info!(
+            "Finding User by username in the external UserStore: {}",
+            username
+        );
+        let mut user = match user_store.find_by_username(username).await {
+            Ok(Some(u)) => u,
+            Ok(None) => return Ok(None),
+            Err(e) => return Err(Error::UserStore(e)),
+        };
+
+        if !self.provision_users {
+            return Ok(Some(user));
+        }
+
+        info!(
+            "Provisioning User from the external UserStore: {}",
+            username
+        );
+
+        user.source = Some(UserSource::Directory);
+
+        // A failure to find the roles of the domain leaves the User with the roles of the UserStore only
+        if let Some(email) = &user.email {
+            match role_service.find_by_email_domain(email, db).await {
+                Ok(r) => user.grant_roles(&r.iter().map(|r| r.id).collect::<Vec<ObjectId>>()),
+                Err(e) => error!("Failed to find roles of email domain: {}", e),
+            }
+        }
+
+        let user = self.user_repository.create(user, db).await?;
+        self.refresh_summary(user.id, db).await;
+        Ok(Some(user))
+    }
+
+    /// # Summary
+    ///
+    /// Verify the password of a User entity against the external UserStore.
+    async fn verify_external_password(
+        &self,
+        username: &str,
+        password: &str,
+    ) -> Result<bool, Error> {
+        match &self.user_store {
+            Some(s) => s
+                .verify_password(username, password)
+                .await
+                .map_err(Error::UserStore),
+            None => Ok(false),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Update a user entity.
+    async fn update(
+        &self,
+        user: User,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<User, Error> {
+        info!("Updating User: {}", user);
+        self.check_roles(&user)?;
+
+        if let Some(current) = self
+            .user_repository
+            .find_by_id(&user.id.to_hex(), db)
+            .await?
+        {
+            if !current.status.can_transition_to(&user.status) {
+                return Err(Error::InvalidStatusTransition(
+                    current.status.to_string(),
+                    user.status.to_string(),
+                ));
+            }
+        }
+
+        if user_id.is_some() {
+            let new_audit = Audit::new(
+                user_id.unwrap(),
+                Update,
+                user.id,
+                ResourceIdType::UserId,
+                ResourceType::User,
+            );
+            match audit_service.create(new_audit, db).await {
+                Ok(_) => {}
+                Err(e) => {
+                    error!("Failed to create Audit: {}", e);
+                    return Err(Error::Audit(e));
+                }
+            }
+        }
+
+        let user = self.user_repository.update(user, db).await?;
+        self.refresh_summary(user.id, db).await;
+        Ok(user)
+    }
+
+    /// # Summary
+    ///
+    /// Update a User entity's password.
+    async fn update_password(
+        &self,
+        id: &str,
+        password: &str,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(), Error> {
+        info!("Updating User password: {}", id);
+
+        if user_id.is_some() {
+            let oid = match ObjectId::parse_str(id) {
+                Ok(oid) => oid,
+                Err(e) => {
+                    return Err(Error::Audit(AuditError::ObjectId(e.to_string())));
+                }
+            };
+
+            let new_audit = Audit::new(
+                user_id.unwrap(),
+                Update,
+                oid,
+                ResourceIdType::UserId,
+                ResourceType::User,
+            );
+            match audit_service.create(new_audit, db).await {
+                Ok(_) => {}
+                Err(e) => {
+                    error!("Failed to create Audit: {}", e);
+                    return Err(Error::Audit(e));
+                }
+            }
+        }
+
+        self.user_repository.update_password(id, password, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Revoke all tokens that have been issued to a User entity.
+    async fn revoke_sessions(
+        &self,
+        id: &str,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(), Error> {
+        info!("Revoking sessions of User: {}", id);
+        self.audit_update(id, user_id, db, audit_service).await?;
+        self.user_repository.revoke_tokens(id, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Add a secondary email address to a User entity.
+    async fn add_email(
+        &self,
+        id: &str,
+        email: &str,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<User, Error> {
+        info!("Adding email address to User: {}", id);
+        self.audit_update(id, user_id, db, audit_service).await?;
+        let user = self.user_repository.add_email(id, email, db).await?;
+        self.refresh_summary(user.id, db).await;
+        Ok(user)
+    }
+
+    /// # Summary
+    ///
+    /// Remove a secondary email address from a User entity.
+    async fn remove_email(
+        &self,
+        id: &str,
+        email: &str,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<User, Error> {
+        info!("Removing email address from User: {}", id);
+        self.audit_update(id, user_id, db, audit_service).await?;
+        let user = self.user_repository.remove_email(id, email, db).await?;
+        self.refresh_summary(user.id, db).await;
+        Ok(user)
+    }
+
+    /// # Summary
+    ///
+    /// Mark a secondary email address of a User entity as its primary email address.
+    async fn set_primary_email(
+        &self,
+        id: &str,
+        email: &str,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<User, Error> {
+        info!("Changing primary email address of User: {}", id);
+        self.audit_update(id, user_id, db, audit_service).await?;
+        let user = self
+            .user_repository
+            .set_primary_email(id, email, db)
+            .await?;
+        self.refresh_summary(user.id, db).await;
+        Ok(user)
+    }
+
+    /// # Summary
+    ///
+    /// Set the verification state of one of the email addresses of a User entity.
+    async fn set_email_verified(
+        &self,
+        id: &str,
+        email: &str,
+        verified: bool,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<User, Error> {
+        info!("Changing email verification state of User: {}", id);
+        self.audit_update(id, user_id, db, audit_service).await?;
+        let user = self
+            .user_repository
+            .set_email_verified(id, email, verified, db)
+            .await?;
+        self.refresh_summary(user.id, db).await;
+        Ok(user)
+    }
+
+    /// # Summary
+    ///
+    /// Delete a User entity by ID.
+    async fn delete(
+        &self,
+        id: &str,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(), Error> {
+        info!("Deleting User: {}", id);
+
+        if user_id.is_some() {
+            let oid = match ObjectId::parse_str(id) {
+                Ok(oid) => oid,
+                Err(e) => {
+                    return Err(Error::Audit(AuditError::ObjectId(e.to_string())));
+                }
+            };
+
+            let new_audit = Audit::new(
+                user_id.unwrap(),
+                Delete,
+                oid,
+                ResourceIdType::UserId,
+                ResourceType::User,
+            );
+            match audit_service.create(new_audit, db).await {
+                Ok(_) => {}
+                Err(e) => {
+                    error!("Failed to create Audit: {}", e);
+                    return Err(Error::Audit(e));
+                }
+            }
+        }
+
+        self.user_repository.delete(id, db).await?;
+
+        if let Ok(oid) = ObjectId::parse_str(id) {
+            if let Err(e) = self.user_summary_repository.delete(oid, db).await {
+                error!("Failed to delete UserSummary of User {}: {}", id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// # Summary
+    ///
+    /// Merge a duplicate User entity into a User entity.
+    async fn merge(&self, user: User, other: User, db: &Database) -> Result<User, Error> {
+        info!("Merging User {} into User: {}", other.id, user.id);
+        self.check_roles(&user)?;
+
+        let user = self.user_repository.merge(user, other, db).await?;
+        self.refresh_summary(user.id, db).await;
+
+        Ok(user)
+    }
+
+    /// # Summary
+    ///
+    /// Complete the merge of a duplicate User entity.
+    async fn complete_merge(
+        &self,
+        id: ObjectId,
+        other_id: ObjectId,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &Arc<dyn AuditService>,
+    ) -> Result<(), Error> {
+        info!(
+            "Completing the merge of User {} into User: {}",
+            other_id, id
+        );
+
+        // The Audits are reattributed before the merge is audited, so that the deletion of the duplicate stays on record
+        match audit_service.reattribute(other_id, id, db).await {
+            Ok(n) => info!("Reattributed {} audits of User: {}", n, other_id),
+            Err(e) => {
+                error!("Failed to reattribute Audits: {}", e);
+                return Err(Error::Audit(e));
+            }
+        }
+
+        if let Some(actor) = user_id {
+            for (action, resource_id) in [(Update, id), (Delete, other_id)] {
+                let new_audit = Audit::new(
+                    actor,
+                    action,
+                    resource_id,
+                    ResourceIdType::UserId,
+                    ResourceType::User,
+                );
+                if let Err(e) = audit_service.create(new_audit, db).await {
+                    error!("Failed to create Audit: {}", e);
+                    return Err(Error::Audit(e));
+                }
+            }
+        }
+
+        self.user_repository.delete(&other_id.to_hex(), db).await?;
+
+        if let Err(e) = self.user_summary_repository.delete(other_id, db).await {
+            error!("Failed to delete UserSummary of User {}: {}", other_id, e);
+        }
+
+        Ok(())
+    }
+
+    /// # Summary
+    ///
+    /// Delete a Role from all Users.
+    async fn delete_role_from_all_users(&self, role_id: &str, db: &Database) -> Result<(), Error> {
+        info!("Deleting Role from all Users: {}", role_id);
+        self.user_repository
+            .delete_role_from_all_users(role_id, db)
+            .await?;
+
+        if let Ok(oid) = ObjectId::parse_str(role_id) {
+            if let Err(e) = self.user_summary_repository.remove_role(oid, db).await {
+                error!(
+                    "Failed to remove Role {} from UserSummaries: {}",
+                    role_id, e
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// # Summary
+    ///
+    /// Count the Users that hold a Role.
+    async fn count_by_role(&self, role_id: &str, db: &Database) -> Result<u64, Error> {
+        info!("Counting Users by Role: {}", role_id);
+        self.user_repository.count_by_role(role_id, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Count the active Users that hold a Role.
+    async fn count_active_by_role(&self, role_id: &str, db: &Database) -> Result<u64, Error> {
+        info!("Counting active Users by Role: {}", role_id);
+        self.user_repository.count_active_by_role(role_id, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Require a User to change its password.
+    async fn require_password_change(&self, id: &str, db: &Database) -> Result<(), Error> {
+        info!("Requiring User to change its password: {}", id);
+        self.user_repository.require_password_change(id, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Update the status of the User entities of which a scheduled activation or expiration date has passed.
+    async fn apply_schedules(&self, db: &Database) -> Result<(), Error> {
+        let activated = self.user_repository.activate_scheduled(db).await?;
+        let expired = self.user_repository.expire_scheduled(db).await?;
+
+        if activated > 0 || expired > 0 {
+            info!(
+                "Applied User schedules: {} activated, {} expired",
+                activated, expired
+            );
+
+            // The IDs of the affected Users are not known, so every UserSummary is rebuilt
+            if let Err(e) = self.user_summary_repository.refresh(None, db).await {
+                error!("Failed to refresh UserSummaries: {}", e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// # Summary
+    ///
+    /// Search for Users by text and/or structured criteria.
+    async fn search(
+        &self,
+        filter: &UserFilter,
+        limit: Option<i64>,
+        page: Option<i64>,
+        db: &Database,
+    ) -> Result<Vec<SearchHit<User>>, Error> {
+        info!("Searching for Users by filter");
+        self.user_repository.search(filter, limit, page, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Count the Users that match a UserFilter.
+    async fn count(&self, filter: &UserFilter, db: &Database) -> Result<u64, Error> {
+        self.user_repository.count(filter, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Find the denormalized UserSummaries.
+    async fn find_summaries(
+        &self,
+        limit: Option<i64>,
+        page: Option<i64>,
+        db: &Database,
+    ) -> Result<Vec<UserSummary>, Error> {
+        info!("Finding all UserSummaries");
+        self.user_summary_repository
+            .find_all(limit, page, db)
+            .await
+            .map_err(summary_error)
+    }
+
+    /// # Summary
+    ///
+    /// Rebuild the UserSummaries of all Users if not every User has a UserSummary.
+    async fn backfill_summaries(&self, db: &Database) -> Result<(), Error> {
+        if self
+            .user_summary_repository
+            .is_complete(db)
+            .await
+            .map_err(summary_error)?
+        {
+            return Ok(());
+        }
+
+        self.rebuild_summaries(db).await
+    }
+
+    /// # Summary
+    ///
+    /// Rebuild the UserSummaries of all Users.
+    async fn rebuild_summaries(&self, db: &Database) -> Result<(), Error> {
+        info!("Rebuilding UserSummaries");
+        self.user_summary_repository
+            .refresh(None, db)
+            .await
+            .map_err(summary_error)
+    }
+
+    /// # Summary
+    ///
+    /// Store the email hashes of the Users that do not have them yet.
+    async fn backfill_email_hashes(&self, db: &Database) -> Result<(), Error> {
+        let (backfilled, skipped) = self.user_repository.backfill_email_hashes(db).await?;
+
+        if backfilled > 0 {
+            info!("Stored the email hashes of {} Users", backfilled);
+        }
+
+        for id in skipped {
+            warn!(
+                "Failed to store the email hashes of User {}, an email address is already in use by another User",
+                id
+            );
+        }
+
+        Ok(())
+    }
+
+    /// # Summary
+    ///
+    /// Record that a User has logged in.
+    async fn record_login(&self, id: ObjectId, db: &Database) {
+        let now: DateTime<Utc> = SystemTime::now().into();
+
+        if let Err(e) = self
+            .user_summary_repository
+            .set_last_login(id, now, db)
+            .await
+        {
+            error!("Failed to record last login of User {}: {}", id, e);
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Update the name of a Role in the UserSummaries.
+    async fn rename_role(&self, role_id: ObjectId, name: &str, db: &Database) -> Result<(), Error> {
+        info!("Renaming Role in UserSummaries: {}", role_id);
+        self.user_summary_repository
+            .rename_role(role_id, name, db)
+            .await
+            .map_err(summary_error)
+    }
+}
+
+/// # Summary
+///
+/// Convert a UserSummaryRepository Error into a UserRepository Error.
+///
+/// # Arguments
+///
+/// * `e` - The UserSummaryRepository Error.
+///
+/// # Returns
+///
+/// * `Error` - The UserRepository Error.
+fn summary_error(e: UserSummaryError) -> Error {
+    match e {
+        UserSummaryError::EmptyCollection => Error::EmptyCollection,
+        UserSummaryError::MongoDb(e) => Error::MongoDb(e),
     }
 }
@@ -0,0 +1,51 @@
+use crate::repository::user::user_model::User;
+use futures::future::BoxFuture;
+
+pub trait UserStore: Send + Sync {
+    /// # Summary
+    ///
+    /// Find a User in the external directory by its username.
+    /// Users that are returned by a UserStore should have an empty password, as authentication is delegated to the UserStore.
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - The username of the User.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user = user_store.find_by_username("username").await;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `BoxFuture<Result<Option<User>, String>>` - The User, if found, or the error that occurred.
+    fn find_by_username<'a>(
+        &'a self,
+        username: &'a str,
+    ) -> BoxFuture<'a, Result<Option<User>, String>>;
+
+    /// # Summary
+    ///
+    /// Verify the password of a User against the external directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - The username of the User.
+    /// * `password` - The password to verify.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let valid = user_store.verify_password("username", "password").await;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `BoxFuture<Result<bool, String>>` - Whether the password is valid or the error that occurred.
+    fn verify_password<'a>(
+        &'a self,
+        username: &'a str,
+        password: &'a str,
+    ) -> BoxFuture<'a, Result<bool, String>>;
+}
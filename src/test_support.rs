@@ -0,0 +1,274 @@
+//! # Summary
+//! Helpers for the unit tests of the application
+//!
+//! # Description
+//!
+//! Builds a Config whose services are the mocks that are generated for the service traits, so that handlers can be
+//! tested without a database. A service that is not expected to be called panics when it is called anyway.
+use crate::components::data_exporter::DataExporter;
+use crate::components::db_watchdog::DbWatchdog;
+use crate::components::email_worker::EmailWorker;
+use crate::components::maintenance_mode::MaintenanceMode;
+use crate::components::self_check::SelfCheck;
+use crate::components::tenant_router::TenantRouter;
+use crate::components::user_scheduler::UserScheduler;
+use crate::configuration::admin_config::AdminConfig;
+use crate::configuration::config::Config;
+use crate::configuration::db_config::DbConfig;
+use crate::configuration::device_config::DeviceConfig;
+use crate::configuration::email_config::EmailConfig;
+use crate::configuration::email_queue_config::EmailQueueConfig;
+use crate::configuration::guest_config::GuestConfig;
+use crate::configuration::health_config::HealthConfig;
+use crate::configuration::jwt_config::JwtConfig;
+use crate::configuration::magic_link_config::MagicLinkConfig;
+use crate::configuration::permission_config::PermissionConfig;
+use crate::configuration::registration_config::RegistrationConfig;
+use crate::configuration::request_logging_config::RequestLoggingConfig;
+use crate::configuration::server_config::ServerConfig;
+use crate::configuration::social_login_config::SocialLoginConfig;
+use crate::configuration::sso_config::SsoConfig;
+use crate::configuration::webauthn_config::WebauthnConfig;
+use crate::repository::user::user_model::User;
+use crate::repository::user::user_status::UserStatus;
+use crate::services::api_key::api_key_service::MockApiKeyService;
+use crate::services::audit::audit_service::MockAuditService;
+use crate::services::authorization_code::authorization_code_service::MockAuthorizationCodeService;
+use crate::services::backup::backup_service::MockBackupService;
+use crate::services::client::client_service::MockClientService;
+use crate::services::consent::consent_service::MockConsentService;
+use crate::services::credential::credential_service::MockCredentialService;
+use crate::services::device::device_service::MockDeviceService;
+use crate::services::email_job::email_job_service::MockEmailJobService;
+use crate::services::invitation::invitation_service::MockInvitationService;
+use crate::services::jwt::jwt_service::JwtService;
+use crate::services::login_attempt::login_attempt_service::LoginAttemptService;
+use crate::services::magic_link::magic_link_service::MockMagicLinkService;
+use crate::services::mfa::totp_service::TotpService;
+use crate::services::mfa::webauthn_service::WebauthnService;
+use crate::services::organization::organization_service::MockOrganizationService;
+use crate::services::password::argon2_password_hasher::Argon2PasswordHasher;
+use crate::services::password::password_service::PasswordService;
+use crate::services::permission::permission_service::MockPermissionService;
+use crate::services::quota::quota_service::{QuotaMode, QuotaService};
+use crate::services::refresh_token::refresh_token_service::MockRefreshTokenService;
+use crate::services::revoked_token::revoked_token_service::MockRevokedTokenService;
+use crate::services::role::role_service::MockRoleService;
+use crate::services::role_change::role_change_service::MockRoleChangeService;
+use crate::services::session::session_service::MockSessionService;
+use crate::services::social::social_login_service::SocialLoginService;
+use crate::services::sso::sso_service::SsoService;
+use crate::services::user::user_service::MockUserService;
+use crate::services::Services;
+use mongodb::bson::oid::ObjectId;
+use mongodb::Client;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Default)]
+pub struct MockServices {
+    pub permission_service: MockPermissionService,
+    pub role_service: MockRoleService,
+    pub user_service: MockUserService,
+    pub audit_service: MockAuditService,
+    pub backup_service: MockBackupService,
+    pub device_service: MockDeviceService,
+    pub client_service: MockClientService,
+    pub consent_service: MockConsentService,
+    pub organization_service: MockOrganizationService,
+    pub invitation_service: MockInvitationService,
+    pub refresh_token_service: MockRefreshTokenService,
+    pub revoked_token_service: MockRevokedTokenService,
+    pub email_job_service: MockEmailJobService,
+    pub session_service: MockSessionService,
+    pub api_key_service: MockApiKeyService,
+    pub authorization_code_service: MockAuthorizationCodeService,
+    pub role_change_service: MockRoleChangeService,
+    pub credential_service: MockCredentialService,
+    pub magic_link_service: MockMagicLinkService,
+}
+
+impl MockServices {
+    /// # Summary
+    ///
+    /// Create a new MockServices instance in which no token has been revoked.
+    ///
+    /// # Returns
+    ///
+    /// * `MockServices` - The new MockServices.
+    pub fn new() -> MockServices {
+        let mut mocks = MockServices::default();
+        mocks
+            .revoked_token_service
+            .expect_is_revoked()
+            .returning(|_, _| Ok(false));
+
+        mocks
+    }
+
+    /// # Summary
+    ///
+    /// Let the UserService return the given User when it is looked up by its ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The User.
+    pub fn with_user(&mut self, user: User) {
+        let id = user.id.to_hex();
+        self.user_service
+            .expect_find_by_id()
+            .withf(move |i, _| i == id)
+            .returning(move |_, _| Ok(Some(user.clone())));
+    }
+}
+
+/// # Summary
+///
+/// Build a Config that uses the given mocks. The Database is never connected to, because the mocks do not use it.
+///
+/// # Arguments
+///
+/// * `mocks` - The MockServices.
+///
+/// # Returns
+///
+/// * `Config` - The Config.
+pub async fn config(mocks: MockServices) -> Config {
+    let client = Client::with_uri_str("mongodb://localhost").await.unwrap();
+    let db = client.database("auth-rs-test");
+
+    let user_service = Arc::new(mocks.user_service);
+    let role_service = Arc::new(mocks.role_service);
+    let audit_service = Arc::new(mocks.audit_service);
+    let backup_service = Arc::new(mocks.backup_service);
+    let email_job_service = Arc::new(mocks.email_job_service);
+
+    let services = Services::new(
+        Arc::new(mocks.permission_service),
+        role_service.clone(),
+        user_service.clone(),
+        JwtService::new(JwtConfig::new(String::from("secret"), 3600, 0, 1209600)).unwrap(),
+        audit_service.clone(),
+        PasswordService::new(Arc::new(Argon2PasswordHasher::default())),
+        QuotaService::new(0, QuotaMode::Monitor),
+        backup_service.clone(),
+        Arc::new(mocks.device_service),
+        Arc::new(mocks.client_service),
+        Arc::new(mocks.consent_service),
+        Arc::new(mocks.organization_service),
+        Arc::new(mocks.invitation_service),
+        Arc::new(mocks.refresh_token_service),
+        Arc::new(mocks.revoked_token_service),
+        email_job_service.clone(),
+        Arc::new(mocks.session_service),
+        Arc::new(mocks.api_key_service),
+        Arc::new(mocks.authorization_code_service),
+        Arc::new(mocks.role_change_service),
+        Arc::new(mocks.credential_service),
+        Arc::new(mocks.magic_link_service),
+        SocialLoginService::new(SocialLoginConfig::new(vec![], String::new(), vec![], false)),
+        SsoService::new(SsoConfig::new(
+            None,
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            vec![],
+            false,
+        )),
+        LoginAttemptService::new(None, String::new(), 0, Duration::from_secs(60)),
+        TotpService::new(None, String::from("auth-rs"), 300),
+        WebauthnService::new(WebauthnConfig::new(
+            None,
+            None,
+            String::from("auth-rs"),
+            300,
+        ))
+        .unwrap(),
+    );
+
+    Config {
+        server_config: ServerConfig::new(String::from("127.0.0.1"), 8080, 100, 1, 0, 60),
+        database: db.clone(),
+        tenant_router: TenantRouter::connect(db.clone(), &HashMap::new(), "auth-rs-test")
+            .await
+            .unwrap(),
+        services,
+        open_api: false,
+        swagger_ui: false,
+        permission_config: PermissionConfig::new(HashMap::new(), false, false).unwrap(),
+        email_config: EmailConfig::new(false, false, None),
+        email_queue_config: EmailQueueConfig::new(60, 10, 3, 60, String::new(), String::new()),
+        device_config: DeviceConfig::new(String::new(), 600, 5),
+        magic_link_config: MagicLinkConfig::new(None, 600, String::new(), String::new()),
+        registration_config: RegistrationConfig::new(false, false, vec![], HashMap::new()),
+        request_logging_config: RequestLoggingConfig::new(false, false, false),
+        health_config: HealthConfig::new(None, String::new(), 0),
+        guest_config: GuestConfig::new(false, vec![], 3600),
+        admin_config: AdminConfig::new(vec![], String::new(), 0),
+        slow_query_logger: None,
+        command_monitor: None,
+        db_watchdog: DbWatchdog::new(db.clone(), Duration::from_secs(60)),
+        user_scheduler: UserScheduler::new(
+            db.clone(),
+            user_service,
+            role_service,
+            Duration::from_secs(60),
+        ),
+        email_worker: EmailWorker::new(db.clone(), email_job_service, Duration::from_secs(60)),
+        data_exporter: DataExporter::new(
+            db.clone(),
+            audit_service,
+            backup_service,
+            None,
+            String::new(),
+            Duration::from_secs(60),
+        ),
+        self_check: SelfCheck::new(db, vec![], None, 6),
+        db_config: Arc::new(DbConfig::default()),
+        maintenance_mode: MaintenanceMode::default(),
+    }
+}
+
+/// # Summary
+///
+/// Create an active User.
+///
+/// # Returns
+///
+/// * `User` - The User.
+pub fn user() -> User {
+    User::new(
+        String::from("test"),
+        Some(String::from("test@example.com")),
+        None,
+        None,
+        String::new(),
+        None,
+        UserStatus::Active,
+    )
+}
+
+/// # Summary
+///
+/// Create the Authorization header value of a JWT token for a User.
+///
+/// # Arguments
+///
+/// * `config` - The Config that signs the token.
+/// * `user_id` - The ID of the User.
+/// * `token_version` - The token version of the User.
+///
+/// # Returns
+///
+/// * `String` - The Authorization header value.
+pub fn bearer(config: &Config, user_id: &ObjectId, token_version: i64) -> String {
+    let token = config
+        .services
+        .jwt_service
+        .generate_jwt_token(&user_id.to_hex(), token_version)
+        .unwrap();
+
+    format!("Bearer {}", token)
+}
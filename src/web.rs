@@ -1,3 +1,6 @@
 pub mod controller;
 pub mod dto;
 pub mod extractors;
+pub mod guards;
+pub mod middleware;
+pub mod pagination;
@@ -1,70 +1,831 @@
+use crate::web::controller::admin::admin_controller;
+use crate::web::controller::api_key::api_key_controller;
 use crate::web::controller::authentication::authentication_controller;
+use crate::web::controller::authorization::authorization_controller;
+use crate::web::controller::client::client_controller;
+use crate::web::controller::consent::consent_controller;
 use crate::web::controller::health::health_controller;
+use crate::web::controller::invitation::invitation_controller;
+use crate::web::controller::oauth::oauth_controller;
+use crate::web::controller::open_api::open_api_controller;
+use crate::web::controller::organization::organization_controller;
 use crate::web::controller::permission::permission_controller;
 use crate::web::controller::role::role_controller;
-use crate::web::controller::user::user_controller;
+use crate::web::controller::role_change::role_change_controller;
+use crate::web::controller::user::{mfa_controller, user_controller};
+use crate::web::controller::well_known::well_known_controller;
+use crate::web::guards::admin_guard::AdminGuard;
+use crate::web::guards::permission_guard::{PublicRoute, RoutePermission};
 use actix_web::web;
 
+pub mod admin;
+pub mod api_key;
 pub mod audit;
 pub mod authentication;
+pub mod authorization;
+pub mod client;
+pub mod consent;
 pub mod health;
+pub mod invitation;
+pub mod oauth;
+pub mod open_api;
+pub mod organization;
 pub mod permission;
 pub mod role;
+pub mod role_change;
 pub mod user;
+pub mod well_known;
+
+/// The permissions that are required by default to access the protected routes.
+/// These can be overridden at runtime using the `PermissionConfig`.
+pub const ROUTE_PERMISSIONS: &[RoutePermission] = &[
+    RoutePermission {
+        method: "POST",
+        path: "/api/v1/permissions/",
+        permission: "CAN_CREATE_PERMISSION",
+    },
+    RoutePermission {
+        method: "GET",
+        path: "/api/v1/permissions/",
+        permission: "CAN_READ_PERMISSION",
+    },
+    RoutePermission {
+        method: "GET",
+        path: "/api/v1/permissions/{id}",
+        permission: "CAN_READ_PERMISSION",
+    },
+    RoutePermission {
+        method: "PUT",
+        path: "/api/v1/permissions/{id}",
+        permission: "CAN_UPDATE_PERMISSION",
+    },
+    RoutePermission {
+        method: "DELETE",
+        path: "/api/v1/permissions/{id}",
+        permission: "CAN_DELETE_PERMISSION",
+    },
+    RoutePermission {
+        method: "POST",
+        path: "/api/v1/roles/",
+        permission: "CAN_CREATE_ROLE",
+    },
+    RoutePermission {
+        method: "GET",
+        path: "/api/v1/roles/",
+        permission: "CAN_READ_ROLE",
+    },
+    RoutePermission {
+        method: "GET",
+        path: "/api/v1/roles/{id}",
+        permission: "CAN_READ_ROLE",
+    },
+    RoutePermission {
+        method: "PUT",
+        path: "/api/v1/roles/{id}",
+        permission: "CAN_UPDATE_ROLE",
+    },
+    RoutePermission {
+        method: "DELETE",
+        path: "/api/v1/roles/{id}",
+        permission: "CAN_DELETE_ROLE",
+    },
+    RoutePermission {
+        method: "POST",
+        path: "/api/v1/users/",
+        permission: "CAN_CREATE_USER",
+    },
+    RoutePermission {
+        method: "GET",
+        path: "/api/v1/users/",
+        permission: "CAN_READ_USER",
+    },
+    RoutePermission {
+        method: "GET",
+        path: "/api/v1/users/{id}",
+        permission: "CAN_READ_USER",
+    },
+    RoutePermission {
+        method: "PUT",
+        path: "/api/v1/users/{id}",
+        permission: "CAN_UPDATE_USER",
+    },
+    RoutePermission {
+        method: "PUT",
+        path: "/api/v1/users/{id}/self/",
+        permission: "CAN_UPDATE_SELF",
+    },
+    RoutePermission {
+        method: "PUT",
+        path: "/api/v1/users/{id}/self/password/",
+        permission: "CAN_UPDATE_SELF",
+    },
+    RoutePermission {
+        method: "PUT",
+        path: "/api/v1/users/{id}/password/",
+        permission: "CAN_UPDATE_USER",
+    },
+    RoutePermission {
+        method: "POST",
+        path: "/api/v1/users/{id}/revoke-sessions/",
+        permission: "CAN_UPDATE_USER",
+    },
+    RoutePermission {
+        method: "POST",
+        path: "/api/v1/users/{id}/approve/",
+        permission: "CAN_UPDATE_USER",
+    },
+    RoutePermission {
+        method: "POST",
+        path: "/api/v1/users/{id}/reject/",
+        permission: "CAN_DELETE_USER",
+    },
+    RoutePermission {
+        method: "POST",
+        path: "/api/v1/users/{id}/emails/",
+        permission: "CAN_UPDATE_USER",
+    },
+    RoutePermission {
+        method: "PUT",
+        path: "/api/v1/users/{id}/emails/",
+        permission: "CAN_UPDATE_USER",
+    },
+    RoutePermission {
+        method: "DELETE",
+        path: "/api/v1/users/{id}/emails/",
+        permission: "CAN_UPDATE_USER",
+    },
+    RoutePermission {
+        method: "POST",
+        path: "/api/v1/users/{id}/merge/{otherId}",
+        permission: "CAN_DELETE_USER",
+    },
+    RoutePermission {
+        method: "DELETE",
+        path: "/api/v1/users/{id}",
+        permission: "CAN_DELETE_USER",
+    },
+    RoutePermission {
+        method: "DELETE",
+        path: "/api/v1/users/{id}/self/",
+        permission: "CAN_DELETE_SELF",
+    },
+    RoutePermission {
+        method: "POST",
+        path: "/api/v1/users/me/mfa/totp/",
+        permission: "CAN_UPDATE_SELF",
+    },
+    RoutePermission {
+        method: "POST",
+        path: "/api/v1/users/me/mfa/totp/confirm/",
+        permission: "CAN_UPDATE_SELF",
+    },
+    RoutePermission {
+        method: "DELETE",
+        path: "/api/v1/users/me/mfa/totp/",
+        permission: "CAN_UPDATE_SELF",
+    },
+    RoutePermission {
+        method: "GET",
+        path: "/api/v1/users/me/mfa/passkeys/",
+        permission: "CAN_UPDATE_SELF",
+    },
+    RoutePermission {
+        method: "POST",
+        path: "/api/v1/users/me/mfa/passkeys/",
+        permission: "CAN_UPDATE_SELF",
+    },
+    RoutePermission {
+        method: "POST",
+        path: "/api/v1/users/me/mfa/passkeys/finish/",
+        permission: "CAN_UPDATE_SELF",
+    },
+    RoutePermission {
+        method: "DELETE",
+        path: "/api/v1/users/me/mfa/passkeys/{id}/",
+        permission: "CAN_UPDATE_SELF",
+    },
+    RoutePermission {
+        method: "GET",
+        path: "/api/v1/users/me/mfa/recovery-codes/",
+        permission: "CAN_UPDATE_SELF",
+    },
+    RoutePermission {
+        method: "POST",
+        path: "/api/v1/users/me/mfa/recovery-codes/",
+        permission: "CAN_UPDATE_SELF",
+    },
+    RoutePermission {
+        method: "GET",
+        path: "/api/v1/role-changes/",
+        permission: "CAN_REVIEW_ROLE_CHANGE",
+    },
+    RoutePermission {
+        method: "POST",
+        path: "/api/v1/role-changes/{id}/approve/",
+        permission: "CAN_REVIEW_ROLE_CHANGE",
+    },
+    RoutePermission {
+        method: "POST",
+        path: "/api/v1/role-changes/{id}/reject/",
+        permission: "CAN_REVIEW_ROLE_CHANGE",
+    },
+    RoutePermission {
+        method: "GET",
+        path: "/api/v1/audits/",
+        permission: "CAN_READ_AUDIT",
+    },
+    RoutePermission {
+        method: "GET",
+        path: "/api/v1/audits/verify/",
+        permission: "CAN_READ_AUDIT",
+    },
+    RoutePermission {
+        method: "GET",
+        path: "/api/v1/audits/{id}",
+        permission: "CAN_READ_AUDIT",
+    },
+    RoutePermission {
+        method: "GET",
+        path: "/api/v1/users/{id}/audit/",
+        permission: "CAN_READ_AUDIT",
+    },
+    RoutePermission {
+        method: "POST",
+        path: "/api/v1/authorization/batch/",
+        permission: "CAN_READ_USER",
+    },
+    RoutePermission {
+        method: "GET",
+        path: "/api/v1/admin/backup/",
+        permission: "CAN_CREATE_BACKUP",
+    },
+    RoutePermission {
+        method: "POST",
+        path: "/api/v1/admin/restore/",
+        permission: "CAN_RESTORE_BACKUP",
+    },
+    RoutePermission {
+        method: "GET",
+        path: "/api/v1/admin/routes/",
+        permission: "CAN_READ_ROUTE",
+    },
+    RoutePermission {
+        method: "GET",
+        path: "/api/v1/admin/selfcheck/",
+        permission: "CAN_RUN_SELFCHECK",
+    },
+    RoutePermission {
+        method: "POST",
+        path: "/api/v1/admin/purge/",
+        permission: "CAN_PURGE_DATA",
+    },
+    RoutePermission {
+        method: "POST",
+        path: "/api/v1/admin/indexes/",
+        permission: "CAN_REBUILD_INDEXES",
+    },
+    RoutePermission {
+        method: "GET",
+        path: "/api/v1/admin/maintenance/",
+        permission: "CAN_MANAGE_MAINTENANCE",
+    },
+    RoutePermission {
+        method: "PUT",
+        path: "/api/v1/admin/maintenance/",
+        permission: "CAN_MANAGE_MAINTENANCE",
+    },
+    RoutePermission {
+        method: "GET",
+        path: "/api/v1/admin/commands/",
+        permission: "CAN_READ_METRICS",
+    },
+    RoutePermission {
+        method: "GET",
+        path: "/api/v1/admin/caches/",
+        permission: "CAN_READ_METRICS",
+    },
+    RoutePermission {
+        method: "POST",
+        path: "/api/v1/clients/",
+        permission: "CAN_CREATE_CLIENT",
+    },
+    RoutePermission {
+        method: "GET",
+        path: "/api/v1/clients/",
+        permission: "CAN_READ_CLIENT",
+    },
+    RoutePermission {
+        method: "GET",
+        path: "/api/v1/clients/{id}",
+        permission: "CAN_READ_CLIENT",
+    },
+    RoutePermission {
+        method: "PUT",
+        path: "/api/v1/clients/{id}",
+        permission: "CAN_UPDATE_CLIENT",
+    },
+    RoutePermission {
+        method: "POST",
+        path: "/api/v1/clients/{id}/secret/",
+        permission: "CAN_UPDATE_CLIENT",
+    },
+    RoutePermission {
+        method: "DELETE",
+        path: "/api/v1/clients/{id}",
+        permission: "CAN_DELETE_CLIENT",
+    },
+    RoutePermission {
+        method: "POST",
+        path: "/api/v1/api-keys/",
+        permission: "CAN_CREATE_API_KEY",
+    },
+    RoutePermission {
+        method: "GET",
+        path: "/api/v1/api-keys/",
+        permission: "CAN_READ_API_KEY",
+    },
+    RoutePermission {
+        method: "GET",
+        path: "/api/v1/api-keys/{id}",
+        permission: "CAN_READ_API_KEY",
+    },
+    RoutePermission {
+        method: "PUT",
+        path: "/api/v1/api-keys/{id}",
+        permission: "CAN_UPDATE_API_KEY",
+    },
+    RoutePermission {
+        method: "POST",
+        path: "/api/v1/api-keys/{id}/key/",
+        permission: "CAN_UPDATE_API_KEY",
+    },
+    RoutePermission {
+        method: "DELETE",
+        path: "/api/v1/api-keys/{id}",
+        permission: "CAN_DELETE_API_KEY",
+    },
+    RoutePermission {
+        method: "POST",
+        path: "/api/v1/organizations/",
+        permission: "CAN_CREATE_ORGANIZATION",
+    },
+    RoutePermission {
+        method: "GET",
+        path: "/api/v1/organizations/",
+        permission: "CAN_READ_ORGANIZATION",
+    },
+    RoutePermission {
+        method: "GET",
+        path: "/api/v1/organizations/{id}",
+        permission: "CAN_READ_ORGANIZATION",
+    },
+    RoutePermission {
+        method: "PUT",
+        path: "/api/v1/organizations/{id}",
+        permission: "CAN_UPDATE_ORGANIZATION",
+    },
+    RoutePermission {
+        method: "PUT",
+        path: "/api/v1/organizations/{id}/members/{user_id}",
+        permission: "CAN_UPDATE_ORGANIZATION",
+    },
+    RoutePermission {
+        method: "DELETE",
+        path: "/api/v1/organizations/{id}/members/{user_id}",
+        permission: "CAN_UPDATE_ORGANIZATION",
+    },
+    RoutePermission {
+        method: "POST",
+        path: "/api/v1/organizations/{id}/invitations/",
+        permission: "CAN_UPDATE_ORGANIZATION",
+    },
+    RoutePermission {
+        method: "GET",
+        path: "/api/v1/organizations/{id}/invitations/",
+        permission: "CAN_READ_ORGANIZATION",
+    },
+    RoutePermission {
+        method: "DELETE",
+        path: "/api/v1/organizations/{id}/invitations/{invitation_id}",
+        permission: "CAN_UPDATE_ORGANIZATION",
+    },
+    RoutePermission {
+        method: "POST",
+        path: "/api/v1/organizations/{id}/roles/",
+        permission: "CAN_UPDATE_ORGANIZATION",
+    },
+    RoutePermission {
+        method: "GET",
+        path: "/api/v1/organizations/{id}/roles/",
+        permission: "CAN_READ_ORGANIZATION",
+    },
+    RoutePermission {
+        method: "PUT",
+        path: "/api/v1/organizations/{id}/roles/{role_id}",
+        permission: "CAN_UPDATE_ORGANIZATION",
+    },
+    RoutePermission {
+        method: "DELETE",
+        path: "/api/v1/organizations/{id}/roles/{role_id}",
+        permission: "CAN_UPDATE_ORGANIZATION",
+    },
+    RoutePermission {
+        method: "DELETE",
+        path: "/api/v1/organizations/{id}",
+        permission: "CAN_DELETE_ORGANIZATION",
+    },
+];
+
+/// The routes that do not require a permission, because they are used to authenticate or only act on the caller
+/// itself. Routes that are neither listed here nor in `ROUTE_PERMISSIONS` are denied.
+pub const PUBLIC_ROUTES: &[PublicRoute] = &[
+    PublicRoute {
+        method: "POST",
+        path: "/api/v1/authentication/login/",
+    },
+    PublicRoute {
+        method: "POST",
+        path: "/api/v1/authentication/mfa/",
+    },
+    PublicRoute {
+        method: "POST",
+        path: "/api/v1/authentication/passkey/",
+    },
+    PublicRoute {
+        method: "POST",
+        path: "/api/v1/authentication/passkey/finish/",
+    },
+    PublicRoute {
+        method: "POST",
+        path: "/api/v1/authentication/magic-link/",
+    },
+    PublicRoute {
+        method: "POST",
+        path: "/api/v1/authentication/magic-link/verify/",
+    },
+    PublicRoute {
+        method: "POST",
+        path: "/api/v1/authentication/refresh/",
+    },
+    PublicRoute {
+        method: "POST",
+        path: "/api/v1/authentication/logout/",
+    },
+    PublicRoute {
+        method: "GET",
+        path: "/api/v1/authentication/current/",
+    },
+    PublicRoute {
+        method: "GET",
+        path: "/api/v1/authentication/permissions/",
+    },
+    PublicRoute {
+        method: "GET",
+        path: "/api/v1/authentication/organizations/",
+    },
+    PublicRoute {
+        method: "POST",
+        path: "/api/v1/authentication/tenant/",
+    },
+    PublicRoute {
+        method: "POST",
+        path: "/api/v1/authentication/guest/",
+    },
+    PublicRoute {
+        method: "POST",
+        path: "/api/v1/authentication/register/",
+    },
+    PublicRoute {
+        method: "GET",
+        path: "/api/v1/authentication/social/{provider}/login/",
+    },
+    PublicRoute {
+        method: "GET",
+        path: "/api/v1/authentication/social/{provider}/callback/",
+    },
+    PublicRoute {
+        method: "GET",
+        path: "/api/v1/authentication/sso/login/",
+    },
+    PublicRoute {
+        method: "GET",
+        path: "/api/v1/authentication/sso/callback/",
+    },
+    PublicRoute {
+        method: "POST",
+        path: "/api/v1/oauth/device/code/",
+    },
+    PublicRoute {
+        method: "POST",
+        path: "/api/v1/oauth/authorize/",
+    },
+    PublicRoute {
+        method: "POST",
+        path: "/api/v1/oauth/token/",
+    },
+    PublicRoute {
+        method: "GET",
+        path: "/api/v1/oauth/device/",
+    },
+    PublicRoute {
+        method: "POST",
+        path: "/api/v1/oauth/device/verify/",
+    },
+    PublicRoute {
+        method: "GET",
+        path: "/api/v1/consents/",
+    },
+    PublicRoute {
+        method: "POST",
+        path: "/api/v1/consents/",
+    },
+    PublicRoute {
+        method: "GET",
+        path: "/api/v1/consents/check/",
+    },
+    PublicRoute {
+        method: "DELETE",
+        path: "/api/v1/consents/{id}",
+    },
+    PublicRoute {
+        method: "POST",
+        path: "/api/v1/invitations/accept/",
+    },
+];
 
 pub struct Controller {}
 
 impl Controller {
     /// # Summary
     ///
-    /// Configure the routes for the web server.
+    /// Configure the routes of the API, relative to the `/api/v1` scope.
+    ///
+    /// # Arguments
+    ///
+    /// * `cfg` - The web server configuration.
+    pub fn configure_api_routes(cfg: &mut web::ServiceConfig) {
+        // Registered before the `/users` scope, which would otherwise claim the path
+        #[cfg(feature = "audit")]
+        cfg.service(audit::audit_controller::find_by_user);
+
+        cfg.service(
+            web::scope("/permissions")
+                .service(permission_controller::create_permission)
+                .service(permission_controller::find_all_permissions)
+                .service(permission_controller::find_by_id)
+                .service(permission_controller::update_permission)
+                .service(permission_controller::delete_permission),
+        )
+        .service(
+            web::scope("/roles")
+                .service(role_controller::create)
+                .service(role_controller::find_all_roles)
+                .service(role_controller::find_by_id)
+                .service(role_controller::update)
+                .service(role_controller::delete),
+        )
+        .service(
+            web::scope("/users")
+                .service(user_controller::create)
+                .service(user_controller::find_all)
+                .service(user_controller::find_by_id)
+                .service(user_controller::update)
+                .service(user_controller::update_password)
+                .service(user_controller::admin_update_password)
+                .service(user_controller::revoke_sessions)
+                .service(user_controller::approve)
+                .service(user_controller::reject)
+                .service(user_controller::add_email)
+                .service(user_controller::update_email)
+                .service(user_controller::remove_email)
+                .service(user_controller::merge)
+                .service(user_controller::delete)
+                .service(user_controller::delete_self)
+                .service(mfa_controller::enroll_totp)
+                .service(mfa_controller::confirm_totp)
+                .service(mfa_controller::remove_totp)
+                .service(mfa_controller::find_passkeys)
+                .service(mfa_controller::start_passkey_registration)
+                .service(mfa_controller::finish_passkey_registration)
+                .service(mfa_controller::remove_passkey)
+                .service(mfa_controller::find_recovery_code_count)
+                .service(mfa_controller::regenerate_recovery_codes),
+        )
+        .service(
+            web::scope("/authentication")
+                .service(authentication_controller::login)
+                .service(authentication_controller::verify_mfa)
+                .service(authentication_controller::start_passkey_login)
+                .service(authentication_controller::finish_passkey_login)
+                .service(authentication_controller::request_magic_link)
+                .service(authentication_controller::verify_magic_link)
+                .service(authentication_controller::refresh)
+                .service(authentication_controller::logout)
+                .service(authentication_controller::current_user)
+                .service(authentication_controller::current_permissions)
+                .service(authentication_controller::current_organizations)
+                .service(authentication_controller::switch_tenant)
+                .service(authentication_controller::guest)
+                .service(authentication_controller::register)
+                .service(authentication_controller::social_login)
+                .service(authentication_controller::social_callback)
+                .service(authentication_controller::sso_login)
+                .service(authentication_controller::sso_callback),
+        )
+        .service(web::scope("/authorization").service(authorization_controller::batch_authorize))
+        .service(
+            web::scope("/oauth")
+                .service(oauth_controller::device_code)
+                .service(oauth_controller::authorize)
+                .service(oauth_controller::token)
+                .service(oauth_controller::find_device)
+                .service(oauth_controller::verify_device),
+        )
+        .service(
+            web::scope("/clients")
+                .service(client_controller::create)
+                .service(client_controller::find_all)
+                .service(client_controller::find_by_id)
+                .service(client_controller::update)
+                .service(client_controller::rotate_secret)
+                .service(client_controller::delete),
+        )
+        .service(
+            web::scope("/api-keys")
+                .service(api_key_controller::create)
+                .service(api_key_controller::find_all)
+                .service(api_key_controller::find_by_id)
+                .service(api_key_controller::update)
+                .service(api_key_controller::rotate_key)
+                .service(api_key_controller::delete),
+        )
+        .service(
+            web::scope("/consents")
+                .service(consent_controller::find_all)
+                .service(consent_controller::grant)
+                .service(consent_controller::check)
+                .service(consent_controller::revoke),
+        )
+        .service(
+            web::scope("/organizations")
+                .service(organization_controller::create)
+                .service(organization_controller::find_all)
+                .service(organization_controller::find_by_id)
+                .service(organization_controller::update)
+                .service(organization_controller::set_member)
+                .service(organization_controller::remove_member)
+                .service(organization_controller::create_invitation)
+                .service(organization_controller::find_invitations)
+                .service(organization_controller::revoke_invitation)
+                .service(organization_controller::create_role)
+                .service(organization_controller::find_roles)
+                .service(organization_controller::update_role)
+                .service(organization_controller::delete_role)
+                .service(organization_controller::delete),
+        )
+        .service(web::scope("/invitations").service(invitation_controller::accept))
+        .service(
+            web::scope("/role-changes")
+                .service(role_change_controller::find_pending)
+                .service(role_change_controller::approve)
+                .service(role_change_controller::reject),
+        );
+
+        #[cfg(feature = "audit")]
+        cfg.service(
+            web::scope("/audits")
+                .service(audit::audit_controller::find_all)
+                .service(audit::audit_controller::verify)
+                .service(audit::audit_controller::find_by_id),
+        );
+    }
+
+    /// # Summary
+    ///
+    /// Configure the operational routes, relative to the `/api/v1` scope. These routes are guarded by the AdminGuard
+    /// in addition to the permissions that they require.
+    ///
+    /// # Arguments
+    ///
+    /// * `cfg` - The web server configuration.
+    pub fn configure_admin_routes(cfg: &mut web::ServiceConfig) {
+        let scope = web::scope("/admin")
+            .wrap(AdminGuard)
+            .app_data(web::JsonConfig::default().limit(admin_controller::MAX_BACKUP_SIZE))
+            .service(admin_controller::backup)
+            .service(admin_controller::restore)
+            .service(admin_controller::routes)
+            .service(admin_controller::selfcheck)
+            .service(admin_controller::purge)
+            .service(admin_controller::indexes)
+            .service(admin_controller::maintenance)
+            .service(admin_controller::update_maintenance);
+
+        #[cfg(feature = "metrics")]
+        let scope = scope
+            .service(admin_controller::commands)
+            .service(admin_controller::caches);
+
+        cfg.service(scope);
+    }
+
+    /// # Summary
+    ///
+    /// Configure the health routes.
     ///
     /// # Arguments
     ///
     /// * `cfg` - The web server configuration.
-    pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    pub fn configure_health_routes(cfg: &mut web::ServiceConfig) {
         cfg.service(
-            web::scope("/api/v1")
-                .service(
-                    web::scope("/permissions")
-                        .service(permission_controller::create_permission)
-                        .service(permission_controller::find_all_permissions)
-                        .service(permission_controller::find_by_id)
-                        .service(permission_controller::update_permission)
-                        .service(permission_controller::delete_permission),
-                )
-                .service(
-                    web::scope("/roles")
-                        .service(role_controller::create)
-                        .service(role_controller::find_all_roles)
-                        .service(role_controller::find_by_id)
-                        .service(role_controller::update)
-                        .service(role_controller::delete),
-                )
-                .service(
-                    web::scope("/users")
-                        .service(user_controller::create)
-                        .service(user_controller::find_all)
-                        .service(user_controller::find_by_id)
-                        .service(user_controller::update)
-                        .service(user_controller::update_password)
-                        .service(user_controller::admin_update_password)
-                        .service(user_controller::delete)
-                        .service(user_controller::delete_self),
-                )
-                .service(
-                    web::scope("/authentication")
-                        .service(authentication_controller::login)
-                        .service(authentication_controller::current_user)
-                        .service(authentication_controller::register),
-                )
-                .service(
-                    web::scope("/audits")
-                        .service(audit::audit_controller::find_all)
-                        .service(audit::audit_controller::find_by_id),
-                ),
+            web::scope("/health")
+                .service(health_controller::health)
+                .service(health_controller::ready),
         );
+    }
+
+    /// # Summary
+    ///
+    /// Configure the well-known routes, such as the JSON Web Key Set that resource servers use to verify tokens.
+    ///
+    /// # Arguments
+    ///
+    /// * `cfg` - The web server configuration.
+    pub fn configure_well_known_routes(cfg: &mut web::ServiceConfig) {
+        cfg.service(web::scope("/.well-known").service(well_known_controller::jwks));
+    }
+
+    /// # Summary
+    ///
+    /// Configure the route that serves the OpenAPI specification.
+    ///
+    /// # Arguments
+    ///
+    /// * `cfg` - The web server configuration.
+    pub fn configure_open_api_routes(cfg: &mut web::ServiceConfig) {
+        cfg.service(web::scope("/api-docs").service(open_api_controller::openapi_json));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::open_api::ApiDoc;
+    use crate::web::controller::admin::admin_controller::method_name;
+
+    #[test]
+    fn every_api_route_has_permission_entry() {
+        for (path, item) in ApiDoc::spec().paths.paths.iter() {
+            if !path.starts_with("/api/v1/") {
+                continue;
+            }
+
+            for path_item_type in item.operations.keys() {
+                let method = method_name(path_item_type);
+                let protected = ROUTE_PERMISSIONS
+                    .iter()
+                    .any(|r| r.method == method && r.path == path);
+                let public = PUBLIC_ROUTES
+                    .iter()
+                    .any(|r| r.method == method && r.path == path);
+
+                assert!(
+                    protected ^ public,
+                    "{} {} must have exactly one entry in ROUTE_PERMISSIONS or PUBLIC_ROUTES",
+                    method,
+                    path
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn every_entry_is_registered_route() {
+        let routes = ROUTE_PERMISSIONS
+            .iter()
+            .map(|r| (r.method, r.path))
+            .chain(PUBLIC_ROUTES.iter().map(|r| (r.method, r.path)));
+
+        for (method, path) in routes {
+            // The routes of disabled features are removed from the specification
+            if !cfg!(feature = "audit")
+                && (path.starts_with("/api/v1/audits") || path.ends_with("/audit/"))
+            {
+                continue;
+            }
+            if !cfg!(feature = "metrics")
+                && (path == "/api/v1/admin/commands/" || path == "/api/v1/admin/caches/")
+            {
+                continue;
+            }
+
+            let registered = ApiDoc::spec()
+                .paths
+                .paths
+                .get(path)
+                .is_some_and(|item| item.operations.keys().any(|t| method_name(t) == method));
 
-        cfg.service(web::scope("/health").service(health_controller::health));
+            assert!(registered, "{} {} is not a registered route", method, path);
+        }
     }
 }
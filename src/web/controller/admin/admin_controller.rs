@@ -0,0 +1,326 @@
+use crate::components::open_api::ApiDoc;
+use crate::configuration::config::Config;
+use crate::errors::internal_server_error::InternalServerError;
+use crate::errors::into_http_response::IntoHttpResponse;
+use crate::web::dto::backup::backup_dto::{BackupDto, BackupQuery, RestoreSummaryDto};
+use crate::web::dto::maintenance::maintenance_dto::{MaintenanceDto, PurgeResultDto};
+use crate::web::dto::metrics::cache_metrics_dto::CacheMetricsDto;
+use crate::web::dto::metrics::command_metrics_dto::CommandMetricsDto;
+use crate::web::dto::route::route_dto::RouteDto;
+use crate::web::dto::selfcheck::selfcheck_dto::SelfCheckDto;
+use crate::web::extractors::sanitized_json::SanitizedJson;
+use actix_web::{get, post, put, web, HttpResponse};
+use log::error;
+use utoipa::openapi::PathItemType;
+
+/// The maximum size of a backup that can be restored, in bytes.
+pub const MAX_BACKUP_SIZE: usize = 16 * 1024 * 1024;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/backup/",
+    params(
+        ("includePasswords" = Option<bool>, Query, description = "Whether the password hashes of the Users should be part of the backup", nullable = true),
+    ),
+    responses(
+        (status = 200, description = "OK", body = BackupDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Administration",
+    security(
+        ("Token" = [])
+    )
+)]
+#[get("/backup/")]
+pub async fn backup(query: web::Query<BackupQuery>, pool: web::Data<Config>) -> HttpResponse {
+    let include_passwords = query.include_passwords.unwrap_or(false);
+
+    match pool
+        .services
+        .backup_service
+        .backup(include_passwords, &pool.database)
+        .await
+    {
+        Ok(data) => HttpResponse::Ok().json(BackupDto { data }),
+        Err(e) => {
+            error!("Error while creating backup: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/restore/",
+    request_body = BackupDto,
+    responses(
+        (status = 200, description = "OK", body = RestoreSummaryDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Administration",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/restore/")]
+pub async fn restore(backup: SanitizedJson<BackupDto>, pool: web::Data<Config>) -> HttpResponse {
+    match pool
+        .services
+        .backup_service
+        .restore(&backup.data, &pool.database)
+        .await
+    {
+        Ok(summary) => {
+            // The restored Users bypass the UserService, so their UserSummaries are rebuilt here
+            if let Err(e) = pool
+                .services
+                .user_service
+                .rebuild_summaries(&pool.database)
+                .await
+            {
+                error!("Error while rebuilding UserSummaries: {}", e);
+            }
+
+            // The restored Permissions and Roles bypass their services as well
+            pool.services.permission_service.clear_cache();
+            pool.services.role_service.clear_cache();
+
+            HttpResponse::Ok().json(RestoreSummaryDto::from(summary))
+        }
+        Err(e) => {
+            error!("Error while restoring backup: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/routes/",
+    responses(
+        (status = 200, description = "OK", body = Vec<RouteDto>),
+    ),
+    tag = "Administration",
+    security(
+        ("Token" = [])
+    )
+)]
+#[get("/routes/")]
+pub async fn routes(pool: web::Data<Config>) -> HttpResponse {
+    let mut routes: Vec<RouteDto> = vec![];
+
+    for (path, item) in ApiDoc::spec().paths.paths.iter() {
+        for (path_item_type, operation) in item.operations.iter() {
+            let method = method_name(path_item_type);
+            let permission = pool.permission_config.required_permission(method, path);
+            let tags = operation.tags.clone().unwrap_or_default();
+
+            routes.push(RouteDto::new(method, path, permission, tags));
+        }
+    }
+
+    routes.sort_by(|a, b| a.path.cmp(&b.path).then_with(|| a.method.cmp(&b.method)));
+
+    HttpResponse::Ok().json(routes)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/selfcheck/",
+    responses(
+        (status = 200, description = "OK", body = SelfCheckDto),
+    ),
+    tag = "Administration",
+    security(
+        ("Token" = [])
+    )
+)]
+#[get("/selfcheck/")]
+pub async fn selfcheck(pool: web::Data<Config>) -> HttpResponse {
+    let report = pool.self_check.run(&pool).await;
+
+    HttpResponse::Ok().json(SelfCheckDto::from(report))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/commands/",
+    responses(
+        (status = 200, description = "OK", body = Vec<CommandMetricsDto>),
+        (status = 404, description = "Not Found"),
+    ),
+    tag = "Administration",
+    security(
+        ("Token" = [])
+    )
+)]
+#[get("/commands/")]
+pub async fn commands(pool: web::Data<Config>) -> HttpResponse {
+    // The metrics are only recorded if DB_COMMAND_MONITORING is enabled
+    let command_monitor = match &pool.command_monitor {
+        Some(m) => m,
+        None => return HttpResponse::NotFound().finish(),
+    };
+
+    let metrics: Vec<CommandMetricsDto> = command_monitor
+        .snapshot()
+        .into_iter()
+        .map(CommandMetricsDto::from)
+        .collect();
+
+    HttpResponse::Ok().json(metrics)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/caches/",
+    responses(
+        (status = 200, description = "OK", body = Vec<CacheMetricsDto>),
+        (status = 404, description = "Not Found"),
+    ),
+    tag = "Administration",
+    security(
+        ("Token" = [])
+    )
+)]
+#[get("/caches/")]
+pub async fn caches(pool: web::Data<Config>) -> HttpResponse {
+    // The caches only exist if CACHE_TTL is set
+    let metrics: Vec<CacheMetricsDto> = [
+        pool.services.permission_service.cache_stats(),
+        pool.services.role_service.cache_stats(),
+    ]
+    .into_iter()
+    .flatten()
+    .map(CacheMetricsDto::from)
+    .collect();
+
+    if metrics.is_empty() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    HttpResponse::Ok().json(metrics)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/purge/",
+    responses(
+        (status = 200, description = "OK", body = Vec<PurgeResultDto>),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Administration",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/purge/")]
+pub async fn purge(pool: web::Data<Config>) -> HttpResponse {
+    match pool.purge_expired().await {
+        Ok(purged) => HttpResponse::Ok().json(
+            purged
+                .into_iter()
+                .map(|(c, d)| PurgeResultDto::new(c, d))
+                .collect::<Vec<PurgeResultDto>>(),
+        ),
+        Err(e) => {
+            error!("Error while purging expired records: {}", e);
+            HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to purge expired records"))
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/indexes/",
+    responses(
+        (status = 204, description = "No Content"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Administration",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/indexes/")]
+pub async fn indexes(pool: web::Data<Config>) -> HttpResponse {
+    match pool.create_indexes().await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(e) => {
+            error!("Error while rebuilding indexes: {}", e);
+            HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to rebuild indexes"))
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/maintenance/",
+    responses(
+        (status = 200, description = "OK", body = MaintenanceDto),
+    ),
+    tag = "Administration",
+    security(
+        ("Token" = [])
+    )
+)]
+#[get("/maintenance/")]
+pub async fn maintenance(pool: web::Data<Config>) -> HttpResponse {
+    HttpResponse::Ok().json(MaintenanceDto {
+        enabled: pool.maintenance_mode.is_enabled(),
+    })
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/maintenance/",
+    request_body = MaintenanceDto,
+    responses(
+        (status = 200, description = "OK", body = MaintenanceDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+    ),
+    tag = "Administration",
+    security(
+        ("Token" = [])
+    )
+)]
+#[put("/maintenance/")]
+pub async fn update_maintenance(
+    maintenance_dto: web::Json<MaintenanceDto>,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    pool.maintenance_mode.set_enabled(maintenance_dto.enabled);
+
+    HttpResponse::Ok().json(MaintenanceDto {
+        enabled: pool.maintenance_mode.is_enabled(),
+    })
+}
+
+/// # Summary
+///
+/// Get the HTTP method that corresponds to a PathItemType.
+///
+/// # Arguments
+///
+/// * `path_item_type` - The PathItemType.
+///
+/// # Returns
+///
+/// * `&'static str` - The HTTP method.
+pub(crate) fn method_name(path_item_type: &PathItemType) -> &'static str {
+    match path_item_type {
+        PathItemType::Get => "GET",
+        PathItemType::Post => "POST",
+        PathItemType::Put => "PUT",
+        PathItemType::Delete => "DELETE",
+        PathItemType::Options => "OPTIONS",
+        PathItemType::Head => "HEAD",
+        PathItemType::Patch => "PATCH",
+        PathItemType::Trace => "TRACE",
+        PathItemType::Connect => "CONNECT",
+    }
+}
@@ -0,0 +1,410 @@
+use crate::configuration::config::Config;
+use crate::errors::bad_request::BadRequest;
+use crate::errors::internal_server_error::InternalServerError;
+use crate::errors::into_http_response::IntoHttpResponse;
+use crate::repository::api_key::api_key_model::ApiKey;
+use crate::repository::permission::permission_repository::Error as PermissionError;
+use crate::web::controller::client::client_controller::parse_permissions;
+use crate::web::controller::role::role_controller::find_permission_dto_from_permissions;
+use crate::web::controller::user::user_controller::parse_date_time;
+use crate::web::dto::api_key::api_key_dto::{ApiKeyDto, ApiKeySecretDto};
+use crate::web::dto::api_key::create_api_key::CreateApiKey;
+use crate::web::dto::api_key::update_api_key::UpdateApiKey;
+use crate::web::dto::search::search_request::SearchRequest;
+use crate::web::extractors::object_id_path::ObjectIdPath;
+use crate::web::extractors::sanitized_json::SanitizedJson;
+use crate::web::extractors::user_id_extractor;
+use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse};
+use log::error;
+
+/// # Summary
+///
+/// Convert an ApiKey into an ApiKeyDto
+///
+/// # Arguments
+///
+/// * `api_key` - An ApiKey
+/// * `config` - A reference to the Config
+///
+/// # Example
+///
+/// ```
+/// let api_key = ApiKey::new("api_key1".to_string(), None, None, None);
+/// let api_key_dto = get_api_key_dto_from_api_key(api_key, &config);
+/// ```
+///
+/// # Returns
+///
+/// * `Result<ApiKeyDto, PermissionError>` - The result containing the ApiKeyDto or the PermissionError that occurred
+pub async fn get_api_key_dto_from_api_key(
+    api_key: ApiKey,
+    config: &Config,
+) -> Result<ApiKeyDto, PermissionError> {
+    let permissions = api_key.permissions.clone();
+    let mut api_key_dto = ApiKeyDto::from(api_key);
+    if let Some(p) = permissions {
+        api_key_dto.permissions = find_permission_dto_from_permissions(p, config).await?;
+    }
+
+    Ok(api_key_dto)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/api-keys/",
+    request_body = CreateApiKey,
+    responses(
+        (status = 200, description = "OK", body = ApiKeySecretDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "API Keys",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/")]
+pub async fn create(
+    api_key_dto: SanitizedJson<CreateApiKey>,
+    pool: web::Data<Config>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(e) => e,
+        None => {
+            error!("Failed to get User ID from token");
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to get User ID from token"));
+        }
+    };
+
+    let api_key_dto = api_key_dto.into_inner();
+    let permissions = match parse_permissions(api_key_dto.permissions, &pool).await {
+        Ok(p) => p,
+        Err(r) => return r,
+    };
+
+    let expires_at = match parse_date_time("expiresAt", api_key_dto.expires_at) {
+        Ok(d) => d,
+        Err(r) => return r,
+    };
+
+    let api_key = ApiKey::new(
+        api_key_dto.name,
+        api_key_dto.description,
+        permissions,
+        expires_at,
+    );
+
+    match pool
+        .services
+        .api_key_service
+        .create(
+            api_key,
+            Some(user_id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok((api_key, key)) => HttpResponse::Ok().json(ApiKeySecretDto {
+            id: api_key.id.to_hex(),
+            key,
+        }),
+        Err(e) => {
+            error!("Error creating ApiKey: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/api-keys/",
+    params(
+        ("limit" = Option<i64>, Query, description = "The limit of API keys to retrieve", nullable = true),
+        ("page" = Option<i64>, Query, description = "The page", nullable = true),
+    ),
+    responses(
+        (status = 200, description = "OK", body = Vec<ApiKeyDto>),
+        (status = 204, description = "No Content"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "API Keys",
+    security(
+        ("Token" = [])
+    )
+)]
+#[get("/")]
+pub async fn find_all(search: web::Query<SearchRequest>, pool: web::Data<Config>) -> HttpResponse {
+    let search = search.into_inner();
+
+    let mut limit = search.limit;
+    let page = search.page;
+
+    let limit_clone = limit.unwrap_or(pool.server_config.max_limit);
+    if limit.is_none()
+        || (limit.is_some() && limit_clone > pool.server_config.max_limit || limit_clone < 1)
+    {
+        limit = Some(pool.server_config.max_limit);
+    }
+
+    let res = match pool
+        .services
+        .api_key_service
+        .find_all(limit, page, &pool.database)
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => {
+            error!("Error while finding all API keys: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    if res.is_empty() {
+        return HttpResponse::NoContent().finish();
+    }
+
+    let mut api_key_dto_list: Vec<ApiKeyDto> = vec![];
+    for c in res {
+        match get_api_key_dto_from_api_key(c, &pool).await {
+            Ok(d) => api_key_dto_list.push(d),
+            Err(e) => {
+                error!("Error converting ApiKey to ApiKeyDto: {}", e);
+                return e.into_http_response();
+            }
+        };
+    }
+
+    HttpResponse::Ok().json(api_key_dto_list)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/api-keys/{id}",
+    params(
+        ("id" = String, Path, description = "The ID of the ApiKey"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = ApiKeyDto),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "API Keys",
+    security(
+        ("Token" = [])
+    )
+)]
+#[get("/{id}")]
+pub async fn find_by_id(path: ObjectIdPath, pool: web::Data<Config>) -> HttpResponse {
+    let res = match pool
+        .services
+        .api_key_service
+        .find_by_id(&path, &pool.database)
+        .await
+    {
+        Ok(Some(d)) => d,
+        Ok(None) => return HttpResponse::NotFound().finish(),
+        Err(e) => {
+            error!("Error finding ApiKey by ID {}: {}", path, e);
+            return e.into_http_response();
+        }
+    };
+
+    match get_api_key_dto_from_api_key(res, &pool).await {
+        Ok(dto) => HttpResponse::Ok().json(dto),
+        Err(e) => {
+            error!("Error converting ApiKey to ApiKeyDto: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/api-keys/{id}",
+    request_body = UpdateApiKey,
+    params(
+        ("id" = String, Path, description = "The ID of the ApiKey"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = ApiKeyDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "API Keys",
+    security(
+        ("Token" = [])
+    )
+)]
+#[put("/{id}")]
+pub async fn update(
+    path: ObjectIdPath,
+    update: SanitizedJson<UpdateApiKey>,
+    pool: web::Data<Config>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let update = update.into_inner();
+
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(e) => e,
+        None => {
+            error!("Failed to get User ID from token");
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to get User ID from token"));
+        }
+    };
+
+    let mut api_key = match pool
+        .services
+        .api_key_service
+        .find_by_id(&path, &pool.database)
+        .await
+    {
+        Ok(Some(d)) => d,
+        Ok(None) => return HttpResponse::NotFound().finish(),
+        Err(e) => {
+            error!("Error finding ApiKey by ID {}: {}", path, e);
+            return e.into_http_response();
+        }
+    };
+
+    api_key.permissions = match parse_permissions(update.permissions, &pool).await {
+        Ok(p) => p,
+        Err(r) => return r,
+    };
+    api_key.expires_at = match parse_date_time("expiresAt", update.expires_at) {
+        Ok(d) => d,
+        Err(r) => return r,
+    };
+    api_key.name = update.name;
+    api_key.description = update.description;
+    api_key.enabled = update.enabled;
+
+    let res = match pool
+        .services
+        .api_key_service
+        .update(
+            api_key,
+            Some(user_id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => {
+            error!("Error updating ApiKey: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    match get_api_key_dto_from_api_key(res, &pool).await {
+        Ok(dto) => HttpResponse::Ok().json(dto),
+        Err(e) => {
+            error!("Error converting ApiKey to ApiKeyDto: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/api-keys/{id}/key/",
+    params(
+        ("id" = String, Path, description = "The ID of the ApiKey"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = ApiKeySecretDto),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "API Keys",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/{id}/key/")]
+pub async fn rotate_key(
+    path: ObjectIdPath,
+    pool: web::Data<Config>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(e) => e,
+        None => {
+            error!("Failed to get User ID from token");
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to get User ID from token"));
+        }
+    };
+
+    match pool
+        .services
+        .api_key_service
+        .rotate_key(
+            &path,
+            Some(user_id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok((api_key, key)) => HttpResponse::Ok().json(ApiKeySecretDto {
+            id: api_key.id.to_hex(),
+            key,
+        }),
+        Err(e) => {
+            error!("Error rotating ApiKey key: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/api-keys/{id}",
+    params(
+        ("id" = String, Path, description = "The ID of the ApiKey"),
+    ),
+    responses(
+        (status = 200, description = "OK"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "API Keys",
+    security(
+        ("Token" = [])
+    )
+)]
+#[delete("/{id}")]
+pub async fn delete(path: ObjectIdPath, pool: web::Data<Config>, req: HttpRequest) -> HttpResponse {
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(e) => e,
+        None => {
+            error!("Failed to get User ID from token");
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to get User ID from token"));
+        }
+    };
+
+    match pool
+        .services
+        .api_key_service
+        .delete(
+            &path,
+            Some(user_id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(_) => HttpResponse::Ok().finish(),
+        Err(e) => {
+            error!("Error deleting ApiKey: {}", e);
+            e.into_http_response()
+        }
+    }
+}
@@ -1,9 +1,13 @@
 use crate::configuration::config::Config;
-use crate::errors::internal_server_error::InternalServerError;
+use crate::errors::into_http_response::IntoHttpResponse;
+use crate::repository::audit::audit_filter::AuditFilter;
+use crate::repository::audit::audit_model::{Action, ResourceType};
+use crate::web::dto::audit::audit_chain_dto::{AuditChainRequest, AuditChainVerificationDto};
 use crate::web::dto::audit::audit_dto::AuditDto;
+use crate::web::dto::audit::audit_search::AuditSearchRequest;
 use crate::web::dto::search::search_request::SearchRequest;
+use crate::web::extractors::object_id_path::ObjectIdPath;
 use actix_web::{get, web, HttpResponse};
-use actix_web_grants::protect;
 use log::error;
 
 #[utoipa::path(
@@ -11,12 +15,15 @@ use log::error;
     path = "/api/v1/audits/",
     params(
         ("text" = Option<String>, Query, description = "The text to search for", nullable = true),
+        ("action" = Option<ActionDto>, Query, description = "The action of the audits", nullable = true),
+        ("resourceType" = Option<ResourceTypeDto>, Query, description = "The type of the resource of the audits", nullable = true),
         ("limit" = Option<i64>, Query, description = "The limit of audits to retrieve", nullable = true),
         ("page" = Option<i64>, Query, description = "The page", nullable = true),
     ),
     responses(
         (status = 200, description = "OK", body = Vec<AuditDto>),
         (status = 204, description = "No Content"),
+        (status = 400, description = "Bad Request"),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
     ),
     tag = "Audits",
@@ -25,8 +32,10 @@ use log::error;
     )
 )]
 #[get("/")]
-#[protect("CAN_READ_AUDIT")]
-pub async fn find_all(search: web::Query<SearchRequest>, pool: web::Data<Config>) -> HttpResponse {
+pub async fn find_all(
+    search: web::Query<AuditSearchRequest>,
+    pool: web::Data<Config>,
+) -> HttpResponse {
     let search = search.into_inner();
 
     let mut limit = search.limit;
@@ -39,33 +48,38 @@ pub async fn find_all(search: web::Query<SearchRequest>, pool: web::Data<Config>
         limit = Some(pool.server_config.max_limit);
     }
 
-    let res = match search.text {
-        Some(t) => match pool
+    let filter = AuditFilter {
+        text: search.text,
+        action: search.action.map(Action::from),
+        resource_type: search.resource_type.map(ResourceType::from),
+    };
+
+    let res = if filter.is_empty() {
+        match pool
             .services
             .audit_service
-            .search(&t, limit, page, &pool.database)
+            .find_all(limit, page, &pool.database)
             .await
         {
             Ok(d) => d,
             Err(e) => {
-                error!("Error while searching for audits: {}", e);
-                return HttpResponse::InternalServerError()
-                    .json(InternalServerError::new(&e.to_string()));
+                error!("Error while finding all audits: {}", e);
+                return e.into_http_response();
             }
-        },
-        None => match pool
+        }
+    } else {
+        match pool
             .services
             .audit_service
-            .find_all(limit, page, &pool.database)
+            .search(&filter, limit, page, &pool.database)
             .await
         {
             Ok(d) => d,
             Err(e) => {
-                error!("Error while finding all audits: {}", e);
-                return HttpResponse::InternalServerError()
-                    .json(InternalServerError::new(&e.to_string()));
+                error!("Error while searching for audits: {}", e);
+                return e.into_http_response();
             }
-        },
+        }
     };
 
     if res.is_empty() {
@@ -77,6 +91,44 @@ pub async fn find_all(search: web::Query<SearchRequest>, pool: web::Data<Config>
     HttpResponse::Ok().json(dto_list)
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/audits/verify/",
+    params(
+        ("partition" = Option<String>, Query, description = "The partition to verify, such as user or role", nullable = true),
+    ),
+    responses(
+        (status = 200, description = "OK", body = Vec<AuditChainVerificationDto>),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Audits",
+    security(
+        ("Token" = [])
+    )
+)]
+#[get("/verify/")]
+pub async fn verify(query: web::Query<AuditChainRequest>, pool: web::Data<Config>) -> HttpResponse {
+    let res = match pool
+        .services
+        .audit_service
+        .verify_chain(query.into_inner().partition, &pool.database)
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => {
+            error!("Error while verifying the audit chain: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    let dto_list = res
+        .into_iter()
+        .map(AuditChainVerificationDto::from)
+        .collect::<Vec<AuditChainVerificationDto>>();
+
+    HttpResponse::Ok().json(dto_list)
+}
+
 #[utoipa::path(
     get,
     path = "/api/v1/audits/{id}",
@@ -94,8 +146,7 @@ pub async fn find_all(search: web::Query<SearchRequest>, pool: web::Data<Config>
     )
 )]
 #[get("/{id}")]
-#[protect("CAN_READ_AUDIT")]
-pub async fn find_by_id(path: web::Path<String>, pool: web::Data<Config>) -> HttpResponse {
+pub async fn find_by_id(path: ObjectIdPath, pool: web::Data<Config>) -> HttpResponse {
     let res = match pool
         .services
         .audit_service
@@ -108,10 +159,68 @@ pub async fn find_by_id(path: web::Path<String>, pool: web::Data<Config>) -> Htt
         },
         Err(e) => {
             error!("Error while finding Audit with ID {}: {}", path, e);
-            return HttpResponse::InternalServerError()
-                .json(InternalServerError::new(&e.to_string()));
+            return e.into_http_response();
         }
     };
 
     HttpResponse::Ok().json(AuditDto::from(res))
 }
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/{id}/audit/",
+    params(
+        ("id" = String, Path, description = "The ID of the User that performed the actions"),
+        ("limit" = Option<i64>, Query, description = "The limit of audits to retrieve", nullable = true),
+        ("page" = Option<i64>, Query, description = "The page", nullable = true),
+    ),
+    responses(
+        (status = 200, description = "OK", body = Vec<AuditDto>),
+        (status = 204, description = "No Content"),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Audits",
+    security(
+        ("Token" = [])
+    )
+)]
+#[get("/users/{id}/audit/")]
+pub async fn find_by_user(
+    path: ObjectIdPath,
+    search: web::Query<SearchRequest>,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    let search = search.into_inner();
+
+    let mut limit = search.limit;
+    let page = search.page;
+
+    let limit_clone = limit.unwrap_or(pool.server_config.max_limit);
+    if limit.is_none()
+        || (limit.is_some() && limit_clone > pool.server_config.max_limit || limit_clone < 1)
+    {
+        limit = Some(pool.server_config.max_limit);
+    }
+
+    let res = match pool
+        .services
+        .audit_service
+        .find_by_user_id(&path, limit, page, &pool.database)
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => {
+            error!("Error while finding audits of User with ID {}: {}", path, e);
+            return e.into_http_response();
+        }
+    };
+
+    if res.is_empty() {
+        return HttpResponse::NoContent().finish();
+    }
+
+    let dto_list = res.into_iter().map(|p| p.into()).collect::<Vec<AuditDto>>();
+
+    HttpResponse::Ok().json(dto_list)
+}
@@ -1,20 +1,68 @@
 use crate::configuration::config::Config;
 use crate::errors::bad_request::BadRequest;
+use crate::errors::captcha_required::CaptchaRequired;
 use crate::errors::internal_server_error::InternalServerError;
+use crate::errors::into_http_response::IntoHttpResponse;
+use crate::errors::mfa_enrollment_required::MfaEnrollmentRequired;
+use crate::errors::mfa_required::MfaRequired;
+use crate::repository::credential::credential_ceremony::CeremonyState;
+use crate::repository::magic_link::magic_link_repository::Error as MagicLinkError;
+use crate::repository::user::user_identity::UserIdentity;
 use crate::repository::user::user_model::User;
-use crate::repository::user::user_repository::Error;
-use crate::services::password::password_service::PasswordService;
-use crate::web::controller::user::user_controller::ConvertError;
+use crate::repository::user::user_source::UserSource;
+use crate::repository::user::user_status::UserStatus;
+use crate::services::client::client_service::generate_secret;
+use crate::services::event::security_event::SecurityEvent;
+use crate::services::login_attempt::login_attempt_service::Error as LoginAttemptError;
+use crate::services::mfa::recovery_code::{hash_recovery_code, is_recovery_code};
+use crate::services::social::external_identity::ExternalIdentity;
+use crate::services::social::social_provider::SocialProvider;
+use crate::services::sso::sso_service::SSO_PROVIDER;
+use crate::web::controller::invitation::invitation_controller::find_invitation;
+use crate::web::controller::user::user_controller::{enqueue_welcome_email, ConvertError};
+use crate::web::dto::authentication::finish_passkey_login::FinishPasskeyLogin;
 use crate::web::dto::authentication::login_request::LoginRequest;
 use crate::web::dto::authentication::login_response::LoginResponse;
+use crate::web::dto::authentication::logout_request::LogoutRequest;
+use crate::web::dto::authentication::magic_link_login_request::MagicLinkLoginRequest;
+use crate::web::dto::authentication::magic_link_request::MagicLinkRequest;
+use crate::web::dto::authentication::mfa_login_request::MfaLoginRequest;
+use crate::web::dto::authentication::passkey_login_request::PasskeyLoginRequest;
+use crate::web::dto::authentication::refresh_request::RefreshRequest;
 use crate::web::dto::authentication::register_request::RegisterRequest;
+use crate::web::dto::authentication::social_callback_query::SocialCallbackQuery;
+use crate::web::dto::authentication::switch_tenant_request::SwitchTenantRequest;
+use crate::web::dto::mfa::passkey_challenge_dto::PasskeyChallengeDto;
+use crate::web::dto::organization::organization_dto::SimpleOrganizationDto;
 use crate::web::dto::permission::permission_dto::SimplePermissionDto;
 use crate::web::dto::role::role_dto::SimpleRoleDto;
-use crate::web::dto::user::user_dto::SimpleUserDto;
-use actix_web::{get, post, web, HttpRequest, HttpResponse};
-use argon2::PasswordHash;
-use log::error;
+use crate::web::dto::user::user_dto::{gravatar_url, SimpleUserDto};
+use crate::web::extractors::jwt_extractor;
+use crate::web::extractors::jwt_extractor::Principal;
+use crate::web::extractors::sanitized_json::SanitizedJson;
+use crate::web::extractors::user_id_extractor;
+use crate::web::middleware::session_resolver::SessionToken;
+use actix_web::cookie::time::Duration as CookieDuration;
+use actix_web::cookie::{Cookie, SameSite};
+use actix_web::http::header;
+use actix_web::{get, post, web, HttpMessage, HttpRequest, HttpResponse};
+use actix_web_grants::authorities::AuthDetails;
+use chrono::{TimeZone, Utc};
+use log::{error, warn};
 use mongodb::bson::oid::ObjectId;
+use std::str::FromStr;
+
+/// The name of the cookie that ties the callback of a social login to the browser that started it.
+const SOCIAL_STATE_COOKIE: &str = "auth_rs_social_state";
+
+/// The path of the social login routes, to which the state cookie is restricted.
+const SOCIAL_PATH: &str = "/api/v1/authentication/social/";
+
+/// The name of the cookie that ties the callback of a single sign-on to the browser that started it.
+const SSO_STATE_COOKIE: &str = "auth_rs_sso_state";
+
+/// The path of the single sign-on routes, to which the state cookie is restricted.
+const SSO_PATH: &str = "/api/v1/authentication/sso/";
 
 /// # Summary
 ///
@@ -41,6 +89,10 @@ pub async fn convert_user_to_simple_dto(
 ) -> Result<SimpleUserDto, ConvertError> {
     let mut user_dto = SimpleUserDto::from(user.clone());
 
+    if pool.email_config.gravatar {
+        user_dto.avatar_url = user.email.as_deref().map(gravatar_url);
+    }
+
     if user.roles.is_some() {
         let mut role_vec: Vec<String> = vec![];
         for r in user.roles.unwrap() {
@@ -64,18 +116,13 @@ pub async fn convert_user_to_simple_dto(
 
             for r in &roles {
                 let mut role_dto = SimpleRoleDto::from(r);
-                if r.permissions.is_some() {
+                if let Some(oid_vec) = r.active_permissions() {
                     let mut permission_dto_list: Vec<SimplePermissionDto> = vec![];
 
-                    let mut p_id_vec: Vec<String> = vec![];
-                    for p in r.permissions.clone().unwrap() {
-                        p_id_vec.push(p.to_hex());
-                    }
-
                     let permissions = match pool
                         .services
                         .permission_service
-                        .find_by_id_vec(p_id_vec, &pool.database)
+                        .find_by_id_vec(oid_vec, &pool.database)
                         .await
                     {
                         Ok(d) => d,
@@ -103,6 +150,364 @@ pub async fn convert_user_to_simple_dto(
     Ok(user_dto)
 }
 
+/// # Summary
+///
+/// Find the names of the permissions that a User holds through its global roles
+///
+/// # Arguments
+///
+/// * `user` - A User
+/// * `pool` - The database connection pool
+///
+/// # Example
+///
+/// ```
+/// let permissions = find_permission_names(&user, &pool).await;
+/// ```
+///
+/// # Returns
+///
+/// * `Result<Vec<String>, ConvertError>` - The sorted names of the permissions or the ConvertError that occurred
+async fn find_permission_names(user: &User, pool: &Config) -> Result<Vec<String>, ConvertError> {
+    let mut permission_names: Vec<String> = vec![];
+
+    if let Some(user_roles) = &user.roles {
+        let role_vec: Vec<String> = user_roles.iter().map(|r| r.to_hex()).collect();
+
+        let roles = match pool
+            .services
+            .role_service
+            .find_in_scope(role_vec, None, &pool.database)
+            .await
+        {
+            Ok(d) => d,
+            Err(e) => return Err(ConvertError::RoleError(e)),
+        };
+
+        for r in roles {
+            if let Some(oid_vec) = r.active_permissions() {
+                let permissions = match pool
+                    .services
+                    .permission_service
+                    .find_by_id_vec(oid_vec, &pool.database)
+                    .await
+                {
+                    Ok(d) => d,
+                    Err(e) => return Err(ConvertError::PermissionError(e)),
+                };
+
+                for p in permissions {
+                    if !permission_names.contains(&p.name) {
+                        permission_names.push(p.name);
+                    }
+                }
+            }
+        }
+    }
+
+    permission_names.sort();
+    Ok(permission_names)
+}
+
+/// # Summary
+///
+/// Find the names of the global roles of a User that require multi-factor authentication
+///
+/// # Arguments
+///
+/// * `user` - A User
+/// * `pool` - The database connection pool
+///
+/// # Example
+///
+/// ```
+/// let mfa_roles = find_mfa_role_names(&user, &pool).await;
+/// ```
+///
+/// # Returns
+///
+/// * `Result<Vec<String>, ConvertError>` - The sorted names of the roles or the ConvertError that occurred
+async fn find_mfa_role_names(user: &User, pool: &Config) -> Result<Vec<String>, ConvertError> {
+    let user_roles = match &user.roles {
+        Some(r) => r,
+        None => return Ok(vec![]),
+    };
+
+    let role_vec: Vec<String> = user_roles.iter().map(|r| r.to_hex()).collect();
+
+    let roles = match pool
+        .services
+        .role_service
+        .find_in_scope(role_vec, None, &pool.database)
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => return Err(ConvertError::RoleError(e)),
+    };
+
+    let mut role_names: Vec<String> = roles
+        .into_iter()
+        .filter(|r| r.require_mfa)
+        .map(|r| r.name)
+        .collect();
+
+    role_names.sort();
+    Ok(role_names)
+}
+
+/// # Summary
+///
+/// Create a LoginResponse that describes when an access token was issued and when it expires. In session mode, the
+/// LoginResponse carries the opaque token of a new Session instead of the JWT token
+///
+/// # Arguments
+///
+/// * `token` - The JWT token
+/// * `pool` - The database connection pool
+///
+/// # Example
+///
+/// ```
+/// let login_response = create_login_response(token, &pool).await;
+/// ```
+///
+/// # Returns
+///
+/// * `Option<LoginResponse>` - The LoginResponse, or None if the claims of the access token could not be read or the Session could not be created
+async fn create_login_response(token: String, pool: &Config) -> Option<LoginResponse> {
+    let claims = match pool.services.jwt_service.decode_jwt_token(&token) {
+        Ok(c) => c,
+        Err(e) => {
+            error!("Failed to decode generated JWT token: {}", e);
+            return None;
+        }
+    };
+
+    let issued_at = Utc.timestamp_opt(claims.issued_at() as i64, 0).single()?;
+    let expires_in = claims.expires_at().saturating_sub(claims.issued_at());
+    let token = pool
+        .services
+        .issue_access_token(token, &pool.database)
+        .await?;
+
+    Some(LoginResponse::new(
+        token,
+        expires_in,
+        issued_at.to_rfc3339(),
+    ))
+}
+
+/// # Summary
+///
+/// Issue a refresh token to a User and add it to a LoginResponse, if refresh tokens are enabled
+///
+/// # Arguments
+///
+/// * `login_response` - The LoginResponse
+/// * `user` - The User that logged in
+/// * `pool` - The database connection pool
+///
+/// # Example
+///
+/// ```
+/// let login_response = add_refresh_token(login_response, &user, &pool).await?;
+/// ```
+///
+/// # Returns
+///
+/// * `Result<LoginResponse, HttpResponse>` - The LoginResponse, or the HttpResponse that should be returned if the refresh token could not be stored
+async fn add_refresh_token(
+    login_response: LoginResponse,
+    user: &User,
+    pool: &Config,
+) -> Result<LoginResponse, HttpResponse> {
+    if !pool.services.jwt_service.refresh_tokens_enabled() {
+        return Ok(login_response);
+    }
+
+    match pool
+        .services
+        .refresh_token_service
+        .create(
+            user.id,
+            user.token_version,
+            &pool.services.jwt_service,
+            &pool.database,
+        )
+        .await
+    {
+        Ok((_, t)) => Ok(login_response.with_refresh_token(
+            t,
+            pool.services.jwt_service.jwt_config.jwt_refresh_expiration,
+        )),
+        Err(e) => {
+            error!("Failed to create refresh token: {}", e);
+            Err(e.into_http_response())
+        }
+    }
+}
+
+/// # Summary
+///
+/// Find the second factors that a User can complete a login with
+///
+/// # Arguments
+///
+/// * `user` - The User
+/// * `pool` - The database connection pool
+///
+/// # Returns
+///
+/// * `Result<Vec<String>, HttpResponse>` - The second factors, or the HttpResponse that should be returned if they could not be found
+async fn find_mfa_methods(user: &User, pool: &Config) -> Result<Vec<String>, HttpResponse> {
+    let mut methods: Vec<String> = vec![];
+
+    if pool.services.totp_service.is_enabled() && user.totp.as_ref().map_or(false, |t| t.confirmed)
+    {
+        methods.push(String::from("totp"));
+    }
+
+    if pool.services.webauthn_service.is_enabled() {
+        match pool
+            .services
+            .credential_service
+            .find_by_user_id(user.id, &pool.database)
+            .await
+        {
+            Ok(c) if !c.is_empty() => methods.push(String::from("passkey")),
+            Ok(_) => {}
+            Err(e) => {
+                error!("Failed to find the passkeys of the user: {}", e);
+                return Err(e.into_http_response());
+            }
+        }
+    }
+
+    // Recovery codes replace a second factor that was lost, so they are not a second factor on their own
+    if !methods.is_empty() && !user.recovery_codes.is_empty() {
+        methods.push(String::from("recoveryCode"));
+    }
+
+    Ok(methods)
+}
+
+/// # Summary
+///
+/// Check whether a User whose credentials were verified may log in, and issue the tokens of the User. A User that
+/// enrolled a TOTP authenticator or registered a passkey receives an MFA challenge instead, unless its second factor
+/// was already verified
+///
+/// # Arguments
+///
+/// * `user` - The User that logged in
+/// * `mfa_verified` - Whether the second factor of the User was verified
+/// * `source_ip` - The IP address that the login originated from
+/// * `pool` - The database connection pool
+///
+/// # Example
+///
+/// ```
+/// let login_response = complete_login(&user, false, source_ip, &pool).await?;
+/// ```
+///
+/// # Returns
+///
+/// * `Result<LoginResponse, HttpResponse>` - The LoginResponse, or the HttpResponse that should be returned if the User may not log in or the tokens could not be issued
+async fn complete_login(
+    user: &User,
+    mfa_verified: bool,
+    source_ip: Option<String>,
+    pool: &Config,
+) -> Result<LoginResponse, HttpResponse> {
+    let login_failed = |reason: &str| SecurityEvent::LoginFailed {
+        username: user.username.clone(),
+        reason: String::from(reason),
+        source_ip: source_ip.clone(),
+    };
+
+    if !user.is_active() {
+        pool.services
+            .publish_security_event(login_failed(&format!("user is {}", user.status)))
+            .await;
+        return Err(HttpResponse::Forbidden().finish());
+    }
+
+    if !user.mfa_enrolled {
+        match find_mfa_role_names(user, pool).await {
+            Ok(r) if !r.is_empty() => {
+                pool.services
+                    .publish_security_event(login_failed(
+                        "multi-factor authentication is not enrolled",
+                    ))
+                    .await;
+                return Err(HttpResponse::Forbidden().json(MfaEnrollmentRequired::new(r)));
+            }
+            Ok(_) => {}
+            Err(e) => {
+                error!(
+                    "Failed to find the roles of the user that require MFA: {}",
+                    e
+                );
+                return Err(e.into_http_response());
+            }
+        }
+    }
+
+    if !mfa_verified {
+        let methods = find_mfa_methods(user, pool).await?;
+
+        if !methods.is_empty() {
+            let expiration = pool.services.totp_service.challenge_expiration;
+            return match pool.services.jwt_service.generate_mfa_challenge(
+                &user.id.to_hex(),
+                user.token_version,
+                expiration,
+            ) {
+                Some(c) => {
+                    Err(HttpResponse::Unauthorized().json(MfaRequired::new(c, expiration, methods)))
+                }
+                None => Err(HttpResponse::InternalServerError()
+                    .json(InternalServerError::new("Failed to generate MFA challenge"))),
+            };
+        }
+    }
+
+    let token = pool
+        .services
+        .jwt_service
+        .generate_jwt_token(&user.id.to_hex(), user.token_version);
+
+    let login_response = match token {
+        Some(t) => create_login_response(t, pool).await,
+        None => None,
+    };
+
+    let login_response = match login_response {
+        Some(r) => r,
+        None => {
+            return Err(HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to generate JWT token")))
+        }
+    };
+
+    let login_response = add_refresh_token(login_response, user, pool).await?;
+
+    pool.services
+        .user_service
+        .record_login(user.id, &pool.database)
+        .await;
+
+    pool.services
+        .publish_security_event(SecurityEvent::LoginSucceeded {
+            user_id: user.id.to_hex(),
+            username: user.username.clone(),
+            source_ip,
+        })
+        .await;
+
+    Ok(login_response)
+}
+
 #[utoipa::path(
     post,
     path = "/api/v1/authentication/login/",
@@ -110,33 +515,68 @@ pub async fn convert_user_to_simple_dto(
     responses(
         (status = 200, description = "OK", body = LoginResponse),
         (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 401, description = "Unauthorized", body = MfaRequired),
+        (status = 403, description = "Forbidden", body = MfaEnrollmentRequired),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
     ),
     tag = "Authentication",
 )]
 #[post("/login/")]
 pub async fn login(
-    login_request: web::Json<LoginRequest>,
+    login_request: SanitizedJson<LoginRequest>,
+    req: HttpRequest,
     pool: web::Data<Config>,
 ) -> HttpResponse {
     let login_request = login_request.into_inner();
+    let source_ip = req.connection_info().realip_remote_addr().map(String::from);
+    let login_failed = |reason: &str| SecurityEvent::LoginFailed {
+        username: login_request.username.clone(),
+        reason: String::from(reason),
+        source_ip: source_ip.clone(),
+    };
 
-    if login_request.username.is_empty() {
-        return HttpResponse::BadRequest().json("Username is required");
-    }
-    if login_request.password.is_empty() {
-        return HttpResponse::BadRequest().json("Password is required");
+    let login_attempt_service = &pool.services.login_attempt_service;
+    // Repeated failures require a CAPTCHA instead of locking the account, so that nobody can lock others out
+    if let Err(e) = login_attempt_service
+        .check(
+            &login_request.username,
+            source_ip.as_deref(),
+            login_request.captcha.as_deref(),
+        )
+        .await
+    {
+        return match e {
+            LoginAttemptError::Request(e) => {
+                error!("Failed to verify CAPTCHA: {}", e);
+                HttpResponse::InternalServerError()
+                    .json(InternalServerError::new("Failed to verify CAPTCHA"))
+            }
+            e => {
+                pool.services
+                    .publish_security_event(login_failed("captcha required"))
+                    .await;
+                HttpResponse::BadRequest().json(CaptchaRequired::new(&e.to_string()))
+            }
+        };
     }
 
     let user = match pool
         .services
         .user_service
-        .find_by_username(&login_request.username, &pool.database)
+        .find_by_username_federated(
+            &login_request.username,
+            &pool.database,
+            &pool.services.role_service,
+        )
         .await
     {
         Ok(u) => match u {
             Some(user) => user,
             None => {
+                login_attempt_service.record_failure(&login_request.username, source_ip.as_deref());
+                pool.services
+                    .publish_security_event(login_failed("unknown user"))
+                    .await;
                 return HttpResponse::BadRequest().finish();
             }
         },
@@ -146,77 +586,1018 @@ pub async fn login(
         }
     };
 
-    let parsed_hash = match PasswordHash::new(&user.password) {
-        Ok(h) => h,
-        Err(e) => {
-            error!("Failed to parse password hash: {}", e);
-            return HttpResponse::InternalServerError()
-                .json(InternalServerError::new("Failed to parse password hash"));
+    let password_valid = if user.password.is_empty() {
+        match pool
+            .services
+            .user_service
+            .verify_external_password(&login_request.username, &login_request.password)
+            .await
+        {
+            Ok(v) => v,
+            Err(e) => {
+                error!(
+                    "Failed to verify password against the external user store: {}",
+                    e
+                );
+                return HttpResponse::InternalServerError()
+                    .json(InternalServerError::new("Failed to verify password"));
+            }
+        }
+    } else {
+        match pool
+            .services
+            .password_service
+            .verify_password(&login_request.password, &user.password)
+        {
+            Ok(v) => v,
+            Err(e) => {
+                error!("Failed to parse password hash: {}", e);
+                return HttpResponse::InternalServerError()
+                    .json(InternalServerError::new("Failed to parse password hash"));
+            }
         }
     };
 
-    if !PasswordService::verify_password(&login_request.password, &parsed_hash) {
+    if !password_valid {
+        login_attempt_service.record_failure(&login_request.username, source_ip.as_deref());
+        pool.services
+            .publish_security_event(login_failed("invalid password"))
+            .await;
         return HttpResponse::BadRequest().finish();
     }
 
-    match pool
-        .services
-        .jwt_service
-        .generate_jwt_token(&user.id.to_hex())
-    {
-        Some(t) => HttpResponse::Ok().json(LoginResponse::new(t)),
-        None => HttpResponse::InternalServerError()
-            .json(InternalServerError::new("Failed to generate JWT token")),
+    login_attempt_service.record_success(&login_request.username);
+
+    // The status is only checked after the password, so that it is not revealed to callers without valid credentials
+    let login_response = match complete_login(&user, false, source_ip, &pool).await {
+        Ok(r) => r,
+        Err(e) => return e,
+    };
+
+    if !login_request.include_permissions {
+        return HttpResponse::Ok().json(login_response);
+    }
+
+    match find_permission_names(&user, &pool).await {
+        Ok(p) => HttpResponse::Ok().json(login_response.with_permissions(p)),
+        Err(e) => {
+            error!("Failed to find the permissions of the user: {}", e);
+            e.into_http_response()
+        }
     }
 }
 
 #[utoipa::path(
     post,
-    path = "/api/v1/authentication/register/",
-    request_body = RegisterRequest,
+    path = "/api/v1/authentication/mfa/",
+    request_body = MfaLoginRequest,
     responses(
-        (status = 200, description = "OK"),
+        (status = 200, description = "OK", body = LoginResponse),
         (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 403, description = "Forbidden", body = MfaEnrollmentRequired),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
     ),
     tag = "Authentication",
 )]
-#[post("/register/")]
-pub async fn register(
-    register_request: web::Json<RegisterRequest>,
+#[post("/mfa/")]
+pub async fn verify_mfa(
+    mfa_request: SanitizedJson<MfaLoginRequest>,
+    req: HttpRequest,
     pool: web::Data<Config>,
 ) -> HttpResponse {
-    let register_request = register_request.into_inner();
-
-    if register_request.username.is_empty() {
-        return HttpResponse::BadRequest().json(BadRequest::new("Empty usernames are not allowed"));
-    }
+    let mfa_request = mfa_request.into_inner();
+    let source_ip = req.connection_info().realip_remote_addr().map(String::from);
 
-    if register_request.password.is_empty() {
-        return HttpResponse::BadRequest().json(BadRequest::new("Empty passwords are not allowed"));
-    }
+    let claims = match pool
+        .services
+        .jwt_service
+        .decode_mfa_challenge(&mfa_request.challenge_token)
+    {
+        Ok(c) => c,
+        Err(e) => {
+            error!("Failed to verify MFA challenge: {}", e);
+            return HttpResponse::BadRequest().json(BadRequest::new(
+                "The MFA challenge is invalid or has expired",
+            ));
+        }
+    };
 
-    let default_roles: Option<Vec<ObjectId>> = match pool
+    let user = match pool
         .services
-        .role_service
-        .find_by_name("DEFAULT", &pool.database)
+        .user_service
+        .find_by_id(claims.subject(), &pool.database)
         .await
     {
-        Ok(r) => match r {
-            Some(role) => Some(vec![role.id]),
-            None => None,
-        },
+        Ok(Some(u)) => u,
+        Ok(None) => return HttpResponse::BadRequest().finish(),
         Err(e) => {
-            error!("Failed to find default role: {}", e);
-            return HttpResponse::InternalServerError()
-                .json(InternalServerError::new(&e.to_string()));
+            error!("Failed to find user by ID: {}", e);
+            return e.into_http_response();
         }
     };
 
-    let mut user = User::from(register_request);
+    // A challenge that was issued before the sessions of the User were revoked cannot be completed anymore
+    if user.is_token_revoked(claims.token_version()) {
+        return HttpResponse::BadRequest().json(BadRequest::new(
+            "The MFA challenge is invalid or has expired",
+        ));
+    }
+
+    let login_failed = |reason: &str| SecurityEvent::LoginFailed {
+        username: user.username.clone(),
+        reason: String::from(reason),
+        source_ip: source_ip.clone(),
+    };
+
+    // Guessing codes counts towards the same CAPTCHA threshold as guessing passwords
+    let login_attempt_service = &pool.services.login_attempt_service;
+    if let Err(e) = login_attempt_service
+        .check(
+            &user.username,
+            source_ip.as_deref(),
+            mfa_request.captcha.as_deref(),
+        )
+        .await
+    {
+        return match e {
+            LoginAttemptError::Request(e) => {
+                error!("Failed to verify CAPTCHA: {}", e);
+                HttpResponse::InternalServerError()
+                    .json(InternalServerError::new("Failed to verify CAPTCHA"))
+            }
+            e => {
+                pool.services
+                    .publish_security_event(login_failed("captcha required"))
+                    .await;
+                HttpResponse::BadRequest().json(CaptchaRequired::new(&e.to_string()))
+            }
+        };
+    }
+
+    if is_recovery_code(&mfa_request.code) {
+        // A recovery code is removed when it is used, so that it cannot be used again
+        match pool
+            .services
+            .user_service
+            .use_recovery_code(
+                user.id,
+                &hash_recovery_code(&mfa_request.code),
+                &pool.database,
+            )
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => {
+                login_attempt_service.record_failure(&user.username, source_ip.as_deref());
+                pool.services
+                    .publish_security_event(login_failed("invalid recovery code"))
+                    .await;
+                return HttpResponse::BadRequest().finish();
+            }
+            Err(e) => {
+                error!("Failed to use the recovery code: {}", e);
+                return e.into_http_response();
+            }
+        }
+    } else {
+        let totp_service = &pool.services.totp_service;
+        let totp = match &user.totp {
+            Some(t) if t.confirmed && totp_service.is_enabled() => t,
+            _ => return HttpResponse::BadRequest().finish(),
+        };
+
+        let secret = match totp_service.decrypt(&totp.secret) {
+            Ok(s) => s,
+            Err(e) => {
+                error!("Failed to decrypt TOTP secret: {}", e);
+                return e.into_http_response();
+            }
+        };
+
+        let step = match totp_service.verify(&secret, &mfa_request.code, Utc::now().timestamp()) {
+            Some(s) => s,
+            None => {
+                login_attempt_service.record_failure(&user.username, source_ip.as_deref());
+                pool.services
+                    .publish_security_event(login_failed("invalid mfa code"))
+                    .await;
+                return HttpResponse::BadRequest().finish();
+            }
+        };
+
+        // Every code can only be used once, so that an observed code cannot be replayed within its time step
+        match pool
+            .services
+            .user_service
+            .use_totp_step(user.id, step, &pool.database)
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => {
+                login_attempt_service.record_failure(&user.username, source_ip.as_deref());
+                pool.services
+                    .publish_security_event(login_failed("reused mfa code"))
+                    .await;
+                return HttpResponse::BadRequest().finish();
+            }
+            Err(e) => {
+                error!("Failed to store the used TOTP step: {}", e);
+                return e.into_http_response();
+            }
+        }
+    }
+
+    login_attempt_service.record_success(&user.username);
+
+    let login_response = match complete_login(&user, true, source_ip, &pool).await {
+        Ok(r) => r,
+        Err(e) => return e,
+    };
+
+    if !mfa_request.include_permissions {
+        return HttpResponse::Ok().json(login_response);
+    }
+
+    match find_permission_names(&user, &pool).await {
+        Ok(p) => HttpResponse::Ok().json(login_response.with_permissions(p)),
+        Err(e) => {
+            error!("Failed to find the permissions of the user: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/authentication/passkey/",
+    request_body = PasskeyLoginRequest,
+    responses(
+        (status = 200, description = "OK", body = PasskeyChallengeDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Authentication",
+)]
+#[post("/passkey/")]
+pub async fn start_passkey_login(
+    passkey_request: SanitizedJson<PasskeyLoginRequest>,
+    req: HttpRequest,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    let webauthn_service = &pool.services.webauthn_service;
+    if !webauthn_service.is_enabled() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let passkey_request = passkey_request.into_inner();
+    let source_ip = req.connection_info().realip_remote_addr().map(String::from);
+    let login_failed = |reason: &str| SecurityEvent::LoginFailed {
+        username: passkey_request.username.clone(),
+        reason: String::from(reason),
+        source_ip: source_ip.clone(),
+    };
+
+    let login_attempt_service = &pool.services.login_attempt_service;
+    if let Err(e) = login_attempt_service
+        .check(
+            &passkey_request.username,
+            source_ip.as_deref(),
+            passkey_request.captcha.as_deref(),
+        )
+        .await
+    {
+        return match e {
+            LoginAttemptError::Request(e) => {
+                error!("Failed to verify CAPTCHA: {}", e);
+                HttpResponse::InternalServerError()
+                    .json(InternalServerError::new("Failed to verify CAPTCHA"))
+            }
+            e => {
+                pool.services
+                    .publish_security_event(login_failed("captcha required"))
+                    .await;
+                HttpResponse::BadRequest().json(CaptchaRequired::new(&e.to_string()))
+            }
+        };
+    }
+
+    let user = match pool
+        .services
+        .user_service
+        .find_by_username(&passkey_request.username, &pool.database)
+        .await
+    {
+        Ok(Some(u)) => u,
+        Ok(None) => {
+            login_attempt_service.record_failure(&passkey_request.username, source_ip.as_deref());
+            pool.services
+                .publish_security_event(login_failed("unknown user"))
+                .await;
+            return HttpResponse::BadRequest().finish();
+        }
+        Err(e) => {
+            error!("Failed to find user by username: {}", e);
+            return HttpResponse::BadRequest().finish();
+        }
+    };
+
+    let credentials = match pool
+        .services
+        .credential_service
+        .find_by_user_id(user.id, &pool.database)
+        .await
+    {
+        Ok(c) if !c.is_empty() => c,
+        Ok(_) => {
+            login_attempt_service.record_failure(&passkey_request.username, source_ip.as_deref());
+            pool.services
+                .publish_security_event(login_failed("no passkey registered"))
+                .await;
+            return HttpResponse::BadRequest().finish();
+        }
+        Err(e) => {
+            error!("Failed to find the passkeys of the user: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    let (challenge, state) = match webauthn_service.start_authentication(&credentials) {
+        Ok(r) => r,
+        Err(e) => {
+            error!("Failed to start passkey login: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    let options = match serde_json::to_value(challenge) {
+        Ok(o) => o,
+        Err(e) => {
+            error!("Failed to serialize passkey login options: {}", e);
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to start passkey login"));
+        }
+    };
+
+    match pool
+        .services
+        .credential_service
+        .start_ceremony(
+            user.id,
+            CeremonyState::Authentication { state },
+            webauthn_service.ceremony_expiration,
+            &pool.database,
+        )
+        .await
+    {
+        Ok(id) => HttpResponse::Ok().json(PasskeyChallengeDto {
+            ceremony_id: id.to_hex(),
+            options,
+        }),
+        Err(e) => {
+            error!("Failed to store passkey login: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/authentication/passkey/finish/",
+    request_body = FinishPasskeyLogin,
+    responses(
+        (status = 200, description = "OK", body = LoginResponse),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 403, description = "Forbidden", body = MfaEnrollmentRequired),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Authentication",
+)]
+#[post("/passkey/finish/")]
+pub async fn finish_passkey_login(
+    finish_request: SanitizedJson<FinishPasskeyLogin>,
+    req: HttpRequest,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    let webauthn_service = &pool.services.webauthn_service;
+    if !webauthn_service.is_enabled() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let finish_request = finish_request.into_inner();
+    let source_ip = req.connection_info().realip_remote_addr().map(String::from);
+
+    // The ceremony is removed when it is taken, so that a signed challenge cannot be replayed
+    let ceremony = match pool
+        .services
+        .credential_service
+        .take_ceremony(&finish_request.ceremony_id, &pool.database)
+        .await
+    {
+        Ok(Some(c)) => c,
+        Ok(None) => {
+            return HttpResponse::BadRequest().json(BadRequest::new(
+                "The passkey challenge is invalid or has expired",
+            ))
+        }
+        Err(e) => {
+            error!("Failed to find passkey login: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    let state = match ceremony.state {
+        CeremonyState::Authentication { state } => state,
+        CeremonyState::Registration { .. } => {
+            return HttpResponse::BadRequest().json(BadRequest::new(
+                "The passkey challenge is invalid or has expired",
+            ))
+        }
+    };
+
+    let user = match pool
+        .services
+        .user_service
+        .find_by_id(&ceremony.user_id.to_hex(), &pool.database)
+        .await
+    {
+        Ok(Some(u)) => u,
+        Ok(None) => return HttpResponse::BadRequest().finish(),
+        Err(e) => {
+            error!("Failed to find user by ID: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    let login_failed = |reason: &str| SecurityEvent::LoginFailed {
+        username: user.username.clone(),
+        reason: String::from(reason),
+        source_ip: source_ip.clone(),
+    };
+
+    let login_attempt_service = &pool.services.login_attempt_service;
+    let result = match webauthn_service.finish_authentication(&finish_request.credential, &state) {
+        Ok(r) => r,
+        Err(e) => {
+            warn!("Failed to verify passkey: {}", e);
+            login_attempt_service.record_failure(&user.username, source_ip.as_deref());
+            pool.services
+                .publish_security_event(login_failed("invalid passkey"))
+                .await;
+            return HttpResponse::BadRequest().finish();
+        }
+    };
+
+    let credentials = match pool
+        .services
+        .credential_service
+        .find_by_user_id(user.id, &pool.database)
+        .await
+    {
+        Ok(c) => c,
+        Err(e) => {
+            error!("Failed to find the passkeys of the user: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    // The passkey may have been removed while the login was in progress
+    let mut credential = match credentials
+        .into_iter()
+        .find(|c| c.passkey.cred_id() == result.cred_id())
+    {
+        Some(c) => c,
+        None => return HttpResponse::BadRequest().finish(),
+    };
+
+    // The signature counter is stored, so that a cloned authenticator can be detected
+    credential.passkey.update_credential(&result);
+    if let Err(e) = pool
+        .services
+        .credential_service
+        .update_passkey(credential.id, &credential.passkey, &pool.database)
+        .await
+    {
+        error!("Failed to update passkey: {}", e);
+        return e.into_http_response();
+    }
+
+    login_attempt_service.record_success(&user.username);
+
+    // A passkey verifies both the possession of the authenticator and the user, so it completes the login on its own
+    let login_response = match complete_login(&user, true, source_ip, &pool).await {
+        Ok(r) => r,
+        Err(e) => return e,
+    };
+
+    if !finish_request.include_permissions {
+        return HttpResponse::Ok().json(login_response);
+    }
+
+    match find_permission_names(&user, &pool).await {
+        Ok(p) => HttpResponse::Ok().json(login_response.with_permissions(p)),
+        Err(e) => {
+            error!("Failed to find the permissions of the user: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/authentication/magic-link/",
+    request_body = MagicLinkRequest,
+    responses(
+        (status = 202, description = "Accepted"),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Authentication",
+)]
+#[post("/magic-link/")]
+pub async fn request_magic_link(
+    magic_link_request: SanitizedJson<MagicLinkRequest>,
+    req: HttpRequest,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    let magic_link_config = &pool.magic_link_config;
+    if !magic_link_config.is_enabled() || pool.services.email_sender.is_none() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let magic_link_request = magic_link_request.into_inner();
+    let source_ip = req.connection_info().realip_remote_addr().map(String::from);
+
+    let login_attempt_service = &pool.services.login_attempt_service;
+    if let Err(e) = login_attempt_service
+        .check(
+            &magic_link_request.email,
+            source_ip.as_deref(),
+            magic_link_request.captcha.as_deref(),
+        )
+        .await
+    {
+        return match e {
+            LoginAttemptError::Request(e) => {
+                error!("Failed to verify CAPTCHA: {}", e);
+                HttpResponse::InternalServerError()
+                    .json(InternalServerError::new("Failed to verify CAPTCHA"))
+            }
+            e => HttpResponse::BadRequest().json(CaptchaRequired::new(&e.to_string())),
+        };
+    }
+
+    // Every request counts towards the CAPTCHA threshold, so that the endpoint cannot be used to flood a mailbox
+    login_attempt_service.record_failure(&magic_link_request.email, source_ip.as_deref());
+
+    // The response does not depend on whether the email address is known, so that it cannot be used to find accounts
+    let user = match pool
+        .services
+        .user_service
+        .find_by_email(&magic_link_request.email, &pool.database)
+        .await
+    {
+        Ok(Some(u)) if u.is_active() => u,
+        Ok(_) => return HttpResponse::Accepted().finish(),
+        Err(e) => {
+            error!("Failed to find user by email: {}", e);
+            return HttpResponse::Accepted().finish();
+        }
+    };
+
+    let magic_link = match pool
+        .services
+        .magic_link_service
+        .create(user.id, magic_link_config.expiration, &pool.database)
+        .await
+    {
+        Ok(m) => m,
+        Err(e) => {
+            error!("Failed to create magic link: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    let token = match pool.services.jwt_service.generate_magic_link(
+        &user.id.to_hex(),
+        user.token_version,
+        &magic_link.id.to_hex(),
+        magic_link_config.expiration,
+    ) {
+        Some(t) => t,
+        None => {
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to generate magic link"))
+        }
+    };
+
+    let (subject, body) = magic_link_config.magic_link_email(&user.username, &token);
+    if let Err(e) = pool
+        .services
+        .email_job_service
+        .enqueue(&magic_link_request.email, &subject, &body, &pool.database)
+        .await
+    {
+        error!(
+            "Failed to queue magic link email to User {}: {}",
+            user.id, e
+        );
+        return HttpResponse::InternalServerError()
+            .json(InternalServerError::new("Failed to send magic link"));
+    }
+
+    HttpResponse::Accepted().finish()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/authentication/magic-link/verify/",
+    request_body = MagicLinkLoginRequest,
+    responses(
+        (status = 200, description = "OK", body = LoginResponse),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 401, description = "Unauthorized", body = MfaRequired),
+        (status = 403, description = "Forbidden", body = MfaEnrollmentRequired),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Authentication",
+)]
+#[post("/magic-link/verify/")]
+pub async fn verify_magic_link(
+    magic_link_request: SanitizedJson<MagicLinkLoginRequest>,
+    req: HttpRequest,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    if !pool.magic_link_config.is_enabled() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let magic_link_request = magic_link_request.into_inner();
+    let source_ip = req.connection_info().realip_remote_addr().map(String::from);
+
+    let claims = match pool
+        .services
+        .jwt_service
+        .decode_magic_link(&magic_link_request.token)
+    {
+        Ok(c) => c,
+        Err(e) => {
+            error!("Failed to verify magic link: {}", e);
+            return HttpResponse::BadRequest()
+                .json(BadRequest::new("The magic link is invalid or has expired"));
+        }
+    };
+
+    // The MagicLink is removed when it is redeemed, so that a link can only be used once
+    if let Err(e) = pool
+        .services
+        .magic_link_service
+        .redeem(
+            claims.magic_link().unwrap_or_default(),
+            claims.subject(),
+            &pool.database,
+        )
+        .await
+    {
+        return match e {
+            MagicLinkError::InvalidLink => HttpResponse::BadRequest()
+                .json(BadRequest::new("The magic link is invalid or has expired")),
+            e => {
+                error!("Failed to redeem magic link: {}", e);
+                e.into_http_response()
+            }
+        };
+    }
+
+    let user = match pool
+        .services
+        .user_service
+        .find_by_id(claims.subject(), &pool.database)
+        .await
+    {
+        Ok(Some(u)) => u,
+        Ok(None) => return HttpResponse::BadRequest().finish(),
+        Err(e) => {
+            error!("Failed to find user by ID: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    // A link that was issued before the sessions of the User were revoked cannot be used anymore
+    if user.is_token_revoked(claims.token_version()) {
+        return HttpResponse::BadRequest()
+            .json(BadRequest::new("The magic link is invalid or has expired"));
+    }
+
+    // The link only proves access to the mailbox, so a User that enrolled a second factor still has to provide it
+    let login_response = match complete_login(&user, false, source_ip, &pool).await {
+        Ok(r) => r,
+        Err(e) => return e,
+    };
+
+    if !magic_link_request.include_permissions {
+        return HttpResponse::Ok().json(login_response);
+    }
+
+    match find_permission_names(&user, &pool).await {
+        Ok(p) => HttpResponse::Ok().json(login_response.with_permissions(p)),
+        Err(e) => {
+            error!("Failed to find the permissions of the user: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/authentication/refresh/",
+    request_body = RefreshRequest,
+    responses(
+        (status = 200, description = "OK", body = LoginResponse),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Authentication",
+)]
+#[post("/refresh/")]
+pub async fn refresh(
+    refresh_request: SanitizedJson<RefreshRequest>,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    if !pool.services.jwt_service.refresh_tokens_enabled() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let (refresh_token, token) = match pool
+        .services
+        .refresh_token_service
+        .rotate(
+            &refresh_request.into_inner().refresh_token,
+            &pool.services.jwt_service,
+            &pool.database,
+        )
+        .await
+    {
+        Ok(r) => r,
+        Err(e) => {
+            error!("Failed to rotate refresh token: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    let user = match pool
+        .services
+        .user_service
+        .find_by_id(&refresh_token.user_id.to_hex(), &pool.database)
+        .await
+    {
+        Ok(u) => u,
+        Err(e) => {
+            error!("Failed to find user by ID: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    // Refresh tokens are bound to the token version, so that revoking the sessions of a User also revokes them
+    let user = match user {
+        Some(u) if u.is_active() && u.token_version == refresh_token.token_version => u,
+        _ => {
+            if let Err(e) = pool
+                .services
+                .refresh_token_service
+                .revoke_family(refresh_token.family, &pool.database)
+                .await
+            {
+                error!("Failed to revoke refresh tokens: {}", e);
+            }
+            return HttpResponse::Forbidden().finish();
+        }
+    };
+
+    let access_token = pool
+        .services
+        .jwt_service
+        .generate_jwt_token(&user.id.to_hex(), user.token_version);
+
+    let login_response = match access_token {
+        Some(t) => create_login_response(t, &pool).await,
+        None => None,
+    };
+
+    match login_response {
+        Some(r) => HttpResponse::Ok().json(r.with_refresh_token(
+            token,
+            pool.services.jwt_service.jwt_config.jwt_refresh_expiration,
+        )),
+        None => HttpResponse::InternalServerError()
+            .json(InternalServerError::new("Failed to generate JWT token")),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/authentication/logout/",
+    request_body = LogoutRequest,
+    responses(
+        (status = 200, description = "OK"),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Authentication",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/logout/")]
+pub async fn logout(
+    logout_request: Option<SanitizedJson<LogoutRequest>>,
+    req: HttpRequest,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    let token = match req
+        .headers()
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+    {
+        Some(t) => t,
+        None => return HttpResponse::Forbidden().finish(),
+    };
+
+    let claims = match pool.services.jwt_service.decode_jwt_token(token) {
+        Ok(c) => c,
+        Err(e) => {
+            error!("Failed to verify JWT token: {}", e);
+            return HttpResponse::Forbidden().finish();
+        }
+    };
+
+    let refresh_token = logout_request.and_then(|r| r.into_inner().refresh_token);
+    if let Some(r) = refresh_token {
+        let user_id = match ObjectId::parse_str(claims.subject()) {
+            Ok(u) => u,
+            Err(_) => {
+                return HttpResponse::BadRequest().json(BadRequest::new(
+                    "Refresh tokens can only be revoked by a User",
+                ))
+            }
+        };
+
+        if let Err(e) = pool
+            .services
+            .refresh_token_service
+            .revoke(&r, user_id, &pool.database)
+            .await
+        {
+            error!("Failed to revoke refresh token: {}", e);
+            return e.into_http_response();
+        }
+    }
+
+    // In session mode, deleting the Session rejects its token immediately
+    let session_token = req.extensions().get::<SessionToken>().cloned();
+    if let Some(s) = session_token {
+        if let Err(e) = pool
+            .services
+            .session_service
+            .revoke(&s.0, &pool.database)
+            .await
+        {
+            error!("Failed to revoke Session: {}", e);
+            return e.into_http_response();
+        }
+    }
+
+    // The token stays on the denylist until it would have been rejected anyway, including the tolerated clock skew
+    let expires_at = match Utc
+        .timestamp_opt(
+            claims.expires_at() as i64 + pool.services.jwt_service.jwt_config.jwt_leeway as i64,
+            0,
+        )
+        .single()
+    {
+        Some(e) => e,
+        None => {
+            return HttpResponse::InternalServerError().json(InternalServerError::new(
+                "Failed to read the expiration of the JWT token",
+            ))
+        }
+    };
+
+    match pool
+        .services
+        .revoked_token_service
+        .revoke(token, claims.subject(), expires_at, &pool.database)
+        .await
+    {
+        Ok(_) => HttpResponse::Ok().finish(),
+        Err(e) => {
+            error!("Failed to revoke JWT token: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/authentication/guest/",
+    responses(
+        (status = 200, description = "OK", body = LoginResponse),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Authentication",
+)]
+#[post("/guest/")]
+pub async fn guest(pool: web::Data<Config>) -> HttpResponse {
+    // Deployments that do not offer guest access should not reveal that the route exists
+    if !pool.guest_config.enabled {
+        return HttpResponse::NotFound().finish();
+    }
+
+    // The subject is never a valid ObjectId, so a guest token can not be mistaken for a token of a User
+    let subject = format!("guest:{}", ObjectId::new().to_hex());
+
+    let token = pool
+        .services
+        .jwt_service
+        .generate_guest_token(&subject, pool.guest_config.expiration);
+
+    let login_response = match token {
+        Some(t) => create_login_response(t, &pool).await,
+        None => None,
+    };
+
+    match login_response {
+        Some(r) => {
+            HttpResponse::Ok().json(r.with_permissions(pool.guest_config.permissions.clone()))
+        }
+        None => HttpResponse::InternalServerError()
+            .json(InternalServerError::new("Failed to generate JWT token")),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/authentication/register/",
+    request_body = RegisterRequest,
+    responses(
+        (status = 200, description = "OK"),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Authentication",
+)]
+#[post("/register/")]
+pub async fn register(
+    register_request: SanitizedJson<RegisterRequest>,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    // Deployments that only allow provisioned accounts should not reveal that the route exists
+    if !pool.registration_config.enabled {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let register_request = register_request.into_inner();
+
+    if !pool
+        .registration_config
+        .is_email_allowed(register_request.email.as_deref())
+    {
+        return HttpResponse::BadRequest().json(BadRequest::new(
+            "Registration requires an email address of one of the allowed domains",
+        ));
+    }
+
+    let invitation_token = register_request.invitation.clone();
+
+    let default_roles: Option<Vec<ObjectId>> = match pool
+        .services
+        .role_service
+        .find_by_name("DEFAULT", &pool.database)
+        .await
+    {
+        Ok(r) => match r {
+            Some(role) => Some(vec![role.id]),
+            None => None,
+        },
+        Err(e) => {
+            error!("Failed to find default role: {}", e);
+            return e.into_http_response();
+        }
+    };
 
-    let password_hash = match PasswordService::hash_password(user.password) {
-        Ok(e) => e.to_string(),
+    let mut user = User::from(register_request);
+
+    let password_hash = match pool.services.password_service.hash_password(&user.password) {
+        Ok(e) => e,
         Err(e) => {
             error!("Failed to hash password: {}", e);
             return HttpResponse::InternalServerError()
@@ -224,34 +1605,603 @@ pub async fn register(
         }
     };
 
-    user.password = password_hash;
-    user.roles = default_roles;
+    user.password = password_hash;
+    user.roles = default_roles;
+
+    if let Some(email) = &user.email {
+        match pool
+            .services
+            .role_service
+            .find_by_email_domain(email, &pool.database)
+            .await
+        {
+            Ok(r) => user.grant_roles(&r.iter().map(|r| r.id).collect::<Vec<ObjectId>>()),
+            Err(e) => {
+                error!("Failed to find roles of email domain: {}", e);
+                return e.into_http_response();
+            }
+        }
+    }
+
+    if pool.registration_config.require_approval {
+        user.status = UserStatus::Pending;
+    }
+
+    let user_id = user.id;
+
+    // The Invitation is checked before the User is created, so that an invalid token does not leave an account behind
+    let invitation = match invitation_token {
+        Some(t) => match find_invitation(&t, &user, &pool).await {
+            Ok(i) => Some(i),
+            Err(e) => {
+                error!("Error finding Invitation: {}", e);
+                return e.into_http_response();
+            }
+        },
+        None => None,
+    };
+
+    let user = match pool
+        .services
+        .user_service
+        .create(
+            user,
+            Some(user_id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => {
+            error!("Error creating User: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    enqueue_welcome_email(&user, &pool).await;
+
+    // The account exists at this point, so a failure to join the Organization does not fail the registration.
+    // The Invitation can still be accepted after logging in.
+    if let Some(i) = invitation {
+        let invitation_db = pool
+            .tenant_router
+            .resolve(Some(&i.organization_id.to_hex()));
+
+        if let Err(e) = pool
+            .services
+            .invitation_service
+            .accept(
+                i,
+                user_id,
+                &pool.database,
+                invitation_db,
+                &pool.services.organization_service,
+                &pool.services.audit_service,
+            )
+            .await
+        {
+            error!("Error accepting Invitation of new User: {}", e);
+        }
+    }
+
+    HttpResponse::Ok().finish()
+}
+
+/// # Summary
+///
+/// Build the cookie that holds the state of a social login or a single sign-on
+///
+/// # Arguments
+///
+/// * `name` - The name of the cookie
+/// * `path` - The path of the routes to which the cookie is restricted
+/// * `value` - The value of the cookie, or an empty value to remove the cookie
+/// * `redirect_uri` - The URL of the callback route, the cookie is only sent over HTTPS if the callback is
+///
+/// # Returns
+///
+/// * `Cookie<'static>` - The cookie
+fn state_cookie(
+    name: &'static str,
+    path: &'static str,
+    value: String,
+    redirect_uri: &str,
+) -> Cookie<'static> {
+    Cookie::build(name, value)
+        .path(path)
+        .http_only(true)
+        .secure(redirect_uri.starts_with("https://"))
+        .same_site(SameSite::Lax)
+        .max_age(CookieDuration::minutes(10))
+        .finish()
+}
+
+/// # Summary
+///
+/// Find the User that is linked to the account of an identity provider, or create a User for the account if it is
+/// unknown and automatic creation is enabled
+///
+/// # Arguments
+///
+/// * `identity` - The account at the identity provider
+/// * `source` - The UserSource of a User that is created for the account
+/// * `roles` - The names of the roles that are granted to a User that is created for the account, besides DEFAULT
+/// * `auto_create` - Whether a User may be created for an unknown account
+/// * `pool` - The database connection pool
+///
+/// # Returns
+///
+/// * `Result<Option<User>, HttpResponse>` - The User, None if the account is unknown and no User may be created, or the HttpResponse that should be returned if an error occurred
+async fn find_external_user(
+    identity: &ExternalIdentity,
+    source: UserSource,
+    roles: &[String],
+    auto_create: bool,
+    pool: &Config,
+) -> Result<Option<User>, HttpResponse> {
+    let provider = identity.provider.clone();
+
+    match pool
+        .services
+        .user_service
+        .find_by_identity(&provider, &identity.subject, &pool.database)
+        .await
+    {
+        Ok(Some(u)) => return Ok(Some(u)),
+        Ok(None) => {}
+        Err(e) => {
+            error!("Failed to find User by identity: {}", e);
+            return Err(e.into_http_response());
+        }
+    }
+
+    if !auto_create {
+        return Ok(None);
+    }
+
+    // Auto creation is a form of registration, so it answers the same way as the register route when that is disabled
+    if !pool.registration_config.enabled {
+        return Err(HttpResponse::NotFound().finish());
+    }
+
+    // An email address that the provider did not verify may belong to someone else, so it does not count for the
+    // allowed domains or the roles of its domain and is not stored on the new User
+    let email = identity.email.clone().filter(|_| identity.email_verified);
+
+    if !pool.registration_config.is_email_allowed(email.as_deref()) {
+        return Err(HttpResponse::BadRequest().json(BadRequest::new(
+            "Registration requires an email address of one of the allowed domains",
+        )));
+    }
+
+    // The username of the provider may already be taken by an unrelated local User
+    let username = match pool
+        .services
+        .user_service
+        .find_by_username(&identity.username, &pool.database)
+        .await
+    {
+        Ok(None) => identity.username.clone(),
+        Ok(Some(_)) => format!("{}-{}", provider, identity.subject),
+        Err(e) => {
+            error!("Failed to find User by username: {}", e);
+            return Err(e.into_http_response());
+        }
+    };
+
+    let mut user = User::new(
+        username,
+        email,
+        identity.first_name.clone(),
+        identity.last_name.clone(),
+        String::new(),
+        None,
+        UserStatus::Active,
+    );
+    user.email_verified = user.email.is_some();
+    user.source = Some(source);
+    user.identities = vec![UserIdentity::new(provider, identity.subject.clone())];
+
+    let role_names = std::iter::once("DEFAULT").chain(roles.iter().map(String::as_str));
+    for name in role_names {
+        match pool
+            .services
+            .role_service
+            .find_by_name(name, &pool.database)
+            .await
+        {
+            Ok(Some(r)) => user.grant_roles(&[r.id]),
+            Ok(None) => warn!("Role of {} Users does not exist: {}", provider, name),
+            Err(e) => {
+                error!("Failed to find role of {} Users: {}", provider, e);
+                return Err(e.into_http_response());
+            }
+        }
+    }
+
+    if let Some(email) = &user.email {
+        match pool
+            .services
+            .role_service
+            .find_by_email_domain(email, &pool.database)
+            .await
+        {
+            Ok(r) => user.grant_roles(&r.iter().map(|r| r.id).collect::<Vec<ObjectId>>()),
+            Err(e) => {
+                error!("Failed to find roles of email domain: {}", e);
+                return Err(e.into_http_response());
+            }
+        }
+    }
+
+    if pool.registration_config.require_approval {
+        user.status = UserStatus::Pending;
+    }
+
+    let user_id = user.id;
+    let user = match pool
+        .services
+        .user_service
+        .create(
+            user,
+            Some(user_id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => {
+            error!("Error creating User: {}", e);
+            return Err(e.into_http_response());
+        }
+    };
+
+    enqueue_welcome_email(&user, pool).await;
+
+    Ok(Some(user))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/authentication/social/{provider}/login/",
+    params(
+        ("provider" = String, Path, description = "The identity provider, either google or github"),
+    ),
+    responses(
+        (status = 302, description = "Found"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Authentication",
+)]
+#[get("/social/{provider}/login/")]
+pub async fn social_login(path: web::Path<String>, pool: web::Data<Config>) -> HttpResponse {
+    let provider = match SocialProvider::from_str(&path.into_inner()) {
+        Ok(p) => p,
+        Err(_) => return HttpResponse::NotFound().finish(),
+    };
+
+    let state = generate_secret();
+    let url = match pool
+        .services
+        .social_login_service
+        .authorization_url(provider, &state)
+    {
+        Ok(u) => u,
+        Err(e) => {
+            error!("Failed to start {} login: {}", provider, e);
+            return e.into_http_response();
+        }
+    };
+
+    HttpResponse::Found()
+        .insert_header((header::LOCATION, url))
+        .cookie(state_cookie(
+            SOCIAL_STATE_COOKIE,
+            SOCIAL_PATH,
+            format!("{}:{}", provider, state),
+            &pool
+                .services
+                .social_login_service
+                .social_login_config
+                .redirect_uri,
+        ))
+        .finish()
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/authentication/social/{provider}/callback/",
+    params(
+        ("provider" = String, Path, description = "The identity provider, either google or github"),
+        ("code" = Option<String>, Query, description = "The authorization code that was issued by the identity provider"),
+        ("state" = Option<String>, Query, description = "The state that was passed to the identity provider"),
+        ("error" = Option<String>, Query, description = "The error that was returned by the identity provider"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = LoginResponse),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 401, description = "Unauthorized", body = MfaRequired),
+        (status = 403, description = "Forbidden", body = MfaEnrollmentRequired),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Authentication",
+)]
+#[get("/social/{provider}/callback/")]
+pub async fn social_callback(
+    path: web::Path<String>,
+    query: web::Query<SocialCallbackQuery>,
+    req: HttpRequest,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    let provider = match SocialProvider::from_str(&path.into_inner()) {
+        Ok(p) => p,
+        Err(_) => return HttpResponse::NotFound().finish(),
+    };
+
+    let query = query.into_inner();
+    let source_ip = req.connection_info().realip_remote_addr().map(String::from);
+    let expected_state = req
+        .cookie(SOCIAL_STATE_COOKIE)
+        .map(|c| c.value().to_string());
 
-    let user_id = user.id.clone();
+    let mut response = social_sign_in(provider, query, expected_state, source_ip, &pool).await;
 
-    match pool
+    // The state can only be used once
+    let redirect_uri = &pool
         .services
-        .user_service
-        .create(
-            user,
-            Some(user_id),
-            &pool.database,
-            &pool.services.audit_service,
-        )
+        .social_login_service
+        .social_login_config
+        .redirect_uri;
+    if let Err(e) = response.add_removal_cookie(&state_cookie(
+        SOCIAL_STATE_COOKIE,
+        SOCIAL_PATH,
+        String::new(),
+        redirect_uri,
+    )) {
+        error!("Failed to remove the social login state cookie: {}", e);
+    }
+
+    response
+}
+
+/// # Summary
+///
+/// Complete a social login using the authorization code that the identity provider returned
+///
+/// # Arguments
+///
+/// * `provider` - The identity provider
+/// * `query` - The parameters that the identity provider passed to the callback
+/// * `expected_state` - The state that was stored in the cookie when the login was started
+/// * `source_ip` - The IP address that the login originated from
+/// * `pool` - The database connection pool
+///
+/// # Returns
+///
+/// * `HttpResponse` - The LoginResponse, or the error that occurred
+async fn social_sign_in(
+    provider: SocialProvider,
+    query: SocialCallbackQuery,
+    expected_state: Option<String>,
+    source_ip: Option<String>,
+    pool: &Config,
+) -> HttpResponse {
+    let code = match callback_code(query, expected_state, &provider.to_string()) {
+        Ok(c) => c,
+        Err(e) => return e,
+    };
+
+    let identity = match pool
+        .services
+        .social_login_service
+        .exchange(provider, &code)
         .await
     {
-        Ok(_) => HttpResponse::Ok().finish(),
+        Ok(i) => i,
         Err(e) => {
-            error!("Error creating User: {}", e);
-            match e {
-                Error::UsernameAlreadyTaken | Error::EmailAlreadyTaken | Error::InvalidEmail(_) => {
-                    HttpResponse::BadRequest().json(BadRequest::new(&e.to_string()))
-                }
-                _ => HttpResponse::InternalServerError()
-                    .json(InternalServerError::new(&e.to_string())),
-            }
+            error!("Failed to complete {} login: {}", provider, e);
+            return e.into_http_response();
+        }
+    };
+
+    let social_login_config = &pool.services.social_login_service.social_login_config;
+    external_sign_in(
+        identity,
+        UserSource::Social,
+        &social_login_config.roles,
+        social_login_config.auto_create,
+        source_ip,
+        pool,
+    )
+    .await
+}
+
+/// # Summary
+///
+/// Validate the parameters that an identity provider passed to a callback
+///
+/// # Arguments
+///
+/// * `query` - The parameters that the identity provider passed to the callback
+/// * `expected_state` - The state that was stored in the cookie when the login was started
+/// * `state_prefix` - The prefix of the state in the cookie, which ties the state to the identity provider
+///
+/// # Returns
+///
+/// * `Result<String, HttpResponse>` - The authorization code, or the HttpResponse that should be returned if the parameters are invalid
+fn callback_code(
+    query: SocialCallbackQuery,
+    expected_state: Option<String>,
+    state_prefix: &str,
+) -> Result<String, HttpResponse> {
+    if let Some(e) = query.error {
+        return Err(HttpResponse::BadRequest().json(BadRequest::new(&format!(
+            "The identity provider returned an error: {}",
+            e
+        ))));
+    }
+
+    let (code, state) = match (query.code, query.state) {
+        (Some(c), Some(s)) => (c, s),
+        _ => {
+            return Err(HttpResponse::BadRequest()
+                .json(BadRequest::new("The code and the state are required")))
+        }
+    };
+
+    if expected_state != Some(format!("{}:{}", state_prefix, state)) {
+        return Err(HttpResponse::BadRequest()
+            .json(BadRequest::new("The login state is invalid or has expired")));
+    }
+
+    Ok(code)
+}
+
+/// # Summary
+///
+/// Log in the User that is linked to the account of an identity provider
+///
+/// # Arguments
+///
+/// * `identity` - The account at the identity provider
+/// * `source` - The UserSource of a User that is created for the account
+/// * `roles` - The names of the roles that are granted to a User that is created for the account, besides DEFAULT
+/// * `auto_create` - Whether a User may be created for an unknown account
+/// * `source_ip` - The IP address that the login originated from
+/// * `pool` - The database connection pool
+///
+/// # Returns
+///
+/// * `HttpResponse` - The LoginResponse, or the error that occurred
+async fn external_sign_in(
+    identity: ExternalIdentity,
+    source: UserSource,
+    roles: &[String],
+    auto_create: bool,
+    source_ip: Option<String>,
+    pool: &Config,
+) -> HttpResponse {
+    let user = match find_external_user(&identity, source, roles, auto_create, pool).await {
+        Ok(Some(u)) => u,
+        Ok(None) => {
+            pool.services
+                .publish_security_event(SecurityEvent::LoginFailed {
+                    username: identity.username,
+                    reason: format!("unknown {} account", identity.provider),
+                    source_ip,
+                })
+                .await;
+            return HttpResponse::Forbidden().finish();
+        }
+        Err(e) => return e,
+    };
+
+    match complete_login(&user, false, source_ip, pool).await {
+        Ok(r) => HttpResponse::Ok().json(r),
+        Err(e) => e,
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/authentication/sso/login/",
+    responses(
+        (status = 302, description = "Found"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Authentication",
+)]
+#[get("/sso/login/")]
+pub async fn sso_login(pool: web::Data<Config>) -> HttpResponse {
+    let state = generate_secret();
+    let url = match pool.services.sso_service.authorization_url(&state).await {
+        Ok(u) => u,
+        Err(e) => {
+            error!("Failed to start single sign-on: {}", e);
+            return e.into_http_response();
         }
+    };
+
+    HttpResponse::Found()
+        .insert_header((header::LOCATION, url))
+        .cookie(state_cookie(
+            SSO_STATE_COOKIE,
+            SSO_PATH,
+            format!("{}:{}", SSO_PROVIDER, state),
+            &pool.services.sso_service.sso_config.redirect_uri,
+        ))
+        .finish()
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/authentication/sso/callback/",
+    params(
+        ("code" = Option<String>, Query, description = "The authorization code that was issued by the identity provider"),
+        ("state" = Option<String>, Query, description = "The state that was passed to the identity provider"),
+        ("error" = Option<String>, Query, description = "The error that was returned by the identity provider"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = LoginResponse),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 401, description = "Unauthorized", body = MfaRequired),
+        (status = 403, description = "Forbidden", body = MfaEnrollmentRequired),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Authentication",
+)]
+#[get("/sso/callback/")]
+pub async fn sso_callback(
+    query: web::Query<SocialCallbackQuery>,
+    req: HttpRequest,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    let sso_config = &pool.services.sso_service.sso_config;
+    if !sso_config.is_enabled() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let source_ip = req.connection_info().realip_remote_addr().map(String::from);
+    let expected_state = req.cookie(SSO_STATE_COOKIE).map(|c| c.value().to_string());
+
+    let mut response = match callback_code(query.into_inner(), expected_state, SSO_PROVIDER) {
+        Ok(code) => match pool.services.sso_service.exchange(&code).await {
+            Ok(identity) => {
+                external_sign_in(
+                    identity,
+                    UserSource::Sso,
+                    &sso_config.roles,
+                    sso_config.auto_create,
+                    source_ip,
+                    &pool,
+                )
+                .await
+            }
+            Err(e) => {
+                error!("Failed to complete single sign-on: {}", e);
+                e.into_http_response()
+            }
+        },
+        Err(e) => e,
+    };
+
+    // The state can only be used once
+    if let Err(e) = response.add_removal_cookie(&state_cookie(
+        SSO_STATE_COOKIE,
+        SSO_PATH,
+        String::new(),
+        &sso_config.redirect_uri,
+    )) {
+        error!("Failed to remove the single sign-on state cookie: {}", e);
     }
+
+    response
 }
 
 #[utoipa::path(
@@ -269,49 +2219,361 @@ pub async fn register(
 )]
 #[get("/current/")]
 pub async fn current_user(req: HttpRequest, pool: web::Data<Config>) -> HttpResponse {
-    if let Some(auth_header) = req.headers().get("Authorization") {
-        if let Ok(auth_str) = auth_header.to_str() {
-            if let Some(token) = auth_str.strip_prefix("Bearer ") {
-                let username = match pool.services.jwt_service.verify_jwt_token(token) {
-                    Ok(user) => user,
-                    Err(e) => {
-                        error!("Failed to verify JWT token: {}", e);
-                        return HttpResponse::Forbidden().finish();
-                    }
-                };
+    let token = match jwt_extractor::bearer_token(req.headers()) {
+        Some(t) => t,
+        None => return HttpResponse::Forbidden().finish(),
+    };
 
-                let user = match pool
-                    .services
-                    .user_service
-                    .find_by_id(&username, &pool.database)
-                    .await
-                {
-                    Ok(u) => match u {
-                        Some(user) => user,
-                        None => {
-                            return HttpResponse::Forbidden().finish();
-                        }
-                    },
-                    Err(e) => {
-                        error!("Failed to find user by ID: {}", e);
-                        return HttpResponse::Forbidden().finish();
-                    }
-                };
+    let user = match jwt_extractor::authenticate(token, &pool).await {
+        Some(Principal::User(u, _)) => u,
+        _ => return HttpResponse::Forbidden().finish(),
+    };
+
+    match convert_user_to_simple_dto(user, &pool).await {
+        Ok(u) => HttpResponse::Ok().json(u),
+        Err(e) => {
+            error!("Failed to convert User to SimpleUserDto: {}", e);
+            HttpResponse::Forbidden().finish()
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/authentication/permissions/",
+    responses(
+        (status = 200, description = "OK", body = Vec<String>),
+        (status = 403, description = "Forbidden"),
+    ),
+    tag = "Authentication",
+    security(
+        ("Token" = [])
+    )
+)]
+#[get("/permissions/")]
+pub async fn current_permissions(
+    details: AuthDetails,
+    req: HttpRequest,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    if user_id_extractor::get_user_id_from_token(&req, &pool)
+        .await
+        .is_none()
+    {
+        return HttpResponse::Forbidden().finish();
+    }
+
+    let mut permissions: Vec<String> = details.authorities.iter().cloned().collect();
+    permissions.sort();
+
+    HttpResponse::Ok().json(permissions)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/authentication/tenant/",
+    request_body = SwitchTenantRequest,
+    responses(
+        (status = 200, description = "OK", body = LoginResponse),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Authentication",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/tenant/")]
+pub async fn switch_tenant(
+    switch_request: SanitizedJson<SwitchTenantRequest>,
+    req: HttpRequest,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    let token = match jwt_extractor::bearer_token(req.headers()) {
+        Some(t) => t,
+        None => return HttpResponse::Unauthorized().finish(),
+    };
 
-                if !user.enabled {
-                    return HttpResponse::Forbidden().finish();
+    // The new token inherits the token version of the User, so revoked tokens must not be exchanged for one
+    let user = match jwt_extractor::authenticate(token, &pool).await {
+        Some(Principal::User(u, _)) => u,
+        _ => return HttpResponse::Unauthorized().finish(),
+    };
+
+    // A User that must change its password cannot obtain a new token to get around it
+    if user.must_change_password {
+        return HttpResponse::Forbidden().finish();
+    }
+
+    let token = match switch_request.into_inner().organization_id {
+        Some(o) => {
+            let organization = match pool
+                .services
+                .organization_service
+                .find_by_id(&o, &pool.database)
+                .await
+            {
+                Ok(Some(o)) => o,
+                Ok(None) => return HttpResponse::Forbidden().finish(),
+                Err(e) => {
+                    error!("Failed to find organization by ID: {}", e);
+                    return e.into_http_response();
                 }
+            };
 
-                return match convert_user_to_simple_dto(user, &pool).await {
-                    Ok(u) => HttpResponse::Ok().json(u),
-                    Err(e) => {
-                        error!("Failed to convert User to SimpleUserDto: {}", e);
-                        HttpResponse::Forbidden().finish()
-                    }
-                };
+            // Users can only switch to the Organizations that they are a member of
+            if organization.find_member(&user.id).is_none() {
+                return HttpResponse::Forbidden().finish();
             }
+
+            pool.services.jwt_service.generate_tenant_token(
+                &user.id.to_hex(),
+                user.token_version,
+                &organization.id.to_hex(),
+            )
+        }
+        None => pool
+            .services
+            .jwt_service
+            .generate_jwt_token(&user.id.to_hex(), user.token_version),
+    };
+
+    let login_response = match token {
+        Some(t) => create_login_response(t, &pool).await,
+        None => None,
+    };
+
+    match login_response {
+        Some(r) => HttpResponse::Ok().json(r),
+        None => HttpResponse::InternalServerError()
+            .json(InternalServerError::new("Failed to generate JWT token")),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/authentication/organizations/",
+    responses(
+        (status = 200, description = "OK", body = Vec<SimpleOrganizationDto>),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Authentication",
+    security(
+        ("Token" = [])
+    )
+)]
+#[get("/organizations/")]
+pub async fn current_organizations(req: HttpRequest, pool: web::Data<Config>) -> HttpResponse {
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(u) => u,
+        None => return HttpResponse::Forbidden().finish(),
+    };
+
+    match pool
+        .services
+        .organization_service
+        .find_by_member(user_id, &pool.database)
+        .await
+    {
+        Ok(o) => HttpResponse::Ok().json(
+            o.into_iter()
+                .map(SimpleOrganizationDto::from)
+                .collect::<Vec<SimpleOrganizationDto>>(),
+        ),
+        Err(e) => {
+            error!("Failed to find organizations of user: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configuration::registration_config::RegistrationConfig;
+    use crate::test_support::{self, MockServices};
+    use actix_web::http::StatusCode;
+    use actix_web::{test, App};
+    use std::collections::HashMap;
+
+    async fn switch_to_no_tenant(user: User, token_version: i64) -> StatusCode {
+        let user_id = user.id;
+        let mut mocks = MockServices::new();
+        mocks.with_user(user);
+
+        let config = test_support::config(mocks).await;
+        let token = test_support::bearer(&config, &user_id, token_version);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(config))
+                .service(switch_tenant),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/tenant/")
+            .insert_header((header::AUTHORIZATION, token))
+            .set_json(serde_json::json!({ "organizationId": null }))
+            .to_request();
+
+        test::call_service(&app, req).await.status()
+    }
+
+    #[actix_web::test]
+    async fn switch_tenant_issues_token_to_active_user() {
+        let user = test_support::user();
+
+        assert_eq!(switch_to_no_tenant(user, 0).await, StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn switch_tenant_rejects_revoked_token() {
+        let mut user = test_support::user();
+        user.token_version = 1;
+
+        assert_eq!(switch_to_no_tenant(user, 0).await, StatusCode::UNAUTHORIZED);
+    }
+
+    #[actix_web::test]
+    async fn switch_tenant_rejects_inactive_user() {
+        let mut user = test_support::user();
+        user.status = UserStatus::Suspended;
+
+        assert_eq!(switch_to_no_tenant(user, 0).await, StatusCode::UNAUTHORIZED);
+    }
+
+    #[actix_web::test]
+    async fn switch_tenant_refuses_user_that_must_change_password() {
+        let mut user = test_support::user();
+        user.must_change_password = true;
+
+        assert_eq!(switch_to_no_tenant(user, 0).await, StatusCode::FORBIDDEN);
+    }
+
+    fn identity(email_verified: bool) -> ExternalIdentity {
+        ExternalIdentity {
+            provider: String::from("github"),
+            subject: String::from("583231"),
+            username: String::from("octocat"),
+            email: Some(String::from("octocat@example.com")),
+            email_verified,
+            first_name: None,
+            last_name: None,
         }
     }
 
-    HttpResponse::Forbidden().finish()
+    fn provisioning_mocks() -> MockServices {
+        let mut mocks = MockServices::new();
+        mocks
+            .user_service
+            .expect_find_by_identity()
+            .returning(|_, _, _| Ok(None));
+        mocks
+            .user_service
+            .expect_find_by_username()
+            .returning(|_, _| Ok(None));
+        mocks
+            .user_service
+            .expect_create()
+            .returning(|u, _, _, _| Ok(u));
+        mocks
+            .role_service
+            .expect_find_by_name()
+            .returning(|_, _| Ok(None));
+
+        mocks
+    }
+
+    async fn open_registration_config(mocks: MockServices) -> Config {
+        let mut config = test_support::config(mocks).await;
+        config.registration_config = RegistrationConfig::new(true, false, vec![], HashMap::new());
+
+        config
+    }
+
+    #[actix_web::test]
+    async fn external_user_keeps_verified_email() {
+        let mut mocks = provisioning_mocks();
+        mocks
+            .role_service
+            .expect_find_by_email_domain()
+            .times(1)
+            .returning(|_, _| Ok(vec![]));
+
+        let config = open_registration_config(mocks).await;
+        let user = find_external_user(&identity(true), UserSource::Social, &[], true, &config)
+            .await
+            .ok()
+            .flatten()
+            .unwrap();
+
+        assert_eq!(user.email.as_deref(), Some("octocat@example.com"));
+        assert!(user.email_verified);
+    }
+
+    #[actix_web::test]
+    async fn external_user_does_not_trust_unverified_email() {
+        let mut mocks = provisioning_mocks();
+        mocks.role_service.expect_find_by_email_domain().never();
+
+        let config = open_registration_config(mocks).await;
+        let user = find_external_user(&identity(false), UserSource::Social, &[], true, &config)
+            .await
+            .ok()
+            .flatten()
+            .unwrap();
+
+        assert_eq!(user.email, None);
+        assert!(!user.email_verified);
+    }
+
+    #[actix_web::test]
+    async fn unverified_email_does_not_pass_allowed_domains() {
+        let mocks = provisioning_mocks();
+
+        let mut config = test_support::config(mocks).await;
+        config.registration_config = RegistrationConfig::new(
+            true,
+            false,
+            vec![String::from("example.com")],
+            HashMap::new(),
+        );
+
+        let res =
+            find_external_user(&identity(false), UserSource::Social, &[], true, &config).await;
+
+        assert_eq!(res.err().map(|r| r.status()), Some(StatusCode::BAD_REQUEST));
+    }
+
+    #[actix_web::test]
+    async fn external_user_is_not_created_while_registration_is_disabled() {
+        let mut mocks = MockServices::new();
+        mocks
+            .user_service
+            .expect_find_by_identity()
+            .returning(|_, _, _| Ok(None));
+        mocks.user_service.expect_create().never();
+
+        let config = test_support::config(mocks).await;
+        let res = find_external_user(&identity(true), UserSource::Social, &[], true, &config).await;
+
+        assert_eq!(res.err().map(|r| r.status()), Some(StatusCode::NOT_FOUND));
+    }
+
+    #[actix_web::test]
+    async fn existing_external_user_signs_in_while_registration_is_disabled() {
+        let mut mocks = MockServices::new();
+        mocks
+            .user_service
+            .expect_find_by_identity()
+            .returning(|_, _, _| Ok(Some(test_support::user())));
+
+        let config = test_support::config(mocks).await;
+        let res = find_external_user(&identity(true), UserSource::Social, &[], true, &config).await;
+
+        assert!(matches!(res, Ok(Some(_))));
+    }
 }
@@ -0,0 +1 @@
+pub mod authorization_controller;
@@ -0,0 +1,171 @@
+use crate::configuration::config::Config;
+use crate::errors::bad_request::BadRequest;
+use crate::errors::into_http_response::IntoHttpResponse;
+use crate::web::dto::authorization::authorization_request::BatchAuthorizationRequest;
+use crate::web::dto::authorization::authorization_response::{
+    AuthorizationResult, BatchAuthorizationResponse,
+};
+use actix_web::{post, web, HttpResponse};
+use log::error;
+use mongodb::bson::oid::ObjectId;
+use std::collections::{HashMap, HashSet};
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/authorization/batch/",
+    request_body = BatchAuthorizationRequest,
+    responses(
+        (status = 200, description = "OK", body = BatchAuthorizationResponse),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Authorization",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/batch/")]
+pub async fn batch_authorize(
+    batch_request: web::Json<BatchAuthorizationRequest>,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    let batch_request = batch_request.into_inner();
+
+    if batch_request.checks.is_empty() {
+        return HttpResponse::BadRequest().json(BadRequest::new("At least one check is required"));
+    }
+
+    if batch_request.checks.len() as i64 > pool.server_config.max_limit {
+        return HttpResponse::BadRequest().json(BadRequest::new(&format!(
+            "A maximum of {} checks is allowed",
+            pool.server_config.max_limit
+        )));
+    }
+
+    let mut subject_vec: Vec<String> = vec![];
+    for c in &batch_request.checks {
+        if let Err(e) = ObjectId::parse_str(&c.subject) {
+            return HttpResponse::BadRequest().json(BadRequest::new(&format!(
+                "Invalid subject {}: {}",
+                c.subject, e
+            )));
+        }
+        if !subject_vec.contains(&c.subject) {
+            subject_vec.push(c.subject.clone());
+        }
+    }
+
+    let users = match pool
+        .services
+        .user_service
+        .find_by_id_vec(subject_vec, &pool.database)
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => {
+            error!("Error finding Users by ID vec: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    let mut role_vec: Vec<String> = vec![];
+    for u in &users {
+        if !u.is_active() {
+            continue;
+        }
+        if let Some(roles) = &u.roles {
+            for r in roles {
+                let r = r.to_hex();
+                if !role_vec.contains(&r) {
+                    role_vec.push(r);
+                }
+            }
+        }
+    }
+
+    let roles = match pool
+        .services
+        .role_service
+        .find_by_id_vec(role_vec, &pool.database)
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => {
+            error!("Error finding Roles by ID vec: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    let mut permission_vec: Vec<ObjectId> = vec![];
+    for r in &roles {
+        if let Some(permissions) = &r.permissions {
+            for p in permissions {
+                if !permission_vec.contains(p) {
+                    permission_vec.push(*p);
+                }
+            }
+        }
+    }
+
+    let permissions = match pool
+        .services
+        .permission_service
+        .find_by_id_vec(permission_vec, &pool.database)
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => {
+            error!("Error finding Permissions by ID vec: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    let permission_names: HashMap<ObjectId, String> =
+        permissions.into_iter().map(|p| (p.id, p.name)).collect();
+
+    let mut role_permissions: HashMap<ObjectId, Vec<&String>> = HashMap::new();
+    for r in &roles {
+        let names = r
+            .active_permissions()
+            .iter()
+            .flatten()
+            .filter_map(|p| permission_names.get(p))
+            .collect();
+        role_permissions.insert(r.id, names);
+    }
+
+    let mut subject_permissions: HashMap<ObjectId, HashSet<&String>> = HashMap::new();
+    for u in &users {
+        let mut names: HashSet<&String> = HashSet::new();
+        if u.is_active() {
+            for r in u.roles.iter().flatten() {
+                if let Some(p) = role_permissions.get(r) {
+                    names.extend(p);
+                }
+            }
+        }
+        subject_permissions.insert(u.id, names);
+    }
+
+    let results = batch_request
+        .checks
+        .into_iter()
+        .map(|c| {
+            let allowed = match ObjectId::parse_str(&c.subject)
+                .ok()
+                .and_then(|id| subject_permissions.get(&id))
+            {
+                Some(p) => p.contains(&c.permission),
+                None => false,
+            };
+
+            AuthorizationResult {
+                subject: c.subject,
+                permission: c.permission,
+                allowed,
+            }
+        })
+        .collect();
+
+    HttpResponse::Ok().json(BatchAuthorizationResponse::new(results))
+}
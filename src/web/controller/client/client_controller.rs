@@ -0,0 +1,456 @@
+use crate::configuration::config::Config;
+use crate::errors::bad_request::BadRequest;
+use crate::errors::internal_server_error::InternalServerError;
+use crate::errors::into_http_response::IntoHttpResponse;
+use crate::repository::client::client_model::Client;
+use crate::repository::permission::permission_repository::Error as PermissionError;
+use crate::web::controller::role::role_controller::{
+    find_permission_dto_from_permissions, validate_permissions,
+};
+use crate::web::dto::client::client_dto::{ClientDto, ClientSecretDto};
+use crate::web::dto::client::create_client::CreateClient;
+use crate::web::dto::client::update_client::UpdateClient;
+use crate::web::dto::search::search_request::SearchRequest;
+use crate::web::extractors::object_id_path::ObjectIdPath;
+use crate::web::extractors::sanitized_json::SanitizedJson;
+use crate::web::extractors::user_id_extractor;
+use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse};
+use log::error;
+use mongodb::bson::oid::ObjectId;
+
+/// # Summary
+///
+/// Convert a Client into a ClientDto
+///
+/// # Arguments
+///
+/// * `client` - A Client
+/// * `config` - A reference to the Config
+///
+/// # Example
+///
+/// ```
+/// let client = Client::new("client1".to_string(), None, None);
+/// let client_dto = get_client_dto_from_client(client, &config);
+/// ```
+///
+/// # Returns
+///
+/// * `Result<ClientDto, PermissionError>` - The result containing the ClientDto or the PermissionError that occurred
+pub async fn get_client_dto_from_client(
+    client: Client,
+    config: &Config,
+) -> Result<ClientDto, PermissionError> {
+    let permissions = client.permissions.clone();
+    let mut client_dto = ClientDto::from(client);
+    if let Some(p) = permissions {
+        client_dto.permissions = find_permission_dto_from_permissions(p, config).await?;
+    }
+
+    Ok(client_dto)
+}
+
+/// # Summary
+///
+/// Validate that the permissions exist and convert them into ObjectIds
+///
+/// # Arguments
+///
+/// * `permissions` - An optional vector of permission IDs
+/// * `pool` - The actix-web shared data
+///
+/// # Returns
+///
+/// * `Result<Option<Vec<ObjectId>>, HttpResponse>` - The permission ObjectIds, or the response that describes why they are invalid
+pub async fn parse_permissions(
+    permissions: Option<Vec<String>>,
+    pool: &web::Data<Config>,
+) -> Result<Option<Vec<ObjectId>>, HttpResponse> {
+    match validate_permissions(permissions.clone(), pool).await {
+        Ok(_) => (),
+        Err(e) => {
+            return Err(match e {
+                PermissionError::PermissionNotFound(r) => HttpResponse::BadRequest()
+                    .json(BadRequest::new(&format!("Permission {} not found", r))),
+                _ => {
+                    error!("Error validating permissions: {}", e);
+                    e.into_http_response()
+                }
+            });
+        }
+    };
+
+    Ok(permissions.map(|p| {
+        p.iter()
+            .filter_map(|oid| ObjectId::parse_str(oid).ok())
+            .collect()
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/clients/",
+    request_body = CreateClient,
+    responses(
+        (status = 200, description = "OK", body = ClientSecretDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Clients",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/")]
+pub async fn create(
+    client_dto: SanitizedJson<CreateClient>,
+    pool: web::Data<Config>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(e) => e,
+        None => {
+            error!("Failed to get User ID from token");
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to get User ID from token"));
+        }
+    };
+
+    let client_dto = client_dto.into_inner();
+    let permissions = match parse_permissions(client_dto.permissions, &pool).await {
+        Ok(p) => p,
+        Err(r) => return r,
+    };
+
+    let mut client = Client::new(client_dto.name, client_dto.description, permissions);
+    client.public = client_dto.public.unwrap_or(false);
+    client.redirect_uris = client_dto.redirect_uris.unwrap_or_default();
+    if let Some(g) = client_dto.grant_types {
+        client.grant_types = g;
+    }
+    client.scopes = client_dto.scopes.unwrap_or_default();
+
+    match pool
+        .services
+        .client_service
+        .create(
+            client,
+            Some(user_id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok((client, secret)) => HttpResponse::Ok().json(ClientSecretDto {
+            client_id: client.id.to_hex(),
+            client_secret: secret,
+        }),
+        Err(e) => {
+            error!("Error creating Client: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/clients/",
+    params(
+        ("limit" = Option<i64>, Query, description = "The limit of clients to retrieve", nullable = true),
+        ("page" = Option<i64>, Query, description = "The page", nullable = true),
+    ),
+    responses(
+        (status = 200, description = "OK", body = Vec<ClientDto>),
+        (status = 204, description = "No Content"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Clients",
+    security(
+        ("Token" = [])
+    )
+)]
+#[get("/")]
+pub async fn find_all(search: web::Query<SearchRequest>, pool: web::Data<Config>) -> HttpResponse {
+    let search = search.into_inner();
+
+    let mut limit = search.limit;
+    let page = search.page;
+
+    let limit_clone = limit.unwrap_or(pool.server_config.max_limit);
+    if limit.is_none()
+        || (limit.is_some() && limit_clone > pool.server_config.max_limit || limit_clone < 1)
+    {
+        limit = Some(pool.server_config.max_limit);
+    }
+
+    let res = match pool
+        .services
+        .client_service
+        .find_all(limit, page, &pool.database)
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => {
+            error!("Error while finding all Clients: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    if res.is_empty() {
+        return HttpResponse::NoContent().finish();
+    }
+
+    let mut client_dto_list: Vec<ClientDto> = vec![];
+    for c in res {
+        match get_client_dto_from_client(c, &pool).await {
+            Ok(d) => client_dto_list.push(d),
+            Err(e) => {
+                error!("Error converting Client to ClientDto: {}", e);
+                return e.into_http_response();
+            }
+        };
+    }
+
+    HttpResponse::Ok().json(client_dto_list)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/clients/{id}",
+    params(
+        ("id" = String, Path, description = "The ID of the Client"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = ClientDto),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Clients",
+    security(
+        ("Token" = [])
+    )
+)]
+#[get("/{id}")]
+pub async fn find_by_id(path: ObjectIdPath, pool: web::Data<Config>) -> HttpResponse {
+    let res = match pool
+        .services
+        .client_service
+        .find_by_id(&path, &pool.database)
+        .await
+    {
+        Ok(Some(d)) => d,
+        Ok(None) => return HttpResponse::NotFound().finish(),
+        Err(e) => {
+            error!("Error finding Client by ID {}: {}", path, e);
+            return e.into_http_response();
+        }
+    };
+
+    match get_client_dto_from_client(res, &pool).await {
+        Ok(dto) => HttpResponse::Ok().json(dto),
+        Err(e) => {
+            error!("Error converting Client to ClientDto: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/clients/{id}",
+    request_body = UpdateClient,
+    params(
+        ("id" = String, Path, description = "The ID of the Client"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = ClientDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Clients",
+    security(
+        ("Token" = [])
+    )
+)]
+#[put("/{id}")]
+pub async fn update(
+    path: ObjectIdPath,
+    update: SanitizedJson<UpdateClient>,
+    pool: web::Data<Config>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let update = update.into_inner();
+
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(e) => e,
+        None => {
+            error!("Failed to get User ID from token");
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to get User ID from token"));
+        }
+    };
+
+    let mut client = match pool
+        .services
+        .client_service
+        .find_by_id(&path, &pool.database)
+        .await
+    {
+        Ok(Some(d)) => d,
+        Ok(None) => return HttpResponse::NotFound().finish(),
+        Err(e) => {
+            error!("Error finding Client by ID {}: {}", path, e);
+            return e.into_http_response();
+        }
+    };
+
+    client.permissions = match parse_permissions(update.permissions, &pool).await {
+        Ok(p) => p,
+        Err(r) => return r,
+    };
+    client.name = update.name;
+    client.description = update.description;
+    client.enabled = update.enabled;
+    client.public = update.public;
+    client.redirect_uris = update.redirect_uris;
+    client.grant_types = update.grant_types;
+    client.scopes = update.scopes;
+
+    let res = match pool
+        .services
+        .client_service
+        .update(
+            client,
+            Some(user_id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => {
+            error!("Error updating Client: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    match get_client_dto_from_client(res, &pool).await {
+        Ok(dto) => HttpResponse::Ok().json(dto),
+        Err(e) => {
+            error!("Error converting Client to ClientDto: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/clients/{id}/secret/",
+    params(
+        ("id" = String, Path, description = "The ID of the Client"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = ClientSecretDto),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Clients",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/{id}/secret/")]
+pub async fn rotate_secret(
+    path: ObjectIdPath,
+    pool: web::Data<Config>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(e) => e,
+        None => {
+            error!("Failed to get User ID from token");
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to get User ID from token"));
+        }
+    };
+
+    match pool
+        .services
+        .client_service
+        .rotate_secret(
+            &path,
+            Some(user_id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok((client, secret)) => HttpResponse::Ok().json(ClientSecretDto {
+            client_id: client.id.to_hex(),
+            client_secret: secret,
+        }),
+        Err(e) => {
+            error!("Error rotating Client secret: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/clients/{id}",
+    params(
+        ("id" = String, Path, description = "The ID of the Client"),
+    ),
+    responses(
+        (status = 200, description = "OK"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Clients",
+    security(
+        ("Token" = [])
+    )
+)]
+#[delete("/{id}")]
+pub async fn delete(path: ObjectIdPath, pool: web::Data<Config>, req: HttpRequest) -> HttpResponse {
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(e) => e,
+        None => {
+            error!("Failed to get User ID from token");
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to get User ID from token"));
+        }
+    };
+
+    match pool
+        .services
+        .client_service
+        .delete(
+            &path,
+            Some(user_id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(_) => (),
+        Err(e) => {
+            error!("Error deleting Client: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    // Consents for a deleted Client are meaningless, so they are removed as well
+    if let Err(e) = pool
+        .services
+        .consent_service
+        .delete_by_client_id(path.object_id(), &pool.database)
+        .await
+    {
+        error!("Error deleting Consents of Client {}: {}", path, e);
+    }
+
+    HttpResponse::Ok().finish()
+}
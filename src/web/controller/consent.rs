@@ -0,0 +1 @@
+pub mod consent_controller;
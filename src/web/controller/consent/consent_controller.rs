@@ -0,0 +1,253 @@
+use crate::configuration::config::Config;
+use crate::errors::bad_request::BadRequest;
+use crate::errors::internal_server_error::InternalServerError;
+use crate::errors::into_http_response::IntoHttpResponse;
+use crate::errors::invalid_id::parse_object_id;
+use crate::repository::consent::consent_model::Consent;
+use crate::web::dto::consent::consent_dto::{ConsentCheckDto, ConsentDto};
+use crate::web::dto::consent::grant_consent::{ConsentQuery, GrantConsent};
+use crate::web::extractors::object_id_path::ObjectIdPath;
+use crate::web::extractors::sanitized_json::SanitizedJson;
+use crate::web::extractors::user_id_extractor;
+use actix_web::{delete, get, post, web, HttpRequest, HttpResponse};
+use log::error;
+
+/// # Summary
+///
+/// Convert a Consent into a ConsentDto, including the name of the Client
+///
+/// # Arguments
+///
+/// * `consent` - A Consent
+/// * `config` - A reference to the Config
+///
+/// # Example
+///
+/// ```
+/// let consent_dto = get_consent_dto_from_consent(consent, &config).await;
+/// ```
+///
+/// # Returns
+///
+/// * `ConsentDto` - The ConsentDto. The name of the Client is omitted if it could not be found
+async fn get_consent_dto_from_consent(consent: Consent, config: &Config) -> ConsentDto {
+    let client_id = consent.client_id.to_hex();
+    let mut consent_dto = ConsentDto::from(consent);
+
+    match config
+        .services
+        .client_service
+        .find_by_id(&client_id, &config.database)
+        .await
+    {
+        Ok(c) => consent_dto.client_name = c.map(|c| c.name),
+        Err(e) => error!("Error finding Client by ID {}: {}", client_id, e),
+    }
+
+    consent_dto
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/consents/",
+    responses(
+        (status = 200, description = "OK", body = Vec<ConsentDto>),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Consents",
+    security(
+        ("Token" = [])
+    )
+)]
+#[get("/")]
+pub async fn find_all(req: HttpRequest, pool: web::Data<Config>) -> HttpResponse {
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(e) => e,
+        None => return HttpResponse::Forbidden().finish(),
+    };
+
+    let consents = match pool
+        .services
+        .consent_service
+        .find_by_user_id(user_id, &pool.database)
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => {
+            error!("Error finding Consents: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    let mut consent_dto_list: Vec<ConsentDto> = vec![];
+    for c in consents {
+        consent_dto_list.push(get_consent_dto_from_consent(c, &pool).await);
+    }
+
+    HttpResponse::Ok().json(consent_dto_list)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/consents/",
+    request_body = GrantConsent,
+    responses(
+        (status = 200, description = "OK", body = ConsentDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Consents",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/")]
+pub async fn grant(
+    grant: SanitizedJson<GrantConsent>,
+    req: HttpRequest,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(e) => e,
+        None => return HttpResponse::Forbidden().finish(),
+    };
+
+    let grant = grant.into_inner();
+    let client = match pool
+        .services
+        .client_service
+        .find_by_id(&grant.client_id, &pool.database)
+        .await
+    {
+        Ok(Some(c)) => c,
+        Ok(None) => return HttpResponse::NotFound().finish(),
+        Err(e) => {
+            error!("Error finding Client by ID {}: {}", grant.client_id, e);
+            return e.into_http_response();
+        }
+    };
+
+    match pool
+        .services
+        .consent_service
+        .grant(
+            user_id,
+            &client,
+            grant.scopes,
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(c) => {
+            let mut consent_dto = ConsentDto::from(c);
+            consent_dto.client_name = Some(client.name);
+            HttpResponse::Ok().json(consent_dto)
+        }
+        Err(e) => {
+            error!("Error granting Consent: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/consents/check/",
+    params(
+        ("clientId" = String, Query, description = "The ID of the Client"),
+        ("scope" = Option<String>, Query, description = "The space-separated scopes that are requested by the Client"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = ConsentCheckDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Consents",
+    security(
+        ("Token" = [])
+    )
+)]
+#[get("/check/")]
+pub async fn check(
+    query: web::Query<ConsentQuery>,
+    req: HttpRequest,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(e) => e,
+        None => return HttpResponse::Forbidden().finish(),
+    };
+
+    let client_id = match parse_object_id("Client ID", &query.client_id) {
+        Ok(oid) => oid,
+        Err(e) => return e.into_http_response(),
+    };
+
+    let scopes: Vec<String> = query
+        .scope
+        .as_deref()
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(String::from)
+        .collect();
+
+    match pool
+        .services
+        .consent_service
+        .missing_scopes(user_id, client_id, &scopes, &pool.database)
+        .await
+    {
+        Ok(missing_scopes) => HttpResponse::Ok().json(ConsentCheckDto {
+            consent_required: !missing_scopes.is_empty(),
+            missing_scopes,
+        }),
+        Err(e) => {
+            error!("Error checking Consent: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/consents/{id}",
+    params(
+        ("id" = String, Path, description = "The ID of the Consent"),
+    ),
+    responses(
+        (status = 200, description = "OK"),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Consents",
+    security(
+        ("Token" = [])
+    )
+)]
+#[delete("/{id}")]
+pub async fn revoke(path: ObjectIdPath, req: HttpRequest, pool: web::Data<Config>) -> HttpResponse {
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(e) => e,
+        None => return HttpResponse::Forbidden().finish(),
+    };
+
+    match pool
+        .services
+        .consent_service
+        .revoke(&path, user_id, &pool.database, &pool.services.audit_service)
+        .await
+    {
+        Ok(_) => HttpResponse::Ok().finish(),
+        Err(e) => {
+            error!("Error revoking Consent: {}", e);
+            e.into_http_response()
+        }
+    }
+}
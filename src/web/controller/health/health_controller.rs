@@ -1,4 +1,6 @@
-use actix_web::{get, HttpResponse};
+use crate::configuration::config::Config;
+use actix_web::{get, web, HttpRequest, HttpResponse};
+use log::warn;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
@@ -26,15 +28,79 @@ impl HealthResponse {
     }
 }
 
+/// # Summary
+///
+/// Check whether a request may call the health endpoints, if a static bearer token is required to call them.
+///
+/// # Arguments
+///
+/// * `req` - The HttpRequest.
+/// * `config` - The Config.
+///
+/// # Example
+///
+/// ```
+/// if !is_authorized(&req, &config) {
+///     return HttpResponse::Unauthorized().finish();
+/// }
+/// ```
+///
+/// # Returns
+///
+/// * `bool` - True if the request may call the health endpoints.
+fn is_authorized(req: &HttpRequest, config: &Config) -> bool {
+    let token = req
+        .headers()
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "));
+
+    config.health_config.is_authorized(token)
+}
+
 #[utoipa::path(
     get,
     path = "/health/",
     responses(
         (status = 200, description = "OK", body = HealthResponse),
+        (status = 401, description = "Unauthorized"),
     ),
     tag = "Health",
 )]
 #[get("/")]
-pub async fn health() -> HttpResponse {
+pub async fn health(req: HttpRequest, config: web::Data<Config>) -> HttpResponse {
+    if !is_authorized(&req, &config) {
+        return HttpResponse::Unauthorized().finish();
+    }
+
+    HttpResponse::Ok().json(HealthResponse::new("UP"))
+}
+
+#[utoipa::path(
+    get,
+    path = "/health/ready/",
+    responses(
+        (status = 200, description = "OK", body = HealthResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 503, description = "Service Unavailable", body = HealthResponse),
+    ),
+    tag = "Health",
+)]
+#[get("/ready/")]
+pub async fn ready(req: HttpRequest, config: web::Data<Config>) -> HttpResponse {
+    if !is_authorized(&req, &config) {
+        return HttpResponse::Unauthorized().finish();
+    }
+
+    if !config.db_watchdog.is_healthy() {
+        return HttpResponse::ServiceUnavailable().json(HealthResponse::new("DOWN"));
+    }
+
+    // Tokens that cannot be issued or verified would fail every login, so the instance should not receive traffic
+    if let Err(e) = config.services.jwt_service.check_configuration() {
+        warn!("Readiness check failed: {}", e);
+        return HttpResponse::ServiceUnavailable().json(HealthResponse::new("DOWN"));
+    }
+
     HttpResponse::Ok().json(HealthResponse::new("UP"))
 }
@@ -0,0 +1,126 @@
+use crate::configuration::config::Config;
+use crate::errors::bad_request::BadRequest;
+use crate::errors::internal_server_error::InternalServerError;
+use crate::errors::into_http_response::IntoHttpResponse;
+use crate::repository::invitation::invitation_model::Invitation;
+use crate::repository::invitation::invitation_repository::Error;
+use crate::repository::user::user_model::User;
+use crate::web::dto::invitation::create_invitation::AcceptInvitation;
+use crate::web::dto::organization::organization_dto::SimpleOrganizationDto;
+use crate::web::extractors::sanitized_json::SanitizedJson;
+use crate::web::extractors::user_id_extractor;
+use actix_web::{post, web, HttpRequest, HttpResponse};
+use log::error;
+
+/// # Summary
+///
+/// Find an Invitation by its token. The token does not identify the Organization, so the Database of every tenant is
+/// searched if Organizations are routed to different Databases.
+///
+/// # Arguments
+///
+/// * `token` - The token of the Invitation.
+/// * `user` - The User that accepts the Invitation.
+/// * `pool` - The Config.
+///
+/// # Example
+///
+/// ```
+/// let invitation = find_invitation("token", &user, &pool).await?;
+/// ```
+///
+/// # Returns
+///
+/// * `Result<Invitation, Error>` - The Invitation, if it has not expired and was sent to one of the email addresses of the User.
+pub async fn find_invitation(token: &str, user: &User, pool: &Config) -> Result<Invitation, Error> {
+    let mut result = Err(Error::InvalidToken);
+    for db in pool.tenant_router.databases() {
+        result = pool
+            .services
+            .invitation_service
+            .find_by_token(token, user, db)
+            .await;
+
+        if !matches!(result, Err(Error::InvalidToken)) {
+            break;
+        }
+    }
+
+    result
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/invitations/accept/",
+    request_body = AcceptInvitation,
+    responses(
+        (status = 200, description = "OK", body = SimpleOrganizationDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Invitations",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/accept/")]
+pub async fn accept(
+    accept: SanitizedJson<AcceptInvitation>,
+    req: HttpRequest,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    let accept = accept.into_inner();
+
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(e) => e,
+        None => return HttpResponse::Forbidden().finish(),
+    };
+
+    let user = match pool
+        .services
+        .user_service
+        .find_by_id(&user_id.to_hex(), &pool.database)
+        .await
+    {
+        Ok(Some(u)) => u,
+        Ok(None) => return HttpResponse::Forbidden().finish(),
+        Err(e) => {
+            error!("Error finding User by ID {}: {}", user_id.to_hex(), e);
+            return e.into_http_response();
+        }
+    };
+
+    let invitation = match find_invitation(&accept.token, &user, &pool).await {
+        Ok(i) => i,
+        Err(e) => {
+            error!("Error accepting Invitation: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    let invitation_db = pool
+        .tenant_router
+        .resolve(Some(&invitation.organization_id.to_hex()));
+
+    match pool
+        .services
+        .invitation_service
+        .accept(
+            invitation,
+            user_id,
+            &pool.database,
+            invitation_db,
+            &pool.services.organization_service,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(o) => HttpResponse::Ok().json(SimpleOrganizationDto::from(o)),
+        Err(e) => {
+            error!("Error accepting Invitation: {}", e);
+            e.into_http_response()
+        }
+    }
+}
@@ -0,0 +1,648 @@
+use crate::configuration::config::Config;
+use crate::errors::internal_server_error::InternalServerError;
+use crate::errors::into_http_response::IntoHttpResponse;
+use crate::repository::authorization_code::authorization_code_repository::Error as AuthorizationCodeError;
+use crate::repository::client::client_model::{
+    Client, AUTHORIZATION_CODE_GRANT_TYPE, CLIENT_CREDENTIALS_GRANT_TYPE, DEVICE_CODE_GRANT_TYPE,
+};
+use crate::repository::client::client_repository::Error as ClientError;
+use crate::repository::device::device_repository::Error;
+use crate::services::oauth::error_code::OAuthErrorCode;
+use crate::services::oauth::pkce;
+use crate::web::dto::oauth::authorization_request::{AuthorizationRequest, AuthorizationResponse};
+use crate::web::dto::oauth::device_request::{
+    DeviceCodeRequest, DeviceVerification, UserCodeQuery,
+};
+use crate::web::dto::oauth::device_response::{DeviceCodeResponse, DeviceDto};
+use crate::web::dto::oauth::token::{OAuthError, TokenRequest, TokenResponse};
+use crate::web::extractors::sanitized_json::SanitizedJson;
+use crate::web::extractors::user_id_extractor;
+use actix_web::http::header;
+use actix_web::{get, post, web, HttpRequest, HttpResponse};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use log::error;
+
+/// # Summary
+///
+/// Convert an Error into an OAuth 2.0 error response, as defined by RFC 6749.
+///
+/// # Arguments
+///
+/// * `e` - The Error.
+///
+/// # Returns
+///
+/// * `HttpResponse` - A BadRequest or Unauthorized response with an OAuthError body, or the default response of the Error.
+fn oauth_error_response<E: OAuthErrorCode + IntoHttpResponse>(e: E) -> HttpResponse {
+    match e.oauth_error_code() {
+        Some("invalid_client") => {
+            HttpResponse::Unauthorized().json(OAuthError::new("invalid_client", &e.to_string()))
+        }
+        Some(code) => HttpResponse::BadRequest().json(OAuthError::new(code, &e.to_string())),
+        None => {
+            error!("Error while processing OAuth request: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/oauth/device/code/",
+    request_body(content = DeviceCodeRequest, content_type = "application/x-www-form-urlencoded"),
+    responses(
+        (status = 200, description = "OK", body = DeviceCodeResponse),
+        (status = 401, description = "Unauthorized", body = OAuthError),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "OAuth",
+)]
+#[post("/device/code/")]
+pub async fn device_code(
+    request: web::Form<DeviceCodeRequest>,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    let device_code = match pool
+        .services
+        .device_service
+        .create(request.client_id.trim(), &pool.database)
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => return oauth_error_response(e),
+    };
+
+    let verification_uri = pool.device_config.verification_uri.clone();
+    let separator = if verification_uri.contains('?') {
+        '&'
+    } else {
+        '?'
+    };
+    let verification_uri_complete = format!(
+        "{}{}userCode={}",
+        verification_uri, separator, device_code.user_code
+    );
+
+    HttpResponse::Ok().json(DeviceCodeResponse {
+        device_code: device_code.device_code,
+        user_code: device_code.user_code,
+        verification_uri,
+        verification_uri_complete,
+        expires_in: device_code.expires_in,
+        interval: device_code.interval,
+    })
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/oauth/token/",
+    request_body(content = TokenRequest, content_type = "application/x-www-form-urlencoded"),
+    responses(
+        (status = 200, description = "OK", body = TokenResponse),
+        (status = 400, description = "Bad Request", body = OAuthError),
+        (status = 401, description = "Unauthorized", body = OAuthError),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "OAuth",
+)]
+#[post("/token/")]
+pub async fn token(
+    request: web::Form<TokenRequest>,
+    req: HttpRequest,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    let request = request.into_inner();
+
+    match request.grant_type.as_str() {
+        AUTHORIZATION_CODE_GRANT_TYPE => authorization_code_token(request, &req, &pool).await,
+        DEVICE_CODE_GRANT_TYPE => device_code_token(request, &pool).await,
+        CLIENT_CREDENTIALS_GRANT_TYPE => client_credentials_token(request, &req, &pool).await,
+        _ => oauth_error_response(Error::UnsupportedGrantType(request.grant_type)),
+    }
+}
+
+/// # Summary
+///
+/// Exchange an approved device code for an access token of the User that approved it.
+///
+/// # Arguments
+///
+/// * `request` - The TokenRequest.
+/// * `pool` - The Config.
+///
+/// # Returns
+///
+/// * `HttpResponse` - A TokenResponse or an OAuthError.
+async fn device_code_token(request: TokenRequest, pool: &Config) -> HttpResponse {
+    let device_code = match request.device_code {
+        Some(d) => d,
+        None => return oauth_error_response(Error::InvalidGrant),
+    };
+
+    let client_id = request.client_id.unwrap_or_default();
+    let user_id = match pool
+        .services
+        .device_service
+        .poll(&device_code, client_id.trim(), &pool.database)
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => return oauth_error_response(e),
+    };
+
+    let user = match pool
+        .services
+        .user_service
+        .find_by_id(&user_id.to_hex(), &pool.database)
+        .await
+    {
+        Ok(Some(u)) if u.is_active() => u,
+        Ok(_) => return oauth_error_response(Error::AccessDenied),
+        Err(e) => {
+            error!("Error while finding user by ID: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    let token = match pool
+        .services
+        .jwt_service
+        .generate_jwt_token(&user.id.to_hex(), user.token_version)
+    {
+        Some(t) => pool.services.issue_access_token(t, &pool.database).await,
+        None => None,
+    };
+
+    match token {
+        Some(t) => HttpResponse::Ok().json(TokenResponse::new(
+            t,
+            pool.services.jwt_service.jwt_config.jwt_expiration,
+        )),
+        None => HttpResponse::InternalServerError()
+            .json(InternalServerError::new("Failed to generate JWT token")),
+    }
+}
+
+/// # Summary
+///
+/// Exchange the credentials of a Client for an access token that carries the permissions of the Client.
+/// The credentials are read from the HTTP Basic Authorization header, or from the request body.
+///
+/// # Arguments
+///
+/// * `request` - The TokenRequest.
+/// * `req` - The HttpRequest.
+/// * `pool` - The Config.
+///
+/// # Returns
+///
+/// * `HttpResponse` - A TokenResponse or an OAuthError.
+async fn client_credentials_token(
+    request: TokenRequest,
+    req: &HttpRequest,
+    pool: &Config,
+) -> HttpResponse {
+    let (client_id, client_secret) = match basic_credentials(req) {
+        Some(c) => c,
+        None => match (request.client_id, request.client_secret) {
+            (Some(i), Some(s)) => (i, s),
+            _ => return oauth_error_response(Error::InvalidClient),
+        },
+    };
+
+    let client = match pool
+        .services
+        .client_service
+        .authenticate(client_id.trim(), &client_secret, &pool.database)
+        .await
+    {
+        Ok(c) => c,
+        Err(ClientError::InvalidCredentials) => return oauth_error_response(Error::InvalidClient),
+        Err(e) => {
+            error!("Error while authenticating Client: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    if client.public || !client.allows_grant(CLIENT_CREDENTIALS_GRANT_TYPE) {
+        return oauth_error_response(Error::UnauthorizedClient);
+    }
+
+    // Every requested scope must have been registered for the Client
+    let scope = match request.scope {
+        Some(s) => {
+            if let Some(u) = s
+                .split_whitespace()
+                .find(|r| !client.scopes.iter().any(|c| c == r))
+            {
+                return oauth_error_response(Error::InvalidScope(u.to_string()));
+            }
+            Some(s.split_whitespace().collect::<Vec<&str>>().join(" "))
+        }
+        None => None,
+    };
+
+    let token = match pool.services.jwt_service.generate_client_token(
+        &client.id.to_hex(),
+        client.token_version,
+        scope.as_deref(),
+    ) {
+        Some(t) => pool.services.issue_access_token(t, &pool.database).await,
+        None => None,
+    };
+
+    match token {
+        Some(t) => {
+            let mut response =
+                TokenResponse::new(t, pool.services.jwt_service.jwt_config.jwt_expiration);
+            response.scope = scope.filter(|s| !s.is_empty());
+            HttpResponse::Ok().json(response)
+        }
+        None => HttpResponse::InternalServerError()
+            .json(InternalServerError::new("Failed to generate JWT token")),
+    }
+}
+
+/// # Summary
+///
+/// Exchange an authorization code for an access token of the User that approved the authorization request. The
+/// access token carries the scopes that were approved. Confidential Clients must authenticate, while public Clients
+/// are identified by their client ID and protected by PKCE instead.
+///
+/// # Arguments
+///
+/// * `request` - The TokenRequest.
+/// * `req` - The HttpRequest.
+/// * `pool` - The Config.
+///
+/// # Returns
+///
+/// * `HttpResponse` - A TokenResponse or an OAuthError.
+async fn authorization_code_token(
+    request: TokenRequest,
+    req: &HttpRequest,
+    pool: &Config,
+) -> HttpResponse {
+    let code = match request.code {
+        Some(c) => c,
+        None => return oauth_error_response(AuthorizationCodeError::InvalidGrant),
+    };
+
+    let credentials = match basic_credentials(req) {
+        Some(c) => Some(c),
+        None => match (&request.client_id, &request.client_secret) {
+            (Some(i), Some(s)) => Some((i.clone(), s.clone())),
+            _ => None,
+        },
+    };
+
+    let client = match credentials {
+        Some((client_id, client_secret)) => match pool
+            .services
+            .client_service
+            .authenticate(client_id.trim(), &client_secret, &pool.database)
+            .await
+        {
+            Ok(c) => c,
+            Err(ClientError::InvalidCredentials) => {
+                return oauth_error_response(AuthorizationCodeError::InvalidClient)
+            }
+            Err(e) => {
+                error!("Error while authenticating Client: {}", e);
+                return e.into_http_response();
+            }
+        },
+        None => {
+            let client_id = request.client_id.unwrap_or_default();
+            match find_client(client_id.trim(), pool).await {
+                Ok(c) if c.public => c,
+                Ok(_) => return oauth_error_response(AuthorizationCodeError::InvalidClient),
+                Err(r) => return r,
+            }
+        }
+    };
+
+    if !client.allows_grant(AUTHORIZATION_CODE_GRANT_TYPE) {
+        return oauth_error_response(AuthorizationCodeError::UnauthorizedClient);
+    }
+
+    let authorization_code = match pool
+        .services
+        .authorization_code_service
+        .redeem(
+            &code,
+            client.id,
+            request.redirect_uri.as_deref(),
+            request.code_verifier.as_deref(),
+            &pool.database,
+        )
+        .await
+    {
+        Ok(a) => a,
+        Err(e) => return oauth_error_response(e),
+    };
+
+    let user = match pool
+        .services
+        .user_service
+        .find_by_id(&authorization_code.user_id.to_hex(), &pool.database)
+        .await
+    {
+        Ok(Some(u)) if u.is_active() => u,
+        Ok(_) => return oauth_error_response(AuthorizationCodeError::InvalidGrant),
+        Err(e) => {
+            error!("Error while finding user by ID: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    let scope = authorization_code.scopes.join(" ");
+    let token = match pool.services.jwt_service.generate_scoped_token(
+        &user.id.to_hex(),
+        user.token_version,
+        Some(&scope),
+    ) {
+        Some(t) => pool.services.issue_access_token(t, &pool.database).await,
+        None => None,
+    };
+
+    match token {
+        Some(t) => {
+            let mut response =
+                TokenResponse::new(t, pool.services.jwt_service.jwt_config.jwt_expiration);
+            response.scope = Some(scope).filter(|s| !s.is_empty());
+            HttpResponse::Ok().json(response)
+        }
+        None => HttpResponse::InternalServerError()
+            .json(InternalServerError::new("Failed to generate JWT token")),
+    }
+}
+
+/// # Summary
+///
+/// Find an enabled Client by the client ID of an OAuth 2.0 request.
+///
+/// # Arguments
+///
+/// * `client_id` - The client ID.
+/// * `pool` - The Config.
+///
+/// # Returns
+///
+/// * `Result<Client, HttpResponse>` - The Client, or the response that should be returned if it is unknown or disabled.
+async fn find_client(client_id: &str, pool: &Config) -> Result<Client, HttpResponse> {
+    match pool
+        .services
+        .client_service
+        .find_by_id(client_id, &pool.database)
+        .await
+    {
+        Ok(Some(c)) if c.enabled => Ok(c),
+        Ok(_) | Err(ClientError::EmptyId) | Err(ClientError::InvalidId(_)) => {
+            Err(oauth_error_response(AuthorizationCodeError::InvalidClient))
+        }
+        Err(e) => {
+            error!("Error while finding Client by ID: {}", e);
+            Err(e.into_http_response())
+        }
+    }
+}
+
+/// # Summary
+///
+/// Percent-encode a value, so that it can be added to the query of a redirect URI.
+///
+/// # Arguments
+///
+/// * `value` - The value to encode.
+///
+/// # Returns
+///
+/// * `String` - The encoded value, in which only unreserved characters are left as they are.
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/oauth/authorize/",
+    request_body = AuthorizationRequest,
+    responses(
+        (status = 200, description = "OK", body = AuthorizationResponse),
+        (status = 400, description = "Bad Request", body = OAuthError),
+        (status = 401, description = "Unauthorized", body = OAuthError),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "OAuth",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/authorize/")]
+pub async fn authorize(
+    request: SanitizedJson<AuthorizationRequest>,
+    req: HttpRequest,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(d) => d,
+        None => return HttpResponse::Forbidden().finish(),
+    };
+
+    let request = request.into_inner();
+    if request.response_type != "code" {
+        return oauth_error_response(AuthorizationCodeError::UnsupportedResponseType(
+            request.response_type,
+        ));
+    }
+
+    let client = match find_client(&request.client_id, &pool).await {
+        Ok(c) => c,
+        Err(r) => return r,
+    };
+
+    let code_challenge = match pkce::parse_challenge(
+        client.public,
+        request.code_challenge.as_deref(),
+        request.code_challenge_method.as_deref(),
+    ) {
+        Ok(c) => c,
+        Err(e) => return oauth_error_response(AuthorizationCodeError::Pkce(e)),
+    };
+
+    let scopes: Vec<String> = request
+        .scope
+        .as_deref()
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(String::from)
+        .collect();
+
+    // The User must have consented to every requested scope through the consent screen of the front end
+    match pool
+        .services
+        .consent_service
+        .missing_scopes(user_id, client.id, &scopes, &pool.database)
+        .await
+    {
+        Ok(m) if m.is_empty() => {}
+        Ok(m) => return oauth_error_response(AuthorizationCodeError::ConsentRequired(m.join(" "))),
+        Err(e) => {
+            error!("Error while checking Consent: {}", e);
+            return e.into_http_response();
+        }
+    }
+
+    let grant = match pool
+        .services
+        .authorization_code_service
+        .create(
+            &client,
+            user_id,
+            request.redirect_uri.as_deref(),
+            scopes,
+            code_challenge,
+            &pool.database,
+        )
+        .await
+    {
+        Ok(g) => g,
+        Err(e) => return oauth_error_response(e),
+    };
+
+    let separator = if grant.redirect_uri.contains('?') {
+        '&'
+    } else {
+        '?'
+    };
+    let mut redirect_uri = format!(
+        "{}{}code={}",
+        grant.redirect_uri,
+        separator,
+        percent_encode(&grant.code)
+    );
+    if let Some(s) = request.state {
+        redirect_uri.push_str(&format!("&state={}", percent_encode(&s)));
+    }
+
+    HttpResponse::Ok().json(AuthorizationResponse { redirect_uri })
+}
+
+/// # Summary
+///
+/// Read the client ID and secret from the HTTP Basic Authorization header, as described by RFC 6749.
+///
+/// # Arguments
+///
+/// * `req` - The HttpRequest.
+///
+/// # Returns
+///
+/// * `Option<(String, String)>` - The client ID and secret, or None if the header is missing or malformed.
+fn basic_credentials(req: &HttpRequest) -> Option<(String, String)> {
+    let encoded = req
+        .headers()
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Basic ")?;
+
+    let decoded = String::from_utf8(STANDARD.decode(encoded.trim()).ok()?).ok()?;
+    let (client_id, client_secret) = decoded.split_once(':')?;
+
+    Some((client_id.to_string(), client_secret.to_string()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/oauth/device/",
+    params(
+        ("userCode" = String, Query, description = "The user code that is shown by the device"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = DeviceDto),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "OAuth",
+    security(
+        ("Token" = [])
+    )
+)]
+#[get("/device/")]
+pub async fn find_device(
+    query: web::Query<UserCodeQuery>,
+    req: HttpRequest,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    if user_id_extractor::get_user_id_from_token(&req, &pool)
+        .await
+        .is_none()
+    {
+        return HttpResponse::Forbidden().finish();
+    }
+
+    match pool
+        .services
+        .device_service
+        .find_by_user_code(&query.user_code, &pool.database)
+        .await
+    {
+        Ok(d) => HttpResponse::Ok().json(DeviceDto::from(d)),
+        Err(e) => oauth_error_response(e),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/oauth/device/verify/",
+    request_body = DeviceVerification,
+    responses(
+        (status = 200, description = "OK"),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "OAuth",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/device/verify/")]
+pub async fn verify_device(
+    verification: SanitizedJson<DeviceVerification>,
+    req: HttpRequest,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(d) => d,
+        None => return HttpResponse::Forbidden().finish(),
+    };
+
+    match pool
+        .services
+        .device_service
+        .verify(
+            &verification.user_code,
+            verification.approve,
+            user_id,
+            &pool.database,
+        )
+        .await
+    {
+        Ok(_) => HttpResponse::Ok().finish(),
+        Err(e) => oauth_error_response(e),
+    }
+}
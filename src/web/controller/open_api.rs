@@ -0,0 +1 @@
+pub mod open_api_controller;
@@ -0,0 +1,7 @@
+use crate::components::open_api::ApiDoc;
+use actix_web::{get, HttpResponse};
+
+#[get("/openapi.json")]
+pub async fn openapi_json() -> HttpResponse {
+    HttpResponse::Ok().json(ApiDoc::spec())
+}
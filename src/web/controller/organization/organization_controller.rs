@@ -0,0 +1,1348 @@
+use crate::configuration::config::Config;
+use crate::errors::bad_request::BadRequest;
+use crate::errors::internal_server_error::InternalServerError;
+use crate::errors::into_http_response::IntoHttpResponse;
+use crate::errors::invalid_id::parse_object_id;
+use crate::repository::organization::organization_model::{Member, Organization};
+use crate::repository::permission::permission_repository::Error as PermissionError;
+use crate::repository::role::role_model::Role;
+use crate::repository::role::role_repository::Error as RoleError;
+use crate::services::event::invalidation_event::InvalidationEvent;
+use crate::web::controller::role::role_controller::{
+    defer_permission_change, get_role_dto_from_role, request_permission_change,
+    resolve_permission_dependencies, validate_permissions,
+};
+use crate::web::controller::user::user_controller::validate_roles_in_scope;
+use crate::web::dto::invitation::create_invitation::CreateInvitation;
+use crate::web::dto::invitation::invitation_dto::{InvitationDto, InvitationTokenDto};
+use crate::web::dto::organization::create_organization::CreateOrganization;
+use crate::web::dto::organization::organization_dto::{MemberDto, OrganizationDto};
+use crate::web::dto::organization::update_member::UpdateMember;
+use crate::web::dto::organization::update_organization::UpdateOrganization;
+use crate::web::dto::role::create_role::CreateRole;
+use crate::web::dto::role::role_dto::{RoleDto, SimpleRoleDto};
+use crate::web::dto::role::update_role::UpdateRole;
+use crate::web::dto::search::search_request::SearchRequest;
+use crate::web::extractors::sanitized_json::SanitizedJson;
+use crate::web::extractors::user_id_extractor;
+use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse};
+use actix_web_grants::authorities::{AuthDetails, AuthoritiesCheck};
+use log::error;
+use mongodb::bson::oid::ObjectId;
+
+/// # Summary
+///
+/// Convert an Organization into an OrganizationDto, including the roles of its members and its default roles
+///
+/// # Arguments
+///
+/// * `organization` - An Organization
+/// * `config` - A reference to the Config
+///
+/// # Example
+///
+/// ```
+/// let organization = Organization::new("organization1".to_string(), None);
+/// let organization_dto = get_organization_dto_from_organization(organization, &config);
+/// ```
+///
+/// # Returns
+///
+/// * `Result<OrganizationDto, RoleError>` - The result containing the OrganizationDto or the RoleError that occurred
+pub async fn get_organization_dto_from_organization(
+    organization: Organization,
+    config: &Config,
+) -> Result<OrganizationDto, RoleError> {
+    let members = organization.members.clone();
+    let default_roles: Vec<String> = organization
+        .default_roles
+        .iter()
+        .map(|r| r.to_hex())
+        .collect();
+    let mut organization_dto = OrganizationDto::from(organization);
+
+    if !default_roles.is_empty() {
+        organization_dto.default_roles = config
+            .services
+            .role_service
+            .find_by_id_vec(default_roles, &config.database)
+            .await?
+            .into_iter()
+            .map(SimpleRoleDto::from)
+            .collect();
+    }
+
+    organization_dto.members = vec![];
+    for m in members {
+        let role_vec: Vec<String> = m.roles.iter().map(|r| r.to_hex()).collect();
+        let mut member_dto = MemberDto::from(m);
+
+        if !role_vec.is_empty() {
+            member_dto.roles = config
+                .services
+                .role_service
+                .find_by_id_vec(role_vec, &config.database)
+                .await?
+                .into_iter()
+                .map(SimpleRoleDto::from)
+                .collect();
+        }
+
+        organization_dto.members.push(member_dto);
+    }
+
+    Ok(organization_dto)
+}
+
+/// # Summary
+///
+/// Check whether the request was made with a tenant token for a different Organization
+///
+/// # Arguments
+///
+/// * `req` - The HttpRequest
+/// * `config` - A reference to the Config
+/// * `organization_id` - The ID of the Organization that is being managed
+///
+/// # Example
+///
+/// ```
+/// if is_foreign_tenant(&req, &pool, &id) {
+///     return HttpResponse::Forbidden().finish();
+/// }
+/// ```
+///
+/// # Returns
+///
+/// * `bool` - True if the token is scoped to another Organization
+fn is_foreign_tenant(req: &HttpRequest, config: &Config, organization_id: &str) -> bool {
+    match user_id_extractor::get_tenant_from_token(req, config) {
+        Some(t) => t != organization_id,
+        None => false,
+    }
+}
+
+/// # Summary
+///
+/// Find the first Permission that is granted by a Role but not held by the caller.
+/// Organization Roles may only hand out Permissions that the caller holds, so that managing
+/// an Organization cannot be used to escalate privileges.
+///
+/// # Arguments
+///
+/// * `permissions` - The IDs of the Permissions of the Role, including their dependencies
+/// * `details` - The AuthDetails of the caller
+/// * `config` - A reference to the Config
+///
+/// # Example
+///
+/// ```
+/// let ungranted = find_ungranted_permission(&role.permissions, &details, &pool).await?;
+/// ```
+///
+/// # Returns
+///
+/// * `Result<Option<String>, PermissionError>` - The name of the first Permission that the caller does not hold, if any
+async fn find_ungranted_permission(
+    permissions: &Option<Vec<ObjectId>>,
+    details: &AuthDetails,
+    config: &Config,
+) -> Result<Option<String>, PermissionError> {
+    let permissions = match permissions {
+        Some(p) if !p.is_empty() => p.clone(),
+        _ => return Ok(None),
+    };
+
+    Ok(config
+        .services
+        .permission_service
+        .find_by_id_vec(permissions, &config.database)
+        .await?
+        .into_iter()
+        .map(|p| p.name)
+        .find(|n| !details.has_authority(n.as_str())))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/organizations/",
+    request_body = CreateOrganization,
+    responses(
+        (status = 200, description = "OK", body = OrganizationDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Organizations",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/")]
+pub async fn create(
+    organization_dto: SanitizedJson<CreateOrganization>,
+    pool: web::Data<Config>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(e) => e,
+        None => {
+            error!("Failed to get User ID from token");
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to get User ID from token"));
+        }
+    };
+
+    let organization_dto = organization_dto.into_inner();
+    let organization = Organization::new(organization_dto.name, organization_dto.description);
+
+    match pool
+        .services
+        .organization_service
+        .create(
+            organization,
+            Some(user_id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(o) => HttpResponse::Ok().json(OrganizationDto::from(o)),
+        Err(e) => {
+            error!("Error creating Organization: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/organizations/",
+    params(
+        ("limit" = Option<i64>, Query, description = "The limit of Organizations to retrieve", nullable = true),
+        ("page" = Option<i64>, Query, description = "The page", nullable = true),
+    ),
+    responses(
+        (status = 200, description = "OK", body = Vec<OrganizationDto>),
+        (status = 204, description = "No Content"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Organizations",
+    security(
+        ("Token" = [])
+    )
+)]
+#[get("/")]
+pub async fn find_all(search: web::Query<SearchRequest>, pool: web::Data<Config>) -> HttpResponse {
+    let search = search.into_inner();
+
+    let mut limit = search.limit;
+    let page = search.page;
+
+    let limit_clone = limit.unwrap_or(pool.server_config.max_limit);
+    if limit.is_none()
+        || (limit.is_some() && limit_clone > pool.server_config.max_limit || limit_clone < 1)
+    {
+        limit = Some(pool.server_config.max_limit);
+    }
+
+    let res = match pool
+        .services
+        .organization_service
+        .find_all(limit, page, &pool.database)
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => {
+            error!("Error while finding all Organizations: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    if res.is_empty() {
+        return HttpResponse::NoContent().finish();
+    }
+
+    let mut organization_dto_list: Vec<OrganizationDto> = vec![];
+    for o in res {
+        match get_organization_dto_from_organization(o, &pool).await {
+            Ok(d) => organization_dto_list.push(d),
+            Err(e) => {
+                error!("Error converting Organization to OrganizationDto: {}", e);
+                return e.into_http_response();
+            }
+        };
+    }
+
+    HttpResponse::Ok().json(organization_dto_list)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/organizations/{id}",
+    params(
+        ("id" = String, Path, description = "The ID of the Organization"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = OrganizationDto),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Organizations",
+    security(
+        ("Token" = [])
+    )
+)]
+#[get("/{id}")]
+pub async fn find_by_id(path: web::Path<String>, pool: web::Data<Config>) -> HttpResponse {
+    let res = match pool
+        .services
+        .organization_service
+        .find_by_id(&path, &pool.database)
+        .await
+    {
+        Ok(Some(d)) => d,
+        Ok(None) => return HttpResponse::NotFound().finish(),
+        Err(e) => {
+            error!("Error finding Organization by ID {}: {}", path, e);
+            return e.into_http_response();
+        }
+    };
+
+    match get_organization_dto_from_organization(res, &pool).await {
+        Ok(dto) => HttpResponse::Ok().json(dto),
+        Err(e) => {
+            error!("Error converting Organization to OrganizationDto: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/organizations/{id}",
+    request_body = UpdateOrganization,
+    params(
+        ("id" = String, Path, description = "The ID of the Organization"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = OrganizationDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Organizations",
+    security(
+        ("Token" = [])
+    )
+)]
+#[put("/{id}")]
+pub async fn update(
+    path: web::Path<String>,
+    update: SanitizedJson<UpdateOrganization>,
+    pool: web::Data<Config>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let update = update.into_inner();
+
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(e) => e,
+        None => {
+            error!("Failed to get User ID from token");
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to get User ID from token"));
+        }
+    };
+
+    let mut organization = match pool
+        .services
+        .organization_service
+        .find_by_id(&path, &pool.database)
+        .await
+    {
+        Ok(Some(d)) => d,
+        Ok(None) => return HttpResponse::NotFound().finish(),
+        Err(e) => {
+            error!("Error finding Organization by ID {}: {}", path, e);
+            return e.into_http_response();
+        }
+    };
+
+    if update.default_roles.is_some() {
+        match validate_roles_in_scope(&update.default_roles, Some(organization.id), &pool).await {
+            Ok(_) => (),
+            Err(e) => {
+                return match e {
+                    RoleError::RoleNotFound(r) => HttpResponse::BadRequest()
+                        .json(BadRequest::new(&format!("Role {} not found", r))),
+                    _ => {
+                        error!("Error validating roles: {}", e);
+                        e.into_http_response()
+                    }
+                };
+            }
+        };
+    }
+
+    organization.name = update.name;
+    organization.description = update.description;
+
+    // The default roles were validated above, so their IDs can be parsed
+    if let Some(default_roles) = update.default_roles {
+        organization.default_roles = default_roles
+            .iter()
+            .filter_map(|r| ObjectId::parse_str(r).ok())
+            .collect();
+    }
+
+    let res = match pool
+        .services
+        .organization_service
+        .update(
+            organization,
+            Some(user_id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => {
+            error!("Error updating Organization: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    match get_organization_dto_from_organization(res, &pool).await {
+        Ok(dto) => HttpResponse::Ok().json(dto),
+        Err(e) => {
+            error!("Error converting Organization to OrganizationDto: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/organizations/{id}/members/{user_id}",
+    request_body = UpdateMember,
+    params(
+        ("id" = String, Path, description = "The ID of the Organization"),
+        ("user_id" = String, Path, description = "The ID of the User"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = OrganizationDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Organizations",
+    security(
+        ("Token" = [])
+    )
+)]
+#[put("/{id}/members/{user_id}")]
+pub async fn set_member(
+    path: web::Path<(String, String)>,
+    update: SanitizedJson<UpdateMember>,
+    pool: web::Data<Config>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let (id, member_id) = path.into_inner();
+    let update = update.into_inner();
+
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(e) => e,
+        None => {
+            error!("Failed to get User ID from token");
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to get User ID from token"));
+        }
+    };
+
+    match pool
+        .services
+        .user_service
+        .find_by_id(&member_id, &pool.database)
+        .await
+    {
+        Ok(Some(_)) => (),
+        Ok(None) => return HttpResponse::NotFound().finish(),
+        Err(e) => {
+            error!("Error finding User by ID {}: {}", member_id, e);
+            return e.into_http_response();
+        }
+    };
+
+    let roles = Some(update.roles);
+    match validate_roles_in_scope(&roles, ObjectId::parse_str(&id).ok(), &pool).await {
+        Ok(_) => (),
+        Err(e) => {
+            return match e {
+                RoleError::RoleNotFound(r) => HttpResponse::BadRequest()
+                    .json(BadRequest::new(&format!("Role {} not found", r))),
+                _ => {
+                    error!("Error validating roles: {}", e);
+                    e.into_http_response()
+                }
+            };
+        }
+    };
+
+    // The User and the roles were validated above, so their IDs can be parsed
+    let member = Member {
+        user_id: ObjectId::parse_str(&member_id).unwrap_or_default(),
+        roles: roles
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|r| ObjectId::parse_str(r).ok())
+            .collect(),
+    };
+
+    let res = match pool
+        .services
+        .organization_service
+        .set_member(
+            &id,
+            member,
+            Some(user_id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => {
+            error!("Error setting Member of Organization: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    match get_organization_dto_from_organization(res, &pool).await {
+        Ok(dto) => HttpResponse::Ok().json(dto),
+        Err(e) => {
+            error!("Error converting Organization to OrganizationDto: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/organizations/{id}/members/{user_id}",
+    params(
+        ("id" = String, Path, description = "The ID of the Organization"),
+        ("user_id" = String, Path, description = "The ID of the User"),
+    ),
+    responses(
+        (status = 200, description = "OK"),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Organizations",
+    security(
+        ("Token" = [])
+    )
+)]
+#[delete("/{id}/members/{user_id}")]
+pub async fn remove_member(
+    path: web::Path<(String, String)>,
+    pool: web::Data<Config>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let (id, member_id) = path.into_inner();
+
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(e) => e,
+        None => {
+            error!("Failed to get User ID from token");
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to get User ID from token"));
+        }
+    };
+
+    let member_id = match parse_object_id("User ID", &member_id) {
+        Ok(oid) => oid,
+        Err(e) => return e.into_http_response(),
+    };
+
+    match pool
+        .services
+        .organization_service
+        .remove_member(
+            &id,
+            member_id,
+            Some(user_id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(_) => HttpResponse::Ok().finish(),
+        Err(e) => {
+            error!("Error removing Member from Organization: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/organizations/{id}/invitations/",
+    request_body = CreateInvitation,
+    params(
+        ("id" = String, Path, description = "The ID of the Organization"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = InvitationTokenDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Organizations",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/{id}/invitations/")]
+pub async fn create_invitation(
+    path: web::Path<String>,
+    invitation: SanitizedJson<CreateInvitation>,
+    pool: web::Data<Config>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let invitation = invitation.into_inner();
+
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(e) => e,
+        None => {
+            error!("Failed to get User ID from token");
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to get User ID from token"));
+        }
+    };
+
+    let organization = match pool
+        .services
+        .organization_service
+        .find_by_id(&path, &pool.database)
+        .await
+    {
+        Ok(Some(d)) => d,
+        Ok(None) => return HttpResponse::NotFound().finish(),
+        Err(e) => {
+            error!("Error finding Organization by ID {}: {}", path, e);
+            return e.into_http_response();
+        }
+    };
+
+    let roles = Some(invitation.roles);
+    match validate_roles_in_scope(&roles, Some(organization.id), &pool).await {
+        Ok(_) => (),
+        Err(e) => {
+            return match e {
+                RoleError::RoleNotFound(r) => HttpResponse::BadRequest()
+                    .json(BadRequest::new(&format!("Role {} not found", r))),
+                _ => {
+                    error!("Error validating roles: {}", e);
+                    e.into_http_response()
+                }
+            };
+        }
+    };
+
+    // The roles were validated above, so their IDs can be parsed
+    let roles = roles
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|r| ObjectId::parse_str(r).ok())
+        .collect();
+
+    match pool
+        .services
+        .invitation_service
+        .create(
+            organization.id,
+            invitation.email,
+            roles,
+            Some(user_id),
+            pool.tenant_router.resolve(Some(&organization.id.to_hex())),
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok((i, token)) => HttpResponse::Ok().json(InvitationTokenDto {
+            invitation_id: i.id.to_hex(),
+            token,
+            expires_at: i.expires_at.to_rfc3339(),
+        }),
+        Err(e) => {
+            error!("Error creating Invitation: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/organizations/{id}/invitations/",
+    params(
+        ("id" = String, Path, description = "The ID of the Organization"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = Vec<InvitationDto>),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Organizations",
+    security(
+        ("Token" = [])
+    )
+)]
+#[get("/{id}/invitations/")]
+pub async fn find_invitations(path: web::Path<String>, pool: web::Data<Config>) -> HttpResponse {
+    let organization = match pool
+        .services
+        .organization_service
+        .find_by_id(&path, &pool.database)
+        .await
+    {
+        Ok(Some(d)) => d,
+        Ok(None) => return HttpResponse::NotFound().finish(),
+        Err(e) => {
+            error!("Error finding Organization by ID {}: {}", path, e);
+            return e.into_http_response();
+        }
+    };
+
+    match pool
+        .services
+        .invitation_service
+        .find_by_organization_id(
+            organization.id,
+            pool.tenant_router.resolve(Some(&organization.id.to_hex())),
+        )
+        .await
+    {
+        Ok(d) => HttpResponse::Ok().json(
+            d.into_iter()
+                .map(InvitationDto::from)
+                .collect::<Vec<InvitationDto>>(),
+        ),
+        Err(e) => {
+            error!("Error finding Invitations of Organization {}: {}", path, e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/organizations/{id}/invitations/{invitation_id}",
+    params(
+        ("id" = String, Path, description = "The ID of the Organization"),
+        ("invitation_id" = String, Path, description = "The ID of the Invitation"),
+    ),
+    responses(
+        (status = 200, description = "OK"),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Organizations",
+    security(
+        ("Token" = [])
+    )
+)]
+#[delete("/{id}/invitations/{invitation_id}")]
+pub async fn revoke_invitation(
+    path: web::Path<(String, String)>,
+    pool: web::Data<Config>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let (id, invitation_id) = path.into_inner();
+
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(e) => e,
+        None => {
+            error!("Failed to get User ID from token");
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to get User ID from token"));
+        }
+    };
+
+    let organization_id = match parse_object_id("Organization ID", &id) {
+        Ok(oid) => oid,
+        Err(e) => return e.into_http_response(),
+    };
+
+    match pool
+        .services
+        .invitation_service
+        .delete(
+            &invitation_id,
+            organization_id,
+            Some(user_id),
+            pool.tenant_router.resolve(Some(&id)),
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(_) => HttpResponse::Ok().finish(),
+        Err(e) => {
+            error!("Error revoking Invitation: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/organizations/{id}/roles/",
+    request_body = CreateRole,
+    params(
+        ("id" = String, Path, description = "The ID of the Organization"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = RoleDto),
+        (status = 202, description = "Accepted", body = RoleChangeDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Organizations",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/{id}/roles/")]
+pub async fn create_role(
+    path: web::Path<String>,
+    role_dto: SanitizedJson<CreateRole>,
+    details: AuthDetails,
+    pool: web::Data<Config>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let id = path.into_inner();
+    let role_dto = role_dto.into_inner();
+
+    if is_foreign_tenant(&req, &pool, &id) {
+        return HttpResponse::Forbidden().finish();
+    }
+
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(e) => e,
+        None => {
+            error!("Failed to get User ID from token");
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to get User ID from token"));
+        }
+    };
+
+    let organization = match pool
+        .services
+        .organization_service
+        .find_by_id(&id, &pool.database)
+        .await
+    {
+        Ok(Some(d)) => d,
+        Ok(None) => return HttpResponse::NotFound().finish(),
+        Err(e) => {
+            error!("Error finding Organization by ID {}: {}", id, e);
+            return e.into_http_response();
+        }
+    };
+
+    if role_dto.permissions.is_some() {
+        match validate_permissions(role_dto.permissions.clone(), &pool).await {
+            Ok(_) => (),
+            Err(e) => {
+                return match e {
+                    PermissionError::PermissionNotFound(r) => HttpResponse::BadRequest()
+                        .json(BadRequest::new(&format!("Permission {} not found", r))),
+                    _ => {
+                        error!("Error validating permissions: {}", e);
+                        e.into_http_response()
+                    }
+                };
+            }
+        };
+    }
+
+    let mut role = Role::from(role_dto);
+    role.organization_id = Some(organization.id);
+
+    if let Err(e) = resolve_permission_dependencies(&mut role.permissions, &pool).await {
+        return e;
+    }
+
+    match find_ungranted_permission(&role.permissions, &details, &pool).await {
+        Ok(None) => (),
+        Ok(Some(p)) => {
+            return HttpResponse::BadRequest().json(BadRequest::new(&format!(
+                "Permission {} is not held by the caller",
+                p
+            )))
+        }
+        Err(e) => {
+            error!("Error validating permissions: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    // A new Role is created without permissions if granting them must be approved by a second User
+    let mut empty = role.clone();
+    empty.permissions = None;
+    empty.permission_expirations = vec![];
+    let pending_change = defer_permission_change(&empty, &mut role, &pool);
+
+    let res = match pool
+        .services
+        .role_service
+        .create(
+            role,
+            Some(user_id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => {
+            error!("Error creating Role: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    if let Some(change) = pending_change {
+        return request_permission_change(change, user_id, &pool).await;
+    }
+
+    match get_role_dto_from_role(res, &pool).await {
+        Ok(dto) => HttpResponse::Ok().json(dto),
+        Err(e) => {
+            error!("Error converting Role to RoleDto: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/organizations/{id}/roles/",
+    params(
+        ("id" = String, Path, description = "The ID of the Organization"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = Vec<RoleDto>),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Organizations",
+    security(
+        ("Token" = [])
+    )
+)]
+#[get("/{id}/roles/")]
+pub async fn find_roles(
+    path: web::Path<String>,
+    pool: web::Data<Config>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let id = path.into_inner();
+
+    if is_foreign_tenant(&req, &pool, &id) {
+        return HttpResponse::Forbidden().finish();
+    }
+
+    let organization = match pool
+        .services
+        .organization_service
+        .find_by_id(&id, &pool.database)
+        .await
+    {
+        Ok(Some(d)) => d,
+        Ok(None) => return HttpResponse::NotFound().finish(),
+        Err(e) => {
+            error!("Error finding Organization by ID {}: {}", id, e);
+            return e.into_http_response();
+        }
+    };
+
+    let roles = match pool
+        .services
+        .role_service
+        .find_by_organization_id(organization.id, &pool.database)
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => {
+            error!("Error finding Roles of Organization {}: {}", id, e);
+            return e.into_http_response();
+        }
+    };
+
+    let mut res: Vec<RoleDto> = vec![];
+    for r in roles {
+        match get_role_dto_from_role(r, &pool).await {
+            Ok(dto) => res.push(dto),
+            Err(e) => {
+                error!("Error converting Role to RoleDto: {}", e);
+                return e.into_http_response();
+            }
+        }
+    }
+
+    HttpResponse::Ok().json(res)
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/organizations/{id}/roles/{role_id}",
+    request_body = UpdateRole,
+    params(
+        ("id" = String, Path, description = "The ID of the Organization"),
+        ("role_id" = String, Path, description = "The ID of the Role"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = RoleDto),
+        (status = 202, description = "Accepted", body = RoleChangeDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Organizations",
+    security(
+        ("Token" = [])
+    )
+)]
+#[put("/{id}/roles/{role_id}")]
+pub async fn update_role(
+    path: web::Path<(String, String)>,
+    update: SanitizedJson<UpdateRole>,
+    details: AuthDetails,
+    pool: web::Data<Config>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let (id, role_id) = path.into_inner();
+    let role_dto = update.into_inner();
+
+    if is_foreign_tenant(&req, &pool, &id) {
+        return HttpResponse::Forbidden().finish();
+    }
+
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(e) => e,
+        None => {
+            error!("Failed to get User ID from token");
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to get User ID from token"));
+        }
+    };
+
+    let organization = match pool
+        .services
+        .organization_service
+        .find_by_id(&id, &pool.database)
+        .await
+    {
+        Ok(Some(d)) => d,
+        Ok(None) => return HttpResponse::NotFound().finish(),
+        Err(e) => {
+            error!("Error finding Organization by ID {}: {}", id, e);
+            return e.into_http_response();
+        }
+    };
+
+    let mut role = match pool
+        .services
+        .role_service
+        .find_by_id(&role_id, &pool.database)
+        .await
+    {
+        Ok(Some(d)) if d.organization_id == Some(organization.id) => d,
+        Ok(_) => return HttpResponse::NotFound().finish(),
+        Err(e) => {
+            error!("Error finding Role by ID {}: {}", role_id, e);
+            return e.into_http_response();
+        }
+    };
+
+    if role_dto.permissions.is_some() {
+        match validate_permissions(role_dto.permissions.clone(), &pool).await {
+            Ok(_) => (),
+            Err(e) => {
+                return match e {
+                    PermissionError::PermissionNotFound(r) => HttpResponse::BadRequest()
+                        .json(BadRequest::new(&format!("Permission {} not found", r))),
+                    _ => {
+                        error!("Error validating permissions: {}", e);
+                        e.into_http_response()
+                    }
+                };
+            }
+        };
+    }
+
+    // The Permissions were validated above, so their IDs can be parsed
+    let previous = role.clone();
+    let old_name = role.name.clone();
+    role.name = role_dto.name;
+    role.description = role_dto.description;
+    role.permissions = role_dto.permissions.map(|p| {
+        p.iter()
+            .filter_map(|oid| ObjectId::parse_str(oid).ok())
+            .collect()
+    });
+
+    if let Err(e) = resolve_permission_dependencies(&mut role.permissions, &pool).await {
+        return e;
+    }
+    role.retain_permission_expirations();
+
+    match find_ungranted_permission(&role.permissions, &details, &pool).await {
+        Ok(None) => (),
+        Ok(Some(p)) => {
+            return HttpResponse::BadRequest().json(BadRequest::new(&format!(
+                "Permission {} is not held by the caller",
+                p
+            )))
+        }
+        Err(e) => {
+            error!("Error validating permissions: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    // Permission changes wait for the approval of a second User, while the other fields are applied right away
+    let pending_change = defer_permission_change(&previous, &mut role, &pool);
+
+    let res = match pool
+        .services
+        .role_service
+        .update(
+            role,
+            Some(user_id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => {
+            error!("Error updating Role: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    if res.name != old_name {
+        if let Err(e) = pool
+            .services
+            .user_service
+            .rename_role(res.id, &res.name, &pool.database)
+            .await
+        {
+            error!("Error renaming Role in UserSummaries: {}", e);
+        }
+
+        pool.services
+            .publish_invalidation(InvalidationEvent::RoleRenamed {
+                id: res.id.to_hex(),
+                old_name,
+                new_name: res.name.clone(),
+            })
+            .await;
+    }
+
+    if let Some(change) = pending_change {
+        return request_permission_change(change, user_id, &pool).await;
+    }
+
+    match get_role_dto_from_role(res, &pool).await {
+        Ok(dto) => HttpResponse::Ok().json(dto),
+        Err(e) => {
+            error!("Error converting Role to RoleDto: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/organizations/{id}/roles/{role_id}",
+    params(
+        ("id" = String, Path, description = "The ID of the Organization"),
+        ("role_id" = String, Path, description = "The ID of the Role"),
+    ),
+    responses(
+        (status = 200, description = "OK"),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Organizations",
+    security(
+        ("Token" = [])
+    )
+)]
+#[delete("/{id}/roles/{role_id}")]
+pub async fn delete_role(
+    path: web::Path<(String, String)>,
+    pool: web::Data<Config>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let (id, role_id) = path.into_inner();
+
+    if is_foreign_tenant(&req, &pool, &id) {
+        return HttpResponse::Forbidden().finish();
+    }
+
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(e) => e,
+        None => {
+            error!("Failed to get User ID from token");
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to get User ID from token"));
+        }
+    };
+
+    let organization = match pool
+        .services
+        .organization_service
+        .find_by_id(&id, &pool.database)
+        .await
+    {
+        Ok(Some(d)) => d,
+        Ok(None) => return HttpResponse::NotFound().finish(),
+        Err(e) => {
+            error!("Error finding Organization by ID {}: {}", id, e);
+            return e.into_http_response();
+        }
+    };
+
+    let role = match pool
+        .services
+        .role_service
+        .find_by_id(&role_id, &pool.database)
+        .await
+    {
+        Ok(Some(d)) if d.organization_id == Some(organization.id) => d,
+        Ok(_) => return HttpResponse::NotFound().finish(),
+        Err(e) => {
+            error!("Error finding Role by ID {}: {}", role_id, e);
+            return e.into_http_response();
+        }
+    };
+
+    match pool
+        .services
+        .role_service
+        .delete(
+            &role_id,
+            Some(user_id),
+            &pool.database,
+            &pool.services.user_service,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(_) => (),
+        Err(e) => {
+            error!("Error deleting Role: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    match pool
+        .services
+        .organization_service
+        .remove_role(organization.id, role.id, &pool.database)
+        .await
+    {
+        Ok(_) => HttpResponse::Ok().finish(),
+        Err(e) => {
+            error!("Error removing Role from Members of Organization: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/organizations/{id}",
+    params(
+        ("id" = String, Path, description = "The ID of the Organization"),
+    ),
+    responses(
+        (status = 200, description = "OK"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Organizations",
+    security(
+        ("Token" = [])
+    )
+)]
+#[delete("/{id}")]
+pub async fn delete(
+    path: web::Path<String>,
+    pool: web::Data<Config>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(e) => e,
+        None => {
+            error!("Failed to get User ID from token");
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to get User ID from token"));
+        }
+    };
+
+    match pool
+        .services
+        .organization_service
+        .delete(
+            &path,
+            Some(user_id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(_) => (),
+        Err(e) => {
+            error!("Error deleting Organization: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    // Pending invitations and Roles of a deleted Organization are no longer of any use
+    if let Ok(organization_id) = ObjectId::parse_str(path.as_str()) {
+        if let Err(e) = pool
+            .services
+            .invitation_service
+            .delete_by_organization_id(
+                organization_id,
+                pool.tenant_router.resolve(Some(path.as_str())),
+            )
+            .await
+        {
+            error!("Error deleting Invitations of Organization {}: {}", path, e);
+        }
+
+        if let Err(e) = pool
+            .services
+            .role_service
+            .delete_by_organization_id(organization_id, &pool.database)
+            .await
+        {
+            error!("Error deleting Roles of Organization {}: {}", path, e);
+        }
+    }
+
+    HttpResponse::Ok().finish()
+}
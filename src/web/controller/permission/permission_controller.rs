@@ -1,16 +1,95 @@
 use crate::configuration::config::Config;
 use crate::errors::bad_request::BadRequest;
 use crate::errors::internal_server_error::InternalServerError;
+use crate::errors::into_http_response::IntoHttpResponse;
+use crate::errors::invalid_id::parse_object_id;
 use crate::repository::permission::permission_model::Permission;
-use crate::repository::permission::permission_repository::Error;
+use crate::repository::search_hit::SearchHit;
+use crate::services::event::invalidation_event::InvalidationEvent;
 use crate::web::dto::permission::create_permission::CreatePermission;
 use crate::web::dto::permission::permission_dto::PermissionDto;
 use crate::web::dto::permission::update_permission::UpdatePermission;
 use crate::web::dto::search::search_request::SearchRequest;
+use crate::web::dto::search::search_result_dto::{highlighter_for, SearchResultDto};
+use crate::web::extractors::object_id_path::ObjectIdPath;
+use crate::web::extractors::sanitized_json::SanitizedJson;
 use crate::web::extractors::user_id_extractor;
+use crate::web::pagination;
+use actix_web::http::header;
 use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse};
-use actix_web_grants::protect;
 use log::error;
+use mongodb::bson::oid::ObjectId;
+
+/// # Summary
+///
+/// Parse the dependencies of a Permission. Every dependency must be an existing Permission other than the Permission
+/// itself.
+///
+/// # Arguments
+///
+/// * `permission_id` - The ID of the Permission that declares the dependencies.
+/// * `dependencies` - The IDs of the Permissions that the Permission depends on.
+/// * `pool` - The Config.
+///
+/// # Example
+///
+/// ```
+/// let dependencies = parse_dependencies(permission.id, vec![read_user.id.to_hex()], &pool).await?;
+/// ```
+///
+/// # Returns
+///
+/// * `Result<Vec<ObjectId>, HttpResponse>` - The IDs of the dependencies, or an error response if they are invalid.
+async fn parse_dependencies(
+    permission_id: ObjectId,
+    dependencies: Vec<String>,
+    pool: &web::Data<Config>,
+) -> Result<Vec<ObjectId>, HttpResponse> {
+    let mut oid_vec: Vec<ObjectId> = vec![];
+    for d in &dependencies {
+        let oid = match parse_object_id("Permission ID", d) {
+            Ok(d) => d,
+            Err(e) => return Err(e.into_http_response()),
+        };
+
+        if oid == permission_id {
+            return Err(HttpResponse::BadRequest()
+                .json(BadRequest::new("A Permission cannot depend on itself")));
+        }
+
+        if !oid_vec.contains(&oid) {
+            oid_vec.push(oid);
+        }
+    }
+
+    if oid_vec.is_empty() {
+        return Ok(oid_vec);
+    }
+
+    let existing = match pool
+        .services
+        .permission_service
+        .find_by_id_vec(oid_vec.clone(), &pool.database)
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => {
+            error!("Error while finding Permission dependencies: {}", e);
+            return Err(e.into_http_response());
+        }
+    };
+
+    for oid in &oid_vec {
+        if !existing.iter().any(|p| &p.id == oid) {
+            return Err(HttpResponse::BadRequest().json(BadRequest::new(&format!(
+                "Permission {} not found",
+                oid.to_hex()
+            ))));
+        }
+    }
+
+    Ok(oid_vec)
+}
 
 #[utoipa::path(
     post,
@@ -27,17 +106,21 @@ use log::error;
     )
 )]
 #[post("/")]
-#[protect("CAN_CREATE_PERMISSION")]
 pub async fn create_permission(
     pool: web::Data<Config>,
-    info: web::Json<CreatePermission>,
+    info: SanitizedJson<CreatePermission>,
     req: HttpRequest,
 ) -> HttpResponse {
-    if info.name.is_empty() {
-        return HttpResponse::BadRequest().json(BadRequest::new("Empty name"));
-    }
+    let mut info = info.into_inner();
+    let dependencies = info.dependencies.take();
+    let mut new_permission = Permission::from(info);
 
-    let new_permission = Permission::from(info.into_inner());
+    if let Some(d) = dependencies {
+        new_permission.dependencies = match parse_dependencies(new_permission.id, d, &pool).await {
+            Ok(d) => d,
+            Err(e) => return e,
+        };
+    }
 
     let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
         Some(e) => e,
@@ -62,13 +145,7 @@ pub async fn create_permission(
         Ok(d) => d,
         Err(e) => {
             error!("Error while creating Permission: {}", e);
-            return match e {
-                Error::NameAlreadyTaken => {
-                    HttpResponse::BadRequest().json(BadRequest::new(&e.to_string()))
-                }
-                _ => HttpResponse::InternalServerError()
-                    .json(InternalServerError::new(&e.to_string())),
-            };
+            return e.into_http_response();
         }
     };
 
@@ -82,6 +159,7 @@ pub async fn create_permission(
         ("text" = Option<String>, Query, description = "The text to search for", nullable = true),
         ("limit" = Option<i64>, Query, description = "The limit of permissions to retrieve", nullable = true),
         ("page" = Option<i64>, Query, description = "The page", nullable = true),
+        ("highlight" = Option<bool>, Query, description = "Whether to highlight the search terms in the name and description", nullable = true),
     ),
     responses(
         (status = 200, description = "OK", body = Vec<PermissionDto>),
@@ -94,9 +172,9 @@ pub async fn create_permission(
     )
 )]
 #[get("/")]
-#[protect("CAN_READ_PERMISSION")]
 pub async fn find_all_permissions(
     search: web::Query<SearchRequest>,
+    req: HttpRequest,
     pool: web::Data<Config>,
 ) -> HttpResponse {
     let search = search.into_inner();
@@ -111,19 +189,20 @@ pub async fn find_all_permissions(
         limit = Some(pool.server_config.max_limit);
     }
 
-    let res = match search.text {
+    let highlighter = highlighter_for(search.text.as_deref(), search.highlight);
+
+    let res: Vec<SearchHit<Permission>> = match &search.text {
         Some(t) => {
             match pool
                 .services
                 .permission_service
-                .search(&t, limit, page, &pool.database)
+                .search(t, limit, page, &pool.database)
                 .await
             {
                 Ok(d) => d,
                 Err(e) => {
                     error!("Error while searching for permissions: {}", e);
-                    return HttpResponse::InternalServerError()
-                        .json(InternalServerError::new(&e.to_string()));
+                    return e.into_http_response();
                 }
             }
         }
@@ -134,11 +213,10 @@ pub async fn find_all_permissions(
                 .find_all(limit, page, &pool.database)
                 .await
             {
-                Ok(d) => d,
+                Ok(d) => d.into_iter().map(SearchHit::from).collect(),
                 Err(e) => {
                     error!("Error while finding all permissions: {}", e);
-                    return HttpResponse::InternalServerError()
-                        .json(InternalServerError::new(&e.to_string()));
+                    return e.into_http_response();
                 }
             }
         }
@@ -148,9 +226,43 @@ pub async fn find_all_permissions(
         return HttpResponse::NoContent().finish();
     }
 
-    let dto_list = res.iter().map(|p| p.into()).collect::<Vec<PermissionDto>>();
+    let dto_list = res
+        .iter()
+        .map(|h| {
+            let highlights = highlighter.as_ref().and_then(|l| {
+                l.highlight_fields(&[
+                    ("name", Some(&h.entity.name)),
+                    ("description", h.entity.description.as_deref()),
+                ])
+            });
 
-    HttpResponse::Ok().json(dto_list)
+            SearchResultDto::new(PermissionDto::from(&h.entity), h.score, highlights)
+        })
+        .collect::<Vec<SearchResultDto<PermissionDto>>>();
+
+    // The pagination links are a convenience, so failing to count the permissions does not fail the request
+    let mut response = HttpResponse::Ok();
+    match pool
+        .services
+        .permission_service
+        .count(search.text.as_deref(), &pool.database)
+        .await
+    {
+        Ok(t) => {
+            response.insert_header((
+                header::LINK,
+                pagination::link_header(
+                    &req,
+                    limit.unwrap_or(pool.server_config.max_limit),
+                    page,
+                    t,
+                ),
+            ));
+        }
+        Err(e) => error!("Error while counting permissions: {}", e),
+    }
+
+    response.json(dto_list)
 }
 
 #[utoipa::path(
@@ -170,8 +282,7 @@ pub async fn find_all_permissions(
     )
 )]
 #[get("/{id}")]
-#[protect("CAN_READ_PERMISSION")]
-pub async fn find_by_id(path: web::Path<String>, pool: web::Data<Config>) -> HttpResponse {
+pub async fn find_by_id(path: ObjectIdPath, pool: web::Data<Config>) -> HttpResponse {
     let res = match pool
         .services
         .permission_service
@@ -184,8 +295,7 @@ pub async fn find_by_id(path: web::Path<String>, pool: web::Data<Config>) -> Htt
         },
         Err(e) => {
             error!("Error while finding Permission with ID {}: {}", path, e);
-            return HttpResponse::InternalServerError()
-                .json(InternalServerError::new(&e.to_string()));
+            return e.into_http_response();
         }
     };
 
@@ -211,17 +321,12 @@ pub async fn find_by_id(path: web::Path<String>, pool: web::Data<Config>) -> Htt
     )
 )]
 #[put("/{id}")]
-#[protect("CAN_UPDATE_PERMISSION")]
 pub async fn update_permission(
-    path: web::Path<String>,
-    update: web::Json<UpdatePermission>,
+    path: ObjectIdPath,
+    update: SanitizedJson<UpdatePermission>,
     pool: web::Data<Config>,
     req: HttpRequest,
 ) -> HttpResponse {
-    if update.name.is_empty() {
-        return HttpResponse::BadRequest().json(BadRequest::new("Empty name"));
-    }
-
     let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
         Some(e) => e,
         None => {
@@ -246,15 +351,21 @@ pub async fn update_permission(
         }
         Err(e) => {
             error!("Error while finding Permission with ID {}: {}", path, e);
-            return HttpResponse::InternalServerError()
-                .json(InternalServerError::new(&e.to_string()));
+            return e.into_http_response();
         }
     };
 
     let update = update.into_inner();
 
+    let old_name = permission.name.clone();
     permission.name = update.name;
     permission.description = update.description;
+    if let Some(d) = update.dependencies {
+        permission.dependencies = match parse_dependencies(permission.id, d, &pool).await {
+            Ok(d) => d,
+            Err(e) => return e,
+        };
+    }
 
     match pool
         .services
@@ -267,16 +378,22 @@ pub async fn update_permission(
         )
         .await
     {
-        Ok(p) => HttpResponse::Ok().json(PermissionDto::from(p)),
+        Ok(p) => {
+            if p.name != old_name {
+                pool.services
+                    .publish_invalidation(InvalidationEvent::PermissionRenamed {
+                        id: p.id.to_hex(),
+                        old_name,
+                        new_name: p.name.clone(),
+                    })
+                    .await;
+            }
+
+            HttpResponse::Ok().json(PermissionDto::from(p))
+        }
         Err(e) => {
             error!("Error while updating Permission with ID {}: {}", path, e);
-            match e {
-                Error::NameAlreadyTaken => {
-                    HttpResponse::BadRequest().json(BadRequest::new(&e.to_string()))
-                }
-                _ => HttpResponse::InternalServerError()
-                    .json(InternalServerError::new(&e.to_string())),
-            }
+            e.into_http_response()
         }
     }
 }
@@ -298,9 +415,8 @@ pub async fn update_permission(
     )
 )]
 #[delete("/{id}")]
-#[protect("CAN_DELETE_PERMISSION")]
 pub async fn delete_permission(
-    path: web::Path<String>,
+    path: ObjectIdPath,
     pool: web::Data<Config>,
     req: HttpRequest,
 ) -> HttpResponse {
@@ -325,12 +441,108 @@ pub async fn delete_permission(
         .await;
     match res {
         Ok(_) => HttpResponse::Ok().finish(),
-        Err(e) => match e {
-            Error::PermissionNotFound(_) => HttpResponse::NotFound().finish(),
-            _ => {
-                error!("Error while deleting Permission with ID {}: {}", path, e);
-                HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
-            }
-        },
+        Err(e) => {
+            error!("Error while deleting Permission with ID {}: {}", path, e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::permission::permission_repository::Error as PermissionError;
+    use crate::test_support::{self, MockServices};
+    use actix_web::http::StatusCode;
+    use actix_web::{test, App};
+
+    #[actix_web::test]
+    async fn find_by_id_returns_not_found_for_unknown_permission() {
+        let mut mocks = MockServices::new();
+        mocks
+            .permission_service
+            .expect_find_by_id()
+            .returning(|_, _| Ok(None));
+
+        let config = test_support::config(mocks).await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(config))
+                .service(web::scope("/permissions").service(find_by_id)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/permissions/{}", ObjectId::new().to_hex()))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_web::test]
+    async fn create_returns_bad_request_for_taken_name() {
+        let user = test_support::user();
+        let user_id = user.id;
+
+        let mut mocks = MockServices::new();
+        mocks.with_user(user);
+        mocks
+            .permission_service
+            .expect_create()
+            .returning(|_, _, _, _| Err(PermissionError::NameAlreadyTaken));
+
+        let config = test_support::config(mocks).await;
+        let token = test_support::bearer(&config, &user_id, 0);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(config))
+                .service(web::scope("/permissions").service(create_permission)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/permissions/")
+            .insert_header((header::AUTHORIZATION, token))
+            .set_json(serde_json::json!({ "name": "CAN_READ" }))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_web::test]
+    async fn create_returns_internal_server_error_for_database_error() {
+        let user = test_support::user();
+        let user_id = user.id;
+
+        let mut mocks = MockServices::new();
+        mocks.with_user(user);
+        mocks
+            .permission_service
+            .expect_create()
+            .returning(|_, _, _, _| {
+                Err(PermissionError::MongoDb(mongodb::error::Error::from(
+                    std::io::ErrorKind::Other,
+                )))
+            });
+
+        let config = test_support::config(mocks).await;
+        let token = test_support::bearer(&config, &user_id, 0);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(config))
+                .service(web::scope("/permissions").service(create_permission)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/permissions/")
+            .insert_header((header::AUTHORIZATION, token))
+            .set_json(serde_json::json!({ "name": "CAN_READ" }))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
     }
 }
@@ -1,19 +1,32 @@
 use crate::configuration::config::Config;
 use crate::errors::bad_request::BadRequest;
 use crate::errors::internal_server_error::InternalServerError;
+use crate::errors::into_http_response::IntoHttpResponse;
+use crate::errors::invalid_id::parse_object_id;
 use crate::repository::permission::permission_repository::Error as PermissionError;
-use crate::repository::role::role_model::Role;
-use crate::repository::role::role_repository::Error;
+use crate::repository::role::role_filter::RoleFilter;
+use crate::repository::role::role_model::{PermissionExpiration, Role};
+use crate::repository::role_change::role_change_model::RoleChangeKind;
+use crate::repository::search_hit::SearchHit;
+use crate::services::event::invalidation_event::InvalidationEvent;
+use crate::web::controller::user::user_controller::parse_date_time;
 use crate::web::dto::permission::permission_dto::PermissionDto;
 use crate::web::dto::role::create_role::CreateRole;
 use crate::web::dto::role::role_dto::RoleDto;
+use crate::web::dto::role::role_search::RoleSearchRequest;
 use crate::web::dto::role::update_role::UpdateRole;
-use crate::web::dto::search::search_request::SearchRequest;
+use crate::web::dto::role_change::role_change_dto::RoleChangeDto;
+use crate::web::dto::search::search_result_dto::{highlighter_for, SearchResultDto};
+use crate::web::extractors::object_id_path::ObjectIdPath;
+use crate::web::extractors::sanitized_json::SanitizedJson;
 use crate::web::extractors::user_id_extractor;
+use crate::web::pagination;
+use actix_web::http::header;
 use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse};
-use actix_web_grants::protect;
+use chrono::Utc;
 use log::error;
 use mongodb::bson::oid::ObjectId;
+use std::collections::HashMap;
 
 /// # Summary
 ///
@@ -40,12 +53,7 @@ pub async fn get_role_dto_from_role(
     config: &Config,
 ) -> Result<RoleDto, PermissionError> {
     let mut role_dto = RoleDto::from(role.clone());
-    if role.permissions.is_some() {
-        let mut oid_vec: Vec<String> = vec![];
-        for oid in role.permissions.unwrap() {
-            oid_vec.push(oid.to_hex());
-        }
-
+    if let Some(oid_vec) = role.permissions {
         role_dto.permissions = match find_permission_dto_from_permissions(oid_vec, config).await {
             Ok(d) => d,
             Err(e) => return Err(e),
@@ -68,7 +76,7 @@ pub async fn get_role_dto_from_role(
 /// # Example
 ///
 /// ```
-/// let permissions = vec!["permission1".to_string(), "permission2".to_string()];
+/// let permissions = vec![ObjectId::new(), ObjectId::new()];
 /// let pool = web::Data::new(Config::new());
 ///
 /// let permission_dto_list = find_permission_dto_from_permissions(permissions, &pool);
@@ -79,7 +87,7 @@ pub async fn get_role_dto_from_role(
 /// * `Option<Vec<PermissionDto>` - The optional vector of PermissionDto
 /// * `PermissionError` - The PermissionError that occurred
 pub async fn find_permission_dto_from_permissions(
-    permissions: Vec<String>,
+    permissions: Vec<ObjectId>,
     config: &Config,
 ) -> Result<Option<Vec<PermissionDto>>, PermissionError> {
     let mut permission_dto_list: Vec<PermissionDto> = vec![];
@@ -156,12 +164,144 @@ pub async fn validate_permissions(
     Ok(())
 }
 
+/// # Summary
+///
+/// Resolve the dependencies of the Permissions of a Role. Depending on the PermissionConfig, missing dependencies are
+/// either added to the Permissions, including the dependencies of those dependencies, or rejected.
+///
+/// # Arguments
+///
+/// * `permissions` - The IDs of the Permissions of the Role.
+/// * `pool` - The actix-web shared data
+///
+/// # Example
+///
+/// ```
+/// resolve_permission_dependencies(&mut role.permissions, &pool).await?;
+/// ```
+///
+/// # Returns
+///
+/// * `Result<(), HttpResponse>` - Ok if the dependencies are satisfied, or a BadRequest response naming the first
+///   missing dependency.
+pub async fn resolve_permission_dependencies(
+    permissions: &mut Option<Vec<ObjectId>>,
+    pool: &web::Data<Config>,
+) -> Result<(), HttpResponse> {
+    let granted = match permissions {
+        Some(p) if !p.is_empty() => p,
+        _ => return Ok(()),
+    };
+
+    let mut pending: Vec<ObjectId> = granted.clone();
+    while !pending.is_empty() {
+        let found = match pool
+            .services
+            .permission_service
+            .find_by_id_vec(pending, &pool.database)
+            .await
+        {
+            Ok(d) => d,
+            Err(e) => {
+                error!("Error resolving Permission dependencies: {}", e);
+                return Err(e.into_http_response());
+            }
+        };
+
+        pending = vec![];
+        for p in found {
+            for d in p.dependencies {
+                if granted.contains(&d) || pending.contains(&d) {
+                    continue;
+                }
+
+                if !pool.permission_config.include_dependencies {
+                    return Err(HttpResponse::BadRequest().json(BadRequest::new(&format!(
+                        "Permission {} requires Permission {}",
+                        p.name,
+                        d.to_hex()
+                    ))));
+                }
+
+                pending.push(d);
+            }
+        }
+
+        granted.extend(pending.iter().cloned());
+    }
+
+    Ok(())
+}
+
+/// # Summary
+///
+/// Parse the expirations of the Permission grants of a Role. Every expiring Permission must be granted by the Role
+/// and must expire in the future.
+///
+/// # Arguments
+///
+/// * `expirations` - A map of Permission IDs to the RFC 3339 date and time at which their grant expires.
+/// * `permissions` - The Permissions that are granted by the Role.
+///
+/// # Example
+///
+/// ```
+/// let expirations = HashMap::from([(permission_id.to_hex(), String::from("2030-01-01T00:00:00Z"))]);
+///
+/// let res = parse_permission_expirations(expirations, &role.permissions);
+/// ```
+///
+/// # Returns
+///
+/// * `Result<Vec<PermissionExpiration>, HttpResponse>` - The expirations, or a BadRequest response if they are invalid.
+fn parse_permission_expirations(
+    expirations: HashMap<String, String>,
+    permissions: &Option<Vec<ObjectId>>,
+) -> Result<Vec<PermissionExpiration>, HttpResponse> {
+    let now = Utc::now();
+    let mut res: Vec<PermissionExpiration> = vec![];
+
+    for (id, expires_at) in expirations {
+        let permission = match parse_object_id("Permission ID", &id) {
+            Ok(d) => d,
+            Err(e) => return Err(e.into_http_response()),
+        };
+
+        if !permissions.iter().flatten().any(|p| p == &permission) {
+            return Err(HttpResponse::BadRequest().json(BadRequest::new(&format!(
+                "Permission {} is not granted by the role",
+                id
+            ))));
+        }
+
+        let expires_at = match parse_date_time("permissionExpirations", Some(expires_at))? {
+            Some(d) => d,
+            None => continue,
+        };
+
+        if expires_at <= now {
+            return Err(HttpResponse::BadRequest().json(BadRequest::new(&format!(
+                "The grant of Permission {} must expire in the future",
+                id
+            ))));
+        }
+
+        res.push(PermissionExpiration {
+            permission,
+            expires_at,
+        });
+    }
+
+    Ok(res)
+}
+
 #[utoipa::path(
     post,
     path = "/api/v1/roles/",
     request_body = CreateRole,
     responses(
         (status = 200, description = "OK", body = RoleDto),
+        (status = 202, description = "Accepted", body = RoleChangeDto),
         (status = 400, description = "Bad Request", body = BadRequest),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
     ),
@@ -171,16 +311,11 @@ pub async fn validate_permissions(
     )
 )]
 #[post("/")]
-#[protect("CAN_CREATE_ROLE")]
 pub async fn create(
-    role_dto: web::Json<CreateRole>,
+    role_dto: SanitizedJson<CreateRole>,
     pool: web::Data<Config>,
     req: HttpRequest,
 ) -> HttpResponse {
-    if role_dto.name.is_empty() {
-        return HttpResponse::BadRequest().json(BadRequest::new("Empty name"));
-    }
-
     let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
         Some(e) => e,
         None => {
@@ -190,7 +325,7 @@ pub async fn create(
         }
     };
 
-    let role_dto = role_dto.into_inner();
+    let mut role_dto = role_dto.into_inner();
     if role_dto.permissions.is_some() {
         match validate_permissions(role_dto.permissions.clone(), &pool).await {
             Ok(_) => (),
@@ -200,15 +335,32 @@ pub async fn create(
                         .json(BadRequest::new(&format!("Permission {} not found", r))),
                     _ => {
                         error!("Error validating permissions: {}", e);
-                        HttpResponse::InternalServerError()
-                            .json(InternalServerError::new(&e.to_string()))
+                        e.into_http_response()
                     }
                 };
             }
         };
     }
 
-    let role = Role::from(role_dto);
+    let permission_expirations = role_dto.permission_expirations.take();
+    let mut role = Role::from(role_dto);
+
+    if let Err(e) = resolve_permission_dependencies(&mut role.permissions, &pool).await {
+        return e;
+    }
+
+    if let Some(e) = permission_expirations {
+        role.permission_expirations = match parse_permission_expirations(e, &role.permissions) {
+            Ok(d) => d,
+            Err(e) => return e,
+        };
+    }
+
+    // A new Role is created without permissions if granting them must be approved by a second User
+    let mut empty = role.clone();
+    empty.permissions = None;
+    empty.permission_expirations = vec![];
+    let pending_change = defer_permission_change(&empty, &mut role, &pool);
 
     let res = match pool
         .services
@@ -224,21 +376,19 @@ pub async fn create(
         Ok(d) => d,
         Err(e) => {
             error!("Error creating Role: {}", e);
-            return match e {
-                Error::NameAlreadyTaken => {
-                    HttpResponse::BadRequest().json(BadRequest::new(&e.to_string()))
-                }
-                _ => HttpResponse::InternalServerError()
-                    .json(InternalServerError::new(&e.to_string())),
-            };
+            return e.into_http_response();
         }
     };
 
+    if let Some(change) = pending_change {
+        return request_permission_change(change, user_id, &pool).await;
+    }
+
     match get_role_dto_from_role(res, &pool).await {
         Ok(dto) => HttpResponse::Ok().json(dto),
         Err(e) => {
             error!("Error converting Role to RoleDto: {}", e);
-            HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
+            e.into_http_response()
         }
     }
 }
@@ -247,13 +397,18 @@ pub async fn create(
     get,
     path = "/api/v1/roles/",
     params(
-        ("text" = Option<String>, Query, description = "The text to search for", nullable = true),
+        ("text" = Option<String>, Query, description = "The text to search for in the name and description", nullable = true),
+        ("permission" = Option<String>, Query, description = "The ID or name of a Permission that the roles must grant", nullable = true),
+        ("createdAfter" = Option<String>, Query, description = "The RFC 3339 date and time after which the roles were created", nullable = true),
+        ("createdBefore" = Option<String>, Query, description = "The RFC 3339 date and time before which the roles were created", nullable = true),
         ("limit" = Option<i64>, Query, description = "The limit of roles to retrieve", nullable = true),
         ("page" = Option<i64>, Query, description = "The page", nullable = true),
+        ("highlight" = Option<bool>, Query, description = "Whether to highlight the search terms in the name and description", nullable = true),
     ),
     responses(
         (status = 200, description = "OK", body = Vec<RoleDto>),
         (status = 204, description = "No Content"),
+        (status = 400, description = "Bad Request", body = BadRequest),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
     ),
     tag = "Roles",
@@ -262,9 +417,9 @@ pub async fn create(
     )
 )]
 #[get("/")]
-#[protect("CAN_READ_ROLE")]
 pub async fn find_all_roles(
-    search: web::Query<SearchRequest>,
+    search: web::Query<RoleSearchRequest>,
+    req: HttpRequest,
     pool: web::Data<Config>,
 ) -> HttpResponse {
     let search = search.into_inner();
@@ -279,54 +434,121 @@ pub async fn find_all_roles(
         limit = Some(pool.server_config.max_limit);
     }
 
-    let res = match search.text {
-        Some(t) => match pool
+    let created_after = match parse_date_time("createdAfter", search.created_after) {
+        Ok(d) => d,
+        Err(e) => return e,
+    };
+
+    let created_before = match parse_date_time("createdBefore", search.created_before) {
+        Ok(d) => d,
+        Err(e) => return e,
+    };
+
+    let permission = match search.permission {
+        Some(p) => match ObjectId::parse_str(&p) {
+            Ok(oid) => Some(oid),
+            Err(_) => match pool
+                .services
+                .permission_service
+                .find_by_name(&p, &pool.database)
+                .await
+            {
+                Ok(Some(d)) => Some(d.id),
+                // No Role can grant a Permission that does not exist
+                Ok(None) => return HttpResponse::NoContent().finish(),
+                Err(e) => {
+                    error!("Error while finding Permission by name: {}", e);
+                    return e.into_http_response();
+                }
+            },
+        },
+        None => None,
+    };
+
+    let highlighter = highlighter_for(search.text.as_deref(), search.highlight);
+
+    let filter = RoleFilter {
+        text: search.text,
+        permission,
+        created_after,
+        created_before,
+    };
+
+    let res: Vec<SearchHit<Role>> = if filter.is_empty() {
+        match pool
             .services
             .role_service
-            .search(&t, limit, page, &pool.database)
+            .find_all(limit, page, &pool.database)
             .await
         {
-            Ok(d) => d,
+            Ok(d) => d.into_iter().map(SearchHit::from).collect(),
             Err(e) => {
-                error!("Error while searching for Roles: {}", e);
-                return HttpResponse::InternalServerError()
-                    .json(InternalServerError::new(&e.to_string()));
+                error!("Error while finding all Roles: {}", e);
+                return e.into_http_response();
             }
-        },
-        None => match pool
+        }
+    } else {
+        match pool
             .services
             .role_service
-            .find_all(limit, page, &pool.database)
+            .search(&filter, limit, page, &pool.database)
             .await
         {
             Ok(d) => d,
             Err(e) => {
-                error!("Error while finding all Roles: {}", e);
-                return HttpResponse::InternalServerError()
-                    .json(InternalServerError::new(&e.to_string()));
+                error!("Error while searching for Roles: {}", e);
+                return e.into_http_response();
             }
-        },
+        }
     };
 
     if res.is_empty() {
         return HttpResponse::NoContent().finish();
     }
 
-    let mut role_dto_list: Vec<RoleDto> = vec![];
-    for r in res {
-        let role_dto = match get_role_dto_from_role(r, &pool).await {
+    let mut role_dto_list: Vec<SearchResultDto<RoleDto>> = vec![];
+    for h in res {
+        let highlights = highlighter.as_ref().and_then(|l| {
+            l.highlight_fields(&[
+                ("name", Some(&h.entity.name)),
+                ("description", h.entity.description.as_deref()),
+            ])
+        });
+
+        let role_dto = match get_role_dto_from_role(h.entity, &pool).await {
             Ok(d) => d,
             Err(e) => {
                 error!("Error converting Role to RoleDto: {}", e);
-                return HttpResponse::InternalServerError()
-                    .json(InternalServerError::new(&e.to_string()));
+                return e.into_http_response();
             }
         };
 
-        role_dto_list.push(role_dto);
+        role_dto_list.push(SearchResultDto::new(role_dto, h.score, highlights));
+    }
+
+    // The pagination links are a convenience, so failing to count the roles does not fail the request
+    let mut response = HttpResponse::Ok();
+    match pool
+        .services
+        .role_service
+        .count(&filter, &pool.database)
+        .await
+    {
+        Ok(t) => {
+            response.insert_header((
+                header::LINK,
+                pagination::link_header(
+                    &req,
+                    limit.unwrap_or(pool.server_config.max_limit),
+                    page,
+                    t,
+                ),
+            ));
+        }
+        Err(e) => error!("Error while counting Roles: {}", e),
     }
 
-    HttpResponse::Ok().json(role_dto_list)
+    response.json(role_dto_list)
 }
 
 #[utoipa::path(
@@ -346,8 +568,7 @@ pub async fn find_all_roles(
     )
 )]
 #[get("/{id}")]
-#[protect("CAN_READ_ROLE")]
-pub async fn find_by_id(path: web::Path<String>, pool: web::Data<Config>) -> HttpResponse {
+pub async fn find_by_id(path: ObjectIdPath, pool: web::Data<Config>) -> HttpResponse {
     let res = match pool
         .services
         .role_service
@@ -360,16 +581,149 @@ pub async fn find_by_id(path: web::Path<String>, pool: web::Data<Config>) -> Htt
         },
         Err(e) => {
             error!("Error finding Role by ID {}: {}", path, e);
-            return HttpResponse::InternalServerError()
-                .json(InternalServerError::new(&e.to_string()));
+            return e.into_http_response();
         }
     };
 
-    match get_role_dto_from_role(res, &pool).await {
-        Ok(dto) => HttpResponse::Ok().json(dto),
+    let mut role_dto = match get_role_dto_from_role(res, &pool).await {
+        Ok(dto) => dto,
         Err(e) => {
             error!("Error converting Role to RoleDto: {}", e);
-            HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
+            return e.into_http_response();
+        }
+    };
+
+    match pool
+        .services
+        .user_service
+        .count_by_role(&role_dto.id, &pool.database)
+        .await
+    {
+        Ok(c) => role_dto.member_count = Some(c),
+        Err(e) => {
+            error!("Error counting Users by Role {}: {}", role_dto.id, e);
+            return e.into_http_response();
+        }
+    };
+
+    HttpResponse::Ok().json(role_dto)
+}
+
+/// # Summary
+///
+/// Check whether the permissions or permission expirations of a Role differ between two versions of it
+///
+/// # Arguments
+///
+/// * `previous` - The Role before the change
+/// * `next` - The Role after the change
+///
+/// # Example
+///
+/// ```
+/// let changed = permissions_changed(&previous, &role);
+/// ```
+///
+/// # Returns
+///
+/// * `bool` - True if the Role grants different permissions after the change
+fn permissions_changed(previous: &Role, next: &Role) -> bool {
+    let permissions = |r: &Role| {
+        let mut p: Vec<ObjectId> = r.permissions.clone().unwrap_or_default();
+        p.sort();
+        p.dedup();
+        p
+    };
+    let expirations = |r: &Role| {
+        let mut e: Vec<(ObjectId, i64)> = r
+            .permission_expirations
+            .iter()
+            .map(|e| (e.permission, e.expires_at.timestamp_millis()))
+            .collect();
+        e.sort();
+        e
+    };
+
+    permissions(previous) != permissions(next) || expirations(previous) != expirations(next)
+}
+
+/// # Summary
+///
+/// Take a change to the permissions of a Role if it must be approved by a second User, so that the Role is stored with
+/// the permissions that it granted before the change.
+///
+/// # Arguments
+///
+/// * `previous` - The Role before the change.
+/// * `role` - The Role after the change, whose permissions are reset to those of the previous Role if they are taken.
+/// * `pool` - The Config.
+///
+/// # Example
+///
+/// ```
+/// let pending_change = defer_permission_change(&previous, &mut role, &pool);
+/// ```
+///
+/// # Returns
+///
+/// * `Option<RoleChangeKind>` - The change that must be approved, or None if it can be applied right away.
+pub fn defer_permission_change(
+    previous: &Role,
+    role: &mut Role,
+    pool: &Config,
+) -> Option<RoleChangeKind> {
+    if !permissions_changed(previous, role)
+        || !pool.services.role_change_service.approval_required()
+    {
+        return None;
+    }
+
+    Some(RoleChangeKind::RolePermissions {
+        role_id: role.id,
+        permissions: std::mem::replace(&mut role.permissions, previous.permissions.clone()),
+        permission_expirations: std::mem::replace(
+            &mut role.permission_expirations,
+            previous.permission_expirations.clone(),
+        ),
+        previous_permissions: previous.permissions.clone(),
+        previous_permission_expirations: previous.permission_expirations.clone(),
+    })
+}
+
+/// # Summary
+///
+/// Request the approval of a change to the permissions of a Role that was taken by defer_permission_change.
+///
+/// # Arguments
+///
+/// * `change` - The change.
+/// * `requested_by` - The ID of the User that made the change.
+/// * `pool` - The Config.
+///
+/// # Example
+///
+/// ```
+/// return request_permission_change(change, user_id, &pool).await;
+/// ```
+///
+/// # Returns
+///
+/// * `HttpResponse` - The pending RoleChange, or the Error that occurred.
+pub async fn request_permission_change(
+    change: RoleChangeKind,
+    requested_by: ObjectId,
+    pool: &Config,
+) -> HttpResponse {
+    match pool
+        .services
+        .role_change_service
+        .request(change, requested_by, &pool.database)
+        .await
+    {
+        Ok(r) => HttpResponse::Accepted().json(RoleChangeDto::from(r)),
+        Err(e) => {
+            error!("Error requesting RoleChange: {}", e);
+            e.into_http_response()
         }
     }
 }
@@ -383,6 +737,7 @@ pub async fn find_by_id(path: web::Path<String>, pool: web::Data<Config>) -> Htt
     ),
     responses(
         (status = 200, description = "OK", body = RoleDto),
+        (status = 202, description = "Accepted", body = RoleChangeDto),
         (status = 400, description = "Bad Request", body = BadRequest),
         (status = 404, description = "Not Found"),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
@@ -393,19 +748,14 @@ pub async fn find_by_id(path: web::Path<String>, pool: web::Data<Config>) -> Htt
     )
 )]
 #[put("/{id}")]
-#[protect("CAN_UPDATE_ROLE")]
 pub async fn update(
-    path: web::Path<String>,
-    update: web::Json<UpdateRole>,
+    path: ObjectIdPath,
+    update: SanitizedJson<UpdateRole>,
     pool: web::Data<Config>,
     req: HttpRequest,
 ) -> HttpResponse {
     let update = update.into_inner();
 
-    if update.name.is_empty() {
-        return HttpResponse::BadRequest().json(BadRequest::new("Empty name"));
-    }
-
     let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
         Some(e) => e,
         None => {
@@ -427,8 +777,7 @@ pub async fn update(
         },
         Err(e) => {
             error!("Error finding Role by ID {}: {}", path, e);
-            return HttpResponse::InternalServerError()
-                .json(InternalServerError::new(&e.to_string()));
+            return e.into_http_response();
         }
     };
 
@@ -441,8 +790,7 @@ pub async fn update(
                         .json(BadRequest::new(&format!("Permission {} not found", r))),
                     _ => {
                         error!("Error validating permissions: {}", e);
-                        HttpResponse::InternalServerError()
-                            .json(InternalServerError::new(&e.to_string()))
+                        e.into_http_response()
                     }
                 };
             }
@@ -453,13 +801,9 @@ pub async fn update(
         Some(p) => {
             let mut oid_vec: Vec<ObjectId> = vec![];
             for oid in p {
-                match ObjectId::parse_str(&oid) {
+                match parse_object_id("Permission ID", &oid) {
                     Ok(d) => oid_vec.push(d),
-                    Err(e) => {
-                        error!("Error parsing ObjectId: {}", e);
-                        return HttpResponse::InternalServerError()
-                            .json(InternalServerError::new(&e.to_string()));
-                    }
+                    Err(e) => return e.into_http_response(),
                 };
             }
             Some(oid_vec)
@@ -467,9 +811,29 @@ pub async fn update(
         None => None,
     };
 
+    let previous = role.clone();
+    let old_name = role.name.clone();
     role.name = update.name;
     role.description = update.description;
     role.permissions = new_permissions;
+    if let Err(e) = resolve_permission_dependencies(&mut role.permissions, &pool).await {
+        return e;
+    }
+    match update.permission_expirations {
+        Some(e) => {
+            role.permission_expirations = match parse_permission_expirations(e, &role.permissions) {
+                Ok(d) => d,
+                Err(e) => return e,
+            }
+        }
+        None => role.retain_permission_expirations(),
+    };
+    if let Some(require_mfa) = update.require_mfa {
+        role.require_mfa = require_mfa;
+    }
+
+    // Permission changes wait for the approval of a second User, while the other fields are applied right away
+    let pending_change = defer_permission_change(&previous, &mut role, &pool);
 
     let res = match pool
         .services
@@ -485,21 +849,38 @@ pub async fn update(
         Ok(d) => d,
         Err(e) => {
             error!("Error updating Role: {}", e);
-            return match e {
-                Error::NameAlreadyTaken => {
-                    HttpResponse::BadRequest().json(BadRequest::new(&e.to_string()))
-                }
-                _ => HttpResponse::InternalServerError()
-                    .json(InternalServerError::new(&e.to_string())),
-            };
+            return e.into_http_response();
         }
     };
 
+    if res.name != old_name {
+        if let Err(e) = pool
+            .services
+            .user_service
+            .rename_role(res.id, &res.name, &pool.database)
+            .await
+        {
+            error!("Error renaming Role in UserSummaries: {}", e);
+        }
+
+        pool.services
+            .publish_invalidation(InvalidationEvent::RoleRenamed {
+                id: res.id.to_hex(),
+                old_name,
+                new_name: res.name.clone(),
+            })
+            .await;
+    }
+
+    if let Some(change) = pending_change {
+        return request_permission_change(change, user_id, &pool).await;
+    }
+
     match get_role_dto_from_role(res, &pool).await {
         Ok(dto) => HttpResponse::Ok().json(dto),
         Err(e) => {
             error!("Error converting Role to RoleDto: {}", e);
-            HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
+            e.into_http_response()
         }
     }
 }
@@ -521,12 +902,7 @@ pub async fn update(
     )
 )]
 #[delete("/{id}")]
-#[protect("CAN_DELETE_ROLE")]
-pub async fn delete(
-    path: web::Path<String>,
-    pool: web::Data<Config>,
-    req: HttpRequest,
-) -> HttpResponse {
+pub async fn delete(path: ObjectIdPath, pool: web::Data<Config>, req: HttpRequest) -> HttpResponse {
     let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
         Some(e) => e,
         None => {
@@ -549,12 +925,229 @@ pub async fn delete(
         .await
     {
         Ok(_) => HttpResponse::Ok().finish(),
-        Err(e) => match e {
-            Error::RoleNotFound(_) => HttpResponse::NotFound().finish(),
-            _ => {
-                error!("Error deleting Role: {}", e);
-                HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
-            }
-        },
+        Err(e) => {
+            error!("Error deleting Role: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::permission::permission_model::Permission;
+    use crate::repository::role::role_repository::Error as RoleError;
+    use crate::repository::role_change::role_change_model::RoleChange;
+    use crate::test_support::{self, MockServices};
+    use actix_web::http::StatusCode;
+    use actix_web::{test, App};
+
+    fn database_error() -> RoleError {
+        RoleError::MongoDb(mongodb::error::Error::from(std::io::ErrorKind::Other))
+    }
+
+    #[actix_web::test]
+    async fn find_by_id_returns_not_found_for_unknown_role() {
+        let mut mocks = MockServices::new();
+        mocks
+            .role_service
+            .expect_find_by_id()
+            .returning(|_, _| Ok(None));
+
+        let config = test_support::config(mocks).await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(config))
+                .service(web::scope("/roles").service(find_by_id)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/roles/{}", ObjectId::new().to_hex()))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_web::test]
+    async fn find_by_id_returns_internal_server_error_for_database_error() {
+        let mut mocks = MockServices::new();
+        mocks
+            .role_service
+            .expect_find_by_id()
+            .returning(|_, _| Err(database_error()));
+
+        let config = test_support::config(mocks).await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(config))
+                .service(web::scope("/roles").service(find_by_id)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/roles/{}", ObjectId::new().to_hex()))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[actix_web::test]
+    async fn create_returns_bad_request_for_taken_name() {
+        let user = test_support::user();
+        let user_id = user.id;
+
+        let mut mocks = MockServices::new();
+        mocks.with_user(user);
+        mocks
+            .role_service
+            .expect_create()
+            .returning(|_, _, _, _| Err(RoleError::NameAlreadyTaken));
+
+        let config = test_support::config(mocks).await;
+        let token = test_support::bearer(&config, &user_id, 0);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(config))
+                .service(web::scope("/roles").service(create)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/roles/")
+            .insert_header((header::AUTHORIZATION, token))
+            .set_json(serde_json::json!({ "name": "admin" }))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_web::test]
+    async fn delete_returns_not_found_for_unknown_role() {
+        let user = test_support::user();
+        let user_id = user.id;
+
+        let mut mocks = MockServices::new();
+        mocks.with_user(user);
+        mocks
+            .role_service
+            .expect_delete()
+            .returning(|id, _, _, _, _| Err(RoleError::RoleNotFound(String::from(id))));
+
+        let config = test_support::config(mocks).await;
+        let token = test_support::bearer(&config, &user_id, 0);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(config))
+                .service(web::scope("/roles").service(delete)),
+        )
+        .await;
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/roles/{}", ObjectId::new().to_hex()))
+            .insert_header((header::AUTHORIZATION, token))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_web::test]
+    async fn delete_returns_internal_server_error_for_database_error() {
+        let user = test_support::user();
+        let user_id = user.id;
+
+        let mut mocks = MockServices::new();
+        mocks.with_user(user);
+        mocks
+            .role_service
+            .expect_delete()
+            .returning(|_, _, _, _, _| Err(database_error()));
+
+        let config = test_support::config(mocks).await;
+        let token = test_support::bearer(&config, &user_id, 0);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(config))
+                .service(web::scope("/roles").service(delete)),
+        )
+        .await;
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/roles/{}", ObjectId::new().to_hex()))
+            .insert_header((header::AUTHORIZATION, token))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[actix_web::test]
+    async fn create_defers_permissions_when_approval_is_required() {
+        let user = test_support::user();
+        let user_id = user.id;
+        let permission = Permission::new(String::from("CAN_READ_ROLE"), None);
+        let permission_id = permission.id;
+
+        let mut mocks = MockServices::new();
+        mocks.with_user(user);
+        mocks
+            .permission_service
+            .expect_find_by_id()
+            .returning(move |_, _| Ok(Some(permission.clone())));
+        mocks
+            .permission_service
+            .expect_find_by_id_vec()
+            .returning(|_, _| Ok(vec![]));
+        mocks
+            .role_change_service
+            .expect_approval_required()
+            .return_const(true);
+        mocks
+            .role_service
+            .expect_create()
+            .withf(|role, _, _, _| role.permissions.is_none())
+            .times(1)
+            .returning(|role, _, _, _| Ok(role));
+        mocks
+            .role_change_service
+            .expect_request()
+            .withf(move |change, requested_by, _| {
+                *requested_by == user_id
+                    && matches!(
+                        change,
+                        RoleChangeKind::RolePermissions {
+                            permissions: Some(p),
+                            previous_permissions: None,
+                            ..
+                        } if p == &vec![permission_id]
+                    )
+            })
+            .times(1)
+            .returning(|change, requested_by, _| Ok(RoleChange::new(change, requested_by)));
+
+        let config = test_support::config(mocks).await;
+        let token = test_support::bearer(&config, &user_id, 0);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(config))
+                .service(web::scope("/roles").service(create)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/roles/")
+            .insert_header((header::AUTHORIZATION, token))
+            .set_json(serde_json::json!({
+                "name": "reader",
+                "permissions": [permission_id.to_hex()],
+            }))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+
+        assert_eq!(res.status(), StatusCode::ACCEPTED);
     }
 }
@@ -0,0 +1,143 @@
+use crate::configuration::config::Config;
+use crate::errors::into_http_response::IntoHttpResponse;
+use crate::repository::role_change::role_change_model::RoleChangeKind;
+use crate::services::event::security_event::SecurityEvent;
+use crate::web::dto::role_change::role_change_dto::RoleChangeDto;
+use crate::web::extractors::object_id_path::ObjectIdPath;
+use crate::web::extractors::user_id_extractor;
+use actix_web::{get, post, web, HttpRequest, HttpResponse};
+use log::error;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/role-changes/",
+    responses(
+        (status = 200, description = "OK", body = [RoleChangeDto]),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Role changes",
+    security(
+        ("Token" = [])
+    )
+)]
+#[get("/")]
+pub async fn find_pending(pool: web::Data<Config>) -> HttpResponse {
+    match pool
+        .services
+        .role_change_service
+        .find_pending(&pool.database)
+        .await
+    {
+        Ok(r) => HttpResponse::Ok().json(
+            r.into_iter()
+                .map(RoleChangeDto::from)
+                .collect::<Vec<RoleChangeDto>>(),
+        ),
+        Err(e) => {
+            error!("Error finding pending RoleChanges: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/role-changes/{id}/approve/",
+    params(
+        ("id" = String, Path, description = "The ID of the RoleChange"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = RoleChangeDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Role changes",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/{id}/approve/")]
+pub async fn approve(
+    path: ObjectIdPath,
+    req: HttpRequest,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(e) => e,
+        None => return HttpResponse::Forbidden().finish(),
+    };
+
+    match pool
+        .services
+        .role_change_service
+        .approve(
+            &path,
+            user_id,
+            &pool.database,
+            &pool.services.role_service,
+            &pool.services.user_service,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(r) => {
+            if let RoleChangeKind::AdminGrant { user_id, role_id } = &r.change {
+                pool.services
+                    .publish_security_event(SecurityEvent::RoleGranted {
+                        user_id: user_id.to_hex(),
+                        role_id: role_id.to_hex(),
+                        actor_id: Some(r.reviewed_by.unwrap_or(r.requested_by).to_hex()),
+                    })
+                    .await;
+            }
+
+            HttpResponse::Ok().json(RoleChangeDto::from(r))
+        }
+        Err(e) => {
+            error!("Error approving RoleChange {}: {}", path, e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/role-changes/{id}/reject/",
+    params(
+        ("id" = String, Path, description = "The ID of the RoleChange"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = RoleChangeDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Role changes",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/{id}/reject/")]
+pub async fn reject(path: ObjectIdPath, req: HttpRequest, pool: web::Data<Config>) -> HttpResponse {
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(e) => e,
+        None => return HttpResponse::Forbidden().finish(),
+    };
+
+    match pool
+        .services
+        .role_change_service
+        .reject(&path, user_id, &pool.database)
+        .await
+    {
+        Ok(r) => HttpResponse::Ok().json(RoleChangeDto::from(r)),
+        Err(e) => {
+            error!("Error rejecting RoleChange {}: {}", path, e);
+            e.into_http_response()
+        }
+    }
+}
@@ -1 +1,2 @@
+pub mod mfa_controller;
 pub mod user_controller;
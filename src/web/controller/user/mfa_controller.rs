@@ -0,0 +1,752 @@
+use crate::configuration::config::Config;
+use crate::errors::bad_request::BadRequest;
+use crate::errors::internal_server_error::InternalServerError;
+use crate::errors::into_http_response::IntoHttpResponse;
+use crate::repository::credential::credential_ceremony::CeremonyState;
+use crate::repository::credential::credential_model::Credential;
+use crate::repository::user::user_model::User;
+use crate::repository::user::user_totp::UserTotp;
+use crate::services::mfa::recovery_code::{generate_recovery_codes, hash_recovery_code};
+use crate::services::mfa::totp_service::TotpService;
+use crate::web::dto::mfa::credential_dto::CredentialDto;
+use crate::web::dto::mfa::finish_passkey_registration::FinishPasskeyRegistration;
+use crate::web::dto::mfa::passkey_challenge_dto::PasskeyChallengeDto;
+use crate::web::dto::mfa::passkey_registration_request::PasskeyRegistrationRequest;
+use crate::web::dto::mfa::recovery_code_count_dto::RecoveryCodeCountDto;
+use crate::web::dto::mfa::recovery_codes_dto::RecoveryCodesDto;
+use crate::web::dto::mfa::totp_code_request::TotpCodeRequest;
+use crate::web::dto::mfa::totp_enrollment_dto::TotpEnrollmentDto;
+use crate::web::extractors::sanitized_json::SanitizedJson;
+use crate::web::extractors::user_id_extractor;
+use actix_web::{delete, get, post, web, HttpRequest, HttpResponse};
+use chrono::Utc;
+use log::error;
+
+/// # Summary
+///
+/// Find the User that sent the request
+///
+/// # Arguments
+///
+/// * `req` - The HttpRequest
+/// * `pool` - The database connection pool
+///
+/// # Returns
+///
+/// * `Result<User, HttpResponse>` - The User, or the HttpResponse that should be returned if it cannot be found
+async fn find_current_user(req: &HttpRequest, pool: &Config) -> Result<User, HttpResponse> {
+    let user_id = match user_id_extractor::get_user_id_from_token(req, pool).await {
+        Some(u) => u,
+        None => return Err(HttpResponse::Forbidden().finish()),
+    };
+
+    match pool
+        .services
+        .user_service
+        .find_by_id(&user_id.to_hex(), &pool.database)
+        .await
+    {
+        Ok(Some(u)) => Ok(u),
+        Ok(None) => Err(HttpResponse::Forbidden().finish()),
+        Err(e) => {
+            error!("Failed to find user by ID: {}", e);
+            Err(e.into_http_response())
+        }
+    }
+}
+
+/// # Summary
+///
+/// Verify a code of the TOTP authenticator of a User and mark its time step as used
+///
+/// # Arguments
+///
+/// * `user` - The User
+/// * `code` - The code to verify
+/// * `pool` - The database connection pool
+///
+/// # Returns
+///
+/// * `Result<i64, HttpResponse>` - The time step of the code, or the HttpResponse that should be returned if it is invalid
+async fn verify_code(user: &User, code: &str, pool: &Config) -> Result<i64, HttpResponse> {
+    let totp = match &user.totp {
+        Some(t) => t,
+        None => {
+            return Err(HttpResponse::BadRequest().json(BadRequest::new("TOTP is not enrolled")))
+        }
+    };
+
+    let secret = match pool.services.totp_service.decrypt(&totp.secret) {
+        Ok(s) => s,
+        Err(e) => {
+            error!("Failed to decrypt TOTP secret: {}", e);
+            return Err(e.into_http_response());
+        }
+    };
+
+    let step = match pool
+        .services
+        .totp_service
+        .verify(&secret, code, Utc::now().timestamp())
+    {
+        Some(s) => s,
+        None => return Err(HttpResponse::BadRequest().json(BadRequest::new("Invalid code"))),
+    };
+
+    match pool
+        .services
+        .user_service
+        .use_totp_step(user.id, step, &pool.database)
+        .await
+    {
+        Ok(true) => Ok(step),
+        Ok(false) => Err(HttpResponse::BadRequest().json(BadRequest::new("Invalid code"))),
+        Err(e) => {
+            error!("Failed to store the used TOTP step: {}", e);
+            Err(e.into_http_response())
+        }
+    }
+}
+
+/// # Summary
+///
+/// Replace the recovery codes of a User with a new set of codes
+///
+/// # Arguments
+///
+/// * `user` - The User
+/// * `pool` - The database connection pool
+///
+/// # Returns
+///
+/// * `Result<Vec<String>, HttpResponse>` - The new recovery codes, or the HttpResponse that should be returned if they could not be stored
+async fn issue_recovery_codes(user: &User, pool: &Config) -> Result<Vec<String>, HttpResponse> {
+    let recovery_codes = generate_recovery_codes();
+
+    match pool
+        .services
+        .user_service
+        .set_recovery_codes(
+            &user.id.to_hex(),
+            recovery_codes
+                .iter()
+                .map(|c| hash_recovery_code(c))
+                .collect(),
+            Some(user.id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(_) => Ok(recovery_codes),
+        Err(e) => {
+            error!("Failed to store the recovery codes of the user: {}", e);
+            Err(e.into_http_response())
+        }
+    }
+}
+
+/// # Summary
+///
+/// Mark a User as enrolled in multi-factor authentication if it has a confirmed TOTP authenticator or a passkey, and
+/// as not enrolled otherwise. A User that becomes enrolled without recovery codes receives a new set of codes, and a
+/// User that is no longer enrolled loses its remaining codes
+///
+/// # Arguments
+///
+/// * `user` - The User, as it was stored after its second factors changed
+/// * `pool` - The database connection pool
+///
+/// # Returns
+///
+/// * `Result<Vec<String>, HttpResponse>` - The recovery codes that were issued, if any, or the HttpResponse that should be returned if the enrollment could not be updated
+async fn update_mfa_enrolled(user: &User, pool: &Config) -> Result<Vec<String>, HttpResponse> {
+    let mut enrolled = user.totp.as_ref().map_or(false, |t| t.confirmed);
+
+    if !enrolled && pool.services.webauthn_service.is_enabled() {
+        enrolled = match pool
+            .services
+            .credential_service
+            .find_by_user_id(user.id, &pool.database)
+            .await
+        {
+            Ok(c) => !c.is_empty(),
+            Err(e) => {
+                error!("Failed to find the passkeys of the user: {}", e);
+                return Err(e.into_http_response());
+            }
+        };
+    }
+
+    if enrolled != user.mfa_enrolled {
+        if let Err(e) = pool
+            .services
+            .user_service
+            .set_mfa_enrolled(
+                &user.id.to_hex(),
+                enrolled,
+                Some(user.id),
+                &pool.database,
+                &pool.services.audit_service,
+            )
+            .await
+        {
+            error!("Failed to update the MFA enrollment of the user: {}", e);
+            return Err(e.into_http_response());
+        }
+    }
+
+    if enrolled && user.recovery_codes.is_empty() {
+        return issue_recovery_codes(user, pool).await;
+    }
+
+    if !enrolled && !user.recovery_codes.is_empty() {
+        if let Err(e) = pool
+            .services
+            .user_service
+            .set_recovery_codes(
+                &user.id.to_hex(),
+                vec![],
+                Some(user.id),
+                &pool.database,
+                &pool.services.audit_service,
+            )
+            .await
+        {
+            error!("Failed to remove the recovery codes of the user: {}", e);
+            return Err(e.into_http_response());
+        }
+    }
+
+    Ok(vec![])
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/me/mfa/totp/",
+    responses(
+        (status = 200, description = "OK", body = TotpEnrollmentDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Users",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/me/mfa/totp/")]
+pub async fn enroll_totp(req: HttpRequest, pool: web::Data<Config>) -> HttpResponse {
+    let totp_service = &pool.services.totp_service;
+    if !totp_service.is_enabled() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let user = match find_current_user(&req, &pool).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    // A pending enrollment is replaced, but a confirmed authenticator has to be removed with a valid code first
+    if user.totp.as_ref().map_or(false, |t| t.confirmed) {
+        return HttpResponse::BadRequest().json(BadRequest::new("TOTP is already enrolled"));
+    }
+
+    let secret = totp_service.generate_secret();
+    let encrypted = match totp_service.encrypt(&secret) {
+        Ok(s) => s,
+        Err(e) => {
+            error!("Failed to encrypt TOTP secret: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    if let Err(e) = pool
+        .services
+        .user_service
+        .set_totp(
+            &user.id.to_hex(),
+            Some(UserTotp::new(encrypted)),
+            Some(user.id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        error!("Failed to store TOTP secret: {}", e);
+        return e.into_http_response();
+    }
+
+    HttpResponse::Ok().json(TotpEnrollmentDto {
+        secret: TotpService::encode_secret(&secret),
+        otpauth_uri: totp_service.otpauth_uri(&user.username, &secret),
+    })
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/me/mfa/totp/confirm/",
+    request_body = TotpCodeRequest,
+    responses(
+        (status = 200, description = "OK", body = RecoveryCodesDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Users",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/me/mfa/totp/confirm/")]
+pub async fn confirm_totp(
+    req: HttpRequest,
+    code_request: SanitizedJson<TotpCodeRequest>,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    if !pool.services.totp_service.is_enabled() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let user = match find_current_user(&req, &pool).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    let mut totp = match &user.totp {
+        Some(t) if !t.confirmed => t.clone(),
+        Some(_) => {
+            return HttpResponse::BadRequest().json(BadRequest::new("TOTP is already enrolled"))
+        }
+        None => return HttpResponse::BadRequest().json(BadRequest::new("TOTP is not enrolled")),
+    };
+
+    let step = match verify_code(&user, &code_request.into_inner().code, &pool).await {
+        Ok(s) => s,
+        Err(e) => return e,
+    };
+
+    // The code that confirmed the authenticator cannot be used to log in afterwards
+    totp.confirmed = true;
+    totp.last_step = Some(step);
+
+    let user = match pool
+        .services
+        .user_service
+        .set_totp(
+            &user.id.to_hex(),
+            Some(totp),
+            Some(user.id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(u) => u,
+        Err(e) => {
+            error!("Failed to confirm TOTP: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    // A User that already registered a passkey keeps its existing recovery codes
+    match update_mfa_enrolled(&user, &pool).await {
+        Ok(recovery_codes) => HttpResponse::Ok().json(RecoveryCodesDto { recovery_codes }),
+        Err(e) => e,
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/users/me/mfa/totp/",
+    request_body = TotpCodeRequest,
+    responses(
+        (status = 200, description = "OK"),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Users",
+    security(
+        ("Token" = [])
+    )
+)]
+#[delete("/me/mfa/totp/")]
+pub async fn remove_totp(
+    req: HttpRequest,
+    code_request: SanitizedJson<TotpCodeRequest>,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    if !pool.services.totp_service.is_enabled() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let user = match find_current_user(&req, &pool).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    if let Err(e) = verify_code(&user, &code_request.into_inner().code, &pool).await {
+        return e;
+    }
+
+    let user = match pool
+        .services
+        .user_service
+        .set_totp(
+            &user.id.to_hex(),
+            None,
+            Some(user.id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(u) => u,
+        Err(e) => {
+            error!("Failed to remove TOTP: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    // A User that registered a passkey stays enrolled
+    match update_mfa_enrolled(&user, &pool).await {
+        Ok(_) => HttpResponse::Ok().finish(),
+        Err(e) => e,
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/me/mfa/passkeys/",
+    responses(
+        (status = 200, description = "OK", body = [CredentialDto]),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Users",
+    security(
+        ("Token" = [])
+    )
+)]
+#[get("/me/mfa/passkeys/")]
+pub async fn find_passkeys(req: HttpRequest, pool: web::Data<Config>) -> HttpResponse {
+    if !pool.services.webauthn_service.is_enabled() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let user = match find_current_user(&req, &pool).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    match pool
+        .services
+        .credential_service
+        .find_by_user_id(user.id, &pool.database)
+        .await
+    {
+        Ok(c) => HttpResponse::Ok().json(
+            c.into_iter()
+                .map(CredentialDto::from)
+                .collect::<Vec<CredentialDto>>(),
+        ),
+        Err(e) => {
+            error!("Failed to find the passkeys of the user: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/me/mfa/passkeys/",
+    request_body = PasskeyRegistrationRequest,
+    responses(
+        (status = 200, description = "OK", body = PasskeyChallengeDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Users",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/me/mfa/passkeys/")]
+pub async fn start_passkey_registration(
+    req: HttpRequest,
+    registration_request: SanitizedJson<PasskeyRegistrationRequest>,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    let webauthn_service = &pool.services.webauthn_service;
+    if !webauthn_service.is_enabled() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let user = match find_current_user(&req, &pool).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    let credentials = match pool
+        .services
+        .credential_service
+        .find_by_user_id(user.id, &pool.database)
+        .await
+    {
+        Ok(c) => c,
+        Err(e) => {
+            error!("Failed to find the passkeys of the user: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    let (challenge, state) =
+        match webauthn_service.start_registration(user.id, &user.username, &credentials) {
+            Ok(r) => r,
+            Err(e) => {
+                error!("Failed to start passkey registration: {}", e);
+                return e.into_http_response();
+            }
+        };
+
+    let options = match serde_json::to_value(challenge) {
+        Ok(o) => o,
+        Err(e) => {
+            error!("Failed to serialize passkey registration options: {}", e);
+            return HttpResponse::InternalServerError().json(InternalServerError::new(
+                "Failed to start passkey registration",
+            ));
+        }
+    };
+
+    let state = CeremonyState::Registration {
+        name: registration_request.into_inner().name,
+        state,
+    };
+
+    match pool
+        .services
+        .credential_service
+        .start_ceremony(
+            user.id,
+            state,
+            webauthn_service.ceremony_expiration,
+            &pool.database,
+        )
+        .await
+    {
+        Ok(id) => HttpResponse::Ok().json(PasskeyChallengeDto {
+            ceremony_id: id.to_hex(),
+            options,
+        }),
+        Err(e) => {
+            error!("Failed to store passkey registration: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/me/mfa/passkeys/finish/",
+    request_body = FinishPasskeyRegistration,
+    responses(
+        (status = 200, description = "OK", body = CredentialDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Users",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/me/mfa/passkeys/finish/")]
+pub async fn finish_passkey_registration(
+    req: HttpRequest,
+    finish_request: SanitizedJson<FinishPasskeyRegistration>,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    let webauthn_service = &pool.services.webauthn_service;
+    if !webauthn_service.is_enabled() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let user = match find_current_user(&req, &pool).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    let finish_request = finish_request.into_inner();
+    let ceremony = match pool
+        .services
+        .credential_service
+        .take_ceremony(&finish_request.ceremony_id, &pool.database)
+        .await
+    {
+        Ok(Some(c)) => c,
+        Ok(None) => {
+            return HttpResponse::BadRequest().json(BadRequest::new(
+                "The passkey registration is invalid or has expired",
+            ))
+        }
+        Err(e) => {
+            error!("Failed to find passkey registration: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    let (name, state) = match ceremony.state {
+        CeremonyState::Registration { name, state } if ceremony.user_id == user.id => (name, state),
+        _ => {
+            return HttpResponse::BadRequest().json(BadRequest::new(
+                "The passkey registration is invalid or has expired",
+            ))
+        }
+    };
+
+    let passkey = match webauthn_service.finish_registration(&finish_request.credential, &state) {
+        Ok(p) => p,
+        Err(e) => {
+            error!("Failed to finish passkey registration: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    let credential = match pool
+        .services
+        .credential_service
+        .create(Credential::new(user.id, name, passkey), &pool.database)
+        .await
+    {
+        Ok(c) => c,
+        Err(e) => {
+            error!("Failed to store passkey: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    let recovery_codes = match update_mfa_enrolled(&user, &pool).await {
+        Ok(r) => r,
+        Err(e) => return e,
+    };
+
+    let mut credential_dto = CredentialDto::from(credential);
+    if !recovery_codes.is_empty() {
+        credential_dto.recovery_codes = Some(recovery_codes);
+    }
+
+    HttpResponse::Ok().json(credential_dto)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/users/me/mfa/passkeys/{id}/",
+    params(
+        ("id" = String, Path, description = "The ID of the passkey"),
+    ),
+    responses(
+        (status = 200, description = "OK"),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Users",
+    security(
+        ("Token" = [])
+    )
+)]
+#[delete("/me/mfa/passkeys/{id}/")]
+pub async fn remove_passkey(
+    path: web::Path<String>,
+    req: HttpRequest,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    if !pool.services.webauthn_service.is_enabled() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let user = match find_current_user(&req, &pool).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    if let Err(e) = pool
+        .services
+        .credential_service
+        .delete(&path.into_inner(), user.id, &pool.database)
+        .await
+    {
+        error!("Failed to remove passkey: {}", e);
+        return e.into_http_response();
+    }
+
+    match update_mfa_enrolled(&user, &pool).await {
+        Ok(_) => HttpResponse::Ok().finish(),
+        Err(e) => e,
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/me/mfa/recovery-codes/",
+    responses(
+        (status = 200, description = "OK", body = RecoveryCodeCountDto),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Users",
+    security(
+        ("Token" = [])
+    )
+)]
+#[get("/me/mfa/recovery-codes/")]
+pub async fn find_recovery_code_count(req: HttpRequest, pool: web::Data<Config>) -> HttpResponse {
+    match find_current_user(&req, &pool).await {
+        Ok(u) => HttpResponse::Ok().json(RecoveryCodeCountDto {
+            remaining: u.recovery_codes.len(),
+        }),
+        Err(e) => e,
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/me/mfa/recovery-codes/",
+    responses(
+        (status = 200, description = "OK", body = RecoveryCodesDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Users",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/me/mfa/recovery-codes/")]
+pub async fn regenerate_recovery_codes(req: HttpRequest, pool: web::Data<Config>) -> HttpResponse {
+    let user = match find_current_user(&req, &pool).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    if !user.mfa_enrolled {
+        return HttpResponse::BadRequest().json(BadRequest::new("MFA is not enrolled"));
+    }
+
+    // The codes that were issued before are replaced, so that codes that were lost or exposed stop working
+    match issue_recovery_codes(&user, &pool).await {
+        Ok(recovery_codes) => HttpResponse::Ok().json(RecoveryCodesDto { recovery_codes }),
+        Err(e) => e,
+    }
+}
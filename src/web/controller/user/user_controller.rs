@@ -1,54 +1,61 @@
 use crate::configuration::config::Config;
 use crate::errors::bad_request::BadRequest;
 use crate::errors::internal_server_error::InternalServerError;
+use crate::errors::into_http_response::IntoHttpResponse;
+use crate::errors::invalid_id::parse_object_id;
+use crate::repository::organization::organization_model::Member;
 use crate::repository::permission::permission_repository::Error as PermissionError;
 use crate::repository::role::role_repository::Error as RoleError;
+use crate::repository::role_change::role_change_model::RoleChangeKind;
+use crate::repository::search_hit::SearchHit;
+use crate::repository::user::user_filter::UserFilter;
 use crate::repository::user::user_model::User;
 use crate::repository::user::user_repository::Error;
-use crate::services::password::password_service::PasswordService;
+use crate::repository::user::user_source::UserSource;
+use crate::repository::user::user_status::UserStatus;
+use crate::services::event::security_event::SecurityEvent;
 use crate::web::controller::role::role_controller::get_role_dto_from_role;
 use crate::web::dto::role::role_dto::RoleDto;
-use crate::web::dto::search::search_request::SearchRequest;
+use crate::web::dto::role_change::role_change_dto::RoleChangeDto;
+use crate::web::dto::search::search_result_dto::{highlighter_for, SearchResultDto};
 use crate::web::dto::user::create_user::CreateUser;
 use crate::web::dto::user::update_password::{AdminUpdatePassword, UpdatePassword};
 use crate::web::dto::user::update_user::{UpdateOwnUser, UpdateUser};
-use crate::web::dto::user::user_dto::UserDto;
+use crate::web::dto::user::user_dto::{gravatar_url, UserDto};
+use crate::web::dto::user::user_email::{AddUserEmail, UpdateUserEmail, UserEmailQuery};
+use crate::web::dto::user::user_search::UserSearchRequest;
+use crate::web::dto::user::user_summary::UserSummaryDto;
+use crate::web::extractors::sanitized_json::SanitizedJson;
 use crate::web::extractors::user_id_extractor;
+use crate::web::pagination;
+use actix_web::http::header;
+use actix_web::http::StatusCode;
 use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse};
-use actix_web_grants::protect;
-use argon2::PasswordHash;
-use log::error;
+use chrono::{DateTime, Utc};
+use log::{error, info, warn};
 use mongodb::bson::oid::ObjectId;
-use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
+#[derive(Debug, thiserror::Error)]
 pub enum ConvertError {
+    #[error(transparent)]
     RoleError(RoleError),
+    #[error(transparent)]
     PermissionError(PermissionError),
 }
 
-impl Display for ConvertError {
-    /// # Summary
-    ///
-    /// Display the error
-    ///
-    /// # Arguments
-    ///
-    /// * `f` - The formatter
-    ///
-    /// # Returns
-    ///
-    /// * `std::fmt::Result` - The result of the display
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+impl IntoHttpResponse for ConvertError {
+    fn status_code(&self) -> StatusCode {
         match self {
-            ConvertError::RoleError(e) => write!(f, "{}", e),
-            ConvertError::PermissionError(e) => write!(f, "{}", e),
+            ConvertError::RoleError(e) => e.status_code(),
+            ConvertError::PermissionError(e) => e.status_code(),
         }
     }
 }
 
 /// # Summary
 ///
-/// Validate whether the roles exist
+/// Validate whether the roles exist and are global roles
 ///
 /// # Arguments
 ///
@@ -65,7 +72,35 @@ impl Display for ConvertError {
 /// # Returns
 ///
 /// * `Result<(), RoleError>` - The result containing the () or the RoleError that occurred
-async fn validate_roles(roles: &Option<Vec<String>>, pool: &Config) -> Result<(), RoleError> {
+pub async fn validate_roles(roles: &Option<Vec<String>>, pool: &Config) -> Result<(), RoleError> {
+    validate_roles_in_scope(roles, None, pool).await
+}
+
+/// # Summary
+///
+/// Validate whether the roles exist and can be used within an Organization
+///
+/// # Arguments
+///
+/// * `roles` - The roles to validate
+/// * `organization_id` - The ID of the Organization, or None if only global roles are allowed
+/// * `pool` - The actix-web shared data
+///
+/// # Example
+///
+/// ```
+/// let roles = vec!["role1".to_string(), "role2".to_string()];
+/// let res = validate_roles_in_scope(&roles, Some(organization_id), &pool);
+/// ```
+///
+/// # Returns
+///
+/// * `Result<(), RoleError>` - The result containing the () or the RoleError that occurred
+pub async fn validate_roles_in_scope(
+    roles: &Option<Vec<String>>,
+    organization_id: Option<ObjectId>,
+    pool: &Config,
+) -> Result<(), RoleError> {
     if roles.is_none() {
         return Ok(());
     }
@@ -81,7 +116,8 @@ async fn validate_roles(roles: &Option<Vec<String>>, pool: &Config) -> Result<()
 
         match res {
             Ok(d) => {
-                if d.is_none() {
+                // Roles of other Organizations are treated as if they do not exist
+                if !d.is_some_and(|r| r.in_scope(organization_id.as_ref())) {
                     return Err(RoleError::RoleNotFound(role));
                 }
             }
@@ -116,6 +152,10 @@ async fn validate_roles(roles: &Option<Vec<String>>, pool: &Config) -> Result<()
 async fn convert_user_to_dto(user: User, pool: &Config) -> Result<UserDto, ConvertError> {
     let mut user_dto = UserDto::from(user.clone());
 
+    if pool.email_config.gravatar {
+        user_dto.avatar_url = user.email.as_deref().map(gravatar_url);
+    }
+
     if user.roles.is_some() {
         let mut role_vec: Vec<String> = vec![];
         for r in user.roles.unwrap() {
@@ -155,12 +195,413 @@ async fn convert_user_to_dto(user: User, pool: &Config) -> Result<UserDto, Conve
     Ok(user_dto)
 }
 
+/// # Summary
+///
+/// Convert a User to a UserDto, tolerating roles and permissions that cannot be resolved. Instead of failing, the UserDto
+/// contains the roles and permissions that could be resolved and a warning for each reference that could not be.
+///
+/// # Arguments
+///
+/// * `user` - The User to convert
+/// * `pool` - The actix-web shared data
+///
+/// # Example
+///
+/// ```
+/// let user = User::new("username", "password", "email");
+/// let user_dto = convert_user_to_dto_lenient(user, &pool).await;
+/// ```
+///
+/// # Returns
+///
+/// * `UserDto` - The UserDto, of which the warnings describe the references that could not be resolved
+async fn convert_user_to_dto_lenient(user: User, pool: &Config) -> UserDto {
+    let mut user_dto = UserDto::from(user.clone());
+
+    if pool.email_config.gravatar {
+        user_dto.avatar_url = user.email.as_deref().map(gravatar_url);
+    }
+
+    let role_ids = match user.roles {
+        Some(r) if !r.is_empty() => r,
+        _ => return user_dto,
+    };
+
+    let roles = match pool
+        .services
+        .role_service
+        .find_by_id_vec(
+            role_ids.iter().map(|r| r.to_hex()).collect(),
+            &pool.database,
+        )
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => {
+            warn!("Failed to find the roles of User {}: {}", user.id, e);
+            user_dto
+                .warnings
+                .push(format!("Failed to find the roles of the user: {}", e));
+            return user_dto;
+        }
+    };
+
+    for id in &role_ids {
+        if !roles.iter().any(|r| r.id == *id) {
+            user_dto
+                .warnings
+                .push(format!("Role {} could not be found", id.to_hex()));
+        }
+    }
+
+    let mut role_dto_list: Vec<RoleDto> = vec![];
+    for r in roles {
+        let role_dto = match get_role_dto_from_role(r.clone(), pool).await {
+            Ok(d) => d,
+            Err(e) => {
+                warn!("Failed to find the permissions of Role {}: {}", r.id, e);
+                user_dto.warnings.push(format!(
+                    "Failed to find the permissions of role {}: {}",
+                    r.name, e
+                ));
+                role_dto_list.push(RoleDto::from(r));
+                continue;
+            }
+        };
+
+        let resolved: Vec<&str> = role_dto
+            .permissions
+            .iter()
+            .flatten()
+            .map(|p| p.id.as_str())
+            .collect();
+
+        for id in r.permissions.iter().flatten() {
+            if !resolved.contains(&id.to_hex().as_str()) {
+                user_dto.warnings.push(format!(
+                    "Permission {} of role {} could not be found",
+                    id.to_hex(),
+                    r.name
+                ));
+            }
+        }
+
+        role_dto_list.push(role_dto);
+    }
+
+    if !role_dto_list.is_empty() {
+        user_dto.roles = Some(role_dto_list);
+    }
+
+    user_dto
+}
+
+/// # Summary
+///
+/// Parse an optional RFC 3339 date and time of a request.
+///
+/// # Arguments
+///
+/// * `field` - The name of the field, used in the error message.
+/// * `value` - The value of the field.
+///
+/// # Example
+///
+/// ```
+/// let active_from = parse_date_time("activeFrom", user_dto.active_from)?;
+/// ```
+///
+/// # Returns
+///
+/// * `Result<Option<DateTime<Utc>>, HttpResponse>` - The date and time, or a BadRequest response if it is invalid.
+pub fn parse_date_time(
+    field: &str,
+    value: Option<String>,
+) -> Result<Option<DateTime<Utc>>, HttpResponse> {
+    match value {
+        Some(v) => match DateTime::parse_from_rfc3339(&v) {
+            Ok(d) => Ok(Some(d.with_timezone(&Utc))),
+            Err(_) => Err(HttpResponse::BadRequest().json(BadRequest::new(&format!(
+                "{} must be an RFC 3339 date and time",
+                field
+            )))),
+        },
+        None => Ok(None),
+    }
+}
+
+/// # Summary
+///
+/// Send a notification email to a User, if the User has an email address and an EmailSender is registered.
+/// A failure to send the notification is logged, but does not fail the request.
+///
+/// # Arguments
+///
+/// * `user` - The User to notify.
+/// * `subject` - The subject of the notification.
+/// * `body` - The body of the notification.
+/// * `pool` - The Config.
+///
+/// # Example
+///
+/// ```
+/// notify_user(&user, "Account approved", "Your account was approved", &pool).await;
+/// ```
+async fn notify_user(user: &User, subject: &str, body: &str, pool: &Config) {
+    let email = match &user.email {
+        Some(e) => e,
+        None => return,
+    };
+
+    match &pool.services.email_sender {
+        Some(s) => {
+            if let Err(e) = s.send(email, subject, body).await {
+                error!("Failed to send notification to User {}: {}", user.id, e);
+            }
+        }
+        None => info!(
+            "No EmailSender registered, skipping notification to User {}",
+            user.id
+        ),
+    }
+}
+
+/// # Summary
+///
+/// Queue the welcome email for a new User, if the User has an email address and an EmailSender is registered.
+/// The email is sent by the EmailWorker, so that a slow or unavailable mail server does not delay the request.
+/// A failure to queue the email is logged, but does not fail the request.
+///
+/// # Arguments
+///
+/// * `user` - The User that was created.
+/// * `pool` - The Config.
+///
+/// # Example
+///
+/// ```
+/// enqueue_welcome_email(&user, &pool).await;
+/// ```
+pub async fn enqueue_welcome_email(user: &User, pool: &Config) {
+    let email = match &user.email {
+        Some(e) => e,
+        None => return,
+    };
+
+    if pool.services.email_sender.is_none() {
+        info!(
+            "No EmailSender registered, skipping welcome email to User {}",
+            user.id
+        );
+        return;
+    }
+
+    let (subject, body) = pool.email_queue_config.welcome_email(&user.username);
+    if let Err(e) = pool
+        .services
+        .email_job_service
+        .enqueue(email, &subject, &body, &pool.database)
+        .await
+    {
+        error!("Failed to queue welcome email to User {}: {}", user.id, e);
+    }
+}
+
+/// # Summary
+///
+/// Publish the SecurityEvents for the roles that were granted to a User and for locking the User.
+///
+/// # Arguments
+///
+/// * `user` - The User after the change.
+/// * `previous_roles` - The IDs of the roles that the User held before the change.
+/// * `previous_status` - The UserStatus before the change, or None if the User was just created.
+/// * `actor_id` - The ID of the User that made the change.
+/// * `pool` - The Config.
+///
+/// # Example
+///
+/// ```
+/// publish_security_events(&user, &previous_roles, Some(previous_status), user_id, &pool).await;
+/// ```
+async fn publish_security_events(
+    user: &User,
+    previous_roles: &[ObjectId],
+    previous_status: Option<UserStatus>,
+    actor_id: ObjectId,
+    pool: &Config,
+) {
+    for r in user.roles.iter().flatten() {
+        if !previous_roles.contains(r) {
+            pool.services
+                .publish_security_event(SecurityEvent::RoleGranted {
+                    user_id: user.id.to_hex(),
+                    role_id: r.to_hex(),
+                    actor_id: Some(actor_id.to_hex()),
+                })
+                .await;
+        }
+    }
+
+    if user.status == UserStatus::Locked && previous_status != Some(UserStatus::Locked) {
+        pool.services
+            .publish_security_event(SecurityEvent::AccountLocked {
+                user_id: user.id.to_hex(),
+                actor_id: Some(actor_id.to_hex()),
+            })
+            .await;
+    }
+}
+
+/// # Summary
+///
+/// Find a User that is pending approval.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the User.
+/// * `pool` - The Config.
+///
+/// # Example
+///
+/// ```
+/// let user = find_pending_user("id", &pool).await?;
+/// ```
+///
+/// # Returns
+///
+/// * `Result<User, HttpResponse>` - The pending User, or the HttpResponse that should be returned.
+async fn find_pending_user(id: &str, pool: &Config) -> Result<User, HttpResponse> {
+    let user = match pool
+        .services
+        .user_service
+        .find_by_id(id, &pool.database)
+        .await
+    {
+        Ok(Some(u)) => u,
+        Ok(None) => return Err(HttpResponse::NotFound().finish()),
+        Err(e) => {
+            error!("Error finding User: {}", e);
+            return Err(e.into_http_response());
+        }
+    };
+
+    if user.status != UserStatus::Pending {
+        return Err(HttpResponse::BadRequest().json(BadRequest::new(&format!(
+            "User {} is not pending approval",
+            id
+        ))));
+    }
+
+    Ok(user)
+}
+
+/// # Summary
+///
+/// Remove a new grant of the ADMIN role from the roles of a User if grants of the ADMIN role require the approval of a
+/// second User.
+///
+/// # Arguments
+///
+/// * `roles` - The roles that the User should hold after the change.
+/// * `previous_roles` - The IDs of the roles that the User held before the change.
+/// * `pool` - The Config.
+///
+/// # Example
+///
+/// ```
+/// let admin_grant = defer_admin_grant(&mut user.roles, &previous_roles, &pool).await?;
+/// ```
+///
+/// # Returns
+///
+/// * `Result<Option<ObjectId>, HttpResponse>` - The ID of the ADMIN role if its grant was removed, or the HttpResponse that should be returned.
+async fn defer_admin_grant(
+    roles: &mut Option<Vec<ObjectId>>,
+    previous_roles: &[ObjectId],
+    pool: &Config,
+) -> Result<Option<ObjectId>, HttpResponse> {
+    if !pool.services.role_change_service.approval_required() {
+        return Ok(None);
+    }
+
+    let admin_role = match pool
+        .services
+        .role_service
+        .find_by_name("ADMIN", &pool.database)
+        .await
+    {
+        Ok(Some(r)) => r,
+        Ok(None) => return Ok(None),
+        Err(e) => {
+            error!("Error finding the ADMIN Role: {}", e);
+            return Err(e.into_http_response());
+        }
+    };
+
+    match roles {
+        Some(r) if r.contains(&admin_role.id) && !previous_roles.contains(&admin_role.id) => {
+            r.retain(|id| *id != admin_role.id);
+            Ok(Some(admin_role.id))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// # Summary
+///
+/// Request the approval of a grant of the ADMIN role that was removed by defer_admin_grant.
+///
+/// # Arguments
+///
+/// * `user` - The User that should receive the ADMIN role.
+/// * `role_id` - The ID of the ADMIN role.
+/// * `requested_by` - The ID of the User that made the change.
+/// * `pool` - The Config.
+///
+/// # Example
+///
+/// ```
+/// return request_admin_grant(&user, role_id, user_id, &pool).await;
+/// ```
+///
+/// # Returns
+///
+/// * `HttpResponse` - An Accepted response with the pending RoleChange, or the error that occurred.
+async fn request_admin_grant(
+    user: &User,
+    role_id: ObjectId,
+    requested_by: ObjectId,
+    pool: &Config,
+) -> HttpResponse {
+    match pool
+        .services
+        .role_change_service
+        .request(
+            RoleChangeKind::AdminGrant {
+                user_id: user.id,
+                role_id,
+            },
+            requested_by,
+            &pool.database,
+        )
+        .await
+    {
+        Ok(r) => HttpResponse::Accepted().json(RoleChangeDto::from(r)),
+        Err(e) => {
+            error!("Error requesting RoleChange: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
 #[utoipa::path(
     post,
     path = "/api/v1/users/",
     request_body = CreateUser,
     responses(
         (status = 200, description = "OK", body = UserDto),
+        (status = 202, description = "Accepted", body = RoleChangeDto),
         (status = 400, description = "Bad Request", body = BadRequest),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
     ),
@@ -170,20 +611,11 @@ async fn convert_user_to_dto(user: User, pool: &Config) -> Result<UserDto, Conve
     )
 )]
 #[post("/")]
-#[protect("CAN_CREATE_USER")]
 pub async fn create(
-    user_dto: web::Json<CreateUser>,
+    user_dto: SanitizedJson<CreateUser>,
     pool: web::Data<Config>,
     req: HttpRequest,
 ) -> HttpResponse {
-    if user_dto.username.is_empty() {
-        return HttpResponse::BadRequest().json(BadRequest::new("Empty usernames are not allowed"));
-    }
-
-    if user_dto.password.is_empty() {
-        return HttpResponse::BadRequest().json(BadRequest::new("Empty passwords are not allowed"));
-    }
-
     let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
         Some(e) => e,
         None => {
@@ -204,18 +636,38 @@ pub async fn create(
                         .json(BadRequest::new(&format!("Role {} not found", r))),
                     _ => {
                         error!("Error validating roles: {}", e);
-                        HttpResponse::InternalServerError()
-                            .json(InternalServerError::new(&e.to_string()))
+                        e.into_http_response()
                     }
                 };
             }
         };
     }
 
+    let active_from = match parse_date_time("activeFrom", user_dto.active_from.clone()) {
+        Ok(d) => d,
+        Err(r) => return r,
+    };
+    let expires_at = match parse_date_time("expiresAt", user_dto.expires_at.clone()) {
+        Ok(d) => d,
+        Err(r) => return r,
+    };
+
     let mut user = User::from(user_dto);
+    user.active_from = active_from;
+    user.expires_at = expires_at;
+
+    let admin_grant = match defer_admin_grant(&mut user.roles, &[], &pool).await {
+        Ok(d) => d,
+        Err(e) => return e,
+    };
+
+    // Pre-provisioned accounts are activated by the UserScheduler once their activation date passes
+    if active_from.is_some_and(|a| a > Utc::now()) {
+        user.status = UserStatus::Pending;
+    }
 
-    let password_hash = match PasswordService::hash_password(user.password) {
-        Ok(e) => e.to_string(),
+    let password_hash = match pool.services.password_service.hash_password(&user.password) {
+        Ok(e) => e,
         Err(e) => {
             error!("Failed to hash password: {}", e);
             return HttpResponse::InternalServerError()
@@ -239,21 +691,22 @@ pub async fn create(
         Ok(d) => d,
         Err(e) => {
             error!("Error creating User: {}", e);
-            return match e {
-                Error::UsernameAlreadyTaken | Error::EmailAlreadyTaken | Error::InvalidEmail(_) => {
-                    HttpResponse::BadRequest().json(BadRequest::new(&e.to_string()))
-                }
-                _ => HttpResponse::InternalServerError()
-                    .json(InternalServerError::new(&e.to_string())),
-            };
+            return e.into_http_response();
         }
     };
 
+    publish_security_events(&res, &[], None, user_id, &pool).await;
+    enqueue_welcome_email(&res, &pool).await;
+
+    if let Some(role_id) = admin_grant {
+        return request_admin_grant(&res, role_id, user_id, &pool).await;
+    }
+
     match convert_user_to_dto(res, &pool).await {
         Ok(dto) => HttpResponse::Ok().json(dto),
         Err(e) => {
             error!("Error converting User to UserDto: {}", e);
-            HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
+            e.into_http_response()
         }
     }
 }
@@ -265,10 +718,16 @@ pub async fn create(
         ("text" = Option<String>, Query, description = "The text to search for", nullable = true),
         ("limit" = Option<i64>, Query, description = "The limit of users to retrieve", nullable = true),
         ("page" = Option<i64>, Query, description = "The page", nullable = true),
+        ("source" = Option<String>, Query, description = "How the users were created, such as registration, invitation, admin, directory, system, scim, social or cli", nullable = true),
+        ("emailMissing" = Option<bool>, Query, description = "Whether the users do or do not have an email address", nullable = true),
+        ("emailVerified" = Option<bool>, Query, description = "Whether the email address of the users is or is not verified", nullable = true),
+        ("summary" = Option<bool>, Query, description = "Whether to return the denormalized user summaries, which cannot be combined with the other filters", nullable = true),
+        ("highlight" = Option<bool>, Query, description = "Whether to highlight the search terms in the username, email address and names", nullable = true),
     ),
     responses(
         (status = 200, description = "OK", body = Vec<UserDto>),
         (status = 204, description = "No Content"),
+        (status = 400, description = "Bad Request", body = BadRequest),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
     ),
     tag = "Users",
@@ -277,8 +736,11 @@ pub async fn create(
     )
 )]
 #[get("/")]
-#[protect("CAN_READ_USER")]
-pub async fn find_all(search: web::Query<SearchRequest>, pool: web::Data<Config>) -> HttpResponse {
+pub async fn find_all(
+    search: web::Query<UserSearchRequest>,
+    req: HttpRequest,
+    pool: web::Data<Config>,
+) -> HttpResponse {
     let search = search.into_inner();
 
     let mut limit = search.limit;
@@ -291,54 +753,154 @@ pub async fn find_all(search: web::Query<SearchRequest>, pool: web::Data<Config>
         limit = Some(pool.server_config.max_limit);
     }
 
-    let res = match search.text {
-        Some(t) => match pool
+    let source = match search.source.as_deref().map(UserSource::from_str) {
+        Some(Ok(s)) => Some(s),
+        Some(Err(e)) => return HttpResponse::BadRequest().json(BadRequest::new(&e)),
+        None => None,
+    };
+
+    let highlighter = highlighter_for(search.text.as_deref(), search.highlight);
+
+    let filter = UserFilter {
+        text: search.text,
+        source,
+        email_missing: search.email_missing,
+        email_verified: search.email_verified,
+    };
+
+    if search.summary {
+        if !filter.is_empty() {
+            return HttpResponse::BadRequest().json(BadRequest::new(
+                "User summaries cannot be combined with other filters",
+            ));
+        }
+
+        let res = match pool
             .services
             .user_service
-            .search(&t, limit, page, &pool.database)
+            .find_summaries(limit, page, &pool.database)
             .await
         {
             Ok(d) => d,
             Err(e) => {
-                error!("Error while searching for Users: {}", e);
-                return HttpResponse::InternalServerError()
-                    .json(InternalServerError::new(&e.to_string()));
+                error!("Error while finding all UserSummaries: {}", e);
+                return e.into_http_response();
             }
-        },
-        None => match pool
-            .services
-            .user_service
-            .find_all(limit, page, &pool.database)
-            .await
-        {
-            Ok(d) => d,
+        };
+
+        if res.is_empty() {
+            return HttpResponse::NoContent().finish();
+        }
+
+        // Every User has a UserSummary, so the Users are counted instead
+        let mut response = HttpResponse::Ok();
+        if let Some(l) = user_pagination_links(&filter, &req, limit, page, &pool).await {
+            response.insert_header((header::LINK, l));
+        }
+
+        return response.json(
+            res.into_iter()
+                .map(UserSummaryDto::from)
+                .collect::<Vec<UserSummaryDto>>(),
+        );
+    }
+
+    let res: Vec<SearchHit<User>> = if filter.is_empty() {
+        match pool
+            .services
+            .user_service
+            .find_all(limit, page, &pool.database)
+            .await
+        {
+            Ok(d) => d.into_iter().map(SearchHit::from).collect(),
             Err(e) => {
                 error!("Error while finding all Users: {}", e);
-                return HttpResponse::InternalServerError()
-                    .json(InternalServerError::new(&e.to_string()));
+                return e.into_http_response();
             }
-        },
+        }
+    } else {
+        match pool
+            .services
+            .user_service
+            .search(&filter, limit, page, &pool.database)
+            .await
+        {
+            Ok(d) => d,
+            Err(e) => {
+                error!("Error while searching for Users: {}", e);
+                return e.into_http_response();
+            }
+        }
     };
 
     if res.is_empty() {
         return HttpResponse::NoContent().finish();
     }
 
-    let mut user_dto_list: Vec<UserDto> = vec![];
-    for u in res {
-        let user_dto = match convert_user_to_dto(u, &pool).await {
-            Ok(d) => d,
-            Err(e) => {
-                error!("Error converting User to UserDto: {}", e);
-                return HttpResponse::InternalServerError()
-                    .json(InternalServerError::new(&e.to_string()));
-            }
-        };
+    // A single User with a dangling Role or Permission should not fail the entire list
+    let mut user_dto_list: Vec<SearchResultDto<UserDto>> = vec![];
+    for h in res {
+        let highlights = highlighter.as_ref().and_then(|l| {
+            l.highlight_fields(&[
+                ("username", Some(&h.entity.username)),
+                ("email", h.entity.email.as_deref()),
+                ("firstName", h.entity.first_name.as_deref()),
+                ("lastName", h.entity.last_name.as_deref()),
+            ])
+        });
+
+        let user_dto = convert_user_to_dto_lenient(h.entity, &pool).await;
+        user_dto_list.push(SearchResultDto::new(user_dto, h.score, highlights));
+    }
 
-        user_dto_list.push(user_dto);
+    let mut response = HttpResponse::Ok();
+    if let Some(l) = user_pagination_links(&filter, &req, limit, page, &pool).await {
+        response.insert_header((header::LINK, l));
     }
 
-    HttpResponse::Ok().json(user_dto_list)
+    response.json(user_dto_list)
+}
+
+/// # Summary
+///
+/// Build the pagination links of a page of Users. The links are a convenience, so failing to count the Users does not
+/// fail the request.
+///
+/// # Arguments
+///
+/// * `filter` - The UserFilter of the request.
+/// * `req` - The HttpRequest.
+/// * `limit` - The effective limit of Users per page.
+/// * `page` - The requested page.
+/// * `pool` - The Config.
+///
+/// # Returns
+///
+/// * `Option<String>` - The value of the Link header, or None if the Users could not be counted.
+async fn user_pagination_links(
+    filter: &UserFilter,
+    req: &HttpRequest,
+    limit: Option<i64>,
+    page: Option<i64>,
+    pool: &Config,
+) -> Option<String> {
+    match pool
+        .services
+        .user_service
+        .count(filter, &pool.database)
+        .await
+    {
+        Ok(t) => Some(pagination::link_header(
+            req,
+            limit.unwrap_or(pool.server_config.max_limit),
+            page,
+            t,
+        )),
+        Err(e) => {
+            error!("Error while counting Users: {}", e);
+            None
+        }
+    }
 }
 
 #[utoipa::path(
@@ -358,7 +920,6 @@ pub async fn find_all(search: web::Query<SearchRequest>, pool: web::Data<Config>
     )
 )]
 #[get("/{id}")]
-#[protect("CAN_READ_USER")]
 pub async fn find_by_id(id: web::Path<String>, pool: web::Data<Config>) -> HttpResponse {
     let id = id.into_inner();
 
@@ -368,17 +929,13 @@ pub async fn find_by_id(id: web::Path<String>, pool: web::Data<Config>) -> HttpR
         .find_by_id(&id, &pool.database)
         .await
     {
-        Ok(d) => {
-            if d.is_some() {
-                d.unwrap()
-            } else {
-                return HttpResponse::NotFound().finish();
-            }
-        }
+        Ok(d) => match d {
+            Some(d) => d,
+            None => return HttpResponse::NotFound().finish(),
+        },
         Err(e) => {
             error!("Error finding User by ID {}: {}", id, e);
-            return HttpResponse::InternalServerError()
-                .json(InternalServerError::new(&e.to_string()));
+            return e.into_http_response();
         }
     };
 
@@ -386,7 +943,7 @@ pub async fn find_by_id(id: web::Path<String>, pool: web::Data<Config>) -> HttpR
         Ok(dto) => HttpResponse::Ok().json(dto),
         Err(e) => {
             error!("Error converting User to UserDto: {}", e);
-            HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
+            e.into_http_response()
         }
     }
 }
@@ -400,6 +957,7 @@ pub async fn find_by_id(id: web::Path<String>, pool: web::Data<Config>) -> HttpR
     ),
     responses(
         (status = 200, description = "OK", body = UserDto),
+        (status = 202, description = "Accepted", body = RoleChangeDto),
         (status = 400, description = "Bad Request", body = BadRequest),
         (status = 404, description = "Not Found"),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
@@ -410,10 +968,9 @@ pub async fn find_by_id(id: web::Path<String>, pool: web::Data<Config>) -> HttpR
     )
 )]
 #[put("/{id}")]
-#[protect("CAN_UPDATE_USER")]
 pub async fn update(
     id: web::Path<String>,
-    user_dto: web::Json<UpdateUser>,
+    user_dto: SanitizedJson<UpdateUser>,
     pool: web::Data<Config>,
     req: HttpRequest,
 ) -> HttpResponse {
@@ -434,24 +991,16 @@ pub async fn update(
         .find_by_id(&id, &pool.database)
         .await
     {
-        Ok(d) => {
-            if d.is_some() {
-                d.unwrap()
-            } else {
-                return HttpResponse::NotFound().finish();
-            }
-        }
+        Ok(d) => match d {
+            Some(d) => d,
+            None => return HttpResponse::NotFound().finish(),
+        },
         Err(e) => {
             error!("Error finding User by ID {}: {}", id, e);
-            return HttpResponse::InternalServerError()
-                .json(InternalServerError::new(&e.to_string()));
+            return e.into_http_response();
         }
     };
 
-    if user_dto.username.is_empty() {
-        return HttpResponse::BadRequest().json(BadRequest::new("Empty usernames are not allowed"));
-    }
-
     let user_dto = user_dto.into_inner();
 
     if user_dto.roles.is_some() {
@@ -463,25 +1012,29 @@ pub async fn update(
                         .json(BadRequest::new(&format!("Role {} not found", r))),
                     _ => {
                         error!("Error validating roles: {}", e);
-                        HttpResponse::InternalServerError()
-                            .json(InternalServerError::new(&e.to_string()))
+                        e.into_http_response()
                     }
                 };
             }
         };
     }
 
+    let active_from = match parse_date_time("activeFrom", user_dto.active_from) {
+        Ok(d) => d,
+        Err(r) => return r,
+    };
+    let expires_at = match parse_date_time("expiresAt", user_dto.expires_at) {
+        Ok(d) => d,
+        Err(r) => return r,
+    };
+
     let role_oid_vec = match user_dto.roles {
         Some(e) => {
             let mut vec = vec![];
             for r in e {
-                match ObjectId::parse_str(&r) {
+                match parse_object_id("Role ID", &r) {
                     Ok(oid) => vec.push(oid),
-                    Err(e) => {
-                        error!("Error parsing role ID {}: {}", r, e);
-                        return HttpResponse::InternalServerError()
-                            .json(InternalServerError::new(&e.to_string()));
-                    }
+                    Err(e) => return e.into_http_response(),
                 };
             }
             Some(vec)
@@ -489,12 +1042,25 @@ pub async fn update(
         None => None,
     };
 
+    let previous_roles = user.roles.clone().unwrap_or_default();
+    let previous_status = user.status;
+
     user.username = user_dto.username;
     user.email = user_dto.email;
     user.first_name = user_dto.first_name;
     user.last_name = user_dto.last_name;
+    user.phone_number = user_dto.phone_number;
+    user.locale = user_dto.locale;
+    user.timezone = user_dto.timezone;
     user.roles = role_oid_vec;
-    user.enabled = user_dto.enabled;
+    user.status = user_dto.status;
+
+    let admin_grant = match defer_admin_grant(&mut user.roles, &previous_roles, &pool).await {
+        Ok(d) => d,
+        Err(e) => return e,
+    };
+    user.active_from = active_from;
+    user.expires_at = expires_at;
 
     let res = match pool
         .services
@@ -510,21 +1076,21 @@ pub async fn update(
         Ok(d) => d,
         Err(e) => {
             error!("Error updating User: {}", e);
-            return match e {
-                Error::UsernameAlreadyTaken | Error::EmailAlreadyTaken | Error::InvalidEmail(_) => {
-                    HttpResponse::BadRequest().json(BadRequest::new(&e.to_string()))
-                }
-                _ => HttpResponse::InternalServerError()
-                    .json(InternalServerError::new(&e.to_string())),
-            };
+            return e.into_http_response();
         }
     };
 
+    publish_security_events(&res, &previous_roles, Some(previous_status), user_id, &pool).await;
+
+    if let Some(role_id) = admin_grant {
+        return request_admin_grant(&res, role_id, user_id, &pool).await;
+    }
+
     match convert_user_to_dto(res, &pool).await {
         Ok(dto) => HttpResponse::Ok().json(dto),
         Err(e) => {
             error!("Error converting User to UserDto: {}", e);
-            HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
+            e.into_http_response()
         }
     }
 }
@@ -539,6 +1105,7 @@ pub async fn update(
     responses(
         (status = 200, description = "OK", body = SimpleUserDto),
         (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 403, description = "Forbidden"),
         (status = 404, description = "Not Found"),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
     ),
@@ -548,95 +1115,67 @@ pub async fn update(
     )
 )]
 #[put("/{id}/self/")]
-#[protect("CAN_UPDATE_SELF")]
 pub async fn update_self(
     req: HttpRequest,
-    user_dto: web::Json<UpdateOwnUser>,
+    user_dto: SanitizedJson<UpdateOwnUser>,
     pool: web::Data<Config>,
 ) -> HttpResponse {
-    if let Some(auth_header) = req.headers().get("Authorization") {
-        if let Ok(auth_str) = auth_header.to_str() {
-            if let Some(token) = auth_str.strip_prefix("Bearer ") {
-                let user_id = match ObjectId::parse_str(token) {
-                    Ok(oid) => oid,
-                    Err(e) => {
-                        error!("Error parsing user ID {}: {}", token, e);
-                        return HttpResponse::InternalServerError()
-                            .json(InternalServerError::new(&e.to_string()));
-                    }
-                };
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(u) => u,
+        None => return HttpResponse::Forbidden().finish(),
+    };
 
-                let mut user = match pool
-                    .services
-                    .user_service
-                    .find_by_username(token, &pool.database)
-                    .await
-                {
-                    Ok(d) => {
-                        if d.is_some() {
-                            d.unwrap()
-                        } else {
-                            return HttpResponse::NotFound().finish();
-                        }
-                    }
-                    Err(e) => {
-                        error!("Error finding User by email {}: {}", token, e);
-                        return HttpResponse::InternalServerError()
-                            .json(InternalServerError::new(&e.to_string()));
-                    }
-                };
+    let mut user = match pool
+        .services
+        .user_service
+        .find_by_id(&user_id.to_hex(), &pool.database)
+        .await
+    {
+        Ok(d) => match d {
+            Some(d) => d,
+            None => return HttpResponse::NotFound().finish(),
+        },
+        Err(e) => {
+            error!("Error finding User by ID {}: {}", user_id, e);
+            return e.into_http_response();
+        }
+    };
 
-                if user_dto.username.is_empty() {
-                    return HttpResponse::BadRequest()
-                        .json(BadRequest::new("Empty usernames are not allowed"));
-                }
+    let user_dto = user_dto.into_inner();
 
-                let user_dto = user_dto.into_inner();
-
-                user.username = user_dto.username;
-                user.email = user_dto.email;
-                user.first_name = user_dto.first_name;
-                user.last_name = user_dto.last_name;
-
-                let res = match pool
-                    .services
-                    .user_service
-                    .update(
-                        user,
-                        Some(user_id),
-                        &pool.database,
-                        &pool.services.audit_service,
-                    )
-                    .await
-                {
-                    Ok(d) => d,
-                    Err(e) => {
-                        error!("Error updating User: {}", e);
-                        return match e {
-                            Error::UsernameAlreadyTaken
-                            | Error::EmailAlreadyTaken
-                            | Error::InvalidEmail(_) => {
-                                HttpResponse::BadRequest().json(BadRequest::new(&e.to_string()))
-                            }
-                            _ => HttpResponse::InternalServerError()
-                                .json(InternalServerError::new(&e.to_string())),
-                        };
-                    }
-                };
+    user.username = user_dto.username;
+    user.email = user_dto.email;
+    user.first_name = user_dto.first_name;
+    user.last_name = user_dto.last_name;
+    user.phone_number = user_dto.phone_number;
+    user.locale = user_dto.locale;
+    user.timezone = user_dto.timezone;
 
-                return match crate::web::controller::authentication::authentication_controller::convert_user_to_simple_dto(res, &pool).await {
-                    Ok(dto) => {
-                        HttpResponse::Ok().json(dto)
-                    },
-                    Err(e) => {
-                        error!("Error converting User to UserDto: {}", e);
-                        HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
-                    }
-                };
-            }
+    let res = match pool
+        .services
+        .user_service
+        .update(
+            user,
+            Some(user_id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => {
+            error!("Error updating User: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    match crate::web::controller::authentication::authentication_controller::convert_user_to_simple_dto(res, &pool).await {
+        Ok(dto) => HttpResponse::Ok().json(dto),
+        Err(e) => {
+            error!("Error converting User to UserDto: {}", e);
+            e.into_http_response()
         }
     }
-    HttpResponse::BadRequest().finish()
 }
 
 #[utoipa::path(
@@ -661,103 +1200,79 @@ pub async fn update_self(
     )
 )]
 #[put("/{id}/self/password/")]
-#[protect("CAN_UPDATE_SELF")]
 pub async fn update_password(
     req: HttpRequest,
-    update_password: web::Json<UpdatePassword>,
+    update_password: SanitizedJson<UpdatePassword>,
     pool: web::Data<Config>,
 ) -> HttpResponse {
-    if let Some(auth_header) = req.headers().get("Authorization") {
-        if let Ok(auth_str) = auth_header.to_str() {
-            if let Some(token) = auth_str.strip_prefix("Bearer ") {
-                let user_oid = match ObjectId::parse_str(token) {
-                    Ok(oid) => oid,
-                    Err(e) => {
-                        error!("Error parsing user ID {}: {}", token, e);
-                        return HttpResponse::InternalServerError()
-                            .json(InternalServerError::new(&e.to_string()));
-                    }
-                };
-
-                let user = match pool
-                    .services
-                    .user_service
-                    .find_by_username(token, &pool.database)
-                    .await
-                {
-                    Ok(d) => {
-                        if d.is_some() {
-                            d.unwrap()
-                        } else {
-                            return HttpResponse::NotFound().finish();
-                        }
-                    }
-                    Err(e) => {
-                        error!("Error finding User by email {}: {}", token, e);
-                        return HttpResponse::InternalServerError()
-                            .json(InternalServerError::new(&e.to_string()));
-                    }
-                };
-
-                let update_password = update_password.into_inner();
+    let user_oid = match user_id_extractor::get_user_id_for_password_change(&req, &pool).await {
+        Some(e) => e,
+        None => return HttpResponse::BadRequest().finish(),
+    };
 
-                if update_password.old_password.is_empty() {
-                    return HttpResponse::BadRequest()
-                        .json(BadRequest::new("Empty old passwords are not allowed"));
-                }
+    let user = match pool
+        .services
+        .user_service
+        .find_by_id(&user_oid.to_hex(), &pool.database)
+        .await
+    {
+        Ok(d) => match d {
+            Some(d) => d,
+            None => return HttpResponse::NotFound().finish(),
+        },
+        Err(e) => {
+            error!("Error finding User by ID {}: {}", user_oid, e);
+            return e.into_http_response();
+        }
+    };
 
-                if update_password.new_password.is_empty() {
-                    return HttpResponse::BadRequest()
-                        .json(BadRequest::new("Empty new passwords are not allowed"));
-                }
+    let update_password = update_password.into_inner();
 
-                let parsed_hash = match PasswordHash::new(&user.password) {
-                    Ok(h) => h,
-                    Err(e) => {
-                        error!("Failed to parse password hash: {}", e);
-                        return HttpResponse::InternalServerError()
-                            .json(InternalServerError::new("Failed to parse password hash"));
-                    }
-                };
+    match pool
+        .services
+        .password_service
+        .verify_password(&update_password.old_password, &user.password)
+    {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::BadRequest().finish(),
+        Err(e) => {
+            error!("Failed to parse password hash: {}", e);
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to parse password hash"));
+        }
+    }
 
-                if !PasswordService::verify_password(&update_password.old_password, &parsed_hash) {
-                    return HttpResponse::BadRequest().finish();
-                }
+    let new_password_hash = match pool
+        .services
+        .password_service
+        .hash_password(&update_password.new_password)
+    {
+        Ok(e) => e,
+        Err(e) => {
+            error!("Error hashing password: {}", e);
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to hash password"));
+        }
+    };
 
-                let new_password_hash =
-                    match PasswordService::hash_password(update_password.new_password) {
-                        Ok(e) => e.to_string(),
-                        Err(e) => {
-                            error!("Error hashing password: {}", e);
-                            return HttpResponse::InternalServerError()
-                                .json(InternalServerError::new("Failed to hash password"));
-                        }
-                    };
-
-                return match pool
-                    .services
-                    .user_service
-                    .update_password(
-                        &user.id.to_hex(),
-                        &new_password_hash,
-                        Some(user_oid),
-                        &pool.database,
-                        &pool.services.audit_service,
-                    )
-                    .await
-                {
-                    Ok(_) => HttpResponse::Ok().finish(),
-                    Err(e) => {
-                        error!("Error updating password: {}", e);
-                        HttpResponse::InternalServerError()
-                            .json(InternalServerError::new(&e.to_string()))
-                    }
-                };
-            }
+    match pool
+        .services
+        .user_service
+        .update_password(
+            &user.id.to_hex(),
+            &new_password_hash,
+            Some(user_oid),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(_) => HttpResponse::Ok().finish(),
+        Err(e) => {
+            error!("Error updating password: {}", e);
+            e.into_http_response()
         }
     }
-
-    HttpResponse::BadRequest().finish()
 }
 
 #[utoipa::path(
@@ -779,10 +1294,9 @@ pub async fn update_password(
     )
 )]
 #[put("/{id}/password/")]
-#[protect("CAN_UPDATE_USER")]
 pub async fn admin_update_password(
     id: web::Path<String>,
-    admin_update_password: web::Json<AdminUpdatePassword>,
+    admin_update_password: SanitizedJson<AdminUpdatePassword>,
     pool: web::Data<Config>,
     req: HttpRequest,
 ) -> HttpResponse {
@@ -804,26 +1318,22 @@ pub async fn admin_update_password(
         .find_by_id(&id, &pool.database)
         .await
     {
-        Ok(d) => {
-            if d.is_some() {
-                d.unwrap()
-            } else {
-                return HttpResponse::NotFound().finish();
-            }
-        }
+        Ok(d) => match d {
+            Some(d) => d,
+            None => return HttpResponse::NotFound().finish(),
+        },
         Err(e) => {
             error!("Error finding User by ID {}: {}", id, e);
-            return HttpResponse::InternalServerError()
-                .json(InternalServerError::new(&e.to_string()));
+            return e.into_http_response();
         }
     };
 
-    if admin_update_password.password.is_empty() {
-        return HttpResponse::BadRequest().json(BadRequest::new("Empty passwords are not allowed"));
-    }
-
-    let password_hash = match PasswordService::hash_password(admin_update_password.password) {
-        Ok(e) => e.to_string(),
+    let password_hash = match pool
+        .services
+        .password_service
+        .hash_password(&admin_update_password.password)
+    {
+        Ok(e) => e,
         Err(e) => {
             error!("Error hashing password: {}", e);
             return HttpResponse::InternalServerError()
@@ -846,19 +1356,20 @@ pub async fn admin_update_password(
         Ok(_) => HttpResponse::Ok().finish(),
         Err(e) => {
             error!("Error updating password: {}", e);
-            HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
+            e.into_http_response()
         }
     }
 }
 
 #[utoipa::path(
-    delete,
-    path = "/api/v1/users/{id}",
+    post,
+    path = "/api/v1/users/{id}/revoke-sessions/",
     params(
         ("id" = String, Path, description = "The ID of the User"),
     ),
     responses(
         (status = 200, description = "OK"),
+        (status = 400, description = "Bad Request", body = BadRequest),
         (status = 404, description = "Not Found"),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
     ),
@@ -867,9 +1378,8 @@ pub async fn admin_update_password(
         ("Token" = [])
     )
 )]
-#[delete("/{id}")]
-#[protect("CAN_DELETE_USER")]
-pub async fn delete(
+#[post("/{id}/revoke-sessions/")]
+pub async fn revoke_sessions(
     id: web::Path<String>,
     pool: web::Data<Config>,
     req: HttpRequest,
@@ -883,38 +1393,46 @@ pub async fn delete(
         }
     };
 
-    match pool
+    let id = id.into_inner();
+
+    if let Err(e) = pool
         .services
         .user_service
-        .delete(
-            &id.into_inner(),
+        .revoke_sessions(
+            &id,
             Some(user_id),
             &pool.database,
             &pool.services.audit_service,
         )
         .await
     {
-        Ok(_) => HttpResponse::Ok().finish(),
-        Err(e) => match e {
-            Error::UserNotFound(_) => HttpResponse::NotFound().finish(),
-            _ => {
-                error!("Error deleting User: {}", e);
-                HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
-            }
-        },
+        error!("Error revoking sessions: {}", e);
+        return e.into_http_response();
+    }
+
+    // The tokens are already rejected because of the new token version, so stale Sessions are only removed here
+    if let Err(e) = pool
+        .services
+        .session_service
+        .revoke_all(&id, &pool.database)
+        .await
+    {
+        error!("Error revoking server-side sessions: {}", e);
     }
+
+    HttpResponse::Ok().finish()
 }
 
 #[utoipa::path(
-    delete,
-    path = "/api/v1/users/{id}/self/",
+    post,
+    path = "/api/v1/users/{id}/approve/",
     params(
         ("id" = String, Path, description = "The ID of the User"),
     ),
     responses(
-        (status = 200, description = "OK"),
+        (status = 200, description = "OK", body = UserDto),
         (status = 400, description = "Bad Request", body = BadRequest),
-        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
     ),
     tag = "Users",
@@ -922,52 +1440,952 @@ pub async fn delete(
         ("Token" = [])
     )
 )]
-#[delete("/{id}/self/")]
-#[protect("CAN_DELETE_SELF")]
-pub async fn delete_self(req: HttpRequest, pool: web::Data<Config>) -> HttpResponse {
-    if let Some(auth_header) = req.headers().get("Authorization") {
-        if let Ok(auth_str) = auth_header.to_str() {
-            if let Some(token) = auth_str.strip_prefix("Bearer ") {
-                let user_oid = match ObjectId::parse_str(token) {
-                    Ok(oid) => oid,
-                    Err(e) => {
-                        error!("Error parsing user ID {}: {}", token, e);
-                        return HttpResponse::InternalServerError()
-                            .json(InternalServerError::new(&e.to_string()));
-                    }
-                };
+#[post("/{id}/approve/")]
+pub async fn approve(
+    id: web::Path<String>,
+    pool: web::Data<Config>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(e) => e,
+        None => {
+            error!("Failed to get User ID from token");
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to get User ID from token"));
+        }
+    };
 
-                let username = match pool.services.jwt_service.verify_jwt_token(token) {
-                    Ok(user) => user,
-                    Err(e) => {
-                        error!("Failed to verify JWT token: {}", e);
-                        return HttpResponse::Forbidden().finish();
-                    }
-                };
+    let mut user = match find_pending_user(&id.into_inner(), &pool).await {
+        Ok(u) => u,
+        Err(r) => return r,
+    };
 
-                return match pool
-                    .services
-                    .user_service
-                    .delete(
-                        &username,
-                        Some(user_oid),
-                        &pool.database,
-                        &pool.services.audit_service,
-                    )
-                    .await
-                {
-                    Ok(_) => HttpResponse::Ok().finish(),
-                    Err(e) => match e {
-                        Error::UserNotFound(_) => HttpResponse::Ok().finish(),
-                        _ => {
-                            error!("Error deleting User: {}", e);
-                            HttpResponse::InternalServerError()
-                                .json(InternalServerError::new(&e.to_string()))
-                        }
-                    },
-                };
-            }
-        }
+    user.status = UserStatus::Active;
+
+    let user = match pool
+        .services
+        .user_service
+        .update(
+            user,
+            Some(user_id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(u) => u,
+        Err(e) => {
+            error!("Error approving User: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    notify_user(
+        &user,
+        "Your account was approved",
+        &format!(
+            "Hello {},\n\nYour account was approved by an administrator. You can now log in.",
+            user.username
+        ),
+        &pool,
+    )
+    .await;
+
+    match convert_user_to_dto(user, &pool).await {
+        Ok(u) => HttpResponse::Ok().json(u),
+        Err(e) => {
+            error!("Error converting User to UserDto: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/{id}/reject/",
+    params(
+        ("id" = String, Path, description = "The ID of the User"),
+    ),
+    responses(
+        (status = 200, description = "OK"),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Users",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/{id}/reject/")]
+pub async fn reject(
+    id: web::Path<String>,
+    pool: web::Data<Config>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(e) => e,
+        None => {
+            error!("Failed to get User ID from token");
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to get User ID from token"));
+        }
+    };
+
+    let user = match find_pending_user(&id.into_inner(), &pool).await {
+        Ok(u) => u,
+        Err(r) => return r,
+    };
+
+    if let Err(e) = pool
+        .services
+        .user_service
+        .delete(
+            &user.id.to_hex(),
+            Some(user_id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        error!("Error rejecting User: {}", e);
+        return e.into_http_response();
+    }
+
+    notify_user(
+        &user,
+        "Your registration was rejected",
+        &format!(
+            "Hello {},\n\nYour registration was rejected by an administrator.",
+            user.username
+        ),
+        &pool,
+    )
+    .await;
+
+    HttpResponse::Ok().finish()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/{id}/emails/",
+    request_body = AddUserEmail,
+    params(
+        ("id" = String, Path, description = "The ID of the User"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = UserDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Users",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/{id}/emails/")]
+pub async fn add_email(
+    id: web::Path<String>,
+    add_user_email: SanitizedJson<AddUserEmail>,
+    pool: web::Data<Config>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(e) => e,
+        None => {
+            error!("Failed to get User ID from token");
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to get User ID from token"));
+        }
+    };
+
+    let res = match pool
+        .services
+        .user_service
+        .add_email(
+            &id.into_inner(),
+            &add_user_email.into_inner().email,
+            Some(user_id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => {
+            error!("Error adding email address: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    match convert_user_to_dto(res, &pool).await {
+        Ok(dto) => HttpResponse::Ok().json(dto),
+        Err(e) => {
+            error!("Error converting User to UserDto: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/users/{id}/emails/",
+    request_body = UpdateUserEmail,
+    params(
+        ("id" = String, Path, description = "The ID of the User"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = UserDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Users",
+    security(
+        ("Token" = [])
+    )
+)]
+#[put("/{id}/emails/")]
+pub async fn update_email(
+    id: web::Path<String>,
+    update_user_email: SanitizedJson<UpdateUserEmail>,
+    pool: web::Data<Config>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let id = id.into_inner();
+    let update_user_email = update_user_email.into_inner();
+
+    if update_user_email.verified.is_none() && update_user_email.primary != Some(true) {
+        return HttpResponse::BadRequest().json(BadRequest::new(
+            "Either verified or primary must be provided",
+        ));
+    }
+
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(e) => e,
+        None => {
+            error!("Failed to get User ID from token");
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to get User ID from token"));
+        }
+    };
+
+    let mut res = None;
+
+    if let Some(verified) = update_user_email.verified {
+        res = match pool
+            .services
+            .user_service
+            .set_email_verified(
+                &id,
+                &update_user_email.email,
+                verified,
+                Some(user_id),
+                &pool.database,
+                &pool.services.audit_service,
+            )
+            .await
+        {
+            Ok(d) => Some(d),
+            Err(e) => {
+                error!("Error updating email verification state: {}", e);
+                return e.into_http_response();
+            }
+        };
+    }
+
+    if update_user_email.primary == Some(true) {
+        res = match pool
+            .services
+            .user_service
+            .set_primary_email(
+                &id,
+                &update_user_email.email,
+                Some(user_id),
+                &pool.database,
+                &pool.services.audit_service,
+            )
+            .await
+        {
+            Ok(d) => Some(d),
+            Err(e) => {
+                error!("Error updating primary email address: {}", e);
+                return e.into_http_response();
+            }
+        };
+    }
+
+    let res = match res {
+        Some(d) => d,
+        None => return HttpResponse::NotFound().finish(),
+    };
+
+    match convert_user_to_dto(res, &pool).await {
+        Ok(dto) => HttpResponse::Ok().json(dto),
+        Err(e) => {
+            error!("Error converting User to UserDto: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/users/{id}/emails/",
+    params(
+        ("id" = String, Path, description = "The ID of the User"),
+        ("email" = String, Query, description = "The secondary email address to remove"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = UserDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Users",
+    security(
+        ("Token" = [])
+    )
+)]
+#[delete("/{id}/emails/")]
+pub async fn remove_email(
+    id: web::Path<String>,
+    query: web::Query<UserEmailQuery>,
+    pool: web::Data<Config>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(e) => e,
+        None => {
+            error!("Failed to get User ID from token");
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to get User ID from token"));
+        }
+    };
+
+    let res = match pool
+        .services
+        .user_service
+        .remove_email(
+            &id.into_inner(),
+            &query.into_inner().email,
+            Some(user_id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => {
+            error!("Error removing email address: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    match convert_user_to_dto(res, &pool).await {
+        Ok(dto) => HttpResponse::Ok().json(dto),
+        Err(e) => {
+            error!("Error converting User to UserDto: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+/// # Summary
+///
+/// Move the entities that belong to a duplicate User to the User that it was merged into. Passkeys and Organization
+/// memberships are moved, while refresh tokens, Consents and server-side sessions of the duplicate are revoked.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the User that the duplicate was merged into.
+/// * `other_id` - The ID of the duplicate User.
+/// * `actor_id` - The ID of the User that merges the Users.
+/// * `pool` - The Config.
+///
+/// # Example
+///
+/// ```
+/// move_merged_entities(user.id, other.id, user_id, &pool).await?;
+/// ```
+///
+/// # Returns
+///
+/// * `Result<(), HttpResponse>` - Nothing, or the HttpResponse that should be returned.
+async fn move_merged_entities(
+    id: ObjectId,
+    other_id: ObjectId,
+    actor_id: ObjectId,
+    pool: &Config,
+) -> Result<(), HttpResponse> {
+    let db = &pool.database;
+
+    if let Err(e) = pool
+        .services
+        .credential_service
+        .reassign(other_id, id, db)
+        .await
+    {
+        error!(
+            "Error moving Credentials of User {}: {}",
+            other_id.to_hex(),
+            e
+        );
+        return Err(e.into_http_response());
+    }
+
+    let organizations = match pool
+        .services
+        .organization_service
+        .find_by_member(other_id, db)
+        .await
+    {
+        Ok(o) => o,
+        Err(e) => {
+            error!(
+                "Error finding Organizations of User {}: {}",
+                other_id.to_hex(),
+                e
+            );
+            return Err(e.into_http_response());
+        }
+    };
+
+    for organization in organizations {
+        let mut roles = organization
+            .find_member(&id)
+            .map(|m| m.roles.clone())
+            .unwrap_or_default();
+        if let Some(member) = organization.find_member(&other_id) {
+            for role in &member.roles {
+                if !roles.contains(role) {
+                    roles.push(*role);
+                }
+            }
+        }
+
+        let organization_id = organization.id.to_hex();
+        if let Err(e) = pool
+            .services
+            .organization_service
+            .set_member(
+                &organization_id,
+                Member { user_id: id, roles },
+                Some(actor_id),
+                db,
+                &pool.services.audit_service,
+            )
+            .await
+        {
+            error!(
+                "Error moving membership of Organization {}: {}",
+                organization_id, e
+            );
+            return Err(e.into_http_response());
+        }
+
+        if let Err(e) = pool
+            .services
+            .organization_service
+            .remove_member(
+                &organization_id,
+                other_id,
+                Some(actor_id),
+                db,
+                &pool.services.audit_service,
+            )
+            .await
+        {
+            error!(
+                "Error moving membership of Organization {}: {}",
+                organization_id, e
+            );
+            return Err(e.into_http_response());
+        }
+    }
+
+    if let Err(e) = pool
+        .services
+        .refresh_token_service
+        .revoke_all(other_id, db)
+        .await
+    {
+        error!(
+            "Error revoking refresh tokens of User {}: {}",
+            other_id.to_hex(),
+            e
+        );
+        return Err(e.into_http_response());
+    }
+
+    if let Err(e) = pool
+        .services
+        .consent_service
+        .delete_by_user_id(other_id, db)
+        .await
+    {
+        error!(
+            "Error revoking Consents of User {}: {}",
+            other_id.to_hex(),
+            e
+        );
+        return Err(e.into_http_response());
+    }
+
+    if let Err(e) = pool
+        .services
+        .session_service
+        .revoke_all(&other_id.to_hex(), db)
+        .await
+    {
+        error!(
+            "Error revoking server-side sessions of User {}: {}",
+            other_id.to_hex(),
+            e
+        );
+        return Err(e.into_http_response());
+    }
+
+    Ok(())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/{id}/merge/{otherId}",
+    params(
+        ("id" = String, Path, description = "The ID of the User that is kept"),
+        ("otherId" = String, Path, description = "The ID of the duplicate User that is merged and deleted"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = UserDto),
+        (status = 202, description = "Accepted", body = RoleChangeDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Users",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/{id}/merge/{otherId}")]
+pub async fn merge(
+    path: web::Path<(String, String)>,
+    pool: web::Data<Config>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(e) => e,
+        None => {
+            error!("Failed to get User ID from token");
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to get User ID from token"));
+        }
+    };
+
+    let (id, other_id) = path.into_inner();
+    if id == other_id {
+        return HttpResponse::BadRequest()
+            .json(BadRequest::new("A User cannot be merged into itself"));
+    }
+
+    let mut users = vec![];
+    for i in [&id, &other_id] {
+        match pool
+            .services
+            .user_service
+            .find_by_id(i, &pool.database)
+            .await
+        {
+            Ok(Some(u)) => users.push(u),
+            Ok(None) => return HttpResponse::NotFound().finish(),
+            Err(e) => {
+                error!("Error finding User by ID {}: {}", i, e);
+                return e.into_http_response();
+            }
+        }
+    }
+
+    let other = users.remove(1);
+    let other_id = other.id;
+    let user = users.remove(0);
+    let previous_roles = user.roles.clone().unwrap_or_default();
+    let previous_status = user.status;
+
+    let mut merged = user;
+    merged.merge(other.clone());
+
+    let admin_grant = match defer_admin_grant(&mut merged.roles, &previous_roles, &pool).await {
+        Ok(d) => d,
+        Err(e) => return e,
+    };
+
+    let res = match pool
+        .services
+        .user_service
+        .merge(merged, other, &pool.database)
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => {
+            error!("Error merging Users: {}", e);
+            return e.into_http_response();
+        }
+    };
+
+    // The duplicate is only deleted, and its Audits reattributed, once everything that belongs to it was moved, so that
+    // a failed merge can be repeated
+    if let Err(e) = move_merged_entities(res.id, other_id, user_id, &pool).await {
+        return e;
+    }
+
+    if let Err(e) = pool
+        .services
+        .user_service
+        .complete_merge(
+            res.id,
+            other_id,
+            Some(user_id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        error!(
+            "Error completing the merge of User {}: {}",
+            other_id.to_hex(),
+            e
+        );
+        return e.into_http_response();
+    }
+
+    publish_security_events(&res, &previous_roles, Some(previous_status), user_id, &pool).await;
+
+    if let Some(role_id) = admin_grant {
+        return request_admin_grant(&res, role_id, user_id, &pool).await;
+    }
+
+    match convert_user_to_dto(res, &pool).await {
+        Ok(dto) => HttpResponse::Ok().json(dto),
+        Err(e) => {
+            error!("Error converting User to UserDto: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/users/{id}",
+    params(
+        ("id" = String, Path, description = "The ID of the User"),
+    ),
+    responses(
+        (status = 200, description = "OK"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Users",
+    security(
+        ("Token" = [])
+    )
+)]
+#[delete("/{id}")]
+pub async fn delete(
+    id: web::Path<String>,
+    pool: web::Data<Config>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(e) => e,
+        None => {
+            error!("Failed to get User ID from token");
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to get User ID from token"));
+        }
+    };
+
+    match pool
+        .services
+        .user_service
+        .delete(
+            &id.into_inner(),
+            Some(user_id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(_) => HttpResponse::Ok().finish(),
+        Err(e) => {
+            error!("Error deleting User: {}", e);
+            e.into_http_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/users/{id}/self/",
+    params(
+        ("id" = String, Path, description = "The ID of the User"),
+    ),
+    responses(
+        (status = 200, description = "OK"),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Users",
+    security(
+        ("Token" = [])
+    )
+)]
+#[delete("/{id}/self/")]
+pub async fn delete_self(req: HttpRequest, pool: web::Data<Config>) -> HttpResponse {
+    let user_oid = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
+        Some(u) => u,
+        None => return HttpResponse::Forbidden().finish(),
+    };
+
+    match pool
+        .services
+        .user_service
+        .delete(
+            &user_oid.to_hex(),
+            Some(user_oid),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(_) => HttpResponse::Ok().finish(),
+        Err(e) => match e {
+            Error::UserNotFound(_) => HttpResponse::Ok().finish(),
+            _ => {
+                error!("Error deleting User: {}", e);
+                e.into_http_response()
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::credential::credential_repository::Error as CredentialError;
+    use crate::repository::organization::organization_model::Organization;
+    use crate::test_support::{self, MockServices};
+    use actix_web::{test, App};
+    use mockall::Sequence;
+
+    struct MergeFixture {
+        mocks: MockServices,
+        actor_id: ObjectId,
+        user_id: ObjectId,
+        other_id: ObjectId,
+    }
+
+    fn merge_fixture() -> MergeFixture {
+        let actor = test_support::user();
+        let user = test_support::user();
+        let other = test_support::user();
+        let (actor_id, user_id, other_id) = (actor.id, user.id, other.id);
+
+        let mut mocks = MockServices::new();
+        mocks.with_user(actor);
+        mocks.with_user(user);
+        mocks.with_user(other);
+        mocks
+            .role_change_service
+            .expect_approval_required()
+            .return_const(false);
+
+        MergeFixture {
+            mocks,
+            actor_id,
+            user_id,
+            other_id,
+        }
+    }
+
+    async fn post_merge(fixture: MergeFixture) -> StatusCode {
+        let config = test_support::config(fixture.mocks).await;
+        let token = test_support::bearer(&config, &fixture.actor_id, 0);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(config))
+                .service(web::scope("/users").service(merge)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!(
+                "/users/{}/merge/{}",
+                fixture.user_id.to_hex(),
+                fixture.other_id.to_hex()
+            ))
+            .insert_header((header::AUTHORIZATION, token))
+            .to_request();
+
+        test::call_service(&app, req).await.status()
+    }
+
+    #[actix_web::test]
+    async fn merge_moves_entities_of_duplicate_before_deleting_it() {
+        let mut fixture = merge_fixture();
+        let (user_id, other_id) = (fixture.user_id, fixture.other_id);
+        let mocks = &mut fixture.mocks;
+        let mut seq = Sequence::new();
+
+        mocks
+            .user_service
+            .expect_merge()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|u, _, _| Ok(u));
+        mocks
+            .credential_service
+            .expect_reassign()
+            .withf(move |from, to, _| *from == other_id && *to == user_id)
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_, _, _| Ok(()));
+        mocks
+            .organization_service
+            .expect_find_by_member()
+            .withf(move |id, _| *id == other_id)
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_, _| Ok(vec![]));
+        mocks
+            .refresh_token_service
+            .expect_revoke_all()
+            .withf(move |id, _| *id == other_id)
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_, _| Ok(()));
+        mocks
+            .consent_service
+            .expect_delete_by_user_id()
+            .withf(move |id, _| *id == other_id)
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_, _| Ok(()));
+        mocks
+            .session_service
+            .expect_revoke_all()
+            .withf(move |subject, _| subject == other_id.to_hex())
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_, _| Ok(()));
+        mocks
+            .user_service
+            .expect_complete_merge()
+            .withf(move |id, other, _, _, _| *id == user_id && *other == other_id)
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_, _, _, _, _| Ok(()));
+
+        assert_eq!(post_merge(fixture).await, StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn merge_moves_organization_memberships_of_duplicate() {
+        let mut fixture = merge_fixture();
+        let (user_id, other_id) = (fixture.user_id, fixture.other_id);
+        let (user_role, other_role) = (ObjectId::new(), ObjectId::new());
+
+        let mut organization = Organization::new(String::from("Opserva"), None);
+        organization.members = vec![
+            Member {
+                user_id,
+                roles: vec![user_role],
+            },
+            Member {
+                user_id: other_id,
+                roles: vec![other_role, user_role],
+            },
+        ];
+        let organization_id = organization.id.to_hex();
+
+        let mocks = &mut fixture.mocks;
+        mocks.user_service.expect_merge().returning(|u, _, _| Ok(u));
+        mocks
+            .credential_service
+            .expect_reassign()
+            .returning(|_, _, _| Ok(()));
+        mocks
+            .organization_service
+            .expect_find_by_member()
+            .returning(move |_, _| Ok(vec![organization.clone()]));
+        let id = organization_id.clone();
+        mocks
+            .organization_service
+            .expect_set_member()
+            .withf(move |o, m, _, _, _| {
+                o == id && m.user_id == user_id && m.roles == vec![user_role, other_role]
+            })
+            .times(1)
+            .returning(|_, _, _, _, _| Ok(Organization::new(String::from("Opserva"), None)));
+        mocks
+            .organization_service
+            .expect_remove_member()
+            .withf(move |o, m, _, _, _| o == organization_id && *m == other_id)
+            .times(1)
+            .returning(|_, _, _, _, _| Ok(()));
+        mocks
+            .refresh_token_service
+            .expect_revoke_all()
+            .returning(|_, _| Ok(()));
+        mocks
+            .consent_service
+            .expect_delete_by_user_id()
+            .returning(|_, _| Ok(()));
+        mocks
+            .session_service
+            .expect_revoke_all()
+            .returning(|_, _| Ok(()));
+        mocks
+            .user_service
+            .expect_complete_merge()
+            .returning(|_, _, _, _, _| Ok(()));
+
+        assert_eq!(post_merge(fixture).await, StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn merge_leaves_duplicate_alone_when_user_cannot_be_stored() {
+        let mut fixture = merge_fixture();
+        let mocks = &mut fixture.mocks;
+        mocks.user_service.expect_merge().returning(|_, _, _| {
+            Err(Error::MongoDb(mongodb::error::Error::from(
+                std::io::ErrorKind::Other,
+            )))
+        });
+        mocks.credential_service.expect_reassign().never();
+        mocks.user_service.expect_complete_merge().never();
+
+        assert_eq!(post_merge(fixture).await, StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[actix_web::test]
+    async fn merge_keeps_duplicate_and_audits_when_entities_cannot_be_moved() {
+        let mut fixture = merge_fixture();
+        let mocks = &mut fixture.mocks;
+        mocks.user_service.expect_merge().returning(|u, _, _| Ok(u));
+        mocks
+            .credential_service
+            .expect_reassign()
+            .returning(|_, _, _| {
+                Err(CredentialError::MongoDb(mongodb::error::Error::from(
+                    std::io::ErrorKind::Other,
+                )))
+            });
+        mocks.organization_service.expect_find_by_member().never();
+        mocks.user_service.expect_complete_merge().never();
+
+        assert_eq!(post_merge(fixture).await, StatusCode::INTERNAL_SERVER_ERROR);
     }
-    HttpResponse::BadRequest().finish()
 }
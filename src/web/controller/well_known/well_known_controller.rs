@@ -0,0 +1,23 @@
+use crate::configuration::config::Config;
+use crate::services::jwt::jwk::JwkSet;
+use actix_web::http::header::{CacheControl, CacheDirective};
+use actix_web::{get, web, HttpResponse};
+
+#[utoipa::path(
+    get,
+    path = "/.well-known/jwks.json",
+    responses(
+        (status = 200, description = "OK", body = JwkSet),
+    ),
+    tag = "Well-known",
+)]
+#[get("/jwks.json")]
+pub async fn jwks(pool: web::Data<Config>) -> HttpResponse {
+    // Resource servers cache the keys, but should pick up a new key within a reasonable time
+    HttpResponse::Ok()
+        .insert_header(CacheControl(vec![
+            CacheDirective::Public,
+            CacheDirective::MaxAge(300),
+        ]))
+        .json(pool.services.jwt_service.jwks())
+}
@@ -1,6 +1,21 @@
+pub mod api_key;
 pub mod audit;
 pub mod authentication;
+pub mod authorization;
+pub mod backup;
+pub mod client;
+pub mod consent;
+pub mod invitation;
+pub mod maintenance;
+pub mod metrics;
+pub mod mfa;
+pub mod oauth;
+pub mod organization;
 pub mod permission;
 pub mod role;
+pub mod role_change;
+pub mod route;
+pub mod sanitize;
 pub mod search;
+pub mod selfcheck;
 pub mod user;
@@ -0,0 +1,3 @@
+pub mod api_key_dto;
+pub mod create_api_key;
+pub mod update_api_key;
@@ -0,0 +1,61 @@
+use crate::repository::api_key::api_key_model::ApiKey;
+use crate::web::dto::permission::permission_dto::PermissionDto;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ApiKeyDto {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub prefix: String,
+    pub permissions: Option<Vec<PermissionDto>>,
+    pub enabled: bool,
+    #[serde(rename = "expiresAt", skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ApiKeySecretDto {
+    pub id: String,
+    pub key: String,
+}
+
+impl From<ApiKey> for ApiKeyDto {
+    /// # Summary
+    ///
+    /// Convert an ApiKey entity into an ApiKeyDto.
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - The ApiKey entity to be converted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let api_key = ApiKey::new(String::from("name"), None, None, None);
+    ///
+    /// let api_key_dto = ApiKeyDto::from(api_key);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `ApiKeyDto` - The new ApiKeyDto.
+    fn from(value: ApiKey) -> Self {
+        ApiKeyDto {
+            id: value.id.to_hex(),
+            name: value.name,
+            description: value.description,
+            prefix: value.prefix,
+            permissions: None,
+            enabled: value.enabled,
+            expires_at: value.expires_at.map(|d| d.to_rfc3339()),
+            created_at: value.created_at.to_rfc3339(),
+            updated_at: value.updated_at.to_rfc3339(),
+        }
+    }
+}
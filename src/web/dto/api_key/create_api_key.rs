@@ -0,0 +1,28 @@
+use crate::web::dto::sanitize::{sanitize_optional, sanitize_required, Sanitize, SanitizeError};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct CreateApiKey {
+    pub name: String,
+    pub description: Option<String>,
+    pub permissions: Option<Vec<String>>,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: Option<String>,
+}
+
+impl Sanitize for CreateApiKey {
+    /// # Summary
+    ///
+    /// Sanitize the CreateApiKey.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SanitizeError>` - The result of the operation.
+    fn sanitize(&mut self) -> Result<(), SanitizeError> {
+        sanitize_required("name", &mut self.name)?;
+        sanitize_optional("expiresAt", &mut self.expires_at)?;
+
+        Ok(())
+    }
+}
@@ -1 +1,3 @@
+pub mod audit_chain_dto;
 pub mod audit_dto;
+pub mod audit_search;
@@ -0,0 +1,52 @@
+use crate::repository::audit::audit_model::AuditChainVerification;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize)]
+pub struct AuditChainRequest {
+    pub partition: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct AuditChainVerificationDto {
+    pub partition: String,
+    pub valid: bool,
+    pub records: u64,
+    #[serde(rename = "firstSequence")]
+    pub first_sequence: Option<i64>,
+    #[serde(rename = "lastSequence")]
+    pub last_sequence: Option<i64>,
+    pub truncated: bool,
+    pub issues: Vec<String>,
+}
+
+impl From<AuditChainVerification> for AuditChainVerificationDto {
+    /// # Summary
+    ///
+    /// Convert an AuditChainVerification into an AuditChainVerificationDto.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The AuditChainVerification to be converted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let dto = AuditChainVerificationDto::from(verification);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `AuditChainVerificationDto` - The new AuditChainVerificationDto.
+    fn from(value: AuditChainVerification) -> Self {
+        AuditChainVerificationDto {
+            partition: value.partition,
+            valid: value.issues.is_empty(),
+            records: value.records,
+            first_sequence: value.first_sequence,
+            last_sequence: value.last_sequence,
+            truncated: value.truncated,
+            issues: value.issues,
+        }
+    }
+}
@@ -10,6 +10,8 @@ pub enum ActionDto {
     Update,
     #[serde(rename = "delete")]
     Delete,
+    #[serde(rename = "deny")]
+    Deny,
 }
 
 #[derive(Serialize, Deserialize, ToSchema)]
@@ -36,6 +38,16 @@ pub enum ResourceIdTypeDto {
     UserName,
     #[serde(rename = "userSearch")]
     UserSearch,
+    #[serde(rename = "clientId")]
+    ClientId,
+    #[serde(rename = "consentId")]
+    ConsentId,
+    #[serde(rename = "organizationId")]
+    OrganizationId,
+    #[serde(rename = "invitationId")]
+    InvitationId,
+    #[serde(rename = "apiKeyId")]
+    ApiKeyId,
     #[serde(rename = "none")]
     None,
 }
@@ -48,6 +60,18 @@ pub enum ResourceTypeDto {
     Role,
     #[serde(rename = "user")]
     User,
+    #[serde(rename = "route")]
+    Route,
+    #[serde(rename = "client")]
+    Client,
+    #[serde(rename = "consent")]
+    Consent,
+    #[serde(rename = "organization")]
+    Organization,
+    #[serde(rename = "invitation")]
+    Invitation,
+    #[serde(rename = "apiKey")]
+    ApiKey,
 }
 
 #[derive(Serialize, Deserialize, ToSchema)]
@@ -62,6 +86,20 @@ pub struct AuditDto {
     pub resource_id_type: ResourceIdTypeDto,
     #[serde(rename = "resourceType")]
     pub resource_type: ResourceTypeDto,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub route: Option<String>,
+    #[serde(rename = "requestId", skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permission: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
     #[serde(rename = "createdAt")]
     pub created_at: String,
 }
@@ -69,7 +107,8 @@ pub struct AuditDto {
 impl From<Audit> for AuditDto {
     /// # Summary
     ///
-    /// Convert an Audit to an AuditDto.
+    /// Convert an Audit to an AuditDto. The IDs are always exposed as hexadecimal strings, regardless of the
+    /// ResourceIdType of the Audit.
     ///
     /// # Arguments
     ///
@@ -82,6 +121,10 @@ impl From<Audit> for AuditDto {
         let action = ActionDto::from(value.action);
         let resource_id_type = ResourceIdTypeDto::from(value.resource_id_type);
         let resource_type = ResourceTypeDto::from(value.resource_type);
+        let (sequence, hash) = match value.chain {
+            Some(c) => (Some(c.sequence), Some(c.hash)),
+            None => (None, None),
+        };
 
         AuditDto {
             id: value.id.to_hex(),
@@ -90,6 +133,13 @@ impl From<Audit> for AuditDto {
             resource_id: value.resource_id.to_hex(),
             resource_id_type,
             resource_type,
+            method: value.method,
+            route: value.route,
+            request_id: value.request_id,
+            permission: value.permission,
+            reason: value.reason,
+            sequence,
+            hash,
             created_at: value.created_at.to_rfc3339(),
         }
     }
@@ -112,6 +162,12 @@ impl From<ResourceType> for ResourceTypeDto {
             ResourceType::Permission => ResourceTypeDto::Permission,
             ResourceType::Role => ResourceTypeDto::Role,
             ResourceType::User => ResourceTypeDto::User,
+            ResourceType::Route => ResourceTypeDto::Route,
+            ResourceType::Client => ResourceTypeDto::Client,
+            ResourceType::Consent => ResourceTypeDto::Consent,
+            ResourceType::Organization => ResourceTypeDto::Organization,
+            ResourceType::Invitation => ResourceTypeDto::Invitation,
+            ResourceType::ApiKey => ResourceTypeDto::ApiKey,
         }
     }
 }
@@ -141,11 +197,43 @@ impl From<ResourceIdType> for ResourceIdTypeDto {
             ResourceIdType::UserId => ResourceIdTypeDto::UserId,
             ResourceIdType::UserName => ResourceIdTypeDto::UserName,
             ResourceIdType::UserSearch => ResourceIdTypeDto::UserSearch,
+            ResourceIdType::ClientId => ResourceIdTypeDto::ClientId,
+            ResourceIdType::ConsentId => ResourceIdTypeDto::ConsentId,
+            ResourceIdType::OrganizationId => ResourceIdTypeDto::OrganizationId,
+            ResourceIdType::InvitationId => ResourceIdTypeDto::InvitationId,
+            ResourceIdType::ApiKeyId => ResourceIdTypeDto::ApiKeyId,
             ResourceIdType::None => ResourceIdTypeDto::None,
         }
     }
 }
 
+impl From<ResourceTypeDto> for ResourceType {
+    /// # Summary
+    ///
+    /// Convert a ResourceTypeDto to a ResourceType.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - A ResourceTypeDto.
+    ///
+    /// # Returns
+    ///
+    /// A ResourceType.
+    fn from(value: ResourceTypeDto) -> Self {
+        match value {
+            ResourceTypeDto::Permission => ResourceType::Permission,
+            ResourceTypeDto::Role => ResourceType::Role,
+            ResourceTypeDto::User => ResourceType::User,
+            ResourceTypeDto::Route => ResourceType::Route,
+            ResourceTypeDto::Client => ResourceType::Client,
+            ResourceTypeDto::Consent => ResourceType::Consent,
+            ResourceTypeDto::Organization => ResourceType::Organization,
+            ResourceTypeDto::Invitation => ResourceType::Invitation,
+            ResourceTypeDto::ApiKey => ResourceType::ApiKey,
+        }
+    }
+}
+
 impl From<Action> for ActionDto {
     /// # Summary
     ///
@@ -163,6 +251,29 @@ impl From<Action> for ActionDto {
             Action::Create => ActionDto::Create,
             Action::Update => ActionDto::Update,
             Action::Delete => ActionDto::Delete,
+            Action::Deny => ActionDto::Deny,
+        }
+    }
+}
+
+impl From<ActionDto> for Action {
+    /// # Summary
+    ///
+    /// Convert an ActionDto to an Action.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - An ActionDto.
+    ///
+    /// # Returns
+    ///
+    /// An Action.
+    fn from(value: ActionDto) -> Self {
+        match value {
+            ActionDto::Create => Action::Create,
+            ActionDto::Update => Action::Update,
+            ActionDto::Delete => Action::Delete,
+            ActionDto::Deny => Action::Deny,
         }
     }
 }
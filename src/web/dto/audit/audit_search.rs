@@ -0,0 +1,12 @@
+use crate::web::dto::audit::audit_dto::{ActionDto, ResourceTypeDto};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct AuditSearchRequest {
+    pub text: Option<String>,
+    pub limit: Option<i64>,
+    pub page: Option<i64>,
+    pub action: Option<ActionDto>,
+    #[serde(rename = "resourceType")]
+    pub resource_type: Option<ResourceTypeDto>,
+}
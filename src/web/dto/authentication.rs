@@ -1,3 +1,12 @@
+pub mod finish_passkey_login;
 pub mod login_request;
 pub mod login_response;
+pub mod logout_request;
+pub mod magic_link_login_request;
+pub mod magic_link_request;
+pub mod mfa_login_request;
+pub mod passkey_login_request;
+pub mod refresh_request;
 pub mod register_request;
+pub mod social_callback_query;
+pub mod switch_tenant_request;
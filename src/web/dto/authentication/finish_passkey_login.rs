@@ -0,0 +1,29 @@
+use crate::services::mfa::webauthn_service::PublicKeyCredential;
+use crate::web::dto::sanitize::{require_non_empty, Sanitize, SanitizeError};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct FinishPasskeyLogin {
+    #[serde(rename = "ceremonyId")]
+    pub ceremony_id: String,
+    #[schema(value_type = Object)]
+    pub credential: PublicKeyCredential,
+    #[serde(rename = "includePermissions", default)]
+    pub include_permissions: bool,
+}
+
+impl Sanitize for FinishPasskeyLogin {
+    /// # Summary
+    ///
+    /// Sanitize the FinishPasskeyLogin.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SanitizeError>` - The result of the operation.
+    fn sanitize(&mut self) -> Result<(), SanitizeError> {
+        require_non_empty("ceremonyId", &self.ceremony_id)?;
+
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+use crate::web::dto::sanitize::{require_non_empty, sanitize_required, Sanitize, SanitizeError};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
@@ -5,4 +6,24 @@ use utoipa::ToSchema;
 pub struct LoginRequest {
     pub username: String,
     pub password: String,
+    #[serde(rename = "includePermissions", default)]
+    pub include_permissions: bool,
+    #[serde(default)]
+    pub captcha: Option<String>,
+}
+
+impl Sanitize for LoginRequest {
+    /// # Summary
+    ///
+    /// Sanitize the LoginRequest.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SanitizeError>` - The result of the operation.
+    fn sanitize(&mut self) -> Result<(), SanitizeError> {
+        sanitize_required("username", &mut self.username)?;
+        require_non_empty("password", &self.password)?;
+
+        Ok(())
+    }
 }
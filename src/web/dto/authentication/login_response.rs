@@ -4,6 +4,26 @@ use utoipa::ToSchema;
 #[derive(Deserialize, Serialize, ToSchema)]
 pub struct LoginResponse {
     pub token: String,
+    #[serde(rename = "tokenType")]
+    pub token_type: String,
+    #[serde(rename = "expiresIn")]
+    pub expires_in: usize,
+    #[serde(rename = "issuedAt")]
+    pub issued_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<Vec<String>>,
+    #[serde(
+        rename = "refreshToken",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub refresh_token: Option<String>,
+    #[serde(
+        rename = "refreshExpiresIn",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub refresh_expires_in: Option<u64>,
 }
 
 impl LoginResponse {
@@ -14,17 +34,77 @@ impl LoginResponse {
     /// # Arguments
     ///
     /// * `token` - The token of the LoginResponse.
+    /// * `expires_in` - The number of seconds until the token expires.
+    /// * `issued_at` - The time at which the token was issued, in RFC 3339 format.
     ///
     /// # Example
     ///
     /// ```
-    /// let login_response = LoginResponse::new(String::from("token"));
+    /// let login_response = LoginResponse::new(String::from("token"), 3600, String::from("2024-01-01T00:00:00+00:00"));
     /// ```
     ///
     /// # Returns
     ///
     /// * `LoginResponse` - The new LoginResponse.
-    pub fn new(token: String) -> LoginResponse {
-        LoginResponse { token }
+    pub fn new(token: String, expires_in: usize, issued_at: String) -> LoginResponse {
+        LoginResponse {
+            token,
+            token_type: String::from("Bearer"),
+            expires_in,
+            issued_at,
+            permissions: None,
+            refresh_token: None,
+            refresh_expires_in: None,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Add the names of the permissions of the User to the LoginResponse.
+    ///
+    /// # Arguments
+    ///
+    /// * `permissions` - The names of the permissions.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let login_response = login_response.with_permissions(vec![String::from("CAN_READ_USER")]);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `LoginResponse` - The LoginResponse with the permissions.
+    pub fn with_permissions(mut self, permissions: Vec<String>) -> LoginResponse {
+        self.permissions = Some(permissions);
+        self
+    }
+
+    /// # Summary
+    ///
+    /// Add a refresh token to the LoginResponse.
+    ///
+    /// # Arguments
+    ///
+    /// * `refresh_token` - The refresh token.
+    /// * `refresh_expires_in` - The number of seconds until the refresh token expires.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let login_response = login_response.with_refresh_token(String::from("token"), 1209600);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `LoginResponse` - The LoginResponse with the refresh token.
+    pub fn with_refresh_token(
+        mut self,
+        refresh_token: String,
+        refresh_expires_in: u64,
+    ) -> LoginResponse {
+        self.refresh_token = Some(refresh_token);
+        self.refresh_expires_in = Some(refresh_expires_in);
+        self
     }
 }
@@ -0,0 +1,22 @@
+use crate::web::dto::sanitize::{Sanitize, SanitizeError};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct LogoutRequest {
+    #[serde(rename = "refreshToken", default)]
+    pub refresh_token: Option<String>,
+}
+
+impl Sanitize for LogoutRequest {
+    /// # Summary
+    ///
+    /// Sanitize the LogoutRequest. The refresh token is kept verbatim.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SanitizeError>` - The result of the operation.
+    fn sanitize(&mut self) -> Result<(), SanitizeError> {
+        Ok(())
+    }
+}
@@ -0,0 +1,25 @@
+use crate::web::dto::sanitize::{require_non_empty, Sanitize, SanitizeError};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct MagicLinkLoginRequest {
+    pub token: String,
+    #[serde(rename = "includePermissions", default)]
+    pub include_permissions: bool,
+}
+
+impl Sanitize for MagicLinkLoginRequest {
+    /// # Summary
+    ///
+    /// Sanitize the MagicLinkLoginRequest.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SanitizeError>` - The result of the operation.
+    fn sanitize(&mut self) -> Result<(), SanitizeError> {
+        require_non_empty("token", &self.token)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,25 @@
+use crate::web::dto::sanitize::{sanitize_required, Sanitize, SanitizeError};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct MagicLinkRequest {
+    pub email: String,
+    #[serde(default)]
+    pub captcha: Option<String>,
+}
+
+impl Sanitize for MagicLinkRequest {
+    /// # Summary
+    ///
+    /// Sanitize the MagicLinkRequest.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SanitizeError>` - The result of the operation.
+    fn sanitize(&mut self) -> Result<(), SanitizeError> {
+        sanitize_required("email", &mut self.email)?;
+
+        Ok(())
+    }
+}
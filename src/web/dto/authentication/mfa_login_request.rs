@@ -0,0 +1,30 @@
+use crate::web::dto::sanitize::{require_non_empty, Sanitize, SanitizeError};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct MfaLoginRequest {
+    #[serde(rename = "challengeToken")]
+    pub challenge_token: String,
+    pub code: String,
+    #[serde(rename = "includePermissions", default)]
+    pub include_permissions: bool,
+    #[serde(default)]
+    pub captcha: Option<String>,
+}
+
+impl Sanitize for MfaLoginRequest {
+    /// # Summary
+    ///
+    /// Sanitize the MfaLoginRequest.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SanitizeError>` - The result of the operation.
+    fn sanitize(&mut self) -> Result<(), SanitizeError> {
+        require_non_empty("challengeToken", &self.challenge_token)?;
+        require_non_empty("code", &self.code)?;
+
+        Ok(())
+    }
+}
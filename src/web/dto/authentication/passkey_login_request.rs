@@ -0,0 +1,25 @@
+use crate::web::dto::sanitize::{sanitize_required, Sanitize, SanitizeError};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct PasskeyLoginRequest {
+    pub username: String,
+    #[serde(default)]
+    pub captcha: Option<String>,
+}
+
+impl Sanitize for PasskeyLoginRequest {
+    /// # Summary
+    ///
+    /// Sanitize the PasskeyLoginRequest.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SanitizeError>` - The result of the operation.
+    fn sanitize(&mut self) -> Result<(), SanitizeError> {
+        sanitize_required("username", &mut self.username)?;
+
+        Ok(())
+    }
+}
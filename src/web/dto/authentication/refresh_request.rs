@@ -0,0 +1,24 @@
+use crate::web::dto::sanitize::{require_non_empty, Sanitize, SanitizeError};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct RefreshRequest {
+    #[serde(rename = "refreshToken")]
+    pub refresh_token: String,
+}
+
+impl Sanitize for RefreshRequest {
+    /// # Summary
+    ///
+    /// Sanitize the RefreshRequest.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SanitizeError>` - The result of the operation.
+    fn sanitize(&mut self) -> Result<(), SanitizeError> {
+        require_non_empty("refreshToken", &self.refresh_token)?;
+
+        Ok(())
+    }
+}
@@ -1,3 +1,6 @@
+use crate::web::dto::sanitize::{
+    require_non_empty, sanitize_optional, sanitize_required, Sanitize, SanitizeError,
+};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
@@ -9,5 +12,33 @@ pub struct RegisterRequest {
     pub first_name: Option<String>,
     #[serde(rename = "lastName")]
     pub last_name: Option<String>,
+    #[serde(rename = "phoneNumber")]
+    pub phone_number: Option<String>,
+    pub locale: Option<String>,
+    pub timezone: Option<String>,
     pub password: String,
+    pub invitation: Option<String>,
+}
+
+impl Sanitize for RegisterRequest {
+    /// # Summary
+    ///
+    /// Sanitize the RegisterRequest.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SanitizeError>` - The result of the operation.
+    fn sanitize(&mut self) -> Result<(), SanitizeError> {
+        sanitize_required("username", &mut self.username)?;
+        sanitize_optional("email", &mut self.email)?;
+        sanitize_optional("firstName", &mut self.first_name)?;
+        sanitize_optional("lastName", &mut self.last_name)?;
+        sanitize_optional("phoneNumber", &mut self.phone_number)?;
+        sanitize_optional("locale", &mut self.locale)?;
+        sanitize_optional("timezone", &mut self.timezone)?;
+        require_non_empty("password", &self.password)?;
+        sanitize_optional("invitation", &mut self.invitation)?;
+
+        Ok(())
+    }
 }
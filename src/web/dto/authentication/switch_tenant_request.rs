@@ -0,0 +1,24 @@
+use crate::web::dto::sanitize::{sanitize_optional, Sanitize, SanitizeError};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct SwitchTenantRequest {
+    #[serde(rename = "organizationId")]
+    pub organization_id: Option<String>,
+}
+
+impl Sanitize for SwitchTenantRequest {
+    /// # Summary
+    ///
+    /// Sanitize the SwitchTenantRequest.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SanitizeError>` - The result of the operation.
+    fn sanitize(&mut self) -> Result<(), SanitizeError> {
+        sanitize_optional("organizationId", &mut self.organization_id)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,2 @@
+pub mod authorization_request;
+pub mod authorization_response;
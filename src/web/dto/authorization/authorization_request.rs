@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize, ToSchema, Clone)]
+pub struct AuthorizationCheck {
+    pub subject: String,
+    pub permission: String,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct BatchAuthorizationRequest {
+    pub checks: Vec<AuthorizationCheck>,
+}
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct AuthorizationResult {
+    pub subject: String,
+    pub permission: String,
+    pub allowed: bool,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct BatchAuthorizationResponse {
+    pub results: Vec<AuthorizationResult>,
+}
+
+impl BatchAuthorizationResponse {
+    /// # Summary
+    ///
+    /// Create a new BatchAuthorizationResponse.
+    ///
+    /// # Arguments
+    ///
+    /// * `results` - The results of the authorization checks, in the order in which they were requested.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let batch_authorization_response = BatchAuthorizationResponse::new(vec![]);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `BatchAuthorizationResponse` - The new BatchAuthorizationResponse.
+    pub fn new(results: Vec<AuthorizationResult>) -> BatchAuthorizationResponse {
+        BatchAuthorizationResponse { results }
+    }
+}
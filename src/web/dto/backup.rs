@@ -0,0 +1 @@
+pub mod backup_dto;
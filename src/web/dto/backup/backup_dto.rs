@@ -0,0 +1,58 @@
+use crate::repository::backup::backup_model::RestoreSummary;
+use crate::web::dto::sanitize::{sanitize_required, Sanitize, SanitizeError};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct BackupDto {
+    pub data: String,
+}
+
+impl Sanitize for BackupDto {
+    /// # Summary
+    ///
+    /// Sanitize the BackupDto.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SanitizeError>` - The result of the operation.
+    fn sanitize(&mut self) -> Result<(), SanitizeError> {
+        sanitize_required("data", &mut self.data)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BackupQuery {
+    #[serde(rename = "includePasswords")]
+    pub include_passwords: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct RestoreSummaryDto {
+    pub permissions: u64,
+    pub roles: u64,
+    pub users: u64,
+}
+
+impl From<RestoreSummary> for RestoreSummaryDto {
+    /// # Summary
+    ///
+    /// Convert a RestoreSummary into a RestoreSummaryDto.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The RestoreSummary to be converted.
+    ///
+    /// # Returns
+    ///
+    /// * `RestoreSummaryDto` - The new RestoreSummaryDto.
+    fn from(value: RestoreSummary) -> Self {
+        RestoreSummaryDto {
+            permissions: value.permissions,
+            roles: value.roles,
+            users: value.users,
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod client_dto;
+pub mod create_client;
+pub mod update_client;
@@ -0,0 +1,68 @@
+use crate::repository::client::client_model::Client;
+use crate::web::dto::permission::permission_dto::PermissionDto;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ClientDto {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub permissions: Option<Vec<PermissionDto>>,
+    pub enabled: bool,
+    pub public: bool,
+    #[serde(rename = "redirectUris")]
+    pub redirect_uris: Vec<String>,
+    #[serde(rename = "grantTypes")]
+    pub grant_types: Vec<String>,
+    pub scopes: Vec<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ClientSecretDto {
+    #[serde(rename = "clientId")]
+    pub client_id: String,
+    #[serde(rename = "clientSecret")]
+    pub client_secret: String,
+}
+
+impl From<Client> for ClientDto {
+    /// # Summary
+    ///
+    /// Convert a Client entity into a ClientDto.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The Client entity to be converted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let client = Client::new(String::from("name"), None, None);
+    ///
+    /// let client_dto = ClientDto::from(client);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `ClientDto` - The new ClientDto.
+    fn from(value: Client) -> Self {
+        ClientDto {
+            id: value.id.to_hex(),
+            name: value.name,
+            description: value.description,
+            permissions: None,
+            enabled: value.enabled,
+            public: value.public,
+            redirect_uris: value.redirect_uris,
+            grant_types: value.grant_types,
+            scopes: value.scopes,
+            created_at: value.created_at.to_rfc3339(),
+            updated_at: value.updated_at.to_rfc3339(),
+        }
+    }
+}
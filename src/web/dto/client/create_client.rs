@@ -0,0 +1,31 @@
+use crate::web::dto::sanitize::{sanitize_required, Sanitize, SanitizeError};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct CreateClient {
+    pub name: String,
+    pub description: Option<String>,
+    pub permissions: Option<Vec<String>>,
+    pub public: Option<bool>,
+    #[serde(rename = "redirectUris")]
+    pub redirect_uris: Option<Vec<String>>,
+    #[serde(rename = "grantTypes")]
+    pub grant_types: Option<Vec<String>>,
+    pub scopes: Option<Vec<String>>,
+}
+
+impl Sanitize for CreateClient {
+    /// # Summary
+    ///
+    /// Sanitize the CreateClient.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SanitizeError>` - The result of the operation.
+    fn sanitize(&mut self) -> Result<(), SanitizeError> {
+        sanitize_required("name", &mut self.name)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,32 @@
+use crate::web::dto::sanitize::{sanitize_required, Sanitize, SanitizeError};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct UpdateClient {
+    pub name: String,
+    pub description: Option<String>,
+    pub permissions: Option<Vec<String>>,
+    pub enabled: bool,
+    pub public: bool,
+    #[serde(rename = "redirectUris")]
+    pub redirect_uris: Vec<String>,
+    #[serde(rename = "grantTypes")]
+    pub grant_types: Vec<String>,
+    pub scopes: Vec<String>,
+}
+
+impl Sanitize for UpdateClient {
+    /// # Summary
+    ///
+    /// Sanitize the UpdateClient.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SanitizeError>` - The result of the operation.
+    fn sanitize(&mut self) -> Result<(), SanitizeError> {
+        sanitize_required("name", &mut self.name)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,2 @@
+pub mod consent_dto;
+pub mod grant_consent;
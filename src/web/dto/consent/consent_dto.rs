@@ -0,0 +1,55 @@
+use crate::repository::consent::consent_model::Consent;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ConsentDto {
+    pub id: String,
+    #[serde(rename = "clientId")]
+    pub client_id: String,
+    #[serde(rename = "clientName")]
+    pub client_name: Option<String>,
+    pub scopes: Vec<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ConsentCheckDto {
+    #[serde(rename = "consentRequired")]
+    pub consent_required: bool,
+    #[serde(rename = "missingScopes")]
+    pub missing_scopes: Vec<String>,
+}
+
+impl From<Consent> for ConsentDto {
+    /// # Summary
+    ///
+    /// Convert a Consent entity into a ConsentDto.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The Consent entity to be converted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let consent_dto = ConsentDto::from(consent);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `ConsentDto` - The new ConsentDto.
+    fn from(value: Consent) -> Self {
+        ConsentDto {
+            id: value.id.to_hex(),
+            client_id: value.client_id.to_hex(),
+            client_name: None,
+            scopes: value.scopes,
+            created_at: value.created_at.to_rfc3339(),
+            updated_at: value.updated_at.to_rfc3339(),
+        }
+    }
+}
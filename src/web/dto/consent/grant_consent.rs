@@ -0,0 +1,32 @@
+use crate::web::dto::sanitize::{sanitize_required, Sanitize, SanitizeError};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct GrantConsent {
+    #[serde(rename = "clientId")]
+    pub client_id: String,
+    pub scopes: Vec<String>,
+}
+
+impl Sanitize for GrantConsent {
+    /// # Summary
+    ///
+    /// Sanitize the GrantConsent.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SanitizeError>` - The result of the operation.
+    fn sanitize(&mut self) -> Result<(), SanitizeError> {
+        sanitize_required("clientId", &mut self.client_id)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ConsentQuery {
+    #[serde(rename = "clientId")]
+    pub client_id: String,
+    pub scope: Option<String>,
+}
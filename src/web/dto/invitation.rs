@@ -0,0 +1,2 @@
+pub mod create_invitation;
+pub mod invitation_dto;
@@ -0,0 +1,48 @@
+use crate::web::dto::sanitize::{sanitize_field, sanitize_required, Sanitize, SanitizeError};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct CreateInvitation {
+    pub email: String,
+    pub roles: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct AcceptInvitation {
+    pub token: String,
+}
+
+impl Sanitize for CreateInvitation {
+    /// # Summary
+    ///
+    /// Sanitize the CreateInvitation.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SanitizeError>` - The result of the operation.
+    fn sanitize(&mut self) -> Result<(), SanitizeError> {
+        sanitize_required("email", &mut self.email)?;
+
+        for r in &mut self.roles {
+            *r = sanitize_field("roles", r)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Sanitize for AcceptInvitation {
+    /// # Summary
+    ///
+    /// Sanitize the AcceptInvitation.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SanitizeError>` - The result of the operation.
+    fn sanitize(&mut self) -> Result<(), SanitizeError> {
+        sanitize_required("token", &mut self.token)?;
+
+        Ok(())
+    }
+}
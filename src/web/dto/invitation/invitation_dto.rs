@@ -0,0 +1,60 @@
+use crate::repository::invitation::invitation_model::Invitation;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct InvitationDto {
+    pub id: String,
+    #[serde(rename = "organizationId")]
+    pub organization_id: String,
+    pub email: String,
+    pub roles: Vec<String>,
+    #[serde(rename = "invitedBy")]
+    pub invited_by: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct InvitationTokenDto {
+    #[serde(rename = "invitationId")]
+    pub invitation_id: String,
+    pub token: String,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: String,
+}
+
+impl From<Invitation> for InvitationDto {
+    /// # Summary
+    ///
+    /// Convert an Invitation entity into an InvitationDto.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The Invitation entity to be converted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let invitation = Invitation::new(organization_id, String::from("user@example.com"), vec![], None, 604800);
+    ///
+    /// let invitation_dto = InvitationDto::from(invitation);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `InvitationDto` - The new InvitationDto.
+    fn from(value: Invitation) -> Self {
+        InvitationDto {
+            id: value.id.to_hex(),
+            organization_id: value.organization_id.to_hex(),
+            email: value.email,
+            roles: value.roles.iter().map(|r| r.to_hex()).collect(),
+            invited_by: value.invited_by.map(|u| u.to_hex()),
+            created_at: value.created_at.to_rfc3339(),
+            expires_at: value.expires_at.to_rfc3339(),
+        }
+    }
+}
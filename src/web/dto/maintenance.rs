@@ -0,0 +1 @@
+pub mod maintenance_dto;
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct MaintenanceDto {
+    pub enabled: bool,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct PurgeResultDto {
+    pub collection: String,
+    pub deleted: u64,
+}
+
+impl PurgeResultDto {
+    /// # Summary
+    ///
+    /// Create a new PurgeResultDto.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection` - The collection that was purged.
+    /// * `deleted` - The amount of expired records that were deleted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let purge_result_dto = PurgeResultDto::new(String::from("sessions"), 12);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `PurgeResultDto` - The new PurgeResultDto.
+    pub fn new(collection: String, deleted: u64) -> Self {
+        PurgeResultDto {
+            collection,
+            deleted,
+        }
+    }
+}
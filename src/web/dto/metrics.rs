@@ -0,0 +1,2 @@
+pub mod cache_metrics_dto;
+pub mod command_metrics_dto;
@@ -0,0 +1,36 @@
+use crate::components::entity_cache::CacheStats;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema)]
+pub struct CacheMetricsDto {
+    pub name: String,
+    pub entries: usize,
+    pub hits: u64,
+    pub misses: u64,
+    #[serde(rename = "hitRate")]
+    pub hit_rate: f64,
+}
+
+impl From<CacheStats> for CacheMetricsDto {
+    /// # Summary
+    ///
+    /// Convert CacheStats into a CacheMetricsDto.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The CacheStats to be converted.
+    ///
+    /// # Returns
+    ///
+    /// * `CacheMetricsDto` - The new CacheMetricsDto.
+    fn from(value: CacheStats) -> Self {
+        CacheMetricsDto {
+            hit_rate: value.hit_rate(),
+            name: value.name,
+            entries: value.entries,
+            hits: value.hits,
+            misses: value.misses,
+        }
+    }
+}
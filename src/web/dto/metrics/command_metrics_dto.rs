@@ -0,0 +1,42 @@
+use crate::components::command_monitor::CommandMetrics;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema)]
+pub struct CommandMetricsDto {
+    pub command: String,
+    pub collection: String,
+    pub count: u64,
+    pub failures: u64,
+    #[serde(rename = "totalMs")]
+    pub total_ms: f64,
+    #[serde(rename = "averageMs")]
+    pub average_ms: f64,
+    #[serde(rename = "maxMs")]
+    pub max_ms: f64,
+}
+
+impl From<CommandMetrics> for CommandMetricsDto {
+    /// # Summary
+    ///
+    /// Convert CommandMetrics into a CommandMetricsDto.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The CommandMetrics to be converted.
+    ///
+    /// # Returns
+    ///
+    /// * `CommandMetricsDto` - The new CommandMetricsDto.
+    fn from(value: CommandMetrics) -> Self {
+        CommandMetricsDto {
+            average_ms: value.average_duration().as_secs_f64() * 1000.0,
+            command: value.command,
+            collection: value.collection,
+            count: value.count,
+            failures: value.failures,
+            total_ms: value.total_duration.as_secs_f64() * 1000.0,
+            max_ms: value.max_duration.as_secs_f64() * 1000.0,
+        }
+    }
+}
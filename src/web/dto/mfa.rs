@@ -0,0 +1,8 @@
+pub mod credential_dto;
+pub mod finish_passkey_registration;
+pub mod passkey_challenge_dto;
+pub mod passkey_registration_request;
+pub mod recovery_code_count_dto;
+pub mod recovery_codes_dto;
+pub mod totp_code_request;
+pub mod totp_enrollment_dto;
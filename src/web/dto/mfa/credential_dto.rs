@@ -0,0 +1,50 @@
+use crate::repository::credential::credential_model::Credential;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct CredentialDto {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    #[serde(rename = "lastUsedAt", skip_serializing_if = "Option::is_none")]
+    pub last_used_at: Option<String>,
+    #[serde(
+        rename = "recoveryCodes",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub recovery_codes: Option<Vec<String>>,
+}
+
+impl From<Credential> for CredentialDto {
+    /// # Summary
+    ///
+    /// Convert a Credential entity into a CredentialDto.
+    ///
+    /// # Arguments
+    ///
+    /// * `credential` - The Credential entity to be converted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let credential = Credential::new(user.id, String::from("Laptop"), passkey);
+    ///
+    /// let credential_dto = CredentialDto::from(credential);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `CredentialDto` - The new CredentialDto.
+    fn from(value: Credential) -> Self {
+        CredentialDto {
+            id: value.id.to_hex(),
+            name: value.name,
+            created_at: value.created_at.to_rfc3339(),
+            last_used_at: value.last_used_at.map(|d| d.to_rfc3339()),
+            recovery_codes: None,
+        }
+    }
+}
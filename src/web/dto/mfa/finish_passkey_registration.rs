@@ -0,0 +1,27 @@
+use crate::services::mfa::webauthn_service::RegisterPublicKeyCredential;
+use crate::web::dto::sanitize::{require_non_empty, Sanitize, SanitizeError};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct FinishPasskeyRegistration {
+    #[serde(rename = "ceremonyId")]
+    pub ceremony_id: String,
+    #[schema(value_type = Object)]
+    pub credential: RegisterPublicKeyCredential,
+}
+
+impl Sanitize for FinishPasskeyRegistration {
+    /// # Summary
+    ///
+    /// Sanitize the FinishPasskeyRegistration.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SanitizeError>` - The result of the operation.
+    fn sanitize(&mut self) -> Result<(), SanitizeError> {
+        require_non_empty("ceremonyId", &self.ceremony_id)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct PasskeyChallengeDto {
+    #[serde(rename = "ceremonyId")]
+    pub ceremony_id: String,
+    pub options: serde_json::Value,
+}
@@ -0,0 +1,23 @@
+use crate::web::dto::sanitize::{sanitize_required, Sanitize, SanitizeError};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct PasskeyRegistrationRequest {
+    pub name: String,
+}
+
+impl Sanitize for PasskeyRegistrationRequest {
+    /// # Summary
+    ///
+    /// Sanitize the PasskeyRegistrationRequest.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SanitizeError>` - The result of the operation.
+    fn sanitize(&mut self) -> Result<(), SanitizeError> {
+        sanitize_required("name", &mut self.name)?;
+
+        Ok(())
+    }
+}
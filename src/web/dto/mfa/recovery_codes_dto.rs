@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct RecoveryCodesDto {
+    #[serde(rename = "recoveryCodes")]
+    pub recovery_codes: Vec<String>,
+}
@@ -0,0 +1,23 @@
+use crate::web::dto::sanitize::{sanitize_required, Sanitize, SanitizeError};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct TotpCodeRequest {
+    pub code: String,
+}
+
+impl Sanitize for TotpCodeRequest {
+    /// # Summary
+    ///
+    /// Sanitize the TotpCodeRequest.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SanitizeError>` - The result of the operation.
+    fn sanitize(&mut self) -> Result<(), SanitizeError> {
+        sanitize_required("code", &mut self.code)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,4 @@
+pub mod authorization_request;
+pub mod device_request;
+pub mod device_response;
+pub mod token;
@@ -0,0 +1,38 @@
+use crate::web::dto::sanitize::{sanitize_optional, sanitize_required, Sanitize, SanitizeError};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct AuthorizationRequest {
+    pub response_type: String,
+    pub client_id: String,
+    pub redirect_uri: Option<String>,
+    pub scope: Option<String>,
+    pub state: Option<String>,
+    pub code_challenge: Option<String>,
+    pub code_challenge_method: Option<String>,
+}
+
+impl Sanitize for AuthorizationRequest {
+    /// # Summary
+    ///
+    /// Sanitize the AuthorizationRequest. The state and the code challenge are returned or compared verbatim, so they are left untouched.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SanitizeError>` - The result of the operation.
+    fn sanitize(&mut self) -> Result<(), SanitizeError> {
+        sanitize_required("response_type", &mut self.response_type)?;
+        sanitize_required("client_id", &mut self.client_id)?;
+        sanitize_optional("redirect_uri", &mut self.redirect_uri)?;
+        sanitize_optional("scope", &mut self.scope)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct AuthorizationResponse {
+    #[serde(rename = "redirectUri")]
+    pub redirect_uri: String,
+}
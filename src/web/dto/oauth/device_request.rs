@@ -0,0 +1,37 @@
+use crate::web::dto::sanitize::{sanitize_required, Sanitize, SanitizeError};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct DeviceCodeRequest {
+    pub client_id: String,
+    pub scope: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct DeviceVerification {
+    #[serde(rename = "userCode")]
+    pub user_code: String,
+    pub approve: bool,
+}
+
+impl Sanitize for DeviceVerification {
+    /// # Summary
+    ///
+    /// Sanitize the DeviceVerification.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SanitizeError>` - The result of the operation.
+    fn sanitize(&mut self) -> Result<(), SanitizeError> {
+        sanitize_required("userCode", &mut self.user_code)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UserCodeQuery {
+    #[serde(rename = "userCode")]
+    pub user_code: String,
+}
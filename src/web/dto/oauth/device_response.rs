@@ -0,0 +1,44 @@
+use crate::repository::device::device_model::DeviceAuthorization;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct DeviceCodeResponse {
+    pub device_code: String,
+    pub user_code: String,
+    pub verification_uri: String,
+    pub verification_uri_complete: String,
+    pub expires_in: u64,
+    pub interval: u64,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct DeviceDto {
+    #[serde(rename = "userCode")]
+    pub user_code: String,
+    #[serde(rename = "clientId")]
+    pub client_id: String,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: String,
+}
+
+impl From<DeviceAuthorization> for DeviceDto {
+    /// # Summary
+    ///
+    /// Convert a DeviceAuthorization into a DeviceDto.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The DeviceAuthorization to be converted.
+    ///
+    /// # Returns
+    ///
+    /// * `DeviceDto` - The new DeviceDto.
+    fn from(value: DeviceAuthorization) -> Self {
+        DeviceDto {
+            user_code: value.user_code,
+            client_id: value.client_id,
+            expires_at: value.expires_at.to_rfc3339(),
+        }
+    }
+}
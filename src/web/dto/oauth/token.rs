@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct TokenRequest {
+    pub grant_type: String,
+    pub device_code: Option<String>,
+    pub code: Option<String>,
+    pub redirect_uri: Option<String>,
+    pub code_verifier: Option<String>,
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+    pub scope: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_in: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+}
+
+impl TokenResponse {
+    /// # Summary
+    ///
+    /// Create a new TokenResponse for a bearer token.
+    ///
+    /// # Arguments
+    ///
+    /// * `access_token` - The access token.
+    /// * `expires_in` - The amount of seconds after which the access token expires.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let token_response = TokenResponse::new(String::from("token"), 3600);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `TokenResponse` - The new TokenResponse.
+    pub fn new(access_token: String, expires_in: usize) -> TokenResponse {
+        TokenResponse {
+            access_token,
+            token_type: String::from("Bearer"),
+            expires_in,
+            scope: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct OAuthError {
+    pub error: String,
+    pub error_description: String,
+}
+
+impl OAuthError {
+    /// # Summary
+    ///
+    /// Create a new OAuthError.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The OAuth 2.0 error code.
+    /// * `error_description` - A human-readable description of the error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let oauth_error = OAuthError::new("authorization_pending", "The authorization request is still pending");
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `OAuthError` - The new OAuthError.
+    pub fn new(error: &str, error_description: &str) -> OAuthError {
+        OAuthError {
+            error: String::from(error),
+            error_description: String::from(error_description),
+        }
+    }
+}
@@ -0,0 +1,4 @@
+pub mod create_organization;
+pub mod organization_dto;
+pub mod update_member;
+pub mod update_organization;
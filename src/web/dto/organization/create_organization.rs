@@ -0,0 +1,24 @@
+use crate::web::dto::sanitize::{sanitize_required, Sanitize, SanitizeError};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct CreateOrganization {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+impl Sanitize for CreateOrganization {
+    /// # Summary
+    ///
+    /// Sanitize the CreateOrganization.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SanitizeError>` - The result of the operation.
+    fn sanitize(&mut self) -> Result<(), SanitizeError> {
+        sanitize_required("name", &mut self.name)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,118 @@
+use crate::repository::organization::organization_model::{Member, Organization};
+use crate::web::dto::role::role_dto::SimpleRoleDto;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct MemberDto {
+    #[serde(rename = "userId")]
+    pub user_id: String,
+    pub roles: Vec<SimpleRoleDto>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct OrganizationDto {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub members: Vec<MemberDto>,
+    #[serde(rename = "defaultRoles")]
+    pub default_roles: Vec<SimpleRoleDto>,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct SimpleOrganizationDto {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+impl From<Member> for MemberDto {
+    /// # Summary
+    ///
+    /// Convert a Member into a MemberDto, without its roles.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The Member to be converted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let member_dto = MemberDto::from(member);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `MemberDto` - The new MemberDto.
+    fn from(value: Member) -> Self {
+        MemberDto {
+            user_id: value.user_id.to_hex(),
+            roles: vec![],
+        }
+    }
+}
+
+impl From<Organization> for OrganizationDto {
+    /// # Summary
+    ///
+    /// Convert an Organization entity into an OrganizationDto. The roles of the members and the default roles are not resolved.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The Organization entity to be converted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let organization = Organization::new(String::from("name"), None);
+    ///
+    /// let organization_dto = OrganizationDto::from(organization);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `OrganizationDto` - The new OrganizationDto.
+    fn from(value: Organization) -> Self {
+        OrganizationDto {
+            id: value.id.to_hex(),
+            name: value.name,
+            description: value.description,
+            members: value.members.into_iter().map(MemberDto::from).collect(),
+            default_roles: vec![],
+            created_at: value.created_at.to_rfc3339(),
+            updated_at: value.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+impl From<Organization> for SimpleOrganizationDto {
+    /// # Summary
+    ///
+    /// Convert an Organization entity into a SimpleOrganizationDto.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The Organization entity to be converted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let organization_dto = SimpleOrganizationDto::from(organization);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `SimpleOrganizationDto` - The new SimpleOrganizationDto.
+    fn from(value: Organization) -> Self {
+        SimpleOrganizationDto {
+            id: value.id.to_hex(),
+            name: value.name,
+            description: value.description,
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use crate::web::dto::sanitize::{sanitize_field, Sanitize, SanitizeError};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct UpdateMember {
+    pub roles: Vec<String>,
+}
+
+impl Sanitize for UpdateMember {
+    /// # Summary
+    ///
+    /// Sanitize the UpdateMember.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SanitizeError>` - The result of the operation.
+    fn sanitize(&mut self) -> Result<(), SanitizeError> {
+        for r in &mut self.roles {
+            *r = sanitize_field("roles", r)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,26 @@
+use crate::web::dto::sanitize::{sanitize_required, Sanitize, SanitizeError};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct UpdateOrganization {
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(rename = "defaultRoles")]
+    pub default_roles: Option<Vec<String>>,
+}
+
+impl Sanitize for UpdateOrganization {
+    /// # Summary
+    ///
+    /// Sanitize the UpdateOrganization.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SanitizeError>` - The result of the operation.
+    fn sanitize(&mut self) -> Result<(), SanitizeError> {
+        sanitize_required("name", &mut self.name)?;
+
+        Ok(())
+    }
+}
@@ -7,6 +7,8 @@ pub struct PermissionDto {
     pub id: String,
     pub name: String,
     pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dependencies: Option<Vec<String>>,
     #[serde(rename = "createdAt")]
     pub created_at: String,
     #[serde(rename = "updatedAt")]
@@ -42,6 +44,7 @@ impl From<Permission> for PermissionDto {
             id: permission.id.to_hex(),
             name: permission.name,
             description: permission.description,
+            dependencies: dependencies(&permission),
             created_at: permission.created_at.to_rfc3339(),
             updated_at: permission.updated_at.to_rfc3339(),
         }
@@ -77,12 +80,32 @@ impl From<&Permission> for PermissionDto {
             id: value.id.to_hex(),
             name: value.name.clone(),
             description: value.description.clone(),
+            dependencies: dependencies(value),
             created_at: value.created_at.to_rfc3339(),
             updated_at: value.updated_at.to_rfc3339(),
         }
     }
 }
 
+/// # Summary
+///
+/// Get the IDs of the Permissions that a Permission depends on.
+///
+/// # Arguments
+///
+/// * `permission` - The Permission.
+///
+/// # Returns
+///
+/// * `Option<Vec<String>>` - The IDs of the dependencies, or None if the Permission has no dependencies.
+fn dependencies(permission: &Permission) -> Option<Vec<String>> {
+    if permission.dependencies.is_empty() {
+        None
+    } else {
+        Some(permission.dependencies.iter().map(|d| d.to_hex()).collect())
+    }
+}
+
 #[derive(Serialize, Deserialize, ToSchema)]
 pub struct SimplePermissionDto {
     pub id: String,
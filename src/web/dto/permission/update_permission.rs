@@ -1,3 +1,4 @@
+use crate::web::dto::sanitize::{sanitize_required, Sanitize, SanitizeError};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
@@ -5,4 +6,20 @@ use utoipa::ToSchema;
 pub struct UpdatePermission {
     pub name: String,
     pub description: Option<String>,
+    pub dependencies: Option<Vec<String>>,
+}
+
+impl Sanitize for UpdatePermission {
+    /// # Summary
+    ///
+    /// Sanitize the UpdatePermission.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SanitizeError>` - The result of the operation.
+    fn sanitize(&mut self) -> Result<(), SanitizeError> {
+        sanitize_required("name", &mut self.name)?;
+
+        Ok(())
+    }
 }
@@ -1,3 +1,4 @@
 pub mod create_role;
 pub mod role_dto;
+pub mod role_search;
 pub mod update_role;
@@ -1,6 +1,7 @@
 use crate::repository::role::role_model::Role;
 use crate::web::dto::permission::permission_dto::{PermissionDto, SimplePermissionDto};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use utoipa::ToSchema;
 
 #[derive(Serialize, Deserialize, ToSchema)]
@@ -9,10 +10,21 @@ pub struct RoleDto {
     pub name: String,
     pub description: Option<String>,
     pub permissions: Option<Vec<PermissionDto>>,
+    #[serde(rename = "organizationId", skip_serializing_if = "Option::is_none")]
+    pub organization_id: Option<String>,
+    #[serde(rename = "requireMfa")]
+    pub require_mfa: bool,
+    #[serde(
+        rename = "permissionExpirations",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub permission_expirations: Option<HashMap<String, String>>,
     #[serde(rename = "createdAt")]
     pub created_at: String,
     #[serde(rename = "updatedAt")]
     pub updated_at: String,
+    #[serde(rename = "memberCount", skip_serializing_if = "Option::is_none")]
+    pub member_count: Option<u64>,
 }
 
 impl From<Role> for RoleDto {
@@ -45,8 +57,12 @@ impl From<Role> for RoleDto {
             name: value.name,
             description: value.description,
             permissions: None,
+            organization_id: value.organization_id.map(|o| o.to_hex()),
+            require_mfa: value.require_mfa,
+            permission_expirations: permission_expirations(&value),
             created_at: value.created_at.to_rfc3339(),
             updated_at: value.updated_at.to_rfc3339(),
+            member_count: None,
         }
     }
 }
@@ -81,12 +97,40 @@ impl From<&Role> for RoleDto {
             name: value.name.clone(),
             description: value.description.clone(),
             permissions: None,
+            organization_id: value.organization_id.map(|o| o.to_hex()),
+            require_mfa: value.require_mfa,
+            permission_expirations: permission_expirations(value),
             created_at: value.created_at.to_rfc3339(),
             updated_at: value.updated_at.to_rfc3339(),
+            member_count: None,
         }
     }
 }
 
+/// # Summary
+///
+/// Map the IDs of the Permissions of a Role that expire to the RFC 3339 date and time at which they expire.
+///
+/// # Arguments
+///
+/// * `role` - The Role.
+///
+/// # Returns
+///
+/// * `Option<HashMap<String, String>>` - The expirations, or None if all Permissions are granted indefinitely.
+fn permission_expirations(role: &Role) -> Option<HashMap<String, String>> {
+    if role.permission_expirations.is_empty() {
+        None
+    } else {
+        Some(
+            role.permission_expirations
+                .iter()
+                .map(|e| (e.permission.to_hex(), e.expires_at.to_rfc3339()))
+                .collect(),
+        )
+    }
+}
+
 #[derive(Serialize, Deserialize, ToSchema)]
 pub struct SimpleRoleDto {
     pub id: String,
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct RoleSearchRequest {
+    pub text: Option<String>,
+    pub limit: Option<i64>,
+    pub page: Option<i64>,
+    pub permission: Option<String>,
+    #[serde(rename = "createdAfter")]
+    pub created_after: Option<String>,
+    #[serde(rename = "createdBefore")]
+    pub created_before: Option<String>,
+    #[serde(default)]
+    pub highlight: bool,
+}
@@ -1,4 +1,6 @@
+use crate::web::dto::sanitize::{sanitize_required, Sanitize, SanitizeError};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use utoipa::ToSchema;
 
 #[derive(Serialize, Deserialize, ToSchema)]
@@ -6,4 +8,23 @@ pub struct UpdateRole {
     pub name: String,
     pub description: Option<String>,
     pub permissions: Option<Vec<String>>,
+    #[serde(rename = "requireMfa")]
+    pub require_mfa: Option<bool>,
+    #[serde(rename = "permissionExpirations")]
+    pub permission_expirations: Option<HashMap<String, String>>,
+}
+
+impl Sanitize for UpdateRole {
+    /// # Summary
+    ///
+    /// Sanitize the UpdateRole.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SanitizeError>` - The result of the operation.
+    fn sanitize(&mut self) -> Result<(), SanitizeError> {
+        sanitize_required("name", &mut self.name)?;
+
+        Ok(())
+    }
 }
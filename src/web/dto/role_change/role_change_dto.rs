@@ -0,0 +1,83 @@
+use crate::repository::role_change::role_change_model::{RoleChange, RoleChangeKind};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct RoleChangeDto {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub change_type: String,
+    #[serde(rename = "roleId")]
+    pub role_id: String,
+    #[serde(rename = "userId", skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<Vec<String>>,
+    pub status: String,
+    #[serde(rename = "requestedBy")]
+    pub requested_by: String,
+    #[serde(rename = "reviewedBy")]
+    pub reviewed_by: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    #[serde(rename = "reviewedAt")]
+    pub reviewed_at: Option<String>,
+}
+
+impl From<RoleChange> for RoleChangeDto {
+    /// # Summary
+    ///
+    /// Convert a RoleChange entity into a RoleChangeDto.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The RoleChange entity to be converted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let role_change = RoleChange::new(RoleChangeKind::AdminGrant { user_id, role_id }, requested_by);
+    ///
+    /// let role_change_dto = RoleChangeDto::from(role_change);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `RoleChangeDto` - The new RoleChangeDto.
+    fn from(value: RoleChange) -> Self {
+        let (change_type, role_id, user_id, permissions) = match value.change {
+            RoleChangeKind::RolePermissions {
+                role_id,
+                permissions,
+                ..
+            } => (
+                "rolePermissions",
+                role_id,
+                None,
+                Some(
+                    permissions
+                        .unwrap_or_default()
+                        .iter()
+                        .map(|p| p.to_hex())
+                        .collect(),
+                ),
+            ),
+            RoleChangeKind::AdminGrant { user_id, role_id } => {
+                ("adminGrant", role_id, Some(user_id.to_hex()), None)
+            }
+        };
+
+        RoleChangeDto {
+            id: value.id.to_hex(),
+            change_type: String::from(change_type),
+            role_id: role_id.to_hex(),
+            user_id,
+            permissions,
+            status: value.status.to_string(),
+            requested_by: value.requested_by.to_hex(),
+            reviewed_by: value.reviewed_by.map(|u| u.to_hex()),
+            created_at: value.created_at.to_rfc3339(),
+            reviewed_at: value.reviewed_at.map(|r| r.to_rfc3339()),
+        }
+    }
+}
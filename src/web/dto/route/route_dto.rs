@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct RouteDto {
+    pub method: String,
+    pub path: String,
+    pub permission: Option<String>,
+    pub tags: Vec<String>,
+}
+
+impl RouteDto {
+    /// # Summary
+    ///
+    /// Create a new RouteDto.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The HTTP method of the route.
+    /// * `path` - The path of the route.
+    /// * `permission` - The permission that is required to access the route, if any.
+    /// * `tags` - The tags of the route.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let route_dto = RouteDto::new("GET", "/api/v1/roles/", Some(String::from("CAN_READ_ROLE")), vec![]);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `RouteDto` - The new RouteDto.
+    pub fn new(method: &str, path: &str, permission: Option<String>, tags: Vec<String>) -> Self {
+        RouteDto {
+            method: method.to_string(),
+            path: path.to_string(),
+            permission,
+            tags,
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use crate::errors::into_http_response::IntoHttpResponse;
+use actix_web::http::StatusCode;
+use unicode_normalization::UnicodeNormalization;
+
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum SanitizeError {
+    #[error("{0} cannot be empty")]
+    Empty(&'static str),
+    #[error("{0} cannot contain control characters")]
+    ControlCharacter(&'static str),
+}
+
+impl IntoHttpResponse for SanitizeError {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::BAD_REQUEST
+    }
+}
+
+pub trait Sanitize {
+    /// # Summary
+    ///
+    /// Sanitize the user-provided fields of a DTO in place.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut create_permission = CreatePermission { name: String::from(" CAN_READ "), description: None };
+    /// create_permission.sanitize()?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SanitizeError>` - The result of the operation.
+    fn sanitize(&mut self) -> Result<(), SanitizeError>;
+}
+
+/// # Summary
+///
+/// Trim leading and trailing whitespace, apply Unicode NFC normalization and reject control characters.
+///
+/// # Arguments
+///
+/// * `field` - The name of the field, used in the error message.
+/// * `value` - The value to sanitize.
+///
+/// # Example
+///
+/// ```
+/// let username = sanitize_field("username", " admin\u{0301} ")?;
+/// ```
+///
+/// # Returns
+///
+/// * `Result<String, SanitizeError>` - The sanitized value or the SanitizeError that occurred.
+pub fn sanitize_field(field: &'static str, value: &str) -> Result<String, SanitizeError> {
+    let value: String = value.trim().nfc().collect();
+
+    if value.chars().any(char::is_control) {
+        return Err(SanitizeError::ControlCharacter(field));
+    }
+
+    Ok(value)
+}
+
+/// # Summary
+///
+/// Sanitize a required field in place and reject it if it is empty after sanitization.
+///
+/// # Arguments
+///
+/// * `field` - The name of the field, used in the error message.
+/// * `value` - The value to sanitize.
+///
+/// # Example
+///
+/// ```
+/// sanitize_required("name", &mut self.name)?;
+/// ```
+///
+/// # Returns
+///
+/// * `Result<(), SanitizeError>` - The result of the operation.
+pub fn sanitize_required(field: &'static str, value: &mut String) -> Result<(), SanitizeError> {
+    *value = sanitize_field(field, value)?;
+    require_non_empty(field, value)
+}
+
+/// # Summary
+///
+/// Sanitize an optional field in place.
+///
+/// # Arguments
+///
+/// * `field` - The name of the field, used in the error message.
+/// * `value` - The value to sanitize.
+///
+/// # Example
+///
+/// ```
+/// sanitize_optional("email", &mut self.email)?;
+/// ```
+///
+/// # Returns
+///
+/// * `Result<(), SanitizeError>` - The result of the operation.
+pub fn sanitize_optional(
+    field: &'static str,
+    value: &mut Option<String>,
+) -> Result<(), SanitizeError> {
+    if let Some(v) = value {
+        *v = sanitize_field(field, v)?;
+    }
+
+    Ok(())
+}
+
+/// # Summary
+///
+/// Reject a field that is empty without otherwise modifying it. Used for secrets such as passwords, which must be kept verbatim.
+///
+/// # Arguments
+///
+/// * `field` - The name of the field, used in the error message.
+/// * `value` - The value to check.
+///
+/// # Example
+///
+/// ```
+/// require_non_empty("password", &self.password)?;
+/// ```
+///
+/// # Returns
+///
+/// * `Result<(), SanitizeError>` - The result of the operation.
+pub fn require_non_empty(field: &'static str, value: &str) -> Result<(), SanitizeError> {
+    if value.is_empty() {
+        return Err(SanitizeError::Empty(field));
+    }
+
+    Ok(())
+}
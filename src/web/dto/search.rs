@@ -1 +1,2 @@
 pub mod search_request;
+pub mod search_result_dto;
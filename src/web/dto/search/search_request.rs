@@ -5,4 +5,6 @@ pub struct SearchRequest {
     pub text: Option<String>,
     pub limit: Option<i64>,
     pub page: Option<i64>,
+    #[serde(default)]
+    pub highlight: bool,
 }
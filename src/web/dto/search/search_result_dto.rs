@@ -0,0 +1,71 @@
+use crate::components::search_highlighter::SearchHighlighter;
+use serde::Serialize;
+use std::collections::HashMap;
+
+#[derive(Serialize)]
+pub struct SearchResultDto<T: Serialize> {
+    #[serde(flatten)]
+    pub item: T,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub highlights: Option<HashMap<String, String>>,
+}
+
+impl<T: Serialize> SearchResultDto<T> {
+    /// # Summary
+    ///
+    /// Create a new SearchResultDto from the DTO of a SearchHit.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The DTO of the entity that was found.
+    /// * `score` - The relevance of the entity, if it was found by a text search.
+    /// * `highlights` - The highlighted fields of the entity, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let result = SearchResultDto::new(PermissionDto::from(&hit.entity), hit.score, None);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `SearchResultDto<T>` - The new SearchResultDto.
+    pub fn new(
+        item: T,
+        score: Option<f64>,
+        highlights: Option<HashMap<String, String>>,
+    ) -> SearchResultDto<T> {
+        SearchResultDto {
+            item,
+            score,
+            highlights,
+        }
+    }
+}
+
+/// # Summary
+///
+/// Create the SearchHighlighter of a search request, if highlighting was requested and the request searches for text.
+///
+/// # Arguments
+///
+/// * `text` - The text that was searched for.
+/// * `highlight` - Whether highlighting was requested.
+///
+/// # Example
+///
+/// ```
+/// let highlighter = highlighter_for(search.text.as_deref(), search.highlight);
+/// ```
+///
+/// # Returns
+///
+/// * `Option<SearchHighlighter>` - The SearchHighlighter, or None if no highlights should be returned.
+pub fn highlighter_for(text: Option<&str>, highlight: bool) -> Option<SearchHighlighter> {
+    match (text, highlight) {
+        (Some(t), true) => Some(SearchHighlighter::new(t)),
+        _ => None,
+    }
+}
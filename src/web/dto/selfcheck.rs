@@ -0,0 +1 @@
+pub mod selfcheck_dto;
@@ -0,0 +1,60 @@
+use crate::components::self_check::{CheckResult, CheckStatus, SelfCheckReport};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema)]
+pub struct CheckResultDto {
+    pub name: String,
+    pub status: CheckStatus,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remedy: Option<String>,
+}
+
+impl From<CheckResult> for CheckResultDto {
+    /// # Summary
+    ///
+    /// Convert a CheckResult into a CheckResultDto.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The CheckResult to be converted.
+    ///
+    /// # Returns
+    ///
+    /// * `CheckResultDto` - The new CheckResultDto.
+    fn from(value: CheckResult) -> Self {
+        CheckResultDto {
+            name: value.name,
+            status: value.status,
+            message: value.message,
+            remedy: value.remedy,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SelfCheckDto {
+    pub healthy: bool,
+    pub checks: Vec<CheckResultDto>,
+}
+
+impl From<SelfCheckReport> for SelfCheckDto {
+    /// # Summary
+    ///
+    /// Convert a SelfCheckReport into a SelfCheckDto.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The SelfCheckReport to be converted.
+    ///
+    /// # Returns
+    ///
+    /// * `SelfCheckDto` - The new SelfCheckDto.
+    fn from(value: SelfCheckReport) -> Self {
+        SelfCheckDto {
+            healthy: value.is_healthy(),
+            checks: value.checks.into_iter().map(CheckResultDto::from).collect(),
+        }
+    }
+}
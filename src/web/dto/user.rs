@@ -2,3 +2,6 @@ pub mod create_user;
 pub mod update_password;
 pub mod update_user;
 pub mod user_dto;
+pub mod user_email;
+pub mod user_search;
+pub mod user_summary;
@@ -1,3 +1,6 @@
+use crate::web::dto::sanitize::{
+    require_non_empty, sanitize_optional, sanitize_required, Sanitize, SanitizeError,
+};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
@@ -9,6 +12,38 @@ pub struct CreateUser {
     pub first_name: Option<String>,
     #[serde(rename = "lastName")]
     pub last_name: Option<String>,
+    #[serde(rename = "phoneNumber")]
+    pub phone_number: Option<String>,
+    pub locale: Option<String>,
+    pub timezone: Option<String>,
     pub password: String,
     pub roles: Option<Vec<String>>,
+    #[serde(rename = "activeFrom")]
+    pub active_from: Option<String>,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: Option<String>,
+}
+
+impl Sanitize for CreateUser {
+    /// # Summary
+    ///
+    /// Sanitize the CreateUser.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SanitizeError>` - The result of the operation.
+    fn sanitize(&mut self) -> Result<(), SanitizeError> {
+        sanitize_required("username", &mut self.username)?;
+        sanitize_optional("email", &mut self.email)?;
+        sanitize_optional("firstName", &mut self.first_name)?;
+        sanitize_optional("lastName", &mut self.last_name)?;
+        sanitize_optional("phoneNumber", &mut self.phone_number)?;
+        sanitize_optional("locale", &mut self.locale)?;
+        sanitize_optional("timezone", &mut self.timezone)?;
+        sanitize_optional("activeFrom", &mut self.active_from)?;
+        sanitize_optional("expiresAt", &mut self.expires_at)?;
+        require_non_empty("password", &self.password)?;
+
+        Ok(())
+    }
 }
@@ -1,3 +1,4 @@
+use crate::web::dto::sanitize::{require_non_empty, Sanitize, SanitizeError};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
@@ -9,7 +10,38 @@ pub struct UpdatePassword {
     pub new_password: String,
 }
 
+impl Sanitize for UpdatePassword {
+    /// # Summary
+    ///
+    /// Sanitize the UpdatePassword.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SanitizeError>` - The result of the operation.
+    fn sanitize(&mut self) -> Result<(), SanitizeError> {
+        require_non_empty("oldPassword", &self.old_password)?;
+        require_non_empty("newPassword", &self.new_password)?;
+
+        Ok(())
+    }
+}
+
 #[derive(Deserialize, Serialize, ToSchema)]
 pub struct AdminUpdatePassword {
     pub password: String,
 }
+
+impl Sanitize for AdminUpdatePassword {
+    /// # Summary
+    ///
+    /// Sanitize the AdminUpdatePassword.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SanitizeError>` - The result of the operation.
+    fn sanitize(&mut self) -> Result<(), SanitizeError> {
+        require_non_empty("password", &self.password)?;
+
+        Ok(())
+    }
+}
@@ -1,23 +1,71 @@
 use crate::repository::user::user_model::User;
+use crate::repository::user::user_source::UserSource;
+use crate::repository::user::user_status::UserStatus;
 use crate::web::dto::role::role_dto::{RoleDto, SimpleRoleDto};
+use crate::web::dto::user::user_email::UserEmailDto;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use utoipa::ToSchema;
 
+/// # Summary
+///
+/// Derive the Gravatar URL of an email address.
+///
+/// # Arguments
+///
+/// * `email` - The email address.
+///
+/// # Example
+///
+/// ```
+/// let avatar_url = gravatar_url("example@codedead.com");
+/// ```
+///
+/// # Returns
+///
+/// * `String` - The Gravatar URL, which falls back to a generated image if no Gravatar exists for the email address.
+pub fn gravatar_url(email: &str) -> String {
+    let hash = Sha256::digest(email.trim().to_lowercase().as_bytes());
+    format!("https://www.gravatar.com/avatar/{:x}?d=identicon", hash)
+}
+
 #[derive(Serialize, Deserialize, ToSchema)]
 pub struct UserDto {
     pub id: String,
     pub username: String,
     pub email: Option<String>,
+    #[serde(rename = "emailVerified")]
+    pub email_verified: bool,
+    #[serde(rename = "secondaryEmails")]
+    pub secondary_emails: Vec<UserEmailDto>,
     #[serde(rename = "firstName")]
     pub first_name: Option<String>,
     #[serde(rename = "lastName")]
     pub last_name: Option<String>,
+    #[serde(rename = "phoneNumber")]
+    pub phone_number: Option<String>,
+    pub locale: Option<String>,
+    pub timezone: Option<String>,
+    #[serde(rename = "avatarUrl")]
+    pub avatar_url: Option<String>,
     pub roles: Option<Vec<RoleDto>>,
     #[serde(rename = "createdAt")]
     pub created_at: String,
     #[serde(rename = "updatedAt")]
     pub updated_at: String,
-    pub enabled: bool,
+    pub status: UserStatus,
+    #[serde(rename = "activeFrom", skip_serializing_if = "Option::is_none")]
+    pub active_from: Option<String>,
+    #[serde(rename = "expiresAt", skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<UserSource>,
+    #[serde(rename = "mfaEnrolled", default)]
+    pub mfa_enrolled: bool,
+    #[serde(rename = "mustChangePassword", default)]
+    pub must_change_password: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
 }
 
 impl From<User> for UserDto {
@@ -53,12 +101,28 @@ impl From<User> for UserDto {
             id: value.id.to_hex(),
             username: value.username,
             email: value.email,
+            email_verified: value.email_verified,
+            secondary_emails: value
+                .secondary_emails
+                .into_iter()
+                .map(UserEmailDto::from)
+                .collect(),
             first_name: value.first_name,
             last_name: value.last_name,
+            phone_number: value.phone_number,
+            locale: value.locale,
+            timezone: value.timezone,
+            avatar_url: None,
             roles: None,
             created_at: value.created_at.to_rfc3339(),
             updated_at: value.updated_at.to_rfc3339(),
-            enabled: value.enabled,
+            status: value.status,
+            active_from: value.active_from.map(|d| d.to_rfc3339()),
+            expires_at: value.expires_at.map(|d| d.to_rfc3339()),
+            source: value.source,
+            mfa_enrolled: value.mfa_enrolled,
+            must_change_password: value.must_change_password,
+            warnings: vec![],
         }
     }
 }
@@ -96,12 +160,29 @@ impl From<&User> for UserDto {
             id: value.id.to_hex(),
             username: value.username.clone(),
             email: value.email.clone(),
+            email_verified: value.email_verified,
+            secondary_emails: value
+                .secondary_emails
+                .iter()
+                .cloned()
+                .map(UserEmailDto::from)
+                .collect(),
             first_name: value.first_name.clone(),
             last_name: value.last_name.clone(),
+            phone_number: value.phone_number.clone(),
+            locale: value.locale.clone(),
+            timezone: value.timezone.clone(),
+            avatar_url: None,
             roles: None,
             created_at: value.created_at.to_rfc3339(),
             updated_at: value.updated_at.to_rfc3339(),
-            enabled: value.enabled,
+            status: value.status,
+            active_from: value.active_from.map(|d| d.to_rfc3339()),
+            expires_at: value.expires_at.map(|d| d.to_rfc3339()),
+            source: value.source,
+            mfa_enrolled: value.mfa_enrolled,
+            must_change_password: value.must_change_password,
+            warnings: vec![],
         }
     }
 }
@@ -115,6 +196,12 @@ pub struct SimpleUserDto {
     pub first_name: Option<String>,
     #[serde(rename = "lastName")]
     pub last_name: Option<String>,
+    #[serde(rename = "phoneNumber")]
+    pub phone_number: Option<String>,
+    pub locale: Option<String>,
+    pub timezone: Option<String>,
+    #[serde(rename = "avatarUrl")]
+    pub avatar_url: Option<String>,
     pub roles: Option<Vec<SimpleRoleDto>>,
 }
 
@@ -153,6 +240,10 @@ impl From<User> for SimpleUserDto {
             email: value.email,
             first_name: value.first_name,
             last_name: value.last_name,
+            phone_number: value.phone_number,
+            locale: value.locale,
+            timezone: value.timezone,
+            avatar_url: None,
             roles: None,
         }
     }
@@ -193,6 +284,10 @@ impl From<&User> for SimpleUserDto {
             email: value.email.clone(),
             first_name: value.first_name.clone(),
             last_name: value.last_name.clone(),
+            phone_number: value.phone_number.clone(),
+            locale: value.locale.clone(),
+            timezone: value.timezone.clone(),
+            avatar_url: None,
             roles: None,
         }
     }
@@ -0,0 +1,84 @@
+use crate::repository::user::user_email::UserEmail;
+use crate::web::dto::sanitize::{sanitize_required, Sanitize, SanitizeError};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct UserEmailDto {
+    pub email: String,
+    pub verified: bool,
+}
+
+impl From<UserEmail> for UserEmailDto {
+    /// # Summary
+    ///
+    /// Convert a UserEmail into a UserEmailDto.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The UserEmail to be converted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_email = UserEmail::new(String::from("alias@opserva.io"));
+    /// let user_email_dto = UserEmailDto::from(user_email);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `UserEmailDto` - The new UserEmailDto.
+    fn from(value: UserEmail) -> Self {
+        UserEmailDto {
+            email: value.email,
+            verified: value.verified,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct AddUserEmail {
+    pub email: String,
+}
+
+impl Sanitize for AddUserEmail {
+    /// # Summary
+    ///
+    /// Sanitize the AddUserEmail.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SanitizeError>` - The result of the operation.
+    fn sanitize(&mut self) -> Result<(), SanitizeError> {
+        sanitize_required("email", &mut self.email)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct UpdateUserEmail {
+    pub email: String,
+    pub primary: Option<bool>,
+    pub verified: Option<bool>,
+}
+
+impl Sanitize for UpdateUserEmail {
+    /// # Summary
+    ///
+    /// Sanitize the UpdateUserEmail.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SanitizeError>` - The result of the operation.
+    fn sanitize(&mut self) -> Result<(), SanitizeError> {
+        sanitize_required("email", &mut self.email)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UserEmailQuery {
+    pub email: String,
+}
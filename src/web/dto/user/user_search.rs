@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct UserSearchRequest {
+    pub text: Option<String>,
+    pub limit: Option<i64>,
+    pub page: Option<i64>,
+    pub source: Option<String>,
+    #[serde(rename = "emailMissing")]
+    pub email_missing: Option<bool>,
+    #[serde(rename = "emailVerified")]
+    pub email_verified: Option<bool>,
+    #[serde(default)]
+    pub summary: bool,
+    #[serde(default)]
+    pub highlight: bool,
+}
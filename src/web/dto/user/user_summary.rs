@@ -0,0 +1,75 @@
+use crate::repository::user::user_status::UserStatus;
+use crate::repository::user_summary::user_summary_model::{UserSummary, UserSummaryRole};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct UserSummaryRoleDto {
+    pub id: String,
+    pub name: String,
+}
+
+impl From<UserSummaryRole> for UserSummaryRoleDto {
+    /// # Summary
+    ///
+    /// Convert a UserSummaryRole into a UserSummaryRoleDto.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The UserSummaryRole to be converted.
+    ///
+    /// # Returns
+    ///
+    /// * `UserSummaryRoleDto` - The new UserSummaryRoleDto.
+    fn from(value: UserSummaryRole) -> Self {
+        UserSummaryRoleDto {
+            id: value.id.to_hex(),
+            name: value.name,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct UserSummaryDto {
+    pub id: String,
+    pub username: String,
+    pub email: Option<String>,
+    pub status: UserStatus,
+    pub roles: Vec<UserSummaryRoleDto>,
+    #[serde(rename = "lastLoginAt")]
+    pub last_login_at: Option<String>,
+}
+
+impl From<UserSummary> for UserSummaryDto {
+    /// # Summary
+    ///
+    /// Convert a UserSummary into a UserSummaryDto.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The UserSummary to be converted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_summary_dto = UserSummaryDto::from(user_summary);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `UserSummaryDto` - The new UserSummaryDto.
+    fn from(value: UserSummary) -> Self {
+        UserSummaryDto {
+            id: value.id.to_hex(),
+            username: value.username,
+            email: value.email,
+            status: value.status,
+            roles: value
+                .roles
+                .into_iter()
+                .map(UserSummaryRoleDto::from)
+                .collect(),
+            last_login_at: value.last_login_at.map(|d| d.to_rfc3339()),
+        }
+    }
+}
@@ -1,2 +1,4 @@
 pub mod jwt_extractor;
+pub mod object_id_path;
+pub mod sanitized_json;
 pub mod user_id_extractor;
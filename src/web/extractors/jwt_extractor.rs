@@ -1,10 +1,139 @@
 use crate::configuration::config::Config;
+use crate::repository::api_key::api_key_repository::Error as ApiKeyError;
+use crate::repository::client::client_model::Client;
+use crate::repository::user::user_model::User;
+use crate::services::jwt::jwt_service::Claims;
 use actix_web::dev::ServiceRequest;
 use actix_web::error::ErrorInternalServerError;
-use actix_web::Error;
+use actix_web::http::header::HeaderMap;
+use actix_web::{Error, HttpMessage};
 use log::error;
+use mongodb::bson::oid::ObjectId;
 use std::collections::HashSet;
 
+/// The header that carries the key of an ApiKey.
+pub const API_KEY_HEADER: &str = "X-Api-Key";
+
+/// The authenticated caller of a request, such as `user:<id>` or `api-key:<id>`, which is stored in the extensions of
+/// the request once its token or API key has been authenticated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AuthenticatedSubject(pub String);
+
+/// The subject that a token authenticates.
+pub enum Principal {
+    User(User, Claims),
+    Client(Client, Claims),
+    Guest(Claims),
+}
+
+/// # Summary
+///
+/// Get the bearer token from the Authorization header.
+///
+/// # Arguments
+///
+/// * `headers` - The headers of the request.
+///
+/// # Example
+///
+/// ```
+/// let token = bearer_token(req.headers());
+/// ```
+///
+/// # Returns
+///
+/// * `Option<&str>` - The bearer token, or None if the request does not carry one.
+pub fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get("Authorization")?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+}
+
+/// # Summary
+///
+/// Authenticate a JWT token. The token must be valid and must not have been revoked by logging out, and the User or
+/// Client that it was issued to must still exist, be active and not have revoked its tokens since. Guest tokens are only
+/// accepted while guest access is enabled. Whether a User that must change its password may proceed is left to the
+/// caller.
+///
+/// # Arguments
+///
+/// * `token` - The JWT token.
+/// * `config` - The Config.
+///
+/// # Example
+///
+/// ```
+/// let principal = authenticate(token, &config).await;
+/// ```
+///
+/// # Returns
+///
+/// * `Option<Principal>` - The Principal that the token authenticates, or None if the token cannot be used.
+pub async fn authenticate(token: &str, config: &Config) -> Option<Principal> {
+    let claims = match config.services.jwt_service.decode_jwt_token(token) {
+        Ok(c) => c,
+        Err(e) => {
+            error!("Failed to verify JWT token: {}", e);
+            return None;
+        }
+    };
+
+    if is_revoked(token, config).await {
+        return None;
+    }
+
+    if claims.is_client() {
+        let client = match config
+            .services
+            .client_service
+            .find_by_id(claims.subject(), &config.database)
+            .await
+        {
+            Ok(c) => c?,
+            Err(e) => {
+                error!("Failed to find client by ID: {}", e);
+                return None;
+            }
+        };
+
+        if !client.enabled || client.is_token_revoked(claims.token_version()) {
+            return None;
+        }
+
+        return Some(Principal::Client(client, claims));
+    }
+
+    // Disabling guest tokens also invalidates the tokens that were already issued
+    if claims.is_guest() {
+        return config
+            .guest_config
+            .enabled
+            .then_some(Principal::Guest(claims));
+    }
+
+    let user = match config
+        .services
+        .user_service
+        .find_by_id(claims.subject(), &config.database)
+        .await
+    {
+        Ok(u) => u?,
+        Err(e) => {
+            error!("Failed to find user by ID: {}", e);
+            return None;
+        }
+    };
+
+    if !user.is_active() || user.is_token_revoked(claims.token_version()) {
+        return None;
+    }
+
+    Some(Principal::User(user, claims))
+}
+
 /// # Summary
 ///
 /// Extract the permissions from the request.
@@ -33,95 +162,417 @@ pub async fn extract(req: &ServiceRequest) -> Result<HashSet<String>, Error> {
         Some(e) => e,
     };
 
+    // A request that carries a bearer token is never authenticated by an API key
+    if !req.headers().contains_key("Authorization") {
+        if let Some(key) = req
+            .headers()
+            .get(API_KEY_HEADER)
+            .and_then(|h| h.to_str().ok())
+        {
+            return Ok(extract_api_key_permissions(req, res, key).await);
+        }
+    }
+
+    let token = match bearer_token(req.headers()) {
+        Some(t) => t,
+        None => return Ok(HashSet::<String>::new()),
+    };
+
+    let (user, claims) = match authenticate(token, res).await {
+        Some(Principal::User(u, c)) => {
+            set_subject(req, format!("user:{}", u.id));
+            (u, c)
+        }
+        Some(Principal::Client(c, claims)) => {
+            set_subject(req, format!("client:{}", c.id));
+            let permissions = extract_client_permissions(res, c).await;
+            return Ok(restrict_to_scope(permissions, &claims));
+        }
+        Some(Principal::Guest(claims)) => {
+            set_subject(req, format!("guest:{}", claims.subject()));
+            return Ok(res.guest_config.permissions.iter().cloned().collect());
+        }
+        None => return Ok(HashSet::<String>::new()),
+    };
+
+    // A User that must change its password can do nothing else until it has done so
+    if user.must_change_password {
+        let permissions = res
+            .permission_config
+            .required_permission("PUT", "/api/v1/users/{id}/self/password/")
+            .into_iter()
+            .collect();
+        return Ok(restrict_to_scope(permissions, &claims));
+    }
+
+    // Tenant tokens only carry the roles that the User holds within the Organization
+    let (user_roles, scope) = match claims.tenant() {
+        Some(t) => match extract_tenant_roles(res, t, &user.id).await {
+            Some(r) => (Some(r), ObjectId::parse_str(t).ok()),
+            None => return Ok(HashSet::<String>::new()),
+        },
+        None => (user.roles, None),
+    };
+
     let mut permission_list: HashSet<String> = HashSet::<String>::new();
-    if let Some(auth_header) = req.headers().get("Authorization") {
-        if let Ok(auth_str) = auth_header.to_str() {
-            if let Some(token) = auth_str.strip_prefix("Bearer ") {
-                match res.services.jwt_service.verify_jwt_token(token) {
-                    Ok(subject) => {
-                        let user = match res
-                            .services
-                            .user_service
-                            .find_by_id(&subject, &res.database)
-                            .await
-                        {
-                            Ok(e) => match e {
-                                Some(e) => e,
-                                None => {
-                                    return Ok(HashSet::<String>::new());
-                                }
-                            },
-                            Err(e) => {
-                                error!("Failed to find user by ID: {}", e);
-                                return Ok(HashSet::<String>::new());
-                            }
-                        };
-
-                        if !user.enabled {
-                            return Ok(HashSet::<String>::new());
-                        }
-
-                        if user.roles.is_some() {
-                            let mut role_vec: Vec<String> = vec![];
-                            for r in user.roles.unwrap() {
-                                role_vec.push(r.to_hex());
-                            }
-
-                            let roles = match res
-                                .services
-                                .role_service
-                                .find_by_id_vec(role_vec, &res.database)
-                                .await
-                            {
-                                Ok(e) => e,
-                                Err(e) => {
-                                    error!("Failed to find roles by id vec: {}", e);
-                                    return Ok(HashSet::<String>::new());
-                                }
-                            };
-
-                            if !roles.is_empty() {
-                                for r in roles {
-                                    if r.permissions.is_some() {
-                                        let mut oid_vec: Vec<String> = vec![];
-                                        for r in r.permissions.unwrap() {
-                                            oid_vec.push(r.to_hex());
-                                        }
-                                        let permissions = match res
-                                            .services
-                                            .permission_service
-                                            .find_by_id_vec(oid_vec, &res.database)
-                                            .await
-                                        {
-                                            Ok(d) => d,
-                                            Err(e) => {
-                                                error!(
-                                                    "Failed to find permissions by id vec: {}",
-                                                    e
-                                                );
-                                                continue;
-                                            }
-                                        };
-
-                                        if !permissions.is_empty() {
-                                            for p in permissions {
-                                                if !permission_list.contains(&p.name) {
-                                                    permission_list.insert(p.name);
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
+    if let Some(user_roles) = user_roles {
+        let mut role_vec: Vec<String> = vec![];
+        for r in user_roles {
+            role_vec.push(r.to_hex());
+        }
+
+        let roles = match res
+            .services
+            .role_service
+            .find_in_scope(role_vec, scope, &res.database)
+            .await
+        {
+            Ok(e) => e,
+            Err(e) => {
+                error!("Failed to find roles by id vec: {}", e);
+                return Ok(HashSet::<String>::new());
+            }
+        };
+
+        for r in roles {
+            if let Some(oid_vec) = r.active_permissions() {
+                let permissions = match res
+                    .services
+                    .permission_service
+                    .find_by_id_vec(oid_vec, &res.database)
+                    .await
+                {
+                    Ok(d) => d,
                     Err(e) => {
-                        error!("Failed to verify JWT token: {}", e);
+                        error!("Failed to find permissions by id vec: {}", e);
+                        continue;
                     }
+                };
+
+                for p in permissions {
+                    permission_list.insert(p.name);
                 }
             }
         }
     }
 
-    Ok(permission_list)
+    Ok(restrict_to_scope(permission_list, &claims))
+}
+
+/// # Summary
+///
+/// Store the authenticated caller of a request in the extensions of the request, so that the middleware that runs after
+/// authentication, such as the QuotaGuard, can tell callers apart without authenticating them again.
+///
+/// # Arguments
+///
+/// * `req` - The request.
+/// * `subject` - The authenticated caller.
+fn set_subject(req: &ServiceRequest, subject: String) {
+    req.extensions_mut().insert(AuthenticatedSubject(subject));
+}
+
+/// # Summary
+///
+/// Restrict permissions to the scopes that a token was issued for. A scope grants the Permission with the same name, so
+/// that scopes which do not name a Permission, such as `openid` or `profile`, do not grant access to any route that
+/// requires a Permission. Tokens without a `scope` claim keep all permissions.
+///
+/// # Arguments
+///
+/// * `permissions` - The permissions of the User or Client that the token was issued to.
+/// * `claims` - The Claims of the token.
+///
+/// # Returns
+///
+/// * `HashSet<String>` - The permissions that the token grants.
+fn restrict_to_scope(permissions: HashSet<String>, claims: &Claims) -> HashSet<String> {
+    let scopes: HashSet<&str> = match claims.scope() {
+        Some(s) => s.split_whitespace().collect(),
+        None => return permissions,
+    };
+
+    permissions
+        .into_iter()
+        .filter(|p| scopes.contains(p.as_str()))
+        .collect()
+}
+
+/// # Summary
+///
+/// Check whether a JWT token was revoked by logging out. Tokens are treated as revoked if the denylist cannot be read.
+///
+/// # Arguments
+///
+/// * `token` - The JWT token.
+/// * `config` - The Config.
+///
+/// # Example
+///
+/// ```
+/// if is_revoked(token, &config).await {
+///     return HttpResponse::Forbidden().finish();
+/// }
+/// ```
+///
+/// # Returns
+///
+/// * `bool` - True if the JWT token was revoked or its status is unknown, false otherwise.
+pub async fn is_revoked(token: &str, config: &Config) -> bool {
+    match config
+        .services
+        .revoked_token_service
+        .is_revoked(token, &config.database)
+        .await
+    {
+        Ok(r) => r,
+        Err(e) => {
+            error!("Failed to check whether the JWT token was revoked: {}", e);
+            true
+        }
+    }
+}
+
+/// # Summary
+///
+/// Extract the roles that a User holds within the Organization that a tenant token was issued for.
+///
+/// # Arguments
+///
+/// * `config` - The Config.
+/// * `tenant` - The ID of the Organization.
+/// * `user_id` - The ID of the User.
+///
+/// # Returns
+///
+/// * `Option<Vec<ObjectId>>` - The IDs of the roles, or None if the Organization is unknown or the User is no longer a member.
+async fn extract_tenant_roles(
+    config: &Config,
+    tenant: &str,
+    user_id: &ObjectId,
+) -> Option<Vec<ObjectId>> {
+    let organization = match config
+        .services
+        .organization_service
+        .find_by_id(tenant, &config.database)
+        .await
+    {
+        Ok(o) => o?,
+        Err(e) => {
+            error!("Failed to find organization by ID: {}", e);
+            return None;
+        }
+    };
+
+    organization.find_member(user_id).map(|m| m.roles.clone())
+}
+
+/// # Summary
+///
+/// Extract the permissions of the OAuth client that a token was issued to.
+///
+/// # Arguments
+///
+/// * `config` - The Config.
+/// * `client` - The Client that the token authenticates.
+///
+/// # Returns
+///
+/// * `HashSet<String>` - The permissions of the Client.
+async fn extract_client_permissions(config: &Config, client: Client) -> HashSet<String> {
+    let oid_vec: Vec<ObjectId> = match client.permissions {
+        Some(p) => p,
+        None => return HashSet::new(),
+    };
+
+    match config
+        .services
+        .permission_service
+        .find_by_id_vec(oid_vec, &config.database)
+        .await
+    {
+        Ok(d) => d.into_iter().map(|p| p.name).collect(),
+        Err(e) => {
+            error!("Failed to find permissions by id vec: {}", e);
+            HashSet::new()
+        }
+    }
+}
+
+/// # Summary
+///
+/// Extract the permissions of the ApiKey that a request carries.
+///
+/// # Arguments
+///
+/// * `req` - The request.
+/// * `config` - The Config.
+/// * `key` - The key from the `X-Api-Key` header.
+///
+/// # Returns
+///
+/// * `HashSet<String>` - The permissions of the ApiKey, or an empty set if the key is unknown, disabled or expired.
+async fn extract_api_key_permissions(
+    req: &ServiceRequest,
+    config: &Config,
+    key: &str,
+) -> HashSet<String> {
+    let api_key = match config
+        .services
+        .api_key_service
+        .authenticate(key, &config.database)
+        .await
+    {
+        Ok(a) => a,
+        Err(ApiKeyError::InvalidKey) => return HashSet::new(),
+        Err(e) => {
+            error!("Failed to authenticate API key: {}", e);
+            return HashSet::new();
+        }
+    };
+
+    set_subject(req, format!("api-key:{}", api_key.id));
+
+    let oid_vec: Vec<ObjectId> = match api_key.permissions {
+        Some(p) => p,
+        None => return HashSet::new(),
+    };
+
+    match config
+        .services
+        .permission_service
+        .find_by_id_vec(oid_vec, &config.database)
+        .await
+    {
+        Ok(d) => d.into_iter().map(|p| p.name).collect(),
+        Err(e) => {
+            error!("Failed to find permissions by id vec: {}", e);
+            HashSet::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::permission::permission_model::Permission;
+    use crate::repository::revoked_token::revoked_token_repository::Error as RevokedTokenError;
+    use crate::repository::role::role_model::Role;
+    use crate::test_support::{self, MockServices};
+    use crate::web::controller::role::role_controller;
+    use crate::web::guards::permission_guard::PermissionGuard;
+    use actix_web::http::{header, StatusCode};
+    use actix_web::{test, web, App};
+    use actix_web_grants::GrantsMiddleware;
+
+    async fn read_role(scope: Option<&str>) -> StatusCode {
+        let permission = Permission::new(String::from("CAN_READ_ROLE"), None);
+        let role = Role::new(String::from("reader"), None, Some(vec![permission.id]));
+        let mut user = test_support::user();
+        user.roles = Some(vec![role.id]);
+        let user_id = user.id;
+
+        let mut mocks = MockServices::new();
+        mocks.with_user(user);
+        mocks
+            .role_service
+            .expect_find_in_scope()
+            .returning(move |_, _, _| Ok(vec![role.clone()]));
+        mocks
+            .permission_service
+            .expect_find_by_id_vec()
+            .returning(move |_, _| Ok(vec![permission.clone()]));
+        mocks
+            .role_service
+            .expect_find_by_id()
+            .returning(|_, _| Ok(None));
+        mocks.audit_service.expect_create().returning(|_, _| Ok(()));
+
+        let config = test_support::config(mocks).await;
+        let token = config
+            .services
+            .jwt_service
+            .generate_scoped_token(&user_id.to_hex(), 0, scope)
+            .unwrap();
+        let app = test::init_service(
+            App::new().app_data(web::Data::new(config)).service(
+                web::scope("/api/v1")
+                    .wrap(PermissionGuard)
+                    .wrap(GrantsMiddleware::with_extractor(extract))
+                    .service(web::scope("/roles").service(role_controller::find_by_id)),
+            ),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/v1/roles/{}", ObjectId::new().to_hex()))
+            .insert_header((header::AUTHORIZATION, format!("Bearer {}", token)))
+            .to_request();
+
+        test::call_service(&app, req).await.status()
+    }
+
+    #[actix_web::test]
+    async fn unscoped_token_carries_permissions_of_user() {
+        assert_eq!(read_role(None).await, StatusCode::NOT_FOUND);
+    }
+
+    #[actix_web::test]
+    async fn scoped_token_carries_permissions_within_scope() {
+        assert_eq!(
+            read_role(Some("openid CAN_READ_ROLE")).await,
+            StatusCode::NOT_FOUND
+        );
+    }
+
+    #[actix_web::test]
+    async fn scoped_token_is_denied_outside_scope() {
+        assert_eq!(
+            read_role(Some("openid profile")).await,
+            StatusCode::FORBIDDEN
+        );
+    }
+
+    async fn authenticate_with_denylist(
+        is_revoked: Result<bool, RevokedTokenError>,
+    ) -> Option<Principal> {
+        let user = test_support::user();
+        let user_id = user.id;
+
+        let mut mocks = MockServices::default();
+        mocks.with_user(user);
+        mocks
+            .revoked_token_service
+            .expect_is_revoked()
+            .times(1)
+            .return_once(move |_, _| is_revoked);
+
+        let config = test_support::config(mocks).await;
+        let token = config
+            .services
+            .jwt_service
+            .generate_jwt_token(&user_id.to_hex(), 0)
+            .unwrap();
+
+        authenticate(&token, &config).await
+    }
+
+    #[actix_web::test]
+    async fn token_that_was_not_revoked_is_authenticated() {
+        assert!(authenticate_with_denylist(Ok(false)).await.is_some());
+    }
+
+    #[actix_web::test]
+    async fn revoked_token_is_not_authenticated() {
+        assert!(authenticate_with_denylist(Ok(true)).await.is_none());
+    }
+
+    #[actix_web::test]
+    async fn token_is_not_authenticated_if_denylist_cannot_be_read() {
+        assert!(
+            authenticate_with_denylist(Err(RevokedTokenError::EmptyCollection))
+                .await
+                .is_none()
+        );
+    }
 }
@@ -0,0 +1,99 @@
+use crate::errors::into_http_response::IntoHttpResponse;
+use crate::errors::invalid_id::{parse_object_id, InvalidId};
+use actix_web::dev::Payload;
+use actix_web::error::InternalError;
+use actix_web::{FromRequest, HttpRequest};
+use futures::future::{ready, Ready};
+use mongodb::bson::oid::ObjectId;
+use std::fmt::{Display, Formatter};
+use std::ops::Deref;
+
+/// # Summary
+///
+/// A path extractor for the `{id}` segment of a route, which rejects malformed IDs with a `400 Bad Request` response
+/// before the request reaches the controller.
+///
+/// # Example
+///
+/// ```
+/// #[get("/{id}")]
+/// pub async fn find_by_id(path: ObjectIdPath, pool: web::Data<Config>) -> HttpResponse {
+///     let role = pool.services.role_service.find_by_id(&path, &pool.database).await;
+/// }
+/// ```
+pub struct ObjectIdPath {
+    id: ObjectId,
+    value: String,
+}
+
+impl ObjectIdPath {
+    /// # Summary
+    ///
+    /// Get the parsed ID.
+    ///
+    /// # Returns
+    ///
+    /// * `ObjectId` - The ID of the path.
+    pub fn object_id(&self) -> ObjectId {
+        self.id
+    }
+}
+
+impl Deref for ObjectIdPath {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.value
+    }
+}
+
+impl Display for ObjectIdPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+impl FromRequest for ObjectIdPath {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    /// # Summary
+    ///
+    /// Parse the `{id}` segment of the path of the request.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - The HttpRequest.
+    /// * `_payload` - The Payload of the request.
+    ///
+    /// # Returns
+    ///
+    /// * `Self::Future` - The ObjectIdPath, or a BadRequest if the segment is missing or not a valid ObjectId.
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let value = req.match_info().get("id").unwrap_or_default();
+
+        ready(match parse_object_id("ID", value) {
+            Ok(id) => Ok(ObjectIdPath {
+                id,
+                value: id.to_hex(),
+            }),
+            Err(e) => Err(invalid_id_error(e)),
+        })
+    }
+}
+
+/// # Summary
+///
+/// Convert an InvalidId into an actix-web Error that responds with a BadRequest body.
+///
+/// # Arguments
+///
+/// * `e` - The InvalidId.
+///
+/// # Returns
+///
+/// * `actix_web::Error` - The Error.
+fn invalid_id_error(e: InvalidId) -> actix_web::Error {
+    let response = e.clone().into_http_response();
+    InternalError::from_response(e, response).into()
+}
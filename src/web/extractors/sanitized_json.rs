@@ -0,0 +1,84 @@
+use crate::errors::into_http_response::IntoHttpResponse;
+use crate::web::dto::sanitize::Sanitize;
+use actix_web::dev::Payload;
+use actix_web::error::InternalError;
+use actix_web::{web, FromRequest, HttpRequest};
+use futures::future::LocalBoxFuture;
+use serde::de::DeserializeOwned;
+use std::ops::{Deref, DerefMut};
+
+/// # Summary
+///
+/// A JSON extractor that sanitizes the deserialized DTO before it reaches the controller.
+///
+/// # Example
+///
+/// ```
+/// pub async fn create_permission(info: SanitizedJson<CreatePermission>) -> HttpResponse {
+///     let new_permission = Permission::from(info.into_inner());
+/// }
+/// ```
+pub struct SanitizedJson<T>(pub T);
+
+impl<T> SanitizedJson<T> {
+    /// # Summary
+    ///
+    /// Unwrap the sanitized DTO.
+    ///
+    /// # Returns
+    ///
+    /// * `T` - The sanitized DTO.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for SanitizedJson<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for SanitizedJson<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> FromRequest for SanitizedJson<T>
+where
+    T: DeserializeOwned + Sanitize + 'static,
+{
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    /// # Summary
+    ///
+    /// Deserialize the JSON body of the request and sanitize it.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - The HttpRequest.
+    /// * `payload` - The Payload of the request.
+    ///
+    /// # Returns
+    ///
+    /// * `Self::Future` - The sanitized DTO, or a BadRequest if the body could not be deserialized or sanitized.
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let json = web::Json::<T>::from_request(req, payload);
+
+        Box::pin(async move {
+            let mut dto = json.await?.into_inner();
+
+            match dto.sanitize() {
+                Ok(_) => Ok(SanitizedJson(dto)),
+                Err(e) => {
+                    let response = e.clone().into_http_response();
+                    Err(InternalError::from_response(e, response).into())
+                }
+            }
+        })
+    }
+}
@@ -1,11 +1,15 @@
 use crate::configuration::config::Config;
+use crate::web::extractors::jwt_extractor;
+use crate::web::extractors::jwt_extractor::Principal;
 use actix_web::HttpRequest;
 use log::error;
 use mongodb::bson::oid::ObjectId;
 
 /// # Summary
 ///
-/// Get the User ID from the Authorization header.
+/// Get the User ID from the Authorization header. Requests that are authenticated by an API key are attributed to the
+/// ID of the ApiKey instead. The token is authenticated the same way as by the JWT extractor, and a User that must
+/// change its password is rejected until it has done so.
 ///
 /// # Arguments
 ///
@@ -22,27 +26,183 @@ use mongodb::bson::oid::ObjectId;
 ///
 /// * `Option<String>` - The User ID.
 pub async fn get_user_id_from_token(req: &HttpRequest, config: &Config) -> Option<ObjectId> {
-    if let Some(auth_header) = req.headers().get("Authorization") {
-        if let Ok(auth_str) = auth_header.to_str() {
-            if let Some(token) = auth_str.strip_prefix("Bearer ") {
-                return match config.services.jwt_service.verify_jwt_token(token) {
-                    Ok(subject) => {
-                        return match ObjectId::parse_str(subject) {
-                            Ok(e) => Some(e),
-                            Err(e) => {
-                                error!("Failed to parse Object ID: {}", e);
-                                None
-                            }
-                        };
-                    }
-                    Err(e) => {
-                        error!("Failed to verify JWT token: {}", e);
-                        None
-                    }
-                };
+    get_user_id(req, config, false).await
+}
+
+/// # Summary
+///
+/// Get the User ID from the Authorization header, also accepting a User that must change its password. This is only
+/// meant for the endpoint that changes the password of the User.
+///
+/// # Arguments
+///
+/// * `req` - The HttpRequest.
+/// * `config` - The Config.
+///
+/// # Example
+///
+/// ```
+/// let user_id = get_user_id_for_password_change(&req, &config).await;
+/// ```
+///
+/// # Returns
+///
+/// * `Option<String>` - The User ID.
+pub async fn get_user_id_for_password_change(
+    req: &HttpRequest,
+    config: &Config,
+) -> Option<ObjectId> {
+    get_user_id(req, config, true).await
+}
+
+/// # Summary
+///
+/// Get the User ID from the Authorization header or the API key of the request.
+///
+/// # Arguments
+///
+/// * `req` - The HttpRequest.
+/// * `config` - The Config.
+/// * `password_change` - Whether a User that must change its password is accepted.
+///
+/// # Returns
+///
+/// * `Option<String>` - The User ID.
+async fn get_user_id(
+    req: &HttpRequest,
+    config: &Config,
+    password_change: bool,
+) -> Option<ObjectId> {
+    if req.headers().contains_key("Authorization") {
+        let token = jwt_extractor::bearer_token(req.headers())?;
+
+        return match jwt_extractor::authenticate(token, config).await? {
+            Principal::User(user, _) => {
+                if user.must_change_password && !password_change {
+                    return None;
+                }
+
+                Some(user.id)
             }
+            Principal::Client(client, _) => Some(client.id),
+            Principal::Guest(claims) => match ObjectId::parse_str(claims.subject()) {
+                Ok(e) => Some(e),
+                Err(e) => {
+                    error!("Failed to parse Object ID: {}", e);
+                    None
+                }
+            },
+        };
+    }
+
+    let key = req
+        .headers()
+        .get(jwt_extractor::API_KEY_HEADER)?
+        .to_str()
+        .ok()?;
+    match config
+        .services
+        .api_key_service
+        .authenticate(key, &config.database)
+        .await
+    {
+        Ok(a) => Some(a.id),
+        Err(e) => {
+            error!("Failed to authenticate API key: {}", e);
+            None
+        }
+    }
+}
+
+/// # Summary
+///
+/// Get the ID of the active Organization from the Authorization header.
+///
+/// # Arguments
+///
+/// * `req` - The HttpRequest.
+/// * `config` - The Config.
+///
+/// # Example
+///
+/// ```
+/// let tenant = get_tenant_from_token(&req, &config);
+/// ```
+///
+/// # Returns
+///
+/// * `Option<String>` - The ID of the active Organization, or None if the token is not scoped to a tenant.
+pub fn get_tenant_from_token(req: &HttpRequest, config: &Config) -> Option<String> {
+    let auth_str = req.headers().get("Authorization")?.to_str().ok()?;
+    let token = auth_str.strip_prefix("Bearer ")?;
+
+    match config.services.jwt_service.decode_jwt_token(token) {
+        Ok(claims) => claims.tenant().map(String::from),
+        Err(e) => {
+            error!("Failed to decode JWT token: {}", e);
+            None
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::user::user_model::User;
+    use crate::repository::user::user_status::UserStatus;
+    use crate::test_support::{self, MockServices};
+    use actix_web::http::header;
+    use actix_web::test::TestRequest;
+
+    async fn user_id_of(user: User, version: i64) -> (Option<ObjectId>, Option<ObjectId>) {
+        let user_id = user.id;
+        let mut mocks = MockServices::new();
+        mocks.with_user(user);
+
+        let config = test_support::config(mocks).await;
+        let req = TestRequest::default()
+            .insert_header((
+                header::AUTHORIZATION,
+                test_support::bearer(&config, &user_id, version),
+            ))
+            .to_http_request();
+
+        (
+            get_user_id_from_token(&req, &config).await,
+            get_user_id_for_password_change(&req, &config).await,
+        )
+    }
+
+    #[actix_web::test]
+    async fn accepts_token_of_active_user() {
+        let user = test_support::user();
+        let user_id = user.id;
+
+        assert_eq!(user_id_of(user, 0).await, (Some(user_id), Some(user_id)));
+    }
+
+    #[actix_web::test]
+    async fn rejects_revoked_token() {
+        let mut user = test_support::user();
+        user.token_version = 1;
+
+        assert_eq!(user_id_of(user, 0).await, (None, None));
+    }
+
+    #[actix_web::test]
+    async fn rejects_token_of_inactive_user() {
+        let mut user = test_support::user();
+        user.status = UserStatus::Suspended;
 
-    None
+        assert_eq!(user_id_of(user, 0).await, (None, None));
+    }
+
+    #[actix_web::test]
+    async fn only_accepts_user_that_must_change_password_for_password_change() {
+        let mut user = test_support::user();
+        user.must_change_password = true;
+        let user_id = user.id;
+
+        assert_eq!(user_id_of(user, 0).await, (None, Some(user_id)));
+    }
 }
@@ -0,0 +1,4 @@
+pub mod admin_guard;
+pub mod maintenance_guard;
+pub mod permission_guard;
+pub mod quota_guard;
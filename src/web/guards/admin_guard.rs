@@ -0,0 +1,90 @@
+use crate::configuration::config::Config;
+use actix_web::body::EitherBody;
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::{Error, HttpResponse};
+use futures::future::{ready, LocalBoxFuture, Ready};
+use log::{error, warn};
+
+pub struct AdminGuard;
+
+impl<S, B> Transform<S, ServiceRequest> for AdminGuard
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = AdminGuardMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    /// # Summary
+    ///
+    /// Create the AdminGuardMiddleware that wraps the given service.
+    ///
+    /// # Arguments
+    ///
+    /// * `service` - The service to wrap.
+    ///
+    /// # Returns
+    ///
+    /// * `Ready<Result<AdminGuardMiddleware<S>, ()>>` - The AdminGuardMiddleware.
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(AdminGuardMiddleware { service }))
+    }
+}
+
+pub struct AdminGuardMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for AdminGuardMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    /// # Summary
+    ///
+    /// Only call the wrapped service if the caller is allowed to reach the admin endpoints.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - The ServiceRequest.
+    ///
+    /// # Returns
+    ///
+    /// * `LocalBoxFuture<'static, Result<ServiceResponse<EitherBody<B>>, Error>>` - The response of the wrapped service or a 404 Not Found response.
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let allowed = match req.app_data::<actix_web::web::Data<Config>>() {
+            Some(c) => c.admin_config.is_allowed(req.peer_addr().map(|a| a.ip())),
+            None => {
+                error!("Failed to get Config from request");
+                false
+            }
+        };
+
+        // Callers that are not allowed should not learn that the admin endpoints exist
+        if !allowed {
+            warn!(
+                "Rejected admin request to {} from {}",
+                req.path(),
+                req.peer_addr()
+                    .map_or_else(|| String::from("unknown address"), |a| a.ip().to_string())
+            );
+
+            let res = req
+                .into_response(HttpResponse::NotFound().finish())
+                .map_into_right_body();
+            return Box::pin(async { Ok(res) });
+        }
+
+        let fut = self.service.call(req);
+        Box::pin(async move { Ok(fut.await?.map_into_left_body()) })
+    }
+}
@@ -0,0 +1,169 @@
+use crate::configuration::config::Config;
+use actix_web::body::EitherBody;
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::{HeaderValue, RETRY_AFTER};
+use actix_web::{Error, HttpResponse};
+use futures::future::{ready, LocalBoxFuture, Ready};
+use log::error;
+
+/// The scope of the API, which precedes the paths of the routes that stay available during maintenance.
+const API_SCOPE: &str = "/api/v1/";
+
+/// The routes that stay available during maintenance, so that administrators can still log in and leave it again.
+const AVAILABLE_ROUTES: &[&str] = &["/api/v1/admin/", "/api/v1/authentication/"];
+
+/// The number of seconds after which clients are asked to retry their request during maintenance.
+const RETRY_AFTER_SECONDS: u32 = 60;
+
+pub struct MaintenanceGuard;
+
+impl<S, B> Transform<S, ServiceRequest> for MaintenanceGuard
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = MaintenanceGuardMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    /// # Summary
+    ///
+    /// Create the MaintenanceGuardMiddleware that wraps the given service.
+    ///
+    /// # Arguments
+    ///
+    /// * `service` - The service to wrap.
+    ///
+    /// # Returns
+    ///
+    /// * `Ready<Result<MaintenanceGuardMiddleware<S>, ()>>` - The MaintenanceGuardMiddleware.
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(MaintenanceGuardMiddleware { service }))
+    }
+}
+
+pub struct MaintenanceGuardMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for MaintenanceGuardMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    /// # Summary
+    ///
+    /// Only call the wrapped service if the instance is not in maintenance mode or the route stays available during
+    /// maintenance.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - The ServiceRequest.
+    ///
+    /// # Returns
+    ///
+    /// * `LocalBoxFuture<'static, Result<ServiceResponse<EitherBody<B>>, Error>>` - The response of the wrapped service or a 503 Service Unavailable response.
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let maintenance = match req.app_data::<actix_web::web::Data<Config>>() {
+            Some(c) => c.maintenance_mode.is_enabled(),
+            None => {
+                error!("Failed to get Config from request");
+                false
+            }
+        };
+
+        if maintenance && !is_available(req.path()) {
+            let mut res = HttpResponse::ServiceUnavailable().finish();
+            res.headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from(RETRY_AFTER_SECONDS));
+
+            let res = req.into_response(res).map_into_right_body();
+            return Box::pin(async { Ok(res) });
+        }
+
+        let fut = self.service.call(req);
+        Box::pin(async move { Ok(fut.await?.map_into_left_body()) })
+    }
+}
+
+/// # Summary
+///
+/// Check whether a route stays available during maintenance. The API may be mounted within another scope, so the path
+/// is compared from the API scope onwards.
+///
+/// # Arguments
+///
+/// * `path` - The path of the request.
+///
+/// # Returns
+///
+/// * `bool` - True if the route stays available during maintenance, false otherwise.
+fn is_available(path: &str) -> bool {
+    let path = path.rfind(API_SCOPE).map_or(path, |i| &path[i..]);
+
+    AVAILABLE_ROUTES.iter().any(|r| path.starts_with(r))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, MockServices};
+    use actix_web::http::StatusCode;
+    use actix_web::{test, web, App};
+
+    async fn ok() -> HttpResponse {
+        HttpResponse::Ok().finish()
+    }
+
+    async fn call(maintenance: bool, path: &str) -> StatusCode {
+        let config = test_support::config(MockServices::new()).await;
+        config.maintenance_mode.set_enabled(maintenance);
+
+        let app = test::init_service(
+            App::new().app_data(web::Data::new(config)).service(
+                web::scope("/api/v1")
+                    .wrap(MaintenanceGuard)
+                    .route("/users/", web::get().to(ok))
+                    .route("/admin/maintenance/", web::get().to(ok))
+                    .route("/authentication/login/", web::get().to(ok)),
+            ),
+        )
+        .await;
+
+        let req = test::TestRequest::get().uri(path).to_request();
+        test::call_service(&app, req).await.status()
+    }
+
+    #[actix_web::test]
+    async fn routes_are_available_outside_maintenance() {
+        assert_eq!(call(false, "/api/v1/users/").await, StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn routes_are_unavailable_during_maintenance() {
+        assert_eq!(
+            call(true, "/api/v1/users/").await,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+    }
+
+    #[actix_web::test]
+    async fn admin_and_authentication_routes_stay_available_during_maintenance() {
+        assert_eq!(
+            call(true, "/api/v1/admin/maintenance/").await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            call(true, "/api/v1/authentication/login/").await,
+            StatusCode::OK
+        );
+    }
+}
@@ -0,0 +1,331 @@
+use crate::configuration::config::Config;
+use crate::errors::forbidden::Forbidden;
+use crate::repository::audit::audit_model::Audit;
+use crate::services::event::security_event::SecurityEvent;
+use crate::web::controller::{PUBLIC_ROUTES, ROUTE_PERMISSIONS};
+use actix_web::body::EitherBody;
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header;
+use actix_web::{web, Error, HttpMessage, HttpResponse};
+use actix_web_grants::authorities::{AuthDetails, AuthoritiesCheck};
+use futures::future::{ready, LocalBoxFuture, Ready};
+use log::{error, warn};
+use mongodb::bson::oid::ObjectId;
+
+#[derive(Clone, Copy)]
+pub struct RoutePermission {
+    pub method: &'static str,
+    pub path: &'static str,
+    pub permission: &'static str,
+}
+
+/// A route that does not require a permission, because it is used to authenticate or only acts on the caller itself.
+#[derive(Clone, Copy)]
+pub struct PublicRoute {
+    pub method: &'static str,
+    pub path: &'static str,
+}
+
+/// The scope that the routes of the API are mounted under.
+const API_SCOPE: &str = "/api/v1/";
+
+pub struct PermissionGuard;
+
+/// A request that was rejected because the caller does not hold the permission that is required by the route.
+struct Denial {
+    method: String,
+    route: String,
+    permission: Option<String>,
+}
+
+impl<S, B> Transform<S, ServiceRequest> for PermissionGuard
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = PermissionGuardMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    /// # Summary
+    ///
+    /// Create the PermissionGuardMiddleware that wraps the given service.
+    ///
+    /// # Arguments
+    ///
+    /// * `service` - The service to wrap.
+    ///
+    /// # Returns
+    ///
+    /// * `Ready<Result<PermissionGuardMiddleware<S>, ()>>` - The PermissionGuardMiddleware.
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(PermissionGuardMiddleware { service }))
+    }
+}
+
+pub struct PermissionGuardMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for PermissionGuardMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    /// # Summary
+    ///
+    /// Verify that the caller holds the permission that is required by the matched route before calling the wrapped service.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - The ServiceRequest.
+    ///
+    /// # Returns
+    ///
+    /// * `LocalBoxFuture<'static, Result<ServiceResponse<EitherBody<B>>, Error>>` - The response of the wrapped service or a 403 Forbidden response.
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        if let Err(denial) = authorize(&req) {
+            let config = req.app_data::<web::Data<Config>>().cloned();
+            let subject = config.as_ref().and_then(|c| get_subject(&req, c));
+            let source_ip = req.connection_info().realip_remote_addr().map(String::from);
+
+            let verbose = config
+                .as_ref()
+                .is_some_and(|c| c.permission_config.verbose_errors);
+            let response = if verbose {
+                HttpResponse::Forbidden().json(Forbidden::new(
+                    &format!("Permission denied for {} {}", denial.method, denial.route),
+                    denial.permission.clone(),
+                ))
+            } else {
+                HttpResponse::Forbidden().finish()
+            };
+
+            let res = req.into_response(response).map_into_right_body();
+            return Box::pin(async move {
+                audit_denial(config, subject, source_ip, denial).await;
+                Ok(res)
+            });
+        }
+
+        let fut = self.service.call(req);
+        Box::pin(async move { fut.await.map(ServiceResponse::map_into_left_body) })
+    }
+}
+
+/// # Summary
+///
+/// Check whether the request is allowed to reach the matched route.
+///
+/// # Arguments
+///
+/// * `req` - The ServiceRequest.
+///
+/// # Returns
+///
+/// * `Result<(), Denial>` - Ok if the route is public or the request holds the permission that is required by the route. Routes without an entry are denied.
+fn authorize(req: &ServiceRequest) -> Result<(), Denial> {
+    let config = match req.app_data::<web::Data<Config>>() {
+        Some(c) => c,
+        None => {
+            error!("Failed to get Config from request");
+            return Err(Denial {
+                method: req.method().to_string(),
+                route: req.path().to_string(),
+                permission: None,
+            });
+        }
+    };
+
+    // No route matched, so the request is answered with a 404 response without reaching a handler
+    let pattern = match req.match_pattern() {
+        Some(p) => p,
+        None => return Ok(()),
+    };
+
+    // The routes can be mounted under a prefix by the host application, which is stripped so that the
+    // full pattern of the route, starting at the API scope, is compared with the known routes.
+    let path = match pattern.rfind(API_SCOPE) {
+        Some(i) => &pattern[i..],
+        None => pattern.as_str(),
+    };
+    let method = req.method().as_str();
+
+    if PUBLIC_ROUTES
+        .iter()
+        .any(|r| r.method == method && r.path == path)
+    {
+        return Ok(());
+    }
+
+    if !ROUTE_PERMISSIONS
+        .iter()
+        .any(|r| r.method == method && r.path == path)
+    {
+        warn!("Route {} {} has no permission entry", method, path);
+        return Err(Denial {
+            method: method.to_string(),
+            route: path.to_string(),
+            permission: None,
+        });
+    }
+
+    // The permission can be removed from the route using the PermissionConfig
+    let permission = match config.permission_config.required_permission(method, path) {
+        Some(p) => p,
+        None => return Ok(()),
+    };
+
+    let extensions = req.extensions();
+    match extensions.get::<AuthDetails>() {
+        Some(details) if details.has_authority(permission.as_str()) => Ok(()),
+        _ => Err(Denial {
+            method: req.method().to_string(),
+            route: path.to_string(),
+            permission: Some(permission),
+        }),
+    }
+}
+
+/// # Summary
+///
+/// Get the subject of the bearer token of the request.
+///
+/// # Arguments
+///
+/// * `req` - The ServiceRequest.
+/// * `config` - The Config.
+///
+/// # Returns
+///
+/// * `Option<ObjectId>` - The ID of the User that made the request, or None if the request does not hold a valid token.
+fn get_subject(req: &ServiceRequest, config: &Config) -> Option<ObjectId> {
+    let token = req
+        .headers()
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")?;
+
+    let claims = config.services.jwt_service.decode_jwt_token(token).ok()?;
+    ObjectId::parse_str(claims.subject()).ok()
+}
+
+/// # Summary
+///
+/// Log a denied request as a security event, publish it to the SecurityEventSinks and record it in the audit trail if
+/// the subject is known.
+///
+/// # Arguments
+///
+/// * `config` - The Config, if available.
+/// * `subject` - The ID of the User that made the request, if known.
+/// * `source_ip` - The IP address of the caller, if known.
+/// * `denial` - The Denial.
+async fn audit_denial(
+    config: Option<web::Data<Config>>,
+    subject: Option<ObjectId>,
+    source_ip: Option<String>,
+    denial: Denial,
+) {
+    warn!(
+        "Permission denied: {} requested {} {} without {}",
+        subject.map_or_else(|| String::from("anonymous"), |s| s.to_hex()),
+        denial.method,
+        denial.route,
+        denial.permission.as_deref().unwrap_or("-")
+    );
+
+    if let Some(c) = &config {
+        c.services
+            .publish_security_event(SecurityEvent::PermissionDenied {
+                subject: subject.map(|s| s.to_hex()),
+                method: denial.method.clone(),
+                route: denial.route.clone(),
+                permission: denial.permission.clone(),
+                source_ip,
+            })
+            .await;
+    }
+
+    let (config, subject) = match (config, subject) {
+        (Some(c), Some(s)) => (c, s),
+        _ => return,
+    };
+
+    let audit = Audit::denial(subject, denial.method, denial.route, denial.permission);
+    if let Err(e) = config
+        .services
+        .audit_service
+        .create(audit, &config.database)
+        .await
+    {
+        error!("Failed to create audit for denied request: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, MockServices};
+    use actix_web::http::StatusCode;
+    use actix_web::{test, App};
+
+    async fn ok() -> HttpResponse {
+        HttpResponse::Ok().finish()
+    }
+
+    async fn call(prefix: &str, path: &str) -> StatusCode {
+        let config = test_support::config(MockServices::new()).await;
+        let app = test::init_service(
+            App::new().app_data(web::Data::new(config)).service(
+                web::scope(prefix).service(
+                    web::scope("/api/v1")
+                        .wrap(PermissionGuard)
+                        .route("/unlisted/", web::get().to(ok))
+                        .route("/authentication/current/", web::get().to(ok))
+                        .route("/roles/", web::get().to(ok)),
+                ),
+            ),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("{}/api/v1{}", prefix, path))
+            .to_request();
+
+        test::call_service(&app, req).await.status()
+    }
+
+    #[actix_web::test]
+    async fn unlisted_route_is_denied() {
+        assert_eq!(call("", "/unlisted/").await, StatusCode::FORBIDDEN);
+    }
+
+    #[actix_web::test]
+    async fn public_route_is_allowed() {
+        assert_eq!(call("", "/authentication/current/").await, StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn protected_route_is_denied_without_permission() {
+        assert_eq!(call("", "/roles/").await, StatusCode::FORBIDDEN);
+    }
+
+    #[actix_web::test]
+    async fn prefixed_route_matches_full_pattern() {
+        assert_eq!(
+            call("/auth", "/authentication/current/").await,
+            StatusCode::OK
+        );
+        assert_eq!(call("/auth", "/roles/").await, StatusCode::FORBIDDEN);
+    }
+}
@@ -0,0 +1,161 @@
+use crate::configuration::config::Config;
+use crate::services::quota::quota_service::QuotaStatus;
+use crate::web::extractors::jwt_extractor::AuthenticatedSubject;
+use actix_web::body::EitherBody;
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::{HeaderMap, HeaderName, HeaderValue, RETRY_AFTER, WARNING};
+use actix_web::{Error, HttpMessage, HttpResponse};
+use futures::future::{ready, LocalBoxFuture, Ready};
+use log::{error, warn};
+
+pub struct QuotaGuard;
+
+impl<S, B> Transform<S, ServiceRequest> for QuotaGuard
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = QuotaGuardMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    /// # Summary
+    ///
+    /// Create the QuotaGuardMiddleware that wraps the given service.
+    ///
+    /// # Arguments
+    ///
+    /// * `service` - The service to wrap.
+    ///
+    /// # Returns
+    ///
+    /// * `Ready<Result<QuotaGuardMiddleware<S>, ()>>` - The QuotaGuardMiddleware.
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(QuotaGuardMiddleware { service }))
+    }
+}
+
+pub struct QuotaGuardMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for QuotaGuardMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    /// # Summary
+    ///
+    /// Count the request against the quota of its authenticated caller before calling the wrapped service. The guard must
+    /// run after authentication, so that unauthenticated requests cannot claim or exhaust the quota of a caller.
+    /// In monitor mode, requests that exceed the quota are logged and marked with a Warning header instead of rejected.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - The ServiceRequest.
+    ///
+    /// # Returns
+    ///
+    /// * `LocalBoxFuture<'static, Result<ServiceResponse<EitherBody<B>>, Error>>` - The response of the wrapped service or a 429 Too Many Requests response.
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let status = consume_quota(&req);
+
+        if let Some(s) = &status {
+            // Only the first request that exceeds the quota within a window is logged in monitor mode
+            if !s.allowed && !s.enforced && s.requests == s.limit.saturating_add(1) {
+                warn!(
+                    "Request quota would have been exceeded for {} by {} (monitor mode)",
+                    req.path(),
+                    s.subject
+                );
+            }
+
+            if !s.allowed && s.enforced {
+                warn!("Request quota exceeded for {}", req.path());
+
+                let mut res = HttpResponse::TooManyRequests().finish();
+                add_quota_headers(res.headers_mut(), s);
+                res.headers_mut()
+                    .insert(RETRY_AFTER, HeaderValue::from(s.reset));
+
+                let res = req.into_response(res).map_into_right_body();
+                return Box::pin(async { Ok(res) });
+            }
+        }
+
+        let fut = self.service.call(req);
+        Box::pin(async move {
+            let mut res = fut.await?;
+            if let Some(s) = &status {
+                add_quota_headers(res.headers_mut(), s);
+            }
+
+            Ok(res.map_into_left_body())
+        })
+    }
+}
+
+/// # Summary
+///
+/// Count the request against the quota of the User, Client or ApiKey that it was authenticated as.
+///
+/// # Arguments
+///
+/// * `req` - The ServiceRequest.
+///
+/// # Returns
+///
+/// * `Option<QuotaStatus>` - The QuotaStatus of the caller, or None if the request is not authenticated or the quota is disabled.
+fn consume_quota(req: &ServiceRequest) -> Option<QuotaStatus> {
+    let config = match req.app_data::<actix_web::web::Data<Config>>() {
+        Some(c) => c,
+        None => {
+            error!("Failed to get Config from request");
+            return None;
+        }
+    };
+
+    let subject = req.extensions().get::<AuthenticatedSubject>()?.0.clone();
+
+    config.services.quota_service.consume(&subject)
+}
+
+/// # Summary
+///
+/// Add the `X-RateLimit-*` headers that describe the quota of a caller to a response.
+///
+/// # Arguments
+///
+/// * `headers` - The headers of the response.
+/// * `status` - The QuotaStatus of the caller.
+fn add_quota_headers(headers: &mut HeaderMap, status: &QuotaStatus) {
+    if !status.allowed && !status.enforced {
+        headers.insert(
+            WARNING,
+            HeaderValue::from_static(
+                "299 - \"Request quota exceeded, this request will be rejected once the quota is enforced\"",
+            ),
+        );
+    }
+
+    headers.insert(
+        HeaderName::from_static("x-ratelimit-limit"),
+        HeaderValue::from(status.limit),
+    );
+    headers.insert(
+        HeaderName::from_static("x-ratelimit-remaining"),
+        HeaderValue::from(status.remaining),
+    );
+    headers.insert(
+        HeaderName::from_static("x-ratelimit-reset"),
+        HeaderValue::from(status.reset),
+    );
+}
@@ -0,0 +1,3 @@
+pub mod request_logger;
+pub mod request_tracker;
+pub mod session_resolver;
@@ -0,0 +1,301 @@
+use crate::components::request_context::RequestContext;
+use crate::configuration::config::Config;
+use crate::configuration::request_logging_config::RequestLoggingConfig;
+use actix_web::body::{BodySize, MessageBody};
+use actix_web::dev::{forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::{HeaderMap, CONTENT_LENGTH, CONTENT_TYPE};
+use actix_web::web::Bytes;
+use actix_web::{web, Error};
+use futures::future::{ready, LocalBoxFuture, Ready};
+use log::info;
+use serde_json::Value;
+use std::rc::Rc;
+use std::time::Instant;
+
+/// The value that replaces sensitive data in the logs.
+const REDACTED: &str = "[REDACTED]";
+
+/// The parts of header, query parameter and field names that mark them as sensitive.
+const SENSITIVE_NAMES: &[&str] = &[
+    "authorization",
+    "cookie",
+    "password",
+    "token",
+    "secret",
+    "code",
+    "invitation",
+    "api-key",
+];
+
+/// The maximum size of a request body that is logged.
+const MAX_LOGGED_BODY_SIZE: usize = 16 * 1024;
+
+pub struct RequestLogger;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestLogger
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = RequestLoggerMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    /// # Summary
+    ///
+    /// Create the RequestLoggerMiddleware that wraps the given service.
+    ///
+    /// # Arguments
+    ///
+    /// * `service` - The service to wrap.
+    ///
+    /// # Returns
+    ///
+    /// * `Ready<Result<RequestLoggerMiddleware<S>, ()>>` - The RequestLoggerMiddleware.
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestLoggerMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct RequestLoggerMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestLoggerMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    /// # Summary
+    ///
+    /// Log the method, path, status, latency and payload sizes of the request if request logging is enabled.
+    /// Passwords, tokens and other secrets are redacted from the logged query string, headers and body.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - The ServiceRequest.
+    ///
+    /// # Returns
+    ///
+    /// * `LocalBoxFuture<'static, Result<ServiceResponse<B>, Error>>` - The response of the wrapped service.
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let config = match req.app_data::<web::Data<Config>>() {
+            Some(c) if c.request_logging_config.enabled => c.request_logging_config.clone(),
+            _ => return Box::pin(self.service.call(req)),
+        };
+
+        let service = self.service.clone();
+        Box::pin(async move {
+            let start = Instant::now();
+            let method = req.method().to_string();
+            let path = redact_path(req.path(), req.query_string());
+            let request_id =
+                RequestContext::current().map_or_else(|| String::from("-"), |c| c.request_id);
+
+            let request_size = content_length(req.headers());
+
+            let headers = match config.headers {
+                true => Some(redact_headers(req.headers())),
+                false => None,
+            };
+
+            let body = match log_body(&config, &req) {
+                true => {
+                    let bytes = req.extract::<Bytes>().await?;
+                    let body = redact_body(&bytes);
+                    req.set_payload(Payload::from(bytes));
+                    body
+                }
+                false => None,
+            };
+
+            let res = service.call(req).await?;
+
+            let response_size = match res.response().body().size() {
+                BodySize::Sized(s) => s.to_string(),
+                _ => String::from("-"),
+            };
+
+            info!(
+                "{} {} {} {} {}ms request={}B response={}B{}{}",
+                request_id,
+                method,
+                path,
+                res.status().as_u16(),
+                start.elapsed().as_millis(),
+                request_size.map_or_else(|| String::from("-"), |s| s.to_string()),
+                response_size,
+                headers.map_or_else(String::new, |h| format!(" headers={}", h)),
+                body.map_or_else(String::new, |b| format!(" body={}", b)),
+            );
+
+            Ok(res)
+        })
+    }
+}
+
+/// # Summary
+///
+/// Get the size of the request body from its Content-Length header.
+///
+/// # Arguments
+///
+/// * `headers` - The headers of the request.
+///
+/// # Returns
+///
+/// * `Option<usize>` - The size of the request body, if known.
+fn content_length(headers: &HeaderMap) -> Option<usize> {
+    headers
+        .get(CONTENT_LENGTH)?
+        .to_str()
+        .ok()?
+        .parse::<usize>()
+        .ok()
+}
+
+/// # Summary
+///
+/// Check whether the body of a request should be logged. Only JSON bodies of a known and limited size are read, so
+/// that the body can still be handed to the wrapped service.
+///
+/// # Arguments
+///
+/// * `config` - The RequestLoggingConfig.
+/// * `req` - The ServiceRequest.
+///
+/// # Returns
+///
+/// * `bool` - True if the body of the request should be logged.
+fn log_body(config: &RequestLoggingConfig, req: &ServiceRequest) -> bool {
+    let is_json = req
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|h| h.to_str().ok())
+        .is_some_and(|h| h.starts_with("application/json"));
+
+    config.bodies
+        && is_json
+        && content_length(req.headers()).is_some_and(|s| s > 0 && s <= MAX_LOGGED_BODY_SIZE)
+}
+
+/// # Summary
+///
+/// Check whether a header, query parameter or field contains sensitive data, based on its name.
+///
+/// # Arguments
+///
+/// * `name` - The name of the header, query parameter or field.
+///
+/// # Returns
+///
+/// * `bool` - True if the value should be redacted.
+fn is_sensitive(name: &str) -> bool {
+    let name = name.to_lowercase();
+    SENSITIVE_NAMES.iter().any(|s| name.contains(s))
+}
+
+/// # Summary
+///
+/// Combine the path and the query string of a request, with the values of sensitive query parameters redacted.
+///
+/// # Arguments
+///
+/// * `path` - The path of the request.
+/// * `query` - The query string of the request.
+///
+/// # Returns
+///
+/// * `String` - The path and redacted query string.
+fn redact_path(path: &str, query: &str) -> String {
+    if query.is_empty() {
+        return path.to_string();
+    }
+
+    let query = query
+        .split('&')
+        .map(|p| match p.split_once('=') {
+            Some((k, _)) if is_sensitive(k) => format!("{}={}", k, REDACTED),
+            _ => p.to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join("&");
+
+    format!("{}?{}", path, query)
+}
+
+/// # Summary
+///
+/// Format the headers of a request, with the values of sensitive headers redacted.
+///
+/// # Arguments
+///
+/// * `headers` - The headers of the request.
+///
+/// # Returns
+///
+/// * `String` - The redacted headers.
+fn redact_headers(headers: &HeaderMap) -> String {
+    let headers = headers
+        .iter()
+        .map(|(k, v)| {
+            let value = match is_sensitive(k.as_str()) {
+                true => REDACTED,
+                false => v.to_str().unwrap_or("<binary>"),
+            };
+            format!("{}: {}", k, value)
+        })
+        .collect::<Vec<String>>();
+
+    format!("[{}]", headers.join(", "))
+}
+
+/// # Summary
+///
+/// Format a JSON request body, with the values of sensitive fields redacted.
+///
+/// # Arguments
+///
+/// * `body` - The request body.
+///
+/// # Returns
+///
+/// * `Option<String>` - The redacted body, or None if the body is not valid JSON.
+fn redact_body(body: &Bytes) -> Option<String> {
+    let mut value = serde_json::from_slice::<Value>(body).ok()?;
+    redact_value(&mut value);
+    Some(value.to_string())
+}
+
+/// # Summary
+///
+/// Redact the sensitive fields of a JSON value, recursively.
+///
+/// # Arguments
+///
+/// * `value` - The JSON value to redact.
+fn redact_value(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (k, v) in map.iter_mut() {
+                if is_sensitive(k) {
+                    *v = Value::from(REDACTED);
+                } else {
+                    redact_value(v);
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(redact_value),
+        _ => (),
+    }
+}
@@ -0,0 +1,115 @@
+use crate::components::request_context::RequestContext;
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::{HeaderName, HeaderValue};
+use actix_web::Error;
+use futures::future::{ready, LocalBoxFuture, Ready};
+use mongodb::bson::oid::ObjectId;
+
+/// The header that carries the ID of a request.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// The maximum length of a request ID that is provided by the caller.
+const MAX_REQUEST_ID_LENGTH: usize = 128;
+
+/// The header that carries the reason for a change, such as the ID of a change-management ticket.
+pub const CHANGE_REASON_HEADER: &str = "x-change-reason";
+
+/// The maximum length of a change reason that is provided by the caller.
+const MAX_CHANGE_REASON_LENGTH: usize = 512;
+
+pub struct RequestTracker;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestTracker
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = RequestTrackerMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    /// # Summary
+    ///
+    /// Create the RequestTrackerMiddleware that wraps the given service.
+    ///
+    /// # Arguments
+    ///
+    /// * `service` - The service to wrap.
+    ///
+    /// # Returns
+    ///
+    /// * `Ready<Result<RequestTrackerMiddleware<S>, ()>>` - The RequestTrackerMiddleware.
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestTrackerMiddleware { service }))
+    }
+}
+
+pub struct RequestTrackerMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestTrackerMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    /// # Summary
+    ///
+    /// Handle the request within a RequestContext, so that the route, method and ID of the request can be recorded
+    /// wherever it is needed. The ID of the request is taken from the `X-Request-Id` header of the request if it is
+    /// present and generated otherwise, and it is returned in the `X-Request-Id` header of the response. The reason for
+    /// a change is taken from the `X-Change-Reason` header and is recorded on the Audits that the request creates.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - The ServiceRequest.
+    ///
+    /// # Returns
+    ///
+    /// * `LocalBoxFuture<'static, Result<ServiceResponse<B>, Error>>` - The response of the wrapped service.
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let request_id = req
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|h| h.to_str().ok())
+            .map(|h| h.trim())
+            .filter(|h| !h.is_empty() && h.len() <= MAX_REQUEST_ID_LENGTH)
+            .map(String::from)
+            .unwrap_or_else(|| ObjectId::new().to_hex());
+
+        let route = req
+            .match_pattern()
+            .unwrap_or_else(|| req.path().to_string());
+
+        let reason = req
+            .headers()
+            .get(CHANGE_REASON_HEADER)
+            .and_then(|h| h.to_str().ok())
+            .map(|h| h.trim())
+            .filter(|h| !h.is_empty())
+            .map(|h| h.chars().take(MAX_CHANGE_REASON_LENGTH).collect());
+
+        let context =
+            RequestContext::new(request_id.clone(), req.method().to_string(), route, reason);
+
+        let fut = context.clone().sync_scope(|| self.service.call(req));
+        Box::pin(context.scope(async move {
+            let mut res = fut.await?;
+
+            if let Ok(v) = HeaderValue::from_str(&request_id) {
+                res.headers_mut()
+                    .insert(HeaderName::from_static(REQUEST_ID_HEADER), v);
+            }
+
+            Ok(res)
+        }))
+    }
+}
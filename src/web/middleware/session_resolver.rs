@@ -0,0 +1,120 @@
+use crate::configuration::config::Config;
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::{HeaderValue, AUTHORIZATION};
+use actix_web::{web, Error, HttpMessage};
+use futures::future::{ready, LocalBoxFuture, Ready};
+use log::error;
+use std::rc::Rc;
+
+/// The opaque session token that a request was made with, kept so that the Session can be revoked on logout.
+#[derive(Clone)]
+pub struct SessionToken(pub String);
+
+pub struct SessionResolver;
+
+impl<S, B> Transform<S, ServiceRequest> for SessionResolver
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = SessionResolverMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    /// # Summary
+    ///
+    /// Create the SessionResolverMiddleware that wraps the given service.
+    ///
+    /// # Arguments
+    ///
+    /// * `service` - The service to wrap.
+    ///
+    /// # Returns
+    ///
+    /// * `Ready<Result<SessionResolverMiddleware<S>, ()>>` - The SessionResolverMiddleware.
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(SessionResolverMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct SessionResolverMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for SessionResolverMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    /// # Summary
+    ///
+    /// Replace the opaque session token in the Authorization header with a JWT token that is signed for the Claims of its
+    /// Session if session mode is enabled, so that the extractors and controllers verify requests the same way in either
+    /// mode. Bearer tokens
+    /// that do not refer to a valid Session are removed, which also rejects JWT tokens that are presented directly.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - The ServiceRequest.
+    ///
+    /// # Returns
+    ///
+    /// * `LocalBoxFuture<'static, Result<ServiceResponse<B>, Error>>` - The response of the wrapped service.
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let config = match req.app_data::<web::Data<Config>>() {
+            Some(c) if c.services.jwt_service.jwt_config.is_session_mode() => c.clone(),
+            _ => return Box::pin(self.service.call(req)),
+        };
+
+        let token = match req
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|h| h.to_str().ok())
+            .and_then(|h| h.strip_prefix("Bearer "))
+        {
+            Some(t) => t.to_string(),
+            None => return Box::pin(self.service.call(req)),
+        };
+
+        let service = self.service.clone();
+        Box::pin(async move {
+            let claims = match config
+                .services
+                .session_service
+                .resolve(&token, &config.database)
+                .await
+            {
+                Ok(c) => c,
+                Err(e) => {
+                    error!("Failed to resolve Session: {}", e);
+                    None
+                }
+            };
+
+            // Sessions do not store JWT tokens, so a token is signed for the Claims of the Session on every request
+            let access_token = claims.and_then(|c| config.services.jwt_service.sign(c));
+
+            match access_token.and_then(|t| HeaderValue::from_str(&format!("Bearer {}", t)).ok()) {
+                Some(v) => {
+                    req.headers_mut().insert(AUTHORIZATION, v);
+                    req.extensions_mut().insert(SessionToken(token));
+                }
+                None => {
+                    req.headers_mut().remove(AUTHORIZATION);
+                }
+            }
+
+            service.call(req).await
+        })
+    }
+}
@@ -0,0 +1,57 @@
+use actix_web::HttpRequest;
+
+/// # Summary
+///
+/// Build the value of an RFC 5988 `Link` header for a page of a list endpoint. The links point to the same path and
+/// keep every query parameter of the request except `limit` and `page`, so that filters and searches are preserved.
+///
+/// # Arguments
+///
+/// * `req` - The HttpRequest of the page.
+/// * `limit` - The effective limit of entities per page.
+/// * `page` - The requested page, which defaults to the first page.
+/// * `total` - The total number of entities that match the request.
+///
+/// # Example
+///
+/// ```
+/// let link = pagination::link_header(&req, 10, Some(2), 42);
+/// // </api/v1/users/?limit=10&page=3>; rel="next", </api/v1/users/?limit=10&page=1>; rel="prev", ...
+/// ```
+///
+/// # Returns
+///
+/// * `String` - The links to the next, previous, first and last page. The next and previous links are omitted on the last and first page.
+pub fn link_header(req: &HttpRequest, limit: i64, page: Option<i64>, total: u64) -> String {
+    let limit = limit.max(1);
+    let page = page.unwrap_or(1).max(1);
+    let last = ((total as i64 + limit - 1) / limit).max(1);
+
+    let query: Vec<&str> = req
+        .query_string()
+        .split('&')
+        .filter(|p| {
+            let key = p.split('=').next().unwrap_or_default();
+            !p.is_empty() && key != "limit" && key != "page"
+        })
+        .collect();
+
+    let uri = |p: i64| {
+        let pagination = format!("limit={}&page={}", limit, p);
+        let mut params = query.clone();
+        params.push(&pagination);
+        format!("<{}?{}>", req.path(), params.join("&"))
+    };
+
+    let mut links: Vec<String> = vec![];
+    if page < last {
+        links.push(format!("{}; rel=\"next\"", uri(page + 1)));
+    }
+    if page > 1 {
+        links.push(format!("{}; rel=\"prev\"", uri((page - 1).min(last))));
+    }
+    links.push(format!("{}; rel=\"first\"", uri(1)));
+    links.push(format!("{}; rel=\"last\"", uri(last)));
+
+    links.join(", ")
+}